@@ -0,0 +1,612 @@
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+
+use crossbeam_abi::{Abi, Param, ParamType};
+use proc_macro2::{Literal, TokenStream};
+use quote::{format_ident, quote};
+use syn::Ident;
+
+use crate::naming::{field, ident, snake, upper_camel, with_suffix};
+use crate::AbigenInput;
+
+pub fn expand(input: AbigenInput) -> syn::Result<TokenStream> {
+    let AbigenInput { vis, name, source } = input;
+    let error = |msg: String| syn::Error::new(source.span(), msg);
+
+    let text = source.value();
+    let (json, json_tokens) = if text.trim_start().starts_with(['[', '{']) {
+        (text.clone(), quote!(#text))
+    } else {
+        let dir = std::env::var("CARGO_MANIFEST_DIR")
+            .map_err(|_| error("CARGO_MANIFEST_DIR is not set".to_owned()))?;
+        let path = PathBuf::from(dir).join(&text);
+        let json = std::fs::read_to_string(&path)
+            .map_err(|e| error(format!("cannot read {}: {e}", path.display())))?;
+        let path = path.to_string_lossy().into_owned();
+        (json, quote!(include_str!(#path)))
+    };
+    let abi = Abi::from_json(&json).map_err(|e| error(e.to_string()))?;
+
+    let module = format_ident!("{}", snake(&name.to_string()));
+    let mut structs = Structs::default();
+    let mut names = Names::default();
+    let calls = functions(&name, &abi, &mut structs, &mut names);
+    let events = events(&name, &abi, &mut structs, &mut names);
+    let errors = errors(&name, &abi, &mut structs, &mut names);
+    for (rust, (qualified, _)) in &structs.shapes {
+        names.claim(&ident(rust), format!("the struct `{qualified}`"));
+    }
+    if let Some(clashes) = structs
+        .clashes
+        .iter()
+        .chain(&names.clashes)
+        .map(|clash| error(clash.clone()))
+        .reduce(|mut all, clash| {
+            all.combine(clash);
+            all
+        })
+    {
+        return Err(clashes);
+    }
+    let structs = structs.items();
+    let doc = format!("Bindings for the `{name}` contract, generated by `abigen!`.");
+
+    Ok(quote! {
+        #[doc = #doc]
+        #[allow(clippy::all, non_camel_case_types, dead_code, unused_imports)]
+        #vis mod #module {
+            use ::crossbeam_abi::__private::{next, tuple, OnceLock};
+            use ::crossbeam_abi::{AbiType, Value};
+
+            /// The ABI these bindings were generated from.
+            pub const ABI_JSON: &str = #json_tokens;
+
+            /// The parsed ABI, shared by all bindings in this module.
+            pub fn abi() -> &'static ::crossbeam_abi::Abi {
+                static ABI: OnceLock<::crossbeam_abi::Abi> = OnceLock::new();
+                ABI.get_or_init(|| ::crossbeam_abi::__private::parse_abi(ABI_JSON))
+            }
+
+            #structs
+            #calls
+            #events
+            #errors
+        }
+    })
+}
+
+/// Solidity structs found while mapping parameter types, keyed by Rust name
+/// so a struct used by several items is emitted once.
+#[derive(Default)]
+struct Structs {
+    defs: BTreeMap<String, TokenStream>,
+    /// The qualified name and the fields each struct was first seen with.
+    shapes: BTreeMap<String, (String, Vec<(String, ParamType)>)>,
+    /// Differently shaped structs that map to the same Rust name.
+    clashes: Vec<String>,
+}
+
+impl Structs {
+    fn items(self) -> TokenStream {
+        self.defs.into_values().collect()
+    }
+
+    fn define(&mut self, qualified: &str, name: &str, kind: &ParamType, components: &[Param]) {
+        let shape: Vec<_> = components
+            .iter()
+            .map(|p| (p.name.clone(), p.kind.clone()))
+            .collect();
+        // Reserve the name first so recursive references terminate.
+        match self.shapes.entry(name.to_owned()) {
+            Entry::Occupied(first) => {
+                let (first, known) = first.get();
+                if *known != shape {
+                    self.clashes.push(format!(
+                        "the structs `{first}` and `{qualified}` both map to `{name}` but \
+                         have different fields"
+                    ));
+                }
+                return;
+            }
+            Entry::Vacant(entry) => {
+                entry.insert((qualified.to_owned(), shape));
+            }
+        }
+        let ident = ident(name);
+        let fields = Fields::new(components, self);
+        let decl = fields.declaration();
+        let construct = fields.construct();
+        let values = fields.values();
+        let param_type = param_type_tokens(kind);
+        let doc = format!("The Solidity struct `{name}`, ABI type `{kind}`.");
+        let def = quote! {
+            #[doc = #doc]
+            #[derive(Debug, Clone, PartialEq, Eq)]
+            pub struct #ident #decl
+
+            impl AbiType for #ident {
+                fn param_type() -> ::crossbeam_abi::ParamType {
+                    #param_type
+                }
+
+                fn into_value(self) -> Value {
+                    let this = self;
+                    Value::Tuple(#values)
+                }
+
+                fn from_value(value: Value) -> ::crossbeam_abi::Result<Self> {
+                    let mut values = tuple(value, &Self::param_type())?;
+                    Ok(#construct)
+                }
+            }
+        };
+        self.defs.insert(name.to_owned(), def);
+    }
+}
+
+/// The qualified and the Rust struct name from an `internalType` such as
+/// `struct Bridge.Deposit[]`: `Bridge.Deposit` and `Deposit`.
+fn struct_name(internal_type: Option<&str>) -> Option<(&str, String)> {
+    let qualified = internal_type?.strip_prefix("struct ")?;
+    let qualified = qualified.split('[').next()?;
+    let name = qualified.rsplit('.').next()?;
+    Some((qualified, upper_camel(name)))
+}
+
+/// Type names the bindings define, so that clashes fail the build instead
+/// of one item shadowing another.
+#[derive(Default)]
+struct Names {
+    owners: HashMap<String, String>,
+    clashes: Vec<String>,
+}
+
+impl Names {
+    fn claim(&mut self, name: &Ident, owner: String) {
+        let name = name.to_string();
+        match self.owners.get(&name) {
+            Some(first) => self.clashes.push(format!(
+                "`{name}` would be generated for both {first} and {owner}"
+            )),
+            None => {
+                self.owners.insert(name, owner);
+            }
+        }
+    }
+}
+
+/// The Rust type a parameter of type `kind` is bound to.
+fn rust_type(kind: &ParamType, param: &Param, structs: &mut Structs) -> TokenStream {
+    match kind {
+        ParamType::Address => quote!(::crossbeam_abi::Address),
+        ParamType::Bool => quote!(bool),
+        ParamType::Uint(bits) => match bits {
+            0..=8 => quote!(u8),
+            9..=16 => quote!(u16),
+            17..=32 => quote!(u32),
+            33..=64 => quote!(u64),
+            65..=128 => quote!(u128),
+            _ => quote!(::crossbeam_abi::U256),
+        },
+        ParamType::Int(bits) => match bits {
+            0..=8 => quote!(i8),
+            9..=16 => quote!(i16),
+            17..=32 => quote!(i32),
+            33..=64 => quote!(i64),
+            65..=128 => quote!(i128),
+            _ => quote!(::crossbeam_abi::I256),
+        },
+        ParamType::FixedBytes(len) => quote!([u8; #len]),
+        ParamType::Bytes => quote!(::crossbeam_abi::Bytes),
+        ParamType::String => quote!(::std::string::String),
+        ParamType::Array(inner) => {
+            let inner = rust_type(inner, param, structs);
+            quote!(::std::vec::Vec<#inner>)
+        }
+        ParamType::FixedArray(inner, len) => {
+            let inner = rust_type(inner, param, structs);
+            quote!(::crossbeam_abi::FixedArray<#inner, #len>)
+        }
+        ParamType::Tuple(types) => {
+            if let Some((qualified, name)) = struct_name(param.internal_type.as_deref()) {
+                if param.components.len() == types.len() {
+                    structs.define(qualified, &name, kind, &param.components);
+                    let ident = ident(&name);
+                    return quote!(#ident);
+                }
+            }
+            let members = types
+                .iter()
+                .enumerate()
+                .map(|(i, ty)| match param.components.get(i) {
+                    Some(component) => rust_type(ty, component, structs),
+                    None => rust_type(ty, &Param::new("", ty.clone()), structs),
+                });
+            quote!((#(#members,)*))
+        }
+    }
+}
+
+/// Builds the expression constructing `kind`, for use in `param_type()`.
+fn param_type_tokens(kind: &ParamType) -> TokenStream {
+    match kind {
+        ParamType::Address => quote!(::crossbeam_abi::ParamType::Address),
+        ParamType::Bool => quote!(::crossbeam_abi::ParamType::Bool),
+        ParamType::Uint(bits) => quote!(::crossbeam_abi::ParamType::Uint(#bits)),
+        ParamType::Int(bits) => quote!(::crossbeam_abi::ParamType::Int(#bits)),
+        ParamType::FixedBytes(len) => quote!(::crossbeam_abi::ParamType::FixedBytes(#len)),
+        ParamType::Bytes => quote!(::crossbeam_abi::ParamType::Bytes),
+        ParamType::String => quote!(::crossbeam_abi::ParamType::String),
+        ParamType::Array(inner) => {
+            let inner = param_type_tokens(inner);
+            quote!(::crossbeam_abi::ParamType::Array(::std::boxed::Box::new(#inner)))
+        }
+        ParamType::FixedArray(inner, len) => {
+            let inner = param_type_tokens(inner);
+            quote!(::crossbeam_abi::ParamType::FixedArray(::std::boxed::Box::new(#inner), #len))
+        }
+        ParamType::Tuple(types) => {
+            let types = types.iter().map(param_type_tokens);
+            quote!(::crossbeam_abi::ParamType::Tuple(::std::vec![#(#types),*]))
+        }
+    }
+}
+
+/// The fields of a generated struct.
+struct Fields {
+    names: Vec<Ident>,
+    types: Vec<TokenStream>,
+}
+
+impl Fields {
+    fn new(params: &[Param], structs: &mut Structs) -> Self {
+        let types = params
+            .iter()
+            .map(|p| rust_type(&p.kind, p, structs))
+            .collect();
+        Self::with_types(params, types)
+    }
+
+    fn with_types(params: &[Param], types: Vec<TokenStream>) -> Self {
+        let names = params
+            .iter()
+            .enumerate()
+            .map(|(i, p)| field(&p.name, i))
+            .collect();
+        Self { names, types }
+    }
+
+    /// `{ pub a: A, ... }`, or `;` for a unit struct.
+    fn declaration(&self) -> TokenStream {
+        if self.names.is_empty() {
+            return quote!(;);
+        }
+        let names = &self.names;
+        let types = &self.types;
+        quote!({ #(pub #names: #types,)* })
+    }
+
+    /// `Self { a: next(&mut values)?, ... }` or `Self`.
+    fn construct(&self) -> TokenStream {
+        if self.names.is_empty() {
+            return quote!(Self);
+        }
+        let names = &self.names;
+        quote!(Self { #(#names: next(&mut values)?,)* })
+    }
+
+    /// `vec![this.a.into_value(), ...]`, with `this` bound to an owned
+    /// struct.
+    fn values(&self) -> TokenStream {
+        let names = &self.names;
+        quote!(::std::vec![#(AbiType::into_value(this.#names),)*])
+    }
+}
+
+fn bytes_literal(bytes: &[u8]) -> TokenStream {
+    let bytes = bytes.iter().map(|b| Literal::u8_suffixed(*b));
+    quote!([#(#bytes),*])
+}
+
+/// Rust names for items, with overloads after the first numbered. A number
+/// another item already has by name is skipped.
+fn item_names<'a>(names: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let names: Vec<String> = names.map(upper_camel).collect();
+    let mut taken: HashSet<String> = names.iter().cloned().collect();
+    let mut seen = HashSet::new();
+    names
+        .iter()
+        .map(|name| {
+            if seen.insert(name) {
+                return name.clone();
+            }
+            let rust = (1..)
+                .map(|count| format!("{name}{count}"))
+                .find(|rust| !taken.contains(rust))
+                .expect("some count is free");
+            taken.insert(rust.clone());
+            rust
+        })
+        .collect()
+}
+
+fn functions(contract: &Ident, abi: &Abi, structs: &mut Structs, names: &mut Names) -> TokenStream {
+    let rust_names = item_names(abi.functions.iter().map(|f| f.name.as_str()));
+    let mut items = TokenStream::new();
+    let mut variants = Vec::new();
+    for (index, (function, name)) in abi.functions.iter().zip(&rust_names).enumerate() {
+        let ty = with_suffix(name, "Call");
+        let signature = function.signature();
+        names.claim(&ty, format!("the function `{signature}`"));
+        let selector = bytes_literal(&function.selector());
+        let fields = Fields::new(&function.inputs, structs);
+        let decl = fields.declaration();
+        let construct = fields.construct();
+        let values = fields.values();
+
+        let outputs: Vec<TokenStream> = function
+            .outputs
+            .iter()
+            .map(|p| rust_type(&p.kind, p, structs))
+            .collect();
+        let (return_type, return_construct) = match outputs.as_slice() {
+            [] => (quote!(()), quote!(())),
+            [single] => (quote!(#single), quote!(next(&mut values)?)),
+            many => {
+                let nexts = many.iter().map(|_| quote!(next(&mut values)?));
+                (quote!((#(#many,)*)), quote!((#(#nexts,)*)))
+            }
+        };
+
+        let doc = format!("Call to `{signature}`.");
+        items.extend(quote! {
+            #[doc = #doc]
+            #[derive(Debug, Clone, PartialEq, Eq)]
+            pub struct #ty #decl
+
+            impl ::crossbeam_abi::ContractCall for #ty {
+                const SIGNATURE: &'static str = #signature;
+                const SELECTOR: [u8; 4] = #selector;
+
+                type Return = #return_type;
+
+                fn function() -> &'static ::crossbeam_abi::Function {
+                    &abi().functions[#index]
+                }
+
+                fn to_values(&self) -> ::std::vec::Vec<Value> {
+                    let this = self.clone();
+                    #values
+                }
+
+                fn from_values(values: ::std::vec::Vec<Value>) -> ::crossbeam_abi::Result<Self> {
+                    let mut values = values.into_iter();
+                    Ok(#construct)
+                }
+
+                fn return_from_values(
+                    values: ::std::vec::Vec<Value>,
+                ) -> ::crossbeam_abi::Result<Self::Return> {
+                    let mut values = values.into_iter();
+                    Ok(#return_construct)
+                }
+            }
+        });
+        variants.push((ident(name), ty, selector));
+    }
+    let dispatch = format_ident!("{contract}Calls");
+    if !variants.is_empty() {
+        names.claim(&dispatch, "the enum of calls".to_owned());
+    }
+    items.extend(dispatch_enum(&dispatch, &variants, "call"));
+    items
+}
+
+fn events(contract: &Ident, abi: &Abi, structs: &mut Structs, names: &mut Names) -> TokenStream {
+    let rust_names = item_names(abi.events.iter().map(|e| e.name.as_str()));
+    let mut items = TokenStream::new();
+    let mut variants = Vec::new();
+    for (index, (event, name)) in abi.events.iter().zip(&rust_names).enumerate() {
+        let ty = with_suffix(name, "Event");
+        let signature = event.signature();
+        names.claim(&ty, format!("the event `{signature}`"));
+        let topic0 = bytes_literal(&event.topic0());
+        let params: Vec<Param> = event.inputs.iter().map(|p| p.param.clone()).collect();
+        let types = event
+            .inputs
+            .iter()
+            .map(|input| {
+                if input.indexed && is_hashed(&input.param.kind) {
+                    quote!([u8; 32])
+                } else {
+                    rust_type(&input.param.kind, &input.param, structs)
+                }
+            })
+            .collect();
+        let fields = Fields::with_types(&params, types);
+        let decl = fields.declaration();
+        let construct = fields.construct();
+
+        let doc = if event.anonymous {
+            format!("The anonymous event `{signature}`.")
+        } else {
+            format!("The event `{signature}`.")
+        };
+        items.extend(quote! {
+            #[doc = #doc]
+            #[derive(Debug, Clone, PartialEq, Eq)]
+            pub struct #ty #decl
+
+            impl ::crossbeam_abi::ContractEvent for #ty {
+                const SIGNATURE: &'static str = #signature;
+                const TOPIC0: [u8; 32] = #topic0;
+
+                fn event() -> &'static ::crossbeam_abi::Event {
+                    &abi().events[#index]
+                }
+
+                fn from_values(values: ::std::vec::Vec<Value>) -> ::crossbeam_abi::Result<Self> {
+                    let mut values = values.into_iter();
+                    Ok(#construct)
+                }
+            }
+        });
+        if !event.anonymous {
+            variants.push((ident(name), ty, topic0));
+        }
+    }
+    let dispatch = format_ident!("{contract}Events");
+    if !variants.is_empty() {
+        names.claim(&dispatch, "the enum of events".to_owned());
+    }
+    items.extend(event_enum(&dispatch, &variants));
+    items
+}
+
+fn errors(contract: &Ident, abi: &Abi, structs: &mut Structs, names: &mut Names) -> TokenStream {
+    let rust_names = item_names(abi.errors.iter().map(|e| e.name.as_str()));
+    let mut items = TokenStream::new();
+    let mut variants = Vec::new();
+    for (index, (error, name)) in abi.errors.iter().zip(&rust_names).enumerate() {
+        let ty = with_suffix(name, "Error");
+        let signature = error.signature();
+        names.claim(&ty, format!("the error `{signature}`"));
+        let selector = bytes_literal(&error.selector());
+        let fields = Fields::new(&error.inputs, structs);
+        let decl = fields.declaration();
+        let construct = fields.construct();
+        let values = fields.values();
+
+        let doc = format!("The custom error `{signature}`.");
+        items.extend(quote! {
+            #[doc = #doc]
+            #[derive(Debug, Clone, PartialEq, Eq)]
+            pub struct #ty #decl
+
+            impl ::crossbeam_abi::ContractError for #ty {
+                const SIGNATURE: &'static str = #signature;
+                const SELECTOR: [u8; 4] = #selector;
+
+                fn error() -> &'static ::crossbeam_abi::CustomError {
+                    &abi().errors[#index]
+                }
+
+                fn to_values(&self) -> ::std::vec::Vec<Value> {
+                    let this = self.clone();
+                    #values
+                }
+
+                fn from_values(values: ::std::vec::Vec<Value>) -> ::crossbeam_abi::Result<Self> {
+                    let mut values = values.into_iter();
+                    Ok(#construct)
+                }
+            }
+        });
+        variants.push((ident(name), ty, selector));
+    }
+    let dispatch = format_ident!("{contract}Errors");
+    if !variants.is_empty() {
+        names.claim(&dispatch, "the enum of errors".to_owned());
+    }
+    items.extend(dispatch_enum(&dispatch, &variants, "error"));
+    items
+}
+
+/// Whether an indexed parameter of this type is only available as a hash.
+fn is_hashed(kind: &ParamType) -> bool {
+    matches!(
+        kind,
+        ParamType::Bytes
+            | ParamType::String
+            | ParamType::Array(_)
+            | ParamType::FixedArray(..)
+            | ParamType::Tuple(_)
+    )
+}
+
+/// An enum over calls or errors, decoded by selector.
+fn dispatch_enum(
+    ident: &Ident,
+    variants: &[(Ident, Ident, TokenStream)],
+    kind: &str,
+) -> TokenStream {
+    if variants.is_empty() {
+        return TokenStream::new();
+    }
+    let trait_path = if kind == "call" {
+        quote!(::crossbeam_abi::ContractCall)
+    } else {
+        quote!(::crossbeam_abi::ContractError)
+    };
+    let names = variants.iter().map(|(v, _, _)| v);
+    let types = variants.iter().map(|(_, t, _)| t);
+    let arms = variants.iter().map(|(variant, ty, selector)| {
+        quote!(#selector => <#ty as #trait_path>::decode(data).map(Self::#variant),)
+    });
+    let doc = format!("Every {kind} of the contract, decoded by selector.");
+    let decode_doc = format!("Decodes {kind} data by its leading selector.");
+    quote! {
+        #[doc = #doc]
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub enum #ident {
+            #(#names(#types),)*
+        }
+
+        impl #ident {
+            #[doc = #decode_doc]
+            pub fn decode(data: &[u8]) -> ::crossbeam_abi::Result<Self> {
+                let selector: [u8; 4] = data
+                    .get(..4)
+                    .and_then(|s| s.try_into().ok())
+                    .ok_or_else(|| ::crossbeam_abi::Error::InvalidData(
+                        "data shorter than a selector".to_owned(),
+                    ))?;
+                match selector {
+                    #(#arms)*
+                    other => Err(::crossbeam_abi::Error::InvalidData(::std::format!(
+                        "unknown selector 0x{:02x}{:02x}{:02x}{:02x}",
+                        other[0], other[1], other[2], other[3],
+                    ))),
+                }
+            }
+        }
+    }
+}
+
+/// An enum over the contract's non-anonymous events, decoded by topic0.
+fn event_enum(ident: &Ident, variants: &[(Ident, Ident, TokenStream)]) -> TokenStream {
+    if variants.is_empty() {
+        return TokenStream::new();
+    }
+    let names = variants.iter().map(|(v, _, _)| v);
+    let types = variants.iter().map(|(_, t, _)| t);
+    let arms = variants.iter().map(|(variant, ty, topic0)| {
+        quote! {
+            #topic0 => <#ty as ::crossbeam_abi::ContractEvent>::decode_log(topics, data)
+                .map(Self::#variant),
+        }
+    });
+    quote! {
+        /// Every non-anonymous event of the contract, decoded by topic0.
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub enum #ident {
+            #(#names(#types),)*
+        }
+
+        impl #ident {
+            /// Decodes a log by its first topic.
+            pub fn decode_log(topics: &[[u8; 32]], data: &[u8]) -> ::crossbeam_abi::Result<Self> {
+                let topic0 = topics.first().ok_or_else(|| {
+                    ::crossbeam_abi::Error::InvalidData("log has no topics".to_owned())
+                })?;
+                match *topic0 {
+                    #(#arms)*
+                    _ => Err(::crossbeam_abi::Error::InvalidData(
+                        "topic0 matches no event of this contract".to_owned(),
+                    )),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,96 @@
+//! Compile-time contract bindings.
+//!
+//! [`abigen!`] reads a Solidity ABI at compile time and generates a module
+//! of typed structs for the contract's functions, events and custom errors.
+//! The generated code refers to `::crossbeam_abi`, which must be a
+//! dependency of the calling crate.
+
+mod expand;
+mod naming;
+
+use proc_macro::TokenStream;
+use syn::parse::{Parse, ParseStream};
+use syn::{parse_macro_input, Ident, LitStr, Token, Visibility};
+
+/// Generates bindings for a contract.
+///
+/// ```ignore
+/// crossbeam_abi_macros::abigen!(pub Bridge, "abi/Bridge.json");
+///
+/// use crossbeam_abi::ContractCall;
+/// let data = bridge::LockCall { deposit }.encode()?;
+/// ```
+///
+/// The path is resolved against the calling crate's `CARGO_MANIFEST_DIR`
+/// and the file is tracked, so editing the ABI rebuilds the bindings. A
+/// string starting with `[` or `{` is taken as the ABI JSON itself. Both a
+/// bare ABI array and a Foundry or Hardhat artifact are accepted.
+///
+/// For a contract `Bridge` the macro emits a module `bridge` containing:
+///
+/// - a `<Name>Call` struct per function, implementing `ContractCall`;
+///   overloads after the first get the lowest numeric suffix no other
+///   function has by name (`Transfer1Call`);
+/// - a `<Name>Event` struct per event, implementing `ContractEvent`;
+/// - a `<Name>Error` struct per custom error, implementing `ContractError`;
+/// - a struct per Solidity struct named in the ABI's `internalType`s;
+/// - `BridgeCalls`, `BridgeEvents` and `BridgeErrors` enums that dispatch
+///   on selector or first topic;
+/// - `abi()`, the parsed ABI the bindings encode and decode with.
+///
+/// Structs are named after the last part of their `internalType`, so
+/// `A.Deposit` and `B.Deposit` share one `Deposit` if their fields match.
+/// Anything that would still define a name twice fails the build, such as
+/// structs with different fields:
+///
+/// ```compile_fail
+/// crossbeam_abi_macros::abigen!(
+///     Vault,
+///     r#"[
+///         {"type": "function", "name": "a", "stateMutability": "nonpayable", "outputs": [],
+///          "inputs": [{"name": "d", "type": "tuple", "internalType": "struct A.Deposit",
+///                      "components": [{"name": "amount", "type": "uint256"}]}]},
+///         {"type": "function", "name": "b", "stateMutability": "nonpayable", "outputs": [],
+///          "inputs": [{"name": "d", "type": "tuple", "internalType": "struct B.Deposit",
+///                      "components": [{"name": "amount", "type": "uint128"}]}]}
+///     ]"#
+/// );
+/// ```
+///
+/// or functions whose names only differ by the suffix:
+///
+/// ```compile_fail
+/// crossbeam_abi_macros::abigen!(
+///     Vault,
+///     r#"[
+///         {"type": "function", "name": "transfer", "stateMutability": "nonpayable",
+///          "inputs": [], "outputs": []},
+///         {"type": "function", "name": "transferCall", "stateMutability": "nonpayable",
+///          "inputs": [], "outputs": []}
+///     ]"#
+/// );
+/// ```
+#[proc_macro]
+pub fn abigen(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as AbigenInput);
+    expand::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+struct AbigenInput {
+    vis: Visibility,
+    name: Ident,
+    source: LitStr,
+}
+
+impl Parse for AbigenInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let vis = input.parse()?;
+        let name = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let source = input.parse()?;
+        input.parse::<Option<Token![,]>>()?;
+        Ok(Self { vis, name, source })
+    }
+}
@@ -0,0 +1,65 @@
+//! Turning Solidity identifiers into Rust ones.
+
+use proc_macro2::{Ident, Span};
+
+/// `safeTransferFrom` and `safe_transfer_from` both become
+/// `SafeTransferFrom`.
+pub fn upper_camel(name: &str) -> String {
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            let first = chars.next().expect("part is non-empty");
+            first.to_ascii_uppercase().to_string() + chars.as_str()
+        })
+        .collect()
+}
+
+/// `targetChain` becomes `target_chain` and `tokenURI` becomes `token_uri`.
+pub fn snake(name: &str) -> String {
+    let chars: Vec<char> = name.trim_matches('_').chars().collect();
+    let mut out = String::with_capacity(chars.len() + 4);
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_ascii_lowercase());
+            if prev.is_ascii_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_ascii_uppercase() && next_is_lower)
+            {
+                out.push('_');
+            }
+        }
+        out.push(c.to_ascii_lowercase());
+    }
+    out
+}
+
+/// A field identifier for parameter `index` called `name`, falling back to
+/// `arg{index}` for unnamed parameters and escaping keywords.
+pub fn field(name: &str, index: usize) -> Ident {
+    let name = snake(name);
+    if name.is_empty() {
+        return Ident::new(&format!("arg{index}"), Span::call_site());
+    }
+    ident(&name)
+}
+
+pub fn ident(name: &str) -> Ident {
+    match name {
+        // These cannot be raw identifiers.
+        "self" | "Self" | "super" | "crate" => Ident::new(&format!("{name}_"), Span::call_site()),
+        _ if syn::parse_str::<Ident>(name).is_err() => Ident::new_raw(name, Span::call_site()),
+        _ => Ident::new(name, Span::call_site()),
+    }
+}
+
+/// `Name` with `suffix` appended unless it already ends with it.
+pub fn with_suffix(name: &str, suffix: &str) -> Ident {
+    let name = upper_camel(name);
+    if name.ends_with(suffix) {
+        ident(&name)
+    } else {
+        ident(&format!("{name}{suffix}"))
+    }
+}
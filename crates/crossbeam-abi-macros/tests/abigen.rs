@@ -0,0 +1,225 @@
+use crossbeam_abi::{
+    AbiType, Address, Bytes, ContractCall, ContractError, ContractEvent, Event, FixedArray, Value,
+    U256,
+};
+use crossbeam_abi_macros::abigen;
+
+abigen!(Bridge, "tests/abi/Bridge.json");
+
+abigen!(
+    pub Erc20,
+    r#"[
+        {"type": "function", "name": "transfer", "stateMutability": "nonpayable",
+         "inputs": [{"name": "to", "type": "address"}, {"name": "amount", "type": "uint256"}],
+         "outputs": [{"name": "", "type": "bool"}]},
+        {"type": "event", "name": "Transfer", "anonymous": false, "inputs": [
+          {"name": "from", "type": "address", "indexed": true},
+          {"name": "to", "type": "address", "indexed": true},
+          {"name": "value", "type": "uint256", "indexed": false}]}
+    ]"#
+);
+
+abigen!(
+    Vault,
+    r#"[
+        {"type": "function", "name": "deposit", "stateMutability": "nonpayable", "outputs": [],
+         "inputs": [{"name": "d", "type": "tuple", "internalType": "struct A.Deposit",
+                     "components": [{"name": "amount", "type": "uint256"}]}]},
+        {"type": "function", "name": "deposit", "stateMutability": "nonpayable", "outputs": [],
+         "inputs": [{"name": "d", "type": "tuple", "internalType": "struct B.Deposit",
+                     "components": [{"name": "amount", "type": "uint256"}]},
+                    {"name": "memo", "type": "bytes"}]},
+        {"type": "function", "name": "deposit1", "stateMutability": "nonpayable",
+         "inputs": [], "outputs": []}
+    ]"#
+);
+
+use bridge::{
+    BridgeCalls, BridgeErrors, BridgeEvents, Deposit, InsufficientBalanceError, LockCall,
+    LockedEvent, PausedError, PendingCall, ReleaseCall, ReleasedEvent, SetFeeCall, Transfer1Call,
+    TransferCall,
+};
+
+fn deposit() -> Deposit {
+    Deposit {
+        recipient: Bytes(vec![0xaa; 32]),
+        amount: U256::from(1_000_000u64),
+        target_chain: 56,
+    }
+}
+
+#[test]
+fn selectors_match_signatures() {
+    assert_eq!(LockCall::SIGNATURE, "lock((bytes,uint256,uint64))");
+    assert_eq!(
+        LockCall::SELECTOR,
+        crossbeam_abi::selector(LockCall::SIGNATURE)
+    );
+    assert_eq!(hex::encode(erc20::TransferCall::SELECTOR), "a9059cbb");
+    assert_eq!(
+        hex::encode(erc20::TransferEvent::TOPIC0),
+        "ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"
+    );
+    assert_eq!(TransferCall::SIGNATURE, "transfer(address,uint256)");
+    assert_eq!(Transfer1Call::SIGNATURE, "transfer(address,uint256,bytes)");
+    assert_eq!(SetFeeCall::SIGNATURE, "setFee(uint8,uint24,int24)");
+    assert_eq!(bridge::abi().functions.len(), 6);
+}
+
+#[test]
+fn calls_round_trip_through_the_runtime_codec() {
+    let call = LockCall { deposit: deposit() };
+    let data = call.encode().unwrap();
+    let runtime = bridge::abi().function("lock").unwrap();
+    assert_eq!(
+        runtime.encode_input(&[deposit().into_value()]).unwrap(),
+        data
+    );
+    assert_eq!(LockCall::decode(&data).unwrap(), call);
+    assert_eq!(BridgeCalls::decode(&data).unwrap(), BridgeCalls::Lock(call));
+
+    let release = ReleaseCall {
+        id: [0x11; 32],
+        to: Address::new([0x22; 20]),
+        amount: U256::from(5u8),
+        signatures: vec![Bytes(vec![1; 65]), Bytes(vec![2; 65])],
+    };
+    let data = release.encode().unwrap();
+    assert_eq!(
+        BridgeCalls::decode(&data).unwrap(),
+        BridgeCalls::Release(release)
+    );
+    assert!(BridgeCalls::decode(&[0, 0, 0, 0]).is_err());
+}
+
+#[test]
+fn odd_widths_and_keywords() {
+    let call = SetFeeCall {
+        r#type: 1,
+        arg1: (1 << 24) - 1,
+        skew: -(1 << 23),
+    };
+    assert_eq!(SetFeeCall::decode(&call.encode().unwrap()).unwrap(), call);
+    let too_wide = SetFeeCall {
+        arg1: 1 << 24,
+        ..call.clone()
+    };
+    assert!(too_wide.encode().is_err());
+    let too_wide = SetFeeCall {
+        skew: 1 << 23,
+        ..call
+    };
+    assert!(too_wide.encode().is_err());
+}
+
+#[test]
+fn decodes_return_data() {
+    let returned = crossbeam_abi::encode(&[Value::Bool(true)]);
+    assert!(TransferCall::decode_return(&returned).unwrap());
+
+    let lock_return = crossbeam_abi::encode(&[Value::FixedBytes(vec![7; 32])]);
+    assert_eq!(LockCall::decode_return(&lock_return).unwrap(), [7; 32]);
+
+    let pending = vec![FixedArray([deposit(), deposit()])];
+    let total = U256::from(2_000_000u64);
+    let function = bridge::abi().function("pending").unwrap();
+    let data = crossbeam_abi::encode(&[pending.clone().into_value(), Value::uint(total)]);
+    assert!(function.decode_output(&data).is_ok());
+    assert_eq!(PendingCall::decode_return(&data).unwrap(), (pending, total));
+    assert_eq!(
+        PendingCall.encode().unwrap(),
+        PendingCall::SELECTOR.to_vec()
+    );
+}
+
+#[test]
+fn events() {
+    let sender = Address::new([0x33; 20]);
+    let recipient = Value::Bytes(deposit().recipient.0);
+    let topics = [
+        LockedEvent::TOPIC0,
+        Event::encode_topic(&Value::Address(sender)),
+        Event::encode_topic(&recipient),
+    ];
+    let data = crossbeam_abi::encode(&[deposit().into_value()]);
+    let event = LockedEvent::decode_log(&topics, &data).unwrap();
+    assert_eq!(
+        event,
+        LockedEvent {
+            sender,
+            recipient: Event::encode_topic(&recipient),
+            deposit: deposit(),
+        }
+    );
+    assert_eq!(
+        BridgeEvents::decode_log(&topics, &data).unwrap(),
+        BridgeEvents::Locked(event)
+    );
+
+    let topics = [
+        ReleasedEvent::TOPIC0,
+        [0x44; 32],
+        Event::encode_topic(&Value::Address(sender)),
+    ];
+    let data = crossbeam_abi::encode(&[Value::uint(U256::from(9u8))]);
+    let BridgeEvents::Released(released) = BridgeEvents::decode_log(&topics, &data).unwrap() else {
+        panic!("expected Released");
+    };
+    assert_eq!(
+        released,
+        ReleasedEvent {
+            id: [0x44; 32],
+            to: sender,
+            amount: U256::from(9u8),
+        }
+    );
+    assert!(BridgeEvents::decode_log(&[[0; 32]], &data).is_err());
+}
+
+#[test]
+fn errors() {
+    let error = InsufficientBalanceError {
+        available: U256::from(1u8),
+        required: U256::from(2u8),
+    };
+    let data = error.encode().unwrap();
+    assert_eq!(
+        BridgeErrors::decode(&data).unwrap(),
+        BridgeErrors::InsufficientBalance(error)
+    );
+    assert_eq!(
+        BridgeErrors::decode(&PausedError::SELECTOR).unwrap(),
+        BridgeErrors::Paused(PausedError)
+    );
+    assert_eq!(
+        bridge::abi().decode_revert(&PausedError::SELECTOR),
+        crossbeam_abi::Revert::Custom {
+            name: "Paused".to_owned(),
+            values: Vec::new(),
+        }
+    );
+}
+
+#[test]
+fn names_are_shared_or_numbered_without_clashing() {
+    // `A.Deposit` and `B.Deposit` have the same fields, so share a struct.
+    let deposit = vault::Deposit {
+        amount: U256::from(3u8),
+    };
+    assert_eq!(
+        vault::DepositCall { d: deposit.clone() }.encode().unwrap()[4..],
+        vault::Deposit2Call {
+            d: deposit,
+            memo: Bytes(Vec::new()),
+        }
+        .encode()
+        .unwrap()[4..36]
+    );
+    // The overload skips the number `deposit1` already has.
+    assert_eq!(vault::Deposit1Call::SIGNATURE, "deposit1()");
+    assert_eq!(vault::Deposit2Call::SIGNATURE, "deposit((uint256),bytes)");
+    assert_eq!(
+        vault::VaultCalls::decode(&vault::Deposit1Call.encode().unwrap()).unwrap(),
+        vault::VaultCalls::Deposit1(vault::Deposit1Call)
+    );
+}
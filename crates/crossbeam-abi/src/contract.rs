@@ -0,0 +1,124 @@
+//! Traits implemented by generated contract bindings.
+//!
+//! `crossbeam_abi_macros::abigen!` turns every function, event and custom
+//! error of an ABI into a struct implementing one of these traits. The
+//! structs only carry typed fields; encoding and decoding go through the
+//! runtime [`Function`], [`Event`] and [`CustomError`] parsed from the same
+//! ABI, so generated and hand-written code share one codec.
+
+use crate::error::Result;
+use crate::event::Event;
+use crate::function::{CustomError, Function};
+use crate::value::Value;
+
+/// A typed call to one contract function.
+pub trait ContractCall: Sized {
+    /// Canonical signature, e.g. `transfer(address,uint256)`.
+    const SIGNATURE: &'static str;
+    const SELECTOR: [u8; 4];
+
+    /// The decoded return data: `()` for no outputs, the bare type for one
+    /// and a tuple for several.
+    type Return;
+
+    fn function() -> &'static Function;
+
+    fn to_values(&self) -> Vec<Value>;
+
+    fn from_values(values: Vec<Value>) -> Result<Self>;
+
+    fn return_from_values(values: Vec<Value>) -> Result<Self::Return>;
+
+    /// Selector followed by the encoded arguments. Fails only when a field
+    /// holds a value wider than its Solidity type, e.g. 2^24 in a `uint24`.
+    fn encode(&self) -> Result<Vec<u8>> {
+        Self::function().encode_input(&self.to_values())
+    }
+
+    fn decode(data: &[u8]) -> Result<Self> {
+        Self::from_values(Self::function().decode_input(data)?)
+    }
+
+    fn decode_return(data: &[u8]) -> Result<Self::Return> {
+        Self::return_from_values(Self::function().decode_output(data)?)
+    }
+}
+
+/// A typed contract event.
+///
+/// Indexed parameters of dynamic type are represented by their `[u8; 32]`
+/// topic hash, as the original value never reaches the log.
+pub trait ContractEvent: Sized {
+    const SIGNATURE: &'static str;
+    /// Keccak-256 of [`SIGNATURE`](Self::SIGNATURE), the first topic of
+    /// non-anonymous events.
+    const TOPIC0: [u8; 32];
+
+    fn event() -> &'static Event;
+
+    fn from_values(values: Vec<Value>) -> Result<Self>;
+
+    fn decode_log(topics: &[[u8; 32]], data: &[u8]) -> Result<Self> {
+        Self::from_values(Self::event().decode_log(topics, data)?.into_values())
+    }
+}
+
+/// A typed Solidity custom error.
+pub trait ContractError: Sized {
+    const SIGNATURE: &'static str;
+    const SELECTOR: [u8; 4];
+
+    fn error() -> &'static CustomError;
+
+    fn to_values(&self) -> Vec<Value>;
+
+    fn from_values(values: Vec<Value>) -> Result<Self>;
+
+    /// Revert data, as the contract would produce it.
+    fn encode(&self) -> Result<Vec<u8>> {
+        Self::error().encode(&self.to_values())
+    }
+
+    fn decode(data: &[u8]) -> Result<Self> {
+        Self::from_values(Self::error().decode(data)?)
+    }
+}
+
+/// Support code for generated bindings. Not part of the public API.
+#[doc(hidden)]
+pub mod __private {
+    pub use std::sync::OnceLock;
+
+    use crate::convert::AbiType;
+    use crate::error::{Error, Result};
+    use crate::param_type::ParamType;
+    use crate::value::Value;
+
+    /// The members of a tuple value bound to a generated struct of type
+    /// `expected`.
+    pub fn tuple(value: Value, expected: &ParamType) -> Result<std::vec::IntoIter<Value>> {
+        match (value, expected) {
+            (Value::Tuple(values), ParamType::Tuple(types)) if values.len() == types.len() => {
+                Ok(values.into_iter())
+            }
+            (value, _) => Err(Error::TypeMismatch {
+                expected: expected.to_string(),
+                actual: value.param_type().to_string(),
+            }),
+        }
+    }
+
+    /// Converts the next value of a decoded sequence.
+    pub fn next<T: AbiType>(values: &mut std::vec::IntoIter<Value>) -> Result<T> {
+        let value = values
+            .next()
+            .ok_or_else(|| Error::InvalidData("too few values for binding".to_owned()))?;
+        T::from_value(value)
+    }
+
+    /// Parses the ABI embedded in a binding. The JSON was already parsed
+    /// once at compile time, so failure here is a bug.
+    pub fn parse_abi(json: &str) -> crate::json::Abi {
+        crate::json::Abi::from_json(json).expect("ABI validated by abigen!")
+    }
+}
@@ -0,0 +1,361 @@
+//! Mapping between Rust types and ABI values.
+
+use crossbeam_core::address::EvmAddress;
+use crossbeam_core::U256;
+
+use crate::error::{Error, Result};
+use crate::param_type::ParamType;
+use crate::value::Value;
+
+/// A Rust type with a fixed Solidity counterpart.
+///
+/// Tuples map to ABI tuples, so a function's whole argument list can be
+/// passed as one value: `(recipient, amount).into_value()`.
+pub trait AbiType: Sized {
+    fn param_type() -> ParamType;
+
+    fn into_value(self) -> Value;
+
+    fn from_value(value: Value) -> Result<Self>;
+}
+
+fn mismatch<T: AbiType>(value: &Value) -> Error {
+    Error::TypeMismatch {
+        expected: T::param_type().to_string(),
+        actual: value.param_type().to_string(),
+    }
+}
+
+/// Dynamic `bytes`, kept distinct from `Vec<u8>`, which maps to `uint8[]`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Bytes(pub Vec<u8>);
+
+impl From<Vec<u8>> for Bytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+}
+
+impl AsRef<[u8]> for Bytes {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// A signed 256-bit integer in two's complement, for `int136` to `int256`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct I256(pub U256);
+
+impl I256 {
+    pub const ZERO: Self = Self(U256::ZERO);
+
+    pub fn is_negative(&self) -> bool {
+        self.0.bit(255)
+    }
+
+    /// The value as an `i128`, if it fits.
+    pub fn to_i128(&self) -> Option<i128> {
+        u256_to_i128(self.0)
+    }
+}
+
+impl From<i128> for I256 {
+    fn from(value: i128) -> Self {
+        Self(i128_to_u256(value))
+    }
+}
+
+/// `T[N]`. Plain arrays are not used because `[u8; N]` already maps to
+/// `bytesN`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FixedArray<T, const N: usize>(pub [T; N]);
+
+impl<T, const N: usize> From<[T; N]> for FixedArray<T, N> {
+    fn from(array: [T; N]) -> Self {
+        Self(array)
+    }
+}
+
+impl AbiType for EvmAddress {
+    fn param_type() -> ParamType {
+        ParamType::Address
+    }
+
+    fn into_value(self) -> Value {
+        Value::Address(self)
+    }
+
+    fn from_value(value: Value) -> Result<Self> {
+        value.as_address().ok_or_else(|| mismatch::<Self>(&value))
+    }
+}
+
+impl AbiType for bool {
+    fn param_type() -> ParamType {
+        ParamType::Bool
+    }
+
+    fn into_value(self) -> Value {
+        Value::Bool(self)
+    }
+
+    fn from_value(value: Value) -> Result<Self> {
+        value.as_bool().ok_or_else(|| mismatch::<Self>(&value))
+    }
+}
+
+impl AbiType for U256 {
+    fn param_type() -> ParamType {
+        ParamType::Uint(256)
+    }
+
+    fn into_value(self) -> Value {
+        Value::Uint(self, 256)
+    }
+
+    fn from_value(value: Value) -> Result<Self> {
+        value.as_uint().ok_or_else(|| mismatch::<Self>(&value))
+    }
+}
+
+macro_rules! impl_abi_uint {
+    ($($ty:ty),*) => {$(
+        impl AbiType for $ty {
+            fn param_type() -> ParamType {
+                ParamType::Uint(<$ty>::BITS as usize)
+            }
+
+            fn into_value(self) -> Value {
+                Value::Uint(U256::from(self), <$ty>::BITS as usize)
+            }
+
+            fn from_value(value: Value) -> Result<Self> {
+                match value {
+                    Value::Uint(n, _) => <$ty>::try_from(n).map_err(|_| mismatch::<Self>(&value)),
+                    _ => Err(mismatch::<Self>(&value)),
+                }
+            }
+        }
+    )*};
+}
+
+impl_abi_uint!(u8, u16, u32, u64, u128);
+
+/// Sign-extends `value` to 256-bit two's complement.
+pub(crate) fn i128_to_u256(value: i128) -> U256 {
+    let n = U256::from(value.unsigned_abs());
+    if value < 0 {
+        n.wrapping_neg()
+    } else {
+        n
+    }
+}
+
+/// Reads a 256-bit two's-complement integer back as an `i128`.
+pub(crate) fn u256_to_i128(value: U256) -> Option<i128> {
+    if value.bit(255) {
+        let magnitude = u128::try_from(value.wrapping_neg()).ok()?;
+        0i128.checked_sub_unsigned(magnitude)
+    } else {
+        i128::try_from(value).ok()
+    }
+}
+
+macro_rules! impl_abi_int {
+    ($($ty:ty),*) => {$(
+        impl AbiType for $ty {
+            fn param_type() -> ParamType {
+                ParamType::Int(<$ty>::BITS as usize)
+            }
+
+            fn into_value(self) -> Value {
+                Value::Int(i128_to_u256(i128::from(self)), <$ty>::BITS as usize)
+            }
+
+            fn from_value(value: Value) -> Result<Self> {
+                match value {
+                    Value::Int(n, _) => u256_to_i128(n)
+                        .and_then(|n| <$ty>::try_from(n).ok())
+                        .ok_or_else(|| mismatch::<Self>(&value)),
+                    _ => Err(mismatch::<Self>(&value)),
+                }
+            }
+        }
+    )*};
+}
+
+impl_abi_int!(i8, i16, i32, i64, i128);
+
+impl AbiType for I256 {
+    fn param_type() -> ParamType {
+        ParamType::Int(256)
+    }
+
+    fn into_value(self) -> Value {
+        Value::Int(self.0, 256)
+    }
+
+    fn from_value(value: Value) -> Result<Self> {
+        match value {
+            Value::Int(n, _) => Ok(Self(n)),
+            _ => Err(mismatch::<Self>(&value)),
+        }
+    }
+}
+
+impl AbiType for String {
+    fn param_type() -> ParamType {
+        ParamType::String
+    }
+
+    fn into_value(self) -> Value {
+        Value::String(self)
+    }
+
+    fn from_value(value: Value) -> Result<Self> {
+        match value {
+            Value::String(s) => Ok(s),
+            _ => Err(mismatch::<Self>(&value)),
+        }
+    }
+}
+
+impl AbiType for Bytes {
+    fn param_type() -> ParamType {
+        ParamType::Bytes
+    }
+
+    fn into_value(self) -> Value {
+        Value::Bytes(self.0)
+    }
+
+    fn from_value(value: Value) -> Result<Self> {
+        match value {
+            Value::Bytes(bytes) => Ok(Bytes(bytes)),
+            _ => Err(mismatch::<Self>(&value)),
+        }
+    }
+}
+
+/// `bytesN`. Only `N` in `1..=32` is a valid ABI type.
+impl<const N: usize> AbiType for [u8; N] {
+    fn param_type() -> ParamType {
+        ParamType::FixedBytes(N)
+    }
+
+    fn into_value(self) -> Value {
+        Value::FixedBytes(self.to_vec())
+    }
+
+    fn from_value(value: Value) -> Result<Self> {
+        match &value {
+            Value::FixedBytes(bytes) => bytes
+                .as_slice()
+                .try_into()
+                .map_err(|_| mismatch::<Self>(&value)),
+            _ => Err(mismatch::<Self>(&value)),
+        }
+    }
+}
+
+impl<T: AbiType> AbiType for Vec<T> {
+    fn param_type() -> ParamType {
+        ParamType::Array(Box::new(T::param_type()))
+    }
+
+    fn into_value(self) -> Value {
+        Value::Array(
+            T::param_type(),
+            self.into_iter().map(AbiType::into_value).collect(),
+        )
+    }
+
+    fn from_value(value: Value) -> Result<Self> {
+        match value {
+            Value::Array(_, values) => values.into_iter().map(T::from_value).collect(),
+            _ => Err(mismatch::<Self>(&value)),
+        }
+    }
+}
+
+impl<T: AbiType, const N: usize> AbiType for FixedArray<T, N> {
+    fn param_type() -> ParamType {
+        ParamType::FixedArray(Box::new(T::param_type()), N)
+    }
+
+    fn into_value(self) -> Value {
+        Value::FixedArray(
+            T::param_type(),
+            self.0.into_iter().map(AbiType::into_value).collect(),
+        )
+    }
+
+    fn from_value(value: Value) -> Result<Self> {
+        match value {
+            Value::FixedArray(_, values) if values.len() == N => {
+                let values = values
+                    .into_iter()
+                    .map(T::from_value)
+                    .collect::<Result<Vec<_>>>()?;
+                let array = values
+                    .try_into()
+                    .unwrap_or_else(|_| unreachable!("length checked"));
+                Ok(Self(array))
+            }
+            _ => Err(mismatch::<Self>(&value)),
+        }
+    }
+}
+
+impl AbiType for () {
+    fn param_type() -> ParamType {
+        ParamType::Tuple(Vec::new())
+    }
+
+    fn into_value(self) -> Value {
+        Value::Tuple(Vec::new())
+    }
+
+    fn from_value(value: Value) -> Result<Self> {
+        match value {
+            Value::Tuple(values) if values.is_empty() => Ok(()),
+            _ => Err(mismatch::<Self>(&value)),
+        }
+    }
+}
+
+macro_rules! impl_abi_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: AbiType),+> AbiType for ($($name,)+) {
+            fn param_type() -> ParamType {
+                ParamType::Tuple(vec![$($name::param_type()),+])
+            }
+
+            #[allow(non_snake_case)]
+            fn into_value(self) -> Value {
+                let ($($name,)+) = self;
+                Value::Tuple(vec![$($name.into_value()),+])
+            }
+
+            fn from_value(value: Value) -> Result<Self> {
+                const LEN: usize = [$(stringify!($name)),+].len();
+                match value {
+                    Value::Tuple(values) if values.len() == LEN => {
+                        let mut values = values.into_iter();
+                        Ok(($($name::from_value(values.next().expect("length checked"))?,)+))
+                    }
+                    _ => Err(mismatch::<Self>(&value)),
+                }
+            }
+        }
+    };
+}
+
+impl_abi_tuple!(A);
+impl_abi_tuple!(A, B);
+impl_abi_tuple!(A, B, C);
+impl_abi_tuple!(A, B, C, D);
+impl_abi_tuple!(A, B, C, D, E);
+impl_abi_tuple!(A, B, C, D, E, F);
+impl_abi_tuple!(A, B, C, D, E, F, G);
+impl_abi_tuple!(A, B, C, D, E, F, G, H);
@@ -0,0 +1,147 @@
+//! Strict decoding of ABI-encoded data.
+//!
+//! Every word is checked: padding must be zero, booleans must be `0` or
+//! `1`, signed integers must be properly sign-extended and offsets and
+//! lengths must stay inside the input. Data that Solidity would never emit
+//! is rejected rather than silently normalized.
+
+use crossbeam_core::address::EvmAddress;
+use crossbeam_core::U256;
+
+use crate::error::{Error, Result};
+use crate::param_type::ParamType;
+use crate::value::{fits_signed, Value};
+
+fn invalid(reason: impl Into<String>) -> Error {
+    Error::InvalidData(reason.into())
+}
+
+fn word(data: &[u8], at: usize) -> Result<&[u8; 32]> {
+    at.checked_add(32)
+        .and_then(|end| data.get(at..end))
+        .map(|w| w.try_into().expect("slice is 32 bytes"))
+        .ok_or_else(|| invalid(format!("word at {at} runs past the end of data")))
+}
+
+fn read_usize(data: &[u8], at: usize) -> Result<usize> {
+    let w = word(data, at)?;
+    let n = U256::from_be_bytes(*w);
+    usize::try_from(n)
+        .ok()
+        .filter(|n| *n <= data.len())
+        .ok_or_else(|| invalid(format!("offset or length {n} exceeds data")))
+}
+
+/// Length-prefixed bytes at `at`, with the zero padding after them checked.
+fn read_bytes(data: &[u8], at: usize) -> Result<&[u8]> {
+    let len = read_usize(data, at)?;
+    let start = at + 32;
+    let padded = len.div_ceil(32) * 32;
+    let bytes = start
+        .checked_add(padded)
+        .and_then(|end| data.get(start..end))
+        .ok_or_else(|| invalid("bytes run past the end of data"))?;
+    if bytes[len..].iter().any(|b| *b != 0) {
+        return Err(invalid("non-zero padding after bytes"));
+    }
+    Ok(&bytes[..len])
+}
+
+fn decode_sequence(data: &[u8], base: usize, types: &[ParamType]) -> Result<Vec<Value>> {
+    let mut values = Vec::with_capacity(types.len());
+    let mut head = base;
+    for kind in types {
+        let value = if kind.is_dynamic() {
+            let offset = read_usize(data, head)?;
+            let at = base
+                .checked_add(offset)
+                .filter(|at| *at <= data.len())
+                .ok_or_else(|| invalid("offset runs past the end of data"))?;
+            decode_value(data, at, kind)?
+        } else {
+            decode_value(data, head, kind)?
+        };
+        head += kind.head_size();
+        values.push(value);
+    }
+    Ok(values)
+}
+
+fn decode_value(data: &[u8], at: usize, kind: &ParamType) -> Result<Value> {
+    match kind {
+        ParamType::Address => {
+            let w = word(data, at)?;
+            if w[..12].iter().any(|b| *b != 0) {
+                return Err(invalid("address has non-zero high bytes"));
+            }
+            let mut bytes = [0u8; 20];
+            bytes.copy_from_slice(&w[12..]);
+            Ok(Value::Address(EvmAddress::new(bytes)))
+        }
+        ParamType::Bool => {
+            let n = U256::from_be_bytes(*word(data, at)?);
+            match u8::try_from(n) {
+                Ok(0) => Ok(Value::Bool(false)),
+                Ok(1) => Ok(Value::Bool(true)),
+                _ => Err(invalid("bool is neither 0 nor 1")),
+            }
+        }
+        ParamType::Uint(bits) => {
+            let n = U256::from_be_bytes(*word(data, at)?);
+            if n.bit_len() > *bits {
+                return Err(invalid(format!("value does not fit uint{bits}")));
+            }
+            Ok(Value::Uint(n, *bits))
+        }
+        ParamType::Int(bits) => {
+            let n = U256::from_be_bytes(*word(data, at)?);
+            if !fits_signed(n, *bits) {
+                return Err(invalid(format!("value does not fit int{bits}")));
+            }
+            Ok(Value::Int(n, *bits))
+        }
+        ParamType::FixedBytes(len) => {
+            let w = word(data, at)?;
+            if w[*len..].iter().any(|b| *b != 0) {
+                return Err(invalid(format!("bytes{len} has non-zero padding")));
+            }
+            Ok(Value::FixedBytes(w[..*len].to_vec()))
+        }
+        ParamType::Bytes => read_bytes(data, at).map(|b| Value::Bytes(b.to_vec())),
+        ParamType::String => {
+            let bytes = read_bytes(data, at)?;
+            let s = std::str::from_utf8(bytes).map_err(|_| invalid("string is not UTF-8"))?;
+            Ok(Value::String(s.to_owned()))
+        }
+        ParamType::Array(inner) => {
+            let len = read_usize(data, at)?;
+            // Each element takes at least one word of the remaining data,
+            // which bounds the allocation below.
+            let base = at + 32;
+            if len.saturating_mul(inner.head_size().max(32)) > data.len().saturating_sub(base) {
+                return Err(invalid("array length exceeds data"));
+            }
+            let types = vec![(**inner).clone(); len];
+            let values = decode_sequence(data, base, &types)?;
+            Ok(Value::Array((**inner).clone(), values))
+        }
+        ParamType::FixedArray(inner, len) => {
+            // As for arrays: the elements' heads must fit the data before
+            // anything is allocated for them.
+            if len.saturating_mul(inner.head_size().max(32)) > data.len().saturating_sub(at) {
+                return Err(invalid("fixed array length exceeds data"));
+            }
+            let types = vec![(**inner).clone(); *len];
+            let values = decode_sequence(data, at, &types)?;
+            Ok(Value::FixedArray((**inner).clone(), values))
+        }
+        ParamType::Tuple(types) => decode_sequence(data, at, types).map(Value::Tuple),
+    }
+}
+
+/// Decodes `data` as a tuple of `types`, the layout of call arguments and
+/// return data. Trailing bytes after the last referenced value are allowed,
+/// as the EVM pads return data freely.
+pub fn decode(types: &[ParamType], data: &[u8]) -> Result<Vec<Value>> {
+    decode_sequence(data, 0, types)
+}
@@ -0,0 +1,103 @@
+//! Head/tail encoding as specified by the Solidity ABI.
+
+use crate::value::Value;
+
+fn pad_right(bytes: &[u8], out: &mut Vec<u8>) {
+    out.extend_from_slice(bytes);
+    let rem = bytes.len() % 32;
+    if rem != 0 {
+        out.resize(out.len() + 32 - rem, 0);
+    }
+}
+
+fn word_from_usize(n: usize, out: &mut Vec<u8>) {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&(n as u64).to_be_bytes());
+    out.extend_from_slice(&word);
+}
+
+fn is_dynamic(value: &Value) -> bool {
+    match value {
+        Value::Bytes(_) | Value::String(_) | Value::Array(..) => true,
+        Value::FixedArray(_, values) | Value::Tuple(values) => values.iter().any(is_dynamic),
+        _ => false,
+    }
+}
+
+/// Encodes a sequence of values as the members of a tuple: static values
+/// in place, dynamic values behind offsets into the tail.
+fn encode_sequence(values: &[Value], out: &mut Vec<u8>) {
+    let head_len: usize = values
+        .iter()
+        .map(|v| if is_dynamic(v) { 32 } else { static_len(v) })
+        .sum();
+    let mut head = Vec::with_capacity(head_len);
+    let mut tail = Vec::new();
+    for value in values {
+        if is_dynamic(value) {
+            word_from_usize(head_len + tail.len(), &mut head);
+            encode_value(value, &mut tail);
+        } else {
+            encode_value(value, &mut head);
+        }
+    }
+    out.extend_from_slice(&head);
+    out.extend_from_slice(&tail);
+}
+
+fn static_len(value: &Value) -> usize {
+    match value {
+        Value::FixedArray(_, values) | Value::Tuple(values) => values.iter().map(static_len).sum(),
+        _ => 32,
+    }
+}
+
+fn encode_value(value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::Address(address) => {
+            out.extend_from_slice(&[0; 12]);
+            out.extend_from_slice(address.as_bytes());
+        }
+        Value::Bool(b) => word_from_usize(usize::from(*b), out),
+        Value::Uint(n, _) | Value::Int(n, _) => out.extend_from_slice(&n.to_be_bytes::<32>()),
+        Value::FixedBytes(bytes) => pad_right(bytes, out),
+        Value::Bytes(bytes) => {
+            word_from_usize(bytes.len(), out);
+            pad_right(bytes, out);
+        }
+        Value::String(s) => {
+            word_from_usize(s.len(), out);
+            pad_right(s.as_bytes(), out);
+        }
+        Value::Array(_, values) => {
+            word_from_usize(values.len(), out);
+            encode_sequence(values, out);
+        }
+        Value::FixedArray(_, values) | Value::Tuple(values) => encode_sequence(values, out),
+    }
+}
+
+/// ABI-encodes `values` as a tuple, the layout of call arguments and return
+/// data. Values are not checked against any declared types; use
+/// [`Value::type_check`] or go through [`Function`](crate::Function).
+pub fn encode(values: &[Value]) -> Vec<u8> {
+    let mut out = Vec::new();
+    encode_sequence(values, &mut out);
+    out
+}
+
+/// Encodes a value the way Solidity does before hashing it into an indexed
+/// event topic: in place, without lengths or offsets, and with every
+/// element of arrays and tuples padded to a full word.
+pub(crate) fn encode_in_place(value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::Bytes(bytes) => pad_right(bytes, out),
+        Value::String(s) => pad_right(s.as_bytes(), out),
+        Value::Array(_, values) | Value::FixedArray(_, values) | Value::Tuple(values) => {
+            for value in values {
+                encode_in_place(value, out);
+            }
+        }
+        _ => encode_value(value, out),
+    }
+}
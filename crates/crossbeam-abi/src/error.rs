@@ -0,0 +1,24 @@
+use thiserror::Error;
+
+/// Errors produced by the ABI codec.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum Error {
+    /// A type string or JSON type could not be parsed.
+    #[error("invalid ABI type `{0}`")]
+    InvalidType(String),
+    /// Encoded data is malformed or does not match the expected types.
+    #[error("invalid ABI data: {0}")]
+    InvalidData(String),
+    /// A value does not fit the parameter it is bound to.
+    #[error("type mismatch: expected {expected}, got {actual}")]
+    TypeMismatch { expected: String, actual: String },
+    /// The ABI has no item of that name.
+    #[error("no {kind} named `{name}` in the ABI")]
+    NotFound { kind: &'static str, name: String },
+    /// The ABI JSON is malformed.
+    #[error("invalid ABI JSON: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
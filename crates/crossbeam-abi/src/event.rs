@@ -0,0 +1,189 @@
+use crate::decode::decode;
+use crate::encode::encode_in_place;
+use crate::error::{Error, Result};
+use crate::keccak256;
+use crate::param_type::{signature, Param, ParamType};
+use crate::value::Value;
+
+/// An event parameter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventParam {
+    pub param: Param,
+    /// Whether the value is carried in a topic instead of the log data.
+    pub indexed: bool,
+}
+
+/// A contract event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub name: String,
+    pub inputs: Vec<EventParam>,
+    /// Anonymous events do not emit their signature hash as the first topic.
+    pub anonymous: bool,
+}
+
+/// A log decoded against an [`Event`].
+///
+/// Indexed parameters of dynamic type (`string`, `bytes`, arrays and
+/// structs) only exist on chain as the Keccak-256 of their encoding, so they
+/// are returned as a 32-byte [`Value::FixedBytes`] hash.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedLog {
+    /// `(name, value)` pairs in declaration order.
+    pub params: Vec<(String, Value)>,
+}
+
+impl DecodedLog {
+    /// The value of the parameter called `name`.
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.params.iter().find(|(n, _)| n == name).map(|(_, v)| v)
+    }
+
+    /// The values in declaration order.
+    pub fn values(&self) -> impl Iterator<Item = &Value> {
+        self.params.iter().map(|(_, v)| v)
+    }
+
+    pub fn into_values(self) -> Vec<Value> {
+        self.params.into_iter().map(|(_, v)| v).collect()
+    }
+}
+
+/// Whether an indexed parameter of this type is stored as a hash.
+fn is_hashed(kind: &ParamType) -> bool {
+    matches!(
+        kind,
+        ParamType::Bytes
+            | ParamType::String
+            | ParamType::Array(_)
+            | ParamType::FixedArray(..)
+            | ParamType::Tuple(_)
+    )
+}
+
+impl Event {
+    /// Parses a signature such as `Transfer(address indexed,address
+    /// indexed,uint256)`. Parameters are left unnamed.
+    pub fn parse(signature: &str) -> Result<Self> {
+        let invalid = || Error::InvalidType(signature.to_owned());
+        let open = signature.find('(').ok_or_else(invalid)?;
+        let inner = signature[open..]
+            .strip_prefix('(')
+            .and_then(|s| s.strip_suffix(')'))
+            .ok_or_else(invalid)?;
+        let mut inputs = Vec::new();
+        let mut depth = 0usize;
+        let mut start = 0;
+        let mut push = |part: &str| -> Result<()> {
+            let part = part.trim();
+            let (kind, indexed) = match part.strip_suffix(" indexed") {
+                Some(kind) => (kind, true),
+                None => (part, false),
+            };
+            inputs.push(EventParam {
+                param: Param::new("", kind.parse()?),
+                indexed,
+            });
+            Ok(())
+        };
+        for (i, c) in inner.char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' => depth = depth.checked_sub(1).ok_or_else(invalid)?,
+                ',' if depth == 0 => {
+                    push(&inner[start..i])?;
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+        if !inner.trim().is_empty() {
+            push(&inner[start..])?;
+        }
+        Ok(Self {
+            name: signature[..open].trim().to_owned(),
+            inputs,
+            anonymous: false,
+        })
+    }
+
+    /// The canonical signature, e.g. `Transfer(address,address,uint256)`.
+    pub fn signature(&self) -> String {
+        signature(&self.name, self.inputs.iter().map(|p| &p.param.kind))
+    }
+
+    /// Keccak-256 of the signature, emitted as the first topic of
+    /// non-anonymous events.
+    pub fn topic0(&self) -> [u8; 32] {
+        keccak256(self.signature())
+    }
+
+    /// The topic an indexed parameter with `value` produces, for building
+    /// log filters.
+    pub fn encode_topic(value: &Value) -> [u8; 32] {
+        match value {
+            // Top-level strings and bytes are hashed without padding.
+            Value::Bytes(bytes) => keccak256(bytes),
+            Value::String(s) => keccak256(s),
+            Value::Array(..) | Value::FixedArray(..) | Value::Tuple(_) => {
+                let mut out = Vec::new();
+                encode_in_place(value, &mut out);
+                keccak256(out)
+            }
+            _ => {
+                let mut out = Vec::with_capacity(32);
+                encode_in_place(value, &mut out);
+                out.try_into().expect("static value encodes to one word")
+            }
+        }
+    }
+
+    /// Decodes a log emitted by this event.
+    pub fn decode_log(&self, topics: &[[u8; 32]], data: &[u8]) -> Result<DecodedLog> {
+        let mut topics = topics.iter();
+        if !self.anonymous {
+            let topic0 = topics
+                .next()
+                .ok_or_else(|| Error::InvalidData("log has no topics".to_owned()))?;
+            if *topic0 != self.topic0() {
+                return Err(Error::InvalidData(format!(
+                    "topic0 does not match event {}",
+                    self.signature()
+                )));
+            }
+        }
+        let indexed_count = self.inputs.iter().filter(|p| p.indexed).count();
+        if topics.len() != indexed_count {
+            return Err(Error::InvalidData(format!(
+                "expected {indexed_count} indexed topics, got {}",
+                topics.len()
+            )));
+        }
+
+        let data_types: Vec<ParamType> = self
+            .inputs
+            .iter()
+            .filter(|p| !p.indexed)
+            .map(|p| p.param.kind.clone())
+            .collect();
+        let mut data_values = decode(&data_types, data)?.into_iter();
+
+        let mut params = Vec::with_capacity(self.inputs.len());
+        for input in &self.inputs {
+            let value = if input.indexed {
+                let topic = topics.next().expect("topic count checked");
+                if is_hashed(&input.param.kind) {
+                    Value::FixedBytes(topic.to_vec())
+                } else {
+                    decode(std::slice::from_ref(&input.param.kind), topic)?
+                        .pop()
+                        .expect("one type decodes to one value")
+                }
+            } else {
+                data_values.next().expect("one value per data type")
+            };
+            params.push((input.param.name.clone(), value));
+        }
+        Ok(DecodedLog { params })
+    }
+}
@@ -0,0 +1,159 @@
+use serde::{Deserialize, Serialize};
+
+use crate::decode::decode;
+use crate::encode::encode;
+use crate::error::{Error, Result};
+use crate::param_type::{signature, Param, ParamType};
+use crate::selector;
+use crate::value::Value;
+
+/// How a function interacts with state, as declared in Solidity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StateMutability {
+    Pure,
+    View,
+    #[default]
+    NonPayable,
+    Payable,
+}
+
+fn types(params: &[Param]) -> Vec<ParamType> {
+    params.iter().map(|p| p.kind.clone()).collect()
+}
+
+/// Checks arity and types of `values` against `params`.
+pub(crate) fn check_args(params: &[Param], values: &[Value]) -> Result<()> {
+    if params.len() != values.len() {
+        return Err(Error::TypeMismatch {
+            expected: format!("{} arguments", params.len()),
+            actual: format!("{} arguments", values.len()),
+        });
+    }
+    params
+        .iter()
+        .zip(values)
+        .try_for_each(|(param, value)| value.type_check(&param.kind))
+}
+
+fn strip_selector(expected: [u8; 4], data: &[u8]) -> Result<&[u8]> {
+    match data.split_first_chunk::<4>() {
+        Some((found, rest)) if *found == expected => Ok(rest),
+        Some((found, _)) => Err(Error::InvalidData(format!(
+            "selector 0x{} does not match 0x{}",
+            hex::encode(found),
+            hex::encode(expected)
+        ))),
+        None => Err(Error::InvalidData(
+            "call data shorter than a selector".to_owned(),
+        )),
+    }
+}
+
+/// A contract function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Function {
+    pub name: String,
+    pub inputs: Vec<Param>,
+    pub outputs: Vec<Param>,
+    pub state_mutability: StateMutability,
+}
+
+impl Function {
+    /// Parses a human-readable signature such as
+    /// `transfer(address,uint256)`. Parameters are left unnamed and the
+    /// function has no declared outputs.
+    pub fn parse(signature: &str) -> Result<Self> {
+        let (name, inputs) = parse_signature(signature)?;
+        Ok(Self {
+            name,
+            inputs,
+            outputs: Vec::new(),
+            state_mutability: StateMutability::default(),
+        })
+    }
+
+    /// The canonical signature, e.g. `transfer(address,uint256)`.
+    pub fn signature(&self) -> String {
+        signature(&self.name, self.inputs.iter().map(|p| &p.kind))
+    }
+
+    pub fn selector(&self) -> [u8; 4] {
+        selector(&self.signature())
+    }
+
+    /// Selector followed by the encoded arguments.
+    pub fn encode_input(&self, args: &[Value]) -> Result<Vec<u8>> {
+        check_args(&self.inputs, args)?;
+        let mut out = self.selector().to_vec();
+        out.extend_from_slice(&encode(args));
+        Ok(out)
+    }
+
+    /// Decodes call data, which must start with this function's selector.
+    pub fn decode_input(&self, data: &[u8]) -> Result<Vec<Value>> {
+        let args = strip_selector(self.selector(), data)?;
+        decode(&types(&self.inputs), args)
+    }
+
+    /// Decodes the data an `eth_call` of this function returned.
+    pub fn decode_output(&self, data: &[u8]) -> Result<Vec<Value>> {
+        decode(&types(&self.outputs), data)
+    }
+}
+
+/// A Solidity custom error, raised with `revert Name(args)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomError {
+    pub name: String,
+    pub inputs: Vec<Param>,
+}
+
+impl CustomError {
+    /// Parses a signature such as `InsufficientBalance(uint256,uint256)`.
+    pub fn parse(signature: &str) -> Result<Self> {
+        let (name, inputs) = parse_signature(signature)?;
+        Ok(Self { name, inputs })
+    }
+
+    pub fn signature(&self) -> String {
+        signature(&self.name, self.inputs.iter().map(|p| &p.kind))
+    }
+
+    pub fn selector(&self) -> [u8; 4] {
+        selector(&self.signature())
+    }
+
+    /// Encodes revert data, as a contract raising this error would.
+    pub fn encode(&self, args: &[Value]) -> Result<Vec<u8>> {
+        check_args(&self.inputs, args)?;
+        let mut out = self.selector().to_vec();
+        out.extend_from_slice(&encode(args));
+        Ok(out)
+    }
+
+    /// Decodes revert data carrying this error.
+    pub fn decode(&self, data: &[u8]) -> Result<Vec<Value>> {
+        let args = strip_selector(self.selector(), data)?;
+        decode(&types(&self.inputs), args)
+    }
+}
+
+/// Splits `name(type,...)` into its name and unnamed parameters.
+pub(crate) fn parse_signature(signature: &str) -> Result<(String, Vec<Param>)> {
+    let invalid = || Error::InvalidType(signature.to_owned());
+    let open = signature.find('(').ok_or_else(invalid)?;
+    let name = signature[..open].trim();
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+    {
+        return Err(invalid());
+    }
+    let ParamType::Tuple(kinds) = signature[open..].parse()? else {
+        return Err(invalid());
+    };
+    let inputs = kinds.into_iter().map(|kind| Param::new("", kind)).collect();
+    Ok((name.to_owned(), inputs))
+}
@@ -0,0 +1,249 @@
+//! Loading contract ABIs from the JSON that `solc`, Foundry and Hardhat
+//! emit.
+
+use serde::{Deserialize, Deserializer};
+
+use crate::encode::encode;
+use crate::error::{Error, Result};
+use crate::event::{Event, EventParam};
+use crate::function::{check_args, CustomError, Function, StateMutability};
+use crate::param_type::{Param, ParamType};
+use crate::revert::Revert;
+use crate::value::Value;
+
+/// A contract constructor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Constructor {
+    pub inputs: Vec<Param>,
+    pub state_mutability: StateMutability,
+}
+
+impl Constructor {
+    /// Deployment data: the creation `bytecode` followed by the encoded
+    /// arguments.
+    pub fn encode_input(&self, bytecode: &[u8], args: &[Value]) -> Result<Vec<u8>> {
+        check_args(&self.inputs, args)?;
+        let mut out = bytecode.to_vec();
+        out.extend_from_slice(&encode(args));
+        Ok(out)
+    }
+}
+
+/// A contract ABI.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Abi {
+    pub constructor: Option<Constructor>,
+    /// Functions in declaration order; overloads share a name.
+    pub functions: Vec<Function>,
+    pub events: Vec<Event>,
+    pub errors: Vec<CustomError>,
+    /// Whether the contract has a `fallback` function.
+    pub fallback: bool,
+    /// Whether the contract has a `receive` function.
+    pub receive: bool,
+}
+
+impl Abi {
+    /// Parses either a bare ABI array or a build artifact with an `abi`
+    /// field.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let mut document: serde_json::Value = serde_json::from_str(json)?;
+        if let Some(abi) = document.get_mut("abi") {
+            document = abi.take();
+        }
+        Ok(serde_json::from_value(document)?)
+    }
+
+    /// The first function called `name`. Use [`Abi::functions_named`] for
+    /// overloaded functions.
+    pub fn function(&self, name: &str) -> Result<&Function> {
+        self.functions
+            .iter()
+            .find(|f| f.name == name)
+            .ok_or_else(|| not_found("function", name))
+    }
+
+    pub fn functions_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Function> {
+        self.functions.iter().filter(move |f| f.name == name)
+    }
+
+    /// The function call data with this selector is addressed to.
+    pub fn function_by_selector(&self, selector: [u8; 4]) -> Option<&Function> {
+        self.functions.iter().find(|f| f.selector() == selector)
+    }
+
+    pub fn event(&self, name: &str) -> Result<&Event> {
+        self.events
+            .iter()
+            .find(|e| e.name == name)
+            .ok_or_else(|| not_found("event", name))
+    }
+
+    /// The non-anonymous event that emits `topic0`.
+    pub fn event_by_topic0(&self, topic0: &[u8; 32]) -> Option<&Event> {
+        self.events
+            .iter()
+            .find(|e| !e.anonymous && e.topic0() == *topic0)
+    }
+
+    pub fn error(&self, name: &str) -> Result<&CustomError> {
+        self.errors
+            .iter()
+            .find(|e| e.name == name)
+            .ok_or_else(|| not_found("error", name))
+    }
+
+    /// Decodes revert data against the built-in and this contract's errors.
+    pub fn decode_revert(&self, data: &[u8]) -> Revert {
+        Revert::decode(data, &self.errors)
+    }
+}
+
+fn not_found(kind: &'static str, name: &str) -> Error {
+    Error::NotFound {
+        kind,
+        name: name.to_owned(),
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawParam {
+    #[serde(default)]
+    name: String,
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    components: Vec<RawParam>,
+    #[serde(default)]
+    internal_type: Option<String>,
+    #[serde(default)]
+    indexed: bool,
+}
+
+impl RawParam {
+    /// Resolves `tuple`, `tuple[]`, `tuple[2][]`, ... against the
+    /// components; other types are parsed directly.
+    fn into_param(self) -> Result<Param> {
+        let components = self
+            .components
+            .into_iter()
+            .map(RawParam::into_param)
+            .collect::<Result<Vec<_>>>()?;
+        let kind = match self.kind.strip_prefix("tuple") {
+            Some(suffix) if suffix.is_empty() || suffix.starts_with('[') => {
+                let tuple = ParamType::Tuple(components.iter().map(|c| c.kind.clone()).collect());
+                format!("{tuple}{suffix}").parse()?
+            }
+            _ => self.kind.parse()?,
+        };
+        Ok(Param {
+            name: self.name,
+            kind,
+            components,
+            internal_type: self.internal_type,
+        })
+    }
+}
+
+fn params(raw: Vec<RawParam>) -> Result<Vec<Param>> {
+    raw.into_iter().map(RawParam::into_param).collect()
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum RawItem {
+    #[serde(rename_all = "camelCase")]
+    Function {
+        name: String,
+        #[serde(default)]
+        inputs: Vec<RawParam>,
+        #[serde(default)]
+        outputs: Vec<RawParam>,
+        #[serde(default)]
+        state_mutability: StateMutability,
+    },
+    Event {
+        name: String,
+        #[serde(default)]
+        inputs: Vec<RawParam>,
+        #[serde(default)]
+        anonymous: bool,
+    },
+    Error {
+        name: String,
+        #[serde(default)]
+        inputs: Vec<RawParam>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Constructor {
+        #[serde(default)]
+        inputs: Vec<RawParam>,
+        #[serde(default)]
+        state_mutability: StateMutability,
+    },
+    Fallback {},
+    Receive {},
+}
+
+impl<'de> Deserialize<'de> for Abi {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let items = Vec::<RawItem>::deserialize(deserializer)?;
+        Abi::from_items(items).map_err(serde::de::Error::custom)
+    }
+}
+
+impl Abi {
+    fn from_items(items: Vec<RawItem>) -> Result<Self> {
+        let mut abi = Abi::default();
+        for item in items {
+            match item {
+                RawItem::Function {
+                    name,
+                    inputs,
+                    outputs,
+                    state_mutability,
+                } => abi.functions.push(Function {
+                    name,
+                    inputs: params(inputs)?,
+                    outputs: params(outputs)?,
+                    state_mutability,
+                }),
+                RawItem::Event {
+                    name,
+                    inputs,
+                    anonymous,
+                } => {
+                    let inputs = inputs
+                        .into_iter()
+                        .map(|raw| {
+                            let indexed = raw.indexed;
+                            raw.into_param().map(|param| EventParam { param, indexed })
+                        })
+                        .collect::<Result<_>>()?;
+                    abi.events.push(Event {
+                        name,
+                        inputs,
+                        anonymous,
+                    });
+                }
+                RawItem::Error { name, inputs } => abi.errors.push(CustomError {
+                    name,
+                    inputs: params(inputs)?,
+                }),
+                RawItem::Constructor {
+                    inputs,
+                    state_mutability,
+                } => {
+                    abi.constructor = Some(Constructor {
+                        inputs: params(inputs)?,
+                        state_mutability,
+                    })
+                }
+                RawItem::Fallback {} => abi.fallback = true,
+                RawItem::Receive {} => abi.receive = true,
+            }
+        }
+        Ok(abi)
+    }
+}
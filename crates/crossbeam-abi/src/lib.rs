@@ -0,0 +1,49 @@
+//! Solidity ABI codec.
+//!
+//! Values are described at runtime by [`ParamType`] and carried by
+//! [`Value`]; [`AbiType`] maps ordinary Rust types onto both. On top of the
+//! raw [`encode`]/[`decode`] functions sit [`Function`], [`Event`] and
+//! [`CustomError`], which are usually loaded from a contract's JSON ABI
+//! through [`Abi`].
+
+mod contract;
+mod convert;
+mod decode;
+mod encode;
+mod error;
+mod event;
+mod function;
+mod json;
+mod param_type;
+mod revert;
+mod value;
+
+pub use contract::{ContractCall, ContractError, ContractEvent};
+pub use convert::{AbiType, Bytes, FixedArray, I256};
+pub use crossbeam_core::address::EvmAddress as Address;
+pub use crossbeam_core::U256;
+pub use decode::decode;
+pub use encode::encode;
+pub use error::{Error, Result};
+pub use event::{DecodedLog, Event, EventParam};
+pub use function::{CustomError, Function, StateMutability};
+pub use json::{Abi, Constructor};
+pub use param_type::{Param, ParamType};
+pub use revert::{Revert, ERROR_SELECTOR, PANIC_SELECTOR};
+pub use value::Value;
+
+#[doc(hidden)]
+pub use contract::__private;
+
+/// Keccak-256 of `data`.
+pub fn keccak256(data: impl AsRef<[u8]>) -> [u8; 32] {
+    use sha3::{Digest, Keccak256};
+    Keccak256::digest(data.as_ref()).into()
+}
+
+/// The 4-byte selector of a canonical signature such as
+/// `transfer(address,uint256)`.
+pub fn selector(signature: &str) -> [u8; 4] {
+    let hash = keccak256(signature);
+    [hash[0], hash[1], hash[2], hash[3]]
+}
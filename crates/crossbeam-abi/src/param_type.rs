@@ -0,0 +1,208 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+
+/// A Solidity ABI type.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ParamType {
+    Address,
+    Bool,
+    /// `uintN`, holding `N`.
+    Uint(usize),
+    /// `intN`, holding `N`.
+    Int(usize),
+    /// `bytesN`, holding `N`.
+    FixedBytes(usize),
+    Bytes,
+    String,
+    /// `T[]`.
+    Array(Box<ParamType>),
+    /// `T[k]`.
+    FixedArray(Box<ParamType>, usize),
+    Tuple(Vec<ParamType>),
+}
+
+impl ParamType {
+    /// Whether values of this type are encoded out of line.
+    pub fn is_dynamic(&self) -> bool {
+        match self {
+            ParamType::Bytes | ParamType::String | ParamType::Array(_) => true,
+            ParamType::FixedArray(inner, len) => *len > 0 && inner.is_dynamic(),
+            ParamType::Tuple(types) => types.iter().any(ParamType::is_dynamic),
+            _ => false,
+        }
+    }
+
+    /// Number of bytes a value of this type occupies in the head of its
+    /// enclosing tuple.
+    pub fn head_size(&self) -> usize {
+        if self.is_dynamic() {
+            return 32;
+        }
+        match self {
+            // Saturates rather than overflowing for absurd declared lengths,
+            // which decoding then rejects as exceeding the data.
+            ParamType::FixedArray(inner, len) => inner.head_size().saturating_mul(*len),
+            ParamType::Tuple(types) => types
+                .iter()
+                .fold(0, |size, kind| size.saturating_add(kind.head_size())),
+            _ => 32,
+        }
+    }
+
+    fn parse_tuple(inner: &str) -> Result<Vec<ParamType>> {
+        if inner.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut types = Vec::new();
+        let mut depth = 0usize;
+        let mut start = 0;
+        for (i, c) in inner.char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' => {
+                    depth = depth
+                        .checked_sub(1)
+                        .ok_or_else(|| Error::InvalidType(inner.to_owned()))?;
+                }
+                ',' if depth == 0 => {
+                    types.push(inner[start..i].parse()?);
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+        types.push(inner[start..].parse()?);
+        Ok(types)
+    }
+}
+
+impl FromStr for ParamType {
+    type Err = Error;
+
+    /// Parses canonical and shorthand type strings: `uint256`, `uint`,
+    /// `bytes32[2][]`, `(address,(uint8,string)[])`, `tuple(bool)`.
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::InvalidType(s.to_owned());
+        let s = s.trim();
+
+        // Array suffixes bind loosest, so peel the last one first.
+        if let Some(body) = s.strip_suffix(']') {
+            let open = body.rfind('[').ok_or_else(invalid)?;
+            let inner: ParamType = body[..open].parse()?;
+            let len = &body[open + 1..];
+            return if len.is_empty() {
+                Ok(ParamType::Array(Box::new(inner)))
+            } else {
+                let len = len.parse().map_err(|_| invalid())?;
+                Ok(ParamType::FixedArray(Box::new(inner), len))
+            };
+        }
+        let tuple = s.strip_prefix("tuple").unwrap_or(s);
+        if let Some(inner) = tuple.strip_prefix('(') {
+            let inner = inner.strip_suffix(')').ok_or_else(invalid)?;
+            return Self::parse_tuple(inner).map(ParamType::Tuple);
+        }
+
+        let sized = |prefix: &str, default: usize| -> Option<Result<usize>> {
+            let digits = s.strip_prefix(prefix)?;
+            if digits.is_empty() {
+                return Some(Ok(default));
+            }
+            if digits.starts_with('0') || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return Some(Err(invalid()));
+            }
+            Some(digits.parse().map_err(|_| invalid()))
+        };
+        let ty = match s {
+            "address" => ParamType::Address,
+            "bool" => ParamType::Bool,
+            "string" => ParamType::String,
+            "bytes" => ParamType::Bytes,
+            "byte" => ParamType::FixedBytes(1),
+            // Function pointers are an address followed by a selector.
+            "function" => ParamType::FixedBytes(24),
+            _ => {
+                if let Some(bits) = sized("uint", 256) {
+                    let bits = bits?;
+                    if bits == 0 || bits > 256 || bits % 8 != 0 {
+                        return Err(invalid());
+                    }
+                    ParamType::Uint(bits)
+                } else if let Some(bits) = sized("int", 256) {
+                    let bits = bits?;
+                    if bits == 0 || bits > 256 || bits % 8 != 0 {
+                        return Err(invalid());
+                    }
+                    ParamType::Int(bits)
+                } else if let Some(len) = sized("bytes", 0) {
+                    let len = len?;
+                    if len == 0 || len > 32 {
+                        return Err(invalid());
+                    }
+                    ParamType::FixedBytes(len)
+                } else {
+                    return Err(invalid());
+                }
+            }
+        };
+        Ok(ty)
+    }
+}
+
+impl fmt::Display for ParamType {
+    /// Writes the canonical form used in signatures.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamType::Address => f.write_str("address"),
+            ParamType::Bool => f.write_str("bool"),
+            ParamType::Uint(bits) => write!(f, "uint{bits}"),
+            ParamType::Int(bits) => write!(f, "int{bits}"),
+            ParamType::FixedBytes(len) => write!(f, "bytes{len}"),
+            ParamType::Bytes => f.write_str("bytes"),
+            ParamType::String => f.write_str("string"),
+            ParamType::Array(inner) => write!(f, "{inner}[]"),
+            ParamType::FixedArray(inner, len) => write!(f, "{inner}[{len}]"),
+            ParamType::Tuple(types) => {
+                f.write_str("(")?;
+                for (i, ty) in types.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{ty}")?;
+                }
+                f.write_str(")")
+            }
+        }
+    }
+}
+
+/// A named function, event or error parameter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Param {
+    pub name: String,
+    pub kind: ParamType,
+    /// Names of tuple members, in order, for tuple types and arrays of
+    /// tuples. Empty for other types.
+    pub components: Vec<Param>,
+    /// The Solidity type the compiler reported, e.g. `struct Bridge.Deposit`.
+    pub internal_type: Option<String>,
+}
+
+impl Param {
+    pub fn new(name: impl Into<String>, kind: ParamType) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            components: Vec::new(),
+            internal_type: None,
+        }
+    }
+}
+
+/// `name(type1,type2,...)`.
+pub(crate) fn signature<'a>(name: &str, params: impl IntoIterator<Item = &'a ParamType>) -> String {
+    let types: Vec<String> = params.into_iter().map(ToString::to_string).collect();
+    format!("{name}({})", types.join(","))
+}
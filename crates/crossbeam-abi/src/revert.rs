@@ -0,0 +1,61 @@
+use crossbeam_core::U256;
+
+use crate::decode::decode;
+use crate::function::CustomError;
+use crate::param_type::ParamType;
+use crate::value::Value;
+
+/// Selector of the built-in `Error(string)`, raised by `require` and
+/// `revert("...")`.
+pub const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+/// Selector of the built-in `Panic(uint256)`, raised by failed assertions,
+/// arithmetic overflow and similar.
+pub const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Why a call reverted, decoded from its revert data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Revert {
+    /// `Error(string)`.
+    Reason(String),
+    /// `Panic(uint256)`, holding the panic code (`0x11` for overflow,
+    /// `0x12` for division by zero, ...).
+    Panic(U256),
+    /// One of the custom errors the decoder was given.
+    Custom { name: String, values: Vec<Value> },
+    /// Anything else, including an empty revert.
+    Unknown(Vec<u8>),
+}
+
+impl Revert {
+    /// Decodes revert data, trying the built-in errors first and then
+    /// `errors`. Data that matches a selector but fails to decode is
+    /// reported as [`Revert::Unknown`] rather than an error, since revert
+    /// data is not under the caller's control.
+    pub fn decode(data: &[u8], errors: &[CustomError]) -> Self {
+        let unknown = || Revert::Unknown(data.to_vec());
+        let Some((selector, args)) = data.split_first_chunk::<4>() else {
+            return unknown();
+        };
+        match *selector {
+            ERROR_SELECTOR => match decode(&[ParamType::String], args).as_deref() {
+                Ok([Value::String(reason)]) => Revert::Reason(reason.clone()),
+                _ => unknown(),
+            },
+            PANIC_SELECTOR => match decode(&[ParamType::Uint(256)], args).as_deref() {
+                Ok([Value::Uint(code, _)]) => Revert::Panic(*code),
+                _ => unknown(),
+            },
+            _ => errors
+                .iter()
+                .find(|error| error.selector() == *selector)
+                .and_then(|error| {
+                    let values = error.decode(data).ok()?;
+                    Some(Revert::Custom {
+                        name: error.name.clone(),
+                        values,
+                    })
+                })
+                .unwrap_or_else(unknown),
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use crossbeam_core::address::EvmAddress;
+use crossbeam_core::U256;
+
+use crate::error::{Error, Result};
+use crate::param_type::ParamType;
+
+/// A runtime ABI value.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Value {
+    Address(EvmAddress),
+    Bool(bool),
+    /// An unsigned integer and its bit width.
+    Uint(U256, usize),
+    /// A signed integer in 256-bit two's complement, and its bit width.
+    Int(U256, usize),
+    /// `bytesN`; the vector holds exactly `N` bytes.
+    FixedBytes(Vec<u8>),
+    Bytes(Vec<u8>),
+    String(String),
+    /// `T[]`, with the element type kept so empty arrays stay typed.
+    Array(ParamType, Vec<Value>),
+    /// `T[k]`.
+    FixedArray(ParamType, Vec<Value>),
+    Tuple(Vec<Value>),
+}
+
+impl Value {
+    /// A `uint256`.
+    pub fn uint(value: U256) -> Self {
+        Value::Uint(value, 256)
+    }
+
+    /// `int256` from an `i128`.
+    pub fn int(value: i128) -> Self {
+        Value::Int(crate::convert::i128_to_u256(value), 256)
+    }
+
+    /// The type this value encodes as.
+    pub fn param_type(&self) -> ParamType {
+        match self {
+            Value::Address(_) => ParamType::Address,
+            Value::Bool(_) => ParamType::Bool,
+            Value::Uint(_, bits) => ParamType::Uint(*bits),
+            Value::Int(_, bits) => ParamType::Int(*bits),
+            Value::FixedBytes(bytes) => ParamType::FixedBytes(bytes.len()),
+            Value::Bytes(_) => ParamType::Bytes,
+            Value::String(_) => ParamType::String,
+            Value::Array(kind, _) => ParamType::Array(Box::new(kind.clone())),
+            Value::FixedArray(kind, values) => {
+                ParamType::FixedArray(Box::new(kind.clone()), values.len())
+            }
+            Value::Tuple(values) => {
+                ParamType::Tuple(values.iter().map(Value::param_type).collect())
+            }
+        }
+    }
+
+    /// Checks that this value can be encoded as `kind`: the shapes match and
+    /// integers fit their declared width.
+    pub fn type_check(&self, kind: &ParamType) -> Result<()> {
+        let mismatch = || Error::TypeMismatch {
+            expected: kind.to_string(),
+            actual: self.param_type().to_string(),
+        };
+        match (self, kind) {
+            (Value::Address(_), ParamType::Address)
+            | (Value::Bool(_), ParamType::Bool)
+            | (Value::Bytes(_), ParamType::Bytes)
+            | (Value::String(_), ParamType::String) => Ok(()),
+            (Value::Uint(n, _), ParamType::Uint(bits)) => {
+                if n.bit_len() <= *bits {
+                    Ok(())
+                } else {
+                    Err(mismatch())
+                }
+            }
+            (Value::Int(n, _), ParamType::Int(bits)) => {
+                if fits_signed(*n, *bits) {
+                    Ok(())
+                } else {
+                    Err(mismatch())
+                }
+            }
+            (Value::FixedBytes(bytes), ParamType::FixedBytes(len)) if bytes.len() == *len => Ok(()),
+            (Value::Array(_, values), ParamType::Array(inner)) => {
+                values.iter().try_for_each(|v| v.type_check(inner))
+            }
+            (Value::FixedArray(_, values), ParamType::FixedArray(inner, len))
+                if values.len() == *len =>
+            {
+                values.iter().try_for_each(|v| v.type_check(inner))
+            }
+            (Value::Tuple(values), ParamType::Tuple(types)) if values.len() == types.len() => {
+                values
+                    .iter()
+                    .zip(types)
+                    .try_for_each(|(v, t)| v.type_check(t))
+            }
+            _ => Err(mismatch()),
+        }
+    }
+
+    pub fn as_address(&self) -> Option<EvmAddress> {
+        match self {
+            Value::Address(address) => Some(*address),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_uint(&self) -> Option<U256> {
+        match self {
+            Value::Uint(n, _) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// The byte content of `bytes` and `bytesN` values.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Value::Bytes(bytes) | Value::FixedBytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// The members of arrays and tuples.
+    pub fn as_slice(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(_, values) | Value::FixedArray(_, values) | Value::Tuple(values) => {
+                Some(values)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Whether the two's-complement `n` is the sign extension of a `bits`-wide
+/// integer.
+pub(crate) fn fits_signed(n: U256, bits: usize) -> bool {
+    if bits >= 256 {
+        return true;
+    }
+    // Bits `bits-1..256` must all equal the sign bit.
+    let high = n >> (bits - 1);
+    high.is_zero() || high == U256::MAX >> (bits - 1)
+}
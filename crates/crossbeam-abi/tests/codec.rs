@@ -0,0 +1,280 @@
+use crossbeam_abi::{decode, encode, selector, AbiType, Bytes, Function, ParamType, Revert, Value};
+use crossbeam_core::address::EvmAddress;
+use crossbeam_core::U256;
+
+fn uint(n: u64, bits: usize) -> Value {
+    Value::Uint(U256::from(n), bits)
+}
+
+fn uints(values: &[u64]) -> Value {
+    Value::Array(
+        ParamType::Uint(256),
+        values.iter().map(|n| uint(*n, 256)).collect(),
+    )
+}
+
+#[test]
+fn parses_and_displays_canonical_types() {
+    for (input, canonical) in [
+        ("uint", "uint256"),
+        ("int", "int256"),
+        ("bytes32[2][]", "bytes32[2][]"),
+        (
+            "tuple(address,(uint8,string)[])",
+            "(address,(uint8,string)[])",
+        ),
+        ("()", "()"),
+    ] {
+        let parsed: ParamType = input.parse().unwrap();
+        assert_eq!(parsed.to_string(), canonical);
+    }
+    for invalid in [
+        "uint7", "uint264", "bytes0", "bytes33", "int08", "(uint256", "foo",
+    ] {
+        assert!(invalid.parse::<ParamType>().is_err(), "{invalid}");
+    }
+    let ty: ParamType = "(uint256,string)[2]".parse().unwrap();
+    assert!(ty.is_dynamic());
+    let ty: ParamType = "(uint256,bytes4)[3]".parse().unwrap();
+    assert_eq!(ty.head_size(), 192);
+}
+
+#[test]
+fn selectors() {
+    assert_eq!(
+        hex::encode(selector("transfer(address,uint256)")),
+        "a9059cbb"
+    );
+    let f = Function::parse("baz(uint32,bool)").unwrap();
+    assert_eq!(hex::encode(f.selector()), "cdcd77c0");
+}
+
+#[test]
+fn solidity_docs_examples() {
+    let sam = Function::parse("sam(bytes,bool,uint256[])").unwrap();
+    let args = vec![
+        Value::Bytes(b"dave".to_vec()),
+        Value::Bool(true),
+        uints(&[1, 2, 3]),
+    ];
+    let data = sam.encode_input(&args).unwrap();
+    assert_eq!(
+        hex::encode(&data),
+        "a5643bf2\
+         0000000000000000000000000000000000000000000000000000000000000060\
+         0000000000000000000000000000000000000000000000000000000000000001\
+         00000000000000000000000000000000000000000000000000000000000000a0\
+         0000000000000000000000000000000000000000000000000000000000000004\
+         6461766500000000000000000000000000000000000000000000000000000000\
+         0000000000000000000000000000000000000000000000000000000000000003\
+         0000000000000000000000000000000000000000000000000000000000000001\
+         0000000000000000000000000000000000000000000000000000000000000002\
+         0000000000000000000000000000000000000000000000000000000000000003"
+    );
+    assert_eq!(sam.decode_input(&data).unwrap(), args);
+
+    let f = Function::parse("f(uint256,uint32[],bytes10,bytes)").unwrap();
+    let args = vec![
+        uint(0x123, 256),
+        Value::Array(ParamType::Uint(32), vec![uint(0x456, 32), uint(0x789, 32)]),
+        Value::FixedBytes(b"1234567890".to_vec()),
+        Value::Bytes(b"Hello, world!".to_vec()),
+    ];
+    let data = f.encode_input(&args).unwrap();
+    assert_eq!(
+        hex::encode(&data),
+        "8be65246\
+         0000000000000000000000000000000000000000000000000000000000000123\
+         0000000000000000000000000000000000000000000000000000000000000080\
+         3132333435363738393000000000000000000000000000000000000000000000\
+         00000000000000000000000000000000000000000000000000000000000000e0\
+         0000000000000000000000000000000000000000000000000000000000000002\
+         0000000000000000000000000000000000000000000000000000000000000456\
+         0000000000000000000000000000000000000000000000000000000000000789\
+         000000000000000000000000000000000000000000000000000000000000000d\
+         48656c6c6f2c20776f726c642100000000000000000000000000000000000000"
+    );
+    assert_eq!(f.decode_input(&data).unwrap(), args);
+
+    let g = Function::parse("g(uint256[][],string[])").unwrap();
+    let args = vec![
+        Value::Array(
+            ParamType::Array(Box::new(ParamType::Uint(256))),
+            vec![uints(&[1, 2]), uints(&[3])],
+        ),
+        Value::Array(
+            ParamType::String,
+            ["one", "two", "three"]
+                .into_iter()
+                .map(|s| Value::String(s.to_owned()))
+                .collect(),
+        ),
+    ];
+    let data = g.encode_input(&args).unwrap();
+    assert_eq!(
+        hex::encode(&data),
+        "2289b18c\
+         0000000000000000000000000000000000000000000000000000000000000040\
+         0000000000000000000000000000000000000000000000000000000000000140\
+         0000000000000000000000000000000000000000000000000000000000000002\
+         0000000000000000000000000000000000000000000000000000000000000040\
+         00000000000000000000000000000000000000000000000000000000000000a0\
+         0000000000000000000000000000000000000000000000000000000000000002\
+         0000000000000000000000000000000000000000000000000000000000000001\
+         0000000000000000000000000000000000000000000000000000000000000002\
+         0000000000000000000000000000000000000000000000000000000000000001\
+         0000000000000000000000000000000000000000000000000000000000000003\
+         0000000000000000000000000000000000000000000000000000000000000003\
+         0000000000000000000000000000000000000000000000000000000000000060\
+         00000000000000000000000000000000000000000000000000000000000000a0\
+         00000000000000000000000000000000000000000000000000000000000000e0\
+         0000000000000000000000000000000000000000000000000000000000000003\
+         6f6e650000000000000000000000000000000000000000000000000000000000\
+         0000000000000000000000000000000000000000000000000000000000000003\
+         74776f0000000000000000000000000000000000000000000000000000000000\
+         0000000000000000000000000000000000000000000000000000000000000005\
+         7468726565000000000000000000000000000000000000000000000000000000"
+    );
+    assert_eq!(g.decode_input(&data).unwrap(), args);
+}
+
+#[test]
+fn nested_tuples_and_signed_integers() {
+    type Entry = (EvmAddress, i32, String);
+    let address: EvmAddress = "0x9d8A62f656a8d1615C1294fd71e9CFb3E4855A4F"
+        .parse()
+        .unwrap();
+    let entry = Value::Tuple(vec![
+        Value::Address(address),
+        Value::Int(U256::from(100u8).wrapping_neg(), 24),
+        Value::String("héllo".to_owned()),
+    ]);
+    let types: Vec<ParamType> = vec![
+        "(address,int24,string)[]".parse().unwrap(),
+        "bytes32[2]".parse().unwrap(),
+        ParamType::Int(256),
+    ];
+    let values = vec![
+        Value::Array("(address,int24,string)".parse().unwrap(), vec![entry]),
+        Value::FixedArray(
+            ParamType::FixedBytes(32),
+            vec![
+                Value::FixedBytes(vec![0x11; 32]),
+                Value::FixedBytes(vec![0x22; 32]),
+            ],
+        ),
+        (-1i64).into_value(),
+    ];
+    let data = encode(&values);
+    assert_eq!(
+        hex::encode(&data),
+        "0000000000000000000000000000000000000000000000000000000000000080\
+         1111111111111111111111111111111111111111111111111111111111111111\
+         2222222222222222222222222222222222222222222222222222222222222222\
+         ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff\
+         0000000000000000000000000000000000000000000000000000000000000001\
+         0000000000000000000000000000000000000000000000000000000000000020\
+         0000000000000000000000009d8a62f656a8d1615c1294fd71e9cfb3e4855a4f\
+         ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff9c\
+         0000000000000000000000000000000000000000000000000000000000000060\
+         0000000000000000000000000000000000000000000000000000000000000006\
+         68c3a96c6c6f0000000000000000000000000000000000000000000000000000"
+    );
+
+    let decoded = decode(&types, &data).unwrap();
+    let mut decoded = decoded.into_iter();
+    let entries = Vec::<Entry>::from_value(decoded.next().unwrap()).unwrap();
+    assert_eq!(entries, vec![(address, -100, "héllo".to_owned())]);
+    let _ = decoded.next();
+    assert_eq!(i64::from_value(decoded.next().unwrap()).unwrap(), -1);
+}
+
+#[test]
+fn typed_round_trip() {
+    let value = (
+        EvmAddress::new([0xab; 20]),
+        U256::MAX,
+        Bytes(vec![1, 2, 3]),
+        vec![true, false],
+        [0xcd_u8; 4],
+        (u8::MAX, i128::MIN),
+    );
+    type T = (EvmAddress, U256, Bytes, Vec<bool>, [u8; 4], (u8, i128));
+    let data = encode(&[value.clone().into_value()]);
+    let decoded = decode(&[T::param_type()], &data).unwrap().pop().unwrap();
+    assert_eq!(T::from_value(decoded).unwrap(), value);
+}
+
+#[test]
+fn rejects_out_of_range_arguments() {
+    let f = Function::parse("set(uint8,int8)").unwrap();
+    assert!(f.encode_input(&[uint(255, 8), Value::int(-128)]).is_ok());
+    assert!(f.encode_input(&[uint(256, 8), Value::int(0)]).is_err());
+    assert!(f.encode_input(&[uint(0, 8), Value::int(128)]).is_err());
+    assert!(f.encode_input(&[uint(0, 8)]).is_err());
+}
+
+#[test]
+fn strict_decoding() {
+    let word = |hex_str: &str| hex::decode(format!("{hex_str:0>64}")).unwrap();
+
+    assert!(decode(&[ParamType::Bool], &word("2")).is_err());
+    assert!(decode(&[ParamType::Uint(8)], &word("100")).is_err());
+    // int8 -1 must be sign-extended across the whole word.
+    assert!(decode(&[ParamType::Int(8)], &word("ff")).is_err());
+    assert!(decode(&[ParamType::Address], &word("1".repeat(41).as_str())).is_err());
+    assert!(decode(&[ParamType::FixedBytes(1)], &word("0101")).is_err());
+    // Offset pointing past the data.
+    assert!(decode(&[ParamType::Bytes], &word("40")).is_err());
+    // Array length far beyond what the data could hold.
+    let mut data = word("20");
+    data.extend(word("ffffffff"));
+    assert!(decode(&[ParamType::Array(Box::new(ParamType::Uint(256)))], &data).is_err());
+    // Declared fixed array lengths are held to the data as well.
+    for kind in [
+        "uint256[1000000000000]",
+        "uint256[1000000000000][1000000000000]",
+        "string[1000000000000]",
+    ] {
+        let kind: ParamType = kind.parse().unwrap();
+        assert!(decode(&[kind], &data).is_err());
+    }
+    // Truncated input.
+    assert!(decode(&[ParamType::Uint(256)], &[0; 31]).is_err());
+    // Invalid UTF-8.
+    let mut data = word("20");
+    data.extend(word("1"));
+    data.extend(hex::decode(format!("{:0<64}", "ff")).unwrap());
+    assert!(decode(&[ParamType::String], &data).is_err());
+}
+
+#[test]
+fn decodes_revert_reasons() {
+    let data = hex::decode(
+        "08c379a0\
+         0000000000000000000000000000000000000000000000000000000000000020\
+         000000000000000000000000000000000000000000000000000000000000001a\
+         4e6f7420656e6f7567682045746865722070726f76696465642e000000000000",
+    )
+    .unwrap();
+    assert_eq!(
+        Revert::decode(&data, &[]),
+        Revert::Reason("Not enough Ether provided.".to_owned())
+    );
+
+    let mut panic = hex::decode("4e487b71").unwrap();
+    panic.extend(encode(&[uint(0x11, 256)]));
+    assert_eq!(Revert::decode(&panic, &[]), Revert::Panic(U256::from(0x11)));
+
+    let error = crossbeam_abi::CustomError::parse("InsufficientBalance(uint256,uint256)").unwrap();
+    let data = error.encode(&[uint(1, 256), uint(2, 256)]).unwrap();
+    assert_eq!(
+        Revert::decode(&data, std::slice::from_ref(&error)),
+        Revert::Custom {
+            name: "InsufficientBalance".to_owned(),
+            values: vec![uint(1, 256), uint(2, 256)],
+        }
+    );
+    assert_eq!(Revert::decode(&data, &[]), Revert::Unknown(data.clone()));
+    assert_eq!(Revert::decode(&[], &[]), Revert::Unknown(Vec::new()));
+}
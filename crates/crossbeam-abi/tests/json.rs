@@ -0,0 +1,181 @@
+use crossbeam_abi::{Abi, AbiType, Event, ParamType, Revert, StateMutability, Value};
+use crossbeam_core::address::EvmAddress;
+use crossbeam_core::U256;
+
+const BRIDGE_ABI: &str = r#"[
+  {"type": "constructor", "inputs": [{"name": "owner", "type": "address"}], "stateMutability": "nonpayable"},
+  {"type": "function", "name": "lock", "stateMutability": "payable",
+   "inputs": [
+     {"name": "deposit", "type": "tuple", "internalType": "struct Bridge.Deposit", "components": [
+       {"name": "recipient", "type": "bytes"},
+       {"name": "amount", "type": "uint256"},
+       {"name": "targetChain", "type": "uint64"}
+     ]}
+   ],
+   "outputs": [{"name": "id", "type": "bytes32"}]},
+  {"type": "function", "name": "batches", "stateMutability": "view",
+   "inputs": [],
+   "outputs": [{"name": "", "type": "tuple[2][]", "components": [
+     {"name": "to", "type": "address"},
+     {"name": "value", "type": "uint128"}
+   ]}]},
+  {"type": "event", "name": "Locked", "anonymous": false, "inputs": [
+    {"name": "sender", "type": "address", "indexed": true},
+    {"name": "recipient", "type": "bytes", "indexed": true},
+    {"name": "amount", "type": "uint256", "indexed": false},
+    {"name": "memo", "type": "string", "indexed": false}
+  ]},
+  {"type": "error", "name": "Paused", "inputs": []},
+  {"type": "receive", "stateMutability": "payable"}
+]"#;
+
+#[test]
+fn loads_bridge_abi() {
+    let abi = Abi::from_json(BRIDGE_ABI).unwrap();
+    assert!(abi.receive);
+    assert!(!abi.fallback);
+    assert_eq!(abi.constructor.as_ref().unwrap().inputs[0].name, "owner");
+
+    let lock = abi.function("lock").unwrap();
+    assert_eq!(lock.signature(), "lock((bytes,uint256,uint64))");
+    assert_eq!(lock.state_mutability, StateMutability::Payable);
+    assert_eq!(lock.inputs[0].components[2].name, "targetChain");
+    assert_eq!(
+        lock.inputs[0].internal_type.as_deref(),
+        Some("struct Bridge.Deposit")
+    );
+    assert_eq!(abi.function_by_selector(lock.selector()), Some(lock));
+
+    let batches = abi.function("batches").unwrap();
+    assert_eq!(
+        batches.outputs[0].kind.to_string(),
+        "(address,uint128)[2][]"
+    );
+
+    assert!(matches!(
+        abi.function("unlock"),
+        Err(crossbeam_abi::Error::NotFound {
+            kind: "function",
+            ..
+        })
+    ));
+}
+
+#[test]
+fn loads_build_artifacts() {
+    let artifact =
+        format!(r#"{{"contractName": "Bridge", "abi": {BRIDGE_ABI}, "bytecode": "0x"}}"#);
+    assert_eq!(
+        Abi::from_json(&artifact).unwrap(),
+        Abi::from_json(BRIDGE_ABI).unwrap()
+    );
+    assert!(Abi::from_json(
+        r#"[{"type": "function", "name": "f", "inputs": [{"type": "uint7"}]}]"#
+    )
+    .is_err());
+}
+
+#[test]
+fn erc20_transfer_event() {
+    let event = Event::parse("Transfer(address indexed,address indexed,uint256)").unwrap();
+    assert_eq!(
+        hex::encode(event.topic0()),
+        "ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"
+    );
+
+    let from = EvmAddress::new([0x11; 20]);
+    let to = EvmAddress::new([0x22; 20]);
+    let topics = [
+        event.topic0(),
+        Event::encode_topic(&Value::Address(from)),
+        Event::encode_topic(&Value::Address(to)),
+    ];
+    let data = crossbeam_abi::encode(&[U256::from(1_000u64).into_value()]);
+    let log = event.decode_log(&topics, &data).unwrap();
+    let values: Vec<_> = log.into_values();
+    assert_eq!(
+        values,
+        vec![
+            Value::Address(from),
+            Value::Address(to),
+            Value::Uint(U256::from(1_000u64), 256)
+        ]
+    );
+
+    assert!(event.decode_log(&topics[..2], &data).is_err());
+    let mut wrong = topics;
+    wrong[0] = [0; 32];
+    assert!(event.decode_log(&wrong, &data).is_err());
+}
+
+#[test]
+fn indexed_dynamic_params_are_hashes() {
+    let abi = Abi::from_json(BRIDGE_ABI).unwrap();
+    let event = abi.event("Locked").unwrap();
+    assert_eq!(event.signature(), "Locked(address,bytes,uint256,string)");
+    assert_eq!(abi.event_by_topic0(&event.topic0()), Some(event));
+
+    let sender = EvmAddress::new([0x33; 20]);
+    let recipient = Value::Bytes(b"bridge".to_vec());
+    let recipient_topic = Event::encode_topic(&recipient);
+    assert_eq!(
+        hex::encode(recipient_topic),
+        "0683d1c283a672fc58eb7940a0dba83ea98b96966a9ca1b030dec2c60cea4d1e"
+    );
+    let topics = [
+        event.topic0(),
+        Event::encode_topic(&Value::Address(sender)),
+        recipient_topic,
+    ];
+    let data =
+        crossbeam_abi::encode(&[Value::uint(U256::from(5u8)), Value::String("hi".to_owned())]);
+    let log = event.decode_log(&topics, &data).unwrap();
+    assert_eq!(log.get("sender"), Some(&Value::Address(sender)));
+    assert_eq!(
+        log.get("recipient"),
+        Some(&Value::FixedBytes(recipient_topic.to_vec()))
+    );
+    assert_eq!(log.get("memo").and_then(Value::as_str), Some("hi"));
+}
+
+#[test]
+fn decodes_declared_errors() {
+    let abi = Abi::from_json(BRIDGE_ABI).unwrap();
+    let paused = abi.error("Paused").unwrap();
+    assert_eq!(
+        abi.decode_revert(&paused.selector()),
+        Revert::Custom {
+            name: "Paused".to_owned(),
+            values: Vec::new(),
+        }
+    );
+}
+
+#[test]
+fn encodes_struct_arguments() {
+    let abi = Abi::from_json(BRIDGE_ABI).unwrap();
+    let lock = abi.function("lock").unwrap();
+    let deposit = (
+        crossbeam_abi::Bytes(vec![0xaa; 20]),
+        U256::from(10u8),
+        56u64,
+    );
+    let data = lock.encode_input(&[deposit.clone().into_value()]).unwrap();
+    let decoded = lock.decode_input(&data).unwrap().pop().unwrap();
+    assert_eq!(decoded.param_type(), lock.inputs[0].kind);
+    assert_eq!(
+        <(crossbeam_abi::Bytes, U256, u64)>::from_value(decoded).unwrap(),
+        deposit
+    );
+    assert!(lock
+        .encode_input(&[Value::Tuple(vec![Value::Bool(true)])])
+        .is_err());
+    assert_eq!(
+        lock.inputs[0].kind,
+        ParamType::Tuple(vec![
+            ParamType::Bytes,
+            ParamType::Uint(256),
+            ParamType::Uint(64)
+        ])
+    );
+}
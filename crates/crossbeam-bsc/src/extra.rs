@@ -0,0 +1,261 @@
+//! Parlia consensus data carried in a BSC header's `extra_data`.
+//!
+//! Every header starts with 32 vanity bytes and ends with the proposer's
+//! 65-byte seal. In between, blocks that open an epoch list the validator
+//! set, and blocks finalized by fast-finality votes carry the aggregated
+//! vote. Both grew over time, so parsing needs the [`ExtraLayout`] in force
+//! at the block.
+
+use crossbeam_ethereum::header::Header;
+use crossbeam_ethereum::rlp::{ListEncoder, Rlp};
+use crossbeam_ethereum::{keccak256, Address, Error, Result, B256};
+
+/// Leading bytes free for the proposer's use.
+pub const EXTRA_VANITY: usize = 32;
+/// Trailing secp256k1 signature of the proposer.
+pub const EXTRA_SEAL: usize = 65;
+/// Length of a validator's BLS vote key.
+pub const BLS_PUBLIC_KEY_LENGTH: usize = 48;
+/// Length of an aggregated BLS signature.
+pub const BLS_SIGNATURE_LENGTH: usize = 96;
+
+const VALIDATOR_LENGTH: usize = 20 + BLS_PUBLIC_KEY_LENGTH;
+
+fn extra_error(reason: impl Into<String>) -> Error {
+    Error::InvalidHeader(reason.into())
+}
+
+/// The shape of `extra_data`, by the hard fork that last changed it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ExtraLayout {
+    /// Epoch blocks list bare validator addresses.
+    PreLuban,
+    /// Epoch blocks prefix the set with its size and pair each address with
+    /// a BLS vote key. Vote attestations follow from Plato on.
+    Luban,
+    /// Epoch blocks also carry the number of consecutive blocks each
+    /// validator proposes.
+    Bohr,
+}
+
+/// A member of the validator set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Validator {
+    /// The consensus address that seals blocks.
+    pub address: Address,
+    /// The BLS key it votes with; `None` before Luban.
+    pub vote_address: Option<[u8; BLS_PUBLIC_KEY_LENGTH]>,
+}
+
+/// The justified block a vote extends, and the block it votes for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VoteData {
+    pub source_number: u64,
+    pub source_hash: B256,
+    pub target_number: u64,
+    pub target_hash: B256,
+}
+
+impl VoteData {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut list = ListEncoder::new();
+        list.append(&self.source_number)
+            .append(&self.source_hash)
+            .append(&self.target_number)
+            .append(&self.target_hash);
+        list.into_bytes()
+    }
+
+    fn decode(item: &Rlp<'_>) -> Result<Self> {
+        let items = item.items()?;
+        let [source_number, source_hash, target_number, target_hash] = items.as_slice() else {
+            return Err(extra_error(format!(
+                "vote data with {} fields",
+                items.len()
+            )));
+        };
+        Ok(Self {
+            source_number: source_number.as_u64()?,
+            source_hash: source_hash.as_b256()?,
+            target_number: target_number.as_u64()?,
+            target_hash: target_hash.as_b256()?,
+        })
+    }
+
+    /// The message validators sign with their BLS keys.
+    pub fn hash(&self) -> B256 {
+        keccak256(self.encode())
+    }
+}
+
+/// Fast-finality votes aggregated into a header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VoteAttestation {
+    /// Bit `i` is set if the `i`-th validator of the current set voted.
+    pub vote_address_set: u64,
+    pub agg_signature: [u8; BLS_SIGNATURE_LENGTH],
+    pub data: VoteData,
+    pub extra: Vec<u8>,
+}
+
+impl VoteAttestation {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut list = ListEncoder::new();
+        list.append(&self.vote_address_set)
+            .append(&self.agg_signature)
+            .append_raw(&self.data.encode())
+            .append(self.extra.as_slice());
+        list.into_bytes()
+    }
+
+    pub fn decode(encoded: &[u8]) -> Result<Self> {
+        let items = Rlp::new(encoded)?.items()?;
+        let [vote_address_set, agg_signature, data, extra] = items.as_slice() else {
+            return Err(extra_error(format!(
+                "vote attestation with {} fields",
+                items.len()
+            )));
+        };
+        Ok(Self {
+            vote_address_set: vote_address_set.as_u64()?,
+            agg_signature: agg_signature.fixed()?,
+            data: VoteData::decode(data)?,
+            extra: extra.bytes()?.to_vec(),
+        })
+    }
+
+    /// The positions, within the validator set, of the validators that
+    /// voted.
+    pub fn voters(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..64).filter(|i| self.vote_address_set & (1 << i) != 0)
+    }
+}
+
+/// The parsed `extra_data` of a BSC header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtraData {
+    pub vanity: [u8; EXTRA_VANITY],
+    /// The validator set taking over; only set on epoch blocks.
+    pub validators: Option<Vec<Validator>>,
+    /// Blocks each validator proposes in a row; on epoch blocks from Bohr.
+    pub turn_length: Option<u8>,
+    pub vote_attestation: Option<VoteAttestation>,
+    pub seal: [u8; EXTRA_SEAL],
+}
+
+impl ExtraData {
+    /// Parses the extra data of `header`, which opens an epoch if its number
+    /// is a multiple of `epoch_length`.
+    pub fn of(header: &Header, epoch_length: u64, layout: ExtraLayout) -> Result<Self> {
+        let offset = header
+            .number
+            .checked_rem(epoch_length)
+            .ok_or_else(|| extra_error("epoch length is zero"))?;
+        Self::parse(&header.extra_data, offset == 0, layout)
+    }
+
+    /// Parses `extra`, taken from an epoch block if `epoch` is set.
+    pub fn parse(extra: &[u8], epoch: bool, layout: ExtraLayout) -> Result<Self> {
+        if extra.len() < EXTRA_VANITY + EXTRA_SEAL {
+            return Err(extra_error(format!(
+                "extra data of {} bytes lacks vanity and seal",
+                extra.len()
+            )));
+        }
+        let (vanity, rest) = extra.split_at(EXTRA_VANITY);
+        let (mut body, seal) = rest.split_at(rest.len() - EXTRA_SEAL);
+
+        let mut validators = None;
+        let mut turn_length = None;
+        if epoch && layout == ExtraLayout::PreLuban {
+            if body.len() % 20 != 0 {
+                return Err(extra_error(format!(
+                    "validator list of {} bytes",
+                    body.len()
+                )));
+            }
+            let set = body
+                .chunks(20)
+                .map(|address| Validator {
+                    address: Address::new(address.try_into().expect("20-byte chunk")),
+                    vote_address: None,
+                })
+                .collect();
+            validators = Some(set);
+            body = &[];
+        } else if epoch {
+            let (&count, rest) = body
+                .split_first()
+                .ok_or_else(|| extra_error("epoch block without a validator set"))?;
+            let len = usize::from(count) * VALIDATOR_LENGTH;
+            if rest.len() < len {
+                return Err(extra_error(format!(
+                    "{count} validators in {} bytes",
+                    rest.len()
+                )));
+            }
+            let (set, rest) = rest.split_at(len);
+            validators = Some(
+                set.chunks(VALIDATOR_LENGTH)
+                    .map(|validator| {
+                        let (address, vote_address) = validator.split_at(20);
+                        Validator {
+                            address: Address::new(address.try_into().expect("20 bytes")),
+                            vote_address: Some(vote_address.try_into().expect("48 bytes")),
+                        }
+                    })
+                    .collect(),
+            );
+            body = rest;
+            if layout >= ExtraLayout::Bohr {
+                let (&turns, rest) = body
+                    .split_first()
+                    .ok_or_else(|| extra_error("epoch block without a turn length"))?;
+                turn_length = Some(turns);
+                body = rest;
+            }
+        }
+
+        let vote_attestation = match body {
+            [] => None,
+            _ if layout == ExtraLayout::PreLuban => {
+                return Err(extra_error(format!(
+                    "{} unexpected bytes before the seal",
+                    body.len()
+                )))
+            }
+            attestation => Some(VoteAttestation::decode(attestation)?),
+        };
+        Ok(Self {
+            vanity: vanity.try_into().expect("vanity length"),
+            validators,
+            turn_length,
+            vote_attestation,
+            seal: seal.try_into().expect("seal length"),
+        })
+    }
+
+    /// Re-encodes the extra data. Validators have BLS keys either all or
+    /// none, as in the layout they were parsed from.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = self.vanity.to_vec();
+        if let Some(validators) = &self.validators {
+            let keyed = validators.iter().any(|v| v.vote_address.is_some());
+            if keyed {
+                out.push(validators.len() as u8);
+            }
+            for validator in validators {
+                out.extend_from_slice(&validator.address.0);
+                if keyed {
+                    out.extend_from_slice(&validator.vote_address.unwrap_or([0; 48]));
+                }
+            }
+        }
+        out.extend(self.turn_length);
+        if let Some(attestation) = &self.vote_attestation {
+            out.extend_from_slice(&attestation.encode());
+        }
+        out.extend_from_slice(&self.seal);
+        out
+    }
+}
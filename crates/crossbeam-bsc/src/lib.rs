@@ -0,0 +1,39 @@
+//! Binance Smart Chain adapter for the CrossBeam SDK.
+//!
+//! BSC executes the EVM, so transaction building, signing and RPC access are
+//! provided by [`crossbeam_ethereum`]. This crate configures the shared
+//! [`EvmChain`] for BSC, parses the Parlia consensus data BSC headers carry
+//! in their extra data, and verifies header chains with a Parlia
+//! [`LightClient`].
+
+pub mod extra;
+pub mod parlia;
+
+use crossbeam_core::Network;
+pub use crossbeam_ethereum::{EvmChain, FeeStrategy, Header, Provider};
+pub use extra::{ExtraData, ExtraLayout, Validator, VoteAttestation, VoteData};
+pub use parlia::{BlockId, LightClient, ParliaConfig, Verified};
+
+/// EIP-155 chain id of BSC mainnet.
+pub const MAINNET_CHAIN_ID: u64 = 56;
+/// EIP-155 chain id of the BSC testnet (Chapel).
+pub const TESTNET_CHAIN_ID: u64 = 97;
+
+/// An adapter for BSC mainnet, priced with [`fee_strategy`].
+pub fn mainnet<P: Provider>(provider: P) -> EvmChain<P> {
+    EvmChain::new(Network::BinanceSmartChain, MAINNET_CHAIN_ID, provider)
+        .with_fee_strategy(fee_strategy())
+}
+
+/// An adapter for the BSC testnet, priced with [`fee_strategy`].
+pub fn testnet<P: Provider>(provider: P) -> EvmChain<P> {
+    EvmChain::new(Network::BinanceSmartChain, TESTNET_CHAIN_ID, provider)
+        .with_fee_strategy(fee_strategy())
+}
+
+/// Legacy gas pricing at the node's suggested price. BSC validators accept
+/// EIP-1559 transactions but run with a zero base fee, so a plain gas price
+/// is both cheaper to estimate and what wallets on the network expect.
+pub fn fee_strategy() -> FeeStrategy {
+    FeeStrategy::legacy()
+}
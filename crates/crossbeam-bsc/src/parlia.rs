@@ -0,0 +1,389 @@
+//! A Parlia light client.
+//!
+//! BSC blocks are sealed in turn by a small validator set that rotates at
+//! epoch boundaries, and since Plato validators also cast BLS votes that
+//! finalize blocks two at a time. [`LightClient`] follows a chain of headers
+//! from a trusted epoch block: it checks every seal against the validator
+//! set in force, picks up new sets from epoch blocks, and verifies the vote
+//! attestations to track which blocks are final.
+//!
+//! The client trusts nothing an RPC node says beyond the headers themselves,
+//! so a bridge can release funds once [`LightClient::is_final`] holds for the
+//! block a deposit landed in.
+
+use std::collections::BTreeMap;
+
+use crossbeam_core::U256;
+use crossbeam_ethereum::bls;
+use crossbeam_ethereum::header::Header;
+use crossbeam_ethereum::rlp::ListEncoder;
+use crossbeam_ethereum::{keccak256, Address, Error, Result, Signature, B256};
+
+use crate::extra::{ExtraData, ExtraLayout, Validator, VoteData, EXTRA_SEAL};
+
+/// Difficulty of a block sealed by the validator whose turn it is.
+pub const DIFFICULTY_IN_TURN: u64 = 2;
+/// Difficulty of a block sealed by any other validator.
+pub const DIFFICULTY_NO_TURN: u64 = 1;
+/// How many block hashes behind the head [`LightClient::is_final`] can
+/// answer for.
+pub const HASH_HISTORY: u64 = 8192;
+
+fn consensus_error(reason: impl Into<String>) -> Error {
+    Error::InvalidHeader(reason.into())
+}
+
+/// The chain parameters a [`LightClient`] verifies against. BSC hard forks
+/// change the epoch length and the extra-data layout; the configuration is
+/// the one in force from the checkpoint on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParliaConfig {
+    pub chain_id: u64,
+    pub epoch_length: u64,
+    pub layout: ExtraLayout,
+}
+
+/// A block by number and hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BlockId {
+    pub number: u64,
+    pub hash: B256,
+}
+
+/// What [`LightClient::apply`] learned from a header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Verified {
+    pub block: BlockId,
+    /// The validator that sealed the block.
+    pub signer: Address,
+    /// Whether it was the signer's turn.
+    pub in_turn: bool,
+    /// The votes the header carried, if any.
+    pub attestation: Option<VoteData>,
+}
+
+/// The hash a validator seals: the header with the seal cut from its extra
+/// data and the chain id prepended.
+pub fn seal_hash(header: &Header, chain_id: u64) -> Result<B256> {
+    let Some(unsealed) = header.extra_data.len().checked_sub(EXTRA_SEAL) else {
+        return Err(consensus_error("extra data without a seal"));
+    };
+    let mut list = ListEncoder::new();
+    list.append(&chain_id)
+        .append(&header.parent_hash)
+        .append(&header.ommers_hash)
+        .append(&header.beneficiary)
+        .append(&header.state_root)
+        .append(&header.transactions_root)
+        .append(&header.receipts_root)
+        .append(&header.logs_bloom)
+        .append(&header.difficulty)
+        .append(&header.number)
+        .append(&header.gas_limit)
+        .append(&header.gas_used)
+        .append(&header.timestamp)
+        .append(&header.extra_data[..unsealed])
+        .append(&header.mix_hash)
+        .append(&header.nonce);
+    // BSC leaves the parent beacon root zeroed, and only then are the
+    // fields from London on covered by the seal. Absent fields encode as
+    // empty strings.
+    if header.parent_beacon_block_root == Some(B256::ZERO) {
+        let withdrawals_root = header
+            .withdrawals_root
+            .map(|root| root.0.to_vec())
+            .unwrap_or_default();
+        list.append(&header.base_fee_per_gas.unwrap_or_default())
+            .append(&withdrawals_root)
+            .append(&header.blob_gas_used.unwrap_or_default())
+            .append(&header.excess_blob_gas.unwrap_or_default())
+            .append(&B256::ZERO);
+        if let Some(requests_hash) = header.requests_hash {
+            list.append(&requests_hash);
+        }
+    }
+    Ok(keccak256(list.into_bytes()))
+}
+
+/// Recovers the validator that sealed `header`.
+pub fn recover_signer(header: &Header, chain_id: u64) -> Result<Address> {
+    let seal: &[u8; EXTRA_SEAL] = header
+        .extra_data
+        .len()
+        .checked_sub(EXTRA_SEAL)
+        .map(|start| header.extra_data[start..].try_into().expect("seal length"))
+        .ok_or_else(|| consensus_error("extra data without a seal"))?;
+    Signature::from_bytes(seal)?.recover(&seal_hash(header, chain_id)?)
+}
+
+/// Rejects an epoch block announcing a set that could never seal.
+fn check_announced(extra: &ExtraData) -> Result<()> {
+    if extra.validators.as_ref().is_some_and(Vec::is_empty) || extra.turn_length == Some(0) {
+        return Err(consensus_error(
+            "epoch block announces an empty validator set",
+        ));
+    }
+    Ok(())
+}
+
+/// Verifies a chain of BSC headers from a trusted checkpoint.
+#[derive(Debug, Clone)]
+pub struct LightClient {
+    config: ParliaConfig,
+    head: BlockId,
+    /// The set in force, sorted by address as turns and vote bits are.
+    validators: Vec<Validator>,
+    turn_length: u8,
+    /// The set announced by the last epoch block, until it takes over.
+    pending: Option<(Vec<Validator>, u8)>,
+    /// Signers of recent blocks by number.
+    recents: BTreeMap<u64, Address>,
+    hashes: BTreeMap<u64, B256>,
+    justified: Option<BlockId>,
+    finalized: Option<BlockId>,
+}
+
+impl LightClient {
+    /// Starts from `checkpoint`, an epoch block the caller trusts, and the
+    /// validator set and turn length in force when it was sealed. The set
+    /// the checkpoint announces is read from its extra data.
+    ///
+    /// Who sealed the blocks before the checkpoint is unknown, so the limit
+    /// on how often a validator may seal only applies from there on.
+    pub fn new(
+        config: ParliaConfig,
+        checkpoint: &Header,
+        mut validators: Vec<Validator>,
+        turn_length: u8,
+    ) -> Result<Self> {
+        if config.epoch_length == 0 {
+            return Err(consensus_error("epoch length is zero"));
+        }
+        if checkpoint.number % config.epoch_length != 0 {
+            return Err(consensus_error(format!(
+                "checkpoint {} is not an epoch block",
+                checkpoint.number
+            )));
+        }
+        if validators.is_empty() || turn_length == 0 {
+            return Err(consensus_error("empty validator set"));
+        }
+        let extra = ExtraData::of(checkpoint, config.epoch_length, config.layout)?;
+        check_announced(&extra)?;
+        validators.sort_by_key(|validator| validator.address);
+        let head = BlockId {
+            number: checkpoint.number,
+            hash: checkpoint.hash()?,
+        };
+        let mut client = Self {
+            config,
+            head,
+            validators,
+            turn_length,
+            pending: None,
+            recents: BTreeMap::new(),
+            hashes: BTreeMap::from([(head.number, head.hash)]),
+            justified: None,
+            finalized: None,
+        };
+        client.pending = client.announced(&extra);
+        Ok(client)
+    }
+
+    pub fn config(&self) -> &ParliaConfig {
+        &self.config
+    }
+
+    /// The last header applied.
+    pub fn head(&self) -> BlockId {
+        self.head
+    }
+
+    /// The validator set in force, sorted by address.
+    pub fn validators(&self) -> &[Validator] {
+        &self.validators
+    }
+
+    pub fn turn_length(&self) -> u8 {
+        self.turn_length
+    }
+
+    /// The latest block with a quorum of votes.
+    pub fn justified(&self) -> Option<BlockId> {
+        self.justified
+    }
+
+    /// The latest finalized block: a justified block whose child is
+    /// justified too.
+    pub fn finalized(&self) -> Option<BlockId> {
+        self.finalized
+    }
+
+    /// Whether the block `number` with `hash` is final. Blocks more than
+    /// [`HASH_HISTORY`] behind the head, or before the checkpoint, are not
+    /// known and reported as not final.
+    pub fn is_final(&self, number: u64, hash: &B256) -> bool {
+        self.finalized
+            .is_some_and(|finalized| number <= finalized.number)
+            && self.hashes.get(&number) == Some(hash)
+    }
+
+    /// The validator whose turn it is to seal block `number`.
+    pub fn in_turn_validator(&self, number: u64) -> Address {
+        let turn = number / u64::from(self.turn_length) % self.validators.len() as u64;
+        self.validators[turn as usize].address
+    }
+
+    /// Verifies `header`, the child of the head, and makes it the new head.
+    /// On error the client is left unchanged.
+    pub fn apply(&mut self, header: &Header) -> Result<Verified> {
+        let number = self.head.number + 1;
+        if header.number != number || header.parent_hash != self.head.hash {
+            return Err(consensus_error(format!(
+                "block {} does not extend {} ({})",
+                header.number, self.head.number, self.head.hash
+            )));
+        }
+        let hash = header.hash()?;
+        let extra = ExtraData::of(header, self.config.epoch_length, self.config.layout)?;
+        check_announced(&extra)?;
+
+        let signer = recover_signer(header, self.config.chain_id)?;
+        if signer != header.beneficiary {
+            return Err(consensus_error(format!(
+                "block {number} sealed by {signer} for {}",
+                header.beneficiary
+            )));
+        }
+        if !self.validators.iter().any(|v| v.address == signer) {
+            return Err(consensus_error(format!(
+                "block {number} sealed by non-validator {signer}"
+            )));
+        }
+        let history = self.history_length();
+        let sealed = self
+            .recents
+            .range(self.head.number.saturating_sub(history) + 1..)
+            .filter(|(_, recent)| **recent == signer)
+            .count();
+        if sealed >= usize::from(self.turn_length) {
+            return Err(consensus_error(format!(
+                "{signer} sealed {sealed} of the last {history} blocks"
+            )));
+        }
+        let in_turn = self.in_turn_validator(number) == signer;
+        let difficulty = if in_turn {
+            DIFFICULTY_IN_TURN
+        } else {
+            DIFFICULTY_NO_TURN
+        };
+        if header.difficulty != U256::from(difficulty) {
+            return Err(consensus_error(format!(
+                "block {number} has difficulty {}, expected {difficulty}",
+                header.difficulty
+            )));
+        }
+
+        let attestation = match &extra.vote_attestation {
+            Some(attestation) => {
+                if attestation.data.target_number != self.head.number
+                    || attestation.data.target_hash != self.head.hash
+                {
+                    return Err(consensus_error(format!(
+                        "block {number} attests block {}, not its parent",
+                        attestation.data.target_number
+                    )));
+                }
+                if let Some(justified) = self.justified {
+                    if attestation.data.source_number != justified.number
+                        || attestation.data.source_hash != justified.hash
+                    {
+                        return Err(consensus_error(format!(
+                            "block {number} attests from block {}, not the justified {}",
+                            attestation.data.source_number, justified.number
+                        )));
+                    }
+                } else if attestation.data.source_number >= attestation.data.target_number {
+                    return Err(consensus_error("attestation source after its target"));
+                }
+                let mut keys = Vec::new();
+                for voter in attestation.voters() {
+                    let validator = self.validators.get(voter).ok_or_else(|| {
+                        consensus_error(format!(
+                            "vote from validator {voter} of {}",
+                            self.validators.len()
+                        ))
+                    })?;
+                    keys.push(validator.vote_address.ok_or_else(|| {
+                        consensus_error(format!("{} has no vote key", validator.address))
+                    })?);
+                }
+                let quorum = (2 * self.validators.len()).div_ceil(3);
+                if keys.len() < quorum {
+                    return Err(consensus_error(format!(
+                        "{} votes, {quorum} needed",
+                        keys.len()
+                    )));
+                }
+                if !bls::fast_aggregate_verify(
+                    &keys,
+                    &attestation.data.hash().0,
+                    &attestation.agg_signature,
+                )? {
+                    return Err(Error::InvalidSignature(format!(
+                        "vote attestation in block {number}"
+                    )));
+                }
+                Some(attestation.data)
+            }
+            None => None,
+        };
+
+        self.head = BlockId { number, hash };
+        self.hashes.insert(number, hash);
+        self.hashes = self.hashes.split_off(&number.saturating_sub(HASH_HISTORY));
+        self.recents.insert(number, signer);
+        self.recents = self.recents.split_off(&number.saturating_sub(history));
+        if let Some(data) = attestation {
+            self.justified = Some(BlockId {
+                number: data.target_number,
+                hash: data.target_hash,
+            });
+            if data.target_number == data.source_number + 1 {
+                self.finalized = Some(BlockId {
+                    number: data.source_number,
+                    hash: data.source_hash,
+                });
+            }
+        }
+        if extra.validators.is_some() {
+            self.pending = self.announced(&extra);
+        } else if number % self.config.epoch_length == history {
+            if let Some((validators, turn_length)) = self.pending.take() {
+                self.validators = validators;
+                self.turn_length = turn_length;
+            }
+        }
+
+        Ok(Verified {
+            block: self.head,
+            signer,
+            in_turn,
+            attestation,
+        })
+    }
+
+    /// How many blocks back the sealing limit looks; also how far into an
+    /// epoch the announced set takes over.
+    fn history_length(&self) -> u64 {
+        (self.validators.len() as u64 / 2 + 1) * u64::from(self.turn_length) - 1
+    }
+
+    /// The set an epoch block announces, sorted, with the turn length it
+    /// keeps or sets.
+    fn announced(&self, extra: &ExtraData) -> Option<(Vec<Validator>, u8)> {
+        let mut validators = extra.validators.clone()?;
+        validators.sort_by_key(|validator| validator.address);
+        Some((validators, extra.turn_length.unwrap_or(self.turn_length)))
+    }
+}
@@ -0,0 +1,21 @@
+use crossbeam_core::{Chain, Network};
+use crossbeam_ethereum::rpc::MockTransport;
+use crossbeam_ethereum::{Client, FeeStrategy};
+
+#[test]
+fn adapters_price_with_legacy_gas() {
+    for (chain, chain_id) in [
+        (
+            crossbeam_bsc::mainnet(Client::new(MockTransport::new())),
+            56,
+        ),
+        (
+            crossbeam_bsc::testnet(Client::new(MockTransport::new())),
+            97,
+        ),
+    ] {
+        assert_eq!(chain.network(), Network::BinanceSmartChain);
+        assert_eq!(chain.chain_id(), chain_id);
+        assert_eq!(chain.fee_strategy(), &FeeStrategy::legacy());
+    }
+}
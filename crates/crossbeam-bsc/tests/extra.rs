@@ -0,0 +1,152 @@
+use crossbeam_bsc::extra::{EXTRA_SEAL, EXTRA_VANITY};
+use crossbeam_bsc::{ExtraData, ExtraLayout, Header, Validator, VoteAttestation, VoteData};
+use crossbeam_ethereum::{Address, Error, B256};
+
+fn validator(i: u8) -> Validator {
+    Validator {
+        address: Address::new([i; 20]),
+        vote_address: Some([0xa0 + i; 48]),
+    }
+}
+
+fn attestation() -> VoteAttestation {
+    VoteAttestation {
+        vote_address_set: 0b1011,
+        agg_signature: [0x5a; 96],
+        data: VoteData {
+            source_number: 41_000_000,
+            source_hash: B256([0x0a; 32]),
+            target_number: 41_000_001,
+            target_hash: B256([0x0b; 32]),
+        },
+        extra: Vec::new(),
+    }
+}
+
+fn extra(
+    validators: Option<Vec<Validator>>,
+    turn_length: Option<u8>,
+    vote_attestation: Option<VoteAttestation>,
+) -> ExtraData {
+    ExtraData {
+        vanity: [0xd8; EXTRA_VANITY],
+        validators,
+        turn_length,
+        vote_attestation,
+        seal: [0x5e; EXTRA_SEAL],
+    }
+}
+
+#[test]
+fn bohr_epoch_blocks() {
+    let data = extra(
+        Some(vec![validator(1), validator(2), validator(3)]),
+        Some(16),
+        Some(attestation()),
+    );
+    let bytes = data.encode();
+    // Vanity, the set size, three addresses with BLS keys, the turn length.
+    assert_eq!(bytes[32], 3);
+    assert_eq!(bytes[33..53], [1; 20]);
+    assert_eq!(bytes[53..101], [0xa1; 48]);
+    assert_eq!(bytes[33 + 3 * 68], 16);
+    assert_eq!(&bytes[bytes.len() - 65..], [0x5e; 65]);
+
+    assert_eq!(
+        ExtraData::parse(&bytes, true, ExtraLayout::Bohr).unwrap(),
+        data
+    );
+    // Read with the wrong layout, the turn length is taken as the start of
+    // the attestation.
+    assert!(ExtraData::parse(&bytes, true, ExtraLayout::Luban).is_err());
+
+    let attestation = data.vote_attestation.unwrap();
+    assert_eq!(attestation.voters().collect::<Vec<_>>(), [0, 1, 3]);
+    assert_eq!(
+        VoteAttestation::decode(&attestation.encode()).unwrap(),
+        attestation
+    );
+}
+
+#[test]
+fn luban_blocks() {
+    let epoch = extra(Some(vec![validator(7)]), None, None);
+    let bytes = epoch.encode();
+    assert_eq!(bytes.len(), 32 + 1 + 68 + 65);
+    assert_eq!(
+        ExtraData::parse(&bytes, true, ExtraLayout::Luban).unwrap(),
+        epoch
+    );
+
+    let voted = extra(None, None, Some(attestation()));
+    let bytes = voted.encode();
+    assert_eq!(
+        ExtraData::parse(&bytes, false, ExtraLayout::Bohr).unwrap(),
+        voted
+    );
+
+    let plain = extra(None, None, None).encode();
+    assert_eq!(plain.len(), 97);
+    let parsed = ExtraData::parse(&plain, false, ExtraLayout::Luban).unwrap();
+    assert_eq!(parsed.validators, None);
+    assert_eq!(parsed.vote_attestation, None);
+
+    // Three validators announced, two present.
+    let mut short = extra(Some(vec![validator(1), validator(2)]), None, None).encode();
+    short[32] = 3;
+    assert!(matches!(
+        ExtraData::parse(&short, true, ExtraLayout::Luban),
+        Err(Error::InvalidHeader(_))
+    ));
+}
+
+#[test]
+fn pre_luban_blocks() {
+    let validators: Vec<Validator> = (1..=21)
+        .map(|i| Validator {
+            address: Address::new([i; 20]),
+            vote_address: None,
+        })
+        .collect();
+    let epoch = extra(Some(validators), None, None);
+    let bytes = epoch.encode();
+    assert_eq!(bytes.len(), 32 + 21 * 20 + 65);
+    assert_eq!(
+        ExtraData::parse(&bytes, true, ExtraLayout::PreLuban).unwrap(),
+        epoch
+    );
+    assert!(ExtraData::parse(&bytes, false, ExtraLayout::PreLuban).is_err());
+    assert!(ExtraData::parse(&bytes[1..], true, ExtraLayout::PreLuban).is_err());
+    assert!(ExtraData::parse(&[0; 96], false, ExtraLayout::PreLuban).is_err());
+}
+
+#[test]
+fn epochs_follow_the_header_number() {
+    let header: Header = serde_json::from_value(serde_json::json!({
+        "parentHash": B256([1; 32]),
+        "sha3Uncles": "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347",
+        "miner": Address::new([2; 20]),
+        "stateRoot": B256([3; 32]),
+        "transactionsRoot": B256([4; 32]),
+        "receiptsRoot": B256([5; 32]),
+        "logsBloom": format!("0x{}", "00".repeat(256)),
+        "difficulty": "0x2",
+        "number": "0x2710",
+        "gasLimit": "0x8583b00",
+        "gasUsed": "0x0",
+        "timestamp": "0x6700f0d0",
+        "extraData": format!("0x{}", hex::encode(extra(Some(vec![validator(9)]), Some(4), None).encode())),
+        "mixHash": B256::ZERO,
+        "nonce": "0x0000000000000000",
+        "baseFeePerGas": "0x0",
+    }))
+    .unwrap();
+    let data = ExtraData::of(&header, 200, ExtraLayout::Bohr).unwrap();
+    assert_eq!(data.validators.unwrap()[0].address, Address::new([9; 20]));
+    assert_eq!(data.turn_length, Some(4));
+    assert!(ExtraData::of(&header, 3_000, ExtraLayout::Bohr).is_err());
+    assert!(matches!(
+        ExtraData::of(&header, 0, ExtraLayout::Bohr),
+        Err(Error::InvalidHeader(_))
+    ));
+}
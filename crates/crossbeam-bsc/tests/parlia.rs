@@ -0,0 +1,396 @@
+use blst::min_pk::{AggregateSignature, SecretKey};
+use crossbeam_bsc::extra::{EXTRA_SEAL, EXTRA_VANITY};
+use crossbeam_bsc::parlia::{self, DIFFICULTY_IN_TURN, DIFFICULTY_NO_TURN};
+use crossbeam_bsc::{
+    ExtraData, ExtraLayout, Header, LightClient, ParliaConfig, Validator, VoteAttestation, VoteData,
+};
+use crossbeam_core::signer::{Secp256k1Signer, Signature, Signer, SigningPayload};
+use crossbeam_core::U256;
+use crossbeam_ethereum::bls::DST;
+use crossbeam_ethereum::{Address, Error, B256};
+
+const CONFIG: ParliaConfig = ParliaConfig {
+    chain_id: 56,
+    epoch_length: 10,
+    layout: ExtraLayout::Bohr,
+};
+
+/// A validator's consensus and vote keys.
+struct Keys {
+    seal: Secp256k1Signer,
+    vote: SecretKey,
+}
+
+impl Keys {
+    fn new(i: u8) -> Self {
+        Self {
+            seal: Secp256k1Signer::from_bytes(&[i; 32]).unwrap(),
+            vote: SecretKey::key_gen(&[i; 32], &[]).unwrap(),
+        }
+    }
+
+    fn address(&self) -> Address {
+        Address::from_public_key(&self.seal.uncompressed_public_key())
+    }
+
+    fn validator(&self) -> Validator {
+        Validator {
+            address: self.address(),
+            vote_address: Some(self.vote.sk_to_pk().to_bytes()),
+        }
+    }
+}
+
+/// Keys for validators `1..=n`, in the client's order.
+fn keys(range: std::ops::RangeInclusive<u8>) -> Vec<Keys> {
+    let mut keys: Vec<Keys> = range.map(Keys::new).collect();
+    keys.sort_by_key(Keys::address);
+    keys
+}
+
+fn unsealed(number: u64, parent_hash: B256, beneficiary: Address, extra: ExtraData) -> Header {
+    Header {
+        parent_hash,
+        ommers_hash: "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347"
+            .parse()
+            .unwrap(),
+        beneficiary,
+        state_root: B256([2; 32]),
+        transactions_root: B256([3; 32]),
+        receipts_root: B256([4; 32]),
+        logs_bloom: [0; 256],
+        difficulty: U256::from(DIFFICULTY_IN_TURN),
+        number,
+        gas_limit: 140_000_000,
+        gas_used: 0,
+        timestamp: 1_730_000_000 + number,
+        extra_data: extra.encode(),
+        mix_hash: B256::ZERO,
+        nonce: [0; 8],
+        base_fee_per_gas: Some(U256::ZERO),
+        withdrawals_root: Some(B256([5; 32])),
+        blob_gas_used: Some(0),
+        excess_blob_gas: Some(0),
+        parent_beacon_block_root: Some(B256::ZERO),
+        requests_hash: None,
+    }
+}
+
+fn seal(header: &mut Header, keys: &Keys) {
+    let hash = parlia::seal_hash(header, CONFIG.chain_id).unwrap();
+    let Signature::Secp256k1 { r, s, recovery_id } =
+        keys.seal.sign(&SigningPayload::Digest(hash.0)).unwrap()
+    else {
+        panic!("secp256k1 signature");
+    };
+    let start = header.extra_data.len() - EXTRA_SEAL;
+    let seal = &mut header.extra_data[start..];
+    seal[..32].copy_from_slice(&r);
+    seal[32..64].copy_from_slice(&s);
+    seal[64] = recovery_id;
+}
+
+fn extra() -> ExtraData {
+    ExtraData {
+        vanity: [0; EXTRA_VANITY],
+        validators: None,
+        turn_length: None,
+        vote_attestation: None,
+        seal: [0; EXTRA_SEAL],
+    }
+}
+
+/// Votes by `voters`, indices into the validator set, for `data`.
+fn attestation(keys: &[Keys], voters: &[usize], data: VoteData) -> VoteAttestation {
+    let message = data.hash();
+    let signatures: Vec<_> = voters
+        .iter()
+        .map(|&i| keys[i].vote.sign(&message.0, DST, &[]))
+        .collect();
+    let signatures: Vec<_> = signatures.iter().collect();
+    VoteAttestation {
+        vote_address_set: voters.iter().map(|i| 1u64 << i).sum(),
+        agg_signature: AggregateSignature::aggregate(&signatures, true)
+            .unwrap()
+            .to_signature()
+            .to_bytes(),
+        data,
+        extra: Vec::new(),
+    }
+}
+
+/// The epoch block the client starts from, sealed by the first validator
+/// and announcing the set that seals it.
+fn checkpoint(keys: &[Keys]) -> (LightClient, Header) {
+    let mut extra = extra();
+    extra.validators = Some(keys.iter().map(Keys::validator).collect());
+    extra.turn_length = Some(1);
+    let mut header = unsealed(0, B256::ZERO, keys[0].address(), extra);
+    seal(&mut header, &keys[0]);
+    let validators = keys.iter().map(Keys::validator).collect();
+    let client = LightClient::new(CONFIG, &header, validators, 1).unwrap();
+    (client, header)
+}
+
+/// The next block, sealed by whoever's turn it is.
+fn next(client: &LightClient, keys: &[Keys], extra: ExtraData) -> Header {
+    let number = client.head().number + 1;
+    let signer = client.in_turn_validator(number);
+    let keys = keys.iter().find(|k| k.address() == signer).unwrap();
+    let mut header = unsealed(number, client.head().hash, signer, extra);
+    seal(&mut header, keys);
+    header
+}
+
+#[test]
+fn finalizes_on_consecutive_justified_blocks() {
+    let keys = keys(1..=3);
+    let (mut client, genesis) = checkpoint(&keys);
+    assert_eq!(client.head().hash, genesis.hash().unwrap());
+    assert_eq!(client.finalized(), None);
+
+    let first = next(&client, &keys, extra());
+    let verified = client.apply(&first).unwrap();
+    assert_eq!(verified.signer, client.in_turn_validator(1));
+    assert!(verified.in_turn);
+    assert_eq!(verified.attestation, None);
+    assert_eq!(client.justified(), None);
+
+    // Two of three validators are a quorum.
+    let mut with_votes = extra();
+    with_votes.vote_attestation = Some(attestation(
+        &keys,
+        &[0, 2],
+        VoteData {
+            source_number: 0,
+            source_hash: genesis.hash().unwrap(),
+            target_number: 1,
+            target_hash: first.hash().unwrap(),
+        },
+    ));
+    let second = next(&client, &keys, with_votes);
+    client.apply(&second).unwrap();
+    assert_eq!(client.justified().unwrap().hash, first.hash().unwrap());
+    assert_eq!(client.finalized().unwrap().number, 0);
+    assert!(client.is_final(0, &genesis.hash().unwrap()));
+    assert!(!client.is_final(1, &first.hash().unwrap()));
+
+    let mut with_votes = extra();
+    with_votes.vote_attestation = Some(attestation(
+        &keys,
+        &[0, 1, 2],
+        VoteData {
+            source_number: 1,
+            source_hash: first.hash().unwrap(),
+            target_number: 2,
+            target_hash: second.hash().unwrap(),
+        },
+    ));
+    let third = next(&client, &keys, with_votes);
+    client.apply(&third).unwrap();
+    assert_eq!(client.justified().unwrap().number, 2);
+    assert!(client.is_final(1, &first.hash().unwrap()));
+    assert!(!client.is_final(1, &B256([1; 32])));
+    assert!(!client.is_final(2, &second.hash().unwrap()));
+    assert_eq!(client.head().number, 3);
+}
+
+#[test]
+fn rejects_a_zero_epoch_length() {
+    let keys = keys(1..=3);
+    let (_, header) = checkpoint(&keys);
+    let config = ParliaConfig {
+        epoch_length: 0,
+        ..CONFIG
+    };
+    let validators = keys.iter().map(Keys::validator).collect();
+    assert!(matches!(
+        LightClient::new(config, &header, validators, 1),
+        Err(Error::InvalidHeader(_))
+    ));
+}
+
+#[test]
+fn rejects_bad_headers_without_changing_state() {
+    let keys = keys(1..=3);
+    let (mut client, _) = checkpoint(&keys);
+    let first = next(&client, &keys, extra());
+    client.apply(&first).unwrap();
+    let before = client.head();
+    let reject = |client: &mut LightClient, header: &Header| {
+        assert!(client.apply(header).is_err(), "{header:?}");
+        assert_eq!(client.head(), before);
+    };
+
+    // Not the child of the head.
+    reject(&mut client, &first);
+
+    // The in-turn validator claiming the out-of-turn difficulty.
+    let mut header = next(&client, &keys, extra());
+    header.difficulty = U256::from(DIFFICULTY_NO_TURN);
+    let signer = keys.iter().find(|k| k.address() == header.beneficiary);
+    seal(&mut header, signer.unwrap());
+    reject(&mut client, &header);
+
+    // Sealed by an outsider, or for someone else.
+    let outsider = Keys::new(9);
+    let mut header = next(&client, &keys, extra());
+    header.beneficiary = outsider.address();
+    seal(&mut header, &outsider);
+    reject(&mut client, &header);
+    let mut header = next(&client, &keys, extra());
+    header.beneficiary = outsider.address();
+    reject(&mut client, &header);
+
+    // The validator that sealed block 1 sealing block 2 as well.
+    let recent = keys.iter().find(|k| k.address() == first.beneficiary);
+    let mut header = next(&client, &keys, extra());
+    header.beneficiary = first.beneficiary;
+    header.difficulty = U256::from(DIFFICULTY_NO_TURN);
+    seal(&mut header, recent.unwrap());
+    reject(&mut client, &header);
+
+    // A tampered header no longer recovers to its beneficiary.
+    let mut header = next(&client, &keys, extra());
+    header.gas_used += 1;
+    reject(&mut client, &header);
+
+    let votes = VoteData {
+        source_number: 0,
+        source_hash: first.parent_hash,
+        target_number: 1,
+        target_hash: first.hash().unwrap(),
+    };
+    let attested = |attestation| {
+        let mut extra = extra();
+        extra.vote_attestation = Some(attestation);
+        next(&client, &keys, extra)
+    };
+    let short = attested(attestation(&keys, &[1], votes));
+    let mut forged = attestation(&keys, &[0, 1], votes);
+    forged.vote_address_set = 0b101;
+    let forged = attested(forged);
+    let wrong_target = attested(attestation(
+        &keys,
+        &[0, 1],
+        VoteData {
+            target_hash: B256([1; 32]),
+            ..votes
+        },
+    ));
+    let good = attested(attestation(&keys, &[0, 1], votes));
+    // A vote bit past the end of the set.
+    let mut out_of_range = good.clone();
+    let mut data = ExtraData::of(&out_of_range, CONFIG.epoch_length, CONFIG.layout).unwrap();
+    data.vote_attestation.as_mut().unwrap().vote_address_set |= 1 << 5;
+    out_of_range.extra_data = data.encode();
+    seal(
+        &mut out_of_range,
+        keys.iter()
+            .find(|k| k.address() == client.in_turn_validator(2))
+            .unwrap(),
+    );
+    for header in [short, wrong_target, out_of_range] {
+        reject(&mut client, &header);
+    }
+    assert!(matches!(
+        client.apply(&forged),
+        Err(Error::InvalidSignature(_))
+    ));
+
+    client.apply(&good).unwrap();
+    assert_eq!(client.justified().unwrap().number, 1);
+
+    // Votes must extend the justified block.
+    let mut extra = extra();
+    extra.vote_attestation = Some(attestation(
+        &keys,
+        &[0, 1, 2],
+        VoteData {
+            source_number: 0,
+            source_hash: first.parent_hash,
+            target_number: 2,
+            target_hash: good.hash().unwrap(),
+        },
+    ));
+    let header = next(&client, &keys, extra);
+    assert!(matches!(
+        client.apply(&header),
+        Err(Error::InvalidHeader(_))
+    ));
+}
+
+#[test]
+fn rotates_validators_after_epoch_blocks() {
+    let old = keys(1..=3);
+    let new = keys(4..=7);
+    let (mut client, _) = checkpoint(&old);
+
+    for _ in 1..CONFIG.epoch_length {
+        client.apply(&next(&client, &old, extra())).unwrap();
+    }
+    let mut announcement = extra();
+    announcement.validators = Some(new.iter().map(Keys::validator).collect());
+    announcement.turn_length = Some(2);
+    let epoch = next(&client, &old, announcement);
+    client.apply(&epoch).unwrap();
+    assert_eq!(client.validators().len(), 3);
+
+    // The old set seals until half of it plus one has sealed since the
+    // epoch block.
+    let handover = next(&client, &old, extra());
+    client.apply(&handover).unwrap();
+    assert_eq!(client.head().number, 11);
+    assert_eq!(client.turn_length(), 2);
+    assert_eq!(
+        client.validators(),
+        new.iter().map(Keys::validator).collect::<Vec<_>>()
+    );
+
+    // Turns now last two blocks.
+    assert_eq!(client.in_turn_validator(12), new[2].address());
+    assert_eq!(client.in_turn_validator(13), new[2].address());
+    assert_eq!(client.in_turn_validator(14), new[3].address());
+    let mut stale = unsealed(12, handover.hash().unwrap(), old[0].address(), extra());
+    stale.difficulty = U256::from(DIFFICULTY_NO_TURN);
+    seal(&mut stale, &old[0]);
+    assert!(client.apply(&stale).is_err());
+    for _ in 12..CONFIG.epoch_length * 2 {
+        let verified = client.apply(&next(&client, &new, extra())).unwrap();
+        assert!(verified.in_turn);
+    }
+}
+
+#[test]
+fn seal_covers_post_cancun_fields_when_the_beacon_root_is_zero() {
+    let keys = keys(1..=1);
+    let mut header = unsealed(1, B256([1; 32]), keys[0].address(), extra());
+    let hash = parlia::seal_hash(&header, CONFIG.chain_id).unwrap();
+    assert_ne!(parlia::seal_hash(&header, 97).unwrap(), hash);
+
+    // The seal itself is not covered.
+    header.extra_data[EXTRA_VANITY + 3] = 0xff;
+    assert_eq!(parlia::seal_hash(&header, CONFIG.chain_id).unwrap(), hash);
+
+    header.base_fee_per_gas = Some(U256::from(1u8));
+    let with_fee = parlia::seal_hash(&header, CONFIG.chain_id).unwrap();
+    assert_ne!(with_fee, hash);
+    header.parent_beacon_block_root = None;
+    let pre_cancun = parlia::seal_hash(&header, CONFIG.chain_id).unwrap();
+    header.base_fee_per_gas = Some(U256::ZERO);
+    assert_eq!(
+        parlia::seal_hash(&header, CONFIG.chain_id).unwrap(),
+        pre_cancun
+    );
+
+    seal(&mut header, &keys[0]);
+    assert_eq!(
+        parlia::recover_signer(&header, CONFIG.chain_id).unwrap(),
+        keys[0].address()
+    );
+    header.extra_data.truncate(EXTRA_SEAL - 1);
+    assert!(matches!(
+        parlia::recover_signer(&header, CONFIG.chain_id),
+        Err(Error::InvalidHeader(_))
+    ));
+}
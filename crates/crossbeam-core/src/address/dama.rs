@@ -0,0 +1,57 @@
+use std::fmt;
+use std::str::FromStr;
+
+use bech32::primitives::decode::CheckedHrpstring;
+use bech32::{Bech32, Hrp};
+
+use super::{invalid, serde_via_str};
+use crate::error::{Error, Result};
+use crate::network::Network;
+
+/// Human-readable part of DAMA Layer addresses.
+pub const DAMA_HRP: &str = "dama";
+
+fn error(reason: impl Into<String>) -> Error {
+    invalid(Network::DamaLayer, reason)
+}
+
+/// A DAMA Layer account: a 20-byte id encoded as bech32 under the `dama`
+/// human-readable part.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct DamaAddress(pub [u8; 20]);
+
+impl DamaAddress {
+    pub const fn new(bytes: [u8; 20]) -> Self {
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 20] {
+        &self.0
+    }
+}
+
+impl fmt::Display for DamaAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hrp = Hrp::parse_unchecked(DAMA_HRP);
+        bech32::encode_lower_to_fmt::<Bech32, _>(f, hrp, &self.0).map_err(|_| fmt::Error)
+    }
+}
+
+impl FromStr for DamaAddress {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let checked = CheckedHrpstring::new::<Bech32>(s).map_err(|err| error(err.to_string()))?;
+        if checked.hrp().to_lowercase() != DAMA_HRP {
+            return Err(error(format!("expected the `{DAMA_HRP}` prefix")));
+        }
+        let bytes: Vec<u8> = checked.byte_iter().collect();
+        let len = bytes.len();
+        bytes
+            .try_into()
+            .map(Self)
+            .map_err(|_| error(format!("expected 20 bytes, got {len}")))
+    }
+}
+
+serde_via_str!(DamaAddress);
@@ -0,0 +1,106 @@
+use std::fmt;
+use std::str::FromStr;
+
+use sha3::{Digest, Keccak256};
+
+use super::{invalid, serde_via_str};
+use crate::error::{Error, Result};
+use crate::network::Network;
+
+/// A 20-byte EVM account, displayed with its EIP-55 checksum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct EvmAddress(pub [u8; 20]);
+
+impl EvmAddress {
+    pub const ZERO: Self = Self([0; 20]);
+
+    pub const fn new(bytes: [u8; 20]) -> Self {
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 20] {
+        &self.0
+    }
+
+    /// Derives the address of an uncompressed secp256k1 public key given
+    /// without its `0x04` prefix.
+    pub fn from_public_key(uncompressed: &[u8; 64]) -> Self {
+        let hash = Keccak256::digest(uncompressed);
+        let mut bytes = [0u8; 20];
+        bytes.copy_from_slice(&hash[12..]);
+        Self(bytes)
+    }
+
+    /// The EIP-55 mixed-case form, with `0x` prefix.
+    pub fn to_checksum(&self) -> String {
+        let lower = hex::encode(self.0);
+        let hash = Keccak256::digest(lower.as_bytes());
+        let mut out = String::with_capacity(42);
+        out.push_str("0x");
+        for (i, c) in lower.chars().enumerate() {
+            let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0x0f;
+            if nibble >= 8 {
+                out.push(c.to_ascii_uppercase());
+            } else {
+                out.push(c);
+            }
+        }
+        out
+    }
+
+    /// Parses an address that must carry a valid EIP-55 checksum. Unlike
+    /// [`FromStr`], all-lowercase and all-uppercase forms are rejected.
+    pub fn parse_checksummed(s: &str) -> Result<Self> {
+        let address: Self = s.parse()?;
+        if address.to_checksum() != s {
+            return Err(invalid(
+                Network::Ethereum,
+                "missing or wrong EIP-55 checksum",
+            ));
+        }
+        Ok(address)
+    }
+}
+
+impl fmt::Display for EvmAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_checksum())
+    }
+}
+
+impl FromStr for EvmAddress {
+    type Err = Error;
+
+    /// Accepts `0x`-prefixed hex. Mixed-case input must match its EIP-55
+    /// checksum; single-case input carries no checksum and is accepted as is.
+    fn from_str(s: &str) -> Result<Self> {
+        let digits = s
+            .strip_prefix("0x")
+            .ok_or_else(|| invalid(Network::Ethereum, "missing 0x prefix"))?;
+        if digits.len() != 40 {
+            return Err(invalid(
+                Network::Ethereum,
+                format!("expected 40 hex digits, got {}", digits.len()),
+            ));
+        }
+        let mut bytes = [0u8; 20];
+        hex::decode_to_slice(digits, &mut bytes)
+            .map_err(|err| invalid(Network::Ethereum, err.to_string()))?;
+        let address = Self(bytes);
+
+        let has_lower = digits.bytes().any(|b| b.is_ascii_lowercase());
+        let has_upper = digits.bytes().any(|b| b.is_ascii_uppercase());
+        if has_lower && has_upper && address.to_checksum()[2..] != *digits {
+            return Err(invalid(Network::Ethereum, "EIP-55 checksum mismatch"));
+        }
+        Ok(address)
+    }
+}
+
+impl From<[u8; 20]> for EvmAddress {
+    fn from(bytes: [u8; 20]) -> Self {
+        Self(bytes)
+    }
+}
+
+serde_via_str!(EvmAddress);
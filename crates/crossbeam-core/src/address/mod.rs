@@ -0,0 +1,146 @@
+//! Account addresses of every supported network.
+//!
+//! Each network family has a strict parser that verifies checksums and a
+//! canonical [`Display`](std::fmt::Display) form. [`Address`] unifies them for
+//! code that handles counterparties on several networks.
+
+mod dama;
+mod evm;
+mod solana;
+mod xrpl;
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+pub use dama::{DamaAddress, DAMA_HRP};
+pub use evm::EvmAddress;
+pub use solana::SolanaAddress;
+pub use xrpl::{XrplAccountId, XrplAddress};
+
+use crate::error::{Error, Result};
+use crate::network::Network;
+
+fn invalid(network: Network, reason: impl Into<String>) -> Error {
+    Error::InvalidAddress {
+        network,
+        reason: reason.into(),
+    }
+}
+
+/// Implements string-based serde for a type with `Display` and `FromStr`.
+macro_rules! serde_via_str {
+    ($ty:ty) => {
+        impl serde::Serialize for $ty {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $ty {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let s = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+                s.parse().map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+pub(crate) use serde_via_str;
+
+/// An address on any supported network.
+///
+/// EVM addresses are shared by Ethereum and BSC, so an `Address` identifies
+/// an account format rather than a network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Address {
+    Evm(EvmAddress),
+    Solana(SolanaAddress),
+    Xrpl(XrplAddress),
+    Dama(DamaAddress),
+}
+
+impl Address {
+    /// Parses `s` with the rules of `network`.
+    pub fn parse(network: Network, s: &str) -> Result<Self> {
+        let address = match network {
+            Network::Ethereum | Network::BinanceSmartChain => Address::Evm(s.parse()?),
+            Network::Solana => Address::Solana(s.parse()?),
+            Network::XrpLedger => Address::Xrpl(s.parse()?),
+            Network::DamaLayer => Address::Dama(s.parse()?),
+        };
+        Ok(address)
+    }
+
+    /// Whether this address can exist on `network`.
+    pub fn is_valid_on(&self, network: Network) -> bool {
+        match self {
+            Address::Evm(_) => network.is_evm(),
+            Address::Solana(_) => network == Network::Solana,
+            Address::Xrpl(_) => network == Network::XrpLedger,
+            Address::Dama(_) => network == Network::DamaLayer,
+        }
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Address::Evm(address) => address.fmt(f),
+            Address::Solana(address) => address.fmt(f),
+            Address::Xrpl(address) => address.fmt(f),
+            Address::Dama(address) => address.fmt(f),
+        }
+    }
+}
+
+impl FromStr for Address {
+    type Err = Error;
+
+    /// Detects the format from the string itself. XRPL formats are tried
+    /// before Solana because their checksums make false positives
+    /// negligible, whereas any 32-byte base58 string is a valid Solana key.
+    /// Prefer [`Address::parse`] when the network is known.
+    fn from_str(s: &str) -> Result<Self> {
+        if s.starts_with("0x") {
+            return s.parse().map(Address::Evm);
+        }
+        // Bech32 is case-insensitive, so `DAMA1...` is a DAMA address too.
+        if s.get(..DAMA_HRP.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(DAMA_HRP))
+        {
+            return s.parse().map(Address::Dama);
+        }
+        if let Ok(address) = s.parse() {
+            return Ok(Address::Xrpl(address));
+        }
+        s.parse()
+            .map(Address::Solana)
+            .map_err(|_| Error::UnrecognizedAddress(s.to_owned()))
+    }
+}
+
+impl From<EvmAddress> for Address {
+    fn from(address: EvmAddress) -> Self {
+        Address::Evm(address)
+    }
+}
+
+impl From<SolanaAddress> for Address {
+    fn from(address: SolanaAddress) -> Self {
+        Address::Solana(address)
+    }
+}
+
+impl From<XrplAddress> for Address {
+    fn from(address: XrplAddress) -> Self {
+        Address::Xrpl(address)
+    }
+}
+
+impl From<DamaAddress> for Address {
+    fn from(address: DamaAddress) -> Self {
+        Address::Dama(address)
+    }
+}
@@ -0,0 +1,61 @@
+use std::fmt;
+use std::str::FromStr;
+
+use super::{invalid, serde_via_str};
+use crate::error::{Error, Result};
+use crate::network::Network;
+
+/// A 32-byte Solana account key, displayed in base58.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct SolanaAddress(pub [u8; 32]);
+
+impl SolanaAddress {
+    pub const fn new(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    pub fn to_bytes(self) -> [u8; 32] {
+        self.0
+    }
+}
+
+impl fmt::Display for SolanaAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&bs58::encode(self.0).into_string())
+    }
+}
+
+impl FromStr for SolanaAddress {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        // 32 bytes never need more than 44 base58 digits; bail out before
+        // decoding arbitrarily long input.
+        if s.len() > 44 {
+            return Err(invalid(Network::Solana, "too long for a 32-byte key"));
+        }
+        let mut bytes = [0u8; 32];
+        let len = bs58::decode(s)
+            .onto(&mut bytes)
+            .map_err(|err| invalid(Network::Solana, err.to_string()))?;
+        if len != 32 {
+            return Err(invalid(
+                Network::Solana,
+                format!("expected 32 bytes, got {len}"),
+            ));
+        }
+        Ok(Self(bytes))
+    }
+}
+
+impl From<[u8; 32]> for SolanaAddress {
+    fn from(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+}
+
+serde_via_str!(SolanaAddress);
@@ -0,0 +1,174 @@
+use std::fmt;
+use std::str::FromStr;
+
+use super::{invalid, serde_via_str};
+use crate::error::{Error, Result};
+use crate::network::Network;
+
+/// Version byte of classic `r…` addresses.
+const CLASSIC_VERSION: u8 = 0x00;
+/// X-address prefixes for mainnet (`X…`) and test networks (`T…`).
+const X_MAIN_PREFIX: [u8; 2] = [0x05, 0x44];
+const X_TEST_PREFIX: [u8; 2] = [0x04, 0x93];
+
+fn error(reason: impl Into<String>) -> Error {
+    invalid(Network::XrpLedger, reason)
+}
+
+/// A 20-byte XRPL account id, displayed as a classic `r…` address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct XrplAccountId(pub [u8; 20]);
+
+impl XrplAccountId {
+    pub const fn new(bytes: [u8; 20]) -> Self {
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 20] {
+        &self.0
+    }
+}
+
+impl fmt::Display for XrplAccountId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let encoded = bs58::encode(self.0)
+            .with_alphabet(bs58::Alphabet::RIPPLE)
+            .with_check_version(CLASSIC_VERSION)
+            .into_string();
+        f.write_str(&encoded)
+    }
+}
+
+impl FromStr for XrplAccountId {
+    type Err = Error;
+
+    /// Parses a classic `r…` address.
+    fn from_str(s: &str) -> Result<Self> {
+        let decoded = bs58::decode(s)
+            .with_alphabet(bs58::Alphabet::RIPPLE)
+            .with_check(Some(CLASSIC_VERSION))
+            .into_vec()
+            .map_err(|err| error(err.to_string()))?;
+        // The decoded payload keeps the version byte in front.
+        decoded[1..]
+            .try_into()
+            .map(Self)
+            .map_err(|_| error(format!("`{s}` does not encode a 20-byte account id")))
+    }
+}
+
+serde_via_str!(XrplAccountId);
+
+/// An XRPL address in either of its two encodings.
+///
+/// X-addresses (XLS-5d) pack the destination tag and the network kind into
+/// the address. Both forms display the way they were parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum XrplAddress {
+    Classic(XrplAccountId),
+    X {
+        account: XrplAccountId,
+        tag: Option<u32>,
+        /// Whether the address is meant for a test network (`T…`).
+        test: bool,
+    },
+}
+
+impl XrplAddress {
+    pub fn account(&self) -> XrplAccountId {
+        match *self {
+            XrplAddress::Classic(account) | XrplAddress::X { account, .. } => account,
+        }
+    }
+
+    /// The destination tag carried by an X-address.
+    pub fn tag(&self) -> Option<u32> {
+        match *self {
+            XrplAddress::Classic(_) => None,
+            XrplAddress::X { tag, .. } => tag,
+        }
+    }
+
+    /// Re-encodes as an X-address.
+    pub fn to_x_address(&self, tag: Option<u32>, test: bool) -> XrplAddress {
+        XrplAddress::X {
+            account: self.account(),
+            tag,
+            test,
+        }
+    }
+
+    fn decode_x(s: &str) -> Result<Self> {
+        let payload = bs58::decode(s)
+            .with_alphabet(bs58::Alphabet::RIPPLE)
+            .with_check(None)
+            .into_vec()
+            .map_err(|err| error(err.to_string()))?;
+        if payload.len() != 31 {
+            return Err(error("X-address payload must be 31 bytes"));
+        }
+        let test = match [payload[0], payload[1]] {
+            X_MAIN_PREFIX => false,
+            X_TEST_PREFIX => true,
+            _ => return Err(error("unknown X-address prefix")),
+        };
+        let mut account = [0u8; 20];
+        account.copy_from_slice(&payload[2..22]);
+        let tag = u32::from_le_bytes(payload[23..27].try_into().expect("4 bytes"));
+        if payload[27..31] != [0; 4] {
+            return Err(error("64-bit destination tags are not supported"));
+        }
+        let tag = match payload[22] {
+            0 if tag == 0 => None,
+            0 => return Err(error("X-address carries a tag without the tag flag")),
+            1 => Some(tag),
+            _ => return Err(error("invalid X-address tag flag")),
+        };
+        Ok(XrplAddress::X {
+            account: XrplAccountId(account),
+            tag,
+            test,
+        })
+    }
+}
+
+impl fmt::Display for XrplAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            XrplAddress::Classic(account) => account.fmt(f),
+            XrplAddress::X { account, tag, test } => {
+                let mut payload = Vec::with_capacity(31);
+                payload.extend_from_slice(if test { &X_TEST_PREFIX } else { &X_MAIN_PREFIX });
+                payload.extend_from_slice(&account.0);
+                payload.push(u8::from(tag.is_some()));
+                payload.extend_from_slice(&tag.unwrap_or(0).to_le_bytes());
+                payload.extend_from_slice(&[0; 4]);
+                let encoded = bs58::encode(payload)
+                    .with_alphabet(bs58::Alphabet::RIPPLE)
+                    .with_check()
+                    .into_string();
+                f.write_str(&encoded)
+            }
+        }
+    }
+}
+
+impl FromStr for XrplAddress {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.as_bytes().first() {
+            Some(b'r') => s.parse().map(XrplAddress::Classic),
+            Some(b'X' | b'T') => Self::decode_x(s),
+            _ => Err(error("expected a classic r-address or an X-address")),
+        }
+    }
+}
+
+impl From<XrplAccountId> for XrplAddress {
+    fn from(account: XrplAccountId) -> Self {
+        XrplAddress::Classic(account)
+    }
+}
+
+serde_via_str!(XrplAddress);
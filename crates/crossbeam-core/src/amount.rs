@@ -0,0 +1,327 @@
+//! Exact, asset-tagged amounts.
+//!
+//! An [`Amount`] is an unsigned count of an asset's base units (wei,
+//! lamports, drops, ...). Every operation is checked: arithmetic never wraps,
+//! and any conversion that would drop precision either fails or follows an
+//! explicit [`Rounding`] mode.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::asset::Asset;
+use crate::error::{Error, Result};
+use crate::U256;
+
+/// Smallest normalized XRPL issued-currency mantissa (`10^15`).
+const XRPL_MIN_MANTISSA: u64 = 1_000_000_000_000_000;
+/// Largest normalized XRPL issued-currency mantissa (`10^16 - 1`).
+const XRPL_MAX_MANTISSA: u64 = 9_999_999_999_999_999;
+const XRPL_MIN_EXPONENT: i32 = -96;
+const XRPL_MAX_EXPONENT: i32 = 80;
+
+/// How to treat digits that do not fit the target precision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Rounding {
+    /// Fail with [`Error::PrecisionLoss`] instead of rounding.
+    Exact,
+    /// Round toward zero.
+    Down,
+    /// Round away from zero.
+    Up,
+    /// Round to nearest, ties away from zero.
+    HalfUp,
+    /// Round to nearest, ties to the even neighbour.
+    HalfEven,
+}
+
+/// `10^exp`, or `None` when it does not fit in 256 bits.
+fn pow10(exp: u32) -> Option<U256> {
+    U256::from(10u8).checked_pow(U256::from(exp))
+}
+
+/// Divides `n` by `10^exp`, rounding as requested.
+fn div_pow10(n: U256, exp: u32, rounding: Rounding) -> Result<U256> {
+    match pow10(exp) {
+        Some(d) => div_round(n, d, rounding),
+        // The divisor exceeds any 256-bit numerator, so the quotient is zero
+        // and the whole numerator is remainder, well below half the divisor.
+        None if n.is_zero() => Ok(U256::ZERO),
+        None => match rounding {
+            Rounding::Exact => Err(Error::PrecisionLoss),
+            Rounding::Up => Ok(U256::from(1u8)),
+            Rounding::Down | Rounding::HalfUp | Rounding::HalfEven => Ok(U256::ZERO),
+        },
+    }
+}
+
+fn div_round(n: U256, d: U256, rounding: Rounding) -> Result<U256> {
+    let (q, rem) = n.div_rem(d);
+    if rem.is_zero() {
+        return Ok(q);
+    }
+    // `rem` and `d - rem` cannot overflow, unlike `2 * rem`.
+    let upper = d - rem;
+    let round_up = match rounding {
+        Rounding::Exact => return Err(Error::PrecisionLoss),
+        Rounding::Down => false,
+        Rounding::Up => true,
+        Rounding::HalfUp => rem >= upper,
+        Rounding::HalfEven => rem > upper || (rem == upper && q.bit(0)),
+    };
+    if round_up {
+        q.checked_add(U256::from(1u8)).ok_or(Error::Overflow)
+    } else {
+        Ok(q)
+    }
+}
+
+/// An exact quantity of a specific [`Asset`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Amount {
+    asset: Asset,
+    #[serde(with = "decimal_units")]
+    units: U256,
+}
+
+impl Amount {
+    /// `units` base units of `asset`.
+    pub fn new(asset: Asset, units: U256) -> Self {
+        Self { asset, units }
+    }
+
+    pub fn zero(asset: Asset) -> Self {
+        Self::new(asset, U256::ZERO)
+    }
+
+    /// Parses a decimal string such as `"12.5"` expressed in whole tokens.
+    /// Fails instead of rounding if `s` has more fractional digits than the
+    /// asset supports.
+    pub fn parse(asset: Asset, s: &str) -> Result<Self> {
+        let invalid = || Error::InvalidAmount(s.to_owned());
+        let (int, frac) = s.split_once('.').unwrap_or((s, ""));
+        if int.is_empty() && frac.is_empty() {
+            return Err(invalid());
+        }
+        if !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let frac = frac.trim_end_matches('0');
+        let decimals = usize::from(asset.decimals());
+        if frac.len() > decimals {
+            return Err(Error::PrecisionLoss);
+        }
+        let digits = format!("{int}{frac}{}", "0".repeat(decimals - frac.len()));
+        let digits = digits.trim_start_matches('0');
+        let units = if digits.is_empty() {
+            U256::ZERO
+        } else {
+            U256::from_str_radix(digits, 10).map_err(|_| Error::Overflow)?
+        };
+        Ok(Self { asset, units })
+    }
+
+    pub fn asset(&self) -> &Asset {
+        &self.asset
+    }
+
+    /// The amount in base units.
+    pub fn units(&self) -> U256 {
+        self.units
+    }
+
+    pub fn decimals(&self) -> u8 {
+        self.asset.decimals()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.units.is_zero()
+    }
+
+    /// The base units as a `u64` (drops, lamports), if they fit.
+    pub fn to_u64(&self) -> Result<u64> {
+        u64::try_from(self.units).map_err(|_| Error::Overflow)
+    }
+
+    /// The base units as a `u128`, if they fit.
+    pub fn to_u128(&self) -> Result<u128> {
+        u128::try_from(self.units).map_err(|_| Error::Overflow)
+    }
+
+    /// The amount in whole tokens, without trailing fractional zeros.
+    pub fn to_decimal_string(&self) -> String {
+        let digits = self.units.to_string();
+        let decimals = usize::from(self.decimals());
+        if decimals == 0 {
+            return digits;
+        }
+        let padded = format!("{digits:0>width$}", width = decimals + 1);
+        let (int, frac) = padded.split_at(padded.len() - decimals);
+        let frac = frac.trim_end_matches('0');
+        if frac.is_empty() {
+            int.to_owned()
+        } else {
+            format!("{int}.{frac}")
+        }
+    }
+
+    fn ensure_same_asset(&self, other: &Amount) -> Result<()> {
+        if self.asset == other.asset {
+            Ok(())
+        } else {
+            Err(Error::AssetMismatch {
+                expected: self.asset.to_string(),
+                actual: other.asset.to_string(),
+            })
+        }
+    }
+
+    pub fn checked_add(&self, other: &Amount) -> Result<Amount> {
+        self.ensure_same_asset(other)?;
+        let units = self.units.checked_add(other.units).ok_or(Error::Overflow)?;
+        Ok(Self::new(self.asset.clone(), units))
+    }
+
+    pub fn checked_sub(&self, other: &Amount) -> Result<Amount> {
+        self.ensure_same_asset(other)?;
+        let units = self.units.checked_sub(other.units).ok_or(Error::Overflow)?;
+        Ok(Self::new(self.asset.clone(), units))
+    }
+
+    /// Computes `self * numerator / denominator`, e.g. to apply a fee rate
+    /// or an exchange ratio, without intermediate precision loss.
+    pub fn mul_div(
+        &self,
+        numerator: U256,
+        denominator: U256,
+        rounding: Rounding,
+    ) -> Result<Amount> {
+        if denominator.is_zero() {
+            return Err(Error::InvalidAmount("division by zero".to_owned()));
+        }
+        let product = self.units.checked_mul(numerator).ok_or(Error::Overflow)?;
+        let units = div_round(product, denominator, rounding)?;
+        Ok(Self::new(self.asset.clone(), units))
+    }
+
+    /// Expresses the same value in `target`'s decimals, e.g. when an
+    /// 18-decimal ERC-20 is wrapped as an 8-decimal SPL token.
+    pub fn rescale(&self, target: &Asset, rounding: Rounding) -> Result<Amount> {
+        let from = u32::from(self.decimals());
+        let to = u32::from(target.decimals());
+        let units = if to >= from {
+            let factor = pow10(to - from).ok_or(Error::Overflow)?;
+            self.units.checked_mul(factor).ok_or(Error::Overflow)?
+        } else {
+            div_pow10(self.units, from - to, rounding)?
+        };
+        Ok(Self::new(target.clone(), units))
+    }
+
+    /// Rescales toward zero and returns the dust that did not fit, still in
+    /// this amount's asset. Rescaling the first half back and adding the
+    /// dust always yields `self` again.
+    pub fn rescale_with_dust(&self, target: &Asset) -> Result<(Amount, Amount)> {
+        let converted = self.rescale(target, Rounding::Down)?;
+        let back = converted.rescale(&self.asset, Rounding::Exact)?;
+        let dust = Self::new(self.asset.clone(), self.units - back.units);
+        Ok((converted, dust))
+    }
+
+    /// Builds an amount from an XRPL issued-currency value
+    /// `mantissa * 10^exponent`, expressed in whole tokens. The exponent
+    /// must be in the XRPL range `-96..=80`.
+    pub fn from_mantissa_exponent(
+        asset: Asset,
+        mantissa: u64,
+        exponent: i32,
+        rounding: Rounding,
+    ) -> Result<Self> {
+        if !(XRPL_MIN_EXPONENT..=XRPL_MAX_EXPONENT).contains(&exponent) {
+            return Err(Error::InvalidAmount(format!(
+                "exponent {exponent} is outside the XRPL range"
+            )));
+        }
+        let shift = exponent
+            .checked_add(i32::from(asset.decimals()))
+            .ok_or(Error::Overflow)?;
+        let mantissa = U256::from(mantissa);
+        let units = if shift >= 0 {
+            let factor = pow10(shift.unsigned_abs()).ok_or(Error::Overflow)?;
+            mantissa.checked_mul(factor).ok_or(Error::Overflow)?
+        } else {
+            div_pow10(mantissa, shift.unsigned_abs(), rounding)?
+        };
+        Ok(Self { asset, units })
+    }
+
+    /// Converts to the normalized XRPL issued-currency representation: a
+    /// mantissa in `10^15..10^16` and an exponent in `-96..=80`. Values with
+    /// more than 16 significant digits are rounded as requested. Zero is
+    /// returned as `(0, 0)`.
+    pub fn to_mantissa_exponent(&self, rounding: Rounding) -> Result<(u64, i32)> {
+        if self.units.is_zero() {
+            return Ok((0, 0));
+        }
+        let mut exponent = -i32::from(self.decimals());
+        let digits = self.units.to_string().len();
+        let mut mantissa = self.units;
+        if digits > 16 {
+            let excess = digits - 16;
+            mantissa = div_pow10(mantissa, excess as u32, rounding)?;
+            exponent += excess as i32;
+            // Rounding up may carry into a 17th digit.
+            if mantissa > U256::from(XRPL_MAX_MANTISSA) {
+                mantissa = div_pow10(mantissa, 1, Rounding::Exact)?;
+                exponent += 1;
+            }
+        }
+        let mut mantissa = u64::try_from(mantissa).map_err(|_| Error::Overflow)?;
+        while mantissa < XRPL_MIN_MANTISSA {
+            mantissa *= 10;
+            exponent -= 1;
+        }
+        if exponent > XRPL_MAX_EXPONENT {
+            return Err(Error::Overflow);
+        }
+        if exponent < XRPL_MIN_EXPONENT {
+            return Err(Error::PrecisionLoss);
+        }
+        Ok((mantissa, exponent))
+    }
+}
+
+impl PartialOrd for Amount {
+    /// Amounts of different assets are unordered.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        (self.asset == other.asset).then(|| self.units.cmp(&other.units))
+    }
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.to_decimal_string(), self.asset.symbol())
+    }
+}
+
+/// Serializes base units as a decimal string, so JSON consumers never parse
+/// them into a float.
+mod decimal_units {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    use crate::U256;
+
+    pub fn serialize<S: Serializer>(units: &U256, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(units)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<U256, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(de::Error::custom(format!("`{s}` is not a decimal integer")));
+        }
+        U256::from_str_radix(&s, 10).map_err(de::Error::custom)
+    }
+}
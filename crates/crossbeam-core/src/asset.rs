@@ -0,0 +1,168 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::address::{Address, SolanaAddress, XrplAccountId};
+use crate::error::{Error, Result};
+use crate::network::Network;
+
+/// Largest supported number of decimals: `10^77` is the largest power of ten
+/// that fits in the 256-bit unit counter of [`Amount`](crate::Amount).
+pub const MAX_DECIMALS: u8 = 77;
+
+/// What kind of asset an [`Asset`] is, and where it is defined.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AssetId {
+    /// The network's fee asset (ETH, BNB, SOL, XRP).
+    Native,
+    /// A token contract: ERC-20 style on EVM networks, or a DAMA Layer
+    /// token.
+    Token { contract: Address },
+    /// An SPL Token or Token-2022 mint.
+    SplToken { mint: SolanaAddress },
+    /// An XRPL issued currency.
+    Issued {
+        currency: String,
+        issuer: XrplAccountId,
+    },
+}
+
+/// Describes an asset: where it lives, how many decimals its base unit has
+/// and how it is called.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "RawAsset")]
+pub struct Asset {
+    network: Network,
+    id: AssetId,
+    decimals: u8,
+    symbol: String,
+}
+
+#[derive(Deserialize)]
+struct RawAsset {
+    network: Network,
+    id: AssetId,
+    decimals: u8,
+    symbol: String,
+}
+
+impl TryFrom<RawAsset> for Asset {
+    type Error = Error;
+
+    fn try_from(raw: RawAsset) -> Result<Self> {
+        Asset::new(raw.network, raw.id, raw.decimals, raw.symbol)
+    }
+}
+
+impl Asset {
+    /// Creates an asset, checking that `id` can exist on `network` and that
+    /// `decimals` is representable.
+    pub fn new(
+        network: Network,
+        id: AssetId,
+        decimals: u8,
+        symbol: impl Into<String>,
+    ) -> Result<Self> {
+        if decimals > MAX_DECIMALS {
+            return Err(Error::InvalidAsset(format!(
+                "{decimals} decimals exceed the maximum of {MAX_DECIMALS}"
+            )));
+        }
+        let compatible = match &id {
+            AssetId::Native => true,
+            AssetId::Token { contract } => contract.is_valid_on(network),
+            AssetId::SplToken { .. } => network == Network::Solana,
+            AssetId::Issued { .. } => network == Network::XrpLedger,
+        };
+        if !compatible {
+            return Err(Error::InvalidAsset(format!(
+                "{id:?} cannot exist on {network}"
+            )));
+        }
+        Ok(Self {
+            network,
+            id,
+            decimals,
+            symbol: symbol.into(),
+        })
+    }
+
+    /// The native asset of `network`, or `None` where its denomination is
+    /// not known yet (DAMA Layer).
+    pub fn native(network: Network) -> Option<Self> {
+        let (decimals, symbol) = match network {
+            Network::Ethereum => (18, "ETH"),
+            Network::BinanceSmartChain => (18, "BNB"),
+            Network::Solana => (9, "SOL"),
+            Network::XrpLedger => (6, "XRP"),
+            Network::DamaLayer => return None,
+        };
+        Some(Self {
+            network,
+            id: AssetId::Native,
+            decimals,
+            symbol: symbol.to_owned(),
+        })
+    }
+
+    /// An ERC-20 (or BEP-20) token.
+    pub fn token(
+        network: Network,
+        contract: impl Into<Address>,
+        decimals: u8,
+        symbol: impl Into<String>,
+    ) -> Result<Self> {
+        let id = AssetId::Token {
+            contract: contract.into(),
+        };
+        Self::new(network, id, decimals, symbol)
+    }
+
+    /// An SPL token identified by its mint.
+    pub fn spl_token(mint: SolanaAddress, decimals: u8, symbol: impl Into<String>) -> Result<Self> {
+        let id = AssetId::SplToken { mint };
+        Self::new(Network::Solana, id, decimals, symbol)
+    }
+
+    /// An XRPL issued currency. Issued currencies have no intrinsic
+    /// decimals; `decimals` fixes the precision the SDK accounts them in.
+    pub fn xrpl_issued(
+        currency: impl Into<String>,
+        issuer: XrplAccountId,
+        decimals: u8,
+    ) -> Result<Self> {
+        let currency = currency.into();
+        let id = AssetId::Issued {
+            currency: currency.clone(),
+            issuer,
+        };
+        Self::new(Network::XrpLedger, id, decimals, currency)
+    }
+
+    pub fn network(&self) -> Network {
+        self.network
+    }
+
+    pub fn id(&self) -> &AssetId {
+        &self.id
+    }
+
+    pub fn decimals(&self) -> u8 {
+        self.decimals
+    }
+
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn is_native(&self) -> bool {
+        self.id == AssetId::Native
+    }
+}
+
+impl fmt::Display for Asset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} on {}", self.symbol, self.network)
+    }
+}
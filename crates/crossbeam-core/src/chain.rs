@@ -0,0 +1,88 @@
+use std::fmt;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::amount::Amount;
+use crate::network::Network;
+use crate::signer::Signer;
+
+/// A value transfer between two accounts of the same network.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transfer<A> {
+    pub from: A,
+    pub to: A,
+    pub amount: Amount,
+}
+
+/// Where a submitted transaction stands.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum Confirmation {
+    /// The network does not know the transaction (yet, or any more).
+    NotFound,
+    /// Accepted by the node but not yet part of a block or ledger.
+    Pending,
+    /// Included and executed successfully, but still subject to reorgs.
+    Confirmed { height: u64 },
+    /// Included and irreversible under the network's finality rules.
+    Finalized { height: u64 },
+    /// Included but reverted or rejected by the network.
+    Failed { height: Option<u64>, reason: String },
+}
+
+impl Confirmation {
+    /// Whether the transaction can no longer change state.
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            Confirmation::Finalized { .. } | Confirmation::Failed { .. }
+        )
+    }
+}
+
+/// A network adapter.
+///
+/// The trait covers the life cycle of an outgoing transaction: parse the
+/// counterparties, build the transaction, sign it through one or more
+/// [`Signer`]s, submit it and follow it until it is final. Adapters that need
+/// network state while building (nonces, recent blockhashes, sequences) fetch
+/// it from their provider, which is why building is asynchronous.
+#[async_trait]
+pub trait Chain: Send + Sync {
+    /// Account identifier on this network.
+    type Address: Clone + fmt::Debug + fmt::Display + Send + Sync;
+    /// A fully populated transaction that still lacks signatures.
+    type Transaction: Send + Sync;
+    /// A transaction ready for broadcast.
+    type SignedTransaction: Send + Sync;
+    /// Identifier returned on submission (hash or signature).
+    type TxId: Clone + fmt::Debug + fmt::Display + Send + Sync;
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    /// The network this adapter talks to.
+    fn network(&self) -> Network;
+
+    /// Parses and validates an address for this network.
+    fn parse_address(&self, s: &str) -> Result<Self::Address, Self::Error>;
+
+    /// Builds a transfer, filling in whatever network state the
+    /// transaction needs.
+    async fn build_transfer(
+        &self,
+        transfer: &Transfer<Self::Address>,
+    ) -> Result<Self::Transaction, Self::Error>;
+
+    /// Signs `tx` with every signer it requires.
+    fn sign(
+        &self,
+        tx: Self::Transaction,
+        signers: &[&dyn Signer],
+    ) -> Result<Self::SignedTransaction, Self::Error>;
+
+    /// Broadcasts a signed transaction.
+    async fn submit(&self, tx: &Self::SignedTransaction) -> Result<Self::TxId, Self::Error>;
+
+    /// Looks up the current state of a submitted transaction.
+    async fn confirmation(&self, id: &Self::TxId) -> Result<Confirmation, Self::Error>;
+}
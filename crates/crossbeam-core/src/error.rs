@@ -0,0 +1,50 @@
+use thiserror::Error;
+
+use crate::network::Network;
+
+/// Errors produced by the chain-agnostic layer.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum Error {
+    /// The adapter does not implement the requested operation.
+    #[error("{operation} is not supported on {network}")]
+    Unsupported {
+        network: Network,
+        operation: &'static str,
+    },
+    /// A network name could not be recognised.
+    #[error("unknown network `{0}`")]
+    UnknownNetwork(String),
+    /// An address failed validation for its network.
+    #[error("invalid {network} address: {reason}")]
+    InvalidAddress { network: Network, reason: String },
+    /// No supported address format matches the input.
+    #[error("`{0}` is not an address of any supported network")]
+    UnrecognizedAddress(String),
+    /// An asset descriptor is inconsistent.
+    #[error("invalid asset: {0}")]
+    InvalidAsset(String),
+    /// An amount string could not be parsed.
+    #[error("invalid amount `{0}`")]
+    InvalidAmount(String),
+    /// Two amounts of different assets were combined.
+    #[error("asset mismatch: expected {expected}, got {actual}")]
+    AssetMismatch { expected: String, actual: String },
+    /// The result does not fit the target representation.
+    #[error("amount overflow")]
+    Overflow,
+    /// The conversion would discard non-zero digits.
+    #[error("conversion would lose precision")]
+    PrecisionLoss,
+    /// Key material is malformed.
+    #[error("invalid key: {0}")]
+    InvalidKey(String),
+    /// A signer was handed a payload it cannot sign.
+    #[error("signer cannot sign a {0} payload")]
+    PayloadMismatch(&'static str),
+    /// The signer itself failed.
+    #[error("signing failed: {0}")]
+    Signing(String),
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
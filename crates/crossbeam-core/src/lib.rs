@@ -0,0 +1,25 @@
+//! Chain-agnostic building blocks for the CrossBeam SDK.
+//!
+//! Bridge services are written against the [`Chain`] trait and stay unaware
+//! of the network they talk to. Each supported network ships its own adapter
+//! crate (`crossbeam-ethereum`, `crossbeam-bsc`, `crossbeam-solana`,
+//! `crossbeam-xrpl` and `crossbeam-dama`) that implements the trait.
+
+pub mod address;
+pub mod amount;
+pub mod asset;
+pub mod chain;
+pub mod error;
+pub mod network;
+pub mod signer;
+
+pub use address::Address;
+pub use amount::{Amount, Rounding};
+pub use asset::{Asset, AssetId};
+pub use chain::{Chain, Confirmation, Transfer};
+pub use error::{Error, Result};
+pub use network::Network;
+pub use signer::{PublicKey, Signature, Signer, SigningPayload};
+
+/// 256-bit unsigned integer used for token quantities and EVM words.
+pub use ruint::aliases::U256;
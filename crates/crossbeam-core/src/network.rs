@@ -0,0 +1,78 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+/// The networks CrossBeam has an adapter for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Network {
+    Ethereum,
+    Solana,
+    XrpLedger,
+    DamaLayer,
+    BinanceSmartChain,
+}
+
+impl Network {
+    /// Every supported network, in README order.
+    pub const ALL: [Network; 5] = [
+        Network::Ethereum,
+        Network::Solana,
+        Network::XrpLedger,
+        Network::DamaLayer,
+        Network::BinanceSmartChain,
+    ];
+
+    /// Stable lowercase identifier, matching the serde representation.
+    pub fn id(self) -> &'static str {
+        match self {
+            Network::Ethereum => "ethereum",
+            Network::Solana => "solana",
+            Network::XrpLedger => "xrp-ledger",
+            Network::DamaLayer => "dama-layer",
+            Network::BinanceSmartChain => "binance-smart-chain",
+        }
+    }
+
+    /// Human readable name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Network::Ethereum => "Ethereum",
+            Network::Solana => "Solana",
+            Network::XrpLedger => "XRP Ledger",
+            Network::DamaLayer => "DAMA Layer",
+            Network::BinanceSmartChain => "Binance Smart Chain",
+        }
+    }
+
+    /// Whether the network runs the EVM and shares its address and
+    /// transaction formats.
+    pub fn is_evm(self) -> bool {
+        matches!(self, Network::Ethereum | Network::BinanceSmartChain)
+    }
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Network {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let network = match s.to_ascii_lowercase().as_str() {
+            "ethereum" | "eth" => Network::Ethereum,
+            "solana" | "sol" => Network::Solana,
+            "xrp-ledger" | "xrpl" | "xrp" => Network::XrpLedger,
+            "dama-layer" | "dama" => Network::DamaLayer,
+            "binance-smart-chain" | "bsc" | "bnb" => Network::BinanceSmartChain,
+            _ => return Err(Error::UnknownNetwork(s.to_owned())),
+        };
+        Ok(network)
+    }
+}
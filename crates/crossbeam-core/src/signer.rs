@@ -0,0 +1,159 @@
+//! Signing hooks.
+//!
+//! Adapters never touch key material directly: they hand a [`SigningPayload`]
+//! to a [`Signer`], which may be an in-process key, an HSM or a remote
+//! co-signer. [`Secp256k1Signer`] and [`Ed25519Signer`] are the in-process
+//! implementations.
+
+use ed25519_dalek::Signer as _;
+use k256::ecdsa::SigningKey;
+
+use crate::error::{Error, Result};
+
+/// What a signer is asked to sign.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SigningPayload {
+    /// A 32-byte prehash to be signed with secp256k1 ECDSA. The hash function
+    /// is chosen by the network (keccak-256, SHA-512Half, ...).
+    Digest([u8; 32]),
+    /// A message to be signed with Ed25519, which hashes internally.
+    Message(Vec<u8>),
+}
+
+impl SigningPayload {
+    fn kind(&self) -> &'static str {
+        match self {
+            SigningPayload::Digest(_) => "digest",
+            SigningPayload::Message(_) => "message",
+        }
+    }
+}
+
+/// A public key of either supported curve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PublicKey {
+    /// SEC1 compressed point.
+    Secp256k1([u8; 33]),
+    Ed25519([u8; 32]),
+}
+
+impl PublicKey {
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            PublicKey::Secp256k1(bytes) => bytes,
+            PublicKey::Ed25519(bytes) => bytes,
+        }
+    }
+}
+
+/// A signature over a [`SigningPayload`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Signature {
+    /// Low-s ECDSA signature with its public key recovery id (0 or 1).
+    Secp256k1 {
+        r: [u8; 32],
+        s: [u8; 32],
+        recovery_id: u8,
+    },
+    Ed25519([u8; 64]),
+}
+
+/// Anything that can produce signatures for one key.
+pub trait Signer: Send + Sync {
+    /// The key whose signatures this signer produces.
+    fn public_key(&self) -> PublicKey;
+
+    /// Signs `payload`, failing if the payload does not match the key type.
+    fn sign(&self, payload: &SigningPayload) -> Result<Signature>;
+}
+
+/// An in-memory secp256k1 key.
+#[derive(Clone)]
+pub struct Secp256k1Signer {
+    key: SigningKey,
+}
+
+impl Secp256k1Signer {
+    pub fn from_bytes(secret: &[u8; 32]) -> Result<Self> {
+        let key = SigningKey::from_bytes(secret.into())
+            .map_err(|err| Error::InvalidKey(err.to_string()))?;
+        Ok(Self { key })
+    }
+
+    /// Uncompressed SEC1 encoding without the `0x04` prefix, as used for
+    /// EVM address derivation.
+    pub fn uncompressed_public_key(&self) -> [u8; 64] {
+        let point = self.key.verifying_key().to_encoded_point(false);
+        let mut out = [0u8; 64];
+        out.copy_from_slice(&point.as_bytes()[1..]);
+        out
+    }
+}
+
+impl Signer for Secp256k1Signer {
+    fn public_key(&self) -> PublicKey {
+        let point = self.key.verifying_key().to_encoded_point(true);
+        let mut out = [0u8; 33];
+        out.copy_from_slice(point.as_bytes());
+        PublicKey::Secp256k1(out)
+    }
+
+    fn sign(&self, payload: &SigningPayload) -> Result<Signature> {
+        let SigningPayload::Digest(digest) = payload else {
+            return Err(Error::PayloadMismatch(payload.kind()));
+        };
+        let (signature, recovery_id) = self
+            .key
+            .sign_prehash_recoverable(digest)
+            .map_err(|err| Error::Signing(err.to_string()))?;
+        let (r, s) = signature.split_bytes();
+        Ok(Signature::Secp256k1 {
+            r: r.into(),
+            s: s.into(),
+            recovery_id: recovery_id.to_byte(),
+        })
+    }
+}
+
+impl std::fmt::Debug for Secp256k1Signer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Secp256k1Signer")
+            .field("public_key", &self.public_key())
+            .finish_non_exhaustive()
+    }
+}
+
+/// An in-memory Ed25519 key.
+#[derive(Clone)]
+pub struct Ed25519Signer {
+    key: ed25519_dalek::SigningKey,
+}
+
+impl Ed25519Signer {
+    pub fn from_bytes(secret: &[u8; 32]) -> Self {
+        Self {
+            key: ed25519_dalek::SigningKey::from_bytes(secret),
+        }
+    }
+}
+
+impl Signer for Ed25519Signer {
+    fn public_key(&self) -> PublicKey {
+        PublicKey::Ed25519(self.key.verifying_key().to_bytes())
+    }
+
+    fn sign(&self, payload: &SigningPayload) -> Result<Signature> {
+        let SigningPayload::Message(message) = payload else {
+            return Err(Error::PayloadMismatch(payload.kind()));
+        };
+        Ok(Signature::Ed25519(self.key.sign(message).to_bytes()))
+    }
+}
+
+impl std::fmt::Debug for Ed25519Signer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Ed25519Signer")
+            .field("public_key", &self.public_key())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,231 @@
+use crossbeam_core::address::{DamaAddress, EvmAddress, SolanaAddress, XrplAccountId, XrplAddress};
+use crossbeam_core::{Address, Error, Network};
+
+#[test]
+fn evm_addresses_display_with_eip55_checksums() {
+    // Vectors from EIP-55.
+    for checksummed in [
+        "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+        "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+        "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+        "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+    ] {
+        let lower: EvmAddress = checksummed.to_lowercase().parse().unwrap();
+        assert_eq!(lower.to_string(), checksummed);
+        assert_eq!(EvmAddress::parse_checksummed(checksummed).unwrap(), lower);
+    }
+}
+
+#[test]
+fn evm_parsing_is_strict() {
+    let wrong_case = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD";
+    assert!(matches!(
+        wrong_case.parse::<EvmAddress>(),
+        Err(Error::InvalidAddress {
+            network: Network::Ethereum,
+            ..
+        })
+    ));
+    assert!(EvmAddress::parse_checksummed("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed").is_err());
+    assert!("0X5AAEB6053F3E94C9B9A09F33669435E7EF1BEAED"
+        .parse::<EvmAddress>()
+        .is_err());
+    assert!("0x5AAEB6053F3E94C9B9A09F33669435E7EF1BEAED"
+        .parse::<EvmAddress>()
+        .is_ok());
+    assert!("5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"
+        .parse::<EvmAddress>()
+        .is_err());
+    assert!("0x5aaeb6053f3e94c9b9a09f33669435e7ef1bea"
+        .parse::<EvmAddress>()
+        .is_err());
+    assert!("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beazz"
+        .parse::<EvmAddress>()
+        .is_err());
+}
+
+#[test]
+fn evm_addresses_derive_from_public_keys() {
+    use crossbeam_core::signer::Secp256k1Signer;
+
+    // The EIP-155 example key.
+    let signer = Secp256k1Signer::from_bytes(&[0x46; 32]).unwrap();
+    let address = EvmAddress::from_public_key(&signer.uncompressed_public_key());
+    assert_eq!(
+        address.to_string(),
+        "0x9d8A62f656a8d1615C1294fd71e9CFb3E4855A4F"
+    );
+}
+
+#[test]
+fn solana_addresses_are_32_byte_base58() {
+    let system: SolanaAddress = "11111111111111111111111111111111".parse().unwrap();
+    assert_eq!(system, SolanaAddress::new([0; 32]));
+
+    let token: SolanaAddress = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        .parse()
+        .unwrap();
+    assert_eq!(
+        hex::encode(token.0),
+        "06ddf6e1d765a193d9cbe146ceeb79ac1cb485ed5f5b37913a8cf5857eff00a9"
+    );
+    assert_eq!(
+        token.to_string(),
+        "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+    );
+
+    assert!("1111111111111111111111111111111"
+        .parse::<SolanaAddress>()
+        .is_err());
+    assert!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5D0"
+        .parse::<SolanaAddress>()
+        .is_err());
+    assert!("Tokenkeg".repeat(10).parse::<SolanaAddress>().is_err());
+}
+
+#[test]
+fn xrpl_x_addresses_match_the_reference_codec() {
+    // Fixtures from the XLS-5d reference implementation.
+    let cases = [
+        (
+            "r9cZA1mLK5R5Am25ArfXFmqgNwjZgnfk59",
+            None,
+            "X7AcgcsBL6XDcUb289X4mJ8djcdyKaB5hJDWMArnXr61cqZ",
+            "T719a5UwUCnEs54UsxG9CJYYDhwmFCqkr7wxCcNcfZ6p5GZ",
+        ),
+        (
+            "r9cZA1mLK5R5Am25ArfXFmqgNwjZgnfk59",
+            Some(11747),
+            "X7AcgcsBL6XDcUb289X4mJ8djcdyKaLFuhLRuNXPrDeJd9A",
+            "T719a5UwUCnEs54UsxG9CJYYDhwmFCziiNHtUukubF2Mg6t",
+        ),
+        (
+            "rGWrZyQqhTp9Xu7G5Pkayo7bXjH4k4QYpf",
+            Some(0),
+            "XVLhHMPHU98es4dbozjVtdWzVrDjtV8AqEL4xcZj5whKbmc",
+            "TVE26TYGhfLC7tQDno7G8dGtxSkYQnSy8RHqGHoGJ59spi2",
+        ),
+        (
+            "rGWrZyQqhTp9Xu7G5Pkayo7bXjH4k4QYpf",
+            Some(4294967295),
+            "XVLhHMPHU98es4dbozjVtdWzVrDjtV18pX8yuPT7y4xaEHi",
+            "TVE26TYGhfLC7tQDno7G8dGtxSkYQnXoy6kSDh6rZzApc69",
+        ),
+        (
+            "rPEPPER7kfTD9w2To4CQk6UCfuHM9c6GDY",
+            Some(13371337),
+            "XV5sbjUmgPpvXv4ixFWZ5ptAYZ6PD2qwGkhgc48zzcx6Gkr",
+            "TVd2rqMkYL2AyS97NdELcpeiprNBjwVUDvp3vhpXbNhLwJi",
+        ),
+    ];
+    for (classic, tag, main, test) in cases {
+        let account: XrplAccountId = classic.parse().unwrap();
+        let classic_address = XrplAddress::Classic(account);
+        assert_eq!(classic_address.to_x_address(tag, false).to_string(), main);
+        assert_eq!(classic_address.to_x_address(tag, true).to_string(), test);
+
+        let parsed: XrplAddress = main.parse().unwrap();
+        assert_eq!(parsed.account(), account);
+        assert_eq!(parsed.tag(), tag);
+        assert_eq!(parsed.to_string(), main);
+        assert_eq!(
+            test.parse::<XrplAddress>().unwrap(),
+            classic_address.to_x_address(tag, true)
+        );
+    }
+}
+
+#[test]
+fn xrpl_parsing_rejects_other_payloads() {
+    assert!("rGWrZyQqhTp9Xu7G5Pkayo7bXjH4k4QYpg"
+        .parse::<XrplAddress>()
+        .is_err());
+    assert!("XVLhHMPHU98es4dbozjVtdWzVrDjtV18pX8yuPT7y4xaEHj"
+        .parse::<XrplAddress>()
+        .is_err());
+    // A family seed has a valid checksum but the wrong version byte.
+    assert!("sp5fghtJtpUorTwvof1NpDXAzNwf5"
+        .parse::<XrplAccountId>()
+        .is_err());
+    assert!("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"
+        .parse::<XrplAddress>()
+        .is_err());
+}
+
+#[test]
+fn dama_addresses_are_bech32() {
+    let address = DamaAddress::new([0xab; 20]);
+    let encoded = address.to_string();
+    assert!(encoded.starts_with("dama1"));
+    assert_eq!(encoded.parse::<DamaAddress>().unwrap(), address);
+    assert_eq!(
+        encoded.to_uppercase().parse::<DamaAddress>().unwrap(),
+        address
+    );
+
+    let mut corrupted = encoded.clone().into_bytes();
+    let last = corrupted.len() - 1;
+    corrupted[last] = if corrupted[last] == b'q' { b'p' } else { b'q' };
+    assert!(String::from_utf8(corrupted)
+        .unwrap()
+        .parse::<DamaAddress>()
+        .is_err());
+    assert!("cosmos1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5lzv7xu"
+        .parse::<DamaAddress>()
+        .is_err());
+}
+
+#[test]
+fn unified_address_parses_per_network_and_by_detection() {
+    let evm = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+    for network in [Network::Ethereum, Network::BinanceSmartChain] {
+        assert!(matches!(
+            Address::parse(network, evm).unwrap(),
+            Address::Evm(_)
+        ));
+    }
+    assert!(Address::parse(Network::Solana, evm).is_err());
+
+    let inputs = [
+        evm,
+        "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+        "rGWrZyQqhTp9Xu7G5Pkayo7bXjH4k4QYpf",
+        "XVLhHMPHU98es4dbozjVtdWzVrDjtV18pX8yuPT7y4xaEHi",
+    ];
+    let expected = [
+        Network::Ethereum,
+        Network::Solana,
+        Network::XrpLedger,
+        Network::XrpLedger,
+    ];
+    for (input, network) in inputs.into_iter().zip(expected) {
+        let address: Address = input.parse().unwrap();
+        assert!(address.is_valid_on(network), "{input}");
+        assert_eq!(address.to_string(), input);
+    }
+
+    let dama = DamaAddress::new([1; 20]).to_string();
+    assert!(matches!(dama.parse::<Address>().unwrap(), Address::Dama(_)));
+    assert!(matches!(
+        dama.to_uppercase().parse::<Address>().unwrap(),
+        Address::Dama(_)
+    ));
+    assert!(matches!(
+        "not an address".parse::<Address>(),
+        Err(Error::UnrecognizedAddress(_))
+    ));
+}
+
+#[test]
+fn addresses_serialize_as_strings() {
+    let address: Address = "rGWrZyQqhTp9Xu7G5Pkayo7bXjH4k4QYpf".parse().unwrap();
+    let json = serde_json::to_value(address).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({ "xrpl": "rGWrZyQqhTp9Xu7G5Pkayo7bXjH4k4QYpf" })
+    );
+    assert_eq!(serde_json::from_value::<Address>(json).unwrap(), address);
+
+    let bad = serde_json::json!({ "evm": "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD" });
+    assert!(serde_json::from_value::<Address>(bad).is_err());
+}
@@ -0,0 +1,246 @@
+use crossbeam_core::address::{EvmAddress, SolanaAddress};
+use crossbeam_core::{Amount, Asset, Error, Network, Rounding, U256};
+
+fn eth() -> Asset {
+    Asset::native(Network::Ethereum).unwrap()
+}
+
+fn asset(decimals: u8) -> Asset {
+    Asset::token(
+        Network::Ethereum,
+        EvmAddress::new([0x11; 20]),
+        decimals,
+        "TKN",
+    )
+    .unwrap()
+}
+
+fn units(amount: &Amount) -> String {
+    amount.units().to_string()
+}
+
+#[test]
+fn parses_and_displays_decimal_strings() {
+    let amount = Amount::parse(eth(), "1.50").unwrap();
+    assert_eq!(units(&amount), "1500000000000000000");
+    assert_eq!(amount.to_string(), "1.5 ETH");
+    assert_eq!(
+        Amount::parse(eth(), ".25").unwrap().to_decimal_string(),
+        "0.25"
+    );
+    assert_eq!(Amount::parse(eth(), "7").unwrap().to_decimal_string(), "7");
+    assert_eq!(
+        Amount::parse(eth(), "0.000000000000000001")
+            .unwrap()
+            .to_decimal_string(),
+        "0.000000000000000001"
+    );
+
+    assert!(matches!(
+        Amount::parse(eth(), "0.0000000000000000001"),
+        Err(Error::PrecisionLoss)
+    ));
+    for bad in ["", ".", "-1", "1e18", "1.2.3", " 1"] {
+        assert!(
+            Amount::parse(eth(), bad).is_err(),
+            "{bad:?} should not parse"
+        );
+    }
+}
+
+#[test]
+fn arithmetic_is_checked_and_asset_aware() {
+    let a = Amount::parse(eth(), "1").unwrap();
+    let b = Amount::parse(eth(), "0.5").unwrap();
+    assert_eq!(a.checked_add(&b).unwrap().to_decimal_string(), "1.5");
+    assert_eq!(a.checked_sub(&b).unwrap().to_decimal_string(), "0.5");
+    assert!(matches!(b.checked_sub(&a), Err(Error::Overflow)));
+    assert!(a > b);
+
+    let bnb = Amount::parse(Asset::native(Network::BinanceSmartChain).unwrap(), "1").unwrap();
+    assert!(matches!(
+        a.checked_add(&bnb),
+        Err(Error::AssetMismatch { .. })
+    ));
+    assert_eq!(a.partial_cmp(&bnb), None);
+
+    let max = Amount::new(eth(), U256::MAX);
+    assert!(matches!(max.checked_add(&a), Err(Error::Overflow)));
+}
+
+#[test]
+fn mul_div_applies_rates_without_intermediate_loss() {
+    // 0.3% fee on 1000.000001 tokens with 6 decimals.
+    let amount = Amount::parse(asset(6), "1000.000001").unwrap();
+    let fee = amount
+        .mul_div(U256::from(3u8), U256::from(1000u16), Rounding::Up)
+        .unwrap();
+    assert_eq!(fee.to_decimal_string(), "3.000001");
+    assert!(matches!(
+        amount.mul_div(U256::from(3u8), U256::from(1000u16), Rounding::Exact),
+        Err(Error::PrecisionLoss)
+    ));
+}
+
+#[test]
+fn rescaling_follows_the_rounding_mode() {
+    let cases = [
+        ("0.25", Rounding::Down, "0.2"),
+        ("0.25", Rounding::Up, "0.3"),
+        ("0.25", Rounding::HalfUp, "0.3"),
+        ("0.25", Rounding::HalfEven, "0.2"),
+        ("0.35", Rounding::HalfEven, "0.4"),
+        ("0.26", Rounding::HalfEven, "0.3"),
+        ("0.30", Rounding::Exact, "0.3"),
+    ];
+    for (input, rounding, expected) in cases {
+        let amount = Amount::parse(asset(2), input).unwrap();
+        let rescaled = amount.rescale(&asset(1), rounding).unwrap();
+        assert_eq!(
+            rescaled.to_decimal_string(),
+            expected,
+            "{input} {rounding:?}"
+        );
+    }
+    let dusty = Amount::parse(asset(2), "0.25").unwrap();
+    assert!(matches!(
+        dusty.rescale(&asset(1), Rounding::Exact),
+        Err(Error::PrecisionLoss)
+    ));
+}
+
+#[test]
+fn rescale_with_dust_accounts_for_every_unit() {
+    let wrapped = Asset::spl_token(SolanaAddress::new([0x22; 32]), 8, "WETH").unwrap();
+    let amount = Amount::parse(eth(), "1.234567891234567891").unwrap();
+    let (converted, dust) = amount.rescale_with_dust(&wrapped).unwrap();
+    assert_eq!(converted.to_string(), "1.23456789 WETH");
+    assert_eq!(dust.to_string(), "0.000000001234567891 ETH");
+
+    let back = converted.rescale(&eth(), Rounding::Exact).unwrap();
+    assert_eq!(back.checked_add(&dust).unwrap(), amount);
+}
+
+#[test]
+fn native_units_map_to_drops_and_lamports() {
+    let xrp = Amount::new(
+        Asset::native(Network::XrpLedger).unwrap(),
+        U256::from(1_500_000u64),
+    );
+    assert_eq!(xrp.to_string(), "1.5 XRP");
+    assert_eq!(xrp.to_u64().unwrap(), 1_500_000);
+
+    let sol = Amount::parse(Asset::native(Network::Solana).unwrap(), "0.000000001").unwrap();
+    assert_eq!(sol.to_u64().unwrap(), 1);
+
+    let huge = Amount::parse(eth(), "100").unwrap();
+    assert!(matches!(huge.to_u64(), Err(Error::Overflow)));
+    assert_eq!(huge.to_u128().unwrap(), 100 * 10u128.pow(18));
+}
+
+#[test]
+fn xrpl_mantissa_exponent_round_trips() {
+    let usd = Asset::xrpl_issued(
+        "USD",
+        "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh".parse().unwrap(),
+        15,
+    )
+    .unwrap();
+    let amount =
+        Amount::from_mantissa_exponent(usd.clone(), 1_234_567_890_123_456, -15, Rounding::Exact)
+            .unwrap();
+    assert_eq!(amount.to_decimal_string(), "1.234567890123456");
+    assert_eq!(
+        amount.to_mantissa_exponent(Rounding::Exact).unwrap(),
+        (1_234_567_890_123_456, -15)
+    );
+
+    let small = Amount::parse(usd.clone(), "0.5").unwrap();
+    assert_eq!(
+        small.to_mantissa_exponent(Rounding::Exact).unwrap(),
+        (5_000_000_000_000_000, -16)
+    );
+    assert_eq!(
+        Amount::zero(usd.clone())
+            .to_mantissa_exponent(Rounding::Exact)
+            .unwrap(),
+        (0, 0)
+    );
+
+    // More precision than the asset keeps: 1e-16 at 15 decimals.
+    assert!(matches!(
+        Amount::from_mantissa_exponent(usd.clone(), 1_000_000_000_000_000, -31, Rounding::Exact),
+        Err(Error::PrecisionLoss)
+    ));
+    let rounded =
+        Amount::from_mantissa_exponent(usd.clone(), 1_000_000_000_000_000, -31, Rounding::Up)
+            .unwrap();
+    assert_eq!(rounded.to_decimal_string(), "0.000000000000001");
+
+    // The largest issued-currency value does not fit in 256 bits.
+    assert!(matches!(
+        Amount::from_mantissa_exponent(usd.clone(), 9_999_999_999_999_999, 80, Rounding::Exact),
+        Err(Error::Overflow)
+    ));
+
+    // Exponents XRPL cannot represent are refused, however extreme.
+    for exponent in [81, -97, i32::MAX, i32::MIN] {
+        assert!(matches!(
+            Amount::from_mantissa_exponent(usd.clone(), 1, exponent, Rounding::Down),
+            Err(Error::InvalidAmount(_))
+        ));
+    }
+}
+
+#[test]
+fn xrpl_mantissa_keeps_sixteen_significant_digits() {
+    let amount = Amount::parse(eth(), "12.345678901234567891").unwrap();
+    assert!(matches!(
+        amount.to_mantissa_exponent(Rounding::Exact),
+        Err(Error::PrecisionLoss)
+    ));
+    assert_eq!(
+        amount.to_mantissa_exponent(Rounding::Down).unwrap(),
+        (1_234_567_890_123_456, -14)
+    );
+    assert_eq!(
+        amount.to_mantissa_exponent(Rounding::HalfUp).unwrap(),
+        (1_234_567_890_123_457, -14)
+    );
+
+    let nines = Amount::new(asset(0), U256::from(99_999_999_999_999_999u64));
+    assert_eq!(
+        nines.to_mantissa_exponent(Rounding::Up).unwrap(),
+        (1_000_000_000_000_000, 2)
+    );
+}
+
+#[test]
+fn serde_keeps_units_as_decimal_strings() {
+    let amount = Amount::parse(eth(), "1.5").unwrap();
+    let json = serde_json::to_value(&amount).unwrap();
+    assert_eq!(json["units"], "1500000000000000000");
+    assert_eq!(json["asset"]["id"]["kind"], "native");
+    assert_eq!(serde_json::from_value::<Amount>(json).unwrap(), amount);
+
+    let bad = serde_json::json!({
+        "asset": serde_json::to_value(eth()).unwrap(),
+        "units": "1.5",
+    });
+    assert!(serde_json::from_value::<Amount>(bad).is_err());
+}
+
+#[test]
+fn assets_validate_network_and_decimals() {
+    assert!(Asset::token(Network::Solana, EvmAddress::ZERO, 18, "X").is_err());
+    assert!(Asset::token(Network::Ethereum, EvmAddress::ZERO, 78, "X").is_err());
+    assert!(Asset::native(Network::DamaLayer).is_none());
+
+    let json = serde_json::json!({
+        "network": "solana",
+        "id": { "kind": "issued", "currency": "USD", "issuer": "r" },
+        "decimals": 6,
+        "symbol": "USD",
+    });
+    assert!(serde_json::from_value::<Asset>(json).is_err());
+}
@@ -0,0 +1,27 @@
+use crossbeam_core::Network;
+
+#[test]
+fn ids_round_trip_through_from_str_and_serde() {
+    for network in Network::ALL {
+        assert_eq!(network.id().parse::<Network>().unwrap(), network);
+        let json = serde_json::to_string(&network).unwrap();
+        assert_eq!(json, format!("\"{}\"", network.id()));
+        assert_eq!(serde_json::from_str::<Network>(&json).unwrap(), network);
+    }
+}
+
+#[test]
+fn aliases_are_accepted() {
+    assert_eq!(
+        "BSC".parse::<Network>().unwrap(),
+        Network::BinanceSmartChain
+    );
+    assert_eq!("xrpl".parse::<Network>().unwrap(), Network::XrpLedger);
+    assert!("bitcoin".parse::<Network>().is_err());
+}
+
+#[test]
+fn only_ethereum_and_bsc_are_evm() {
+    let evm: Vec<_> = Network::ALL.into_iter().filter(|n| n.is_evm()).collect();
+    assert_eq!(evm, [Network::Ethereum, Network::BinanceSmartChain]);
+}
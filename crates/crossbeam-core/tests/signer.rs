@@ -0,0 +1,63 @@
+use crossbeam_core::signer::{Ed25519Signer, Secp256k1Signer};
+use crossbeam_core::{Error, PublicKey, Signature, Signer, SigningPayload};
+
+#[test]
+fn secp256k1_signatures_verify_and_recover() {
+    use k256::ecdsa::{RecoveryId, Signature as EcdsaSignature, VerifyingKey};
+
+    let signer = Secp256k1Signer::from_bytes(&[0x46; 32]).unwrap();
+    let digest = [0xab; 32];
+    let Signature::Secp256k1 { r, s, recovery_id } =
+        signer.sign(&SigningPayload::Digest(digest)).unwrap()
+    else {
+        panic!("expected a secp256k1 signature");
+    };
+
+    let signature = EcdsaSignature::from_scalars(r, s).unwrap();
+    assert!(signature.normalize_s().is_none(), "signature must be low-s");
+    let recovered = VerifyingKey::recover_from_prehash(
+        &digest,
+        &signature,
+        RecoveryId::from_byte(recovery_id).unwrap(),
+    )
+    .unwrap();
+    let PublicKey::Secp256k1(expected) = signer.public_key() else {
+        panic!("expected a secp256k1 key");
+    };
+    assert_eq!(recovered.to_encoded_point(true).as_bytes(), expected);
+}
+
+#[test]
+fn ed25519_signatures_verify() {
+    use ed25519_dalek::{Signature as DalekSignature, Verifier, VerifyingKey};
+
+    let signer = Ed25519Signer::from_bytes(&[7; 32]);
+    let message = b"crossbeam".to_vec();
+    let Signature::Ed25519(bytes) = signer
+        .sign(&SigningPayload::Message(message.clone()))
+        .unwrap()
+    else {
+        panic!("expected an ed25519 signature");
+    };
+    let PublicKey::Ed25519(key) = signer.public_key() else {
+        panic!("expected an ed25519 key");
+    };
+    VerifyingKey::from_bytes(&key)
+        .unwrap()
+        .verify(&message, &DalekSignature::from_bytes(&bytes))
+        .unwrap();
+}
+
+#[test]
+fn signers_reject_foreign_payloads() {
+    let secp = Secp256k1Signer::from_bytes(&[1; 32]).unwrap();
+    let ed = Ed25519Signer::from_bytes(&[1; 32]);
+    assert!(matches!(
+        secp.sign(&SigningPayload::Message(vec![1])),
+        Err(Error::PayloadMismatch("message"))
+    ));
+    assert!(matches!(
+        ed.sign(&SigningPayload::Digest([0; 32])),
+        Err(Error::PayloadMismatch("digest"))
+    ));
+}
@@ -0,0 +1,53 @@
+use async_trait::async_trait;
+use crossbeam_core::address::DamaAddress;
+use crossbeam_core::{Chain, Confirmation, Error, Network, Result, Signer, Transfer};
+
+fn unsupported(operation: &'static str) -> Error {
+    Error::Unsupported {
+        network: Network::DamaLayer,
+        operation,
+    }
+}
+
+/// [`Chain`] implementation for the DAMA Layer.
+#[derive(Debug, Clone, Default)]
+pub struct DamaChain;
+
+impl DamaChain {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Chain for DamaChain {
+    type Address = DamaAddress;
+    type Transaction = Transfer<DamaAddress>;
+    type SignedTransaction = Vec<u8>;
+    type TxId = String;
+    type Error = Error;
+
+    fn network(&self) -> Network {
+        Network::DamaLayer
+    }
+
+    fn parse_address(&self, s: &str) -> Result<DamaAddress> {
+        s.parse()
+    }
+
+    async fn build_transfer(&self, _transfer: &Transfer<DamaAddress>) -> Result<Self::Transaction> {
+        Err(unsupported("transaction building"))
+    }
+
+    fn sign(&self, _tx: Self::Transaction, _signers: &[&dyn Signer]) -> Result<Vec<u8>> {
+        Err(unsupported("transaction signing"))
+    }
+
+    async fn submit(&self, _tx: &Vec<u8>) -> Result<String> {
+        Err(unsupported("transaction submission"))
+    }
+
+    async fn confirmation(&self, _id: &String) -> Result<Confirmation> {
+        Err(unsupported("confirmation tracking"))
+    }
+}
@@ -0,0 +1,10 @@
+//! DAMA Layer adapter for the CrossBeam SDK.
+//!
+//! The DAMA Layer transaction formats are not published yet. Apart from
+//! address handling, every operation reports
+//! [`Error::Unsupported`](crossbeam_core::Error::Unsupported) until the
+//! protocol is specified.
+
+pub mod chain;
+
+pub use chain::DamaChain;
@@ -0,0 +1,636 @@
+//! A beacon-chain light client.
+//!
+//! Since the merge, an execution block is final when the beacon block that
+//! carries it is. A sync committee of 512 validators, rotated every 256
+//! epochs, signs each beacon block header; [`LightClient`] follows those
+//! signatures from a trusted checkpoint, as the Altair light-client
+//! protocol specifies, and learns each next committee from Merkle branches
+//! into the state the current one signed.
+//!
+//! The client only moves on finality updates signed by two thirds of the
+//! committee, so [`LightClient::finalized_state_root`] is an execution state
+//! root that account and storage proofs can be checked against without
+//! trusting the RPC node that served them.
+//!
+//! Objects deserialize from the Beacon API's JSON, as served by
+//! `/eth/v1/beacon/light_client/bootstrap/{block_root}` and
+//! `/eth/v1/beacon/light_client/updates`.
+
+use crossbeam_core::U256;
+use serde::{Deserialize, Serialize};
+
+use crate::bls;
+use crate::error::{Error, Result};
+use crate::primitives::B256;
+use crate::rpc::serde_hex;
+use crate::ssz;
+use crate::Address;
+
+pub const SLOTS_PER_EPOCH: u64 = 32;
+pub const EPOCHS_PER_SYNC_COMMITTEE_PERIOD: u64 = 256;
+pub const SYNC_COMMITTEE_SIZE: usize = 512;
+/// The domain type sync committee members sign under.
+pub const DOMAIN_SYNC_COMMITTEE: [u8; 4] = [7, 0, 0, 0];
+
+/// Where the execution payload header sits in a block body.
+const EXECUTION_PAYLOAD_GINDEX: u64 = 25;
+
+fn update_error(reason: impl Into<String>) -> Error {
+    Error::InvalidUpdate(reason.into())
+}
+
+/// The sync committee period a slot falls in.
+pub fn period_at_slot(slot: u64) -> u64 {
+    slot / SLOTS_PER_EPOCH / EPOCHS_PER_SYNC_COMMITTEE_PERIOD
+}
+
+/// Consensus-layer forks. Light-client headers carry an execution payload
+/// header from Capella on; Electra deepened the state tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BeaconFork {
+    Phase0,
+    Altair,
+    Bellatrix,
+    Capella,
+    Deneb,
+    Electra,
+    Fulu,
+}
+
+impl BeaconFork {
+    fn current_sync_committee_gindex(self) -> u64 {
+        if self >= BeaconFork::Electra {
+            86
+        } else {
+            54
+        }
+    }
+
+    fn next_sync_committee_gindex(self) -> u64 {
+        if self >= BeaconFork::Electra {
+            87
+        } else {
+            55
+        }
+    }
+
+    fn finalized_root_gindex(self) -> u64 {
+        if self >= BeaconFork::Electra {
+            169
+        } else {
+            105
+        }
+    }
+}
+
+/// A fork's activation epoch and version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ForkVersion {
+    pub fork: BeaconFork,
+    pub epoch: u64,
+    pub version: [u8; 4],
+}
+
+/// The parameters signatures are domain-separated by.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainSpec {
+    pub genesis_validators_root: B256,
+    /// Every fork from genesis on, in activation order.
+    pub forks: Vec<ForkVersion>,
+}
+
+impl ChainSpec {
+    /// Ethereum mainnet, through Fulu.
+    pub fn mainnet() -> Self {
+        let fork = |fork, epoch, version| ForkVersion {
+            fork,
+            epoch,
+            version: [version, 0, 0, 0],
+        };
+        Self {
+            genesis_validators_root:
+                "0x4b363db94e286120d76eb905340fdd4e54bfe9f06bf33ff6cf5ad27f511bfe95"
+                    .parse()
+                    .expect("valid root"),
+            forks: vec![
+                fork(BeaconFork::Phase0, 0, 0),
+                fork(BeaconFork::Altair, 74_240, 1),
+                fork(BeaconFork::Bellatrix, 144_896, 2),
+                fork(BeaconFork::Capella, 194_048, 3),
+                fork(BeaconFork::Deneb, 269_568, 4),
+                fork(BeaconFork::Electra, 364_032, 5),
+                fork(BeaconFork::Fulu, 411_392, 6),
+            ],
+        }
+    }
+
+    /// The fork in force at `slot`.
+    pub fn fork_at_slot(&self, slot: u64) -> &ForkVersion {
+        let epoch = slot / SLOTS_PER_EPOCH;
+        self.forks
+            .iter()
+            .rev()
+            .find(|fork| fork.epoch <= epoch)
+            .expect("the fork schedule starts at genesis")
+    }
+
+    /// The domain a sync committee signs under at `slot`.
+    pub fn sync_committee_domain(&self, slot: u64) -> B256 {
+        let version = self.fork_at_slot(slot).version;
+        let mut current_version = [0; 32];
+        current_version[..4].copy_from_slice(&version);
+        let fork_data_root = ssz::hash_pair(&B256(current_version), &self.genesis_validators_root);
+        let mut domain = [0; 32];
+        domain[..4].copy_from_slice(&DOMAIN_SYNC_COMMITTEE);
+        domain[4..].copy_from_slice(&fork_data_root.0[..28]);
+        B256(domain)
+    }
+}
+
+/// The Beacon API writes integers as decimal strings.
+mod decimal {
+    use std::fmt::Display;
+    use std::str::FromStr;
+
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer, T: Display>(n: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(n)
+    }
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: FromStr,
+        T::Err: Display,
+    {
+        let s = String::deserialize(deserializer)?;
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(de::Error::custom(format!("`{s}` is not a decimal integer")));
+        }
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+/// A list of BLS public keys.
+mod public_keys {
+    use serde::ser::SerializeSeq;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::bls::PUBLIC_KEY_LENGTH;
+
+    pub fn serialize<S: Serializer>(
+        keys: &[[u8; PUBLIC_KEY_LENGTH]],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(keys.len()))?;
+        for key in keys {
+            seq.serialize_element(&format!("0x{}", hex::encode(key)))?;
+        }
+        seq.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<[u8; PUBLIC_KEY_LENGTH]>, D::Error> {
+        #[derive(Deserialize)]
+        struct Key(#[serde(with = "crate::rpc::serde_hex::fixed")] [u8; PUBLIC_KEY_LENGTH]);
+
+        let keys = Vec::<Key>::deserialize(deserializer)?;
+        Ok(keys.into_iter().map(|key| key.0).collect())
+    }
+}
+
+/// A beacon block, summarized by the roots of its state and body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct BeaconBlockHeader {
+    #[serde(with = "decimal")]
+    pub slot: u64,
+    #[serde(with = "decimal")]
+    pub proposer_index: u64,
+    pub parent_root: B256,
+    pub state_root: B256,
+    pub body_root: B256,
+}
+
+impl BeaconBlockHeader {
+    /// The block root.
+    pub fn hash_tree_root(&self) -> B256 {
+        ssz::container(&[
+            ssz::uint64(self.slot),
+            ssz::uint64(self.proposer_index),
+            self.parent_root,
+            self.state_root,
+            self.body_root,
+        ])
+    }
+}
+
+/// The execution block a beacon block carries, without its transactions
+/// and withdrawals.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionPayloadHeader {
+    pub parent_hash: B256,
+    pub fee_recipient: Address,
+    pub state_root: B256,
+    pub receipts_root: B256,
+    #[serde(with = "serde_hex::fixed")]
+    pub logs_bloom: [u8; 256],
+    pub prev_randao: B256,
+    #[serde(with = "decimal")]
+    pub block_number: u64,
+    #[serde(with = "decimal")]
+    pub gas_limit: u64,
+    #[serde(with = "decimal")]
+    pub gas_used: u64,
+    #[serde(with = "decimal")]
+    pub timestamp: u64,
+    #[serde(with = "serde_hex::bytes")]
+    pub extra_data: Vec<u8>,
+    #[serde(with = "decimal")]
+    pub base_fee_per_gas: U256,
+    pub block_hash: B256,
+    pub transactions_root: B256,
+    pub withdrawals_root: B256,
+    /// Set from Deneb on.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "option_decimal"
+    )]
+    pub blob_gas_used: Option<u64>,
+    /// Set from Deneb on.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "option_decimal"
+    )]
+    pub excess_blob_gas: Option<u64>,
+}
+
+mod option_decimal {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(n: &Option<u64>, serializer: S) -> Result<S::Ok, S::Error> {
+        match n {
+            Some(n) => super::decimal::serialize(n, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<u64>, D::Error> {
+        #[derive(Deserialize)]
+        struct Decimal(#[serde(with = "super::decimal")] u64);
+
+        Ok(Option::<Decimal>::deserialize(deserializer)?.map(|n| n.0))
+    }
+}
+
+impl ExecutionPayloadHeader {
+    pub fn hash_tree_root(&self) -> B256 {
+        let mut fields = vec![
+            self.parent_hash,
+            ssz::byte_vector(&self.fee_recipient.0),
+            self.state_root,
+            self.receipts_root,
+            ssz::byte_vector(&self.logs_bloom),
+            self.prev_randao,
+            ssz::uint64(self.block_number),
+            ssz::uint64(self.gas_limit),
+            ssz::uint64(self.gas_used),
+            ssz::uint64(self.timestamp),
+            ssz::byte_list(&self.extra_data, 32),
+            ssz::uint256(&self.base_fee_per_gas),
+            self.block_hash,
+            self.transactions_root,
+            self.withdrawals_root,
+        ];
+        if let (Some(blob_gas_used), Some(excess_blob_gas)) =
+            (self.blob_gas_used, self.excess_blob_gas)
+        {
+            fields.push(ssz::uint64(blob_gas_used));
+            fields.push(ssz::uint64(excess_blob_gas));
+        }
+        ssz::container(&fields)
+    }
+}
+
+/// A beacon block header with the execution payload header it commits to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LightClientHeader {
+    pub beacon: BeaconBlockHeader,
+    pub execution: ExecutionPayloadHeader,
+    /// The branch from the execution payload header to the body root.
+    pub execution_branch: Vec<B256>,
+}
+
+impl LightClientHeader {
+    /// Checks that the execution payload header is the one the beacon block
+    /// carries.
+    pub fn verify_execution(&self) -> Result<()> {
+        if !ssz::verify_branch(
+            &self.execution.hash_tree_root(),
+            &self.execution_branch,
+            EXECUTION_PAYLOAD_GINDEX,
+            &self.beacon.body_root,
+        ) {
+            return Err(Error::InvalidProof(format!(
+                "execution payload of slot {} is not in its body",
+                self.beacon.slot
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// The validators that sign beacon headers for one period.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncCommittee {
+    #[serde(with = "public_keys")]
+    pub pubkeys: Vec<[u8; bls::PUBLIC_KEY_LENGTH]>,
+    #[serde(with = "serde_hex::fixed")]
+    pub aggregate_pubkey: [u8; bls::PUBLIC_KEY_LENGTH],
+}
+
+impl SyncCommittee {
+    pub fn hash_tree_root(&self) -> B256 {
+        let keys: Vec<B256> = self
+            .pubkeys
+            .iter()
+            .map(|key| ssz::byte_vector(key))
+            .collect();
+        ssz::container(&[
+            ssz::merkleize(&keys, SYNC_COMMITTEE_SIZE),
+            ssz::byte_vector(&self.aggregate_pubkey),
+        ])
+    }
+
+    fn check_size(&self) -> Result<()> {
+        if self.pubkeys.len() != SYNC_COMMITTEE_SIZE {
+            return Err(update_error(format!(
+                "sync committee of {} members",
+                self.pubkeys.len()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// The committee members that signed a header, and their signature.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncAggregate {
+    /// Bit `i`, least significant first, is set if member `i` signed.
+    #[serde(with = "serde_hex::fixed")]
+    pub sync_committee_bits: [u8; SYNC_COMMITTEE_SIZE / 8],
+    #[serde(with = "serde_hex::fixed")]
+    pub sync_committee_signature: [u8; bls::SIGNATURE_LENGTH],
+}
+
+impl SyncAggregate {
+    /// The positions of the members that signed.
+    pub fn participants(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..SYNC_COMMITTEE_SIZE).filter(|i| self.sync_committee_bits[i / 8] >> (i % 8) & 1 == 1)
+    }
+}
+
+/// A trusted header with the committee signing its period.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LightClientBootstrap {
+    pub header: LightClientHeader,
+    pub current_sync_committee: SyncCommittee,
+    pub current_sync_committee_branch: Vec<B256>,
+}
+
+/// A signed header, with the finalized header and next committee its state
+/// commits to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LightClientUpdate {
+    /// The header the committee signed.
+    pub attested_header: LightClientHeader,
+    /// Absent from finality-only updates.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_sync_committee: Option<SyncCommittee>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub next_sync_committee_branch: Vec<B256>,
+    pub finalized_header: LightClientHeader,
+    pub finality_branch: Vec<B256>,
+    pub sync_aggregate: SyncAggregate,
+    #[serde(with = "decimal")]
+    pub signature_slot: u64,
+}
+
+impl LightClientUpdate {
+    /// The next committee, if the update proves one. The Beacon API fills
+    /// an update without one with zeros.
+    fn next_committee(&self) -> Option<&SyncCommittee> {
+        self.next_sync_committee.as_ref().filter(|_| {
+            self.next_sync_committee_branch
+                .iter()
+                .any(|node| *node != B256::ZERO)
+        })
+    }
+}
+
+/// Follows finalized beacon headers from a trusted checkpoint.
+#[derive(Debug, Clone)]
+pub struct LightClient {
+    spec: ChainSpec,
+    finalized: LightClientHeader,
+    current_sync_committee: SyncCommittee,
+    next_sync_committee: Option<SyncCommittee>,
+}
+
+impl LightClient {
+    /// Starts from `bootstrap`, which must be for the block with
+    /// `trusted_block_root`, a root obtained out of band.
+    pub fn new(
+        spec: ChainSpec,
+        trusted_block_root: &B256,
+        bootstrap: LightClientBootstrap,
+    ) -> Result<Self> {
+        let header = &bootstrap.header;
+        if header.beacon.hash_tree_root() != *trusted_block_root {
+            return Err(update_error(format!(
+                "bootstrap header is not block {trusted_block_root}"
+            )));
+        }
+        header.verify_execution()?;
+        bootstrap.current_sync_committee.check_size()?;
+        let fork = spec.fork_at_slot(header.beacon.slot).fork;
+        if !ssz::verify_branch(
+            &bootstrap.current_sync_committee.hash_tree_root(),
+            &bootstrap.current_sync_committee_branch,
+            fork.current_sync_committee_gindex(),
+            &header.beacon.state_root,
+        ) {
+            return Err(Error::InvalidProof(
+                "current sync committee is not in the bootstrap state".to_owned(),
+            ));
+        }
+        Ok(Self {
+            spec,
+            finalized: bootstrap.header,
+            current_sync_committee: bootstrap.current_sync_committee,
+            next_sync_committee: None,
+        })
+    }
+
+    pub fn spec(&self) -> &ChainSpec {
+        &self.spec
+    }
+
+    /// The latest finalized header.
+    pub fn finalized(&self) -> &LightClientHeader {
+        &self.finalized
+    }
+
+    /// The execution state root of the latest finalized block, against
+    /// which [`AccountProof`](crate::AccountProof)s can be verified.
+    pub fn finalized_state_root(&self) -> B256 {
+        self.finalized.execution.state_root
+    }
+
+    /// The number of the latest finalized execution block.
+    pub fn finalized_block_number(&self) -> u64 {
+        self.finalized.execution.block_number
+    }
+
+    /// The sync committee period of the finalized header.
+    pub fn period(&self) -> u64 {
+        period_at_slot(self.finalized.beacon.slot)
+    }
+
+    pub fn current_sync_committee(&self) -> &SyncCommittee {
+        &self.current_sync_committee
+    }
+
+    pub fn next_sync_committee(&self) -> Option<&SyncCommittee> {
+        self.next_sync_committee.as_ref()
+    }
+
+    /// Verifies `update` and moves the finalized header forward, rotating
+    /// committees when it crosses into the next period. `current_slot` is
+    /// the wall-clock slot; updates signed later are rejected.
+    ///
+    /// Only updates signed by two thirds of the committee are accepted. On
+    /// error the client is left unchanged.
+    pub fn apply(&mut self, update: &LightClientUpdate, current_slot: u64) -> Result<()> {
+        let participants: Vec<usize> = update.sync_aggregate.participants().collect();
+        if participants.len() * 3 < SYNC_COMMITTEE_SIZE * 2 {
+            return Err(update_error(format!(
+                "{} of {SYNC_COMMITTEE_SIZE} committee members signed",
+                participants.len()
+            )));
+        }
+
+        let attested = &update.attested_header.beacon;
+        let finalized = &update.finalized_header.beacon;
+        if !(current_slot >= update.signature_slot
+            && update.signature_slot > attested.slot
+            && attested.slot >= finalized.slot)
+        {
+            return Err(update_error(format!(
+                "slots out of order: finalized {}, attested {}, signed {}, now {current_slot}",
+                finalized.slot, attested.slot, update.signature_slot
+            )));
+        }
+        let store_period = self.period();
+        let signature_period = period_at_slot(update.signature_slot);
+        let committee = match &self.next_sync_committee {
+            _ if signature_period == store_period => &self.current_sync_committee,
+            Some(next) if signature_period == store_period + 1 => next,
+            _ => {
+                return Err(update_error(format!(
+                    "signed in period {signature_period}, the client is in {store_period}"
+                )))
+            }
+        };
+
+        let next_committee = update.next_committee();
+        let attested_period = period_at_slot(attested.slot);
+        let learns_next = self.next_sync_committee.is_none()
+            && next_committee.is_some()
+            && attested_period == store_period;
+        if finalized.slot <= self.finalized.beacon.slot && !learns_next {
+            return Err(update_error(format!(
+                "finalized slot {} is not past {}",
+                finalized.slot, self.finalized.beacon.slot
+            )));
+        }
+        let finalized_period = period_at_slot(finalized.slot);
+        if self.next_sync_committee.is_none() && finalized_period != store_period {
+            return Err(update_error(format!(
+                "finalized period {finalized_period} skips period {store_period}'s committee"
+            )));
+        }
+
+        update.attested_header.verify_execution()?;
+        update.finalized_header.verify_execution()?;
+        let fork = self.spec.fork_at_slot(attested.slot).fork;
+        if !ssz::verify_branch(
+            &finalized.hash_tree_root(),
+            &update.finality_branch,
+            fork.finalized_root_gindex(),
+            &attested.state_root,
+        ) {
+            return Err(Error::InvalidProof(format!(
+                "slot {} is not finalized in the state of slot {}",
+                finalized.slot, attested.slot
+            )));
+        }
+        if let Some(next) = next_committee {
+            next.check_size()?;
+            if attested_period == store_period {
+                if let Some(known) = &self.next_sync_committee {
+                    if known != next {
+                        return Err(update_error("conflicting next sync committee"));
+                    }
+                }
+            }
+            if !ssz::verify_branch(
+                &next.hash_tree_root(),
+                &update.next_sync_committee_branch,
+                fork.next_sync_committee_gindex(),
+                &attested.state_root,
+            ) {
+                return Err(Error::InvalidProof(format!(
+                    "next sync committee is not in the state of slot {}",
+                    attested.slot
+                )));
+            }
+        }
+
+        let keys: Vec<_> = participants.iter().map(|&i| committee.pubkeys[i]).collect();
+        let domain = self
+            .spec
+            .sync_committee_domain(update.signature_slot.max(1) - 1);
+        let signing_root = ssz::hash_pair(&attested.hash_tree_root(), &domain);
+        if !bls::fast_aggregate_verify(
+            &keys,
+            &signing_root.0,
+            &update.sync_aggregate.sync_committee_signature,
+        )? {
+            return Err(Error::InvalidSignature(format!(
+                "sync committee signature over slot {}",
+                attested.slot
+            )));
+        }
+
+        if self.next_sync_committee.is_none() {
+            self.next_sync_committee = next_committee.cloned();
+        } else if finalized_period == store_period + 1 {
+            let next = self
+                .next_sync_committee
+                .take()
+                .expect("next committee known");
+            self.current_sync_committee = next;
+            self.next_sync_committee = next_committee.cloned();
+        }
+        if finalized.slot > self.finalized.beacon.slot {
+            self.finalized = update.finalized_header.clone();
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,51 @@
+//! BLS12-381 signatures as used by Ethereum's consensus layer and BSC's
+//! fast-finality votes.
+//!
+//! Both use the proof-of-possession scheme with public keys in G1 and
+//! signatures in G2, so the only operation a light client needs is
+//! [`fast_aggregate_verify`]: many signers, one message.
+
+use blst::min_pk::{PublicKey, Signature};
+use blst::BLST_ERROR;
+
+use crate::error::{Error, Result};
+
+/// Length of a compressed public key.
+pub const PUBLIC_KEY_LENGTH: usize = 48;
+/// Length of a compressed signature.
+pub const SIGNATURE_LENGTH: usize = 96;
+/// The hash-to-curve domain of the proof-of-possession scheme.
+pub const DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+/// Checks that `signature` aggregates signatures of `message` by every key
+/// in `public_keys`.
+///
+/// Returns `Ok(false)` for a well-formed signature that does not verify,
+/// and an error for keys or signatures that are not valid curve points.
+/// An empty key set never verifies.
+pub fn fast_aggregate_verify(
+    public_keys: &[[u8; PUBLIC_KEY_LENGTH]],
+    message: &[u8],
+    signature: &[u8; SIGNATURE_LENGTH],
+) -> Result<bool> {
+    if public_keys.is_empty() {
+        return Ok(false);
+    }
+    let keys = public_keys
+        .iter()
+        .map(|key| {
+            PublicKey::key_validate(key)
+                .map_err(|err| Error::InvalidSignature(format!("BLS public key: {err:?}")))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let signature = Signature::sig_validate(signature, true)
+        .map_err(|err| Error::InvalidSignature(format!("BLS signature: {err:?}")))?;
+    let keys: Vec<&PublicKey> = keys.iter().collect();
+    match signature.fast_aggregate_verify(false, message, DST, &keys) {
+        BLST_ERROR::BLST_SUCCESS => Ok(true),
+        BLST_ERROR::BLST_VERIFY_FAIL => Ok(false),
+        err => Err(Error::InvalidSignature(format!(
+            "BLS verification: {err:?}"
+        ))),
+    }
+}
@@ -0,0 +1,145 @@
+use async_trait::async_trait;
+use crossbeam_core::{Chain, Confirmation, Network, Signer, Transfer};
+
+use crate::error::{Error, Result};
+use crate::fees::{FeeStrategy, Fees};
+use crate::primitives::TxHash;
+use crate::provider::Provider;
+use crate::transaction::{
+    Eip1559Transaction, LegacyTransaction, SignedTransaction, Transaction, TRANSFER_GAS,
+};
+use crate::Address;
+use crate::MAINNET_CHAIN_ID;
+
+/// [`Chain`] implementation for any EVM network.
+#[derive(Debug, Clone)]
+pub struct EvmChain<P> {
+    network: Network,
+    chain_id: u64,
+    provider: P,
+    fees: FeeStrategy,
+}
+
+impl<P: Provider> EvmChain<P> {
+    /// An adapter for `network`, signing for EIP-155 `chain_id` and
+    /// pricing transfers with [`FeeStrategy::eip1559`].
+    pub fn new(network: Network, chain_id: u64, provider: P) -> Self {
+        Self {
+            network,
+            chain_id,
+            provider,
+            fees: FeeStrategy::eip1559(),
+        }
+    }
+
+    /// Prices transfers with `fees` instead, e.g. to cap what they may pay.
+    pub fn with_fee_strategy(mut self, fees: FeeStrategy) -> Self {
+        self.fees = fees;
+        self
+    }
+
+    /// An adapter for Ethereum mainnet.
+    pub fn ethereum(provider: P) -> Self {
+        Self::new(Network::Ethereum, MAINNET_CHAIN_ID, provider)
+    }
+
+    pub fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    pub fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    pub fn fee_strategy(&self) -> &FeeStrategy {
+        &self.fees
+    }
+
+    fn unsupported(&self, operation: &'static str) -> Error {
+        crossbeam_core::Error::Unsupported {
+            network: self.network,
+            operation,
+        }
+        .into()
+    }
+}
+
+#[async_trait]
+impl<P: Provider> Chain for EvmChain<P> {
+    type Address = Address;
+    type Transaction = Transaction;
+    type SignedTransaction = SignedTransaction;
+    type TxId = TxHash;
+    type Error = Error;
+
+    fn network(&self) -> Network {
+        self.network
+    }
+
+    fn parse_address(&self, s: &str) -> Result<Address> {
+        Ok(s.parse()?)
+    }
+
+    /// Builds a transfer priced by the adapter's [`FeeStrategy`]: an
+    /// EIP-1559 transaction under EIP-1559 pricing, otherwise an EIP-155
+    /// legacy one. Fails if the market needs more than the strategy's caps.
+    async fn build_transfer(&self, transfer: &Transfer<Address>) -> Result<Transaction> {
+        let asset = transfer.amount.asset();
+        if asset.network() != self.network || !asset.is_native() {
+            return Err(self.unsupported("transfers of non-native assets"));
+        }
+        let nonce = self.provider.transaction_count(&transfer.from).await?;
+        Ok(match self.fees.estimate(&self.provider).await? {
+            Fees::Legacy { gas_price } => LegacyTransaction {
+                chain_id: Some(self.chain_id),
+                nonce,
+                gas_price,
+                gas_limit: TRANSFER_GAS,
+                to: Some(transfer.to),
+                value: transfer.amount.units(),
+                input: Vec::new(),
+            }
+            .into(),
+            Fees::Eip1559 {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            } => Eip1559Transaction {
+                chain_id: self.chain_id,
+                nonce,
+                max_priority_fee_per_gas,
+                max_fee_per_gas,
+                gas_limit: TRANSFER_GAS,
+                to: Some(transfer.to),
+                value: transfer.amount.units(),
+                input: Vec::new(),
+                access_list: Vec::new(),
+            }
+            .into(),
+        })
+    }
+
+    fn sign(&self, tx: Transaction, signers: &[&dyn Signer]) -> Result<SignedTransaction> {
+        let [signer] = signers else {
+            return Err(Error::InvalidTransaction(format!(
+                "EVM transactions take exactly one signer, got {}",
+                signers.len()
+            )));
+        };
+        if tx.chain_id().is_some_and(|id| id != self.chain_id) {
+            return Err(Error::InvalidTransaction(format!(
+                "transaction is for chain {:?}, adapter signs for chain {}",
+                tx.chain_id(),
+                self.chain_id
+            )));
+        }
+        tx.sign(*signer)
+    }
+
+    async fn submit(&self, tx: &SignedTransaction) -> Result<TxHash> {
+        self.provider.send_raw_transaction(&tx.encode()).await
+    }
+
+    async fn confirmation(&self, id: &TxHash) -> Result<Confirmation> {
+        self.provider.transaction_confirmation(id).await
+    }
+}
@@ -0,0 +1,61 @@
+use thiserror::Error;
+
+/// Errors produced by the EVM adapter.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum Error {
+    #[error(transparent)]
+    Core(#[from] crossbeam_core::Error),
+    #[error("invalid hex: {0}")]
+    Hex(#[from] hex::FromHexError),
+    #[error("expected {expected} bytes, got {actual}")]
+    InvalidLength { expected: usize, actual: usize },
+    #[error("invalid RLP: {0}")]
+    Rlp(String),
+    #[error("invalid transaction: {0}")]
+    InvalidTransaction(String),
+    #[error("invalid signature: {0}")]
+    InvalidSignature(String),
+    #[error("provider error: {0}")]
+    Provider(String),
+    #[error(transparent)]
+    Abi(#[from] crossbeam_abi::Error),
+    /// The node answered with a JSON-RPC error object.
+    #[error("JSON-RPC error {code}: {message}")]
+    Rpc {
+        code: i64,
+        message: String,
+        data: Option<serde_json::Value>,
+    },
+    /// An `eth_call` or gas estimate reverted. `data` is the raw revert
+    /// payload, for [`Revert::decode`](crossbeam_abi::Revert::decode).
+    #[error("{message}")]
+    Reverted { message: String, data: Vec<u8> },
+    /// The request never got a JSON-RPC answer.
+    #[error("transport error: {0}")]
+    Transport(String),
+    #[error("invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("invalid filter: {0}")]
+    InvalidFilter(String),
+    /// The fee market needs more than the configured cap.
+    #[error("fee of {required} wei per gas exceeds the cap of {cap}")]
+    FeeCapExceeded {
+        required: crossbeam_core::U256,
+        cap: crossbeam_core::U256,
+    },
+    /// A Merkle-Patricia proof did not check out against its root.
+    #[error("invalid proof: {0}")]
+    InvalidProof(String),
+    #[error("invalid header: {0}")]
+    InvalidHeader(String),
+    /// A light-client update broke the sync protocol's rules.
+    #[error("invalid light client update: {0}")]
+    InvalidUpdate(String),
+    #[error("nonce management: {0}")]
+    Nonce(String),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -0,0 +1,350 @@
+//! Fee estimation and bumping.
+//!
+//! A [`FeeStrategy`] turns the node's view of the fee market into [`Fees`]
+//! for a new transaction: EIP-1559 fees from `eth_feeHistory` on Ethereum,
+//! or a plain gas price on chains such as BSC where legacy pricing is the
+//! norm. [`FeeCaps`] bound what a strategy may ever offer; when the market
+//! needs more than the caps allow, estimation fails instead of producing a
+//! transaction that would never be mined.
+
+use crossbeam_core::U256;
+
+use crate::error::{Error, Result};
+use crate::nonce::MIN_REPLACEMENT_BUMP;
+use crate::provider::Provider;
+use crate::transaction::Transaction;
+
+/// One gwei in wei.
+pub const GWEI: u64 = 1_000_000_000;
+
+/// What a transaction offers to pay per unit of gas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fees {
+    Legacy {
+        gas_price: U256,
+    },
+    Eip1559 {
+        max_fee_per_gas: U256,
+        max_priority_fee_per_gas: U256,
+    },
+}
+
+impl Fees {
+    /// The fees `tx` offers.
+    pub fn of(tx: &Transaction) -> Self {
+        match tx {
+            Transaction::Legacy(tx) => Fees::Legacy {
+                gas_price: tx.gas_price,
+            },
+            Transaction::Eip2930(tx) => Fees::Legacy {
+                gas_price: tx.gas_price,
+            },
+            Transaction::Eip1559(tx) => Fees::Eip1559 {
+                max_fee_per_gas: tx.max_fee_per_gas,
+                max_priority_fee_per_gas: tx.max_priority_fee_per_gas,
+            },
+        }
+    }
+
+    /// The most the transaction can pay per gas.
+    pub fn max_fee_per_gas(&self) -> U256 {
+        match *self {
+            Fees::Legacy { gas_price } => gas_price,
+            Fees::Eip1559 {
+                max_fee_per_gas, ..
+            } => max_fee_per_gas,
+        }
+    }
+
+    /// The most a transaction using `gas_limit` can cost, in wei.
+    pub fn max_cost(&self, gas_limit: u64) -> U256 {
+        self.max_fee_per_gas().saturating_mul(U256::from(gas_limit))
+    }
+
+    /// Sets the fees of `tx`. Legacy and access-list transactions take
+    /// only a gas price, and EIP-1559 transactions only EIP-1559 fees.
+    pub fn apply(&self, tx: &mut Transaction) -> Result<()> {
+        match (tx, *self) {
+            (Transaction::Legacy(tx), Fees::Legacy { gas_price }) => tx.gas_price = gas_price,
+            (Transaction::Eip2930(tx), Fees::Legacy { gas_price }) => tx.gas_price = gas_price,
+            (
+                Transaction::Eip1559(tx),
+                Fees::Eip1559 {
+                    max_fee_per_gas,
+                    max_priority_fee_per_gas,
+                },
+            ) => {
+                tx.max_fee_per_gas = max_fee_per_gas;
+                tx.max_priority_fee_per_gas = max_priority_fee_per_gas;
+            }
+            (tx, fees) => {
+                return Err(Error::InvalidTransaction(format!(
+                    "cannot apply {fees:?} to a type {} transaction",
+                    tx.tx_type()
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    /// Every component raised by `percent`, rounded up.
+    pub fn bumped(&self, percent: u64) -> Self {
+        let bump = |fee: U256| {
+            fee.saturating_mul(U256::from(100 + percent))
+                .div_ceil(U256::from(100u8))
+        };
+        match *self {
+            Fees::Legacy { gas_price } => Fees::Legacy {
+                gas_price: bump(gas_price),
+            },
+            Fees::Eip1559 {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            } => Fees::Eip1559 {
+                max_fee_per_gas: bump(max_fee_per_gas),
+                max_priority_fee_per_gas: bump(max_priority_fee_per_gas),
+            },
+        }
+    }
+}
+
+/// Upper bounds on the fees a strategy offers. `None` leaves a component
+/// unbounded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FeeCaps {
+    /// Bounds the gas price, or the EIP-1559 max fee.
+    pub max_fee_per_gas: Option<U256>,
+    /// Bounds the EIP-1559 priority fee; lowering it only slows inclusion.
+    pub max_priority_fee_per_gas: Option<U256>,
+}
+
+impl FeeCaps {
+    /// Clamps `fees` to the caps. Fails if `required`, the least per-gas
+    /// fee the transaction must offer to be mined, is above the cap.
+    fn enforce(&self, fees: Fees, required: U256) -> Result<Fees> {
+        let cap = self.max_fee_per_gas.unwrap_or(U256::MAX);
+        if required > cap {
+            return Err(Error::FeeCapExceeded { required, cap });
+        }
+        Ok(match fees {
+            Fees::Legacy { gas_price } => Fees::Legacy {
+                gas_price: gas_price.min(cap),
+            },
+            Fees::Eip1559 {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            } => {
+                let max_fee_per_gas = max_fee_per_gas.min(cap);
+                let tip_cap = self.max_priority_fee_per_gas.unwrap_or(U256::MAX);
+                Fees::Eip1559 {
+                    max_fee_per_gas,
+                    max_priority_fee_per_gas: max_priority_fee_per_gas
+                        .min(tip_cap)
+                        .min(max_fee_per_gas),
+                }
+            }
+        })
+    }
+}
+
+/// EIP-1559 fees from recent blocks.
+///
+/// The priority fee is the median, over the last `blocks` non-empty blocks,
+/// of the tip paid at `reward_percentile` within each block. The max fee
+/// leaves room for the base fee to grow by `base_fee_headroom_percent`
+/// before the transaction is priced out: the default of 100 doubles the
+/// next base fee, which survives six consecutive full blocks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Eip1559Estimator {
+    pub blocks: u64,
+    pub reward_percentile: f64,
+    pub base_fee_headroom_percent: u64,
+    /// Floor for the priority fee, e.g. when recent blocks were empty.
+    pub min_priority_fee_per_gas: U256,
+}
+
+impl Default for Eip1559Estimator {
+    fn default() -> Self {
+        Self {
+            blocks: 10,
+            reward_percentile: 50.0,
+            base_fee_headroom_percent: 100,
+            min_priority_fee_per_gas: U256::from(GWEI / 10),
+        }
+    }
+}
+
+impl Eip1559Estimator {
+    async fn estimate<P: Provider + ?Sized>(&self, provider: &P) -> Result<(Fees, U256)> {
+        let history = provider
+            .fee_history(self.blocks, &[self.reward_percentile])
+            .await?;
+        let base_fee = history
+            .next_base_fee()
+            .ok_or_else(|| Error::Provider("fee history without base fees".to_owned()))?;
+        let mut tips: Vec<U256> = history
+            .reward
+            .iter()
+            .zip(&history.gas_used_ratio)
+            .filter(|(_, used)| **used > 0.0)
+            .filter_map(|(rewards, _)| rewards.first().copied())
+            .collect();
+        tips.sort();
+        let tip = tips
+            .get(tips.len() / 2)
+            .copied()
+            .unwrap_or_default()
+            .max(self.min_priority_fee_per_gas);
+        let headroom =
+            base_fee.saturating_mul(U256::from(self.base_fee_headroom_percent)) / U256::from(100u8);
+        let fees = Fees::Eip1559 {
+            max_fee_per_gas: base_fee.saturating_add(headroom).saturating_add(tip),
+            max_priority_fee_per_gas: tip,
+        };
+        Ok((fees, base_fee))
+    }
+}
+
+/// A legacy gas price: the node's `eth_gasPrice`, scaled by
+/// `multiplier_percent` and raised to at least `min_gas_price`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LegacyEstimator {
+    pub multiplier_percent: u64,
+    pub min_gas_price: U256,
+}
+
+impl Default for LegacyEstimator {
+    fn default() -> Self {
+        Self {
+            multiplier_percent: 100,
+            min_gas_price: U256::ZERO,
+        }
+    }
+}
+
+impl LegacyEstimator {
+    async fn estimate<P: Provider + ?Sized>(&self, provider: &P) -> Result<(Fees, U256)> {
+        let suggested = provider.gas_price().await?;
+        let gas_price = (suggested.saturating_mul(U256::from(self.multiplier_percent))
+            / U256::from(100u8))
+        .max(self.min_gas_price);
+        Ok((Fees::Legacy { gas_price }, suggested))
+    }
+}
+
+/// How a chain adapter prices transactions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FeeStrategy {
+    pub estimator: Estimator,
+    pub caps: FeeCaps,
+}
+
+/// The pricing model of a [`FeeStrategy`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Estimator {
+    Eip1559(Eip1559Estimator),
+    Legacy(LegacyEstimator),
+}
+
+impl FeeStrategy {
+    /// EIP-1559 pricing with the default estimator and no caps.
+    pub fn eip1559() -> Self {
+        Self {
+            estimator: Estimator::Eip1559(Eip1559Estimator::default()),
+            caps: FeeCaps::default(),
+        }
+    }
+
+    /// Legacy pricing at the node's gas price, with no caps.
+    pub fn legacy() -> Self {
+        Self {
+            estimator: Estimator::Legacy(LegacyEstimator::default()),
+            caps: FeeCaps::default(),
+        }
+    }
+
+    /// Bounds the gas price or EIP-1559 max fee.
+    pub fn max_fee_per_gas(mut self, cap: U256) -> Self {
+        self.caps.max_fee_per_gas = Some(cap);
+        self
+    }
+
+    /// Bounds the EIP-1559 priority fee.
+    pub fn max_priority_fee_per_gas(mut self, cap: U256) -> Self {
+        self.caps.max_priority_fee_per_gas = Some(cap);
+        self
+    }
+
+    /// Fees for a new transaction, within the caps.
+    pub async fn estimate<P: Provider + ?Sized>(&self, provider: &P) -> Result<Fees> {
+        let (fees, required) = self.market(provider).await?;
+        self.caps.enforce(fees, required)
+    }
+
+    async fn market<P: Provider + ?Sized>(&self, provider: &P) -> Result<(Fees, U256)> {
+        match &self.estimator {
+            Estimator::Eip1559(estimator) => estimator.estimate(provider).await,
+            Estimator::Legacy(estimator) => estimator.estimate(provider).await,
+        }
+    }
+
+    /// Re-prices a stuck transaction for resubmission under the same nonce.
+    ///
+    /// Each fee becomes the larger of the current estimate and the old fee
+    /// plus [`MIN_REPLACEMENT_BUMP`] percent, the least nodes accept as a
+    /// replacement. Fails if that exceeds the caps; the transaction is then
+    /// best left to wait.
+    pub async fn bump<P: Provider + ?Sized>(
+        &self,
+        provider: &P,
+        tx: &Transaction,
+    ) -> Result<Transaction> {
+        let (market, required) = self.market(provider).await?;
+        let floor = Fees::of(tx).bumped(MIN_REPLACEMENT_BUMP);
+        let fees = match (floor, market) {
+            (Fees::Legacy { gas_price: old }, Fees::Legacy { gas_price: new }) => Fees::Legacy {
+                gas_price: old.max(new),
+            },
+            (
+                Fees::Eip1559 {
+                    max_fee_per_gas: old_max,
+                    max_priority_fee_per_gas: old_tip,
+                },
+                Fees::Eip1559 {
+                    max_fee_per_gas: new_max,
+                    max_priority_fee_per_gas: new_tip,
+                },
+            ) => Fees::Eip1559 {
+                max_fee_per_gas: old_max.max(new_max),
+                max_priority_fee_per_gas: old_tip.max(new_tip),
+            },
+            // The strategy prices differently from the original; keep its
+            // type and bump by the minimum.
+            (floor, _) => floor,
+        };
+        // Clamping must not undo the bump, so the floor is itself required.
+        let required = required.max(floor.max_fee_per_gas());
+        let fees = self.caps.enforce(fees, required)?;
+        if let (
+            Fees::Eip1559 {
+                max_priority_fee_per_gas: tip,
+                ..
+            },
+            Fees::Eip1559 {
+                max_priority_fee_per_gas: min_tip,
+                ..
+            },
+        ) = (fees, floor)
+        {
+            if tip < min_tip {
+                return Err(Error::FeeCapExceeded {
+                    required: min_tip,
+                    cap: tip,
+                });
+            }
+        }
+        let mut tx = tx.clone();
+        fees.apply(&mut tx)?;
+        Ok(tx)
+    }
+}
@@ -0,0 +1,213 @@
+//! Block headers.
+//!
+//! A block hash is the keccak256 of the RLP-encoded header. Forks append
+//! fields to the end of the header list, so a header's encoding, and with it
+//! its hash, depends on which of the optional fields it carries. BSC uses
+//! the same layout, with its consensus data packed into `extra_data`.
+
+use crossbeam_core::U256;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::primitives::{keccak256, B256};
+use crate::rlp::{ListEncoder, Rlp};
+use crate::rpc::serde_hex;
+use crate::Address;
+
+/// The fork whose header layout a header follows. Only forks that changed
+/// the layout are listed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Fork {
+    /// The original fifteen fields.
+    Frontier,
+    /// Adds `base_fee_per_gas`.
+    London,
+    /// Adds `withdrawals_root`.
+    Shanghai,
+    /// Adds `blob_gas_used`, `excess_blob_gas` and
+    /// `parent_beacon_block_root`.
+    Cancun,
+    /// Adds `requests_hash`.
+    Prague,
+}
+
+impl Fork {
+    pub const ALL: [Fork; 5] = [
+        Fork::Frontier,
+        Fork::London,
+        Fork::Shanghai,
+        Fork::Cancun,
+        Fork::Prague,
+    ];
+
+    fn field_count(self) -> usize {
+        match self {
+            Fork::Frontier => 15,
+            Fork::London => 16,
+            Fork::Shanghai => 17,
+            Fork::Cancun => 20,
+            Fork::Prague => 21,
+        }
+    }
+}
+
+/// A block header, as hashed and as returned by `eth_getBlockByNumber`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Header {
+    pub parent_hash: B256,
+    #[serde(rename = "sha3Uncles")]
+    pub ommers_hash: B256,
+    #[serde(rename = "miner")]
+    pub beneficiary: Address,
+    pub state_root: B256,
+    pub transactions_root: B256,
+    pub receipts_root: B256,
+    #[serde(with = "serde_hex::fixed")]
+    pub logs_bloom: [u8; 256],
+    pub difficulty: U256,
+    #[serde(with = "serde_hex::quantity")]
+    pub number: u64,
+    #[serde(with = "serde_hex::quantity")]
+    pub gas_limit: u64,
+    #[serde(with = "serde_hex::quantity")]
+    pub gas_used: u64,
+    #[serde(with = "serde_hex::quantity")]
+    pub timestamp: u64,
+    #[serde(with = "serde_hex::bytes")]
+    pub extra_data: Vec<u8>,
+    pub mix_hash: B256,
+    #[serde(with = "serde_hex::fixed")]
+    pub nonce: [u8; 8],
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_fee_per_gas: Option<U256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub withdrawals_root: Option<B256>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "serde_hex::option_quantity"
+    )]
+    pub blob_gas_used: Option<u64>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "serde_hex::option_quantity"
+    )]
+    pub excess_blob_gas: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_beacon_block_root: Option<B256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requests_hash: Option<B256>,
+}
+
+impl Header {
+    /// The layout the header follows. Fails unless the fork fields set are
+    /// exactly those of one fork: a field set past a missing one would be
+    /// left out of the encoding and the hash.
+    pub fn fork(&self) -> Result<Fork> {
+        let cancun = [
+            self.blob_gas_used.is_some(),
+            self.excess_blob_gas.is_some(),
+            self.parent_beacon_block_root.is_some(),
+        ];
+        if cancun.contains(&true) && cancun.contains(&false) {
+            return Err(Error::InvalidHeader(
+                "only some of the Cancun fields are set".to_owned(),
+            ));
+        }
+        let set = [
+            self.base_fee_per_gas.is_some(),
+            self.withdrawals_root.is_some(),
+            cancun[0],
+            self.requests_hash.is_some(),
+        ];
+        let contiguous = set.iter().take_while(|set| **set).count();
+        if let Some(gap) = set[contiguous..].iter().position(|set| *set) {
+            return Err(Error::InvalidHeader(format!(
+                "{:?} fields are set without the {:?} ones",
+                Fork::ALL[contiguous + gap + 1],
+                Fork::ALL[contiguous + 1]
+            )));
+        }
+        Ok(Fork::ALL[contiguous])
+    }
+
+    /// The RLP encoding, with the fork fields of [`Header::fork`].
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        let mut list = ListEncoder::new();
+        list.append(&self.parent_hash)
+            .append(&self.ommers_hash)
+            .append(&self.beneficiary)
+            .append(&self.state_root)
+            .append(&self.transactions_root)
+            .append(&self.receipts_root)
+            .append(&self.logs_bloom)
+            .append(&self.difficulty)
+            .append(&self.number)
+            .append(&self.gas_limit)
+            .append(&self.gas_used)
+            .append(&self.timestamp)
+            .append(self.extra_data.as_slice())
+            .append(&self.mix_hash)
+            .append(&self.nonce);
+        // `fork` guarantees the fields it covers are set.
+        let fork = self.fork()?;
+        if fork >= Fork::London {
+            list.append(&self.base_fee_per_gas.unwrap_or_default());
+        }
+        if fork >= Fork::Shanghai {
+            list.append(&self.withdrawals_root.unwrap_or_default());
+        }
+        if fork >= Fork::Cancun {
+            list.append(&self.blob_gas_used.unwrap_or_default())
+                .append(&self.excess_blob_gas.unwrap_or_default())
+                .append(&self.parent_beacon_block_root.unwrap_or_default());
+        }
+        if fork >= Fork::Prague {
+            list.append(&self.requests_hash.unwrap_or_default());
+        }
+        Ok(list.into_bytes())
+    }
+
+    pub fn decode(encoded: &[u8]) -> Result<Self> {
+        let items = Rlp::new(encoded)?.items()?;
+        if !Fork::ALL
+            .iter()
+            .any(|fork| fork.field_count() == items.len())
+        {
+            return Err(Error::InvalidHeader(format!(
+                "header with {} fields",
+                items.len()
+            )));
+        }
+        Ok(Self {
+            parent_hash: items[0].as_b256()?,
+            ommers_hash: items[1].as_b256()?,
+            beneficiary: items[2].as_address()?,
+            state_root: items[3].as_b256()?,
+            transactions_root: items[4].as_b256()?,
+            receipts_root: items[5].as_b256()?,
+            logs_bloom: items[6].fixed()?,
+            difficulty: items[7].as_u256()?,
+            number: items[8].as_u64()?,
+            gas_limit: items[9].as_u64()?,
+            gas_used: items[10].as_u64()?,
+            timestamp: items[11].as_u64()?,
+            extra_data: items[12].bytes()?.to_vec(),
+            mix_hash: items[13].as_b256()?,
+            nonce: items[14].fixed()?,
+            base_fee_per_gas: items.get(15).map(Rlp::as_u256).transpose()?,
+            withdrawals_root: items.get(16).map(Rlp::as_b256).transpose()?,
+            blob_gas_used: items.get(17).map(Rlp::as_u64).transpose()?,
+            excess_blob_gas: items.get(18).map(Rlp::as_u64).transpose()?,
+            parent_beacon_block_root: items.get(19).map(Rlp::as_b256).transpose()?,
+            requests_hash: items.get(20).map(Rlp::as_b256).transpose()?,
+        })
+    }
+
+    /// The block hash.
+    pub fn hash(&self) -> Result<B256> {
+        self.encode().map(keccak256)
+    }
+}
@@ -0,0 +1,43 @@
+//! Ethereum adapter for the CrossBeam SDK.
+//!
+//! Everything EVM-specific lives here and is shared with the Binance Smart
+//! Chain adapter, which only differs in chain id and consensus.
+
+pub mod beacon;
+pub mod bls;
+pub mod chain;
+pub mod error;
+pub mod fees;
+pub mod header;
+pub mod logs;
+pub mod nonce;
+pub mod primitives;
+pub mod proof;
+pub mod provider;
+pub mod rlp;
+pub mod rpc;
+pub mod ssz;
+pub mod transaction;
+pub mod trie;
+
+pub use chain::EvmChain;
+pub use crossbeam_abi as abi;
+pub use crossbeam_core::address::EvmAddress as Address;
+pub use error::{Error, Result};
+pub use fees::{FeeCaps, FeeStrategy, Fees};
+pub use header::{Fork, Header};
+pub use logs::{Filter, Log, LogBatch, LogStream};
+pub use nonce::{FileNonceStore, MemoryNonceStore, NonceManager, NonceStore};
+pub use primitives::{keccak256, TxHash, B256};
+pub use proof::{AccountProof, StorageProof};
+pub use provider::Provider;
+pub use rpc::{BlockNumber, CallRequest, Client, FeeHistory, TransactionReceipt, Transport};
+pub use transaction::{
+    AccessList, AccessListItem, Eip1559Transaction, Eip2930Transaction, LegacyTransaction,
+    Signature, SignedTransaction, Transaction,
+};
+
+/// EIP-155 chain id of Ethereum mainnet.
+pub const MAINNET_CHAIN_ID: u64 = 1;
+/// EIP-155 chain id of the Sepolia testnet.
+pub const SEPOLIA_CHAIN_ID: u64 = 11_155_111;
@@ -0,0 +1,372 @@
+//! Event logs: filters, decoding and chunked retrieval.
+//!
+//! [`LogStream`] walks a block range in `eth_getLogs` calls small enough for
+//! the provider. When a node rejects a range as too large or too busy, the
+//! range is halved and retried, then grown again after a run of successful
+//! calls. Each batch reports the blocks it covers, so a caller can
+//! checkpoint after processing it and resume from [`LogStream::next_block`]
+//! after a restart.
+
+use crossbeam_abi::{ContractEvent, DecodedLog, Event};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::error::{Error, Result};
+use crate::primitives::{TxHash, B256};
+use crate::rpc::serde_hex;
+use crate::rpc::{BlockNumber, Client, Transport};
+use crate::Address;
+
+/// A log entry as returned by `eth_getLogs` and transaction receipts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Log {
+    pub address: Address,
+    pub topics: Vec<B256>,
+    #[serde(with = "serde_hex::bytes")]
+    pub data: Vec<u8>,
+    /// `None` for logs of pending transactions; likewise below.
+    #[serde(default, with = "serde_hex::option_quantity")]
+    pub block_number: Option<u64>,
+    #[serde(default)]
+    pub block_hash: Option<B256>,
+    #[serde(default)]
+    pub transaction_hash: Option<TxHash>,
+    #[serde(default, with = "serde_hex::option_quantity")]
+    pub transaction_index: Option<u64>,
+    #[serde(default, with = "serde_hex::option_quantity")]
+    pub log_index: Option<u64>,
+    /// Set when the log was dropped by a chain reorganization.
+    #[serde(default)]
+    pub removed: bool,
+}
+
+impl Log {
+    /// The topics as plain words, as the ABI decoder takes them.
+    pub fn topic_words(&self) -> Vec<[u8; 32]> {
+        self.topics.iter().map(|t| t.0).collect()
+    }
+
+    /// Decodes the log as the generated binding `E`.
+    pub fn decode<E: ContractEvent>(&self) -> Result<E> {
+        Ok(E::decode_log(&self.topic_words(), &self.data)?)
+    }
+
+    /// Decodes the log against a runtime event description.
+    pub fn decode_with(&self, event: &Event) -> Result<DecodedLog> {
+        Ok(event.decode_log(&self.topic_words(), &self.data)?)
+    }
+}
+
+/// An `eth_getLogs` filter.
+///
+/// Each of the four topic positions is either a wildcard or a set of
+/// accepted values; addresses work the same way. Trailing wildcards are
+/// omitted on the wire.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Filter {
+    pub addresses: Vec<Address>,
+    pub topics: [Option<Vec<B256>>; 4],
+    pub from_block: Option<BlockNumber>,
+    pub to_block: Option<BlockNumber>,
+    /// Restricts the filter to one block; excludes a block range.
+    pub block_hash: Option<B256>,
+}
+
+impl Filter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an emitting contract. Logs from any of the added addresses match.
+    pub fn address(mut self, address: Address) -> Self {
+        self.addresses.push(address);
+        self
+    }
+
+    /// Matches logs of the generated event binding `E`.
+    pub fn event<E: ContractEvent>(self) -> Self {
+        self.topic(0, [B256(E::TOPIC0)])
+    }
+
+    /// Matches logs of `event`.
+    pub fn event_signature(self, event: &Event) -> Self {
+        self.topic(0, [B256(event.topic0())])
+    }
+
+    /// Accepts any of `values` at topic `position` (0 to 3). Use
+    /// [`Event::encode_topic`] to turn indexed arguments into topics.
+    pub fn topic(mut self, position: usize, values: impl IntoIterator<Item = B256>) -> Self {
+        assert!(position < 4, "logs have at most four topics");
+        self.topics[position]
+            .get_or_insert_with(Vec::new)
+            .extend(values);
+        self
+    }
+
+    pub fn from_block(mut self, block: impl Into<BlockNumber>) -> Self {
+        self.from_block = Some(block.into());
+        self
+    }
+
+    pub fn to_block(mut self, block: impl Into<BlockNumber>) -> Self {
+        self.to_block = Some(block.into());
+        self
+    }
+
+    /// Inclusive block range.
+    pub fn blocks(self, from: u64, to: u64) -> Self {
+        self.from_block(from).to_block(to)
+    }
+
+    pub fn at_block_hash(mut self, hash: B256) -> Self {
+        self.block_hash = Some(hash);
+        self
+    }
+
+    /// Whether `log` passes the address and topic conditions. Block bounds
+    /// are not checked.
+    pub fn matches(&self, log: &Log) -> bool {
+        if !self.addresses.is_empty() && !self.addresses.contains(&log.address) {
+            return false;
+        }
+        self.topics
+            .iter()
+            .enumerate()
+            .all(|(i, accepted)| match accepted {
+                None => true,
+                Some(values) => log.topics.get(i).is_some_and(|t| values.contains(t)),
+            })
+    }
+}
+
+impl Serialize for Filter {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        match self.addresses.as_slice() {
+            [] => {}
+            [one] => map.serialize_entry("address", one)?,
+            many => map.serialize_entry("address", many)?,
+        }
+        let used = self
+            .topics
+            .iter()
+            .rposition(Option::is_some)
+            .map_or(0, |i| i + 1);
+        if used > 0 {
+            let topics: Vec<serde_json::Value> = self.topics[..used]
+                .iter()
+                .map(|t| match t.as_deref() {
+                    None => serde_json::Value::Null,
+                    Some([one]) => serde_json::json!(one),
+                    Some(many) => serde_json::json!(many),
+                })
+                .collect();
+            map.serialize_entry("topics", &topics)?;
+        }
+        if let Some(hash) = &self.block_hash {
+            map.serialize_entry("blockHash", hash)?;
+        } else {
+            if let Some(from) = &self.from_block {
+                map.serialize_entry("fromBlock", from)?;
+            }
+            if let Some(to) = &self.to_block {
+                map.serialize_entry("toBlock", to)?;
+            }
+        }
+        map.end()
+    }
+}
+
+/// Logs from an inclusive block range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogBatch {
+    pub from_block: u64,
+    pub to_block: u64,
+    pub logs: Vec<Log>,
+}
+
+/// Default upper bound on blocks per `eth_getLogs` call.
+pub const DEFAULT_MAX_RANGE: u64 = 2_000;
+
+/// Consecutive successes after which a reduced range is doubled again.
+const GROW_AFTER: u32 = 4;
+
+/// Whether `error` is a node refusing a query for its size rather than
+/// failing outright. Providers word this differently, so the message is
+/// matched as well as the code Infura and Geth use.
+pub fn is_range_limit_error(error: &Error) -> bool {
+    const HINTS: &[&str] = &[
+        "query returned more than",
+        "block range",
+        "range too large",
+        "range is too large",
+        "too many blocks",
+        "too many results",
+        "response size",
+        "limit exceeded",
+        "query timeout",
+    ];
+    match error {
+        Error::Rpc { code, message, .. } => {
+            let message = message.to_lowercase();
+            *code == -32005 || HINTS.iter().any(|hint| message.contains(hint))
+        }
+        _ => false,
+    }
+}
+
+/// Where a [`LogStream`] stops.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum End {
+    /// A fixed height; the stream finishes there.
+    At(u64),
+    /// Whatever the tag resolves to on each call, minus confirmations.
+    Follow(BlockNumber),
+}
+
+/// Fetches the logs matching a filter in adaptive block-range chunks.
+#[derive(Debug)]
+pub struct LogStream<'a, T> {
+    client: &'a Client<T>,
+    filter: Filter,
+    next_block: u64,
+    end: End,
+    confirmations: u64,
+    max_range: u64,
+    range: u64,
+    /// Successful calls since the last rejection; the range only grows
+    /// after a run of them, so a hard provider cap is not probed every call.
+    streak: u32,
+}
+
+impl<'a, T: Transport> LogStream<'a, T> {
+    /// Streams logs matching `filter`, starting at its `from_block`, which
+    /// must be a height or `earliest`.
+    ///
+    /// A numeric `to_block` makes a finite backfill. Without one, or with a
+    /// tag, the stream follows the chain: the tag (`latest` by default) is
+    /// resolved again on every call.
+    pub fn new(client: &'a Client<T>, filter: Filter) -> Result<Self> {
+        if filter.block_hash.is_some() {
+            return Err(Error::InvalidFilter(
+                "cannot stream a filter pinned to a block hash".to_owned(),
+            ));
+        }
+        let next_block = match filter.from_block {
+            Some(BlockNumber::Number(n)) => n,
+            Some(BlockNumber::Earliest) => 0,
+            other => {
+                return Err(Error::InvalidFilter(format!(
+                    "streaming needs a numeric from_block, got {other:?}"
+                )))
+            }
+        };
+        let end = match filter.to_block {
+            Some(BlockNumber::Number(n)) => End::At(n),
+            Some(BlockNumber::Earliest) => End::At(0),
+            Some(tag) => End::Follow(tag),
+            None => End::Follow(BlockNumber::Latest),
+        };
+        Ok(Self {
+            client,
+            filter,
+            next_block,
+            end,
+            confirmations: 0,
+            max_range: DEFAULT_MAX_RANGE,
+            range: DEFAULT_MAX_RANGE,
+            streak: 0,
+        })
+    }
+
+    /// Stays `confirmations` blocks behind the followed head, so shallow
+    /// reorgs never reach the caller. Has no effect on a fixed end block.
+    pub fn confirmations(mut self, confirmations: u64) -> Self {
+        self.confirmations = confirmations;
+        self
+    }
+
+    /// Caps the blocks per request. Providers commonly allow 1k to 10k.
+    pub fn max_range(mut self, blocks: u64) -> Self {
+        self.max_range = blocks.max(1);
+        self.range = self.max_range;
+        self
+    }
+
+    /// The first block not yet returned; persist it to resume later.
+    pub fn next_block(&self) -> u64 {
+        self.next_block
+    }
+
+    /// Whether a finite stream has returned its last batch. Following
+    /// streams are never done.
+    pub fn is_done(&self) -> bool {
+        matches!(self.end, End::At(end) if self.next_block > end)
+    }
+
+    async fn target(&self) -> Result<Option<u64>> {
+        match self.end {
+            End::At(end) => Ok(Some(end)),
+            End::Follow(tag) => {
+                let head = self.client.resolve_block_number(tag).await?;
+                Ok(head.checked_sub(self.confirmations))
+            }
+        }
+    }
+
+    /// Fetches the next chunk. Returns `None` when no new blocks are
+    /// available: either the stream [is done](Self::is_done), or it is
+    /// following the chain and has caught up, in which case the caller
+    /// should wait and call again.
+    pub async fn next_batch(&mut self) -> Result<Option<LogBatch>> {
+        let Some(target) = self.target().await? else {
+            return Ok(None);
+        };
+        if self.next_block > target {
+            return Ok(None);
+        }
+        let from = self.next_block;
+        loop {
+            let to = from.saturating_add(self.range - 1).min(target);
+            let mut filter = self.filter.clone();
+            filter.from_block = Some(BlockNumber::Number(from));
+            filter.to_block = Some(BlockNumber::Number(to));
+            match self.client.get_logs(&filter).await {
+                Ok(logs) => {
+                    self.next_block = to + 1;
+                    self.streak += 1;
+                    if self.streak >= GROW_AFTER {
+                        self.streak = 0;
+                        self.range = self.range.saturating_mul(2).min(self.max_range);
+                    }
+                    return Ok(Some(LogBatch {
+                        from_block: from,
+                        to_block: to,
+                        logs,
+                    }));
+                }
+                Err(error) if is_range_limit_error(&error) && to > from => {
+                    self.range = (to - from).div_ceil(2);
+                    self.streak = 0;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    /// Drains a finite stream into one list. Fails for following streams,
+    /// which never finish.
+    pub async fn collect(mut self) -> Result<Vec<Log>> {
+        if matches!(self.end, End::Follow(_)) {
+            return Err(Error::InvalidFilter(
+                "cannot collect a stream without a numeric to_block".to_owned(),
+            ));
+        }
+        let mut logs = Vec::new();
+        while let Some(batch) = self.next_batch().await? {
+            logs.extend(batch.logs);
+        }
+        Ok(logs)
+    }
+}
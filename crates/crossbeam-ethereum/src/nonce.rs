@@ -0,0 +1,485 @@
+//! Nonce management for a hot wallet.
+//!
+//! [`NonceManager`] hands out nonces to concurrent senders without asking
+//! the node each time, remembers what was sent under each nonce, and
+//! persists both through a [`NonceStore`] before a nonce leaves the
+//! manager. After a restart it resumes from the stored state, so a nonce
+//! that was handed out is never handed out again, even if its transaction
+//! never reached the node.
+//!
+//! Call [`NonceManager::reconcile`] periodically. It drops mined nonces,
+//! notices nonces spent by other senders, and reports transactions the
+//! node no longer knows and nonces that were reserved but never sent.
+//! Both leave a gap that blocks every later transaction; close it by
+//! rebroadcasting, [replacing](replacement) or [cancelling](cancellation)
+//! the transaction, or by sending a [filler](NonceManager::filler).
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crossbeam_core::U256;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::error::{Error, Result};
+use crate::fees::Fees;
+use crate::primitives::TxHash;
+use crate::rpc::serde_hex;
+use crate::rpc::{BlockNumber, Client, Transport};
+use crate::transaction::{LegacyTransaction, SignedTransaction, Transaction, TRANSFER_GAS};
+use crate::Address;
+
+/// The smallest fee increase, in percent, nodes accept for a replacement.
+pub const MIN_REPLACEMENT_BUMP: u64 = 10;
+
+/// A transaction sent under a managed nonce.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingTransaction {
+    pub hash: TxHash,
+    /// The signed encoding, kept for rebroadcasting and re-pricing.
+    #[serde(with = "serde_hex::bytes")]
+    pub raw: Vec<u8>,
+    /// Seconds since the Unix epoch.
+    pub sent_at: u64,
+}
+
+impl PendingTransaction {
+    pub fn decode(&self) -> Result<SignedTransaction> {
+        SignedTransaction::decode(&self.raw)
+    }
+}
+
+/// What a [`NonceStore`] persists per account.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NonceState {
+    /// The next nonce to hand out.
+    pub next: u64,
+    /// Transactions sent and not yet seen mined, by nonce.
+    pub pending: BTreeMap<u64, PendingTransaction>,
+}
+
+/// Durable storage for [`NonceState`], keyed by chain id and account.
+pub trait NonceStore: Send + Sync {
+    fn load(&self, chain_id: u64, address: &Address) -> Result<Option<NonceState>>;
+
+    /// Stores `state`. It must be durable when this returns.
+    fn save(&self, chain_id: u64, address: &Address, state: &NonceState) -> Result<()>;
+}
+
+/// A [`NonceStore`] that forgets everything on restart; for tests and
+/// wallets that only ever send from one short-lived process.
+#[derive(Debug, Default)]
+pub struct MemoryNonceStore {
+    states: Mutex<HashMap<(u64, Address), NonceState>>,
+}
+
+impl MemoryNonceStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl NonceStore for MemoryNonceStore {
+    fn load(&self, chain_id: u64, address: &Address) -> Result<Option<NonceState>> {
+        let states = self.states.lock().expect("nonce store lock poisoned");
+        Ok(states.get(&(chain_id, *address)).cloned())
+    }
+
+    fn save(&self, chain_id: u64, address: &Address, state: &NonceState) -> Result<()> {
+        let mut states = self.states.lock().expect("nonce store lock poisoned");
+        states.insert((chain_id, *address), state.clone());
+        Ok(())
+    }
+}
+
+/// A [`NonceStore`] keeping one JSON file per account in a directory.
+///
+/// Files are replaced atomically, so a crash mid-write leaves the previous
+/// state intact. Only one process may manage an account at a time.
+#[derive(Debug, Clone)]
+pub struct FileNonceStore {
+    dir: PathBuf,
+}
+
+impl FileNonceStore {
+    /// A store in `dir`, which is created if missing.
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    fn path(&self, chain_id: u64, address: &Address) -> PathBuf {
+        self.dir.join(format!(
+            "{chain_id}-{}.json",
+            address.to_string().to_lowercase()
+        ))
+    }
+}
+
+impl NonceStore for FileNonceStore {
+    fn load(&self, chain_id: u64, address: &Address) -> Result<Option<NonceState>> {
+        match fs::read(self.path(chain_id, address)) {
+            Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn save(&self, chain_id: u64, address: &Address, state: &NonceState) -> Result<()> {
+        let path = self.path(chain_id, address);
+        let tmp = path.with_extension("json.tmp");
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(&serde_json::to_vec_pretty(state)?)?;
+        file.sync_all()?;
+        fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+}
+
+/// The outcome of [`NonceManager::reconcile`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Reconciliation {
+    /// The account's nonce in the latest block: every lower nonce is mined.
+    pub mined: u64,
+    /// Tracked transactions whose nonce has been mined.
+    pub confirmed: Vec<(u64, PendingTransaction)>,
+    /// Tracked transactions the node no longer knows. They stay tracked,
+    /// but block every later nonce until [rebroadcast], replaced or
+    /// cancelled.
+    ///
+    /// [rebroadcast]: NonceManager::rebroadcast
+    pub dropped: Vec<(u64, PendingTransaction)>,
+    /// Unmined nonces below the next one with no known transaction.
+    pub gaps: Vec<u64>,
+}
+
+/// Hands out and tracks the nonces of one account on one chain.
+#[derive(Debug)]
+pub struct NonceManager<T, S> {
+    client: Client<T>,
+    store: S,
+    chain_id: u64,
+    address: Address,
+    state: Mutex<Option<NonceState>>,
+}
+
+impl<T: Transport, S: NonceStore> NonceManager<T, S> {
+    pub fn new(client: Client<T>, store: S, chain_id: u64, address: Address) -> Self {
+        Self {
+            client,
+            store,
+            chain_id,
+            address,
+            state: Mutex::new(None),
+        }
+    }
+
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    pub fn client(&self) -> &Client<T> {
+        &self.client
+    }
+
+    /// A copy of the current state, if loaded.
+    pub fn state(&self) -> Option<NonceState> {
+        self.lock().clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<NonceState>> {
+        self.state.lock().expect("nonce manager lock poisoned")
+    }
+
+    /// Applies `f` to the loaded state and persists the result before
+    /// releasing the lock.
+    fn update<R>(&self, f: impl FnOnce(&mut NonceState) -> Result<R>) -> Result<R> {
+        let mut guard = self.lock();
+        let state = guard
+            .as_mut()
+            .ok_or_else(|| Error::Nonce("nonce state not loaded".to_owned()))?;
+        let mut next = state.clone();
+        let out = f(&mut next)?;
+        if next != *state {
+            self.store.save(self.chain_id, &self.address, &next)?;
+            *state = next;
+        }
+        Ok(out)
+    }
+
+    /// Loads the stored state, or starts from the node's pending nonce for
+    /// an account seen for the first time.
+    async fn ensure_loaded(&self) -> Result<()> {
+        if self.lock().is_some() {
+            return Ok(());
+        }
+        let stored = self.store.load(self.chain_id, &self.address)?;
+        let fresh = match stored {
+            Some(_) => None,
+            None => Some(
+                self.client
+                    .get_transaction_count(&self.address, BlockNumber::Pending)
+                    .await?,
+            ),
+        };
+        // Concurrent first calls all get here; only the first may save, or
+        // a later one could overwrite reservations made in between.
+        let mut guard = self.lock();
+        if guard.is_none() {
+            let state = match (stored, fresh) {
+                (Some(state), _) => state,
+                (None, next) => {
+                    let state = NonceState {
+                        next: next.unwrap_or_default(),
+                        pending: BTreeMap::new(),
+                    };
+                    self.store.save(self.chain_id, &self.address, &state)?;
+                    state
+                }
+            };
+            *guard = Some(state);
+        }
+        Ok(())
+    }
+
+    /// Reserves the next nonce. The reservation is persisted before it is
+    /// returned; follow it with [`send`](Self::send), or
+    /// [`release`](Self::release) it if the transaction is abandoned.
+    pub async fn reserve(&self) -> Result<u64> {
+        self.ensure_loaded().await?;
+        self.update(|state| {
+            let nonce = state.next;
+            state.next += 1;
+            Ok(nonce)
+        })
+    }
+
+    /// Gives back a reserved nonce that was never sent. Only the most
+    /// recent reservation can be returned; for any other this is a no-op
+    /// returning `false`, and the nonce stays a gap to be filled.
+    pub fn release(&self, nonce: u64) -> Result<bool> {
+        self.update(|state| {
+            if nonce + 1 == state.next && !state.pending.contains_key(&nonce) {
+                state.next = nonce;
+                Ok(true)
+            } else {
+                Ok(false)
+            }
+        })
+    }
+
+    /// Records `tx` under its nonce, replacing whatever was tracked there.
+    /// The transaction must be signed by this manager's address, for its
+    /// chain.
+    pub fn record(&self, tx: &SignedTransaction) -> Result<()> {
+        let nonce = tx.transaction.nonce();
+        if let Some(chain_id) = tx.transaction.chain_id().filter(|id| *id != self.chain_id) {
+            return Err(Error::Nonce(format!(
+                "transaction is for chain {chain_id}, nonces are managed for chain {}",
+                self.chain_id
+            )));
+        }
+        let sender = tx.recover_signer()?;
+        if sender != self.address {
+            return Err(Error::Nonce(format!(
+                "transaction is signed by {sender}, nonces are managed for {}",
+                self.address
+            )));
+        }
+        self.update(|state| {
+            if nonce >= state.next {
+                return Err(Error::Nonce(format!(
+                    "nonce {nonce} was not reserved; next is {}",
+                    state.next
+                )));
+            }
+            state.pending.insert(
+                nonce,
+                PendingTransaction {
+                    hash: tx.hash(),
+                    raw: tx.encode(),
+                    sent_at: unix_now(),
+                },
+            );
+            Ok(())
+        })
+    }
+
+    /// Records and broadcasts `tx`, whose nonce must have been reserved.
+    ///
+    /// The record is kept even if broadcasting fails, since the node may
+    /// have accepted the transaction before the error; [`reconcile`]
+    /// reports it as dropped if it did not.
+    ///
+    /// [`reconcile`]: Self::reconcile
+    pub async fn send(&self, tx: &SignedTransaction) -> Result<TxHash> {
+        self.record(tx)?;
+        match self.client.send_raw_transaction(&tx.encode()).await {
+            Err(err) if is_already_known(&err) => Ok(tx.hash()),
+            other => other,
+        }
+    }
+
+    /// Broadcasts a tracked transaction again, e.g. after the node dropped
+    /// it from its pool.
+    pub async fn rebroadcast(&self, nonce: u64) -> Result<TxHash> {
+        self.ensure_loaded().await?;
+        let pending = self
+            .lock()
+            .as_ref()
+            .and_then(|state| state.pending.get(&nonce).cloned())
+            .ok_or_else(|| Error::Nonce(format!("no transaction tracked at nonce {nonce}")))?;
+        let tx = pending.decode()?;
+        self.send(&tx).await
+    }
+
+    /// Brings the state in line with the chain and reports what needs
+    /// attention.
+    pub async fn reconcile(&self) -> Result<Reconciliation> {
+        self.ensure_loaded().await?;
+        let mined = self
+            .client
+            .get_transaction_count(&self.address, BlockNumber::Latest)
+            .await?;
+        let in_pool = self
+            .client
+            .get_transaction_count(&self.address, BlockNumber::Pending)
+            .await?;
+        let unmined: Vec<(u64, TxHash)> = self
+            .lock()
+            .as_ref()
+            .map(|state| {
+                state
+                    .pending
+                    .range(mined..)
+                    .map(|(nonce, tx)| (*nonce, tx.hash))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let mut unknown = Vec::new();
+        for (nonce, hash) in unmined {
+            let tx: Option<Value> = self
+                .client
+                .request("eth_getTransactionByHash", json!([hash]))
+                .await?;
+            if tx.is_none() {
+                unknown.push((nonce, hash));
+            }
+        }
+
+        self.update(|state| {
+            let unmined = state.pending.split_off(&mined);
+            let confirmed = std::mem::replace(&mut state.pending, unmined);
+            // Nonces spent by another sender, mined or still in the pool.
+            state.next = state.next.max(mined).max(in_pool);
+            // Dropped transactions stay tracked so they can be rebroadcast.
+            // Skip anything re-recorded while the node was being asked.
+            let dropped = unknown
+                .into_iter()
+                .filter_map(|(nonce, hash)| {
+                    let tx = state.pending.get(&nonce).filter(|tx| tx.hash == hash)?;
+                    Some((nonce, tx.clone()))
+                })
+                .collect();
+            let gaps = (mined..state.next)
+                .filter(|nonce| !state.pending.contains_key(nonce) && *nonce >= in_pool)
+                .collect();
+            Ok(Reconciliation {
+                mined,
+                confirmed: confirmed.into_iter().collect(),
+                dropped,
+                gaps,
+            })
+        })
+    }
+
+    /// Tracked transactions sent at least `age` ago, by nonce: candidates
+    /// for [replacement] at a higher fee.
+    pub fn stuck(&self, age: Duration) -> Vec<(u64, PendingTransaction)> {
+        let cutoff = unix_now().saturating_sub(age.as_secs());
+        self.lock()
+            .as_ref()
+            .map(|state| {
+                state
+                    .pending
+                    .iter()
+                    .filter(|(_, tx)| tx.sent_at <= cutoff)
+                    .map(|(nonce, tx)| (*nonce, tx.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// A zero-value transfer to self at `nonce`, priced at `gas_price`, to
+    /// fill a gap. Sign it and pass it to [`send`](Self::send).
+    pub fn filler(&self, nonce: u64, gas_price: U256) -> Transaction {
+        LegacyTransaction {
+            chain_id: Some(self.chain_id),
+            nonce,
+            gas_price,
+            gas_limit: TRANSFER_GAS,
+            to: Some(self.address),
+            value: U256::ZERO,
+            input: Vec::new(),
+        }
+        .into()
+    }
+}
+
+/// `tx` with every fee raised by `bump_percent` (rounded up), for
+/// resubmitting under the same nonce. Nodes reject replacements that bump
+/// by less than [`MIN_REPLACEMENT_BUMP`].
+pub fn replacement(tx: &Transaction, bump_percent: u64) -> Transaction {
+    let mut tx = tx.clone();
+    Fees::of(&tx)
+        .bumped(bump_percent)
+        .apply(&mut tx)
+        .expect("fees fit the transaction they came from");
+    tx
+}
+
+/// A [replacement] of `tx` that does nothing: a zero-value transfer from
+/// `from` to itself, voiding the original once mined.
+pub fn cancellation(tx: &Transaction, from: Address, bump_percent: u64) -> Transaction {
+    let mut tx = replacement(tx, bump_percent);
+    match &mut tx {
+        Transaction::Legacy(tx) => {
+            (tx.gas_limit, tx.to, tx.value, tx.input) =
+                (TRANSFER_GAS, Some(from), U256::ZERO, Vec::new());
+        }
+        Transaction::Eip2930(tx) => {
+            (tx.gas_limit, tx.to, tx.value, tx.input) =
+                (TRANSFER_GAS, Some(from), U256::ZERO, Vec::new());
+            tx.access_list.clear();
+        }
+        Transaction::Eip1559(tx) => {
+            (tx.gas_limit, tx.to, tx.value, tx.input) =
+                (TRANSFER_GAS, Some(from), U256::ZERO, Vec::new());
+            tx.access_list.clear();
+        }
+    }
+    tx
+}
+
+/// Whether a broadcast failed only because the node already has the
+/// transaction.
+fn is_already_known(error: &Error) -> bool {
+    match error {
+        Error::Rpc { message, .. } => {
+            let message = message.to_lowercase();
+            message.contains("already known") || message.contains("known transaction")
+        }
+        _ => false,
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
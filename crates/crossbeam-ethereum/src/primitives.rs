@@ -0,0 +1,71 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use sha3::{Digest, Keccak256};
+
+use crate::error::{Error, Result};
+
+fn decode_fixed<const N: usize>(s: &str) -> Result<[u8; N]> {
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    let bytes = hex::decode(s)?;
+    bytes
+        .try_into()
+        .map_err(|bytes: Vec<u8>| Error::InvalidLength {
+            expected: N,
+            actual: bytes.len(),
+        })
+}
+
+/// A 32-byte word: hashes, storage keys and slots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct B256(pub [u8; 32]);
+
+impl B256 {
+    pub const ZERO: Self = Self([0; 32]);
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl fmt::Display for B256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{}", hex::encode(self.0))
+    }
+}
+
+impl FromStr for B256 {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        decode_fixed(s).map(Self)
+    }
+}
+
+impl From<[u8; 32]> for B256 {
+    fn from(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl Serialize for B256 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for B256 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+/// A transaction hash.
+pub type TxHash = B256;
+
+/// Keccak-256 of `data`.
+pub fn keccak256(data: impl AsRef<[u8]>) -> B256 {
+    B256(Keccak256::digest(data.as_ref()).into())
+}
@@ -0,0 +1,346 @@
+//! State and receipt proofs.
+//!
+//! [`AccountProof`] is the answer to `eth_getProof`; verifying it against
+//! the state root of a trusted header shows the account and storage slots
+//! it reports are real, whatever node served them. Receipts are committed
+//! to by the header's `receiptsRoot`: [`receipts_root`] recomputes it from
+//! a block's receipts, and [`verify_receipt`] checks a single receipt
+//! against it with a proof from [`receipt_proof`].
+
+use crossbeam_core::U256;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::logs::Log;
+use crate::primitives::{keccak256, B256};
+use crate::rlp::{self, ListEncoder, Rlp};
+use crate::rpc::{serde_hex, TransactionReceipt};
+use crate::trie::{verify_proof, Trie, EMPTY_ROOT};
+use crate::Address;
+
+/// The code hash of accounts without code, `keccak256("")`.
+pub const EMPTY_CODE_HASH: B256 = B256([
+    0xc5, 0xd2, 0x46, 0x01, 0x86, 0xf7, 0x23, 0x3c, 0x92, 0x7e, 0x7d, 0xb2, 0xdc, 0xc7, 0x03, 0xc0,
+    0xe5, 0x00, 0xb6, 0x53, 0xca, 0x82, 0x27, 0x3b, 0x7b, 0xfa, 0xd8, 0x04, 0x5d, 0x85, 0xa4, 0x70,
+]);
+
+fn proof_error(reason: impl Into<String>) -> Error {
+    Error::InvalidProof(reason.into())
+}
+
+/// An account as stored in the state trie.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Account {
+    pub nonce: u64,
+    pub balance: U256,
+    pub storage_root: B256,
+    pub code_hash: B256,
+}
+
+impl Default for Account {
+    /// An account that does not exist: no nonce, balance, storage or code.
+    fn default() -> Self {
+        Self {
+            nonce: 0,
+            balance: U256::ZERO,
+            storage_root: EMPTY_ROOT,
+            code_hash: EMPTY_CODE_HASH,
+        }
+    }
+}
+
+impl Account {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut list = ListEncoder::new();
+        list.append(&self.nonce)
+            .append(&self.balance)
+            .append(&self.storage_root)
+            .append(&self.code_hash);
+        list.into_bytes()
+    }
+
+    pub fn decode(encoded: &[u8]) -> Result<Self> {
+        let items = Rlp::new(encoded)?.items()?;
+        let [nonce, balance, storage_root, code_hash] = items.as_slice() else {
+            return Err(Error::Rlp(format!("account with {} fields", items.len())));
+        };
+        Ok(Self {
+            nonce: nonce.as_u64()?,
+            balance: balance.as_u256()?,
+            storage_root: storage_root.as_b256()?,
+            code_hash: code_hash.as_b256()?,
+        })
+    }
+}
+
+/// The answer to `eth_getProof`: an account and some of its storage
+/// slots, with the trie nodes proving them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountProof {
+    pub address: Address,
+    #[serde(with = "serde_hex::bytes_list")]
+    pub account_proof: Vec<Vec<u8>>,
+    pub balance: U256,
+    pub code_hash: B256,
+    #[serde(with = "serde_hex::quantity")]
+    pub nonce: u64,
+    pub storage_hash: B256,
+    pub storage_proof: Vec<StorageProof>,
+}
+
+impl AccountProof {
+    /// The account fields the node reported.
+    pub fn account(&self) -> Account {
+        Account {
+            nonce: self.nonce,
+            balance: self.balance,
+            storage_root: self.storage_hash,
+            code_hash: self.code_hash,
+        }
+    }
+
+    /// Checks the account against `state_root`, then each storage slot
+    /// against the account's storage root.
+    ///
+    /// An account proven absent must be reported empty. Nodes differ in
+    /// the hashes they report for one, so zero hashes are accepted too.
+    pub fn verify(&self, state_root: &B256) -> Result<()> {
+        let key = keccak256(self.address.0);
+        let reported = self.account();
+        let storage_root = match verify_proof(state_root, &key.0, &self.account_proof)? {
+            Some(encoded) => {
+                if Account::decode(&encoded)? != reported {
+                    return Err(proof_error(format!(
+                        "account {} does not match its proof",
+                        self.address
+                    )));
+                }
+                self.storage_hash
+            }
+            None => {
+                let empty = Account {
+                    storage_root: EMPTY_ROOT,
+                    code_hash: EMPTY_CODE_HASH,
+                    ..reported
+                };
+                let hashes_empty = [B256::ZERO, EMPTY_ROOT].contains(&self.storage_hash)
+                    && [B256::ZERO, EMPTY_CODE_HASH].contains(&self.code_hash);
+                if !hashes_empty || empty != Account::default() {
+                    return Err(proof_error(format!(
+                        "account {} is absent but reported non-empty",
+                        self.address
+                    )));
+                }
+                EMPTY_ROOT
+            }
+        };
+        for slot in &self.storage_proof {
+            slot.verify(&storage_root)?;
+        }
+        Ok(())
+    }
+}
+
+/// One storage slot of an [`AccountProof`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageProof {
+    #[serde(with = "serde_hex::word")]
+    pub key: B256,
+    pub value: U256,
+    #[serde(with = "serde_hex::bytes_list")]
+    pub proof: Vec<Vec<u8>>,
+}
+
+impl StorageProof {
+    /// Checks the slot against the account's `storage_root`. Unset slots
+    /// hold zero.
+    pub fn verify(&self, storage_root: &B256) -> Result<()> {
+        let key = keccak256(self.key.0);
+        let proven = match verify_proof(storage_root, &key.0, &self.proof)? {
+            Some(encoded) => Rlp::new(&encoded)?.as_u256()?,
+            None => U256::ZERO,
+        };
+        if proven != self.value {
+            return Err(proof_error(format!(
+                "slot {} holds {proven}, not {}",
+                self.key, self.value
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// A receipt as committed to by `receiptsRoot`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Receipt {
+    /// The EIP-2718 type of the transaction; 0 for legacy.
+    pub transaction_type: u8,
+    pub success: bool,
+    pub cumulative_gas_used: u64,
+    pub logs_bloom: [u8; 256],
+    /// Logs carry only their address, topics and data.
+    pub logs: Vec<Log>,
+}
+
+impl Receipt {
+    /// The consensus form of an RPC receipt. Pre-Byzantium receipts commit
+    /// to a state root the RPC form lacks, so they are rejected.
+    pub fn from_rpc(receipt: &TransactionReceipt) -> Result<Self> {
+        let status = receipt.status.ok_or_else(|| {
+            proof_error(format!(
+                "receipt of {} has no status",
+                receipt.transaction_hash
+            ))
+        })?;
+        let transaction_type = receipt.transaction_type.unwrap_or(0);
+        Ok(Self {
+            transaction_type: u8::try_from(transaction_type)
+                .ok()
+                .filter(|t| *t < 0x80)
+                .ok_or_else(|| proof_error(format!("transaction type {transaction_type}")))?,
+            success: status == 1,
+            cumulative_gas_used: receipt.cumulative_gas_used,
+            logs_bloom: logs_bloom(&receipt.logs),
+            logs: receipt.logs.clone(),
+        })
+    }
+
+    /// The EIP-2718 encoding: the type byte, if any, then the RLP list.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut logs = ListEncoder::new();
+        for log in &self.logs {
+            let mut topics = ListEncoder::new();
+            for topic in &log.topics {
+                topics.append(topic);
+            }
+            let mut entry = ListEncoder::new();
+            entry
+                .append(&log.address)
+                .append_raw(&topics.into_bytes())
+                .append(log.data.as_slice());
+            logs.append_raw(&entry.into_bytes());
+        }
+        let mut list = ListEncoder::new();
+        list.append(&self.success)
+            .append(&self.cumulative_gas_used)
+            .append(&self.logs_bloom)
+            .append_raw(&logs.into_bytes());
+
+        let mut out = Vec::new();
+        if self.transaction_type != 0 {
+            out.push(self.transaction_type);
+        }
+        list.finish(&mut out);
+        out
+    }
+
+    pub fn decode(encoded: &[u8]) -> Result<Self> {
+        let (transaction_type, payload) = match encoded.first() {
+            Some(&first) if first < 0x80 => (first, &encoded[1..]),
+            _ => (0, encoded),
+        };
+        let items = Rlp::new(payload)?.items()?;
+        let [status, cumulative_gas_used, logs_bloom, logs] = items.as_slice() else {
+            return Err(Error::Rlp(format!("receipt with {} fields", items.len())));
+        };
+        let success = match status.bytes()? {
+            [] => false,
+            [1] => true,
+            _ => return Err(proof_error("receipt carries a state root, not a status")),
+        };
+        let logs = logs
+            .items()?
+            .iter()
+            .map(|log| {
+                let items = log.items()?;
+                let [address, topics, data] = items.as_slice() else {
+                    return Err(Error::Rlp(format!("log with {} fields", items.len())));
+                };
+                Ok(Log {
+                    address: address.as_address()?,
+                    topics: topics
+                        .items()?
+                        .iter()
+                        .map(Rlp::as_b256)
+                        .collect::<Result<_>>()?,
+                    data: data.bytes()?.to_vec(),
+                    block_number: None,
+                    block_hash: None,
+                    transaction_hash: None,
+                    transaction_index: None,
+                    log_index: None,
+                    removed: false,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            transaction_type,
+            success,
+            cumulative_gas_used: cumulative_gas_used.as_u64()?,
+            logs_bloom: logs_bloom.fixed()?,
+            logs,
+        })
+    }
+}
+
+/// The 2048-bit bloom filter over the addresses and topics of `logs`.
+pub fn logs_bloom(logs: &[Log]) -> [u8; 256] {
+    let mut bloom = [0u8; 256];
+    let mut add = |item: &[u8]| {
+        let hash = keccak256(item);
+        for pair in hash.0[..6].chunks(2) {
+            let bit = ((usize::from(pair[0]) << 8) | usize::from(pair[1])) & 2047;
+            bloom[255 - bit / 8] |= 1 << (bit % 8);
+        }
+    };
+    for log in logs {
+        add(&log.address.0);
+        for topic in &log.topics {
+            add(&topic.0);
+        }
+    }
+    bloom
+}
+
+/// The receipt trie of a block, keyed by the RLP-encoded transaction index.
+/// `receipts` must be the block's receipts in order.
+pub fn receipts_trie(receipts: &[TransactionReceipt]) -> Result<Trie> {
+    let mut trie = Trie::new();
+    for (index, receipt) in receipts.iter().enumerate() {
+        trie.insert(
+            rlp::encode(&(index as u64)),
+            Receipt::from_rpc(receipt)?.encode(),
+        );
+    }
+    Ok(trie)
+}
+
+/// Recomputes a block's `receiptsRoot`. If it matches the root of a trusted
+/// header, every receipt in `receipts` is authentic.
+pub fn receipts_root(receipts: &[TransactionReceipt]) -> Result<B256> {
+    Ok(receipts_trie(receipts)?.root())
+}
+
+/// A proof of the receipt at `index` among a block's `receipts`.
+pub fn receipt_proof(receipts: &[TransactionReceipt], index: u64) -> Result<Vec<Vec<u8>>> {
+    if index >= receipts.len() as u64 {
+        return Err(proof_error(format!(
+            "no receipt {index} among {}",
+            receipts.len()
+        )));
+    }
+    Ok(receipts_trie(receipts)?.proof(&rlp::encode(&index)))
+}
+
+/// Verifies that `proof` commits to a receipt at `index` under
+/// `receipts_root`, and returns it.
+pub fn verify_receipt<P: AsRef<[u8]>>(
+    receipts_root: &B256,
+    index: u64,
+    proof: &[P],
+) -> Result<Receipt> {
+    let encoded = verify_proof(receipts_root, &rlp::encode(&index), proof)?
+        .ok_or_else(|| proof_error(format!("no receipt at index {index}")))?;
+    Receipt::decode(&encoded)
+}
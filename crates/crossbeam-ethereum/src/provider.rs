@@ -0,0 +1,28 @@
+use async_trait::async_trait;
+use crossbeam_core::{Confirmation, U256};
+
+use crate::error::Result;
+use crate::primitives::TxHash;
+use crate::rpc::FeeHistory;
+use crate::Address;
+
+/// The node access [`EvmChain`](crate::EvmChain) needs.
+#[async_trait]
+pub trait Provider: Send + Sync {
+    /// The next nonce of `address`, counting pending transactions.
+    async fn transaction_count(&self, address: &Address) -> Result<u64>;
+
+    /// The node's suggested legacy gas price, in wei.
+    async fn gas_price(&self) -> Result<U256>;
+
+    /// Fee market history over the latest `block_count` blocks, with
+    /// priority fees sampled at `reward_percentiles`.
+    async fn fee_history(&self, block_count: u64, reward_percentiles: &[f64])
+        -> Result<FeeHistory>;
+
+    /// Broadcasts a signed, RLP-encoded transaction.
+    async fn send_raw_transaction(&self, raw: &[u8]) -> Result<TxHash>;
+
+    /// Reports where the transaction `hash` stands.
+    async fn transaction_confirmation(&self, hash: &TxHash) -> Result<Confirmation>;
+}
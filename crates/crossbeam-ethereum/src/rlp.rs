@@ -0,0 +1,306 @@
+//! Recursive Length Prefix encoding.
+//!
+//! Encoding goes through [`Encodable`]; lists are written with
+//! [`encode_list`] or [`ListEncoder`]. Decoding is zero-copy through [`Rlp`]
+//! and rejects every non-canonical form, so a decoded value re-encodes to the
+//! same bytes.
+
+use crossbeam_core::U256;
+
+use crate::error::{Error, Result};
+use crate::primitives::B256;
+use crate::Address;
+
+const STRING_OFFSET: u8 = 0x80;
+const LIST_OFFSET: u8 = 0xc0;
+
+fn encode_header(len: usize, offset: u8, out: &mut Vec<u8>) {
+    if len < 56 {
+        out.push(offset + len as u8);
+    } else {
+        let len_bytes = len.to_be_bytes();
+        let skip = len_bytes.iter().take_while(|b| **b == 0).count();
+        out.push(offset + 55 + (len_bytes.len() - skip) as u8);
+        out.extend_from_slice(&len_bytes[skip..]);
+    }
+}
+
+/// Encodes a byte string.
+pub fn encode_bytes(bytes: &[u8], out: &mut Vec<u8>) {
+    if bytes.len() == 1 && bytes[0] < STRING_OFFSET {
+        out.push(bytes[0]);
+    } else {
+        encode_header(bytes.len(), STRING_OFFSET, out);
+        out.extend_from_slice(bytes);
+    }
+}
+
+/// Wraps an already encoded list payload in a list header.
+pub fn encode_list_payload(payload: &[u8], out: &mut Vec<u8>) {
+    encode_header(payload.len(), LIST_OFFSET, out);
+    out.extend_from_slice(payload);
+}
+
+/// Encodes `items` as an RLP list.
+pub fn encode_list<T: Encodable>(items: &[T], out: &mut Vec<u8>) {
+    let mut list = ListEncoder::new();
+    for item in items {
+        list.append(item);
+    }
+    list.finish(out);
+}
+
+/// Encodes a single value into a fresh buffer.
+pub fn encode<T: Encodable + ?Sized>(value: &T) -> Vec<u8> {
+    let mut out = Vec::new();
+    value.encode(&mut out);
+    out
+}
+
+/// A value with an RLP encoding.
+pub trait Encodable {
+    fn encode(&self, out: &mut Vec<u8>);
+}
+
+/// Collects heterogeneous list items before the list length is known.
+#[derive(Debug, Default)]
+pub struct ListEncoder {
+    payload: Vec<u8>,
+}
+
+impl ListEncoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn append<T: Encodable + ?Sized>(&mut self, value: &T) -> &mut Self {
+        value.encode(&mut self.payload);
+        self
+    }
+
+    /// Appends bytes that are already RLP encoded.
+    pub fn append_raw(&mut self, encoded: &[u8]) -> &mut Self {
+        self.payload.extend_from_slice(encoded);
+        self
+    }
+
+    pub fn finish(&self, out: &mut Vec<u8>) {
+        encode_list_payload(&self.payload, out);
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.payload.len() + 9);
+        self.finish(&mut out);
+        out
+    }
+}
+
+impl Encodable for [u8] {
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_bytes(self, out);
+    }
+}
+
+impl Encodable for Vec<u8> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_bytes(self, out);
+    }
+}
+
+impl<const N: usize> Encodable for [u8; N] {
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_bytes(self, out);
+    }
+}
+
+impl Encodable for str {
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_bytes(self.as_bytes(), out);
+    }
+}
+
+impl Encodable for bool {
+    fn encode(&self, out: &mut Vec<u8>) {
+        u64::from(*self).encode(out);
+    }
+}
+
+macro_rules! impl_encodable_uint {
+    ($($ty:ty),*) => {$(
+        impl Encodable for $ty {
+            fn encode(&self, out: &mut Vec<u8>) {
+                let bytes = self.to_be_bytes();
+                let skip = bytes.iter().take_while(|b| **b == 0).count();
+                encode_bytes(&bytes[skip..], out);
+            }
+        }
+    )*};
+}
+
+impl_encodable_uint!(u8, u16, u32, u64, u128);
+
+impl Encodable for U256 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        let bytes = self.to_be_bytes::<32>();
+        let skip = bytes.iter().take_while(|b| **b == 0).count();
+        encode_bytes(&bytes[skip..], out);
+    }
+}
+
+impl Encodable for Address {
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_bytes(&self.0, out);
+    }
+}
+
+impl Encodable for B256 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_bytes(&self.0, out);
+    }
+}
+
+impl<T: Encodable + ?Sized> Encodable for &T {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (**self).encode(out);
+    }
+}
+
+fn rlp_error(reason: impl Into<String>) -> Error {
+    Error::Rlp(reason.into())
+}
+
+/// A decoded RLP item borrowing from the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rlp<'a> {
+    /// A byte string.
+    Bytes(&'a [u8]),
+    /// A list, holding its encoded payload.
+    List(&'a [u8]),
+}
+
+impl<'a> Rlp<'a> {
+    /// Decodes exactly one item spanning all of `input`.
+    pub fn new(input: &'a [u8]) -> Result<Self> {
+        let (item, rest) = Self::decode_prefix(input)?;
+        if !rest.is_empty() {
+            return Err(rlp_error("trailing bytes after item"));
+        }
+        Ok(item)
+    }
+
+    /// Decodes the first item of `input` and returns the remaining bytes.
+    pub fn decode_prefix(input: &'a [u8]) -> Result<(Self, &'a [u8])> {
+        let (&first, rest) = input
+            .split_first()
+            .ok_or_else(|| rlp_error("empty input"))?;
+        let (is_list, header_len, payload_len) = match first {
+            0x00..=0x7f => return Ok((Rlp::Bytes(&input[..1]), rest)),
+            0x80..=0xb7 => (false, 1, usize::from(first - STRING_OFFSET)),
+            0xb8..=0xbf => {
+                let len_of_len = usize::from(first - 0xb7);
+                (false, 1 + len_of_len, Self::long_length(rest, len_of_len)?)
+            }
+            0xc0..=0xf7 => (true, 1, usize::from(first - LIST_OFFSET)),
+            0xf8..=0xff => {
+                let len_of_len = usize::from(first - 0xf7);
+                (true, 1 + len_of_len, Self::long_length(rest, len_of_len)?)
+            }
+        };
+        let end = header_len
+            .checked_add(payload_len)
+            .filter(|end| *end <= input.len())
+            .ok_or_else(|| rlp_error("item runs past the end of input"))?;
+        let payload = &input[header_len..end];
+        let item = if is_list {
+            Rlp::List(payload)
+        } else {
+            if payload.len() == 1 && payload[0] < STRING_OFFSET {
+                return Err(rlp_error("single byte below 0x80 must not be prefixed"));
+            }
+            Rlp::Bytes(payload)
+        };
+        Ok((item, &input[end..]))
+    }
+
+    fn long_length(input: &[u8], len_of_len: usize) -> Result<usize> {
+        let bytes = input
+            .get(..len_of_len)
+            .ok_or_else(|| rlp_error("truncated length"))?;
+        if bytes[0] == 0 {
+            return Err(rlp_error("length has leading zeros"));
+        }
+        if len_of_len > std::mem::size_of::<usize>() {
+            return Err(rlp_error("length overflows usize"));
+        }
+        let len = bytes
+            .iter()
+            .fold(0usize, |acc, b| (acc << 8) | usize::from(*b));
+        if len < 56 {
+            return Err(rlp_error("long form used for a short item"));
+        }
+        Ok(len)
+    }
+
+    pub fn is_list(&self) -> bool {
+        matches!(self, Rlp::List(_))
+    }
+
+    /// The byte string payload.
+    pub fn bytes(&self) -> Result<&'a [u8]> {
+        match self {
+            Rlp::Bytes(bytes) => Ok(bytes),
+            Rlp::List(_) => Err(rlp_error("expected bytes, found a list")),
+        }
+    }
+
+    /// Decodes the items of a list.
+    pub fn items(&self) -> Result<Vec<Rlp<'a>>> {
+        let Rlp::List(mut payload) = *self else {
+            return Err(rlp_error("expected a list, found bytes"));
+        };
+        let mut items = Vec::new();
+        while !payload.is_empty() {
+            let (item, rest) = Self::decode_prefix(payload)?;
+            items.push(item);
+            payload = rest;
+        }
+        Ok(items)
+    }
+
+    /// A byte string of exactly `N` bytes.
+    pub fn fixed<const N: usize>(&self) -> Result<[u8; N]> {
+        let bytes = self.bytes()?;
+        bytes
+            .try_into()
+            .map_err(|_| rlp_error(format!("expected {N} bytes, got {}", bytes.len())))
+    }
+
+    fn uint_bytes(&self, max: usize) -> Result<&'a [u8]> {
+        let bytes = self.bytes()?;
+        if bytes.first() == Some(&0) {
+            return Err(rlp_error("integer has leading zeros"));
+        }
+        if bytes.len() > max {
+            return Err(rlp_error(format!("integer wider than {max} bytes")));
+        }
+        Ok(bytes)
+    }
+
+    pub fn as_u64(&self) -> Result<u64> {
+        let bytes = self.uint_bytes(8)?;
+        Ok(bytes.iter().fold(0u64, |acc, b| (acc << 8) | u64::from(*b)))
+    }
+
+    pub fn as_u256(&self) -> Result<U256> {
+        let bytes = self.uint_bytes(32)?;
+        Ok(U256::from_be_slice(bytes))
+    }
+
+    pub fn as_address(&self) -> Result<Address> {
+        self.fixed().map(Address::new)
+    }
+
+    pub fn as_b256(&self) -> Result<B256> {
+        self.fixed().map(B256)
+    }
+}
@@ -0,0 +1,100 @@
+use std::marker::PhantomData;
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::{Client, Transport};
+use crate::error::{Error, Result};
+
+/// Requests queued for one round trip.
+///
+/// Each [`add`](Batch::add) returns a typed handle; after
+/// [`send`](Batch::send), the handles take their results out of the
+/// [`BatchResults`]. Requests fail individually: one error response does
+/// not spoil the others.
+#[derive(Debug)]
+pub struct Batch<'a, T> {
+    client: &'a Client<T>,
+    calls: Vec<(String, Value)>,
+}
+
+/// Identifies one request of a [`Batch`] and the type of its result.
+#[derive(Debug)]
+pub struct BatchCall<R> {
+    index: usize,
+    _result: PhantomData<fn() -> R>,
+}
+
+impl<R> Clone for BatchCall<R> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<R> Copy for BatchCall<R> {}
+
+/// The answers to a sent [`Batch`], in request order.
+#[derive(Debug)]
+pub struct BatchResults {
+    results: Vec<Option<Result<Value>>>,
+}
+
+impl<'a, T: Transport> Batch<'a, T> {
+    pub(crate) fn new(client: &'a Client<T>) -> Self {
+        Self {
+            client,
+            calls: Vec::new(),
+        }
+    }
+
+    /// Queues `method` with `params`.
+    pub fn add<R: DeserializeOwned>(&mut self, method: &str, params: Value) -> BatchCall<R> {
+        self.calls.push((method.to_owned(), params));
+        BatchCall {
+            index: self.calls.len() - 1,
+            _result: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.calls.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    /// Sends the queued requests. Fails only if the batch as a whole got
+    /// no answer.
+    pub async fn send(self) -> Result<BatchResults> {
+        if self.calls.is_empty() {
+            return Ok(BatchResults {
+                results: Vec::new(),
+            });
+        }
+        let expected = self.calls.len();
+        let results = self.client.transport().request_batch(self.calls).await?;
+        if results.len() != expected {
+            return Err(Error::Transport(format!(
+                "batch of {expected} requests got {} answers",
+                results.len()
+            )));
+        }
+        Ok(BatchResults {
+            results: results.into_iter().map(Some).collect(),
+        })
+    }
+}
+
+impl BatchResults {
+    /// Takes the result of `call`. Panics if `call` belongs to another
+    /// batch or was already taken.
+    pub fn take<R: DeserializeOwned>(&mut self, call: BatchCall<R>) -> Result<R> {
+        let result = self
+            .results
+            .get_mut(call.index)
+            .and_then(Option::take)
+            .expect("batch result taken twice or from another batch")?;
+        Ok(serde_json::from_value(result)?)
+    }
+}
@@ -0,0 +1,119 @@
+//! JSON-RPC 2.0 envelopes, for transports that speak the wire format.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+
+/// A request object.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Request {
+    pub jsonrpc: String,
+    pub id: u64,
+    pub method: String,
+    pub params: Value,
+}
+
+impl Request {
+    pub fn new(id: u64, method: &str, params: Value) -> Self {
+        Self {
+            jsonrpc: "2.0".to_owned(),
+            id,
+            method: method.to_owned(),
+            params,
+        }
+    }
+}
+
+/// The error member of a failed response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorObject {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl From<ErrorObject> for Error {
+    fn from(error: ErrorObject) -> Self {
+        Error::Rpc {
+            code: error.code,
+            message: error.message,
+            data: error.data,
+        }
+    }
+}
+
+/// A response object.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Response {
+    pub jsonrpc: String,
+    /// `null` when the server could not read the request id.
+    pub id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorObject>,
+}
+
+impl Response {
+    pub fn success(id: u64, result: Value) -> Self {
+        Self {
+            jsonrpc: "2.0".to_owned(),
+            id: Some(id),
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    pub fn failure(id: u64, error: ErrorObject) -> Self {
+        Self {
+            jsonrpc: "2.0".to_owned(),
+            id: Some(id),
+            result: None,
+            error: Some(error),
+        }
+    }
+
+    /// The result, or the error object as [`Error::Rpc`]. A response with
+    /// neither member carries a `null` result.
+    pub fn into_result(self) -> Result<Value> {
+        match self.error {
+            Some(error) => Err(error.into()),
+            None => Ok(self.result.unwrap_or(Value::Null)),
+        }
+    }
+}
+
+/// Matches the responses of a batch to its requests. Servers may answer a
+/// batch in any order, so responses are paired by id; a request without a
+/// response fails the whole batch.
+pub fn match_batch(requests: &[Request], responses: Vec<Response>) -> Result<Vec<Result<Value>>> {
+    let mut by_id: Vec<Option<Response>> = vec![None; requests.len()];
+    for response in responses {
+        let slot = response
+            .id
+            .and_then(|id| requests.iter().position(|r| r.id == id));
+        match slot {
+            Some(i) => by_id[i] = Some(response),
+            None => {
+                if let Some(error) = response.error {
+                    return Err(error.into());
+                }
+                return Err(Error::Transport(format!(
+                    "response with unknown id {:?}",
+                    response.id
+                )));
+            }
+        }
+    }
+    by_id
+        .into_iter()
+        .zip(requests)
+        .map(|(response, request)| {
+            response
+                .map(Response::into_result)
+                .ok_or_else(|| Error::Transport(format!("no response to request {}", request.id)))
+        })
+        .collect()
+}
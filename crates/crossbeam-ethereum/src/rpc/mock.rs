@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::jsonrpc::ErrorObject;
+use super::Transport;
+use crate::error::{Error, Result};
+
+type Handler = Box<dyn Fn(&Value) -> Result<Value> + Send + Sync>;
+
+/// One recorded request and the node's answer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fixture {
+    pub method: String,
+    /// `null` in a hand-written fixture matches any params.
+    #[serde(default)]
+    pub params: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorObject>,
+}
+
+impl Fixture {
+    fn matches(&self, method: &str, params: &Value) -> bool {
+        self.method == method && (self.params.is_null() || same_params(&self.params, params))
+    }
+
+    fn answer(&self) -> Result<Value> {
+        match &self.error {
+            Some(error) => Err(error.clone().into()),
+            None => Ok(self.result.clone().unwrap_or(Value::Null)),
+        }
+    }
+}
+
+/// Compares params, ignoring the case of hex strings, so hand-written
+/// lowercase addresses match the checksummed ones the client sends.
+fn same_params(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::String(a), Value::String(b)) if a.starts_with("0x") => a.eq_ignore_ascii_case(b),
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| same_params(a, b))
+        }
+        (Value::Object(a), Value::Object(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .all(|(key, a)| b.get(key).is_some_and(|b| same_params(a, b)))
+        }
+        _ => a == b,
+    }
+}
+
+/// An in-process node for tests.
+///
+/// Requests are answered from replayed [`Fixture`]s first: each fixture
+/// answers one request, the first unused one whose method and params
+/// match. Remaining requests go to per-method handler closures; methods
+/// without one fail with the JSON-RPC "method not found" error, as a real
+/// node would. Every request is recorded for later assertions.
+#[derive(Default)]
+pub struct MockTransport {
+    handlers: HashMap<String, Handler>,
+    fixtures: Mutex<Vec<(Fixture, bool)>>,
+    requests: Mutex<Vec<(String, Value)>>,
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answers `method` with `handler`, called with the request params.
+    pub fn on(
+        mut self,
+        method: &str,
+        handler: impl Fn(&Value) -> Result<Value> + Send + Sync + 'static,
+    ) -> Self {
+        self.handlers.insert(method.to_owned(), Box::new(handler));
+        self
+    }
+
+    /// Answers `method` with the same result every time.
+    pub fn on_result(self, method: &str, result: Value) -> Self {
+        self.on(method, move |_| Ok(result.clone()))
+    }
+
+    /// Queues `fixtures` for replay.
+    pub fn replay(self, fixtures: impl IntoIterator<Item = Fixture>) -> Self {
+        self.fixtures
+            .lock()
+            .expect("mock lock poisoned")
+            .extend(fixtures.into_iter().map(|f| (f, false)));
+        self
+    }
+
+    /// Queues the fixtures of a JSON array, as written by
+    /// [`RecordingTransport::to_json`].
+    pub fn replay_json(self, json: &str) -> Result<Self> {
+        let fixtures: Vec<Fixture> = serde_json::from_str(json)?;
+        Ok(self.replay(fixtures))
+    }
+
+    /// The `(method, params)` pairs received so far, in order.
+    pub fn requests(&self) -> Vec<(String, Value)> {
+        self.requests.lock().expect("mock lock poisoned").clone()
+    }
+
+    /// Replayed fixtures no request has used yet.
+    pub fn unused_fixtures(&self) -> Vec<Fixture> {
+        self.fixtures
+            .lock()
+            .expect("mock lock poisoned")
+            .iter()
+            .filter(|(_, used)| !used)
+            .map(|(fixture, _)| fixture.clone())
+            .collect()
+    }
+}
+
+impl std::fmt::Debug for MockTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut methods: Vec<_> = self.handlers.keys().collect();
+        methods.sort();
+        f.debug_struct("MockTransport")
+            .field("methods", &methods)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl Transport for MockTransport {
+    async fn request(&self, method: &str, params: Value) -> Result<Value> {
+        self.requests
+            .lock()
+            .expect("mock lock poisoned")
+            .push((method.to_owned(), params.clone()));
+        let replayed = {
+            let mut fixtures = self.fixtures.lock().expect("mock lock poisoned");
+            fixtures
+                .iter_mut()
+                .find(|(fixture, used)| !used && fixture.matches(method, &params))
+                .map(|(fixture, used)| {
+                    *used = true;
+                    fixture.answer()
+                })
+        };
+        if let Some(answer) = replayed {
+            return answer;
+        }
+        match self.handlers.get(method) {
+            Some(handler) => handler(&params),
+            None => Err(Error::Rpc {
+                code: -32601,
+                message: format!("the method {method} does not exist/is not available"),
+                data: None,
+            }),
+        }
+    }
+}
+
+/// Wraps a transport and records every exchange as a [`Fixture`], to
+/// capture a session against a live node for offline replay.
+#[derive(Debug, Default)]
+pub struct RecordingTransport<T> {
+    inner: T,
+    fixtures: Mutex<Vec<Fixture>>,
+}
+
+impl<T: Transport> RecordingTransport<T> {
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            fixtures: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn fixtures(&self) -> Vec<Fixture> {
+        self.fixtures
+            .lock()
+            .expect("recorder lock poisoned")
+            .clone()
+    }
+
+    /// The recorded fixtures as a pretty-printed JSON array.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.fixtures()).expect("fixtures serialize")
+    }
+}
+
+#[async_trait]
+impl<T: Transport> Transport for RecordingTransport<T> {
+    async fn request(&self, method: &str, params: Value) -> Result<Value> {
+        let answer = self.inner.request(method, params.clone()).await;
+        let (result, error) = match &answer {
+            Ok(result) => (Some(result.clone()), None),
+            Err(Error::Rpc {
+                code,
+                message,
+                data,
+            }) => (
+                None,
+                Some(ErrorObject {
+                    code: *code,
+                    message: message.clone(),
+                    data: data.clone(),
+                }),
+            ),
+            // No answer from the node; nothing to replay.
+            Err(_) => return answer,
+        };
+        self.fixtures
+            .lock()
+            .expect("recorder lock poisoned")
+            .push(Fixture {
+                method: method.to_owned(),
+                params,
+                result,
+                error,
+            });
+        answer
+    }
+}
@@ -0,0 +1,430 @@
+//! Ethereum JSON-RPC access.
+//!
+//! [`Client`] speaks the `eth_` namespace over any [`Transport`]. The SDK
+//! ships no network stack: applications implement [`Transport`] over the
+//! HTTP or WebSocket client they already use, with [`jsonrpc`] providing
+//! the envelopes. Tests use [`MockTransport`], which can replay fixtures
+//! captured from a live node with [`RecordingTransport`].
+
+mod batch;
+pub mod jsonrpc;
+mod mock;
+pub(crate) mod serde_hex;
+mod types;
+
+use std::fmt;
+
+use async_trait::async_trait;
+use crossbeam_abi::ContractCall;
+use crossbeam_core::{Confirmation, U256};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::{json, Value};
+
+use crate::error::{Error, Result};
+use crate::header::Header;
+use crate::logs::{Filter, Log};
+use crate::primitives::{TxHash, B256};
+use crate::proof::AccountProof;
+use crate::provider::Provider;
+use crate::Address;
+
+pub use batch::{Batch, BatchCall, BatchResults};
+pub use mock::{Fixture, MockTransport, RecordingTransport};
+pub use types::{CallRequest, FeeHistory, TransactionReceipt};
+
+/// Carries JSON-RPC requests to a node.
+#[async_trait]
+pub trait Transport: Send + Sync {
+    /// Sends one request and returns its `result`. A JSON-RPC error object
+    /// in the response is reported as [`Error::Rpc`].
+    async fn request(&self, method: &str, params: Value) -> Result<Value>;
+
+    /// Sends several requests, returning one result per request in order.
+    ///
+    /// The default sends them one by one; transports that can put a
+    /// JSON-RPC batch on the wire should override it.
+    async fn request_batch(&self, calls: Vec<(String, Value)>) -> Result<Vec<Result<Value>>> {
+        let mut results = Vec::with_capacity(calls.len());
+        for (method, params) in calls {
+            results.push(self.request(&method, params).await);
+        }
+        Ok(results)
+    }
+}
+
+#[async_trait]
+impl<T: Transport + ?Sized> Transport for &T {
+    async fn request(&self, method: &str, params: Value) -> Result<Value> {
+        (**self).request(method, params).await
+    }
+
+    async fn request_batch(&self, calls: Vec<(String, Value)>) -> Result<Vec<Result<Value>>> {
+        (**self).request_batch(calls).await
+    }
+}
+
+#[async_trait]
+impl<T: Transport + ?Sized> Transport for std::sync::Arc<T> {
+    async fn request(&self, method: &str, params: Value) -> Result<Value> {
+        (**self).request(method, params).await
+    }
+
+    async fn request_batch(&self, calls: Vec<(String, Value)>) -> Result<Vec<Result<Value>>> {
+        (**self).request_batch(calls).await
+    }
+}
+
+/// A block selector: a height or one of the named tags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlockNumber {
+    Number(u64),
+    Earliest,
+    Latest,
+    /// The latest block the consensus layer considers safe from reorgs.
+    Safe,
+    /// The latest finalized block.
+    Finalized,
+    Pending,
+}
+
+impl From<u64> for BlockNumber {
+    fn from(n: u64) -> Self {
+        BlockNumber::Number(n)
+    }
+}
+
+impl fmt::Display for BlockNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlockNumber::Number(n) => f.write_str(&serde_hex::format_u64(*n)),
+            BlockNumber::Earliest => f.write_str("earliest"),
+            BlockNumber::Latest => f.write_str("latest"),
+            BlockNumber::Safe => f.write_str("safe"),
+            BlockNumber::Finalized => f.write_str("finalized"),
+            BlockNumber::Pending => f.write_str("pending"),
+        }
+    }
+}
+
+impl Serialize for BlockNumber {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// An Ethereum JSON-RPC client.
+#[derive(Debug, Clone)]
+pub struct Client<T> {
+    transport: T,
+}
+
+impl<T: Transport> Client<T> {
+    pub fn new(transport: T) -> Self {
+        Self { transport }
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Calls `method` and deserializes its result.
+    pub async fn request<R: DeserializeOwned>(&self, method: &str, params: Value) -> Result<R> {
+        let result = self.transport.request(method, params).await?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    /// Starts a batch of requests sent in one round trip.
+    pub fn batch(&self) -> Batch<'_, T> {
+        Batch::new(self)
+    }
+
+    async fn quantity(&self, method: &str, params: Value) -> Result<u64> {
+        let n: String = self.request(method, params).await?;
+        serde_hex::parse_u64(&n).map_err(Error::Provider)
+    }
+
+    /// `eth_chainId`.
+    pub async fn chain_id(&self) -> Result<u64> {
+        self.quantity("eth_chainId", json!([])).await
+    }
+
+    /// `eth_blockNumber`.
+    pub async fn block_number(&self) -> Result<u64> {
+        self.quantity("eth_blockNumber", json!([])).await
+    }
+
+    /// The height `block` currently resolves to. Named tags other than
+    /// `latest` are resolved through `eth_getBlockByNumber`.
+    pub async fn resolve_block_number(&self, block: BlockNumber) -> Result<u64> {
+        #[derive(Deserialize)]
+        struct Header {
+            #[serde(with = "serde_hex::quantity")]
+            number: u64,
+        }
+
+        match block {
+            BlockNumber::Number(n) => Ok(n),
+            BlockNumber::Earliest => Ok(0),
+            BlockNumber::Latest => self.block_number().await,
+            _ => {
+                let header: Option<Header> = self
+                    .request("eth_getBlockByNumber", json!([block, false]))
+                    .await?;
+                header
+                    .map(|h| h.number)
+                    .ok_or_else(|| Error::Provider(format!("node has no `{block}` block")))
+            }
+        }
+    }
+
+    /// `eth_getBalance`, in wei.
+    pub async fn get_balance(&self, address: &Address, block: BlockNumber) -> Result<U256> {
+        self.request("eth_getBalance", json!([address, block]))
+            .await
+    }
+
+    /// `eth_getTransactionCount`: the nonce of `address` at `block`. Use
+    /// [`BlockNumber::Pending`] to count transactions in the mempool.
+    pub async fn get_transaction_count(
+        &self,
+        address: &Address,
+        block: BlockNumber,
+    ) -> Result<u64> {
+        self.quantity("eth_getTransactionCount", json!([address, block]))
+            .await
+    }
+
+    /// `eth_getCode`.
+    pub async fn get_code(&self, address: &Address, block: BlockNumber) -> Result<Vec<u8>> {
+        let code: String = self.request("eth_getCode", json!([address, block])).await?;
+        parse_data(&code)
+    }
+
+    /// `eth_gasPrice`, in wei.
+    pub async fn gas_price(&self) -> Result<U256> {
+        self.request("eth_gasPrice", json!([])).await
+    }
+
+    /// `eth_maxPriorityFeePerGas`: the node's suggested EIP-1559 tip.
+    pub async fn max_priority_fee_per_gas(&self) -> Result<U256> {
+        self.request("eth_maxPriorityFeePerGas", json!([])).await
+    }
+
+    /// `eth_feeHistory` over the `block_count` blocks ending at `newest`,
+    /// with priority fees sampled at `reward_percentiles` (0 to 100,
+    /// ascending).
+    pub async fn fee_history(
+        &self,
+        block_count: u64,
+        newest: BlockNumber,
+        reward_percentiles: &[f64],
+    ) -> Result<FeeHistory> {
+        self.request(
+            "eth_feeHistory",
+            json!([
+                serde_hex::format_u64(block_count),
+                newest,
+                reward_percentiles
+            ]),
+        )
+        .await
+    }
+
+    /// `eth_call`: executes `call` against the state at `block` and returns
+    /// the output. A revert is reported as [`Error::Reverted`].
+    pub async fn call(&self, call: &CallRequest, block: BlockNumber) -> Result<Vec<u8>> {
+        let output: String = self
+            .request("eth_call", json!([call, block]))
+            .await
+            .map_err(revert_error)?;
+        parse_data(&output)
+    }
+
+    /// Calls the contract function `C` on `to` and decodes its return value.
+    pub async fn call_contract<C: ContractCall>(
+        &self,
+        to: Address,
+        call: &C,
+        block: BlockNumber,
+    ) -> Result<C::Return> {
+        let output = self
+            .call(&CallRequest::new(to, call.encode()?), block)
+            .await?;
+        Ok(C::decode_return(&output)?)
+    }
+
+    /// `eth_estimateGas`. A revert is reported as [`Error::Reverted`].
+    pub async fn estimate_gas(&self, call: &CallRequest) -> Result<u64> {
+        self.quantity("eth_estimateGas", json!([call]))
+            .await
+            .map_err(revert_error)
+    }
+
+    /// `eth_sendRawTransaction`: broadcasts a signed, encoded transaction.
+    pub async fn send_raw_transaction(&self, raw: &[u8]) -> Result<TxHash> {
+        self.request(
+            "eth_sendRawTransaction",
+            json!([format!("0x{}", hex::encode(raw))]),
+        )
+        .await
+    }
+
+    /// `eth_getTransactionReceipt`; `None` until the transaction is mined.
+    pub async fn get_transaction_receipt(
+        &self,
+        hash: &TxHash,
+    ) -> Result<Option<TransactionReceipt>> {
+        self.request("eth_getTransactionReceipt", json!([hash]))
+            .await
+    }
+
+    /// The header of `block`, or `None` if the node does not have it. Fails
+    /// if the header does not hash to the block hash the node reports.
+    pub async fn get_header(&self, block: BlockNumber) -> Result<Option<Header>> {
+        self.header("eth_getBlockByNumber", json!([block, false]))
+            .await
+    }
+
+    /// The header of the block with hash `hash`, or `None` if the node does
+    /// not have it.
+    pub async fn get_header_by_hash(&self, hash: &B256) -> Result<Option<Header>> {
+        let header = self
+            .header("eth_getBlockByHash", json!([hash, false]))
+            .await?;
+        match header {
+            Some(header) if header.hash()? != *hash => Err(Error::InvalidHeader(format!(
+                "node returned block {} for {hash}",
+                header.number
+            ))),
+            header => Ok(header),
+        }
+    }
+
+    async fn header(&self, method: &str, params: Value) -> Result<Option<Header>> {
+        #[derive(Deserialize)]
+        struct Block {
+            hash: B256,
+            #[serde(flatten)]
+            header: Header,
+        }
+
+        let Some(block) = self.request::<Option<Block>>(method, params).await? else {
+            return Ok(None);
+        };
+        let hash = block.header.hash()?;
+        if hash != block.hash {
+            return Err(Error::InvalidHeader(format!(
+                "block {} hashes to {hash}, not the reported {}",
+                block.header.number, block.hash
+            )));
+        }
+        Ok(Some(block.header))
+    }
+
+    /// `eth_getBlockReceipts`: every receipt of `block`, in transaction
+    /// order; `None` if the node does not have the block.
+    pub async fn get_block_receipts(
+        &self,
+        block: BlockNumber,
+    ) -> Result<Option<Vec<TransactionReceipt>>> {
+        self.request("eth_getBlockReceipts", json!([block])).await
+    }
+
+    /// `eth_getProof`: the account at `address` and its storage `keys`,
+    /// with proofs against the state root of `block`. See
+    /// [`AccountProof::verify`].
+    pub async fn get_proof(
+        &self,
+        address: &Address,
+        keys: &[B256],
+        block: BlockNumber,
+    ) -> Result<AccountProof> {
+        self.request("eth_getProof", json!([address, keys, block]))
+            .await
+    }
+
+    /// `eth_getLogs`.
+    pub async fn get_logs(&self, filter: &Filter) -> Result<Vec<Log>> {
+        self.request("eth_getLogs", json!([filter])).await
+    }
+}
+
+#[async_trait]
+impl<T: Transport> Provider for Client<T> {
+    async fn transaction_count(&self, address: &Address) -> Result<u64> {
+        self.get_transaction_count(address, BlockNumber::Pending)
+            .await
+    }
+
+    async fn gas_price(&self) -> Result<U256> {
+        Client::gas_price(self).await
+    }
+
+    async fn fee_history(
+        &self,
+        block_count: u64,
+        reward_percentiles: &[f64],
+    ) -> Result<FeeHistory> {
+        Client::fee_history(self, block_count, BlockNumber::Latest, reward_percentiles).await
+    }
+
+    async fn send_raw_transaction(&self, raw: &[u8]) -> Result<TxHash> {
+        Client::send_raw_transaction(self, raw).await
+    }
+
+    /// Mined transactions are reported as finalized once the node's
+    /// `finalized` block has reached them.
+    async fn transaction_confirmation(&self, hash: &TxHash) -> Result<Confirmation> {
+        let Some(receipt) = self.get_transaction_receipt(hash).await? else {
+            let known: Option<Value> = self
+                .request("eth_getTransactionByHash", json!([hash]))
+                .await?;
+            return Ok(match known {
+                Some(_) => Confirmation::Pending,
+                None => Confirmation::NotFound,
+            });
+        };
+        let height = receipt.block_number;
+        if !receipt.succeeded() {
+            return Ok(Confirmation::Failed {
+                height: Some(height),
+                reason: "execution reverted".to_owned(),
+            });
+        }
+        let finalized = self.resolve_block_number(BlockNumber::Finalized).await?;
+        Ok(if height <= finalized {
+            Confirmation::Finalized { height }
+        } else {
+            Confirmation::Confirmed { height }
+        })
+    }
+}
+
+fn parse_data(s: &str) -> Result<Vec<u8>> {
+    let digits = s
+        .strip_prefix("0x")
+        .ok_or_else(|| Error::Provider(format!("data `{s}` lacks 0x prefix")))?;
+    Ok(hex::decode(digits)?)
+}
+
+/// Turns a node's revert error into [`Error::Reverted`]. Geth reports
+/// reverts with code 3 and the payload as `data`; other clients vary in the
+/// code, so any error whose data is hex counts.
+fn revert_error(error: Error) -> Error {
+    match error {
+        Error::Rpc {
+            code,
+            message,
+            data: Some(Value::String(data)),
+        } => match data.strip_prefix("0x").map(hex::decode) {
+            Some(Ok(data)) => Error::Reverted { message, data },
+            _ => Error::Rpc {
+                code,
+                message,
+                data: Some(Value::String(data)),
+            },
+        },
+        other => other,
+    }
+}
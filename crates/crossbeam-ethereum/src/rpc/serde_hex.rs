@@ -0,0 +1,140 @@
+//! Serde helpers for the hex encodings used by the Ethereum JSON-RPC API.
+
+use serde::{de, Deserialize, Deserializer, Serializer};
+
+/// Parses a `0x`-prefixed quantity without leading zeros.
+pub fn parse_u64(s: &str) -> Result<u64, String> {
+    let digits = s
+        .strip_prefix("0x")
+        .ok_or_else(|| format!("quantity `{s}` lacks 0x prefix"))?;
+    if digits.is_empty() || (digits.len() > 1 && digits.starts_with('0')) {
+        return Err(format!("`{s}` is not a canonical quantity"));
+    }
+    u64::from_str_radix(digits, 16).map_err(|e| format!("quantity `{s}`: {e}"))
+}
+
+pub fn format_u64(n: u64) -> String {
+    format!("{n:#x}")
+}
+
+/// `u64` as a hex quantity.
+pub mod quantity {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(n: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format_u64(*n))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        parse_u64(&s).map_err(de::Error::custom)
+    }
+}
+
+/// `Option<u64>` as a hex quantity or `null`.
+pub mod option_quantity {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(n: &Option<u64>, serializer: S) -> Result<S::Ok, S::Error> {
+        match n {
+            Some(n) => serializer.serialize_str(&format_u64(*n)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<u64>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|s| parse_u64(&s).map_err(de::Error::custom))
+            .transpose()
+    }
+}
+
+/// Byte strings as `0x`-prefixed hex.
+pub mod bytes {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("0x{}", hex::encode(bytes)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        let digits = s
+            .strip_prefix("0x")
+            .ok_or_else(|| de::Error::custom(format!("data `{s}` lacks 0x prefix")))?;
+        hex::decode(digits).map_err(de::Error::custom)
+    }
+}
+
+/// A list of byte strings, such as the nodes of a Merkle proof.
+pub mod bytes_list {
+    use super::*;
+    use serde::ser::SerializeSeq;
+
+    pub fn serialize<S: Serializer>(list: &[Vec<u8>], serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(list.len()))?;
+        for bytes in list {
+            seq.serialize_element(&format!("0x{}", hex::encode(bytes)))?;
+        }
+        seq.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Vec<u8>>, D::Error> {
+        #[derive(Deserialize)]
+        struct Bytes(#[serde(with = "super::bytes")] Vec<u8>);
+
+        let list = Vec::<Bytes>::deserialize(deserializer)?;
+        Ok(list.into_iter().map(|b| b.0).collect())
+    }
+}
+
+/// A 32-byte word that may arrive as a shorter quantity, as the storage
+/// keys echoed by `eth_getProof` do.
+pub mod word {
+    use super::*;
+    use crate::primitives::B256;
+
+    pub fn serialize<S: Serializer>(word: &B256, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&word.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<B256, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        let digits = s
+            .strip_prefix("0x")
+            .ok_or_else(|| de::Error::custom(format!("word `{s}` lacks 0x prefix")))?;
+        if digits.is_empty() || digits.len() > 64 {
+            return Err(de::Error::custom(format!("`{s}` is not a 32-byte word")));
+        }
+        let bytes = hex::decode(format!("{digits:0>64}")).map_err(de::Error::custom)?;
+        let mut word = [0; 32];
+        word.copy_from_slice(&bytes);
+        Ok(B256(word))
+    }
+}
+
+/// Fixed-size byte arrays, such as bloom filters and PoW nonces.
+pub mod fixed {
+    use super::*;
+
+    pub fn serialize<S: Serializer, const N: usize>(
+        bytes: &[u8; N],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        super::bytes::serialize(bytes, serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, const N: usize>(
+        deserializer: D,
+    ) -> Result<[u8; N], D::Error> {
+        let bytes = super::bytes::deserialize(deserializer)?;
+        let len = bytes.len();
+        bytes
+            .try_into()
+            .map_err(|_| de::Error::custom(format!("expected {N} bytes, got {len}")))
+    }
+}
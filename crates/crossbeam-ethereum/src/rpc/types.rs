@@ -0,0 +1,127 @@
+//! Request and response objects of the `eth_` namespace.
+
+use crossbeam_core::U256;
+use serde::{Deserialize, Serialize};
+
+use super::serde_hex;
+use crate::logs::Log;
+use crate::primitives::{TxHash, B256};
+use crate::Address;
+
+/// Parameters of `eth_call` and `eth_estimateGas`. Unset fields are left
+/// for the node to fill in.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<Address>,
+    /// `None` to simulate a contract creation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<Address>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "serde_hex::option_quantity"
+    )]
+    pub gas: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_price: Option<U256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_fee_per_gas: Option<U256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_priority_fee_per_gas: Option<U256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<U256>,
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        with = "serde_hex::bytes"
+    )]
+    pub input: Vec<u8>,
+}
+
+impl CallRequest {
+    /// A call of `to` with calldata `input`.
+    pub fn new(to: Address, input: Vec<u8>) -> Self {
+        Self {
+            to: Some(to),
+            input,
+            ..Self::default()
+        }
+    }
+
+    /// Simulates the call as sent by `from`.
+    pub fn sender(mut self, from: Address) -> Self {
+        self.from = Some(from);
+        self
+    }
+
+    pub fn value(mut self, value: U256) -> Self {
+        self.value = Some(value);
+        self
+    }
+
+    pub fn gas(mut self, gas: u64) -> Self {
+        self.gas = Some(gas);
+        self
+    }
+}
+
+/// The outcome of an included transaction, from `eth_getTransactionReceipt`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionReceipt {
+    pub transaction_hash: TxHash,
+    #[serde(with = "serde_hex::quantity")]
+    pub transaction_index: u64,
+    pub block_hash: B256,
+    #[serde(with = "serde_hex::quantity")]
+    pub block_number: u64,
+    pub from: Address,
+    /// `None` for contract creations.
+    pub to: Option<Address>,
+    #[serde(default)]
+    pub contract_address: Option<Address>,
+    #[serde(with = "serde_hex::quantity")]
+    pub gas_used: u64,
+    #[serde(with = "serde_hex::quantity")]
+    pub cumulative_gas_used: u64,
+    #[serde(default)]
+    pub effective_gas_price: Option<U256>,
+    /// `1` for success and `0` for failure; absent before Byzantium.
+    #[serde(default, with = "serde_hex::option_quantity")]
+    pub status: Option<u64>,
+    /// The EIP-2718 transaction type; absent from pre-Berlin nodes.
+    #[serde(default, rename = "type", with = "serde_hex::option_quantity")]
+    pub transaction_type: Option<u64>,
+    pub logs: Vec<Log>,
+}
+
+impl TransactionReceipt {
+    /// Whether execution succeeded. Pre-Byzantium receipts carry no status
+    /// and count as successful.
+    pub fn succeeded(&self) -> bool {
+        self.status != Some(0)
+    }
+}
+
+/// Fee market history, from `eth_feeHistory`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeHistory {
+    #[serde(with = "serde_hex::quantity")]
+    pub oldest_block: u64,
+    /// One entry per block, plus the base fee of the block after the newest.
+    pub base_fee_per_gas: Vec<U256>,
+    pub gas_used_ratio: Vec<f64>,
+    /// Per block, the priority fee at each requested percentile.
+    #[serde(default)]
+    pub reward: Vec<Vec<U256>>,
+}
+
+impl FeeHistory {
+    /// The base fee of the block after the newest one covered.
+    pub fn next_base_fee(&self) -> Option<U256> {
+        self.base_fee_per_gas.last().copied()
+    }
+}
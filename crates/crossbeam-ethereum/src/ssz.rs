@@ -0,0 +1,113 @@
+//! SSZ merkleization, as the consensus layer hashes its objects.
+//!
+//! Only hashing is needed to follow the beacon chain: a light client is
+//! handed objects as JSON, computes their `hash_tree_root`, and checks
+//! Merkle branches from those roots into beacon state. Values are split
+//! into 32-byte chunks, padded with zero chunks to a power of two and
+//! hashed pairwise with SHA-256.
+
+use crossbeam_core::U256;
+use sha2::{Digest, Sha256};
+
+use crate::primitives::B256;
+
+/// SHA-256 of two concatenated chunks.
+pub fn hash_pair(left: &B256, right: &B256) -> B256 {
+    let mut hasher = Sha256::new();
+    hasher.update(left.0);
+    hasher.update(right.0);
+    B256(hasher.finalize().into())
+}
+
+/// The root of a tree of `depth` levels of zero chunks.
+fn zero_root(depth: usize) -> B256 {
+    (0..depth).fold(B256::ZERO, |node, _| hash_pair(&node, &node))
+}
+
+/// The root of `chunks`, padded with zero chunks to `limit` rounded up to a
+/// power of two.
+///
+/// # Panics
+///
+/// If there are more chunks than `limit`.
+pub fn merkleize(chunks: &[B256], limit: usize) -> B256 {
+    assert!(
+        chunks.len() <= limit,
+        "{} chunks over a limit of {limit}",
+        chunks.len()
+    );
+    let depth = limit.next_power_of_two().trailing_zeros() as usize;
+    let mut layer = chunks.to_vec();
+    for level in 0..depth {
+        if layer.len() % 2 == 1 {
+            layer.push(zero_root(level));
+        }
+        layer = layer
+            .chunks(2)
+            .map(|pair| hash_pair(&pair[0], &pair[1]))
+            .collect();
+    }
+    layer.pop().unwrap_or_else(|| zero_root(depth))
+}
+
+/// Mixes the length of a list into the root of its contents.
+pub fn mix_in_length(root: &B256, len: usize) -> B256 {
+    hash_pair(root, &uint64(len as u64))
+}
+
+/// `bytes` split into zero-padded chunks.
+pub fn pack(bytes: &[u8]) -> Vec<B256> {
+    bytes
+        .chunks(32)
+        .map(|chunk| {
+            let mut word = [0; 32];
+            word[..chunk.len()].copy_from_slice(chunk);
+            B256(word)
+        })
+        .collect()
+}
+
+/// The root of a fixed-length byte vector.
+pub fn byte_vector(bytes: &[u8]) -> B256 {
+    merkleize(&pack(bytes), bytes.len().div_ceil(32))
+}
+
+/// The root of a byte list of at most `max_len` bytes.
+pub fn byte_list(bytes: &[u8], max_len: usize) -> B256 {
+    mix_in_length(&merkleize(&pack(bytes), max_len.div_ceil(32)), bytes.len())
+}
+
+pub fn uint64(n: u64) -> B256 {
+    let mut word = [0; 32];
+    word[..8].copy_from_slice(&n.to_le_bytes());
+    B256(word)
+}
+
+pub fn uint256(n: &U256) -> B256 {
+    B256(n.to_le_bytes())
+}
+
+/// The root of a container whose fields have the given roots.
+pub fn container(fields: &[B256]) -> B256 {
+    merkleize(fields, fields.len())
+}
+
+/// Checks that `leaf` sits at generalized index `gindex` of the tree with
+/// `root`, given the sibling of each node on the way up.
+pub fn verify_branch(leaf: &B256, branch: &[B256], gindex: u64, root: &B256) -> bool {
+    let depth = gindex.ilog2() as usize;
+    if branch.len() != depth {
+        return false;
+    }
+    let node = branch
+        .iter()
+        .enumerate()
+        .fold(*leaf, |node, (level, sibling)| {
+            if gindex >> level & 1 == 1 {
+                hash_pair(sibling, &node)
+            } else {
+                hash_pair(&node, sibling)
+            }
+        });
+    node == *root
+}
@@ -0,0 +1,522 @@
+//! Legacy, EIP-2930 and EIP-1559 transactions.
+//!
+//! Building, hashing and signing are fully offline. A [`SignedTransaction`]
+//! encodes to the EIP-2718 wire format accepted by `eth_sendRawTransaction`
+//! and can be decoded back, so an air-gapped signer can check exactly what it
+//! is asked to sign.
+
+use crossbeam_core::{Signer, SigningPayload, U256};
+use k256::ecdsa::{RecoveryId, VerifyingKey};
+
+use crate::error::{Error, Result};
+use crate::primitives::{keccak256, TxHash, B256};
+use crate::rlp::{self, Encodable, ListEncoder, Rlp};
+use crate::Address;
+
+/// Gas used by a plain value transfer.
+pub const TRANSFER_GAS: u64 = 21_000;
+
+const EIP2930_TYPE: u8 = 0x01;
+const EIP1559_TYPE: u8 = 0x02;
+
+fn invalid(reason: impl Into<String>) -> Error {
+    Error::InvalidTransaction(reason.into())
+}
+
+/// An address and the storage slots a transaction declares it will touch.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct AccessListItem {
+    pub address: Address,
+    pub storage_keys: Vec<B256>,
+}
+
+/// EIP-2930 access list.
+pub type AccessList = Vec<AccessListItem>;
+
+impl Encodable for AccessListItem {
+    fn encode(&self, out: &mut Vec<u8>) {
+        let mut keys = Vec::new();
+        rlp::encode_list(&self.storage_keys, &mut keys);
+        ListEncoder::new()
+            .append(&self.address)
+            .append_raw(&keys)
+            .finish(out);
+    }
+}
+
+fn encode_to(to: &Option<Address>, list: &mut ListEncoder) {
+    match to {
+        Some(address) => list.append(address),
+        // Contract creation.
+        None => list.append(&[] as &[u8]),
+    };
+}
+
+fn decode_to(item: &Rlp<'_>) -> Result<Option<Address>> {
+    let bytes = item.bytes()?;
+    if bytes.is_empty() {
+        Ok(None)
+    } else {
+        item.as_address().map(Some)
+    }
+}
+
+fn decode_access_list(item: &Rlp<'_>) -> Result<AccessList> {
+    item.items()?
+        .iter()
+        .map(|entry| {
+            let fields = entry.items()?;
+            let [address, keys] = fields.as_slice() else {
+                return Err(invalid("access list entries have two fields"));
+            };
+            Ok(AccessListItem {
+                address: address.as_address()?,
+                storage_keys: keys
+                    .items()?
+                    .iter()
+                    .map(Rlp::as_b256)
+                    .collect::<Result<_>>()?,
+            })
+        })
+        .collect()
+}
+
+/// A pre-EIP-2718 transaction, replay-protected by EIP-155 when `chain_id`
+/// is set.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LegacyTransaction {
+    pub chain_id: Option<u64>,
+    pub nonce: u64,
+    pub gas_price: U256,
+    pub gas_limit: u64,
+    /// `None` deploys a contract.
+    pub to: Option<Address>,
+    pub value: U256,
+    pub input: Vec<u8>,
+}
+
+impl LegacyTransaction {
+    fn encode_fields(&self, list: &mut ListEncoder) {
+        list.append(&self.nonce)
+            .append(&self.gas_price)
+            .append(&self.gas_limit);
+        encode_to(&self.to, list);
+        list.append(&self.value).append(&self.input);
+    }
+}
+
+/// An EIP-2930 access-list transaction (type 1).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Eip2930Transaction {
+    pub chain_id: u64,
+    pub nonce: u64,
+    pub gas_price: U256,
+    pub gas_limit: u64,
+    pub to: Option<Address>,
+    pub value: U256,
+    pub input: Vec<u8>,
+    pub access_list: AccessList,
+}
+
+impl Eip2930Transaction {
+    fn encode_fields(&self, list: &mut ListEncoder) {
+        list.append(&self.chain_id)
+            .append(&self.nonce)
+            .append(&self.gas_price)
+            .append(&self.gas_limit);
+        encode_to(&self.to, list);
+        list.append(&self.value).append(&self.input);
+        let mut access_list = Vec::new();
+        rlp::encode_list(&self.access_list, &mut access_list);
+        list.append_raw(&access_list);
+    }
+}
+
+/// An EIP-1559 dynamic-fee transaction (type 2).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Eip1559Transaction {
+    pub chain_id: u64,
+    pub nonce: u64,
+    pub max_priority_fee_per_gas: U256,
+    pub max_fee_per_gas: U256,
+    pub gas_limit: u64,
+    pub to: Option<Address>,
+    pub value: U256,
+    pub input: Vec<u8>,
+    pub access_list: AccessList,
+}
+
+impl Eip1559Transaction {
+    fn encode_fields(&self, list: &mut ListEncoder) {
+        list.append(&self.chain_id)
+            .append(&self.nonce)
+            .append(&self.max_priority_fee_per_gas)
+            .append(&self.max_fee_per_gas)
+            .append(&self.gas_limit);
+        encode_to(&self.to, list);
+        list.append(&self.value).append(&self.input);
+        let mut access_list = Vec::new();
+        rlp::encode_list(&self.access_list, &mut access_list);
+        list.append_raw(&access_list);
+    }
+}
+
+/// An unsigned transaction of any supported envelope.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Transaction {
+    Legacy(LegacyTransaction),
+    Eip2930(Eip2930Transaction),
+    Eip1559(Eip1559Transaction),
+}
+
+impl Transaction {
+    /// The EIP-2718 type byte; 0 for legacy transactions.
+    pub fn tx_type(&self) -> u8 {
+        match self {
+            Transaction::Legacy(_) => 0,
+            Transaction::Eip2930(_) => EIP2930_TYPE,
+            Transaction::Eip1559(_) => EIP1559_TYPE,
+        }
+    }
+
+    pub fn chain_id(&self) -> Option<u64> {
+        match self {
+            Transaction::Legacy(tx) => tx.chain_id,
+            Transaction::Eip2930(tx) => Some(tx.chain_id),
+            Transaction::Eip1559(tx) => Some(tx.chain_id),
+        }
+    }
+
+    pub fn nonce(&self) -> u64 {
+        match self {
+            Transaction::Legacy(tx) => tx.nonce,
+            Transaction::Eip2930(tx) => tx.nonce,
+            Transaction::Eip1559(tx) => tx.nonce,
+        }
+    }
+
+    pub fn gas_limit(&self) -> u64 {
+        match self {
+            Transaction::Legacy(tx) => tx.gas_limit,
+            Transaction::Eip2930(tx) => tx.gas_limit,
+            Transaction::Eip1559(tx) => tx.gas_limit,
+        }
+    }
+
+    pub fn to(&self) -> Option<Address> {
+        match self {
+            Transaction::Legacy(tx) => tx.to,
+            Transaction::Eip2930(tx) => tx.to,
+            Transaction::Eip1559(tx) => tx.to,
+        }
+    }
+
+    pub fn value(&self) -> U256 {
+        match self {
+            Transaction::Legacy(tx) => tx.value,
+            Transaction::Eip2930(tx) => tx.value,
+            Transaction::Eip1559(tx) => tx.value,
+        }
+    }
+
+    pub fn input(&self) -> &[u8] {
+        match self {
+            Transaction::Legacy(tx) => &tx.input,
+            Transaction::Eip2930(tx) => &tx.input,
+            Transaction::Eip1559(tx) => &tx.input,
+        }
+    }
+
+    /// The bytes whose keccak-256 hash is signed.
+    pub fn encode_for_signing(&self) -> Vec<u8> {
+        let mut list = ListEncoder::new();
+        let mut out = Vec::new();
+        match self {
+            Transaction::Legacy(tx) => {
+                tx.encode_fields(&mut list);
+                if let Some(chain_id) = tx.chain_id {
+                    list.append(&chain_id).append(&0u8).append(&0u8);
+                }
+            }
+            Transaction::Eip2930(tx) => {
+                out.push(EIP2930_TYPE);
+                tx.encode_fields(&mut list);
+            }
+            Transaction::Eip1559(tx) => {
+                out.push(EIP1559_TYPE);
+                tx.encode_fields(&mut list);
+            }
+        }
+        list.finish(&mut out);
+        out
+    }
+
+    /// The digest a signer signs.
+    pub fn signing_hash(&self) -> B256 {
+        keccak256(self.encode_for_signing())
+    }
+
+    /// Attaches an externally produced signature.
+    pub fn into_signed(self, signature: Signature) -> SignedTransaction {
+        SignedTransaction {
+            transaction: self,
+            signature,
+        }
+    }
+
+    /// Signs the transaction through a signing hook.
+    pub fn sign(self, signer: &dyn Signer) -> Result<SignedTransaction> {
+        if let Transaction::Legacy(tx) = &self {
+            let worst = Signature {
+                y_parity: true,
+                ..Signature::default()
+            };
+            if worst.legacy_v(tx.chain_id).is_none() {
+                return Err(invalid(format!(
+                    "chain id {} is too large for a legacy transaction",
+                    tx.chain_id.unwrap_or_default()
+                )));
+            }
+        }
+        let payload = SigningPayload::Digest(self.signing_hash().0);
+        match signer.sign(&payload)? {
+            crossbeam_core::Signature::Secp256k1 { r, s, recovery_id } => {
+                let signature = Signature {
+                    r: U256::from_be_bytes(r),
+                    s: U256::from_be_bytes(s),
+                    y_parity: recovery_id == 1,
+                };
+                Ok(self.into_signed(signature))
+            }
+            crossbeam_core::Signature::Ed25519(_) => Err(Error::InvalidSignature(
+                "EVM transactions need a secp256k1 signer".to_owned(),
+            )),
+        }
+    }
+}
+
+impl From<LegacyTransaction> for Transaction {
+    fn from(tx: LegacyTransaction) -> Self {
+        Transaction::Legacy(tx)
+    }
+}
+
+impl From<Eip2930Transaction> for Transaction {
+    fn from(tx: Eip2930Transaction) -> Self {
+        Transaction::Eip2930(tx)
+    }
+}
+
+impl From<Eip1559Transaction> for Transaction {
+    fn from(tx: Eip1559Transaction) -> Self {
+        Transaction::Eip1559(tx)
+    }
+}
+
+/// A secp256k1 transaction signature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Signature {
+    pub r: U256,
+    pub s: U256,
+    /// Parity of the `R` point's y coordinate, i.e. the recovery id.
+    pub y_parity: bool,
+}
+
+impl Signature {
+    /// The legacy `v` value: `27 + parity`, or the EIP-155
+    /// `chain_id * 2 + 35 + parity`. `None` if that overflows a `u64`.
+    pub fn legacy_v(&self, chain_id: Option<u64>) -> Option<u64> {
+        let parity = u64::from(self.y_parity);
+        match chain_id {
+            Some(chain_id) => chain_id.checked_mul(2)?.checked_add(35 + parity),
+            None => Some(27 + parity),
+        }
+    }
+
+    /// Parses the 65-byte `r || s || v` form, with `v` either the recovery
+    /// id or `27` plus it.
+    pub fn from_bytes(bytes: &[u8; 65]) -> Result<Self> {
+        let y_parity = match bytes[64] {
+            0 | 27 => false,
+            1 | 28 => true,
+            v => return Err(Error::InvalidSignature(format!("invalid v {v}"))),
+        };
+        Ok(Self {
+            r: U256::from_be_slice(&bytes[..32]),
+            s: U256::from_be_slice(&bytes[32..64]),
+            y_parity,
+        })
+    }
+
+    /// Recovers the address that signed `hash`. High-s signatures are
+    /// rejected as required since EIP-2.
+    pub fn recover(&self, hash: &B256) -> Result<Address> {
+        let signature = k256::ecdsa::Signature::from_scalars(
+            self.r.to_be_bytes::<32>(),
+            self.s.to_be_bytes::<32>(),
+        )
+        .map_err(|err| Error::InvalidSignature(err.to_string()))?;
+        if signature.normalize_s().is_some() {
+            return Err(Error::InvalidSignature(
+                "s is not in the lower half order".to_owned(),
+            ));
+        }
+        let recovery_id = RecoveryId::new(self.y_parity, false);
+        let key = VerifyingKey::recover_from_prehash(&hash.0, &signature, recovery_id)
+            .map_err(|err| Error::InvalidSignature(err.to_string()))?;
+        let point = key.to_encoded_point(false);
+        let uncompressed: &[u8; 64] = point.as_bytes()[1..].try_into().expect("64-byte point");
+        Ok(Address::from_public_key(uncompressed))
+    }
+}
+
+/// A transaction together with its signature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedTransaction {
+    pub transaction: Transaction,
+    pub signature: Signature,
+}
+
+impl SignedTransaction {
+    /// The EIP-2718 encoding broadcast with `eth_sendRawTransaction`.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut list = ListEncoder::new();
+        let mut out = Vec::new();
+        let sig = &self.signature;
+        match &self.transaction {
+            Transaction::Legacy(tx) => {
+                tx.encode_fields(&mut list);
+                match sig.legacy_v(tx.chain_id) {
+                    Some(v) => list.append(&v),
+                    // Only reachable through `into_signed`; `sign` refuses
+                    // such chain ids.
+                    None => list.append(
+                        &(U256::from(tx.chain_id.unwrap_or_default()) * U256::from(2u8)
+                            + U256::from(35 + u64::from(sig.y_parity))),
+                    ),
+                };
+            }
+            Transaction::Eip2930(tx) => {
+                out.push(EIP2930_TYPE);
+                tx.encode_fields(&mut list);
+                list.append(&sig.y_parity);
+            }
+            Transaction::Eip1559(tx) => {
+                out.push(EIP1559_TYPE);
+                tx.encode_fields(&mut list);
+                list.append(&sig.y_parity);
+            }
+        }
+        list.append(&sig.r).append(&sig.s);
+        list.finish(&mut out);
+        out
+    }
+
+    /// The transaction hash: keccak-256 of the wire encoding.
+    pub fn hash(&self) -> TxHash {
+        keccak256(self.encode())
+    }
+
+    /// Decodes a raw signed transaction.
+    pub fn decode(raw: &[u8]) -> Result<Self> {
+        let (&first, rest) = raw.split_first().ok_or_else(|| invalid("empty input"))?;
+        match first {
+            EIP2930_TYPE => Self::decode_eip2930(rest),
+            EIP1559_TYPE => Self::decode_eip1559(rest),
+            0xc0..=0xff => Self::decode_legacy(raw),
+            other => Err(invalid(format!(
+                "unsupported transaction type {other:#04x}"
+            ))),
+        }
+    }
+
+    fn decode_legacy(raw: &[u8]) -> Result<Self> {
+        let fields = Rlp::new(raw)?.items()?;
+        let [nonce, gas_price, gas_limit, to, value, input, v, r, s] = fields.as_slice() else {
+            return Err(invalid("legacy transactions have nine fields"));
+        };
+        let v = v.as_u64()?;
+        let (chain_id, y_parity) = match v {
+            27 | 28 => (None, v == 28),
+            35.. => (Some((v - 35) / 2), (v - 35) % 2 == 1),
+            _ => return Err(Error::InvalidSignature(format!("invalid v value {v}"))),
+        };
+        let transaction = LegacyTransaction {
+            chain_id,
+            nonce: nonce.as_u64()?,
+            gas_price: gas_price.as_u256()?,
+            gas_limit: gas_limit.as_u64()?,
+            to: decode_to(to)?,
+            value: value.as_u256()?,
+            input: input.bytes()?.to_vec(),
+        };
+        let signature = Signature {
+            r: r.as_u256()?,
+            s: s.as_u256()?,
+            y_parity,
+        };
+        Ok(Transaction::Legacy(transaction).into_signed(signature))
+    }
+
+    fn decode_eip2930(payload: &[u8]) -> Result<Self> {
+        let fields = Rlp::new(payload)?.items()?;
+        let [chain_id, nonce, gas_price, gas_limit, to, value, input, access_list, y_parity, r, s] =
+            fields.as_slice()
+        else {
+            return Err(invalid("EIP-2930 transactions have eleven fields"));
+        };
+        let transaction = Eip2930Transaction {
+            chain_id: chain_id.as_u64()?,
+            nonce: nonce.as_u64()?,
+            gas_price: gas_price.as_u256()?,
+            gas_limit: gas_limit.as_u64()?,
+            to: decode_to(to)?,
+            value: value.as_u256()?,
+            input: input.bytes()?.to_vec(),
+            access_list: decode_access_list(access_list)?,
+        };
+        let signature = decode_typed_signature(y_parity, r, s)?;
+        Ok(Transaction::Eip2930(transaction).into_signed(signature))
+    }
+
+    fn decode_eip1559(payload: &[u8]) -> Result<Self> {
+        let fields = Rlp::new(payload)?.items()?;
+        let [chain_id, nonce, max_priority_fee_per_gas, max_fee_per_gas, gas_limit, to, value, input, access_list, y_parity, r, s] =
+            fields.as_slice()
+        else {
+            return Err(invalid("EIP-1559 transactions have twelve fields"));
+        };
+        let transaction = Eip1559Transaction {
+            chain_id: chain_id.as_u64()?,
+            nonce: nonce.as_u64()?,
+            max_priority_fee_per_gas: max_priority_fee_per_gas.as_u256()?,
+            max_fee_per_gas: max_fee_per_gas.as_u256()?,
+            gas_limit: gas_limit.as_u64()?,
+            to: decode_to(to)?,
+            value: value.as_u256()?,
+            input: input.bytes()?.to_vec(),
+            access_list: decode_access_list(access_list)?,
+        };
+        let signature = decode_typed_signature(y_parity, r, s)?;
+        Ok(Transaction::Eip1559(transaction).into_signed(signature))
+    }
+
+    /// Recovers the sender from the signature. High-s signatures are
+    /// rejected as required since EIP-2.
+    pub fn recover_signer(&self) -> Result<Address> {
+        self.signature.recover(&self.transaction.signing_hash())
+    }
+}
+
+fn decode_typed_signature(y_parity: &Rlp<'_>, r: &Rlp<'_>, s: &Rlp<'_>) -> Result<Signature> {
+    let y_parity = match y_parity.as_u64()? {
+        0 => false,
+        1 => true,
+        other => return Err(Error::InvalidSignature(format!("invalid y parity {other}"))),
+    };
+    Ok(Signature {
+        r: r.as_u256()?,
+        s: s.as_u256()?,
+        y_parity,
+    })
+}
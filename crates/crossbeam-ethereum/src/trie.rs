@@ -0,0 +1,300 @@
+//! Merkle-Patricia tries.
+//!
+//! Ethereum commits to accounts, storage slots, transactions and receipts
+//! through the root hash of a hexary Merkle-Patricia trie. [`verify_proof`]
+//! checks a proof, the nodes on the path from the root to a key, against
+//! such a root. [`Trie`] builds small tries in memory, which is enough to
+//! recompute the transaction or receipt root of a block and to produce
+//! proofs for its entries.
+
+use std::collections::BTreeMap;
+
+use crate::error::{Error, Result};
+use crate::primitives::{keccak256, B256};
+use crate::rlp::{self, ListEncoder, Rlp};
+
+/// The root of the empty trie, `keccak256(rlp(""))`.
+pub const EMPTY_ROOT: B256 = B256([
+    0x56, 0xe8, 0x1f, 0x17, 0x1b, 0xcc, 0x55, 0xa6, 0xff, 0x83, 0x45, 0xe6, 0x92, 0xc0, 0xf8, 0x6e,
+    0x5b, 0x48, 0xe0, 0x1b, 0x99, 0x6c, 0xad, 0xc0, 0x01, 0x62, 0x2f, 0xb5, 0xe3, 0x63, 0xb4, 0x21,
+]);
+
+fn proof_error(reason: impl Into<String>) -> Error {
+    Error::InvalidProof(reason.into())
+}
+
+fn nibbles(key: &[u8]) -> Vec<u8> {
+    key.iter().flat_map(|b| [b >> 4, b & 0x0f]).collect()
+}
+
+/// Decodes a hex-prefix encoded path into its nibbles and leaf flag.
+fn decode_path(encoded: &[u8]) -> Result<(Vec<u8>, bool)> {
+    let (&first, rest) = encoded
+        .split_first()
+        .ok_or_else(|| proof_error("empty node path"))?;
+    let flag = first >> 4;
+    if flag > 3 {
+        return Err(proof_error(format!("invalid path flag {flag}")));
+    }
+    let mut path = Vec::with_capacity(rest.len() * 2 + 1);
+    if flag & 1 == 1 {
+        path.push(first & 0x0f);
+    } else if first & 0x0f != 0 {
+        return Err(proof_error("even path with a non-zero pad nibble"));
+    }
+    path.extend(nibbles(rest));
+    Ok((path, flag & 2 == 2))
+}
+
+fn encode_path(path: &[u8], leaf: bool) -> Vec<u8> {
+    let flag = if leaf { 2 } else { 0 };
+    let mut out = Vec::with_capacity(path.len() / 2 + 1);
+    let rest = if path.len() % 2 == 1 {
+        out.push(((flag + 1) << 4) | path[0]);
+        &path[1..]
+    } else {
+        out.push(flag << 4);
+        path
+    };
+    out.extend(rest.chunks(2).map(|pair| (pair[0] << 4) | pair[1]));
+    out
+}
+
+/// Where a node points next.
+enum Child {
+    /// A node of 32 bytes or more, by hash; it is the next proof element.
+    Hash(B256),
+    /// A shorter node, embedded in its parent.
+    Inline(Vec<u8>),
+}
+
+impl Child {
+    /// `None` for an empty slot, which proves the key absent.
+    fn of(item: &Rlp<'_>) -> Result<Option<Self>> {
+        match *item {
+            Rlp::Bytes([]) => Ok(None),
+            Rlp::Bytes(hash) => Ok(Some(Child::Hash(B256(item.fixed().map_err(|_| {
+                proof_error(format!("child reference of {} bytes", hash.len()))
+            })?)))),
+            Rlp::List(payload) => {
+                let mut node = Vec::with_capacity(payload.len() + 1);
+                rlp::encode_list_payload(payload, &mut node);
+                Ok(Some(Child::Inline(node)))
+            }
+        }
+    }
+}
+
+/// Verifies `proof` for `key` against the trie `root`.
+///
+/// `proof` lists the encoded nodes from the root towards the key, as
+/// returned by `eth_getProof`; nodes embedded in their parent are not
+/// repeated. Returns the value stored at `key`, or `None` if the proof
+/// shows the key is absent. Fails if the proof does not hash to `root`,
+/// stops short of an answer, or carries nodes beyond it.
+pub fn verify_proof<P: AsRef<[u8]>>(
+    root: &B256,
+    key: &[u8],
+    proof: &[P],
+) -> Result<Option<Vec<u8>>> {
+    if proof.is_empty() && *root == EMPTY_ROOT {
+        return Ok(None);
+    }
+    let key = nibbles(key);
+    let mut path = key.as_slice();
+    let mut nodes = proof.iter().map(AsRef::as_ref);
+    let mut next = Child::Hash(*root);
+    let value = loop {
+        let node = match next {
+            Child::Hash(hash) => {
+                let node = nodes
+                    .next()
+                    .ok_or_else(|| proof_error("proof ends before reaching the key"))?;
+                if keccak256(node) != hash {
+                    return Err(proof_error(format!("node does not hash to {hash}")));
+                }
+                node.to_vec()
+            }
+            Child::Inline(node) => node,
+        };
+        let node = Rlp::new(&node)?;
+        if node == Rlp::Bytes(&[]) {
+            break None;
+        }
+        let items = node.items()?;
+        match items.len() {
+            17 => match path.split_first() {
+                None => {
+                    let value = items[16].bytes()?;
+                    break (!value.is_empty()).then(|| value.to_vec());
+                }
+                Some((&nibble, rest)) => {
+                    path = rest;
+                    match Child::of(&items[usize::from(nibble)])? {
+                        Some(child) => next = child,
+                        None => break None,
+                    }
+                }
+            },
+            2 => {
+                let (node_path, leaf) = decode_path(items[0].bytes()?)?;
+                if leaf {
+                    if path != node_path {
+                        break None;
+                    }
+                    break Some(items[1].bytes()?.to_vec());
+                }
+                let Some(rest) = path.strip_prefix(node_path.as_slice()) else {
+                    break None;
+                };
+                if node_path.is_empty() {
+                    return Err(proof_error("extension node with an empty path"));
+                }
+                path = rest;
+                next = Child::of(&items[1])?
+                    .ok_or_else(|| proof_error("extension node without a child"))?;
+            }
+            n => return Err(proof_error(format!("node with {n} items"))),
+        }
+    };
+    if nodes.next().is_some() {
+        return Err(proof_error("proof has nodes past the key"));
+    }
+    Ok(value)
+}
+
+/// An in-memory Merkle-Patricia trie.
+///
+/// Meant for the few thousand entries of a block's transactions or
+/// receipts: the root and proofs are recomputed from scratch on each call.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Trie {
+    entries: BTreeMap<Vec<u8>, Vec<u8>>,
+}
+
+impl Trie {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the value at `key`. An empty value removes the key, as in
+    /// Ethereum's tries.
+    pub fn insert(&mut self, key: impl Into<Vec<u8>>, value: impl Into<Vec<u8>>) {
+        let (key, value) = (key.into(), value.into());
+        if value.is_empty() {
+            self.entries.remove(&key);
+        } else {
+            self.entries.insert(key, value);
+        }
+    }
+
+    pub fn get(&self, key: &[u8]) -> Option<&[u8]> {
+        self.entries.get(key).map(Vec::as_slice)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn root(&self) -> B256 {
+        keccak256(self.root_node(None, &mut Vec::new()))
+    }
+
+    /// A proof for `key` in the form [`verify_proof`] accepts. For an absent
+    /// key, the proof shows its absence.
+    pub fn proof(&self, key: &[u8]) -> Vec<Vec<u8>> {
+        let mut proof = Vec::new();
+        let root = self.root_node(Some(&nibbles(key)), &mut proof);
+        proof.push(root);
+        proof.reverse();
+        proof
+    }
+
+    /// The encoded root node. Hashed nodes on the path to `target` are
+    /// collected into `proof`, deepest first.
+    fn root_node(&self, target: Option<&[u8]>, proof: &mut Vec<Vec<u8>>) -> Vec<u8> {
+        if self.entries.is_empty() {
+            return rlp::encode(&[] as &[u8]);
+        }
+        let entries: Vec<(Vec<u8>, &[u8])> = self
+            .entries
+            .iter()
+            .map(|(key, value)| (nibbles(key), value.as_slice()))
+            .collect();
+        encode_node(&entries, 0, target, proof)
+    }
+}
+
+/// Encodes the node holding `entries`, which share their first `depth`
+/// nibbles. `target` is set while the node lies on the proven path.
+fn encode_node(
+    entries: &[(Vec<u8>, &[u8])],
+    depth: usize,
+    target: Option<&[u8]>,
+    proof: &mut Vec<Vec<u8>>,
+) -> Vec<u8> {
+    let mut node = ListEncoder::new();
+    if let [(key, value)] = entries {
+        node.append(&encode_path(&key[depth..], true))
+            .append(*value);
+        return node.into_bytes();
+    }
+
+    let (first, last) = (&entries[0].0, &entries[entries.len() - 1].0);
+    let shared = first[depth..]
+        .iter()
+        .zip(&last[depth..])
+        .take_while(|(a, b)| a == b)
+        .count();
+    if shared > 0 {
+        let path = &first[depth..depth + shared];
+        let target = target.filter(|t| t.get(depth..depth + shared) == Some(path));
+        let child = encode_node(entries, depth + shared, target, proof);
+        node.append(&encode_path(path, false));
+        append_child(&mut node, child, target.is_some(), proof);
+        return node.into_bytes();
+    }
+
+    // Entries are sorted, so a key ending here comes first, and each
+    // nibble's entries are contiguous.
+    let mut rest = entries;
+    let mut value: &[u8] = &[];
+    if first.len() == depth {
+        value = entries[0].1;
+        rest = &entries[1..];
+    }
+    for nibble in 0..16u8 {
+        let count = rest
+            .iter()
+            .take_while(|(key, _)| key[depth] == nibble)
+            .count();
+        let (children, tail) = rest.split_at(count);
+        rest = tail;
+        if children.is_empty() {
+            node.append(&[] as &[u8]);
+            continue;
+        }
+        let target = target.filter(|t| t.get(depth) == Some(&nibble));
+        let child = encode_node(children, depth + 1, target, proof);
+        append_child(&mut node, child, target.is_some(), proof);
+    }
+    node.append(value);
+    node.into_bytes()
+}
+
+/// References `child` from its parent: by hash when its encoding is 32
+/// bytes or more, inline otherwise.
+fn append_child(parent: &mut ListEncoder, child: Vec<u8>, on_path: bool, proof: &mut Vec<Vec<u8>>) {
+    if child.len() < 32 {
+        parent.append_raw(&child);
+        return;
+    }
+    parent.append(&keccak256(&child));
+    if on_path {
+        proof.push(child);
+    }
+}
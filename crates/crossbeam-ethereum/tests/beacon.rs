@@ -0,0 +1,192 @@
+//! The fixtures follow a light client from an Electra bootstrap through a
+//! committee rotation, in the Beacon API's JSON. Roots and branches were
+//! produced with an independent SSZ implementation, and signatures with
+//! 512-member committees of generated keys.
+
+use crossbeam_ethereum::beacon::{
+    period_at_slot, ChainSpec, LightClient, LightClientBootstrap, LightClientUpdate,
+};
+use crossbeam_ethereum::{ssz, Error, B256};
+use serde_json::Value;
+
+fn word(s: &str) -> B256 {
+    s.parse().unwrap()
+}
+
+fn trusted_root() -> B256 {
+    word("0x453b6b860abb74d6cd4bad7f3843648e8de13968eaa0393ff4e9c031d4ae2f89")
+}
+
+fn data<T: serde::de::DeserializeOwned>(response: &Value) -> T {
+    serde_json::from_value(response["data"].clone()).unwrap()
+}
+
+fn bootstrap() -> LightClientBootstrap {
+    data(&serde_json::from_str(include_str!("fixtures/light_client/bootstrap.json")).unwrap())
+}
+
+fn updates() -> Vec<LightClientUpdate> {
+    let responses: Vec<Value> =
+        serde_json::from_str(include_str!("fixtures/light_client/updates.json")).unwrap();
+    let finality: Value =
+        serde_json::from_str(include_str!("fixtures/light_client/finality_update.json")).unwrap();
+    responses.iter().chain([&finality]).map(data).collect()
+}
+
+fn client() -> LightClient {
+    LightClient::new(ChainSpec::mainnet(), &trusted_root(), bootstrap()).unwrap()
+}
+
+#[test]
+fn follows_finality_across_committee_periods() {
+    let mut client = client();
+    assert_eq!(client.period(), 1430);
+    assert_eq!(client.finalized_block_number(), 22_000_000);
+    assert!(client.next_sync_committee().is_none());
+
+    let updates = updates();
+    let [learn_next, rotate, finality] = updates.as_slice() else {
+        panic!("three updates");
+    };
+
+    client.apply(learn_next, learn_next.signature_slot).unwrap();
+    assert_eq!(client.period(), 1430);
+    assert_eq!(client.finalized_block_number(), 22_000_100);
+    assert_eq!(
+        client.next_sync_committee(),
+        learn_next.next_sync_committee.as_ref()
+    );
+
+    client.apply(rotate, rotate.signature_slot + 10).unwrap();
+    assert_eq!(client.period(), 1431);
+    assert_eq!(
+        Some(client.current_sync_committee()),
+        learn_next.next_sync_committee.as_ref()
+    );
+    assert_eq!(
+        client.next_sync_committee(),
+        rotate.next_sync_committee.as_ref()
+    );
+
+    // A finality update carries no committee and keeps the known one.
+    assert!(finality.next_sync_committee.is_none());
+    client.apply(finality, finality.signature_slot).unwrap();
+    assert_eq!(client.finalized_block_number(), 22_011_900);
+    assert_eq!(
+        client.finalized_state_root(),
+        word("0x000000000d1ebfda0000000000000000000000000000000000000000000000ee")
+    );
+    assert_eq!(period_at_slot(client.finalized().beacon.slot), 1431);
+    assert!(client.next_sync_committee().is_some());
+}
+
+#[test]
+fn bootstrap_must_match_the_trusted_root() {
+    assert!(matches!(
+        LightClient::new(ChainSpec::mainnet(), &B256([1; 32]), bootstrap()),
+        Err(Error::InvalidUpdate(_))
+    ));
+
+    let mut forged = bootstrap();
+    forged.current_sync_committee.pubkeys.swap(0, 1);
+    assert!(matches!(
+        LightClient::new(ChainSpec::mainnet(), &trusted_root(), forged),
+        Err(Error::InvalidProof(_))
+    ));
+
+    let mut forged = bootstrap();
+    forged.header.execution.state_root = B256([1; 32]);
+    assert!(matches!(
+        LightClient::new(ChainSpec::mainnet(), &trusted_root(), forged),
+        Err(Error::InvalidProof(_))
+    ));
+}
+
+#[test]
+fn rejects_bad_updates_without_changing_state() {
+    let mut client = client();
+    let updates = updates();
+    let update = &updates[0];
+    let slot = update.signature_slot;
+    let finalized = client.finalized().clone();
+    let mut reject = |update: &LightClientUpdate, current_slot| {
+        let err = client.apply(update, current_slot).unwrap_err();
+        assert_eq!(*client.finalized(), finalized);
+        assert!(client.next_sync_committee().is_none());
+        err
+    };
+
+    // Signed in the future.
+    assert!(matches!(reject(update, slot - 1), Error::InvalidUpdate(_)));
+
+    // A member that did not sign claimed as a participant.
+    let mut forged = update.clone();
+    forged.sync_aggregate.sync_committee_bits[0] |= 1 << 3;
+    assert!(matches!(reject(&forged, slot), Error::InvalidSignature(_)));
+
+    // Short of two thirds.
+    let mut forged = update.clone();
+    forged.sync_aggregate.sync_committee_bits[..22].fill(0);
+    assert!(matches!(reject(&forged, slot), Error::InvalidUpdate(_)));
+
+    // A finalized header the attested state does not commit to.
+    let mut forged = update.clone();
+    forged.finalized_header = updates[1].finalized_header.clone();
+    assert!(matches!(reject(&forged, slot), Error::InvalidUpdate(_)));
+    let mut forged = update.clone();
+    forged.finality_branch[3] = B256([1; 32]);
+    assert!(matches!(reject(&forged, slot), Error::InvalidProof(_)));
+    let mut forged = update.clone();
+    forged.finalized_header.execution.state_root = B256([1; 32]);
+    assert!(matches!(reject(&forged, slot), Error::InvalidProof(_)));
+
+    // A next committee the attested state does not hold.
+    let mut forged = update.clone();
+    forged.next_sync_committee = updates[1].next_sync_committee.clone();
+    assert!(matches!(reject(&forged, slot), Error::InvalidProof(_)));
+
+    // The next period's update before its committee is known.
+    assert!(matches!(
+        reject(&updates[1], updates[1].signature_slot),
+        Error::InvalidUpdate(_)
+    ));
+
+    client.apply(update, slot).unwrap();
+    assert!(matches!(
+        client.apply(update, slot),
+        Err(Error::InvalidUpdate(_))
+    ));
+}
+
+#[test]
+fn updates_round_trip_through_json() {
+    for update in updates() {
+        let json = serde_json::to_value(&update).unwrap();
+        assert_eq!(json["signature_slot"], update.signature_slot.to_string());
+        assert_eq!(
+            serde_json::from_value::<LightClientUpdate>(json).unwrap(),
+            update
+        );
+    }
+}
+
+#[test]
+fn merkleizes_and_checks_branches() {
+    let zero_hash = word("0xf5a5fd42d16a20302798ef6ed309979b43003d2320d9f0e8ea9831a92759fb4b");
+    assert_eq!(ssz::merkleize(&[], 2), zero_hash);
+    assert_eq!(ssz::merkleize(&[B256::ZERO], 1), B256::ZERO);
+    assert_eq!(ssz::hash_pair(&B256::ZERO, &B256::ZERO), zero_hash);
+
+    let leaves: Vec<B256> = (1..=5).map(|i| B256([i; 32])).collect();
+    let root = ssz::merkleize(&leaves, 8);
+    let left = ssz::hash_pair(&leaves[0], &leaves[1]);
+    let right = ssz::hash_pair(&leaves[2], &leaves[3]);
+    let fifth = ssz::hash_pair(&ssz::hash_pair(&leaves[4], &B256::ZERO), &zero_hash);
+    assert_eq!(root, ssz::hash_pair(&ssz::hash_pair(&left, &right), &fifth));
+
+    // Leaf 2 of 8 has generalized index 8 + 2.
+    let branch = [leaves[3], left, fifth];
+    assert!(ssz::verify_branch(&leaves[2], &branch, 10, &root));
+    assert!(!ssz::verify_branch(&leaves[2], &branch, 11, &root));
+    assert!(!ssz::verify_branch(&leaves[2], &branch[..2], 10, &root));
+}
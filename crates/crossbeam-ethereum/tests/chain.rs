@@ -0,0 +1,171 @@
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use crossbeam_core::signer::Secp256k1Signer;
+use crossbeam_core::{Amount, Asset, Chain, Confirmation, Network, Transfer, U256};
+use crossbeam_ethereum::fees::GWEI;
+use crossbeam_ethereum::rpc::FeeHistory;
+use crossbeam_ethereum::{
+    keccak256, Address, Error, EvmChain, FeeStrategy, Provider, Result, Transaction, TxHash,
+};
+
+#[derive(Default)]
+struct RecordingProvider {
+    sent: Mutex<Vec<Vec<u8>>>,
+}
+
+fn gwei(n: u64) -> U256 {
+    U256::from(n * GWEI)
+}
+
+#[async_trait]
+impl Provider for RecordingProvider {
+    async fn transaction_count(&self, _address: &Address) -> Result<u64> {
+        Ok(9)
+    }
+
+    async fn gas_price(&self) -> Result<U256> {
+        Ok(gwei(20))
+    }
+
+    /// A 10 gwei base fee, with busy blocks tipping 2 gwei.
+    async fn fee_history(
+        &self,
+        block_count: u64,
+        _reward_percentiles: &[f64],
+    ) -> Result<FeeHistory> {
+        let blocks = block_count as usize;
+        Ok(FeeHistory {
+            oldest_block: 100,
+            base_fee_per_gas: vec![gwei(10); blocks + 1],
+            gas_used_ratio: vec![0.5; blocks],
+            reward: vec![vec![gwei(2)]; blocks],
+        })
+    }
+
+    async fn send_raw_transaction(&self, raw: &[u8]) -> Result<TxHash> {
+        self.sent.lock().unwrap().push(raw.to_vec());
+        Ok(keccak256(raw))
+    }
+
+    async fn transaction_confirmation(&self, _hash: &TxHash) -> Result<Confirmation> {
+        Ok(Confirmation::Confirmed { height: 7 })
+    }
+}
+
+#[tokio::test]
+async fn transfers_are_built_signed_and_submitted_through_the_provider() {
+    let chain =
+        EvmChain::ethereum(RecordingProvider::default()).with_fee_strategy(FeeStrategy::legacy());
+    assert_eq!(chain.network(), Network::Ethereum);
+    assert_eq!(chain.chain_id(), 1);
+
+    let signer = Secp256k1Signer::from_bytes(&[0x46; 32]).unwrap();
+    let eth = Asset::native(Network::Ethereum).unwrap();
+    let transfer = Transfer {
+        from: chain
+            .parse_address("0x9d8A62f656a8d1615C1294fd71e9CFb3E4855A4F")
+            .unwrap(),
+        to: Address::new([0x35; 20]),
+        amount: Amount::parse(eth, "1").unwrap(),
+    };
+    let tx = chain.build_transfer(&transfer).await.unwrap();
+    let signed = chain.sign(tx, &[&signer]).unwrap();
+    let hash = chain.submit(&signed).await.unwrap();
+
+    // Nonce 9 at 20 gwei reproduces the EIP-155 example transaction.
+    let sent = chain.provider().sent.lock().unwrap().clone();
+    assert_eq!(
+        hex::encode(&sent[0]),
+        "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83"
+    );
+    assert_eq!(hash, signed.hash());
+    assert_eq!(
+        chain.confirmation(&hash).await.unwrap(),
+        Confirmation::Confirmed { height: 7 }
+    );
+}
+
+fn transfer() -> Transfer<Address> {
+    let eth = Asset::native(Network::Ethereum).unwrap();
+    Transfer {
+        from: Address::new([0x9d; 20]),
+        to: Address::new([0x35; 20]),
+        amount: Amount::new(eth, U256::from(1u8)),
+    }
+}
+
+#[tokio::test]
+async fn ethereum_transfers_are_eip1559_within_the_fee_caps() {
+    let chain = EvmChain::ethereum(RecordingProvider::default());
+    let Transaction::Eip1559(tx) = chain.build_transfer(&transfer()).await.unwrap() else {
+        panic!("expected an EIP-1559 transfer");
+    };
+    assert_eq!(tx.chain_id, 1);
+    assert_eq!(tx.nonce, 9);
+    assert_eq!(tx.max_fee_per_gas, gwei(22));
+    assert_eq!(tx.max_priority_fee_per_gas, gwei(2));
+
+    // Above the base fee the cap clamps the offer...
+    let capped = EvmChain::ethereum(RecordingProvider::default())
+        .with_fee_strategy(FeeStrategy::eip1559().max_fee_per_gas(gwei(15)));
+    let Transaction::Eip1559(tx) = capped.build_transfer(&transfer()).await.unwrap() else {
+        panic!("expected an EIP-1559 transfer");
+    };
+    assert_eq!(tx.max_fee_per_gas, gwei(15));
+    assert_eq!(tx.max_priority_fee_per_gas, gwei(2));
+
+    // ...below it the transfer could never be mined.
+    let too_low = EvmChain::ethereum(RecordingProvider::default())
+        .with_fee_strategy(FeeStrategy::eip1559().max_fee_per_gas(gwei(9)));
+    assert!(matches!(
+        too_low.build_transfer(&transfer()).await,
+        Err(Error::FeeCapExceeded { required, cap }) if required == gwei(10) && cap == gwei(9)
+    ));
+}
+
+#[tokio::test]
+async fn legacy_gas_prices_over_the_cap_are_rejected() {
+    let chain = EvmChain::ethereum(RecordingProvider::default())
+        .with_fee_strategy(FeeStrategy::legacy().max_fee_per_gas(gwei(19)));
+    assert!(matches!(
+        chain.build_transfer(&transfer()).await,
+        Err(Error::FeeCapExceeded { required, cap }) if required == gwei(20) && cap == gwei(19)
+    ));
+}
+
+#[tokio::test]
+async fn foreign_assets_and_signer_counts_are_rejected() {
+    let chain = EvmChain::ethereum(RecordingProvider::default());
+    let bnb = Asset::native(Network::BinanceSmartChain).unwrap();
+    let transfer = Transfer {
+        from: Address::ZERO,
+        to: Address::ZERO,
+        amount: Amount::new(bnb, U256::from(1u8)),
+    };
+    assert!(chain.build_transfer(&transfer).await.is_err());
+
+    let eth = Asset::native(Network::Ethereum).unwrap();
+    let transfer = Transfer {
+        amount: Amount::new(eth, U256::from(1u8)),
+        ..transfer
+    };
+    let tx = chain.build_transfer(&transfer).await.unwrap();
+    assert!(chain.sign(tx, &[]).is_err());
+}
+
+#[test]
+fn addresses_are_parsed_strictly_and_displayed_checksummed() {
+    let chain = EvmChain::ethereum(RecordingProvider::default());
+    let address = chain
+        .parse_address("0xfb6916095ca1df60bb79ce92ce3ea74c37c5d359")
+        .unwrap();
+    assert_eq!(
+        address.to_string(),
+        "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359"
+    );
+    assert!(chain
+        .parse_address("fb6916095ca1df60bb79ce92ce3ea74c37c5d359")
+        .is_err());
+    assert!(chain.parse_address("0x1234").is_err());
+}
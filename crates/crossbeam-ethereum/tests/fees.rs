@@ -0,0 +1,231 @@
+use crossbeam_core::U256;
+use crossbeam_ethereum::fees::{Eip1559Estimator, Estimator, LegacyEstimator, GWEI};
+use crossbeam_ethereum::rpc::MockTransport;
+use crossbeam_ethereum::{
+    Address, Client, Eip1559Transaction, Error, FeeStrategy, Fees, LegacyTransaction, Transaction,
+};
+use serde_json::{json, Value};
+
+fn gwei(n: u64) -> U256 {
+    U256::from(n * GWEI)
+}
+
+fn hex(n: U256) -> String {
+    format!("{n:#x}")
+}
+
+/// A node whose recent blocks paid `tips` at the requested percentile.
+/// Blocks with a zero tip were empty.
+fn market(next_base_fee: u64, tips: &[u64]) -> Client<MockTransport> {
+    let rewards: Vec<Value> = tips.iter().map(|t| json!([hex(gwei(*t))])).collect();
+    let used: Vec<f64> = tips
+        .iter()
+        .map(|t| if *t == 0 { 0.0 } else { 0.6 })
+        .collect();
+    let mut base_fees = vec![hex(gwei(next_base_fee)); tips.len()];
+    base_fees.push(hex(gwei(next_base_fee)));
+    let history = json!({
+        "oldestBlock": "0x100",
+        "baseFeePerGas": base_fees,
+        "gasUsedRatio": used,
+        "reward": rewards,
+    });
+    Client::new(
+        MockTransport::new()
+            .on_result("eth_feeHistory", history)
+            .on_result("eth_gasPrice", json!(hex(gwei(3)))),
+    )
+}
+
+fn dynamic(max_fee: u64, tip: u64) -> Transaction {
+    Eip1559Transaction {
+        chain_id: 1,
+        nonce: 3,
+        max_priority_fee_per_gas: gwei(tip),
+        max_fee_per_gas: gwei(max_fee),
+        gas_limit: 100_000,
+        to: Some(Address::new([0x44; 20])),
+        value: U256::ZERO,
+        input: vec![0xde, 0xad],
+        access_list: Vec::new(),
+    }
+    .into()
+}
+
+fn legacy(gas_price: u64) -> Transaction {
+    LegacyTransaction {
+        chain_id: Some(56),
+        nonce: 3,
+        gas_price: gwei(gas_price),
+        gas_limit: 21_000,
+        to: Some(Address::new([0x44; 20])),
+        value: U256::from(1u8),
+        input: Vec::new(),
+    }
+    .into()
+}
+
+#[tokio::test]
+async fn eip1559_tip_is_the_median_of_busy_blocks() {
+    let client = market(20, &[1, 0, 3, 2, 0]);
+    let fees = FeeStrategy::eip1559().estimate(&client).await.unwrap();
+    assert_eq!(
+        fees,
+        Fees::Eip1559 {
+            max_fee_per_gas: gwei(42),
+            max_priority_fee_per_gas: gwei(2),
+        }
+    );
+    assert_eq!(fees.max_cost(21_000), gwei(42 * 21_000));
+
+    let (_, params) = &client.transport().requests()[0];
+    assert_eq!(params, &json!(["0xa", "latest", [50.0]]));
+
+    let idle = market(20, &[0, 0, 0]);
+    let Fees::Eip1559 {
+        max_priority_fee_per_gas,
+        ..
+    } = FeeStrategy::eip1559().estimate(&idle).await.unwrap()
+    else {
+        panic!("expected EIP-1559 fees");
+    };
+    assert_eq!(max_priority_fee_per_gas, U256::from(GWEI / 10));
+
+    let cautious = FeeStrategy {
+        estimator: Estimator::Eip1559(Eip1559Estimator {
+            blocks: 5,
+            reward_percentile: 90.0,
+            base_fee_headroom_percent: 25,
+            min_priority_fee_per_gas: U256::ZERO,
+        }),
+        ..FeeStrategy::eip1559()
+    };
+    assert_eq!(
+        cautious.estimate(&client).await.unwrap().max_fee_per_gas(),
+        gwei(27)
+    );
+}
+
+#[tokio::test]
+async fn caps_clamp_or_refuse() {
+    let client = market(20, &[2, 2, 2]);
+    let capped = FeeStrategy::eip1559()
+        .max_fee_per_gas(gwei(30))
+        .max_priority_fee_per_gas(gwei(1));
+    assert_eq!(
+        capped.estimate(&client).await.unwrap(),
+        Fees::Eip1559 {
+            max_fee_per_gas: gwei(30),
+            max_priority_fee_per_gas: gwei(1),
+        }
+    );
+
+    let too_low = FeeStrategy::eip1559().max_fee_per_gas(gwei(15));
+    assert!(matches!(
+        too_low.estimate(&client).await,
+        Err(Error::FeeCapExceeded { required, cap }) if required == gwei(20) && cap == gwei(15)
+    ));
+
+    let legacy = FeeStrategy::legacy().max_fee_per_gas(gwei(2));
+    assert!(matches!(
+        legacy.estimate(&client).await,
+        Err(Error::FeeCapExceeded { .. })
+    ));
+}
+
+#[tokio::test]
+async fn legacy_prices_scale_the_node_suggestion() {
+    let client = market(20, &[1]);
+    assert_eq!(
+        FeeStrategy::legacy().estimate(&client).await.unwrap(),
+        Fees::Legacy { gas_price: gwei(3) }
+    );
+    let generous = FeeStrategy {
+        estimator: Estimator::Legacy(LegacyEstimator {
+            multiplier_percent: 150,
+            min_gas_price: U256::ZERO,
+        }),
+        ..FeeStrategy::legacy()
+    };
+    assert_eq!(
+        generous.estimate(&client).await.unwrap().max_fee_per_gas(),
+        U256::from(4_500_000_000u64)
+    );
+    let floored = FeeStrategy {
+        estimator: Estimator::Legacy(LegacyEstimator {
+            multiplier_percent: 100,
+            min_gas_price: gwei(5),
+        }),
+        ..FeeStrategy::legacy()
+    };
+    assert_eq!(
+        floored.estimate(&client).await.unwrap().max_fee_per_gas(),
+        gwei(5)
+    );
+}
+
+#[tokio::test]
+async fn bumps_satisfy_the_replacement_rule() {
+    // The market fell since the original was sent: the 10% floor wins.
+    let calm = market(5, &[1, 1, 1]);
+    let stuck = dynamic(50, 3);
+    let bumped = FeeStrategy::eip1559().bump(&calm, &stuck).await.unwrap();
+    assert_eq!(
+        Fees::of(&bumped),
+        Fees::Eip1559 {
+            max_fee_per_gas: gwei(55),
+            max_priority_fee_per_gas: U256::from(3_300_000_000u64),
+        }
+    );
+    assert_eq!(bumped.nonce(), 3);
+    assert_eq!(bumped.input(), [0xde, 0xad]);
+
+    // The market rose past the floor: the fresh estimate wins.
+    let busy = market(40, &[4, 4, 4]);
+    let bumped = FeeStrategy::eip1559().bump(&busy, &stuck).await.unwrap();
+    assert_eq!(
+        Fees::of(&bumped),
+        Fees::Eip1559 {
+            max_fee_per_gas: gwei(84),
+            max_priority_fee_per_gas: gwei(4),
+        }
+    );
+
+    // A cap below the replacement floor refuses rather than clamping.
+    let capped = FeeStrategy::eip1559().max_fee_per_gas(gwei(52));
+    assert!(matches!(
+        capped.bump(&calm, &stuck).await,
+        Err(Error::FeeCapExceeded { required, .. }) if required == gwei(55)
+    ));
+    let tip_capped = FeeStrategy::eip1559().max_priority_fee_per_gas(gwei(3));
+    assert!(matches!(
+        tip_capped.bump(&calm, &stuck).await,
+        Err(Error::FeeCapExceeded { .. })
+    ));
+
+    let bumped = FeeStrategy::legacy().bump(&calm, &legacy(1)).await.unwrap();
+    assert_eq!(Fees::of(&bumped).max_fee_per_gas(), gwei(3));
+    let bumped = FeeStrategy::legacy()
+        .bump(&calm, &legacy(10))
+        .await
+        .unwrap();
+    assert_eq!(Fees::of(&bumped).max_fee_per_gas(), gwei(11));
+}
+
+#[test]
+fn fees_only_fit_matching_transactions() {
+    let mut tx = legacy(1);
+    let dynamic_fees = Fees::of(&dynamic(10, 1));
+    assert!(dynamic_fees.apply(&mut tx).is_err());
+    Fees::Legacy { gas_price: gwei(2) }.apply(&mut tx).unwrap();
+    assert_eq!(Fees::of(&tx).max_fee_per_gas(), gwei(2));
+    assert_eq!(
+        Fees::Legacy {
+            gas_price: U256::from(1u8)
+        }
+        .bumped(10),
+        Fees::Legacy {
+            gas_price: U256::from(2u8)
+        }
+    );
+}
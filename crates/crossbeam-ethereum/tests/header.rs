@@ -0,0 +1,209 @@
+use crossbeam_core::U256;
+use crossbeam_ethereum::rlp::{self, Rlp};
+use crossbeam_ethereum::rpc::MockTransport;
+use crossbeam_ethereum::{Address, BlockNumber, Client, Error, Fork, Header, B256};
+use serde_json::{json, Value};
+
+fn word(s: &str) -> B256 {
+    s.parse().unwrap()
+}
+
+/// Ethereum mainnet's genesis block, as `eth_getBlockByNumber` returns it.
+fn genesis() -> Value {
+    json!({
+        "hash": "0xd4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3",
+        "parentHash": B256::ZERO,
+        "sha3Uncles": "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347",
+        "miner": Address::new([0; 20]),
+        "stateRoot": "0xd7f8974fb5ac78d9ac099b9ad5018bedc2ce0a72dad1827a1709da30580f0544",
+        "transactionsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+        "receiptsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+        "logsBloom": format!("0x{}", "00".repeat(256)),
+        "difficulty": "0x400000000",
+        "number": "0x0",
+        "gasLimit": "0x1388",
+        "gasUsed": "0x0",
+        "timestamp": "0x0",
+        "extraData": "0x11bbe8db4e347b4e8c937c1c8370e4b5ed33adb3db69cbdb7a38e1e50b1b82fa",
+        "mixHash": B256::ZERO,
+        "nonce": "0x0000000000000042",
+        "totalDifficulty": "0x400000000",
+        "size": "0x21c",
+        "transactions": [],
+        "uncles": [],
+    })
+}
+
+/// A post-merge header; hashes for each fork's layout were computed with
+/// alloy.
+fn london() -> Header {
+    let mut logs_bloom = [0; 256];
+    logs_bloom[3] = 0x10;
+    logs_bloom[200] = 0x01;
+    Header {
+        parent_hash: B256([1; 32]),
+        ommers_hash: word("0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347"),
+        beneficiary: "0x95222290DD7278Aa3Ddd389Cc1E1d165CC4BAfe5"
+            .parse()
+            .unwrap(),
+        state_root: B256([2; 32]),
+        transactions_root: B256([3; 32]),
+        receipts_root: B256([4; 32]),
+        logs_bloom,
+        difficulty: U256::ZERO,
+        number: 21_000_000,
+        gas_limit: 30_000_000,
+        gas_used: 12_345_678,
+        timestamp: 1_730_000_000,
+        extra_data: b"beaverbuild.org".to_vec(),
+        mix_hash: B256([5; 32]),
+        nonce: [0; 8],
+        base_fee_per_gas: Some(U256::from(7_000_000_000u64)),
+        withdrawals_root: None,
+        blob_gas_used: None,
+        excess_blob_gas: None,
+        parent_beacon_block_root: None,
+        requests_hash: None,
+    }
+}
+
+#[test]
+fn hashes_every_layout() {
+    let genesis: Header = serde_json::from_value(genesis()).unwrap();
+    assert_eq!(genesis.fork().unwrap(), Fork::Frontier);
+    assert_eq!(
+        genesis.hash().unwrap(),
+        word("0xd4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3")
+    );
+
+    let mut header = london();
+    assert_eq!(header.fork().unwrap(), Fork::London);
+    assert_eq!(
+        header.hash().unwrap(),
+        word("0x0d4c60573a53c2fd975544dfcf3f1713b462f931bb44a6a6731ebc7b004c707b")
+    );
+
+    header.withdrawals_root = Some(B256([6; 32]));
+    assert_eq!(header.fork().unwrap(), Fork::Shanghai);
+    assert_eq!(
+        header.hash().unwrap(),
+        word("0xbeffadc28708430a0e75b8441eb76e54a390b9265493fa8c4b5fe8f00e28f4c9")
+    );
+
+    header.blob_gas_used = Some(393_216);
+    header.excess_blob_gas = Some(0);
+    // Incomplete Cancun fields match no layout.
+    assert!(matches!(header.fork(), Err(Error::InvalidHeader(_))));
+    header.parent_beacon_block_root = Some(B256([7; 32]));
+    assert_eq!(header.fork().unwrap(), Fork::Cancun);
+    assert_eq!(
+        header.hash().unwrap(),
+        word("0xc39aded0c3c4f1ed2fb2845bab9412b7c4525e58ebe89f7adb48d75142fc392f")
+    );
+
+    header.requests_hash = Some(word(
+        "0xe3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+    ));
+    assert_eq!(header.fork().unwrap(), Fork::Prague);
+    assert_eq!(
+        header.hash().unwrap(),
+        word("0xd66ff571b88256a8dde496c6985756cd5132efd7f6bb6bb8c0470ba6dffb7358")
+    );
+}
+
+#[test]
+fn decodes_what_it_encodes() {
+    let mut header = london();
+    for fork in Fork::ALL {
+        match fork {
+            Fork::Frontier => header.base_fee_per_gas = None,
+            Fork::London => header.base_fee_per_gas = Some(U256::from(7u8)),
+            Fork::Shanghai => header.withdrawals_root = Some(B256([6; 32])),
+            Fork::Cancun => {
+                header.blob_gas_used = Some(0);
+                header.excess_blob_gas = Some(131_072);
+                header.parent_beacon_block_root = Some(B256::ZERO);
+            }
+            Fork::Prague => header.requests_hash = Some(B256([8; 32])),
+        }
+        assert_eq!(header.fork().unwrap(), fork);
+        assert_eq!(Header::decode(&header.encode().unwrap()).unwrap(), header);
+    }
+
+    let json = serde_json::to_value(&header).unwrap();
+    assert_eq!(json["excessBlobGas"], "0x20000");
+    assert_eq!(json["miner"], "0x95222290DD7278Aa3Ddd389Cc1E1d165CC4BAfe5");
+    assert_eq!(serde_json::from_value::<Header>(json).unwrap(), header);
+
+    // The first eighteen fields match no fork.
+    let encoded = header.encode().unwrap();
+    let Rlp::List(payload) = Rlp::new(&encoded).unwrap() else {
+        panic!("header is a list");
+    };
+    let mut rest = payload;
+    for _ in 0..18 {
+        rest = Rlp::decode_prefix(rest).unwrap().1;
+    }
+    let mut encoded = Vec::new();
+    rlp::encode_list_payload(&payload[..payload.len() - rest.len()], &mut encoded);
+    assert!(matches!(
+        Header::decode(&encoded),
+        Err(Error::InvalidHeader(_))
+    ));
+}
+
+#[test]
+fn rejects_fork_fields_past_a_missing_one() {
+    let mut header = london();
+    header.requests_hash = Some(B256([8; 32]));
+    for result in [
+        header.fork().map(drop),
+        header.encode().map(drop),
+        header.hash().map(drop),
+    ] {
+        assert!(matches!(result, Err(Error::InvalidHeader(_))), "{result:?}");
+    }
+
+    // The same from JSON, where a node could omit a field.
+    let mut json = serde_json::to_value(london()).unwrap();
+    json["withdrawalsRoot"] = json!(B256([6; 32]));
+    json.as_object_mut().unwrap().remove("baseFeePerGas");
+    let header: Header = serde_json::from_value(json).unwrap();
+    assert!(matches!(header.hash(), Err(Error::InvalidHeader(_))));
+}
+
+#[tokio::test]
+async fn client_checks_reported_hashes() {
+    let genesis_hash = word("0xd4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3");
+    let client = Client::new(
+        MockTransport::new()
+            .on_result("eth_getBlockByNumber", genesis())
+            .on_result("eth_getBlockByHash", genesis()),
+    );
+    let header = client
+        .get_header(BlockNumber::Earliest)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(header.hash().unwrap(), genesis_hash);
+    assert!(client
+        .get_header_by_hash(&genesis_hash)
+        .await
+        .unwrap()
+        .is_some());
+    assert!(matches!(
+        client.get_header_by_hash(&B256([1; 32])).await,
+        Err(Error::InvalidHeader(_))
+    ));
+
+    let mut forged = genesis();
+    forged["gasLimit"] = json!("0x1389");
+    let client = Client::new(MockTransport::new().on_result("eth_getBlockByNumber", forged));
+    assert!(matches!(
+        client.get_header(BlockNumber::Number(0)).await,
+        Err(Error::InvalidHeader(_))
+    ));
+
+    let client = Client::new(MockTransport::new().on_result("eth_getBlockByNumber", Value::Null));
+    assert_eq!(client.get_header(BlockNumber::Latest).await.unwrap(), None);
+}
@@ -0,0 +1,279 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crossbeam_abi::{ContractEvent, Event, Value, U256};
+use crossbeam_abi_macros::abigen;
+use crossbeam_ethereum::rpc::MockTransport;
+use crossbeam_ethereum::{Address, BlockNumber, Client, Error, Filter, Log, LogStream, B256};
+use serde_json::{json, Value as Json};
+
+abigen!(
+    Vault,
+    r#"[
+        {"type": "event", "name": "Locked", "anonymous": false, "inputs": [
+          {"name": "sender", "type": "address", "indexed": true},
+          {"name": "targetChain", "type": "uint64", "indexed": true},
+          {"name": "recipient", "type": "bytes", "indexed": false},
+          {"name": "amount", "type": "uint256", "indexed": false}]}
+    ]"#
+);
+
+use vault::LockedEvent;
+
+const VAULT: Address = Address::new([0xbb; 20]);
+const RANGE_LIMIT: u64 = 1_000;
+
+fn locked_log(block: u64, sender: Address, amount: u64) -> Log {
+    Log {
+        address: VAULT,
+        topics: vec![
+            B256(LockedEvent::TOPIC0),
+            B256(Event::encode_topic(&Value::Address(sender))),
+            B256(Event::encode_topic(&Value::uint(U256::from(56u64)))),
+        ],
+        data: crossbeam_abi::encode(&[
+            Value::Bytes(vec![0xaa; 20]),
+            Value::uint(U256::from(amount)),
+        ]),
+        block_number: Some(block),
+        block_hash: Some(B256([block as u8; 32])),
+        transaction_hash: Some(B256([0x77; 32])),
+        transaction_index: Some(0),
+        log_index: Some(0),
+        removed: false,
+    }
+}
+
+fn quantity(value: &Json) -> u64 {
+    let s = value.as_str().unwrap();
+    u64::from_str_radix(s.trim_start_matches("0x"), 16).unwrap()
+}
+
+/// A node holding `logs` that rejects `eth_getLogs` spans over 1000 blocks.
+fn node(logs: Vec<Log>, head: Arc<AtomicU64>) -> MockTransport {
+    MockTransport::new()
+        .on("eth_blockNumber", move |_| {
+            Ok(json!(format!("{:#x}", head.load(Ordering::SeqCst))))
+        })
+        .on("eth_getLogs", move |params| {
+            let filter = &params[0];
+            let from = quantity(&filter["fromBlock"]);
+            let to = quantity(&filter["toBlock"]);
+            if to - from + 1 > RANGE_LIMIT {
+                return Err(Error::Rpc {
+                    code: -32000,
+                    message: format!("exceed maximum block range: {RANGE_LIMIT}"),
+                    data: None,
+                });
+            }
+            let matching: Vec<&Log> = logs
+                .iter()
+                .filter(|log| (from..=to).contains(&log.block_number.unwrap()))
+                .collect();
+            Ok(json!(matching))
+        })
+}
+
+#[test]
+fn filter_wire_format() {
+    let sender = Address::new([0x11; 20]);
+    let filter = Filter::new()
+        .address(VAULT)
+        .event::<LockedEvent>()
+        .topic(
+            2,
+            [B256(Event::encode_topic(&Value::uint(U256::from(56u64))))],
+        )
+        .blocks(0x10, 0x20);
+    assert_eq!(
+        serde_json::to_value(&filter).unwrap(),
+        json!({
+            "address": format!("{VAULT}"),
+            "topics": [
+                format!("0x{}", hex::encode(LockedEvent::TOPIC0)),
+                null,
+                format!("0x{:064x}", 56),
+            ],
+            "fromBlock": "0x10",
+            "toBlock": "0x20",
+        })
+    );
+
+    let any_sender = Filter::new()
+        .address(VAULT)
+        .address(sender)
+        .topic(0, [B256([1; 32]), B256([2; 32])])
+        .to_block(BlockNumber::Finalized);
+    let wire = serde_json::to_value(&any_sender).unwrap();
+    assert_eq!(wire["address"].as_array().unwrap().len(), 2);
+    assert_eq!(wire["topics"].as_array().unwrap().len(), 1);
+    assert_eq!(wire["topics"][0].as_array().unwrap().len(), 2);
+    assert_eq!(wire["toBlock"], "finalized");
+    assert!(wire.get("fromBlock").is_none());
+
+    let pinned = Filter::new().from_block(1).at_block_hash(B256([9; 32]));
+    let wire = serde_json::to_value(&pinned).unwrap();
+    assert!(wire.get("fromBlock").is_none());
+    assert!(wire.get("blockHash").is_some());
+}
+
+#[test]
+fn filter_matches_logs() {
+    let sender = Address::new([0x11; 20]);
+    let log = locked_log(5, sender, 1);
+    let by_sender = |s: Address| {
+        Filter::new()
+            .address(VAULT)
+            .event::<LockedEvent>()
+            .topic(1, [B256(Event::encode_topic(&Value::Address(s)))])
+    };
+    assert!(Filter::new().matches(&log));
+    assert!(by_sender(sender).matches(&log));
+    assert!(!by_sender(Address::new([0x12; 20])).matches(&log));
+    assert!(!Filter::new().address(sender).matches(&log));
+    assert!(!Filter::new().topic(3, [B256([0; 32])]).matches(&log));
+}
+
+#[test]
+fn log_json_round_trip_and_decoding() {
+    let sender = Address::new([0x11; 20]);
+    let log = locked_log(0x1234, sender, 500);
+    let wire = serde_json::to_value(&log).unwrap();
+    assert_eq!(wire["blockNumber"], "0x1234");
+    assert_eq!(wire["removed"], false);
+    let parsed: Log = serde_json::from_value(wire).unwrap();
+    assert_eq!(parsed, log);
+
+    let pending: Log = serde_json::from_value(json!({
+        "address": format!("{VAULT}"),
+        "topics": [],
+        "data": "0x",
+        "blockNumber": null,
+    }))
+    .unwrap();
+    assert_eq!(pending.block_number, None);
+    assert!(!pending.removed);
+
+    let event = log.decode::<LockedEvent>().unwrap();
+    assert_eq!(event.sender, sender);
+    assert_eq!(event.target_chain, 56);
+    assert_eq!(event.amount, U256::from(500u64));
+
+    let decoded = log.decode_with(LockedEvent::event()).unwrap();
+    assert_eq!(
+        decoded.get("amount"),
+        Some(&Value::uint(U256::from(500u64)))
+    );
+    assert!(pending.decode::<LockedEvent>().is_err());
+}
+
+#[tokio::test]
+async fn backfill_splits_ranges_the_node_rejects() {
+    let logs: Vec<Log> = (0..40)
+        .map(|i| locked_log(i * 250 + 3, Address::new([i as u8; 20]), i))
+        .collect();
+    let head = Arc::new(AtomicU64::new(20_000));
+    let client = Client::new(node(logs.clone(), head));
+
+    let filter = Filter::new()
+        .address(VAULT)
+        .event::<LockedEvent>()
+        .blocks(0, 9_999);
+    let mut stream = LogStream::new(&client, filter).unwrap().max_range(4_000);
+    let mut collected = Vec::new();
+    let mut expected_from = 0;
+    while let Some(batch) = stream.next_batch().await.unwrap() {
+        assert_eq!(batch.from_block, expected_from);
+        assert!(batch.to_block - batch.from_block < RANGE_LIMIT);
+        expected_from = batch.to_block + 1;
+        collected.extend(batch.logs);
+    }
+    assert!(stream.is_done());
+    assert_eq!(stream.next_block(), 10_000);
+    assert_eq!(collected, logs);
+
+    let amounts: Vec<U256> = collected
+        .iter()
+        .map(|log| log.decode::<LockedEvent>().unwrap().amount)
+        .collect();
+    assert_eq!(amounts[39], U256::from(39u64));
+
+    let requests = client.transport().requests();
+    assert!(requests.iter().all(|(method, _)| method == "eth_getLogs"));
+    assert!(requests.len() < 20, "{} requests", requests.len());
+}
+
+#[tokio::test]
+async fn collect_and_resume() {
+    let logs: Vec<Log> = (0..10)
+        .map(|i| locked_log(i * 100, Address::new([1; 20]), i))
+        .collect();
+    let client = Client::new(node(logs.clone(), Arc::new(AtomicU64::new(0))));
+
+    let all = LogStream::new(&client, Filter::new().blocks(0, 999))
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    assert_eq!(all, logs);
+
+    let resumed = LogStream::new(&client, Filter::new().blocks(450, 999))
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    assert_eq!(resumed, logs[5..]);
+}
+
+#[tokio::test]
+async fn follows_the_head_behind_confirmations() {
+    let logs: Vec<Log> = (1..=30)
+        .map(|i| locked_log(i * 10, Address::new([2; 20]), i))
+        .collect();
+    let head = Arc::new(AtomicU64::new(100));
+    let client = Client::new(node(logs, head.clone()));
+
+    let mut stream = LogStream::new(&client, Filter::new().from_block(50))
+        .unwrap()
+        .confirmations(12);
+    let batch = stream.next_batch().await.unwrap().unwrap();
+    assert_eq!((batch.from_block, batch.to_block), (50, 88));
+    assert_eq!(batch.logs.len(), 4);
+    assert_eq!(stream.next_batch().await.unwrap(), None);
+    assert!(!stream.is_done());
+
+    head.store(212, Ordering::SeqCst);
+    let batch = stream.next_batch().await.unwrap().unwrap();
+    assert_eq!((batch.from_block, batch.to_block), (89, 200));
+    assert_eq!(batch.logs.first().unwrap().block_number, Some(90));
+    assert_eq!(batch.logs.last().unwrap().block_number, Some(200));
+    assert_eq!(stream.next_block(), 201);
+}
+
+#[tokio::test]
+async fn rejects_unstreamable_filters_and_surfaces_other_errors() {
+    let client = Client::new(MockTransport::new());
+    assert!(LogStream::new(&client, Filter::new()).is_err());
+    assert!(LogStream::new(&client, Filter::new().from_block(BlockNumber::Latest)).is_err());
+    assert!(LogStream::new(
+        &client,
+        Filter::new().from_block(1).at_block_hash(B256([0; 32]))
+    )
+    .is_err());
+    let following = LogStream::new(&client, Filter::new().from_block(1)).unwrap();
+    assert!(following.collect().await.is_err());
+
+    let failing = Client::new(MockTransport::new().on("eth_getLogs", |_| {
+        Err(Error::Rpc {
+            code: -32000,
+            message: "header not found".to_owned(),
+            data: None,
+        })
+    }));
+    let mut stream = LogStream::new(&failing, Filter::new().blocks(0, 10)).unwrap();
+    assert!(matches!(
+        stream.next_batch().await,
+        Err(Error::Rpc { code: -32000, .. })
+    ));
+    assert_eq!(stream.next_block(), 0);
+}
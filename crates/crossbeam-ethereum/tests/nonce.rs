@@ -0,0 +1,345 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crossbeam_core::signer::Secp256k1Signer;
+use crossbeam_core::U256;
+use crossbeam_ethereum::nonce::{cancellation, replacement, MIN_REPLACEMENT_BUMP};
+use crossbeam_ethereum::rpc::MockTransport;
+use crossbeam_ethereum::{
+    Address, Client, Eip1559Transaction, Error, FileNonceStore, LegacyTransaction,
+    MemoryNonceStore, NonceManager, NonceStore, SignedTransaction, Transaction, TxHash,
+};
+use serde_json::{json, Value};
+
+const CHAIN_ID: u64 = 56;
+
+fn signer() -> Secp256k1Signer {
+    Secp256k1Signer::from_bytes(&[0x46; 32]).unwrap()
+}
+
+fn wallet() -> Address {
+    Address::from_public_key(&signer().uncompressed_public_key())
+}
+
+/// The slice of a node's mempool the nonce manager can observe.
+#[derive(Debug, Default)]
+struct Node {
+    /// Nonces below this are mined.
+    mined: u64,
+    /// Known, unmined transactions by nonce.
+    pool: HashMap<u64, TxHash>,
+}
+
+impl Node {
+    fn pending_count(&self) -> u64 {
+        let mut n = self.mined;
+        while self.pool.contains_key(&n) {
+            n += 1;
+        }
+        n
+    }
+
+    fn mine_through(&mut self, nonce: u64) {
+        self.mined = nonce + 1;
+        self.pool.retain(|n, _| *n > nonce);
+    }
+}
+
+fn node(state: Arc<Mutex<Node>>) -> MockTransport {
+    let counts = state.clone();
+    let sends = state.clone();
+    MockTransport::new()
+        .on("eth_getTransactionCount", move |params| {
+            let node = counts.lock().unwrap();
+            let n = match params[1].as_str().unwrap() {
+                "pending" => node.pending_count(),
+                _ => node.mined,
+            };
+            Ok(json!(format!("{n:#x}")))
+        })
+        .on("eth_sendRawTransaction", move |params| {
+            let raw = hex::decode(&params[0].as_str().unwrap()[2..]).unwrap();
+            let tx = SignedTransaction::decode(&raw).unwrap();
+            let mut node = sends.lock().unwrap();
+            let nonce = tx.transaction.nonce();
+            if nonce < node.mined {
+                return Err(Error::Rpc {
+                    code: -32000,
+                    message: "nonce too low".to_owned(),
+                    data: None,
+                });
+            }
+            if node.pool.get(&nonce) == Some(&tx.hash()) {
+                return Err(Error::Rpc {
+                    code: -32000,
+                    message: "already known".to_owned(),
+                    data: None,
+                });
+            }
+            node.pool.insert(nonce, tx.hash());
+            Ok(json!(tx.hash()))
+        })
+        .on("eth_getTransactionByHash", move |params| {
+            let hash: TxHash = serde_json::from_value(params[0].clone()).unwrap();
+            let node = state.lock().unwrap();
+            Ok(match node.pool.values().any(|h| *h == hash) {
+                true => json!({ "hash": hash }),
+                false => Value::Null,
+            })
+        })
+}
+
+fn transfer(nonce: u64) -> SignedTransaction {
+    Transaction::from(LegacyTransaction {
+        chain_id: Some(CHAIN_ID),
+        nonce,
+        gas_price: U256::from(3_000_000_000u64),
+        gas_limit: 21_000,
+        to: Some(Address::new([0x22; 20])),
+        value: U256::from(1u8),
+        input: Vec::new(),
+    })
+    .sign(&signer())
+    .unwrap()
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("crossbeam-nonce-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[tokio::test]
+async fn concurrent_reservations_are_unique() {
+    let chain = Arc::new(Mutex::new(Node {
+        mined: 7,
+        ..Node::default()
+    }));
+    let manager = NonceManager::new(
+        Client::new(node(chain)),
+        MemoryNonceStore::new(),
+        CHAIN_ID,
+        wallet(),
+    );
+    let reservations = reserve_eight(&manager).await;
+    let mut nonces: Vec<u64> = reservations.into_iter().map(Result::unwrap).collect();
+    nonces.sort();
+    assert_eq!(nonces, (7..15).collect::<Vec<_>>());
+    assert_eq!(manager.state().unwrap().next, 15);
+    let node_queries = manager
+        .client()
+        .transport()
+        .requests()
+        .iter()
+        .filter(|(method, _)| method == "eth_getTransactionCount")
+        .count();
+    assert!(node_queries <= 8);
+
+    assert!(!manager.release(9).unwrap());
+    assert!(manager.release(14).unwrap());
+    assert_eq!(manager.reserve().await.unwrap(), 14);
+}
+
+async fn reserve_eight<T: crossbeam_ethereum::Transport, S: NonceStore>(
+    manager: &NonceManager<T, S>,
+) -> Vec<crossbeam_ethereum::Result<u64>> {
+    let (a, b, c, d) = tokio::join!(
+        manager.reserve(),
+        manager.reserve(),
+        manager.reserve(),
+        manager.reserve()
+    );
+    let (e, f, g, h) = tokio::join!(
+        manager.reserve(),
+        manager.reserve(),
+        manager.reserve(),
+        manager.reserve()
+    );
+    vec![a, b, c, d, e, f, g, h]
+}
+
+#[tokio::test]
+async fn restarts_never_reuse_a_reserved_nonce() {
+    let dir = temp_dir("restart");
+    let chain = Arc::new(Mutex::new(Node::default()));
+    {
+        let manager = NonceManager::new(
+            Client::new(node(chain.clone())),
+            FileNonceStore::new(&dir).unwrap(),
+            CHAIN_ID,
+            wallet(),
+        );
+        for _ in 0..3 {
+            manager.reserve().await.unwrap();
+        }
+        manager.send(&transfer(0)).await.unwrap();
+        manager.send(&transfer(1)).await.unwrap();
+        // Nonce 2 was reserved, then the process died before sending it.
+    }
+
+    let manager = NonceManager::new(
+        Client::new(node(chain.clone())),
+        FileNonceStore::new(&dir).unwrap(),
+        CHAIN_ID,
+        wallet(),
+    );
+    assert_eq!(manager.reserve().await.unwrap(), 3);
+    manager.send(&transfer(3)).await.unwrap();
+
+    let report = manager.reconcile().await.unwrap();
+    assert_eq!(report.mined, 0);
+    assert_eq!(report.gaps, [2]);
+    assert!(report.dropped.is_empty());
+
+    let filler = manager
+        .filler(2, U256::from(3_000_000_000u64))
+        .sign(&signer())
+        .unwrap();
+    assert_eq!(filler.transaction.to(), Some(wallet()));
+    manager.send(&filler).await.unwrap();
+    assert_eq!(chain.lock().unwrap().pending_count(), 4);
+
+    chain.lock().unwrap().mine_through(3);
+    let report = manager.reconcile().await.unwrap();
+    assert_eq!(report.mined, 4);
+    assert_eq!(
+        report.confirmed.iter().map(|(n, _)| *n).collect::<Vec<_>>(),
+        [0, 1, 2, 3]
+    );
+    assert!(report.gaps.is_empty());
+
+    let stored = FileNonceStore::new(&dir)
+        .unwrap()
+        .load(CHAIN_ID, &wallet())
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored, manager.state().unwrap());
+    assert!(stored.pending.is_empty());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn dropped_transactions_are_reported_and_rebroadcast() {
+    let chain = Arc::new(Mutex::new(Node::default()));
+    let manager = NonceManager::new(
+        Client::new(node(chain.clone())),
+        MemoryNonceStore::new(),
+        CHAIN_ID,
+        wallet(),
+    );
+    for nonce in 0..3 {
+        assert_eq!(manager.reserve().await.unwrap(), nonce);
+        manager.send(&transfer(nonce)).await.unwrap();
+    }
+    // Resending what the node already has is not an error.
+    assert_eq!(
+        manager.send(&transfer(2)).await.unwrap(),
+        transfer(2).hash()
+    );
+
+    chain.lock().unwrap().pool.remove(&1);
+    let report = manager.reconcile().await.unwrap();
+    assert_eq!(report.dropped.len(), 1);
+    assert_eq!(report.dropped[0].0, 1);
+    assert_eq!(report.dropped[0].1.hash, transfer(1).hash());
+    // The dropped transaction is still tracked, so its nonce is no gap.
+    assert!(report.gaps.is_empty());
+    assert_eq!(manager.stuck(Duration::ZERO).len(), 3);
+    assert!(manager.stuck(Duration::from_secs(3600)).is_empty());
+
+    assert_eq!(manager.rebroadcast(1).await.unwrap(), transfer(1).hash());
+    assert!(chain.lock().unwrap().pool.contains_key(&1));
+    let report = manager.reconcile().await.unwrap();
+    assert!(report.dropped.is_empty());
+    assert!(report.gaps.is_empty());
+}
+
+#[tokio::test]
+async fn nonces_spent_elsewhere_are_skipped() {
+    let chain = Arc::new(Mutex::new(Node::default()));
+    let manager = NonceManager::new(
+        Client::new(node(chain.clone())),
+        MemoryNonceStore::new(),
+        CHAIN_ID,
+        wallet(),
+    );
+    assert_eq!(manager.reserve().await.unwrap(), 0);
+    manager.send(&transfer(0)).await.unwrap();
+
+    // Another process sent nonces 1 to 4 from the same wallet.
+    {
+        let mut node = chain.lock().unwrap();
+        node.mine_through(2);
+        node.pool.insert(3, transfer(3).hash());
+        node.pool.insert(4, transfer(4).hash());
+    }
+    let report = manager.reconcile().await.unwrap();
+    assert_eq!(report.mined, 3);
+    assert_eq!(report.confirmed.len(), 1);
+    assert!(report.gaps.is_empty());
+    assert_eq!(manager.reserve().await.unwrap(), 5);
+
+    assert!(matches!(manager.record(&transfer(9)), Err(Error::Nonce(_))));
+
+    // A reserved nonce only takes this wallet's transactions for this chain.
+    let nonce = manager.reserve().await.unwrap();
+    let mut foreign_chain = transfer(nonce);
+    let Transaction::Legacy(tx) = &mut foreign_chain.transaction else {
+        unreachable!()
+    };
+    tx.chain_id = Some(1);
+    let foreign_chain = foreign_chain.transaction.sign(&signer()).unwrap();
+    assert!(matches!(
+        manager.record(&foreign_chain),
+        Err(Error::Nonce(_))
+    ));
+    let stranger = Secp256k1Signer::from_bytes(&[0x47; 32]).unwrap();
+    let foreign_sender = transfer(nonce).transaction.sign(&stranger).unwrap();
+    assert!(matches!(
+        manager.record(&foreign_sender),
+        Err(Error::Nonce(_))
+    ));
+    manager.record(&transfer(nonce)).unwrap();
+}
+
+#[test]
+fn replacements_raise_every_fee() {
+    let legacy = transfer(4).transaction;
+    let Transaction::Legacy(bumped) = replacement(&legacy, MIN_REPLACEMENT_BUMP) else {
+        panic!("type changed");
+    };
+    assert_eq!(bumped.gas_price, U256::from(3_300_000_000u64));
+    assert_eq!(bumped.nonce, 4);
+    assert_eq!(bumped.value, U256::from(1u8));
+
+    let dynamic = Transaction::from(Eip1559Transaction {
+        chain_id: CHAIN_ID,
+        nonce: 8,
+        max_priority_fee_per_gas: U256::from(15u8),
+        max_fee_per_gas: U256::from(1_001u64),
+        gas_limit: 90_000,
+        to: Some(Address::new([0x33; 20])),
+        value: U256::from(5u8),
+        input: vec![1, 2, 3],
+        access_list: Vec::new(),
+    });
+    let Transaction::Eip1559(bumped) = replacement(&dynamic, 10) else {
+        panic!("type changed");
+    };
+    // Rounded up, so a bump never falls short of the node's threshold.
+    assert_eq!(bumped.max_priority_fee_per_gas, U256::from(17u8));
+    assert_eq!(bumped.max_fee_per_gas, U256::from(1_102u64));
+
+    let cancel = cancellation(&dynamic, wallet(), 25);
+    assert_eq!(cancel.nonce(), 8);
+    assert_eq!(cancel.to(), Some(wallet()));
+    assert_eq!(cancel.value(), U256::ZERO);
+    assert!(cancel.input().is_empty());
+    assert_eq!(cancel.gas_limit(), 21_000);
+    let Transaction::Eip1559(cancel) = cancel else {
+        panic!("type changed");
+    };
+    assert_eq!(cancel.max_fee_per_gas, U256::from(1_252u64));
+}
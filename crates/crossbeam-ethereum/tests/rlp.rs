@@ -0,0 +1,82 @@
+use crossbeam_core::U256;
+use crossbeam_ethereum::rlp::{self, ListEncoder, Rlp};
+
+#[test]
+fn encodes_the_reference_examples() {
+    assert_eq!(rlp::encode("dog"), hex::decode("83646f67").unwrap());
+    assert_eq!(rlp::encode(""), [0x80]);
+    assert_eq!(rlp::encode(&0u64), [0x80]);
+    assert_eq!(rlp::encode(&15u64), [0x0f]);
+    assert_eq!(rlp::encode(&1024u64), [0x82, 0x04, 0x00]);
+    assert_eq!(rlp::encode(&[0x00u8][..]), [0x00]);
+
+    let mut out = Vec::new();
+    rlp::encode_list(&["cat", "dog"], &mut out);
+    assert_eq!(out, hex::decode("c88363617483646f67").unwrap());
+
+    // The set-theoretic representation of three: [ [], [[]], [ [], [[]] ] ].
+    let empty = ListEncoder::new().into_bytes();
+    let one = {
+        let mut list = ListEncoder::new();
+        list.append_raw(&empty);
+        list.into_bytes()
+    };
+    let mut three = ListEncoder::new();
+    three.append_raw(&empty).append_raw(&one);
+    let mut two = ListEncoder::new();
+    two.append_raw(&empty).append_raw(&one);
+    three.append_raw(&two.into_bytes());
+    assert_eq!(three.into_bytes(), hex::decode("c7c0c1c0c3c0c1c0").unwrap());
+
+    let lorem = "Lorem ipsum dolor sit amet, consectetur adipisicing elit";
+    let encoded = rlp::encode(lorem);
+    assert_eq!(&encoded[..2], [0xb8, 0x38]);
+    assert_eq!(&encoded[2..], lorem.as_bytes());
+
+    let max = rlp::encode(&U256::MAX);
+    assert_eq!(max.len(), 33);
+    assert_eq!(max[0], 0xa0);
+}
+
+#[test]
+fn decodes_what_it_encodes() {
+    let mut list = ListEncoder::new();
+    list.append(&7u64)
+        .append(&U256::from(1_000_000u64))
+        .append("Lorem ipsum dolor sit amet, consectetur adipisicing elit");
+    let encoded = list.into_bytes();
+
+    let items = Rlp::new(&encoded).unwrap().items().unwrap();
+    assert_eq!(items.len(), 3);
+    assert_eq!(items[0].as_u64().unwrap(), 7);
+    assert_eq!(items[1].as_u256().unwrap(), U256::from(1_000_000u64));
+    assert_eq!(items[2].bytes().unwrap().len(), 56);
+}
+
+#[test]
+fn rejects_non_canonical_encodings() {
+    let rejected = [
+        "",           // empty input
+        "8100",       // single byte < 0x80 wrapped in a header
+        "8105",       // same
+        "b800",       // long form for a short string
+        "b90000",     // long length with leading zero
+        "83646f",     // truncated
+        "83646f6767", // trailing bytes
+        "c3646f",     // truncated list
+    ];
+    for input in rejected {
+        let bytes = hex::decode(input).unwrap();
+        assert!(
+            Rlp::new(&bytes)
+                .and_then(|item| item.items().map(drop))
+                .is_err(),
+            "{input} should be rejected"
+        );
+    }
+    assert!(Rlp::new(&[0x82, 0x00, 0x01]).unwrap().as_u64().is_err());
+    assert!(Rlp::new(&hex::decode("89010000000000000000").unwrap())
+        .unwrap()
+        .as_u64()
+        .is_err());
+}
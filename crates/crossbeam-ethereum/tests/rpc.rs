@@ -0,0 +1,338 @@
+use crossbeam_abi::{Revert, U256};
+use crossbeam_abi_macros::abigen;
+use crossbeam_core::Confirmation;
+use crossbeam_ethereum::rpc::jsonrpc::{self, ErrorObject, Request, Response};
+use crossbeam_ethereum::rpc::{Fixture, MockTransport, RecordingTransport};
+use crossbeam_ethereum::{
+    Address, BlockNumber, CallRequest, Client, Error, Provider, Transport, B256,
+};
+use serde_json::{json, Value};
+
+abigen!(
+    Erc20,
+    r#"[
+        {"type": "function", "name": "balanceOf", "stateMutability": "view",
+         "inputs": [{"name": "owner", "type": "address"}],
+         "outputs": [{"name": "", "type": "uint256"}]},
+        {"type": "function", "name": "transfer", "stateMutability": "nonpayable",
+         "inputs": [{"name": "to", "type": "address"}, {"name": "amount", "type": "uint256"}],
+         "outputs": [{"name": "", "type": "bool"}]},
+        {"type": "event", "name": "Transfer", "anonymous": false, "inputs": [
+          {"name": "from", "type": "address", "indexed": true},
+          {"name": "to", "type": "address", "indexed": true},
+          {"name": "value", "type": "uint256", "indexed": false}]}
+    ]"#
+);
+
+const TOKEN: Address = Address::new([0xbb; 20]);
+const RELAYER: Address = Address::new([0x11; 20]);
+const RECIPIENT: Address = Address::new([0x22; 20]);
+const TX: B256 = B256([0x77; 32]);
+
+fn replayed() -> Client<MockTransport> {
+    let fixtures = include_str!("fixtures/relayer.json");
+    Client::new(MockTransport::new().replay_json(fixtures).unwrap())
+}
+
+#[tokio::test]
+async fn relayer_session_replays_offline() {
+    let client = replayed();
+    assert_eq!(client.chain_id().await.unwrap(), 56);
+
+    let balance = client
+        .call_contract(
+            TOKEN,
+            &erc20::BalanceOfCall { owner: RELAYER },
+            BlockNumber::Latest,
+        )
+        .await
+        .unwrap();
+    assert_eq!(balance, U256::from(1_000_000u64));
+
+    let transfer = erc20::TransferCall {
+        to: RECIPIENT,
+        amount: U256::from(1_000u64),
+    };
+    let Err(Error::Reverted { message, data }) = client
+        .call_contract(TOKEN, &transfer, BlockNumber::Latest)
+        .await
+    else {
+        panic!("expected a revert");
+    };
+    assert_eq!(message, "execution reverted: insufficient balance");
+    assert_eq!(
+        Revert::decode(&data, &[]),
+        Revert::Reason("insufficient balance".to_owned())
+    );
+
+    let raw = [0xf8, 0x6c, 0x01, 0x85];
+    assert_eq!(client.send_raw_transaction(&raw).await.unwrap(), TX);
+    assert_eq!(client.get_transaction_receipt(&TX).await.unwrap(), None);
+    let receipt = client.get_transaction_receipt(&TX).await.unwrap().unwrap();
+    assert!(receipt.succeeded());
+    assert_eq!(receipt.block_number, 40_000_000);
+    assert_eq!(receipt.gas_used, 46_097);
+    assert_eq!(receipt.transaction_type, Some(2));
+    assert_eq!(
+        receipt.effective_gas_price,
+        Some(U256::from(3_000_000_000u64))
+    );
+    let event = receipt.logs[0].decode::<erc20::TransferEvent>().unwrap();
+    assert_eq!(
+        (event.from, event.to, event.value),
+        (RELAYER, RECIPIENT, U256::from(1_000u64))
+    );
+
+    let history = client
+        .fee_history(3, BlockNumber::Latest, &[25.0, 75.0])
+        .await
+        .unwrap();
+    assert_eq!(history.oldest_block, 39_999_998);
+    assert_eq!(history.base_fee_per_gas.len(), 4);
+    assert_eq!(history.next_base_fee(), Some(U256::from(1_250_000_000u64)));
+    assert_eq!(history.reward[2], [U256::ZERO, U256::from(100_000_000u64)]);
+    assert_eq!(history.gas_used_ratio, [0.5, 1.0, 0.9]);
+
+    assert!(client.transport().unused_fixtures().is_empty());
+    assert!(matches!(
+        client.block_number().await,
+        Err(Error::Rpc { code: -32601, .. })
+    ));
+}
+
+#[tokio::test]
+async fn fixtures_are_used_once_and_match_params() {
+    let client = Client::new(MockTransport::new().replay([
+        Fixture {
+            method: "eth_getBalance".to_owned(),
+            params: json!(["0x1111111111111111111111111111111111111111", "latest"]),
+            result: Some(json!("0x64")),
+            error: None,
+        },
+        Fixture {
+            method: "eth_getBalance".to_owned(),
+            params: Value::Null,
+            result: Some(json!("0x0")),
+            error: None,
+        },
+    ]));
+    let other = Address::new([0x33; 20]);
+    assert_eq!(
+        client
+            .get_balance(&other, BlockNumber::Latest)
+            .await
+            .unwrap(),
+        U256::ZERO
+    );
+    assert_eq!(
+        client
+            .get_balance(&RELAYER, BlockNumber::Latest)
+            .await
+            .unwrap(),
+        U256::from(100u8)
+    );
+    assert!(client
+        .get_balance(&RELAYER, BlockNumber::Latest)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn batches_fail_per_request() {
+    let client = Client::new(
+        MockTransport::new()
+            .on_result("eth_blockNumber", json!("0x10"))
+            .on_result("eth_gasPrice", json!("0x3b9aca00")),
+    );
+    let mut batch = client.batch();
+    let head = batch.add::<String>("eth_blockNumber", json!([]));
+    let missing = batch.add::<String>("eth_syncing", json!([]));
+    let price = batch.add::<U256>("eth_gasPrice", json!([]));
+    assert_eq!(batch.len(), 3);
+    let mut results = batch.send().await.unwrap();
+    assert_eq!(results.take(price).unwrap(), U256::from(1_000_000_000u64));
+    assert_eq!(results.take(head).unwrap(), "0x10");
+    assert!(matches!(
+        results.take(missing),
+        Err(Error::Rpc { code: -32601, .. })
+    ));
+    assert_eq!(client.transport().requests().len(), 3);
+    assert!(client.batch().send().await.is_ok());
+}
+
+#[tokio::test]
+async fn recorded_sessions_replay_identically() {
+    let live = MockTransport::new()
+        .on_result("eth_chainId", json!("0x1"))
+        .on("eth_estimateGas", |_| {
+            Err(Error::Rpc {
+                code: 3,
+                message: "execution reverted".to_owned(),
+                data: Some(json!("0x")),
+            })
+        });
+    let recorder = RecordingTransport::new(live);
+    let call = CallRequest::new(TOKEN, vec![1, 2, 3]).sender(RELAYER);
+    {
+        let client = Client::new(&recorder);
+        assert_eq!(client.chain_id().await.unwrap(), 1);
+        assert!(matches!(
+            client.estimate_gas(&call).await,
+            Err(Error::Reverted { .. })
+        ));
+        assert!(client.gas_price().await.is_err());
+    }
+    assert_eq!(recorder.fixtures().len(), 3);
+
+    let replay = Client::new(
+        MockTransport::new()
+            .replay_json(&recorder.to_json())
+            .unwrap(),
+    );
+    assert_eq!(replay.chain_id().await.unwrap(), 1);
+    let Err(Error::Reverted { data, .. }) = replay.estimate_gas(&call).await else {
+        panic!("expected a revert");
+    };
+    assert!(data.is_empty());
+    assert!(matches!(
+        replay.gas_price().await,
+        Err(Error::Rpc { code: -32601, .. })
+    ));
+}
+
+#[tokio::test]
+async fn client_is_a_provider() {
+    let receipt = |status: &str| {
+        json!({
+            "transactionHash": TX,
+            "transactionIndex": "0x0",
+            "blockHash": B256([9; 32]),
+            "blockNumber": "0x64",
+            "from": RELAYER,
+            "to": TOKEN,
+            "gasUsed": "0x5208",
+            "cumulativeGasUsed": "0x5208",
+            "status": status,
+            "logs": [],
+        })
+    };
+    let finalized = |n: &str| {
+        let n = n.to_owned();
+        move |params: &Value| {
+            assert_eq!(params[0], "finalized");
+            Ok(json!({ "number": n }))
+        }
+    };
+    let confirmation = |transport: MockTransport| async move {
+        Client::new(transport)
+            .transaction_confirmation(&TX)
+            .await
+            .unwrap()
+    };
+
+    let unknown = MockTransport::new()
+        .on_result("eth_getTransactionReceipt", Value::Null)
+        .on_result("eth_getTransactionByHash", Value::Null);
+    assert_eq!(confirmation(unknown).await, Confirmation::NotFound);
+
+    let pending = MockTransport::new()
+        .on_result("eth_getTransactionReceipt", Value::Null)
+        .on_result("eth_getTransactionByHash", json!({ "hash": TX }));
+    assert_eq!(confirmation(pending).await, Confirmation::Pending);
+
+    let mined = MockTransport::new()
+        .on_result("eth_getTransactionReceipt", receipt("0x1"))
+        .on("eth_getBlockByNumber", finalized("0x63"));
+    assert_eq!(
+        confirmation(mined).await,
+        Confirmation::Confirmed { height: 100 }
+    );
+
+    let final_ = MockTransport::new()
+        .on_result("eth_getTransactionReceipt", receipt("0x1"))
+        .on("eth_getBlockByNumber", finalized("0x64"));
+    assert_eq!(
+        confirmation(final_).await,
+        Confirmation::Finalized { height: 100 }
+    );
+
+    let reverted = MockTransport::new().on_result("eth_getTransactionReceipt", receipt("0x0"));
+    assert!(matches!(
+        confirmation(reverted).await,
+        Confirmation::Failed {
+            height: Some(100),
+            ..
+        }
+    ));
+
+    let client = Client::new(
+        MockTransport::new().on("eth_getTransactionCount", |params| {
+            assert_eq!(params[1], "pending");
+            Ok(json!("0x2a"))
+        }),
+    );
+    assert_eq!(client.transaction_count(&RELAYER).await.unwrap(), 42);
+}
+
+#[test]
+fn call_request_wire_format() {
+    let call = CallRequest::new(TOKEN, vec![0xab])
+        .sender(RELAYER)
+        .value(U256::from(16u8))
+        .gas(21_000);
+    let wire = serde_json::to_value(&call).unwrap();
+    assert_eq!(wire["input"], "0xab");
+    assert_eq!(wire["value"], "0x10");
+    assert_eq!(wire["gas"], "0x5208");
+    assert!(wire.get("gasPrice").is_none());
+    assert_eq!(serde_json::from_value::<CallRequest>(wire).unwrap(), call);
+    assert_eq!(
+        serde_json::to_value(CallRequest::default()).unwrap(),
+        json!({})
+    );
+}
+
+#[test]
+fn batch_responses_are_matched_by_id() {
+    let requests = [
+        Request::new(1, "eth_chainId", json!([])),
+        Request::new(2, "eth_blockNumber", json!([])),
+    ];
+    let responses = vec![
+        Response::failure(
+            2,
+            ErrorObject {
+                code: -32000,
+                message: "busy".to_owned(),
+                data: None,
+            },
+        ),
+        Response::success(1, json!("0x1")),
+    ];
+    let results = jsonrpc::match_batch(&requests, responses).unwrap();
+    assert_eq!(results[0].as_ref().unwrap(), "0x1");
+    assert!(matches!(results[1], Err(Error::Rpc { code: -32000, .. })));
+
+    let partial = vec![Response::success(1, json!("0x1"))];
+    assert!(jsonrpc::match_batch(&requests, partial).is_err());
+
+    let wire: Response = serde_json::from_str(r#"{"jsonrpc":"2.0","id":7,"result":null}"#).unwrap();
+    assert_eq!(wire.into_result().unwrap(), Value::Null);
+    assert_eq!(
+        serde_json::to_value(&requests[0]).unwrap(),
+        json!({"jsonrpc": "2.0", "id": 1, "method": "eth_chainId", "params": []})
+    );
+}
+
+#[tokio::test]
+async fn default_batch_goes_through_request() {
+    let transport = MockTransport::new().on_result("eth_chainId", json!("0x1"));
+    let results = transport
+        .request_batch(vec![
+            ("eth_chainId".to_owned(), json!([])),
+            ("eth_chainId".to_owned(), json!([])),
+        ])
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 2);
+}
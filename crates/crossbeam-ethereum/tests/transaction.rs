@@ -0,0 +1,220 @@
+use crossbeam_core::signer::Secp256k1Signer;
+use crossbeam_core::U256;
+use crossbeam_ethereum::{
+    AccessListItem, Address, Eip1559Transaction, Eip2930Transaction, Error, LegacyTransaction,
+    Signature, SignedTransaction, Transaction,
+};
+
+fn signer() -> Secp256k1Signer {
+    Secp256k1Signer::from_bytes(&[0x46; 32]).unwrap()
+}
+
+fn sender() -> Address {
+    "0x9d8A62f656a8d1615C1294fd71e9CFb3E4855A4F"
+        .parse()
+        .unwrap()
+}
+
+fn recipient() -> Address {
+    Address::new([0x35; 20])
+}
+
+fn ether(n: u64) -> U256 {
+    U256::from(n) * U256::from(10u64).pow(U256::from(18u8))
+}
+
+fn access_list() -> Vec<AccessListItem> {
+    vec![AccessListItem {
+        address: "0xde0b295669a9fd93d5f28d9ec85e40f4cb697bae"
+            .parse()
+            .unwrap(),
+        storage_keys: vec![
+            "0x0000000000000000000000000000000000000000000000000000000000000003"
+                .parse()
+                .unwrap(),
+            "0x0000000000000000000000000000000000000000000000000000000000000007"
+                .parse()
+                .unwrap(),
+        ],
+    }]
+}
+
+/// Signs `tx`, checks the signing hash and raw encoding, and checks that
+/// decoding the raw bytes gives back the same transaction and sender.
+fn check(tx: Transaction, signing_hash: &str, raw: &str) -> SignedTransaction {
+    assert_eq!(tx.signing_hash().to_string(), signing_hash);
+    let signed = tx.sign(&signer()).unwrap();
+    assert_eq!(format!("0x{}", hex::encode(signed.encode())), raw);
+
+    let decoded = SignedTransaction::decode(&signed.encode()).unwrap();
+    assert_eq!(decoded, signed);
+    assert_eq!(decoded.recover_signer().unwrap(), sender());
+    signed
+}
+
+#[test]
+fn eip155_example_transaction() {
+    // The worked example from EIP-155.
+    let tx = LegacyTransaction {
+        chain_id: Some(1),
+        nonce: 9,
+        gas_price: U256::from(20_000_000_000u64),
+        gas_limit: 21_000,
+        to: Some(recipient()),
+        value: ether(1),
+        input: Vec::new(),
+    };
+    let tx = Transaction::from(tx);
+    assert_eq!(
+        hex::encode(tx.encode_for_signing()),
+        "ec098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080018080"
+    );
+    let signed = check(
+        tx,
+        "0xdaf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53",
+        "0xf86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83",
+    );
+    assert_eq!(signed.signature.legacy_v(Some(1)), Some(37));
+}
+
+#[test]
+fn pre_eip155_legacy_transaction() {
+    let tx = LegacyTransaction {
+        chain_id: None,
+        nonce: 0,
+        gas_price: U256::from(1u8),
+        gas_limit: 21_000,
+        to: Some(Address::ZERO),
+        value: U256::from(1u8),
+        input: Vec::new(),
+    };
+    check(
+        tx.into(),
+        "0xeb8ec270408ca95b624ded213a4895567583fc645a83bb5f41ff939cf6da0b62",
+        "0xf85f800182520894000000000000000000000000000000000000000001801ba022f10c6ee6e5f1dfe0a1563ef4dc4155c05a328e0641ae4c408d522075253de3a06d03640008495ca1394a4eee9cfc7d287f3872da64bf365d77d8c322fd698742",
+    );
+}
+
+#[test]
+fn legacy_chain_ids_must_fit_v() {
+    let largest = (u64::MAX - 36) / 2;
+    let signature = Signature {
+        y_parity: true,
+        ..Signature::default()
+    };
+    assert_eq!(signature.legacy_v(Some(largest)), Some(u64::MAX - 1));
+    assert_eq!(signature.legacy_v(Some(largest + 1)), None);
+    assert_eq!(signature.legacy_v(Some(u64::MAX)), None);
+
+    let tx = |chain_id| -> Transaction {
+        LegacyTransaction {
+            chain_id: Some(chain_id),
+            gas_limit: 21_000,
+            ..Default::default()
+        }
+        .into()
+    };
+    assert!(tx(largest).sign(&signer()).is_ok());
+    assert!(matches!(
+        tx(largest + 1).sign(&signer()),
+        Err(Error::InvalidTransaction(_))
+    ));
+}
+
+#[test]
+fn eip2930_transaction_with_access_list() {
+    let tx = Eip2930Transaction {
+        chain_id: 1,
+        nonce: 3,
+        gas_price: U256::from(30_000_000_000u64),
+        gas_limit: 50_000,
+        to: Some(recipient()),
+        value: U256::from(1_000_000_000_000_000u64),
+        input: hex::decode("a9059cbb").unwrap(),
+        access_list: access_list(),
+    };
+    let signed = check(
+        tx.into(),
+        "0xbf2abd3e39f6af1bdf4a208c991a19b591ac19e72d737b3852b523a2f626191e",
+        "0x01f8cd01038506fc23ac0082c35094353535353535353535353535353535353535353587038d7ea4c6800084a9059cbbf85bf85994de0b295669a9fd93d5f28d9ec85e40f4cb697baef842a00000000000000000000000000000000000000000000000000000000000000003a0000000000000000000000000000000000000000000000000000000000000000701a0b9bc36483ffadd4feb77ff46d0e1e0a2b8cff6abf1488bfa20144662236a447aa0356374c0d6dc59ce6265c776e61d311e9c4c2a72307c4d645508e187df036e36",
+    );
+    assert_eq!(
+        signed.hash().to_string(),
+        "0x401996675f965e2d5ecce3affe7cae50824fd8fc1a132ec9720b843abb8f0baa"
+    );
+}
+
+#[test]
+fn eip1559_transfer_on_bsc() {
+    let tx = Eip1559Transaction {
+        chain_id: 56,
+        nonce: 42,
+        max_priority_fee_per_gas: U256::from(1_000_000_000u64),
+        max_fee_per_gas: U256::from(100_000_000_000u64),
+        gas_limit: 21_000,
+        to: Some(recipient()),
+        value: ether(1),
+        input: Vec::new(),
+        access_list: Vec::new(),
+    };
+    let signed = check(
+        tx.into(),
+        "0xe9e4ff355ded7e3942f5597c11045194d7a7ff0de251e745af1a8712e6b71968",
+        "0x02f873382a843b9aca0085174876e800825208943535353535353535353535353535353535353535880de0b6b3a764000080c080a06c0ea4d31a153259b93f04b54a25d7d44e2328e4ea3753732f803afdf6ff5787a036d52934e04bbd0c8c4db00f1966ddb94309c3b5dd02e2321875695f6a994fbb",
+    );
+    assert_eq!(
+        signed.hash().to_string(),
+        "0x9e2a14def2cf703bc9e38fa06500c013d1723988620556a19d56710119e93e38"
+    );
+}
+
+#[test]
+fn eip1559_contract_creation() {
+    let tx = Eip1559Transaction {
+        chain_id: 1,
+        nonce: 0,
+        max_priority_fee_per_gas: U256::from(2_000_000_000u64),
+        max_fee_per_gas: U256::from(40_000_000_000u64),
+        gas_limit: 1_000_000,
+        to: None,
+        value: U256::ZERO,
+        input: hex::decode("6080604052348015600f57600080fd5b50603f80601d6000396000f3fe6080604052600080fdfea2646970667358221220").unwrap(),
+        access_list: access_list(),
+    };
+    let signed = check(
+        tx.into(),
+        "0xa875e499e88adc7b1940638b5972732679872d038c2afa358363f3cc853a5944",
+        "0x02f8e5018084773594008509502f9000830f42408080b16080604052348015600f57600080fd5b50603f80601d6000396000f3fe6080604052600080fdfea2646970667358221220f85bf85994de0b295669a9fd93d5f28d9ec85e40f4cb697baef842a00000000000000000000000000000000000000000000000000000000000000003a0000000000000000000000000000000000000000000000000000000000000000780a092a39564412c2351d0aafa594ec5613b2160f8ac399805158c6221c2ec4014cca02259ce1aa230e09237d880027ab2a3325fe8d972881c74043a05bdfe3f17060c",
+    );
+    assert_eq!(signed.transaction.to(), None);
+    assert_eq!(
+        signed.hash().to_string(),
+        "0x14929d0f44f5b00f5276e6d6475d94ff820b0c17adaef70a851994a702561902"
+    );
+}
+
+#[test]
+fn decoding_rejects_malformed_input() {
+    assert!(SignedTransaction::decode(&[]).is_err());
+    assert!(SignedTransaction::decode(&[0x03, 0xc0]).is_err());
+    assert!(SignedTransaction::decode(&[0x02, 0xc0]).is_err());
+
+    // Flip the signature to its high-s twin: still decodes, but the sender
+    // cannot be recovered.
+    let tx: Transaction = LegacyTransaction {
+        chain_id: Some(1),
+        gas_limit: 21_000,
+        ..Default::default()
+    }
+    .into();
+    let mut signed = tx.sign(&signer()).unwrap();
+    let n = U256::from_str_radix(
+        "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141",
+        16,
+    )
+    .unwrap();
+    signed.signature.s = n - signed.signature.s;
+    signed.signature.y_parity = !signed.signature.y_parity;
+    let decoded = SignedTransaction::decode(&signed.encode()).unwrap();
+    assert!(decoded.recover_signer().is_err());
+}
@@ -0,0 +1,301 @@
+use crossbeam_core::U256;
+use crossbeam_ethereum::proof::{
+    receipt_proof, receipts_root, verify_receipt, Account, Receipt, EMPTY_CODE_HASH,
+};
+use crossbeam_ethereum::rpc::MockTransport;
+use crossbeam_ethereum::trie::{verify_proof, Trie, EMPTY_ROOT};
+use crossbeam_ethereum::{
+    keccak256, AccountProof, Address, BlockNumber, Client, Error, StorageProof, TransactionReceipt,
+    B256,
+};
+use serde_json::json;
+
+const TRANSFER: &str = "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
+
+fn word(s: &str) -> B256 {
+    s.parse().unwrap()
+}
+
+fn nodes(hex_nodes: &[&str]) -> Vec<Vec<u8>> {
+    hex_nodes.iter().map(|n| hex::decode(n).unwrap()).collect()
+}
+
+fn padded(byte: u8) -> String {
+    format!("0x{}{}", "00".repeat(12), format!("{byte:02x}").repeat(20))
+}
+
+/// Twenty receipts mixing legacy, access-list and EIP-1559 transactions,
+/// failures, and zero to two ERC-20 transfer logs each. The expected root
+/// and proofs were computed with alloy.
+fn block_receipts() -> Vec<TransactionReceipt> {
+    (0..20u64)
+        .map(|i| {
+            let logs: Vec<_> = (0..i % 3)
+                .map(|j| {
+                    json!({
+                        "address": Address::new([0xbb; 20]),
+                        "topics": [TRANSFER, padded((i + j) as u8), padded(0x22)],
+                        "data": format!("0x{:064x}", 1000 * i + j),
+                        "removed": false,
+                    })
+                })
+                .collect();
+            let tx_type = ["0x0", "0x2", "0x1"][(i % 3) as usize];
+            serde_json::from_value(json!({
+                "transactionHash": B256([i as u8; 32]),
+                "transactionIndex": format!("{i:#x}"),
+                "blockHash": B256([0xbc; 32]),
+                "blockNumber": "0x1",
+                "from": Address::new([0x11; 20]),
+                "to": Address::new([0xbb; 20]),
+                "gasUsed": "0x5208",
+                "cumulativeGasUsed": format!("{:#x}", 21_000 * (i + 1) + i * 1000),
+                "status": if i % 7 == 5 { "0x0" } else { "0x1" },
+                "type": tx_type,
+                "logs": logs,
+            }))
+            .unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn builds_and_verifies_the_reference_trie() {
+    let mut trie = Trie::new();
+    assert_eq!(trie.root(), EMPTY_ROOT);
+    assert_eq!(
+        verify_proof(&EMPTY_ROOT, b"dog", &trie.proof(b"dog")).unwrap(),
+        None
+    );
+
+    trie.insert(*b"doe", *b"reindeer");
+    trie.insert(*b"dog", *b"puppy");
+    trie.insert(*b"dogglesworth", *b"cat");
+    let root = trie.root();
+    assert_eq!(
+        root,
+        word("0x8aad789dff2f538bca5d8ea56e8abe10f4c7ba3a5dea95fea4cd6e7c3a1168d3")
+    );
+    for (key, value) in [
+        (&b"doe"[..], &b"reindeer"[..]),
+        (b"dog", b"puppy"),
+        (b"dogglesworth", b"cat"),
+    ] {
+        let proof = trie.proof(key);
+        assert_eq!(
+            verify_proof(&root, key, &proof).unwrap().as_deref(),
+            Some(value)
+        );
+    }
+    for absent in [&b"do"[..], b"dogg", b"cat", b""] {
+        assert_eq!(
+            verify_proof(&root, absent, &trie.proof(absent)).unwrap(),
+            None
+        );
+    }
+
+    // An empty value deletes.
+    trie.insert(*b"cat", *b"meow");
+    trie.insert(*b"cat", Vec::new());
+    assert_eq!(trie.root(), root);
+    assert_eq!(trie.len(), 3);
+}
+
+#[test]
+fn rejects_forged_and_partial_proofs() {
+    let mut trie = Trie::new();
+    for i in 0..64u64 {
+        trie.insert(keccak256(i.to_be_bytes()).0, vec![i as u8 + 1; 40]);
+    }
+    let root = trie.root();
+    let key = keccak256(7u64.to_be_bytes()).0;
+    let proof = trie.proof(&key);
+    assert!(proof.len() > 1);
+    assert_eq!(
+        verify_proof(&root, &key, &proof).unwrap(),
+        Some(vec![8; 40])
+    );
+
+    let mut forged = proof.clone();
+    let leaf = forged.last_mut().unwrap();
+    *leaf.last_mut().unwrap() ^= 1;
+    assert!(matches!(
+        verify_proof(&root, &key, &forged),
+        Err(Error::InvalidProof(_))
+    ));
+
+    let short = &proof[..proof.len() - 1];
+    assert!(verify_proof(&root, &key, short).is_err());
+
+    let mut long = proof.clone();
+    long.push(proof[0].clone());
+    assert!(verify_proof(&root, &key, &long).is_err());
+
+    // The right path under another key proves nothing.
+    let other = keccak256(8u64.to_be_bytes()).0;
+    assert!(verify_proof(&root, &other, &proof).map_or(true, |v| v.is_none()));
+    assert!(verify_proof(&B256([1; 32]), &key, &proof).is_err());
+}
+
+/// An account with three storage slots, and the absent account
+/// `0x9999…99`, in a six-account state. Proofs were computed with alloy.
+fn account_proof() -> AccountProof {
+    let state = [
+        "f8d18080a0ea922efd1d63ee32774263b4ee0f5cba95fe319ff084076e14d991b3de9c2199a0942bda8bb680caea4a957832e0df4ea980454f982308d59c35e492e29379adb6a0056b1a28071e19ee9a1b30c2fb4814fb55db4825d3045fe197696172cf4a0ebf80a0e11fb47ed06d75f407b37803491955526cd9937a9d9d3f7892213afb86d51d8880a06d352239a744755515c3190b3194a77e7275f4cebe4f5aef27f1a57b050fe2a18080808080a045054049ffb7ab094b83374bb3dfdf44e582dad39da54f6342f2d41915192b668080",
+        "f871a03ab0a4443bbea3fbe4d0e1503d11ff1367842fb0c8b28a5c8550f27599a40751b84ef84c02881bc16d674ec80000a013bf4c82804053d0926af9be328afbf8a0aa80f4e5278f430d6d7523aeaa5dbea007ad118d6cc8642c86c03827f276d8b791a65e5c99a3845faf186be720a1455d",
+    ];
+    let storage_root = "f871a02ea0e9ef629961d1615144831a7df497ebc5c434b9eb8f33e0cb491d1ea01e4980a0f73cea67884580eec8c3f6d0746360906cf897bf812183520e51b89a12166cfe8080808080808080a038b224cdad1072fc3e9bfdfab598188dbc4ac167b80a4d924e959cc95da6ad1c8080808080";
+    let hex_list =
+        |nodes: &[&str]| -> Vec<String> { nodes.iter().map(|n| format!("0x{n}")).collect() };
+    serde_json::from_value(json!({
+        "address": "0x2222222222222222222222222222222222222222",
+        "accountProof": hex_list(&state),
+        "balance": "0x1bc16d674ec80000",
+        "codeHash": "0x07ad118d6cc8642c86c03827f276d8b791a65e5c99a3845faf186be720a1455d",
+        "nonce": "0x2",
+        "storageHash": "0x13bf4c82804053d0926af9be328afbf8a0aa80f4e5278f430d6d7523aeaa5dbe",
+        "storageProof": [
+            {
+                "key": "0x1",
+                "value": "0xdeadbeef",
+                "proof": hex_list(&[
+                    storage_root,
+                    "e7a0310e2d527612073b26eecdfd717e6a320cf44b4afac2b0732d9fcbe2b7fa0cf68584deadbeef",
+                ]),
+            },
+            {
+                "key": "0x0000000000000000000000000000000000000000000000000000000000000002",
+                "value": "0x0",
+                "proof": hex_list(&[storage_root]),
+            },
+        ],
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn account_and_storage_proofs() {
+    let state_root = word("0x5663b8dbb149a20ffcfa2c51d0fcf4f876358ee3bf096162b640e4e4ee73bcdc");
+    let client = Client::new(MockTransport::new().on("eth_getProof", |params| {
+        assert_eq!(params[1], json!([B256([0; 32]), B256([1; 32])]));
+        assert_eq!(params[2], "0x10");
+        Ok(serde_json::to_value(account_proof()).unwrap())
+    }));
+    let proof = client
+        .get_proof(
+            &Address::new([0x22; 20]),
+            &[B256([0; 32]), B256([1; 32])],
+            BlockNumber::Number(16),
+        )
+        .await
+        .unwrap();
+    assert_eq!(proof, account_proof());
+    assert_eq!(proof.storage_proof[0].key, word(&format!("{:#066x}", 1)));
+    proof.verify(&state_root).unwrap();
+    assert_eq!(
+        Account::decode(&proof.account().encode()).unwrap(),
+        proof.account()
+    );
+
+    let mut rich = proof.clone();
+    rich.balance += U256::from(1u8);
+    assert!(matches!(
+        rich.verify(&state_root),
+        Err(Error::InvalidProof(_))
+    ));
+
+    let mut slot = proof.clone();
+    slot.storage_proof[1].value = U256::from(5u8);
+    assert!(slot.verify(&state_root).is_err());
+    slot.storage_proof[1].value = U256::ZERO;
+    slot.storage_proof[0].value = U256::ZERO;
+    assert!(slot.verify(&state_root).is_err());
+
+    // The same path proves 0x9999…99 absent.
+    let absent = AccountProof {
+        address: Address::new([0x99; 20]),
+        balance: U256::ZERO,
+        nonce: 0,
+        code_hash: EMPTY_CODE_HASH,
+        storage_hash: B256::ZERO,
+        storage_proof: vec![StorageProof {
+            key: B256::ZERO,
+            value: U256::ZERO,
+            proof: Vec::new(),
+        }],
+        ..proof.clone()
+    };
+    absent.verify(&state_root).unwrap();
+    let funded = AccountProof {
+        balance: U256::from(1u8),
+        ..absent.clone()
+    };
+    assert!(funded.verify(&state_root).is_err());
+}
+
+#[tokio::test]
+async fn receipt_trie_matches_the_block() {
+    let receipts = block_receipts();
+    let root = word("0xec421eb571ab46dc9dca034c65b945edc4bddf25d21390345c25ebedf6080ebe");
+    assert_eq!(receipts_root(&receipts).unwrap(), root);
+
+    let expected = nodes(&[
+        "f871a0134d1d7965d12540c4a86e01dbd60eb3ad1ba163a4472579f785d9f41c83b0e6a0e3d8eea7e31956a9c9693280d07dd97b653dd4f0a16f717fcace276575ca4178808080808080a0e58215be848c1293dd381210359d84485553000a82b67410406d183b42adbbdd8080808080808080",
+        "f901f180a0386e6a0eccc0cf65d9d34ef998e38f847c9e21d0fbcb31f82a59760c97cd9934a061e5c923814f81cfb125dbe9691df8f887927d51a9c2e2e34a23312b8f123455a0cd98a8fd77d3fe08de3277f1671e093c3537d98fa5d6b7d8a65e0cc3befd9088a0bd2c69bf03d0084ae9b70bcc10a506834431e6f0f97565363926341afac02283a02c3e8b2fcd10ad6b26653ecffe80d344aa1b2d6deec5e79c43e4bf99dea9cb42a077e7a8052b38224e35ea2050476de6b5095eb058d5300aff460283a5605030e4a0123e93d1b71ca55be50b66f4319a2faf048ef5eb9eb036d323e674d1595677e0a0126941389827cfd96bef35aedc0015b6376fd62e6e26f0b3681896e9d96c0a75a01ccea43db2bcbd7de15763d530135ebc29282d5bd8f7378d81c1d7d851a790aaa016d1adde8402eb84931204d8fd653d265541d9ee623deab4867bc17a8de90d6ba02ce0e5b7a07a0ce08dcf77347bdd9406f5beb4540aee15f1c32557c14da5c680a02033c3efa2865454587e23a42fdd4c4b187c02b20fdf9578b1edd50bd8888706a0b4c53e3aef40a90e6582b7e54fa971832ab25d22ce67bd2c5ef1679cbde46d42a044ba87c4dff3edffc781666fd49b6b0a2f0596f4686c74d756a02d2c251ea1cfa0481e0fa94e67f61ad73270bd1b5f6b7d0c62575579838ff392b7be60e7110ad480",
+    ]);
+    let proof = receipt_proof(&receipts, 13).unwrap();
+    assert_eq!(proof.len(), 3);
+    assert_eq!(proof[..2], expected[..]);
+
+    let receipt = verify_receipt(&root, 13, &proof).unwrap();
+    assert_eq!(receipt, Receipt::from_rpc(&receipts[13]).unwrap());
+    assert_eq!(receipt.transaction_type, 2);
+    assert!(receipt.success);
+    assert_eq!(receipt.logs[0].data, receipts[13].logs[0].data);
+    assert!(
+        !verify_receipt(&root, 5, &receipt_proof(&receipts, 5).unwrap())
+            .unwrap()
+            .success
+    );
+    assert!(verify_receipt(&root, 12, &proof).is_err());
+    assert!(receipt_proof(&receipts, 20).is_err());
+
+    // A provider that alters a receipt no longer matches the header.
+    let mut altered = receipts.clone();
+    altered[3].logs = receipts[4].logs.clone();
+    assert_ne!(receipts_root(&altered).unwrap(), root);
+
+    let mut pre_byzantium = receipts.clone();
+    pre_byzantium[0].status = None;
+    assert!(receipts_root(&pre_byzantium).is_err());
+
+    let client = Client::new(
+        MockTransport::new().on("eth_getBlockReceipts", move |params| {
+            assert_eq!(params[0], "0x1");
+            Ok(serde_json::to_value(&receipts).unwrap())
+        }),
+    );
+    let fetched = client
+        .get_block_receipts(BlockNumber::Number(1))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(receipts_root(&fetched).unwrap(), root);
+}
+
+#[test]
+fn receipt_encoding() {
+    let receipts = block_receipts();
+    let legacy = Receipt::from_rpc(&receipts[0]).unwrap();
+    assert_eq!(legacy.encode()[..4], [0xf9, 0x01, 0x08, 0x01]);
+    assert_eq!(legacy.logs_bloom, [0; 256]);
+
+    let access_list = Receipt::from_rpc(&receipts[5]).unwrap();
+    let encoded = access_list.encode();
+    assert_eq!(encoded[..6], [0x01, 0xf9, 0x02, 0x45, 0x80, 0x83]);
+    assert_eq!(Receipt::decode(&encoded).unwrap(), access_list);
+    assert_eq!(
+        hex::encode(&access_list.logs_bloom[..16]),
+        "00000000000002000000000000000000"
+    );
+}
@@ -0,0 +1,90 @@
+//! Associated token accounts: the canonical token account of a wallet for a
+//! mint, at a PDA of the associated token program.
+
+use crate::error::{Error, Result};
+use crate::instruction::{AccountMeta, Instruction};
+use crate::pda::find_program_address;
+use crate::system::SYSTEM_PROGRAM_ID;
+use crate::token::is_token_program;
+use crate::Pubkey;
+
+/// `ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL`.
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey = Pubkey::new([
+    140, 151, 37, 143, 78, 36, 137, 241, 187, 61, 16, 41, 20, 142, 13, 131, 11, 90, 19, 153, 218,
+    255, 16, 132, 4, 142, 123, 216, 219, 233, 248, 89,
+]);
+
+const CREATE: u8 = 0;
+const CREATE_IDEMPOTENT: u8 = 1;
+
+/// The associated token account of `wallet` for `mint` under
+/// `token_program`, with its bump seed. The token program is part of the
+/// seeds, so Token and Token-2022 accounts for the same mint differ.
+pub fn find_associated_token_address(
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<(Pubkey, u8)> {
+    if !is_token_program(token_program) {
+        return Err(Error::InvalidSeeds(format!(
+            "{token_program} is not a token program"
+        )));
+    }
+    find_program_address(
+        &[&wallet.0, &token_program.0, &mint.0],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+}
+
+/// [`find_associated_token_address`] without the bump.
+pub fn associated_token_address(
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<Pubkey> {
+    find_associated_token_address(wallet, mint, token_program).map(|(address, _)| address)
+}
+
+fn create_instruction(
+    tag: u8,
+    payer: &Pubkey,
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<Instruction> {
+    let address = associated_token_address(wallet, mint, token_program)?;
+    Ok(Instruction::new(
+        ASSOCIATED_TOKEN_PROGRAM_ID,
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(address, false),
+            AccountMeta::new_readonly(*wallet, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(*token_program, false),
+        ],
+        vec![tag],
+    ))
+}
+
+/// Creates the associated token account of `wallet` for `mint`, funded by
+/// `payer`. Fails on chain if the account exists.
+pub fn create_associated_token_account(
+    payer: &Pubkey,
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<Instruction> {
+    create_instruction(CREATE, payer, wallet, mint, token_program)
+}
+
+/// [`create_associated_token_account`], succeeding without change if the
+/// account already exists.
+pub fn create_associated_token_account_idempotent(
+    payer: &Pubkey,
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<Instruction> {
+    create_instruction(CREATE_IDEMPOTENT, payer, wallet, mint, token_program)
+}
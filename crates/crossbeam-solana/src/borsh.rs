@@ -0,0 +1,559 @@
+//! Borsh, the binary format of Solana program arguments and account data,
+//! driven by types known only at runtime.
+//!
+//! Values are described by [`Type`] and carried by [`Value`]; named structs
+//! and enums are looked up in [`Types`], which an Anchor IDL fills in.
+//! Integers are little-endian, collections and strings carry a `u32` length,
+//! options a one-byte tag and enums a one-byte variant index. Decoding is
+//! strict: tags must be canonical, strings valid UTF-8 and floats not NaN.
+//! Encoding never rounds, so an `f32` only takes values it holds exactly.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::error::{Error, Result};
+use crate::Pubkey;
+
+/// Deepest nesting of defined types a value may have, bounding recursion
+/// through self-referential types.
+const MAX_DEPTH: usize = 64;
+
+/// A Borsh type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Type {
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    F32,
+    F64,
+    /// Length-prefixed bytes.
+    Bytes,
+    String,
+    Pubkey,
+    /// Length-prefixed `T`s.
+    Vec(Box<Type>),
+    /// `N` `T`s without a prefix.
+    Array(Box<Type>, usize),
+    Option(Box<Type>),
+    /// An option with a four-byte tag, as in `solana_program::COption`.
+    COption(Box<Type>),
+    /// A struct, enum or alias defined in [`Types`].
+    Defined(String),
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Bool => f.write_str("bool"),
+            Type::U8 => f.write_str("u8"),
+            Type::U16 => f.write_str("u16"),
+            Type::U32 => f.write_str("u32"),
+            Type::U64 => f.write_str("u64"),
+            Type::U128 => f.write_str("u128"),
+            Type::I8 => f.write_str("i8"),
+            Type::I16 => f.write_str("i16"),
+            Type::I32 => f.write_str("i32"),
+            Type::I64 => f.write_str("i64"),
+            Type::I128 => f.write_str("i128"),
+            Type::F32 => f.write_str("f32"),
+            Type::F64 => f.write_str("f64"),
+            Type::Bytes => f.write_str("bytes"),
+            Type::String => f.write_str("string"),
+            Type::Pubkey => f.write_str("pubkey"),
+            Type::Vec(inner) => write!(f, "vec<{inner}>"),
+            Type::Array(inner, len) => write!(f, "[{inner}; {len}]"),
+            Type::Option(inner) => write!(f, "option<{inner}>"),
+            Type::COption(inner) => write!(f, "coption<{inner}>"),
+            Type::Defined(name) => f.write_str(name),
+        }
+    }
+}
+
+/// A named field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    pub name: String,
+    pub ty: Type,
+}
+
+/// The fields of a struct or enum variant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fields {
+    Named(Vec<Field>),
+    /// Positional fields; empty for unit structs and variants.
+    Tuple(Vec<Type>),
+}
+
+impl Fields {
+    fn len(&self) -> usize {
+        match self {
+            Fields::Named(fields) => fields.len(),
+            Fields::Tuple(types) => types.len(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Variant {
+    pub name: String,
+    pub fields: Fields,
+}
+
+/// The definition behind a [`Type::Defined`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeDef {
+    Struct(Fields),
+    Enum(Vec<Variant>),
+    /// Another name for `Type`.
+    Alias(Type),
+}
+
+/// A runtime Borsh value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    /// Any unsigned integer.
+    Uint(u128),
+    /// Any signed integer.
+    Int(i128),
+    /// `f32` or `f64`.
+    Float(f64),
+    /// `bytes`. Also accepted for `vec<u8>` and `[u8; N]`.
+    Bytes(Vec<u8>),
+    String(String),
+    Pubkey(Pubkey),
+    /// `vec<T>` or `[T; N]`.
+    Array(Vec<Value>),
+    Option(Option<Box<Value>>),
+    /// A struct with named fields, in declaration order.
+    Struct(Vec<(String, Value)>),
+    /// A struct with positional fields.
+    Tuple(Vec<Value>),
+    /// An enum variant and its fields, a [`Value::Struct`] or
+    /// [`Value::Tuple`].
+    Enum(String, Box<Value>),
+}
+
+impl Value {
+    /// A unit enum variant.
+    pub fn unit_variant(name: &str) -> Self {
+        Value::Enum(name.to_owned(), Box::new(Value::Tuple(Vec::new())))
+    }
+
+    /// The field called `name` of a struct.
+    pub fn field(&self, name: &str) -> Option<&Value> {
+        match self {
+            Value::Struct(fields) => fields.iter().find(|(n, _)| n == name).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            Value::Bool(_) => "bool",
+            Value::Uint(_) => "unsigned integer",
+            Value::Int(_) => "signed integer",
+            Value::Float(_) => "float",
+            Value::Bytes(_) => "bytes",
+            Value::String(_) => "string",
+            Value::Pubkey(_) => "pubkey",
+            Value::Array(_) => "array",
+            Value::Option(_) => "option",
+            Value::Struct(_) => "struct",
+            Value::Tuple(_) => "tuple",
+            Value::Enum(..) => "enum",
+        }
+    }
+}
+
+fn invalid(reason: impl Into<String>) -> Error {
+    Error::InvalidData(reason.into())
+}
+
+/// Named type definitions, against which [`Type::Defined`] resolves.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Types(BTreeMap<String, TypeDef>);
+
+impl Types {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, name: impl Into<String>, def: TypeDef) {
+        self.0.insert(name.into(), def);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&TypeDef> {
+        self.0.get(name)
+    }
+
+    fn resolve(&self, name: &str) -> Result<&TypeDef> {
+        self.get(name).ok_or_else(|| Error::NotFound {
+            kind: "type",
+            name: name.to_owned(),
+        })
+    }
+
+    /// Encodes `value` as `ty`.
+    pub fn encode(&self, ty: &Type, value: &Value) -> Result<Vec<u8>> {
+        let mut out = Vec::new();
+        self.encode_into(ty, value, &mut out, 0)?;
+        Ok(out)
+    }
+
+    /// Encodes `values` one after another as `fields`, the way instruction
+    /// arguments are laid out.
+    pub fn encode_fields(&self, fields: &[Field], values: &[Value]) -> Result<Vec<u8>> {
+        if fields.len() != values.len() {
+            return Err(Error::TypeMismatch {
+                expected: format!("{} values", fields.len()),
+                actual: format!("{} values", values.len()),
+            });
+        }
+        let mut out = Vec::new();
+        for (field, value) in fields.iter().zip(values) {
+            self.encode_into(&field.ty, value, &mut out, 0)?;
+        }
+        Ok(out)
+    }
+
+    /// Decodes a whole `ty` from `data`, rejecting trailing bytes.
+    pub fn decode(&self, ty: &Type, data: &[u8]) -> Result<Value> {
+        let mut input = data;
+        let value = self.decode_from(ty, &mut input, 0)?;
+        expect_end(input)?;
+        Ok(value)
+    }
+
+    /// Decodes a `ty` from the front of `data`, returning it with the bytes
+    /// that follow. Account data is often longer than what it holds.
+    pub fn decode_prefix<'a>(&self, ty: &Type, data: &'a [u8]) -> Result<(Value, &'a [u8])> {
+        let mut input = data;
+        let value = self.decode_from(ty, &mut input, 0)?;
+        Ok((value, input))
+    }
+
+    /// Decodes `fields` one after another from the whole of `data`.
+    pub fn decode_fields(&self, fields: &[Field], data: &[u8]) -> Result<Vec<Value>> {
+        let mut input = data;
+        let values = fields
+            .iter()
+            .map(|field| self.decode_from(&field.ty, &mut input, 0))
+            .collect::<Result<_>>()?;
+        expect_end(input)?;
+        Ok(values)
+    }
+
+    fn encode_into(&self, ty: &Type, value: &Value, out: &mut Vec<u8>, depth: usize) -> Result<()> {
+        let mismatch = || Error::TypeMismatch {
+            expected: ty.to_string(),
+            actual: value.kind().to_owned(),
+        };
+        match (ty, value) {
+            (Type::Bool, Value::Bool(b)) => out.push(u8::from(*b)),
+            (Type::U8, Value::Uint(n)) => out.push(u8::try_from(*n).map_err(|_| mismatch())?),
+            (Type::U16, Value::Uint(n)) => {
+                out.extend_from_slice(&u16::try_from(*n).map_err(|_| mismatch())?.to_le_bytes())
+            }
+            (Type::U32, Value::Uint(n)) => {
+                out.extend_from_slice(&u32::try_from(*n).map_err(|_| mismatch())?.to_le_bytes())
+            }
+            (Type::U64, Value::Uint(n)) => {
+                out.extend_from_slice(&u64::try_from(*n).map_err(|_| mismatch())?.to_le_bytes())
+            }
+            (Type::U128, Value::Uint(n)) => out.extend_from_slice(&n.to_le_bytes()),
+            (Type::I8, Value::Int(n)) => {
+                out.extend_from_slice(&i8::try_from(*n).map_err(|_| mismatch())?.to_le_bytes())
+            }
+            (Type::I16, Value::Int(n)) => {
+                out.extend_from_slice(&i16::try_from(*n).map_err(|_| mismatch())?.to_le_bytes())
+            }
+            (Type::I32, Value::Int(n)) => {
+                out.extend_from_slice(&i32::try_from(*n).map_err(|_| mismatch())?.to_le_bytes())
+            }
+            (Type::I64, Value::Int(n)) => {
+                out.extend_from_slice(&i64::try_from(*n).map_err(|_| mismatch())?.to_le_bytes())
+            }
+            (Type::I128, Value::Int(n)) => out.extend_from_slice(&n.to_le_bytes()),
+            (Type::F32 | Type::F64, Value::Float(x)) if x.is_nan() => {
+                return Err(invalid("Borsh cannot encode NaN"))
+            }
+            (Type::F32, Value::Float(x)) => {
+                let narrowed = *x as f32;
+                if f64::from(narrowed) != *x {
+                    return Err(invalid(format!("{x} is not exactly an f32")));
+                }
+                out.extend_from_slice(&narrowed.to_le_bytes())
+            }
+            (Type::F64, Value::Float(x)) => out.extend_from_slice(&x.to_le_bytes()),
+            (Type::Bytes, Value::Bytes(bytes)) => encode_prefixed(bytes, out)?,
+            (Type::Vec(inner), Value::Bytes(bytes)) if **inner == Type::U8 => {
+                encode_prefixed(bytes, out)?
+            }
+            (Type::String, Value::String(s)) => encode_prefixed(s.as_bytes(), out)?,
+            (Type::Pubkey, Value::Pubkey(key)) => out.extend_from_slice(&key.0),
+            (Type::Vec(inner), Value::Array(items)) => {
+                encode_len(items.len(), out)?;
+                for item in items {
+                    let start = out.len();
+                    self.encode_into(inner, item, out, depth)?;
+                    if out.len() == start {
+                        return Err(zero_sized(inner));
+                    }
+                }
+            }
+            (Type::Array(inner, len), Value::Bytes(bytes))
+                if **inner == Type::U8 && bytes.len() == *len =>
+            {
+                out.extend_from_slice(bytes);
+            }
+            (Type::Array(inner, len), Value::Array(items)) if items.len() == *len => {
+                for item in items {
+                    self.encode_into(inner, item, out, depth)?;
+                }
+            }
+            (Type::Option(inner), Value::Option(item)) => match item {
+                None => out.push(0),
+                Some(item) => {
+                    out.push(1);
+                    self.encode_into(inner, item, out, depth)?;
+                }
+            },
+            (Type::COption(inner), Value::Option(item)) => match item {
+                None => out.extend_from_slice(&0u32.to_le_bytes()),
+                Some(item) => {
+                    out.extend_from_slice(&1u32.to_le_bytes());
+                    self.encode_into(inner, item, out, depth)?;
+                }
+            },
+            (Type::Defined(name), value) => {
+                let depth = enter(depth)?;
+                match self.resolve(name)? {
+                    TypeDef::Alias(ty) => self.encode_into(ty, value, out, depth)?,
+                    TypeDef::Struct(fields) => self.encode_struct(fields, value, out, depth)?,
+                    TypeDef::Enum(variants) => {
+                        let Value::Enum(variant, fields) = value else {
+                            return Err(mismatch());
+                        };
+                        let index = variants
+                            .iter()
+                            .position(|v| v.name == *variant)
+                            .ok_or_else(|| Error::NotFound {
+                                kind: "variant",
+                                name: format!("{name}::{variant}"),
+                            })?;
+                        out.push(
+                            u8::try_from(index).map_err(|_| {
+                                invalid(format!("{name} has more than 256 variants"))
+                            })?,
+                        );
+                        self.encode_struct(&variants[index].fields, fields, out, depth)?;
+                    }
+                }
+            }
+            _ => return Err(mismatch()),
+        }
+        Ok(())
+    }
+
+    fn encode_struct(
+        &self,
+        fields: &Fields,
+        value: &Value,
+        out: &mut Vec<u8>,
+        depth: usize,
+    ) -> Result<()> {
+        let mismatch = |actual: String| Error::TypeMismatch {
+            expected: format!("{} fields", fields.len()),
+            actual,
+        };
+        match (fields, value) {
+            (Fields::Named(fields), Value::Struct(values)) => {
+                if fields.len() != values.len() {
+                    return Err(mismatch(format!("{} fields", values.len())));
+                }
+                for (field, (name, value)) in fields.iter().zip(values) {
+                    if field.name != *name {
+                        return Err(Error::TypeMismatch {
+                            expected: format!("field `{}`", field.name),
+                            actual: format!("field `{name}`"),
+                        });
+                    }
+                    self.encode_into(&field.ty, value, out, depth)?;
+                }
+            }
+            (Fields::Tuple(types), Value::Tuple(values)) => {
+                if types.len() != values.len() {
+                    return Err(mismatch(format!("{} fields", values.len())));
+                }
+                for (ty, value) in types.iter().zip(values) {
+                    self.encode_into(ty, value, out, depth)?;
+                }
+            }
+            _ => return Err(mismatch(value.kind().to_owned())),
+        }
+        Ok(())
+    }
+
+    fn decode_from(&self, ty: &Type, input: &mut &[u8], depth: usize) -> Result<Value> {
+        Ok(match ty {
+            Type::Bool => match take::<1>(input)? {
+                [0] => Value::Bool(false),
+                [1] => Value::Bool(true),
+                [b] => return Err(invalid(format!("bool byte {b}"))),
+            },
+            Type::U8 => Value::Uint(take::<1>(input)?[0].into()),
+            Type::U16 => Value::Uint(u16::from_le_bytes(take(input)?).into()),
+            Type::U32 => Value::Uint(u32::from_le_bytes(take(input)?).into()),
+            Type::U64 => Value::Uint(u64::from_le_bytes(take(input)?).into()),
+            Type::U128 => Value::Uint(u128::from_le_bytes(take(input)?)),
+            Type::I8 => Value::Int(i8::from_le_bytes(take(input)?).into()),
+            Type::I16 => Value::Int(i16::from_le_bytes(take(input)?).into()),
+            Type::I32 => Value::Int(i32::from_le_bytes(take(input)?).into()),
+            Type::I64 => Value::Int(i64::from_le_bytes(take(input)?).into()),
+            Type::I128 => Value::Int(i128::from_le_bytes(take(input)?)),
+            Type::F32 => float(f32::from_le_bytes(take(input)?).into())?,
+            Type::F64 => float(f64::from_le_bytes(take(input)?))?,
+            Type::Bytes => Value::Bytes(take_prefixed(input)?.to_vec()),
+            Type::String => Value::String(
+                String::from_utf8(take_prefixed(input)?.to_vec())
+                    .map_err(|_| invalid("string is not UTF-8"))?,
+            ),
+            Type::Pubkey => Value::Pubkey(Pubkey::new(take(input)?)),
+            Type::Vec(inner) => {
+                let len = u32::from_le_bytes(take(input)?) as usize;
+                // Each item must take at least a byte, so the prefix cannot
+                // ask for more items than the input holds.
+                let mut items = Vec::with_capacity(len.min(input.len()));
+                for _ in 0..len {
+                    let remaining = input.len();
+                    items.push(self.decode_from(inner, input, depth)?);
+                    if input.len() == remaining {
+                        return Err(zero_sized(inner));
+                    }
+                }
+                Value::Array(items)
+            }
+            Type::Array(inner, len) => Value::Array(
+                (0..*len)
+                    .map(|_| self.decode_from(inner, input, depth))
+                    .collect::<Result<_>>()?,
+            ),
+            Type::Option(inner) => match take::<1>(input)? {
+                [0] => Value::Option(None),
+                [1] => Value::Option(Some(Box::new(self.decode_from(inner, input, depth)?))),
+                [tag] => return Err(invalid(format!("option tag {tag}"))),
+            },
+            Type::COption(inner) => match u32::from_le_bytes(take(input)?) {
+                0 => Value::Option(None),
+                1 => Value::Option(Some(Box::new(self.decode_from(inner, input, depth)?))),
+                tag => return Err(invalid(format!("option tag {tag}"))),
+            },
+            Type::Defined(name) => {
+                let depth = enter(depth)?;
+                match self.resolve(name)? {
+                    TypeDef::Alias(ty) => self.decode_from(ty, input, depth)?,
+                    TypeDef::Struct(fields) => self.decode_struct(fields, input, depth)?,
+                    TypeDef::Enum(variants) => {
+                        let [index] = take::<1>(input)?;
+                        let variant = variants
+                            .get(usize::from(index))
+                            .ok_or_else(|| invalid(format!("{name} has no variant {index}")))?;
+                        Value::Enum(
+                            variant.name.clone(),
+                            Box::new(self.decode_struct(&variant.fields, input, depth)?),
+                        )
+                    }
+                }
+            }
+        })
+    }
+
+    fn decode_struct(&self, fields: &Fields, input: &mut &[u8], depth: usize) -> Result<Value> {
+        Ok(match fields {
+            Fields::Named(fields) => Value::Struct(
+                fields
+                    .iter()
+                    .map(|field| {
+                        Ok((
+                            field.name.clone(),
+                            self.decode_from(&field.ty, input, depth)?,
+                        ))
+                    })
+                    .collect::<Result<_>>()?,
+            ),
+            Fields::Tuple(types) => Value::Tuple(
+                types
+                    .iter()
+                    .map(|ty| self.decode_from(ty, input, depth))
+                    .collect::<Result<_>>()?,
+            ),
+        })
+    }
+}
+
+fn enter(depth: usize) -> Result<usize> {
+    if depth >= MAX_DEPTH {
+        return Err(invalid(format!("types nested more than {MAX_DEPTH} deep")));
+    }
+    Ok(depth + 1)
+}
+
+fn encode_len(len: usize, out: &mut Vec<u8>) -> Result<()> {
+    let len = u32::try_from(len).map_err(|_| invalid(format!("length {len} exceeds u32")))?;
+    out.extend_from_slice(&len.to_le_bytes());
+    Ok(())
+}
+
+/// Like `borsh`, refuses vecs of zero-sized items, whose length prefix
+/// alone could demand unbounded work.
+fn zero_sized(ty: &Type) -> Error {
+    invalid(format!("vec of zero-sized {ty}"))
+}
+
+fn encode_prefixed(bytes: &[u8], out: &mut Vec<u8>) -> Result<()> {
+    encode_len(bytes.len(), out)?;
+    out.extend_from_slice(bytes);
+    Ok(())
+}
+
+fn take<const N: usize>(input: &mut &[u8]) -> Result<[u8; N]> {
+    if input.len() < N {
+        return Err(invalid("unexpected end of data"));
+    }
+    let (head, rest) = input.split_at(N);
+    *input = rest;
+    Ok(head.try_into().expect("N bytes"))
+}
+
+fn take_prefixed<'a>(input: &mut &'a [u8]) -> Result<&'a [u8]> {
+    let len = u32::from_le_bytes(take(input)?) as usize;
+    if input.len() < len {
+        return Err(invalid("unexpected end of data"));
+    }
+    let (head, rest) = input.split_at(len);
+    *input = rest;
+    Ok(head)
+}
+
+fn float(x: f64) -> Result<Value> {
+    if x.is_nan() {
+        return Err(invalid("Borsh forbids NaN"));
+    }
+    Ok(Value::Float(x))
+}
+
+fn expect_end(input: &[u8]) -> Result<()> {
+    if !input.is_empty() {
+        return Err(invalid(format!("{} trailing bytes", input.len())));
+    }
+    Ok(())
+}
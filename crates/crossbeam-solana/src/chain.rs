@@ -0,0 +1,79 @@
+use async_trait::async_trait;
+use crossbeam_core::{Chain, Confirmation, Network, Signer, Transfer};
+
+use crate::error::{Error, Result};
+use crate::message::Message;
+use crate::provider::Provider;
+use crate::signature::Signature;
+use crate::system;
+use crate::transaction::Transaction;
+use crate::Pubkey;
+
+/// [`Chain`] implementation for Solana.
+#[derive(Debug, Clone)]
+pub struct SolanaChain<P> {
+    provider: P,
+}
+
+impl<P: Provider> SolanaChain<P> {
+    pub fn new(provider: P) -> Self {
+        Self { provider }
+    }
+
+    pub fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    fn unsupported(&self, operation: &'static str) -> Error {
+        crossbeam_core::Error::Unsupported {
+            network: Network::Solana,
+            operation,
+        }
+        .into()
+    }
+}
+
+#[async_trait]
+impl<P: Provider> Chain for SolanaChain<P> {
+    type Address = Pubkey;
+    type Transaction = Transaction;
+    type SignedTransaction = Transaction;
+    type TxId = Signature;
+    type Error = Error;
+
+    fn network(&self) -> Network {
+        Network::Solana
+    }
+
+    fn parse_address(&self, s: &str) -> Result<Pubkey> {
+        Ok(s.parse()?)
+    }
+
+    /// Builds a legacy system-program transfer paid for by the sender,
+    /// referencing the node's latest blockhash.
+    async fn build_transfer(&self, transfer: &Transfer<Pubkey>) -> Result<Transaction> {
+        let asset = transfer.amount.asset();
+        if asset.network() != Network::Solana || !asset.is_native() {
+            return Err(self.unsupported("transfers of non-native assets"));
+        }
+        let lamports = transfer.amount.to_u64()?;
+        let blockhash = self.provider.latest_blockhash().await?;
+        let instruction = system::transfer(&transfer.from, &transfer.to, lamports);
+        let message = Message::new(&[instruction], &transfer.from, blockhash)?;
+        Ok(Transaction::new(message))
+    }
+
+    fn sign(&self, mut tx: Transaction, signers: &[&dyn Signer]) -> Result<Transaction> {
+        tx.sign(signers)?;
+        tx.ensure_signed()?;
+        Ok(tx)
+    }
+
+    async fn submit(&self, tx: &Transaction) -> Result<Signature> {
+        self.provider.send_transaction(&tx.encode()).await
+    }
+
+    async fn confirmation(&self, id: &Signature) -> Result<Confirmation> {
+        self.provider.signature_confirmation(id).await
+    }
+}
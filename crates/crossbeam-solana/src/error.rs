@@ -0,0 +1,51 @@
+use thiserror::Error;
+
+/// Errors produced by the Solana adapter.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum Error {
+    #[error(transparent)]
+    Core(#[from] crossbeam_core::Error),
+    #[error("invalid base58: {0}")]
+    Base58(#[from] bs58::decode::Error),
+    #[error("expected {expected} bytes, got {actual}")]
+    InvalidLength { expected: usize, actual: usize },
+    #[error("invalid transaction: {0}")]
+    InvalidTransaction(String),
+    #[error("invalid signature: {0}")]
+    InvalidSignature(String),
+    #[error("invalid seeds: {0}")]
+    InvalidSeeds(String),
+    #[error("invalid account data: {0}")]
+    InvalidAccount(String),
+    /// Borsh data is malformed or does not match its type.
+    #[error("invalid Borsh data: {0}")]
+    InvalidData(String),
+    /// A value does not fit the type it is encoded as.
+    #[error("type mismatch: expected {expected}, got {actual}")]
+    TypeMismatch { expected: String, actual: String },
+    /// The IDL has no item of that name.
+    #[error("no {kind} named `{name}` in the IDL")]
+    NotFound { kind: &'static str, name: String },
+    /// The IDL is well-formed JSON but not a usable Anchor IDL.
+    #[error("invalid IDL: {0}")]
+    InvalidIdl(String),
+    #[error("invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("provider error: {0}")]
+    Provider(String),
+    /// The node answered with a JSON-RPC error object.
+    #[error("JSON-RPC error {code}: {message}")]
+    Rpc {
+        code: i64,
+        message: String,
+        data: Option<serde_json::Value>,
+    },
+    /// The request never got a JSON-RPC answer.
+    #[error("transport error: {0}")]
+    Transport(String),
+    #[error("invalid base64: {0}")]
+    Base64(#[from] base64::DecodeError),
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
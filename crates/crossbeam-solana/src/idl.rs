@@ -0,0 +1,594 @@
+//! Anchor IDLs: encoding instructions and decoding accounts and events of
+//! Anchor programs from the JSON `anchor build` emits.
+//!
+//! Both the current IDL format (Anchor 0.30 and later), which lists every
+//! discriminator, and the legacy one, which leaves them to be derived from
+//! names, are accepted. Anchor prefixes instruction data, account data and
+//! event data with an eight-byte discriminator, the first bytes of the
+//! SHA-256 of `global:<instruction>`, `account:<Account>` or
+//! `event:<Event>`, and lays the rest out in Borsh.
+
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::borsh::{Field, Fields, Type, TypeDef, Types, Value, Variant};
+use crate::error::{Error, Result};
+use crate::instruction::{AccountMeta, Instruction};
+use crate::Pubkey;
+
+/// The discriminator Anchor derives for `name` in `namespace`.
+pub fn discriminator(namespace: &str, name: &str) -> [u8; 8] {
+    let hash = Sha256::digest(format!("{namespace}:{name}"));
+    hash[..8].try_into().expect("8 bytes")
+}
+
+/// The discriminator of the instruction handler `name`. Legacy IDLs spell
+/// handlers in camelCase; the hash is over the snake_case Rust name.
+pub fn instruction_discriminator(name: &str) -> [u8; 8] {
+    discriminator("global", &to_snake_case(name))
+}
+
+/// The discriminator of accounts of type `name`.
+pub fn account_discriminator(name: &str) -> [u8; 8] {
+    discriminator("account", name)
+}
+
+/// The discriminator of events of type `name`.
+pub fn event_discriminator(name: &str) -> [u8; 8] {
+    discriminator("event", name)
+}
+
+/// `initializeV2` to `initialize_v2`, `getATA` to `get_ata`.
+fn to_snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut out = String::with_capacity(name.len() + 4);
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            if prev.is_lowercase() || prev.is_ascii_digit() || (prev.is_uppercase() && next_lower) {
+                out.push('_');
+            }
+        }
+        out.extend(c.to_lowercase());
+    }
+    out
+}
+
+/// An account an instruction takes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdlAccountItem {
+    pub name: String,
+    pub writable: bool,
+    pub signer: bool,
+    /// Optional accounts may be left out; the program id takes their place.
+    pub optional: bool,
+    /// The account's fixed address, for programs and sysvars.
+    pub address: Option<Pubkey>,
+}
+
+/// An instruction handler.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdlInstruction {
+    pub name: String,
+    pub discriminator: Vec<u8>,
+    /// The accounts in the order the handler expects them, with composite
+    /// account groups flattened.
+    pub accounts: Vec<IdlAccountItem>,
+    pub args: Vec<Field>,
+}
+
+/// An account or event type: its discriminator, and the name of its
+/// definition in [`Idl::types`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdlTypeRef {
+    pub name: String,
+    pub discriminator: Vec<u8>,
+}
+
+/// A program error, reported as custom error `code`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct IdlError {
+    pub code: u32,
+    pub name: String,
+    #[serde(default)]
+    pub msg: Option<String>,
+}
+
+/// An Anchor program interface.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Idl {
+    /// The program id, if the IDL records it.
+    pub address: Option<Pubkey>,
+    pub name: String,
+    pub version: String,
+    pub instructions: Vec<IdlInstruction>,
+    pub accounts: Vec<IdlTypeRef>,
+    pub events: Vec<IdlTypeRef>,
+    pub errors: Vec<IdlError>,
+    /// Every Borsh-serialized type the IDL defines. Zero-copy and generic
+    /// types are left out.
+    pub types: Types,
+}
+
+fn not_found(kind: &'static str, name: &str) -> Error {
+    Error::NotFound {
+        kind,
+        name: name.to_owned(),
+    }
+}
+
+impl Idl {
+    pub fn from_json(json: &str) -> Result<Self> {
+        let raw: RawIdl = serde_json::from_str(json)?;
+        raw.into_idl()
+    }
+
+    pub fn instruction(&self, name: &str) -> Result<&IdlInstruction> {
+        self.instructions
+            .iter()
+            .find(|ix| ix.name == name)
+            .ok_or_else(|| not_found("instruction", name))
+    }
+
+    pub fn account(&self, name: &str) -> Result<&IdlTypeRef> {
+        self.accounts
+            .iter()
+            .find(|account| account.name == name)
+            .ok_or_else(|| not_found("account", name))
+    }
+
+    pub fn event(&self, name: &str) -> Result<&IdlTypeRef> {
+        self.events
+            .iter()
+            .find(|event| event.name == name)
+            .ok_or_else(|| not_found("event", name))
+    }
+
+    /// The error a failed transaction reported as custom error `code`.
+    pub fn error(&self, code: u32) -> Option<&IdlError> {
+        self.errors.iter().find(|error| error.code == code)
+    }
+
+    /// Instruction data: the discriminator, then `args` in Borsh.
+    pub fn encode_instruction_data(&self, name: &str, args: &[Value]) -> Result<Vec<u8>> {
+        let instruction = self.instruction(name)?;
+        let mut data = instruction.discriminator.clone();
+        data.extend(self.types.encode_fields(&instruction.args, args)?);
+        Ok(data)
+    }
+
+    /// A call to handler `name` of `program_id`. Accounts are given by
+    /// name; those with a fixed address may be left out, as may optional
+    /// ones.
+    pub fn build_instruction(
+        &self,
+        program_id: &Pubkey,
+        name: &str,
+        accounts: &[(&str, Pubkey)],
+        args: &[Value],
+    ) -> Result<Instruction> {
+        let instruction = self.instruction(name)?;
+        if let Some((unknown, _)) = accounts
+            .iter()
+            .find(|(given, _)| !instruction.accounts.iter().any(|a| a.name == *given))
+        {
+            return Err(not_found("account", &format!("{name}.{unknown}")));
+        }
+        let metas = instruction
+            .accounts
+            .iter()
+            .map(|item| {
+                let given = accounts
+                    .iter()
+                    .find(|(given, _)| *given == item.name)
+                    .map(|(_, key)| *key);
+                match given.or(item.address) {
+                    Some(pubkey) => Ok(AccountMeta {
+                        pubkey,
+                        is_signer: item.signer,
+                        is_writable: item.writable,
+                    }),
+                    None if item.optional => Ok(AccountMeta::new_readonly(*program_id, false)),
+                    None => Err(not_found("account", &format!("{name}.{}", item.name))),
+                }
+            })
+            .collect::<Result<_>>()?;
+        Ok(Instruction::new(
+            *program_id,
+            metas,
+            self.encode_instruction_data(name, args)?,
+        ))
+    }
+
+    /// The instruction `data` calls and its decoded arguments.
+    pub fn decode_instruction(&self, data: &[u8]) -> Result<(&IdlInstruction, Vec<Value>)> {
+        let instruction = self
+            .instructions
+            .iter()
+            .find(|ix| data.starts_with(&ix.discriminator))
+            .ok_or_else(|| Error::InvalidData("unknown instruction discriminator".to_owned()))?;
+        let args = self
+            .types
+            .decode_fields(&instruction.args, &data[instruction.discriminator.len()..])?;
+        Ok((instruction, args))
+    }
+
+    /// Account data for an account of type `name`.
+    pub fn encode_account(&self, name: &str, value: &Value) -> Result<Vec<u8>> {
+        encode_prefixed(&self.types, self.account(name)?, value)
+    }
+
+    /// Decodes the data of an account of type `name`, checking its
+    /// discriminator. Bytes past the value, such as space reserved for
+    /// growth, are ignored.
+    pub fn decode_account(&self, name: &str, data: &[u8]) -> Result<Value> {
+        decode_prefixed(&self.types, self.account(name)?, data)
+    }
+
+    /// The account type whose discriminator `data` starts with.
+    pub fn account_type(&self, data: &[u8]) -> Option<&IdlTypeRef> {
+        self.accounts
+            .iter()
+            .find(|account| data.starts_with(&account.discriminator))
+    }
+
+    /// Decodes event data, as logged in base64 after `Program data: `.
+    pub fn decode_event(&self, data: &[u8]) -> Result<(&IdlTypeRef, Value)> {
+        let event = self
+            .events
+            .iter()
+            .find(|event| data.starts_with(&event.discriminator))
+            .ok_or_else(|| Error::InvalidData("unknown event discriminator".to_owned()))?;
+        let value = self.types.decode(
+            &Type::Defined(event.name.clone()),
+            &data[event.discriminator.len()..],
+        )?;
+        Ok((event, value))
+    }
+}
+
+fn encode_prefixed(types: &Types, item: &IdlTypeRef, value: &Value) -> Result<Vec<u8>> {
+    let mut data = item.discriminator.clone();
+    data.extend(types.encode(&Type::Defined(item.name.clone()), value)?);
+    Ok(data)
+}
+
+fn decode_prefixed(types: &Types, item: &IdlTypeRef, data: &[u8]) -> Result<Value> {
+    let Some(body) = data.strip_prefix(item.discriminator.as_slice()) else {
+        return Err(Error::InvalidData(format!(
+            "data is not a `{}`: discriminator mismatch",
+            item.name
+        )));
+    };
+    let (value, _) = types.decode_prefix(&Type::Defined(item.name.clone()), body)?;
+    Ok(value)
+}
+
+fn invalid_idl(reason: impl Into<String>) -> Error {
+    Error::InvalidIdl(reason.into())
+}
+
+fn parse_pubkey(s: &str) -> Result<Pubkey> {
+    s.parse()
+        .map_err(|_| invalid_idl(format!("`{s}` is not a public key")))
+}
+
+/// A type as the IDL spells it: a primitive name, or an object naming a
+/// container or definition.
+fn parse_type(json: &serde_json::Value) -> Result<Type> {
+    use serde_json::Value as Json;
+
+    let unsupported = || invalid_idl(format!("unsupported type {json}"));
+    match json {
+        Json::String(name) => Ok(match name.as_str() {
+            "bool" => Type::Bool,
+            "u8" => Type::U8,
+            "u16" => Type::U16,
+            "u32" => Type::U32,
+            "u64" => Type::U64,
+            "u128" => Type::U128,
+            "i8" => Type::I8,
+            "i16" => Type::I16,
+            "i32" => Type::I32,
+            "i64" => Type::I64,
+            "i128" => Type::I128,
+            "f32" => Type::F32,
+            "f64" => Type::F64,
+            "bytes" => Type::Bytes,
+            "string" => Type::String,
+            "pubkey" | "publicKey" => Type::Pubkey,
+            _ => return Err(unsupported()),
+        }),
+        Json::Object(object) if object.len() == 1 => {
+            let (kind, inner) = object.iter().next().expect("one entry");
+            match kind.as_str() {
+                "vec" => Ok(Type::Vec(Box::new(parse_type(inner)?))),
+                "option" => Ok(Type::Option(Box::new(parse_type(inner)?))),
+                "coption" => Ok(Type::COption(Box::new(parse_type(inner)?))),
+                "array" => match inner.as_array().map(Vec::as_slice) {
+                    Some([ty, Json::Number(len)]) => {
+                        let len = len.as_u64().ok_or_else(unsupported)?;
+                        Ok(Type::Array(
+                            Box::new(parse_type(ty)?),
+                            usize::try_from(len).map_err(|_| unsupported())?,
+                        ))
+                    }
+                    _ => Err(unsupported()),
+                },
+                "defined" => match inner {
+                    Json::String(name) => Ok(Type::Defined(name.clone())),
+                    Json::Object(defined) => {
+                        let generic = defined
+                            .get("generics")
+                            .and_then(Json::as_array)
+                            .is_some_and(|generics| !generics.is_empty());
+                        match defined.get("name") {
+                            Some(Json::String(name)) if !generic => Ok(Type::Defined(name.clone())),
+                            _ => Err(unsupported()),
+                        }
+                    }
+                    _ => Err(unsupported()),
+                },
+                _ => Err(unsupported()),
+            }
+        }
+        _ => Err(unsupported()),
+    }
+}
+
+#[derive(Deserialize)]
+struct RawField {
+    name: String,
+    #[serde(rename = "type")]
+    ty: serde_json::Value,
+}
+
+impl RawField {
+    fn into_field(self) -> Result<Field> {
+        Ok(Field {
+            ty: parse_type(&self.ty)?,
+            name: self.name,
+        })
+    }
+}
+
+fn fields(raw: Vec<RawField>) -> Result<Vec<Field>> {
+    raw.into_iter().map(RawField::into_field).collect()
+}
+
+/// Named fields are `{"name", "type"}` objects; positional ones are bare
+/// types.
+fn parse_fields(json: Option<&serde_json::Value>) -> Result<Fields> {
+    let Some(items) = json else {
+        return Ok(Fields::Tuple(Vec::new()));
+    };
+    let items = items
+        .as_array()
+        .ok_or_else(|| invalid_idl(format!("fields {items} are not a list")))?;
+    let named = items
+        .first()
+        .is_some_and(|item| item.get("name").is_some() && item.get("type").is_some());
+    if named {
+        let raw: Vec<RawField> = serde_json::from_value(serde_json::Value::Array(items.clone()))?;
+        Ok(Fields::Named(fields(raw)?))
+    } else {
+        Ok(Fields::Tuple(
+            items.iter().map(parse_type).collect::<Result<_>>()?,
+        ))
+    }
+}
+
+#[derive(Deserialize)]
+struct RawVariant {
+    name: String,
+    #[serde(default)]
+    fields: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum RawTypeDefTy {
+    Struct {
+        #[serde(default)]
+        fields: Option<serde_json::Value>,
+    },
+    Enum {
+        variants: Vec<RawVariant>,
+    },
+    Type {
+        alias: serde_json::Value,
+    },
+}
+
+impl RawTypeDefTy {
+    fn into_type_def(self) -> Result<TypeDef> {
+        Ok(match self {
+            RawTypeDefTy::Struct { fields } => TypeDef::Struct(parse_fields(fields.as_ref())?),
+            RawTypeDefTy::Enum { variants } => TypeDef::Enum(
+                variants
+                    .into_iter()
+                    .map(|variant| {
+                        Ok(Variant {
+                            fields: parse_fields(variant.fields.as_ref())?,
+                            name: variant.name,
+                        })
+                    })
+                    .collect::<Result<_>>()?,
+            ),
+            RawTypeDefTy::Type { alias } => TypeDef::Alias(parse_type(&alias)?),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct RawTypeDef {
+    name: String,
+    #[serde(rename = "type")]
+    ty: RawTypeDefTy,
+    #[serde(default)]
+    serialization: Option<String>,
+    #[serde(default)]
+    generics: Vec<serde_json::Value>,
+}
+
+/// An account or event. Legacy IDLs define accounts inline under `type`
+/// and events under `fields`; current ones only name them.
+#[derive(Deserialize)]
+struct RawTypeItem {
+    name: String,
+    #[serde(default)]
+    discriminator: Option<Vec<u8>>,
+    #[serde(default, rename = "type")]
+    ty: Option<RawTypeDefTy>,
+    #[serde(default)]
+    fields: Option<Vec<RawField>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawAccountItem {
+    name: String,
+    #[serde(default)]
+    writable: bool,
+    #[serde(default)]
+    signer: bool,
+    #[serde(default)]
+    optional: bool,
+    #[serde(default)]
+    is_mut: bool,
+    #[serde(default)]
+    is_signer: bool,
+    #[serde(default)]
+    is_optional: bool,
+    #[serde(default)]
+    address: Option<String>,
+    /// The members of a composite account group.
+    #[serde(default)]
+    accounts: Option<Vec<RawAccountItem>>,
+}
+
+impl RawAccountItem {
+    fn flatten_into(self, out: &mut Vec<IdlAccountItem>) -> Result<()> {
+        if let Some(group) = self.accounts {
+            for item in group {
+                item.flatten_into(out)?;
+            }
+            return Ok(());
+        }
+        out.push(IdlAccountItem {
+            address: self.address.as_deref().map(parse_pubkey).transpose()?,
+            name: self.name,
+            writable: self.writable || self.is_mut,
+            signer: self.signer || self.is_signer,
+            optional: self.optional || self.is_optional,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+struct RawInstruction {
+    name: String,
+    #[serde(default)]
+    discriminator: Option<Vec<u8>>,
+    #[serde(default)]
+    accounts: Vec<RawAccountItem>,
+    #[serde(default)]
+    args: Vec<RawField>,
+}
+
+#[derive(Deserialize, Default)]
+struct RawMetadata {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    address: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawIdl {
+    #[serde(default)]
+    address: Option<String>,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    metadata: RawMetadata,
+    #[serde(default)]
+    instructions: Vec<RawInstruction>,
+    #[serde(default)]
+    accounts: Vec<RawTypeItem>,
+    #[serde(default)]
+    events: Vec<RawTypeItem>,
+    #[serde(default)]
+    errors: Vec<IdlError>,
+    #[serde(default)]
+    types: Vec<RawTypeDef>,
+}
+
+impl RawIdl {
+    fn into_idl(self) -> Result<Idl> {
+        let mut idl = Idl {
+            address: self
+                .address
+                .or(self.metadata.address)
+                .as_deref()
+                .map(parse_pubkey)
+                .transpose()?,
+            name: self.metadata.name.or(self.name).unwrap_or_default(),
+            version: self.metadata.version.or(self.version).unwrap_or_default(),
+            errors: self.errors,
+            ..Idl::default()
+        };
+        for def in self.types {
+            let borsh = def.serialization.as_deref().map_or(true, |s| s == "borsh");
+            if borsh && def.generics.is_empty() {
+                idl.types.insert(def.name, def.ty.into_type_def()?);
+            }
+        }
+        for instruction in self.instructions {
+            let mut accounts = Vec::new();
+            for item in instruction.accounts {
+                item.flatten_into(&mut accounts)?;
+            }
+            idl.instructions.push(IdlInstruction {
+                discriminator: instruction
+                    .discriminator
+                    .unwrap_or_else(|| instruction_discriminator(&instruction.name).to_vec()),
+                accounts,
+                args: fields(instruction.args)?,
+                name: instruction.name,
+            });
+        }
+        for account in self.accounts {
+            if let Some(ty) = account.ty {
+                idl.types.insert(account.name.clone(), ty.into_type_def()?);
+            }
+            idl.accounts.push(IdlTypeRef {
+                discriminator: account
+                    .discriminator
+                    .unwrap_or_else(|| account_discriminator(&account.name).to_vec()),
+                name: account.name,
+            });
+        }
+        for event in self.events {
+            if let Some(raw) = event.fields {
+                idl.types.insert(
+                    event.name.clone(),
+                    TypeDef::Struct(Fields::Named(fields(raw)?)),
+                );
+            }
+            idl.events.push(IdlTypeRef {
+                discriminator: event
+                    .discriminator
+                    .unwrap_or_else(|| event_discriminator(&event.name).to_vec()),
+                name: event.name,
+            });
+        }
+        Ok(idl)
+    }
+}
@@ -0,0 +1,82 @@
+//! Program instructions, before and after compilation into a message.
+
+use crate::error::Result;
+use crate::short_vec;
+use crate::Pubkey;
+
+/// An account an instruction reads or writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccountMeta {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl AccountMeta {
+    /// A writable account.
+    pub fn new(pubkey: Pubkey, is_signer: bool) -> Self {
+        Self {
+            pubkey,
+            is_signer,
+            is_writable: true,
+        }
+    }
+
+    /// A read-only account.
+    pub fn new_readonly(pubkey: Pubkey, is_signer: bool) -> Self {
+        Self {
+            pubkey,
+            is_signer,
+            is_writable: false,
+        }
+    }
+}
+
+/// A call into a program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    pub program_id: Pubkey,
+    pub accounts: Vec<AccountMeta>,
+    pub data: Vec<u8>,
+}
+
+impl Instruction {
+    pub fn new(program_id: Pubkey, accounts: Vec<AccountMeta>, data: Vec<u8>) -> Self {
+        Self {
+            program_id,
+            accounts,
+            data,
+        }
+    }
+}
+
+/// An instruction with its program and accounts replaced by indices into
+/// the message's account keys.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompiledInstruction {
+    pub program_id_index: u8,
+    pub accounts: Vec<u8>,
+    pub data: Vec<u8>,
+}
+
+impl CompiledInstruction {
+    pub(crate) fn encode(&self, out: &mut Vec<u8>) {
+        out.push(self.program_id_index);
+        short_vec::encode(&self.accounts, out, |index, out| out.push(*index));
+        short_vec::encode(&self.data, out, |byte, out| out.push(*byte));
+    }
+
+    pub(crate) fn decode(input: &[u8]) -> Result<(Self, &[u8])> {
+        let ([program_id_index], rest) = short_vec::take::<1>(input)?;
+        let (accounts, rest) = short_vec::decode_bytes(rest)?;
+        let (data, rest) = short_vec::decode_bytes(rest)?;
+        Ok((
+            Self {
+                program_id_index,
+                accounts,
+                data,
+            },
+            rest,
+        ))
+    }
+}
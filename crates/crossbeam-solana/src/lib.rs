@@ -0,0 +1,36 @@
+//! Solana adapter for the CrossBeam SDK.
+
+pub mod associated_token;
+pub mod borsh;
+pub mod chain;
+pub mod error;
+pub mod hash;
+pub mod idl;
+pub mod instruction;
+pub mod message;
+pub mod pda;
+pub mod provider;
+pub mod rpc;
+pub mod sender;
+pub mod short_vec;
+pub mod signature;
+pub mod system;
+pub mod token;
+pub mod token_2022;
+pub mod transaction;
+
+pub use chain::SolanaChain;
+pub use crossbeam_core::address::SolanaAddress as Pubkey;
+pub use error::{Error, Result};
+pub use hash::Hash;
+pub use idl::Idl;
+pub use instruction::{AccountMeta, CompiledInstruction, Instruction};
+pub use message::{
+    AddressLookupTableAccount, Message, MessageAddressTableLookup, MessageHeader, MessageV0,
+    VersionedMessage,
+};
+pub use provider::Provider;
+pub use rpc::{Client, Commitment, PubsubTransport, Transport};
+pub use sender::{PendingTransaction, SendStatus, Sender};
+pub use signature::Signature;
+pub use transaction::Transaction;
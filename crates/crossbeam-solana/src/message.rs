@@ -0,0 +1,563 @@
+//! Transaction messages: legacy, and v0 with address lookup tables.
+//!
+//! Compiling a message collects every account its instructions touch into a
+//! single key list, ordered writable signers, read-only signers, writable
+//! non-signers, read-only non-signers, with the fee payer first. The header
+//! records the size of each group, and instructions refer to accounts by
+//! their index in the list. Legacy messages must spell out every key, which
+//! caps them at a few dozen accounts; v0 messages can instead load
+//! non-signer accounts from on-chain lookup tables by one-byte index.
+
+use std::collections::BTreeMap;
+
+use crate::error::{Error, Result};
+use crate::hash::Hash;
+use crate::instruction::{CompiledInstruction, Instruction};
+use crate::short_vec;
+use crate::system;
+use crate::Pubkey;
+
+/// The largest serialized transaction the network accepts.
+pub const PACKET_DATA_SIZE: usize = 1232;
+/// The high bit of the first byte marks a versioned message.
+const VERSION_PREFIX: u8 = 0x80;
+
+fn compile_error(reason: impl Into<String>) -> Error {
+    Error::InvalidTransaction(reason.into())
+}
+
+/// The sizes of the signer and read-only groups of a message's keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MessageHeader {
+    /// Leading keys that must sign.
+    pub num_required_signatures: u8,
+    /// Trailing signers that are read-only.
+    pub num_readonly_signed_accounts: u8,
+    /// Trailing non-signers that are read-only.
+    pub num_readonly_unsigned_accounts: u8,
+}
+
+impl MessageHeader {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&[
+            self.num_required_signatures,
+            self.num_readonly_signed_accounts,
+            self.num_readonly_unsigned_accounts,
+        ]);
+    }
+
+    fn decode(input: &[u8]) -> Result<(Self, &[u8])> {
+        let ([signatures, readonly_signed, readonly_unsigned], rest) = short_vec::take(input)?;
+        Ok((
+            Self {
+                num_required_signatures: signatures,
+                num_readonly_signed_accounts: readonly_signed,
+                num_readonly_unsigned_accounts: readonly_unsigned,
+            },
+            rest,
+        ))
+    }
+}
+
+/// How an account is used across a message's instructions.
+#[derive(Debug, Clone, Copy, Default)]
+struct KeyMeta {
+    is_signer: bool,
+    is_writable: bool,
+    /// Programs must be listed statically, never loaded from a table.
+    is_invoked: bool,
+}
+
+/// A lookup and the keys it loads, writable first.
+struct LoadedLookup {
+    lookup: MessageAddressTableLookup,
+    writable: Vec<Pubkey>,
+    readonly: Vec<Pubkey>,
+}
+
+/// The accounts of a message, before they are laid out.
+struct CompiledKeys {
+    payer: Pubkey,
+    keys: BTreeMap<Pubkey, KeyMeta>,
+}
+
+impl CompiledKeys {
+    fn compile(instructions: &[Instruction], payer: &Pubkey) -> Self {
+        let mut keys: BTreeMap<Pubkey, KeyMeta> = BTreeMap::new();
+        for instruction in instructions {
+            keys.entry(instruction.program_id).or_default().is_invoked = true;
+            for account in &instruction.accounts {
+                let meta = keys.entry(account.pubkey).or_default();
+                meta.is_signer |= account.is_signer;
+                meta.is_writable |= account.is_writable;
+            }
+        }
+        let meta = keys.entry(*payer).or_default();
+        meta.is_signer = true;
+        meta.is_writable = true;
+        Self {
+            payer: *payer,
+            keys,
+        }
+    }
+
+    /// Moves the non-signer, non-program accounts found in `table` out of
+    /// the static keys, returning the lookup and the keys it loads.
+    fn extract_lookup(
+        &mut self,
+        table: &AddressLookupTableAccount,
+    ) -> Result<Option<LoadedLookup>> {
+        let (writable_indexes, writable) = self.drain_found(table, true)?;
+        let (readonly_indexes, readonly) = self.drain_found(table, false)?;
+        if writable_indexes.is_empty() && readonly_indexes.is_empty() {
+            return Ok(None);
+        }
+        let lookup = MessageAddressTableLookup {
+            account_key: table.key,
+            writable_indexes,
+            readonly_indexes,
+        };
+        Ok(Some(LoadedLookup {
+            lookup,
+            writable,
+            readonly,
+        }))
+    }
+
+    fn drain_found(
+        &mut self,
+        table: &AddressLookupTableAccount,
+        writable: bool,
+    ) -> Result<(Vec<u8>, Vec<Pubkey>)> {
+        let mut indexes = Vec::new();
+        let mut drained = Vec::new();
+        for (key, meta) in &self.keys {
+            if meta.is_signer || meta.is_invoked || meta.is_writable != writable {
+                continue;
+            }
+            let Some(index) = table.addresses.iter().position(|address| address == key) else {
+                continue;
+            };
+            let index = u8::try_from(index).map_err(|_| {
+                compile_error(format!(
+                    "{key} is at index {index} of lookup table {}",
+                    table.key
+                ))
+            })?;
+            indexes.push(index);
+            drained.push(*key);
+        }
+        for key in &drained {
+            self.keys.remove(key);
+        }
+        Ok((indexes, drained))
+    }
+
+    fn into_message_components(self) -> Result<(MessageHeader, Vec<Pubkey>)> {
+        let group = |signer: bool, writable: bool| -> Vec<Pubkey> {
+            self.keys
+                .iter()
+                .filter(|(key, meta)| {
+                    **key != self.payer && meta.is_signer == signer && meta.is_writable == writable
+                })
+                .map(|(key, _)| *key)
+                .collect()
+        };
+        let writable_signers = group(true, true);
+        let readonly_signers = group(true, false);
+        let writable_non_signers = group(false, true);
+        let readonly_non_signers = group(false, false);
+
+        let count = |len: usize, what: &str| {
+            u8::try_from(len).map_err(|_| compile_error(format!("{len} {what}")))
+        };
+        let header = MessageHeader {
+            num_required_signatures: count(
+                1 + writable_signers.len() + readonly_signers.len(),
+                "signers",
+            )?,
+            num_readonly_signed_accounts: count(readonly_signers.len(), "read-only signers")?,
+            num_readonly_unsigned_accounts: count(
+                readonly_non_signers.len(),
+                "read-only accounts",
+            )?,
+        };
+        let keys: Vec<Pubkey> = std::iter::once(self.payer)
+            .chain(writable_signers)
+            .chain(readonly_signers)
+            .chain(writable_non_signers)
+            .chain(readonly_non_signers)
+            .collect();
+        Ok((header, keys))
+    }
+}
+
+/// Replaces the keys of `instructions` with their index in `keys`.
+fn compile_instructions(
+    instructions: &[Instruction],
+    keys: &[Pubkey],
+) -> Result<Vec<CompiledInstruction>> {
+    if keys.len() > 256 {
+        return Err(compile_error(format!(
+            "{} accounts, at most 256 can be addressed",
+            keys.len()
+        )));
+    }
+    let index = |key: &Pubkey| -> u8 {
+        let position = keys.iter().position(|k| k == key);
+        position.expect("every key was collected") as u8
+    };
+    instructions
+        .iter()
+        .map(|instruction| {
+            if instruction.data.len() > PACKET_DATA_SIZE {
+                return Err(compile_error(format!(
+                    "{} bytes of instruction data",
+                    instruction.data.len()
+                )));
+            }
+            Ok(CompiledInstruction {
+                program_id_index: index(&instruction.program_id),
+                accounts: instruction
+                    .accounts
+                    .iter()
+                    .map(|account| index(&account.pubkey))
+                    .collect(),
+                data: instruction.data.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Prepends the instruction that advances `nonce_account`.
+fn with_nonce(
+    instructions: &[Instruction],
+    nonce_account: &Pubkey,
+    nonce_authority: &Pubkey,
+) -> Vec<Instruction> {
+    std::iter::once(system::advance_nonce_account(
+        nonce_account,
+        nonce_authority,
+    ))
+    .chain(instructions.iter().cloned())
+    .collect()
+}
+
+fn encode_keys(keys: &[Pubkey], out: &mut Vec<u8>) {
+    short_vec::encode(keys, out, |key, out| out.extend_from_slice(&key.0));
+}
+
+fn decode_key(input: &[u8]) -> Result<(Pubkey, &[u8])> {
+    let (key, rest) = short_vec::take(input)?;
+    Ok((Pubkey::new(key), rest))
+}
+
+/// A message whose every account is listed in it.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Message {
+    pub header: MessageHeader,
+    pub account_keys: Vec<Pubkey>,
+    pub recent_blockhash: Hash,
+    pub instructions: Vec<CompiledInstruction>,
+}
+
+impl Message {
+    /// Compiles `instructions`, with fees paid by `payer`.
+    pub fn new(
+        instructions: &[Instruction],
+        payer: &Pubkey,
+        recent_blockhash: Hash,
+    ) -> Result<Self> {
+        let (header, account_keys) =
+            CompiledKeys::compile(instructions, payer).into_message_components()?;
+        let instructions = compile_instructions(instructions, &account_keys)?;
+        Ok(Self {
+            header,
+            account_keys,
+            recent_blockhash,
+            instructions,
+        })
+    }
+
+    /// Compiles `instructions` to run on the durable nonce `nonce`, stored
+    /// in `nonce_account` and advanced by `nonce_authority`.
+    pub fn new_with_nonce(
+        instructions: &[Instruction],
+        payer: &Pubkey,
+        nonce_account: &Pubkey,
+        nonce_authority: &Pubkey,
+        nonce: Hash,
+    ) -> Result<Self> {
+        Self::new(
+            &with_nonce(instructions, nonce_account, nonce_authority),
+            payer,
+            nonce,
+        )
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.header.encode(&mut out);
+        encode_keys(&self.account_keys, &mut out);
+        out.extend_from_slice(&self.recent_blockhash.0);
+        short_vec::encode(&self.instructions, &mut out, CompiledInstruction::encode);
+        out
+    }
+
+    fn decode_body(input: &[u8]) -> Result<(Self, &[u8])> {
+        let (header, rest) = MessageHeader::decode(input)?;
+        let (account_keys, rest) = short_vec::decode(rest, decode_key)?;
+        let (recent_blockhash, rest) = short_vec::take(rest)?;
+        let (instructions, rest) = short_vec::decode(rest, CompiledInstruction::decode)?;
+        Ok((
+            Self {
+                header,
+                account_keys,
+                recent_blockhash: Hash(recent_blockhash),
+                instructions,
+            },
+            rest,
+        ))
+    }
+}
+
+/// An on-chain table of addresses a v0 message can load accounts from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddressLookupTableAccount {
+    pub key: Pubkey,
+    pub addresses: Vec<Pubkey>,
+}
+
+/// The accounts a v0 message loads from one lookup table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageAddressTableLookup {
+    pub account_key: Pubkey,
+    pub writable_indexes: Vec<u8>,
+    pub readonly_indexes: Vec<u8>,
+}
+
+impl MessageAddressTableLookup {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.account_key.0);
+        short_vec::encode(&self.writable_indexes, out, |index, out| out.push(*index));
+        short_vec::encode(&self.readonly_indexes, out, |index, out| out.push(*index));
+    }
+
+    fn decode(input: &[u8]) -> Result<(Self, &[u8])> {
+        let (account_key, rest) = decode_key(input)?;
+        let (writable_indexes, rest) = short_vec::decode_bytes(rest)?;
+        let (readonly_indexes, rest) = short_vec::decode_bytes(rest)?;
+        Ok((
+            Self {
+                account_key,
+                writable_indexes,
+                readonly_indexes,
+            },
+            rest,
+        ))
+    }
+}
+
+/// A v0 message. Instructions index the static keys first, then the
+/// writable and then the read-only accounts loaded by each lookup in turn.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MessageV0 {
+    pub header: MessageHeader,
+    pub account_keys: Vec<Pubkey>,
+    pub recent_blockhash: Hash,
+    pub instructions: Vec<CompiledInstruction>,
+    pub address_table_lookups: Vec<MessageAddressTableLookup>,
+}
+
+impl MessageV0 {
+    /// Compiles `instructions`, loading every account it can from
+    /// `lookup_tables`. Signers and programs always stay static.
+    pub fn compile(
+        instructions: &[Instruction],
+        payer: &Pubkey,
+        lookup_tables: &[AddressLookupTableAccount],
+        recent_blockhash: Hash,
+    ) -> Result<Self> {
+        let mut compiled = CompiledKeys::compile(instructions, payer);
+        let mut address_table_lookups = Vec::new();
+        let mut loaded_writable = Vec::new();
+        let mut loaded_readonly = Vec::new();
+        for table in lookup_tables {
+            if let Some(loaded) = compiled.extract_lookup(table)? {
+                address_table_lookups.push(loaded.lookup);
+                loaded_writable.extend(loaded.writable);
+                loaded_readonly.extend(loaded.readonly);
+            }
+        }
+        let (header, account_keys) = compiled.into_message_components()?;
+        let keys: Vec<Pubkey> = account_keys
+            .iter()
+            .chain(&loaded_writable)
+            .chain(&loaded_readonly)
+            .copied()
+            .collect();
+        let instructions = compile_instructions(instructions, &keys)?;
+        Ok(Self {
+            header,
+            account_keys,
+            recent_blockhash,
+            instructions,
+            address_table_lookups,
+        })
+    }
+
+    /// [`MessageV0::compile`] on the durable nonce `nonce`.
+    pub fn compile_with_nonce(
+        instructions: &[Instruction],
+        payer: &Pubkey,
+        lookup_tables: &[AddressLookupTableAccount],
+        nonce_account: &Pubkey,
+        nonce_authority: &Pubkey,
+        nonce: Hash,
+    ) -> Result<Self> {
+        Self::compile(
+            &with_nonce(instructions, nonce_account, nonce_authority),
+            payer,
+            lookup_tables,
+            nonce,
+        )
+    }
+
+    /// The encoding, with its version prefix.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = vec![VERSION_PREFIX];
+        self.header.encode(&mut out);
+        encode_keys(&self.account_keys, &mut out);
+        out.extend_from_slice(&self.recent_blockhash.0);
+        short_vec::encode(&self.instructions, &mut out, CompiledInstruction::encode);
+        short_vec::encode(
+            &self.address_table_lookups,
+            &mut out,
+            MessageAddressTableLookup::encode,
+        );
+        out
+    }
+
+    fn decode_body(input: &[u8]) -> Result<(Self, &[u8])> {
+        let (legacy, rest) = Message::decode_body(input)?;
+        let (address_table_lookups, rest) =
+            short_vec::decode(rest, MessageAddressTableLookup::decode)?;
+        Ok((
+            Self {
+                header: legacy.header,
+                account_keys: legacy.account_keys,
+                recent_blockhash: legacy.recent_blockhash,
+                instructions: legacy.instructions,
+                address_table_lookups,
+            },
+            rest,
+        ))
+    }
+}
+
+/// Either kind of message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionedMessage {
+    Legacy(Message),
+    V0(MessageV0),
+}
+
+impl From<Message> for VersionedMessage {
+    fn from(message: Message) -> Self {
+        VersionedMessage::Legacy(message)
+    }
+}
+
+impl From<MessageV0> for VersionedMessage {
+    fn from(message: MessageV0) -> Self {
+        VersionedMessage::V0(message)
+    }
+}
+
+impl VersionedMessage {
+    pub fn header(&self) -> &MessageHeader {
+        match self {
+            VersionedMessage::Legacy(message) => &message.header,
+            VersionedMessage::V0(message) => &message.header,
+        }
+    }
+
+    /// The keys listed in the message itself; for v0 messages, without the
+    /// accounts loaded from lookup tables.
+    pub fn static_account_keys(&self) -> &[Pubkey] {
+        match self {
+            VersionedMessage::Legacy(message) => &message.account_keys,
+            VersionedMessage::V0(message) => &message.account_keys,
+        }
+    }
+
+    pub fn instructions(&self) -> &[CompiledInstruction] {
+        match self {
+            VersionedMessage::Legacy(message) => &message.instructions,
+            VersionedMessage::V0(message) => &message.instructions,
+        }
+    }
+
+    pub fn recent_blockhash(&self) -> &Hash {
+        match self {
+            VersionedMessage::Legacy(message) => &message.recent_blockhash,
+            VersionedMessage::V0(message) => &message.recent_blockhash,
+        }
+    }
+
+    pub fn set_recent_blockhash(&mut self, blockhash: Hash) {
+        match self {
+            VersionedMessage::Legacy(message) => message.recent_blockhash = blockhash,
+            VersionedMessage::V0(message) => message.recent_blockhash = blockhash,
+        }
+    }
+
+    /// The keys that must sign, in signature order.
+    pub fn signers(&self) -> &[Pubkey] {
+        let keys = self.static_account_keys();
+        let count = usize::from(self.header().num_required_signatures);
+        &keys[..count.min(keys.len())]
+    }
+
+    /// Whether the message runs on a durable nonce rather than a recent
+    /// blockhash: its first instruction advances a nonce account.
+    pub fn uses_durable_nonce(&self) -> bool {
+        self.instructions().first().is_some_and(|instruction| {
+            self.static_account_keys()
+                .get(usize::from(instruction.program_id_index))
+                .is_some_and(|program_id| system::is_advance_nonce(program_id, &instruction.data))
+        })
+    }
+
+    /// The bytes signers sign.
+    pub fn encode(&self) -> Vec<u8> {
+        match self {
+            VersionedMessage::Legacy(message) => message.encode(),
+            VersionedMessage::V0(message) => message.encode(),
+        }
+    }
+
+    pub fn decode(input: &[u8]) -> Result<Self> {
+        let (message, rest) = match input.first() {
+            Some(&prefix) if prefix & VERSION_PREFIX != 0 => {
+                let version = prefix & !VERSION_PREFIX;
+                if version != 0 {
+                    return Err(compile_error(format!("message version {version}")));
+                }
+                let (message, rest) = MessageV0::decode_body(&input[1..])?;
+                (VersionedMessage::V0(message), rest)
+            }
+            _ => {
+                let (message, rest) = Message::decode_body(input)?;
+                (VersionedMessage::Legacy(message), rest)
+            }
+        };
+        if !rest.is_empty() {
+            return Err(compile_error(format!(
+                "{} bytes after the message",
+                rest.len()
+            )));
+        }
+        Ok(message)
+    }
+}
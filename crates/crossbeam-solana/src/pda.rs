@@ -0,0 +1,110 @@
+//! Addresses derived from seeds: program-derived addresses and
+//! `create_with_seed` accounts.
+//!
+//! A program-derived address (PDA) is a SHA-256 of the seeds, the program id
+//! and a marker, rejected if it lands on the Ed25519 curve so that no private
+//! key can sign for it. [`find_program_address`] appends a one-byte bump seed,
+//! counting down from 255, until the hash falls off the curve.
+
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use sha2::{Digest, Sha256};
+
+use crate::error::{Error, Result};
+use crate::Pubkey;
+
+/// Most seeds a PDA can have, bump included.
+pub const MAX_SEEDS: usize = 16;
+/// Longest a single seed can be.
+pub const MAX_SEED_LEN: usize = 32;
+
+const PDA_MARKER: &[u8] = b"ProgramDerivedAddress";
+
+/// Whether `key` is a point on the Ed25519 curve, and so could have a
+/// private key.
+pub fn is_on_curve(key: &Pubkey) -> bool {
+    CompressedEdwardsY(key.0).decompress().is_some()
+}
+
+fn check_seeds(seeds: &[&[u8]]) -> Result<()> {
+    if seeds.len() > MAX_SEEDS {
+        return Err(Error::InvalidSeeds(format!(
+            "{} seeds, at most {MAX_SEEDS} are allowed",
+            seeds.len()
+        )));
+    }
+    if let Some(seed) = seeds.iter().find(|seed| seed.len() > MAX_SEED_LEN) {
+        return Err(Error::InvalidSeeds(format!(
+            "a seed of {} bytes, at most {MAX_SEED_LEN} are allowed",
+            seed.len()
+        )));
+    }
+    Ok(())
+}
+
+/// The address for `seeds` followed by `bump`, unless it is on the curve.
+fn derive(seeds: &[&[u8]], bump: &[u8], program_id: &Pubkey) -> Option<Pubkey> {
+    let mut hasher = Sha256::new();
+    for seed in seeds {
+        hasher.update(seed);
+    }
+    hasher.update(bump);
+    hasher.update(program_id.0);
+    hasher.update(PDA_MARKER);
+    let address = Pubkey::new(hasher.finalize().into());
+    (!is_on_curve(&address)).then_some(address)
+}
+
+/// The PDA of `program_id` for exactly `seeds`. Fails if the seeds are too
+/// many or too long, or if the address they give is on the curve.
+pub fn create_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> Result<Pubkey> {
+    check_seeds(seeds)?;
+    derive(seeds, &[], program_id).ok_or_else(|| {
+        Error::InvalidSeeds(format!(
+            "the address for these seeds and program {program_id} is on the curve"
+        ))
+    })
+}
+
+/// The canonical PDA of `program_id` for `seeds`, with the bump seed that
+/// gives it: the highest one whose address is off the curve.
+pub fn find_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> Result<(Pubkey, u8)> {
+    if seeds.len() >= MAX_SEEDS {
+        return Err(Error::InvalidSeeds(format!(
+            "{} seeds leave no room for the bump, at most {} are allowed",
+            seeds.len(),
+            MAX_SEEDS - 1
+        )));
+    }
+    check_seeds(seeds)?;
+    for bump in (0..=u8::MAX).rev() {
+        if let Some(address) = derive(seeds, &[bump], program_id) {
+            return Ok((address, bump));
+        }
+    }
+    Err(Error::InvalidSeeds(format!(
+        "no bump gives an address off the curve for program {program_id}"
+    )))
+}
+
+/// The address `base` can create with `seed` for `owner` through the system
+/// program's `*_with_seed` instructions. Unlike a PDA, it may be on the
+/// curve, but `owner` may not look like a PDA marker.
+pub fn create_with_seed(base: &Pubkey, seed: &str, owner: &Pubkey) -> Result<Pubkey> {
+    if seed.len() > MAX_SEED_LEN {
+        return Err(Error::InvalidSeeds(format!(
+            "a seed of {} bytes, at most {MAX_SEED_LEN} are allowed",
+            seed.len()
+        )));
+    }
+    if owner.0.ends_with(PDA_MARKER) {
+        return Err(Error::InvalidSeeds(format!(
+            "owner {owner} ends with the PDA marker"
+        )));
+    }
+    let hash = Sha256::new()
+        .chain_update(base.0)
+        .chain_update(seed)
+        .chain_update(owner.0)
+        .finalize();
+    Ok(Pubkey::new(hash.into()))
+}
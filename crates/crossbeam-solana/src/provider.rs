@@ -0,0 +1,19 @@
+use async_trait::async_trait;
+use crossbeam_core::Confirmation;
+
+use crate::error::Result;
+use crate::hash::Hash;
+use crate::signature::Signature;
+
+/// The node access [`SolanaChain`](crate::SolanaChain) needs.
+#[async_trait]
+pub trait Provider: Send + Sync {
+    /// A recent blockhash for new transactions to reference.
+    async fn latest_blockhash(&self) -> Result<Hash>;
+
+    /// Broadcasts a serialized, signed transaction.
+    async fn send_transaction(&self, wire: &[u8]) -> Result<Signature>;
+
+    /// Reports where the transaction identified by `signature` stands.
+    async fn signature_confirmation(&self, signature: &Signature) -> Result<Confirmation>;
+}
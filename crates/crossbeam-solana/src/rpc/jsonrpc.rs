@@ -0,0 +1,124 @@
+//! JSON-RPC 2.0 envelopes, for transports that speak the wire format.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+
+/// A request object.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Request {
+    pub jsonrpc: String,
+    pub id: u64,
+    pub method: String,
+    pub params: Value,
+}
+
+impl Request {
+    pub fn new(id: u64, method: &str, params: Value) -> Self {
+        Self {
+            jsonrpc: "2.0".to_owned(),
+            id,
+            method: method.to_owned(),
+            params,
+        }
+    }
+}
+
+/// The error member of a failed response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorObject {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl From<ErrorObject> for Error {
+    fn from(error: ErrorObject) -> Self {
+        Error::Rpc {
+            code: error.code,
+            message: error.message,
+            data: error.data,
+        }
+    }
+}
+
+/// A response object.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Response {
+    pub jsonrpc: String,
+    /// `null` when the server could not read the request id.
+    pub id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorObject>,
+}
+
+impl Response {
+    pub fn success(id: u64, result: Value) -> Self {
+        Self {
+            jsonrpc: "2.0".to_owned(),
+            id: Some(id),
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    pub fn failure(id: u64, error: ErrorObject) -> Self {
+        Self {
+            jsonrpc: "2.0".to_owned(),
+            id: Some(id),
+            result: None,
+            error: Some(error),
+        }
+    }
+
+    /// The result, or the error object as [`Error::Rpc`]. A response with
+    /// neither member carries a `null` result.
+    pub fn into_result(self) -> Result<Value> {
+        match self.error {
+            Some(error) => Err(error.into()),
+            None => Ok(self.result.unwrap_or(Value::Null)),
+        }
+    }
+}
+
+/// A subscription notification, which the node pushes over the websocket
+/// without a request id.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Notification {
+    pub jsonrpc: String,
+    /// `accountNotification`, `logsNotification` and so on.
+    pub method: String,
+    pub params: NotificationParams,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotificationParams {
+    pub result: Value,
+    /// The id the subscribe call returned.
+    pub subscription: u64,
+}
+
+impl Notification {
+    pub fn new(method: &str, subscription: u64, result: Value) -> Self {
+        Self {
+            jsonrpc: "2.0".to_owned(),
+            method: method.to_owned(),
+            params: NotificationParams {
+                result,
+                subscription,
+            },
+        }
+    }
+}
+
+/// Anything a Solana websocket endpoint sends.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Message {
+    Notification(Notification),
+    Response(Response),
+}
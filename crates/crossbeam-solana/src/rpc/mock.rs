@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use super::pubsub::Subscriptions;
+use super::{PubsubTransport, Transport};
+use crate::error::{Error, Result};
+
+type Handler = Box<dyn Fn(&Value) -> Result<Value> + Send + Sync>;
+
+#[derive(Debug, Default)]
+struct Active {
+    next_id: u64,
+    /// Subscription ids and the methods that opened them.
+    methods: HashMap<u64, String>,
+}
+
+/// An in-process node for tests, answering both RPC requests and
+/// subscriptions.
+///
+/// Requests are answered by the handler registered for their method; any
+/// other method fails as the node would. Subscribe methods without a
+/// handler open a subscription, fed by [`notify`](Self::notify), and their
+/// unsubscribe counterparts close it. Every request is recorded for later
+/// assertions.
+#[derive(Default)]
+pub struct MockTransport {
+    handlers: HashMap<String, Handler>,
+    requests: Mutex<Vec<(String, Value)>>,
+    active: Mutex<Active>,
+    subscriptions: Subscriptions,
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answers `method` with `handler`, called with the request params.
+    pub fn on(
+        mut self,
+        method: &str,
+        handler: impl Fn(&Value) -> Result<Value> + Send + Sync + 'static,
+    ) -> Self {
+        self.handlers.insert(method.to_owned(), Box::new(handler));
+        self
+    }
+
+    /// Answers `method` with the same result every time.
+    pub fn on_result(self, method: &str, result: Value) -> Self {
+        self.on(method, move |_| Ok(result.clone()))
+    }
+
+    /// The `(method, params)` pairs received so far, in order.
+    pub fn requests(&self) -> Vec<(String, Value)> {
+        self.requests.lock().expect("mock lock poisoned").clone()
+    }
+
+    /// The open subscriptions and the methods that opened them, by id.
+    pub fn subscriptions(&self) -> Vec<(u64, String)> {
+        let active = self.active.lock().expect("mock lock poisoned");
+        let mut subscriptions: Vec<_> = active
+            .methods
+            .iter()
+            .map(|(id, method)| (*id, method.clone()))
+            .collect();
+        subscriptions.sort();
+        subscriptions
+    }
+
+    /// Sends a notification with `result` on subscription `id`. Like the
+    /// node, ends signature subscriptions after their first notification.
+    pub fn notify(&self, id: u64, result: Value) {
+        let mut active = self.active.lock().expect("mock lock poisoned");
+        if active.methods.get(&id).map(String::as_str) == Some("signatureSubscribe") {
+            active.methods.remove(&id);
+        }
+        self.subscriptions.push(id, result);
+    }
+
+    /// Ends subscription `id` from the node's side.
+    pub fn close(&self, id: u64) {
+        self.active
+            .lock()
+            .expect("mock lock poisoned")
+            .methods
+            .remove(&id);
+        self.subscriptions.close(id);
+    }
+
+    /// Drops the connection, ending every subscription.
+    pub fn disconnect(&self) {
+        self.active
+            .lock()
+            .expect("mock lock poisoned")
+            .methods
+            .clear();
+        self.subscriptions.disconnect();
+    }
+
+    fn subscribe(&self, method: &str) -> Value {
+        let mut active = self.active.lock().expect("mock lock poisoned");
+        let id = active.next_id;
+        active.next_id += 1;
+        active.methods.insert(id, method.to_owned());
+        json!(id)
+    }
+
+    fn unsubscribe(&self, params: &Value) -> Result<Value> {
+        let removed = params[0].as_u64().and_then(|id| {
+            self.active
+                .lock()
+                .expect("mock lock poisoned")
+                .methods
+                .remove(&id)
+        });
+        match removed {
+            Some(_) => Ok(json!(true)),
+            None => Err(Error::Rpc {
+                code: -32602,
+                message: "Invalid subscription id.".to_owned(),
+                data: None,
+            }),
+        }
+    }
+}
+
+impl std::fmt::Debug for MockTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut methods: Vec<_> = self.handlers.keys().collect();
+        methods.sort();
+        f.debug_struct("MockTransport")
+            .field("methods", &methods)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl Transport for MockTransport {
+    async fn request(&self, method: &str, params: Value) -> Result<Value> {
+        self.requests
+            .lock()
+            .expect("mock lock poisoned")
+            .push((method.to_owned(), params.clone()));
+        match self.handlers.get(method) {
+            Some(handler) => handler(&params),
+            None if method.ends_with("Unsubscribe") => self.unsubscribe(&params),
+            None if method.ends_with("Subscribe") => Ok(self.subscribe(method)),
+            None => Err(Error::Rpc {
+                code: -32601,
+                message: "Method not found".to_owned(),
+                data: None,
+            }),
+        }
+    }
+}
+
+#[async_trait]
+impl PubsubTransport for MockTransport {
+    async fn next_notification(&self, id: u64) -> Result<Option<Value>> {
+        Ok(self.subscriptions.next(id).await)
+    }
+
+    fn release(&self, id: u64) {
+        self.subscriptions.remove(id);
+    }
+}
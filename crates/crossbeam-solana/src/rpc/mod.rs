@@ -0,0 +1,352 @@
+//! Solana JSON-RPC and PubSub access.
+//!
+//! [`Client`] speaks the HTTP methods over any [`Transport`], and the
+//! websocket subscriptions over any [`PubsubTransport`]. The SDK ships no
+//! network stack: applications implement the transports over the HTTP and
+//! WebSocket clients they already use, with [`jsonrpc`] providing the
+//! envelopes and [`Subscriptions`] the routing of notifications. Tests use
+//! [`MockTransport`], which serves both in process.
+//!
+//! Reads and subscriptions take the [`Commitment`] they need; the client's
+//! own commitment applies where the [`Provider`] interface leaves no room
+//! for one.
+
+pub mod jsonrpc;
+mod mock;
+mod pubsub;
+pub(crate) mod serde_base64;
+mod types;
+
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use crossbeam_core::Confirmation;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use crate::error::{Error, Result};
+use crate::hash::Hash;
+use crate::provider::Provider;
+use crate::signature::Signature;
+use crate::Pubkey;
+
+pub use mock::MockTransport;
+pub use pubsub::{Subscription, Subscriptions};
+pub use types::{
+    Account, Commitment, Context, LatestBlockhash, Logs, LogsFilter, SendTransactionConfig,
+    SignatureResult, SignatureStatus, WithContext,
+};
+
+/// The most accounts `getMultipleAccounts` returns at once.
+pub const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+/// The most signatures `getSignatureStatuses` looks up at once.
+pub const MAX_SIGNATURE_STATUSES: usize = 256;
+
+/// Carries JSON-RPC requests to a node.
+#[async_trait]
+pub trait Transport: Send + Sync {
+    /// Sends one request and returns its `result`. A JSON-RPC error object
+    /// in the response is reported as [`Error::Rpc`].
+    async fn request(&self, method: &str, params: Value) -> Result<Value>;
+}
+
+/// A transport over a connection the node can push notifications on.
+///
+/// Subscribe and unsubscribe calls go through [`Transport::request`]; the
+/// transport routes the notifications that follow by subscription id,
+/// typically through [`Subscriptions`].
+#[async_trait]
+pub trait PubsubTransport: Transport {
+    /// Waits for the next notification of subscription `id` and returns its
+    /// `result`; `None` once the subscription or the connection has ended.
+    async fn next_notification(&self, id: u64) -> Result<Option<Value>>;
+
+    /// Forgets subscription `id`, which will not be read again.
+    fn release(&self, id: u64);
+}
+
+#[async_trait]
+impl<T: Transport + ?Sized> Transport for &T {
+    async fn request(&self, method: &str, params: Value) -> Result<Value> {
+        (**self).request(method, params).await
+    }
+}
+
+#[async_trait]
+impl<T: Transport + ?Sized> Transport for std::sync::Arc<T> {
+    async fn request(&self, method: &str, params: Value) -> Result<Value> {
+        (**self).request(method, params).await
+    }
+}
+
+#[async_trait]
+impl<T: PubsubTransport + ?Sized> PubsubTransport for &T {
+    async fn next_notification(&self, id: u64) -> Result<Option<Value>> {
+        (**self).next_notification(id).await
+    }
+
+    fn release(&self, id: u64) {
+        (**self).release(id)
+    }
+}
+
+#[async_trait]
+impl<T: PubsubTransport + ?Sized> PubsubTransport for std::sync::Arc<T> {
+    async fn next_notification(&self, id: u64) -> Result<Option<Value>> {
+        (**self).next_notification(id).await
+    }
+
+    fn release(&self, id: u64) {
+        (**self).release(id)
+    }
+}
+
+/// A Solana JSON-RPC client.
+#[derive(Debug, Clone)]
+pub struct Client<T> {
+    transport: T,
+    commitment: Commitment,
+}
+
+impl<T: Transport> Client<T> {
+    /// A client whose [`Provider`] calls use finalized state.
+    pub fn new(transport: T) -> Self {
+        Self {
+            transport,
+            commitment: Commitment::default(),
+        }
+    }
+
+    /// Sets the commitment of the [`Provider`] calls: the blockhash new
+    /// transactions reference, the state they are simulated against before
+    /// sending, and the level at which they count as finalized.
+    pub fn with_commitment(mut self, commitment: Commitment) -> Self {
+        self.commitment = commitment;
+        self
+    }
+
+    pub fn commitment(&self) -> Commitment {
+        self.commitment
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Calls `method` and deserializes its result.
+    pub async fn request<R: DeserializeOwned>(&self, method: &str, params: Value) -> Result<R> {
+        let result = self.transport.request(method, params).await?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    /// `getAccountInfo`; `None` if the account does not exist.
+    pub async fn get_account_info(
+        &self,
+        pubkey: &Pubkey,
+        commitment: Commitment,
+    ) -> Result<WithContext<Option<Account>>> {
+        self.request(
+            "getAccountInfo",
+            json!([pubkey, {"encoding": "base64", "commitment": commitment}]),
+        )
+        .await
+    }
+
+    /// `getMultipleAccounts`, in the order of `pubkeys`. At most
+    /// [`MAX_MULTIPLE_ACCOUNTS`] can be fetched at once.
+    pub async fn get_multiple_accounts(
+        &self,
+        pubkeys: &[Pubkey],
+        commitment: Commitment,
+    ) -> Result<WithContext<Vec<Option<Account>>>> {
+        if pubkeys.len() > MAX_MULTIPLE_ACCOUNTS {
+            return Err(Error::Provider(format!(
+                "{} accounts requested, at most {MAX_MULTIPLE_ACCOUNTS} allowed",
+                pubkeys.len()
+            )));
+        }
+        let accounts: WithContext<Vec<Option<Account>>> = self
+            .request(
+                "getMultipleAccounts",
+                json!([pubkeys, {"encoding": "base64", "commitment": commitment}]),
+            )
+            .await?;
+        if accounts.value.len() != pubkeys.len() {
+            return Err(Error::Provider(format!(
+                "{} accounts returned for {} requested",
+                accounts.value.len(),
+                pubkeys.len()
+            )));
+        }
+        Ok(accounts)
+    }
+
+    /// `getLatestBlockhash`.
+    pub async fn get_latest_blockhash(&self, commitment: Commitment) -> Result<LatestBlockhash> {
+        let response: WithContext<LatestBlockhash> = self
+            .request("getLatestBlockhash", json!([{"commitment": commitment}]))
+            .await?;
+        Ok(response.value)
+    }
+
+    /// `getBlockHeight`: the number of blocks beneath the latest one at
+    /// `commitment`. Blockhashes expire by block height, not slot.
+    pub async fn get_block_height(&self, commitment: Commitment) -> Result<u64> {
+        self.request("getBlockHeight", json!([{"commitment": commitment}]))
+            .await
+    }
+
+    /// `getSignatureStatuses`, in the order of `signatures`; `None` for
+    /// transactions the node does not know. Without `search_history` only
+    /// recent transactions are found. At most [`MAX_SIGNATURE_STATUSES`]
+    /// can be looked up at once.
+    pub async fn get_signature_statuses(
+        &self,
+        signatures: &[Signature],
+        search_history: bool,
+    ) -> Result<Vec<Option<SignatureStatus>>> {
+        if signatures.len() > MAX_SIGNATURE_STATUSES {
+            return Err(Error::Provider(format!(
+                "{} signatures requested, at most {MAX_SIGNATURE_STATUSES} allowed",
+                signatures.len()
+            )));
+        }
+        let response: WithContext<Vec<Option<SignatureStatus>>> = self
+            .request(
+                "getSignatureStatuses",
+                json!([signatures, {"searchTransactionHistory": search_history}]),
+            )
+            .await?;
+        if response.value.len() != signatures.len() {
+            return Err(Error::Provider(format!(
+                "{} statuses returned for {} signatures",
+                response.value.len(),
+                signatures.len()
+            )));
+        }
+        Ok(response.value)
+    }
+
+    /// `sendTransaction` of a serialized, signed transaction. A failed
+    /// preflight simulation is reported as [`Error::Rpc`], with the
+    /// simulation's error and logs as `data`.
+    pub async fn send_transaction(
+        &self,
+        wire: &[u8],
+        config: &SendTransactionConfig,
+    ) -> Result<Signature> {
+        let mut options = serde_json::to_value(config)?;
+        options["encoding"] = json!("base64");
+        self.request("sendTransaction", json!([BASE64.encode(wire), options]))
+            .await
+    }
+}
+
+impl<T: PubsubTransport> Client<T> {
+    async fn subscribe<R: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Value,
+        unsubscribe_method: &'static str,
+        one_shot: bool,
+    ) -> Result<Subscription<'_, T, R>> {
+        let id: u64 = self.request(method, params).await?;
+        Ok(Subscription::new(
+            &self.transport,
+            id,
+            unsubscribe_method,
+            one_shot,
+        ))
+    }
+
+    /// `accountSubscribe`: the account each time its lamports or data
+    /// change at `commitment`.
+    pub async fn account_subscribe(
+        &self,
+        pubkey: &Pubkey,
+        commitment: Commitment,
+    ) -> Result<Subscription<'_, T, WithContext<Account>>> {
+        self.subscribe(
+            "accountSubscribe",
+            json!([pubkey, {"encoding": "base64", "commitment": commitment}]),
+            "accountUnsubscribe",
+            false,
+        )
+        .await
+    }
+
+    /// `logsSubscribe`: the logs of each transaction `filter` selects.
+    pub async fn logs_subscribe(
+        &self,
+        filter: LogsFilter,
+        commitment: Commitment,
+    ) -> Result<Subscription<'_, T, WithContext<Logs>>> {
+        self.subscribe(
+            "logsSubscribe",
+            json!([filter, {"commitment": commitment}]),
+            "logsUnsubscribe",
+            false,
+        )
+        .await
+    }
+
+    /// `signatureSubscribe`: a single notification once the transaction
+    /// reaches `commitment`, after which the subscription ends.
+    pub async fn signature_subscribe(
+        &self,
+        signature: &Signature,
+        commitment: Commitment,
+    ) -> Result<Subscription<'_, T, WithContext<SignatureResult>>> {
+        self.subscribe(
+            "signatureSubscribe",
+            json!([signature, {"commitment": commitment}]),
+            "signatureUnsubscribe",
+            true,
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl<T: Transport> Provider for Client<T> {
+    async fn latest_blockhash(&self) -> Result<Hash> {
+        Ok(self.get_latest_blockhash(self.commitment).await?.blockhash)
+    }
+
+    async fn send_transaction(&self, wire: &[u8]) -> Result<Signature> {
+        let config = SendTransactionConfig {
+            preflight_commitment: Some(self.commitment),
+            ..SendTransactionConfig::default()
+        };
+        Client::send_transaction(self, wire, &config).await
+    }
+
+    /// Transactions count as finalized, or failed if they erred, once they
+    /// reach the client's commitment. Below it, those a supermajority has
+    /// voted on are confirmed, and processed ones, whose block may still be
+    /// skipped, pending; an error is not final until then.
+    async fn signature_confirmation(&self, signature: &Signature) -> Result<Confirmation> {
+        let status = self
+            .get_signature_statuses(std::slice::from_ref(signature), true)
+            .await?
+            .pop()
+            .flatten();
+        let Some(status) = status else {
+            return Ok(Confirmation::NotFound);
+        };
+        let height = status.slot;
+        Ok(if status.satisfies(self.commitment) {
+            match status.err {
+                Some(err) => Confirmation::Failed {
+                    height: Some(height),
+                    reason: err.to_string(),
+                },
+                None => Confirmation::Finalized { height },
+            }
+        } else if status.satisfies(Commitment::Confirmed) {
+            Confirmation::Confirmed { height }
+        } else {
+            Confirmation::Pending
+        })
+    }
+}
@@ -0,0 +1,176 @@
+use std::collections::{HashMap, VecDeque};
+use std::future::poll_fn;
+use std::marker::PhantomData;
+use std::sync::Mutex;
+use std::task::{Poll, Waker};
+
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use super::jsonrpc::Notification;
+use super::PubsubTransport;
+use crate::error::Result;
+
+#[derive(Debug, Default)]
+struct Channel {
+    queue: VecDeque<Value>,
+    closed: bool,
+    waker: Option<Waker>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    channels: HashMap<u64, Channel>,
+    disconnected: bool,
+}
+
+/// Routes notifications to the readers of their subscriptions.
+///
+/// A websocket transport feeds every notification it reads to
+/// [`dispatch`](Self::dispatch) and implements
+/// [`PubsubTransport::next_notification`] with [`next`](Self::next).
+/// Notifications are buffered by subscription id, so those that arrive
+/// before the subscribe call has returned are not lost.
+#[derive(Debug, Default)]
+pub struct Subscriptions {
+    state: Mutex<State>,
+}
+
+impl Subscriptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn with_channel(&self, id: u64, f: impl FnOnce(&mut Channel)) {
+        let mut state = self.state.lock().expect("subscriptions lock poisoned");
+        let channel = state.channels.entry(id).or_default();
+        f(channel);
+        if let Some(waker) = channel.waker.take() {
+            waker.wake();
+        }
+    }
+
+    pub fn dispatch(&self, notification: Notification) {
+        self.push(notification.params.subscription, notification.params.result);
+    }
+
+    /// Queues the `result` of a notification for subscription `id`.
+    pub fn push(&self, id: u64, result: Value) {
+        self.with_channel(id, |channel| channel.queue.push_back(result));
+    }
+
+    /// Ends subscription `id` once its queued notifications are read.
+    pub fn close(&self, id: u64) {
+        self.with_channel(id, |channel| channel.closed = true);
+    }
+
+    /// Ends every subscription, as when the connection drops.
+    pub fn disconnect(&self) {
+        let mut state = self.state.lock().expect("subscriptions lock poisoned");
+        state.disconnected = true;
+        for channel in state.channels.values_mut() {
+            if let Some(waker) = channel.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+
+    /// Forgets subscription `id` and whatever is queued for it.
+    pub fn remove(&self, id: u64) {
+        let mut state = self.state.lock().expect("subscriptions lock poisoned");
+        if let Some(mut channel) = state.channels.remove(&id) {
+            if let Some(waker) = channel.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+
+    /// Waits for the next notification of subscription `id`; `None` once it
+    /// is closed and drained, or the connection is gone.
+    pub async fn next(&self, id: u64) -> Option<Value> {
+        poll_fn(|cx| {
+            let mut state = self.state.lock().expect("subscriptions lock poisoned");
+            let disconnected = state.disconnected;
+            let channel = state.channels.entry(id).or_default();
+            if let Some(result) = channel.queue.pop_front() {
+                return Poll::Ready(Some(result));
+            }
+            if channel.closed || disconnected {
+                return Poll::Ready(None);
+            }
+            channel.waker = Some(cx.waker().clone());
+            Poll::Pending
+        })
+        .await
+    }
+}
+
+/// A live subscription, yielding its notifications as `R`.
+///
+/// Dropping it without [`unsubscribe`](Self::unsubscribe) leaves the node
+/// sending notifications until the connection closes.
+#[derive(Debug)]
+pub struct Subscription<'a, T: PubsubTransport, R> {
+    transport: &'a T,
+    id: u64,
+    unsubscribe_method: &'static str,
+    /// Signature subscriptions end with their first notification.
+    one_shot: bool,
+    done: bool,
+    _result: PhantomData<fn() -> R>,
+}
+
+impl<'a, T: PubsubTransport, R: DeserializeOwned> Subscription<'a, T, R> {
+    pub(super) fn new(
+        transport: &'a T,
+        id: u64,
+        unsubscribe_method: &'static str,
+        one_shot: bool,
+    ) -> Self {
+        Self {
+            transport,
+            id,
+            unsubscribe_method,
+            one_shot,
+            done: false,
+            _result: PhantomData,
+        }
+    }
+
+    /// The id the node assigned.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Waits for the next notification; `None` once the subscription has
+    /// ended.
+    pub async fn next(&mut self) -> Result<Option<R>> {
+        if self.done {
+            return Ok(None);
+        }
+        let Some(result) = self.transport.next_notification(self.id).await? else {
+            self.done = true;
+            self.transport.release(self.id);
+            return Ok(None);
+        };
+        if self.one_shot {
+            self.done = true;
+            self.transport.release(self.id);
+        }
+        Ok(Some(serde_json::from_value(result)?))
+    }
+
+    /// Cancels the subscription. A subscription that has already ended
+    /// needs no call to the node.
+    pub async fn unsubscribe(mut self) -> Result<()> {
+        if self.done {
+            return Ok(());
+        }
+        self.done = true;
+        self.transport.release(self.id);
+        self.transport
+            .request(self.unsubscribe_method, json!([self.id]))
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,183 @@
+//! Request and response objects of the Solana RPC and PubSub APIs.
+
+use std::fmt;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::Value;
+
+use crate::hash::Hash;
+use crate::signature::Signature;
+use crate::Pubkey;
+
+/// How settled the state a request reads, or waits for, must be.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Commitment {
+    /// The node's most recent block, which the cluster may still skip.
+    Processed,
+    /// A block voted on by a supermajority of the cluster.
+    Confirmed,
+    /// A block rooted by a supermajority of the cluster. The nodes' own
+    /// default.
+    #[default]
+    Finalized,
+}
+
+impl fmt::Display for Commitment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Commitment::Processed => "processed",
+            Commitment::Confirmed => "confirmed",
+            Commitment::Finalized => "finalized",
+        })
+    }
+}
+
+/// The slot a response was read at.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Context {
+    pub slot: u64,
+}
+
+/// A value and the slot it was read at, as most methods and every
+/// notification return it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WithContext<T> {
+    pub context: Context,
+    pub value: T,
+}
+
+/// An account as `getAccountInfo` reports it with base64 data.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Account {
+    pub lamports: u64,
+    /// The program that owns the account.
+    pub owner: Pubkey,
+    #[serde(with = "base64_data")]
+    pub data: Vec<u8>,
+    pub executable: bool,
+    pub rent_epoch: u64,
+}
+
+/// Account data as the `[data, encoding]` pair of the base64 encoding.
+mod base64_data {
+    use super::*;
+    use serde::de::Error as _;
+    use serde::Deserializer;
+
+    pub fn serialize<S: Serializer>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        (BASE64.encode(data), "base64").serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let (data, encoding) = <(String, String)>::deserialize(deserializer)?;
+        if encoding != "base64" {
+            return Err(D::Error::custom(format!(
+                "expected base64 account data, got {encoding}"
+            )));
+        }
+        BASE64.decode(data).map_err(D::Error::custom)
+    }
+}
+
+/// `getLatestBlockhash`: a blockhash and the last block height at which
+/// transactions referencing it are still accepted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatestBlockhash {
+    pub blockhash: Hash,
+    pub last_valid_block_height: u64,
+}
+
+/// Where a transaction stands, from `getSignatureStatuses`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignatureStatus {
+    /// The slot the transaction was processed in.
+    pub slot: u64,
+    /// Blocks confirmed on top of it; `None` once rooted.
+    pub confirmations: Option<u64>,
+    /// The transaction error, if it failed.
+    pub err: Option<Value>,
+    #[serde(default)]
+    pub confirmation_status: Option<Commitment>,
+}
+
+impl SignatureStatus {
+    /// The commitment the transaction has reached. Nodes that predate
+    /// `confirmationStatus` only report `confirmations`, which is `None`
+    /// once the block is rooted.
+    pub fn commitment(&self) -> Commitment {
+        match (self.confirmation_status, self.confirmations) {
+            (Some(status), _) => status,
+            (None, None) => Commitment::Finalized,
+            (None, Some(_)) => Commitment::Processed,
+        }
+    }
+
+    /// Whether the transaction has reached `commitment`.
+    pub fn satisfies(&self, commitment: Commitment) -> bool {
+        self.commitment() >= commitment
+    }
+}
+
+/// Options of `sendTransaction`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendTransactionConfig {
+    /// Skips the simulation the node runs before forwarding.
+    pub skip_preflight: bool,
+    /// The state the simulation runs against; the node's default is
+    /// finalized.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preflight_commitment: Option<Commitment>,
+    /// How often the node rebroadcasts the transaction until it expires.
+    /// `None` leaves it to the node.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_retries: Option<usize>,
+    /// Refuses to send unless the node has reached this slot.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_context_slot: Option<u64>,
+}
+
+/// Which transactions `logsSubscribe` reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogsFilter {
+    /// Every transaction except simple votes.
+    All,
+    AllWithVotes,
+    /// Transactions that mention the account.
+    Mentions(Pubkey),
+}
+
+impl Serialize for LogsFilter {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            LogsFilter::All => serializer.serialize_str("all"),
+            LogsFilter::AllWithVotes => serializer.serialize_str("allWithVotes"),
+            LogsFilter::Mentions(key) => {
+                serde_json::json!({ "mentions": [key] }).serialize(serializer)
+            }
+        }
+    }
+}
+
+/// A `logsNotification`: the logs of one transaction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Logs {
+    pub signature: Signature,
+    pub err: Option<Value>,
+    pub logs: Vec<String>,
+}
+
+/// A `signatureNotification`: the transaction reached the subscribed
+/// commitment, having succeeded unless `err` is set.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignatureResult {
+    pub err: Option<Value>,
+}
@@ -0,0 +1,265 @@
+//! Sending transactions until they land.
+//!
+//! A transaction that references a recent blockhash is accepted only while
+//! the cluster's block height is at most the `lastValidBlockHeight` that
+//! came with the blockhash, some 150 blocks. Nodes drop transactions
+//! without notice, so [`Sender`] rebroadcasts until the transaction reaches
+//! the client's commitment. When the blockhash expires first, the
+//! transaction is re-signed with a fresh one, but only once the old
+//! signature is provably dead: the finalized block height is past its last
+//! valid height and the node's history does not know the signature.
+//! Re-signing any earlier could land the same transfer twice.
+//!
+//! The state of a transaction in flight is a [`PendingTransaction`]. It
+//! serializes, so it can be persisted before the transaction is first sent
+//! and again whenever it is re-signed; a sender that restarts from the
+//! stored state can neither lose the transaction nor send it twice.
+
+use std::future::Future;
+
+use crossbeam_core::Signer;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+use crate::message::VersionedMessage;
+use crate::rpc::{serde_base64, Client, Commitment, SendTransactionConfig, Transport};
+use crate::signature::Signature;
+use crate::transaction::Transaction;
+
+/// A signed transaction on its way to the cluster.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingTransaction {
+    /// The signature of the current attempt, its transaction id.
+    pub signature: Signature,
+    /// The wire format of the current attempt.
+    #[serde(with = "serde_base64")]
+    pub wire: Vec<u8>,
+    /// The last block height at which the current attempt can land.
+    pub last_valid_block_height: u64,
+    /// Earlier attempts, each proven dead before it was replaced.
+    pub expired: Vec<Signature>,
+}
+
+impl PendingTransaction {
+    pub fn transaction(&self) -> Result<Transaction> {
+        Transaction::decode(&self.wire)
+    }
+}
+
+/// Where a [`PendingTransaction`] stands.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SendStatus {
+    /// Not at the client's commitment yet, with the blockhash possibly
+    /// still valid.
+    Pending,
+    /// Reached the client's commitment and succeeded.
+    Landed { signature: Signature, slot: u64 },
+    /// Reached the client's commitment but failed. The fee is spent and the
+    /// transaction cannot land again.
+    Failed {
+        signature: Signature,
+        slot: u64,
+        err: Value,
+    },
+    /// The blockhash expired before the transaction landed, and it never
+    /// will; [`Sender::renew`] may re-sign it.
+    Expired,
+}
+
+/// Sends transactions and follows them until they land or expire.
+#[derive(Debug, Clone)]
+pub struct Sender<T> {
+    client: Client<T>,
+}
+
+impl<T: Transport> Sender<T> {
+    /// A sender waiting for the commitment of `client`, which also governs
+    /// the blockhashes it fetches.
+    pub fn new(client: Client<T>) -> Self {
+        Self { client }
+    }
+
+    pub fn client(&self) -> &Client<T> {
+        &self.client
+    }
+
+    fn commitment(&self) -> Commitment {
+        self.client.commitment()
+    }
+
+    /// Signs `message` with a fresh blockhash, without sending it. Persist
+    /// the result, then [`send`](Self::send) it.
+    ///
+    /// Messages on a durable nonce do not expire by block height and are
+    /// rejected.
+    pub async fn prepare(
+        &self,
+        message: impl Into<VersionedMessage>,
+        signers: &[&dyn Signer],
+    ) -> Result<PendingTransaction> {
+        let message = message.into();
+        if message.uses_durable_nonce() {
+            return Err(Error::InvalidTransaction(
+                "durable nonce transactions do not expire by block height".to_owned(),
+            ));
+        }
+        self.sign(message, signers, Vec::new()).await
+    }
+
+    async fn sign(
+        &self,
+        mut message: VersionedMessage,
+        signers: &[&dyn Signer],
+        expired: Vec<Signature>,
+    ) -> Result<PendingTransaction> {
+        let latest = self.client.get_latest_blockhash(self.commitment()).await?;
+        message.set_recent_blockhash(latest.blockhash);
+        let mut tx = Transaction::new(message);
+        tx.sign(signers)?;
+        tx.ensure_signed()?;
+        Ok(PendingTransaction {
+            signature: tx.id(),
+            wire: tx.encode(),
+            last_valid_block_height: latest.last_valid_block_height,
+            expired,
+        })
+    }
+
+    /// Broadcasts the current attempt after simulating it. A transaction
+    /// the node has already processed counts as sent.
+    pub async fn send(&self, pending: &PendingTransaction) -> Result<Signature> {
+        let config = SendTransactionConfig {
+            preflight_commitment: Some(self.commitment()),
+            ..SendTransactionConfig::default()
+        };
+        match self.client.send_transaction(&pending.wire, &config).await {
+            Ok(signature) if signature != pending.signature => Err(Error::Provider(format!(
+                "node reports {signature} for transaction {}",
+                pending.signature
+            ))),
+            Err(err) if is_already_processed(&err) => Ok(pending.signature),
+            other => other,
+        }
+    }
+
+    /// Checks on the current attempt, and rebroadcasts it while the node
+    /// has not seen it and its blockhash may still be valid.
+    ///
+    /// A rebroadcast skips the simulation, and one that fails is not an
+    /// error: the next poll tries again.
+    pub async fn poll(&self, pending: &PendingTransaction) -> Result<SendStatus> {
+        let (status, seen) = self.check(pending).await?;
+        if status == SendStatus::Pending && !seen {
+            let config = SendTransactionConfig {
+                skip_preflight: true,
+                ..SendTransactionConfig::default()
+            };
+            let _ = self.client.send_transaction(&pending.wire, &config).await;
+        }
+        Ok(status)
+    }
+
+    /// The status of the current attempt, and whether the node has seen
+    /// it at all.
+    async fn check(&self, pending: &PendingTransaction) -> Result<(SendStatus, bool)> {
+        // The height must be read first. Once the finalized height is past
+        // the last valid one, every block that could hold the transaction
+        // is final, so a status lookup that follows is conclusive; in the
+        // other order the transaction could land in between.
+        let finalized_height = self.client.get_block_height(Commitment::Finalized).await?;
+        let status = self
+            .client
+            .get_signature_statuses(&[pending.signature], true)
+            .await?
+            .pop()
+            .flatten();
+        let signature = pending.signature;
+        Ok(match status {
+            Some(status) if status.satisfies(self.commitment()) => match status.err {
+                Some(err) => (
+                    SendStatus::Failed {
+                        signature,
+                        slot: status.slot,
+                        err,
+                    },
+                    true,
+                ),
+                None => (
+                    SendStatus::Landed {
+                        signature,
+                        slot: status.slot,
+                    },
+                    true,
+                ),
+            },
+            // Processed or confirmed on a fork that may yet be finalized.
+            Some(_) => (SendStatus::Pending, true),
+            None if finalized_height > pending.last_valid_block_height => {
+                (SendStatus::Expired, false)
+            }
+            None => (SendStatus::Pending, false),
+        })
+    }
+
+    /// Re-signs the transaction with a fresh blockhash if the current
+    /// attempt is provably dead, returning whether it did. Nothing is
+    /// sent: persist the renewed transaction, then [`send`](Self::send)
+    /// it.
+    ///
+    /// `signers` must cover every required signature.
+    pub async fn renew(
+        &self,
+        pending: &mut PendingTransaction,
+        signers: &[&dyn Signer],
+    ) -> Result<bool> {
+        if self.check(pending).await?.0 != SendStatus::Expired {
+            return Ok(false);
+        }
+        let message = pending.transaction()?.message;
+        let mut expired = pending.expired.clone();
+        expired.push(pending.signature);
+        *pending = self.sign(message, signers, expired).await?;
+        Ok(true)
+    }
+
+    /// Follows the transaction until it lands or fails, rebroadcasting and
+    /// renewing it as needed; returns [`SendStatus::Landed`] or
+    /// [`SendStatus::Failed`].
+    ///
+    /// `wait` is awaited between polls, typically a sleep of a slot or
+    /// two. `save` is called with each renewed transaction before it is
+    /// sent, and must persist it. An error leaves `pending` in a state to
+    /// resume from.
+    pub async fn confirm<W: Future<Output = ()>>(
+        &self,
+        pending: &mut PendingTransaction,
+        signers: &[&dyn Signer],
+        mut wait: impl FnMut() -> W,
+        mut save: impl FnMut(&PendingTransaction) -> Result<()>,
+    ) -> Result<SendStatus> {
+        loop {
+            match self.poll(pending).await? {
+                SendStatus::Pending => wait().await,
+                SendStatus::Expired => {
+                    let mut renewed = pending.clone();
+                    if self.renew(&mut renewed, signers).await? {
+                        save(&renewed)?;
+                        *pending = renewed;
+                        self.send(pending).await?;
+                    }
+                }
+                done => return Ok(done),
+            }
+        }
+    }
+}
+
+/// Whether a send failed because the node already processed the
+/// transaction.
+fn is_already_processed(err: &Error) -> bool {
+    matches!(
+        err,
+        Error::Rpc { data: Some(data), .. } if data["err"] == "AlreadyProcessed"
+    )
+}
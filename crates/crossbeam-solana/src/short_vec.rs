@@ -0,0 +1,97 @@
+//! The compact-u16 length prefix of Solana's wire format.
+//!
+//! Lengths are written seven bits at a time, least significant first, with
+//! the high bit of each byte flagging that another follows. A `u16` takes at
+//! most three bytes.
+
+use crate::error::{Error, Result};
+
+/// Appends the compact-u16 encoding of `len`.
+pub fn encode_len(len: u16, out: &mut Vec<u8>) {
+    let mut rest = len;
+    loop {
+        let byte = (rest & 0x7f) as u8;
+        rest >>= 7;
+        if rest == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+/// Reads a compact-u16 from the front of `input`, returning it with the
+/// bytes that follow. Encodings with redundant zero bytes are rejected, as
+/// the runtime does.
+pub fn decode_len(input: &[u8]) -> Result<(u16, &[u8])> {
+    let mut len: u32 = 0;
+    for (i, &byte) in input.iter().enumerate().take(3) {
+        len |= u32::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            if byte == 0 && i > 0 {
+                return Err(decode_error("non-canonical compact-u16"));
+            }
+            let len = u16::try_from(len).map_err(|_| decode_error("compact-u16 overflows"))?;
+            return Ok((len, &input[i + 1..]));
+        }
+    }
+    Err(decode_error(if input.len() < 3 {
+        "truncated compact-u16"
+    } else {
+        "compact-u16 longer than three bytes"
+    }))
+}
+
+fn decode_error(reason: &str) -> Error {
+    Error::InvalidTransaction(reason.to_owned())
+}
+
+/// Appends `items`, prefixed with their count.
+///
+/// # Panics
+///
+/// If there are more than `u16::MAX` items; callers bound their lists well
+/// below that.
+pub fn encode<T>(items: &[T], out: &mut Vec<u8>, mut item: impl FnMut(&T, &mut Vec<u8>)) {
+    let len = u16::try_from(items.len()).expect("short_vec of at most u16::MAX items");
+    encode_len(len, out);
+    for value in items {
+        item(value, out);
+    }
+}
+
+/// Reads a length-prefixed list, decoding each item from the front of the
+/// remaining input.
+pub fn decode<'a, T>(
+    input: &'a [u8],
+    mut item: impl FnMut(&'a [u8]) -> Result<(T, &'a [u8])>,
+) -> Result<(Vec<T>, &'a [u8])> {
+    let (len, mut rest) = decode_len(input)?;
+    // Every item takes at least a byte; don't trust the prefix beyond that.
+    let mut items = Vec::with_capacity(usize::from(len).min(rest.len()));
+    for _ in 0..len {
+        let (value, tail) = item(rest)?;
+        items.push(value);
+        rest = tail;
+    }
+    Ok((items, rest))
+}
+
+/// Splits `N` bytes off the front of `input`.
+pub(crate) fn take<const N: usize>(input: &[u8]) -> Result<([u8; N], &[u8])> {
+    if input.len() < N {
+        return Err(decode_error("truncated message"));
+    }
+    let (head, rest) = input.split_at(N);
+    Ok((head.try_into().expect("N bytes"), rest))
+}
+
+/// A length-prefixed byte string.
+pub(crate) fn decode_bytes(input: &[u8]) -> Result<(Vec<u8>, &[u8])> {
+    let (len, rest) = decode_len(input)?;
+    let len = usize::from(len);
+    if rest.len() < len {
+        return Err(decode_error("truncated message"));
+    }
+    Ok((rest[..len].to_vec(), &rest[len..]))
+}
@@ -0,0 +1,118 @@
+//! The system program: lamport transfers, account creation and durable
+//! nonces.
+
+use crate::error::{Error, Result};
+use crate::hash::Hash;
+use crate::instruction::{AccountMeta, Instruction};
+use crate::Pubkey;
+
+pub const SYSTEM_PROGRAM_ID: Pubkey = Pubkey::new([0; 32]);
+/// `SysvarRecentB1ockHashes11111111111111111111`, which nonce instructions
+/// still take.
+pub const SYSVAR_RECENT_BLOCKHASHES_ID: Pubkey = Pubkey::new([
+    6, 167, 213, 23, 25, 44, 86, 142, 224, 138, 132, 95, 115, 210, 151, 136, 207, 3, 92, 49, 69,
+    178, 26, 179, 68, 216, 6, 46, 169, 64, 0, 0,
+]);
+/// `SysvarRent111111111111111111111111111111111`.
+pub const SYSVAR_RENT_ID: Pubkey = Pubkey::new([
+    6, 167, 213, 23, 25, 44, 92, 81, 33, 140, 201, 76, 61, 74, 241, 127, 88, 218, 238, 8, 155, 161,
+    253, 68, 227, 219, 217, 138, 0, 0, 0, 0,
+]);
+
+/// Size of an initialized nonce account.
+pub const NONCE_ACCOUNT_LENGTH: usize = 80;
+
+const CREATE_ACCOUNT: u32 = 0;
+const TRANSFER: u32 = 2;
+const ADVANCE_NONCE_ACCOUNT: u32 = 4;
+
+/// Instruction data: the little-endian variant tag, then the arguments.
+fn data(tag: u32, args: &[&[u8]]) -> Vec<u8> {
+    let mut data = tag.to_le_bytes().to_vec();
+    for arg in args {
+        data.extend_from_slice(arg);
+    }
+    data
+}
+
+/// Moves `lamports` from `from`, which signs, to `to`.
+pub fn transfer(from: &Pubkey, to: &Pubkey, lamports: u64) -> Instruction {
+    Instruction::new(
+        SYSTEM_PROGRAM_ID,
+        vec![AccountMeta::new(*from, true), AccountMeta::new(*to, false)],
+        data(TRANSFER, &[&lamports.to_le_bytes()]),
+    )
+}
+
+/// Creates `to` with `space` bytes owned by `owner`, funded by `from`. Both
+/// sign.
+pub fn create_account(
+    from: &Pubkey,
+    to: &Pubkey,
+    lamports: u64,
+    space: u64,
+    owner: &Pubkey,
+) -> Instruction {
+    Instruction::new(
+        SYSTEM_PROGRAM_ID,
+        vec![AccountMeta::new(*from, true), AccountMeta::new(*to, true)],
+        data(
+            CREATE_ACCOUNT,
+            &[&lamports.to_le_bytes(), &space.to_le_bytes(), &owner.0],
+        ),
+    )
+}
+
+/// Consumes the nonce stored in `nonce_account`. A transaction that starts
+/// with this instruction uses the nonce in place of a recent blockhash and
+/// stays valid until the nonce advances.
+pub fn advance_nonce_account(nonce_account: &Pubkey, authority: &Pubkey) -> Instruction {
+    Instruction::new(
+        SYSTEM_PROGRAM_ID,
+        vec![
+            AccountMeta::new(*nonce_account, false),
+            AccountMeta::new_readonly(SYSVAR_RECENT_BLOCKHASHES_ID, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+        data(ADVANCE_NONCE_ACCOUNT, &[]),
+    )
+}
+
+/// Whether an instruction of `program_id` with `data` advances a nonce
+/// account.
+pub fn is_advance_nonce(program_id: &Pubkey, data: &[u8]) -> bool {
+    *program_id == SYSTEM_PROGRAM_ID && data == ADVANCE_NONCE_ACCOUNT.to_le_bytes()
+}
+
+/// The state of an initialized nonce account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NonceAccount {
+    /// The key that may advance the nonce.
+    pub authority: Pubkey,
+    /// The value transactions use as their blockhash.
+    pub nonce: Hash,
+    pub lamports_per_signature: u64,
+}
+
+impl NonceAccount {
+    /// Decodes the data of a nonce account, which must be initialized.
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let data: &[u8; NONCE_ACCOUNT_LENGTH] =
+            data.try_into().map_err(|_| Error::InvalidLength {
+                expected: NONCE_ACCOUNT_LENGTH,
+                actual: data.len(),
+            })?;
+        // The version (1 since nonces became durable) and the state (1 for
+        // initialized), both as u32.
+        if data[..8] != [1, 0, 0, 0, 1, 0, 0, 0] {
+            return Err(Error::InvalidAccount(
+                "not an initialized nonce account".to_owned(),
+            ));
+        }
+        Ok(Self {
+            authority: Pubkey::new(data[8..40].try_into().expect("32 bytes")),
+            nonce: Hash(data[40..72].try_into().expect("32 bytes")),
+            lamports_per_signature: u64::from_le_bytes(data[72..].try_into().expect("8 bytes")),
+        })
+    }
+}
@@ -0,0 +1,352 @@
+//! The SPL Token and Token-2022 programs: instructions and account layouts.
+//!
+//! Both programs share their instruction set and base account layouts.
+//! Token-2022 appends extensions to the base layout; see
+//! [`token_2022`](crate::token_2022) for those.
+
+use crate::associated_token::{
+    associated_token_address, create_associated_token_account_idempotent,
+};
+use crate::error::{Error, Result};
+use crate::instruction::{AccountMeta, Instruction};
+use crate::system;
+use crate::token_2022::Extensions;
+use crate::Pubkey;
+
+/// `TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA`.
+pub const TOKEN_PROGRAM_ID: Pubkey = Pubkey::new([
+    6, 221, 246, 225, 215, 101, 161, 147, 217, 203, 225, 70, 206, 235, 121, 172, 28, 180, 133, 237,
+    95, 91, 55, 145, 58, 140, 245, 133, 126, 255, 0, 169,
+]);
+/// `TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb`.
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = Pubkey::new([
+    6, 221, 246, 225, 238, 117, 143, 222, 24, 66, 93, 188, 228, 108, 205, 218, 182, 26, 252, 77,
+    131, 185, 13, 39, 254, 189, 249, 40, 216, 161, 139, 252,
+]);
+/// Wrapped SOL, `So11111111111111111111111111111111111111112`.
+pub const NATIVE_MINT: Pubkey = Pubkey::new([
+    6, 155, 136, 87, 254, 171, 129, 132, 251, 104, 127, 99, 70, 24, 192, 53, 218, 196, 57, 220, 26,
+    235, 59, 85, 152, 160, 240, 0, 0, 0, 0, 1,
+]);
+/// Wrapped SOL under Token-2022, `9pan9bMn5HatX4EJdBwg9VgCa7Uz5HL8N1m5D3NdXejP`.
+pub const NATIVE_MINT_2022: Pubkey = Pubkey::new([
+    131, 13, 252, 159, 222, 95, 230, 184, 170, 124, 4, 164, 118, 233, 30, 138, 198, 187, 38, 74,
+    173, 144, 250, 25, 201, 223, 73, 216, 92, 62, 91, 94,
+]);
+
+/// Size of a mint without extensions.
+pub const MINT_LENGTH: usize = 82;
+/// Size of a token account without extensions.
+pub const ACCOUNT_LENGTH: usize = 165;
+
+const APPROVE: u8 = 4;
+const MINT_TO: u8 = 7;
+const BURN: u8 = 8;
+const CLOSE_ACCOUNT: u8 = 9;
+const TRANSFER_CHECKED: u8 = 12;
+const SYNC_NATIVE: u8 = 17;
+
+/// Whether `program_id` is one of the token programs.
+pub fn is_token_program(program_id: &Pubkey) -> bool {
+    TokenProgram::from_id(program_id).is_some()
+}
+
+/// Which token program owns a mint, and so builds its instructions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenProgram {
+    Token,
+    Token2022,
+}
+
+impl TokenProgram {
+    pub fn id(self) -> Pubkey {
+        match self {
+            TokenProgram::Token => TOKEN_PROGRAM_ID,
+            TokenProgram::Token2022 => TOKEN_2022_PROGRAM_ID,
+        }
+    }
+
+    /// The program with id `program_id`, typically an account's owner.
+    pub fn from_id(program_id: &Pubkey) -> Option<Self> {
+        match *program_id {
+            TOKEN_PROGRAM_ID => Some(TokenProgram::Token),
+            TOKEN_2022_PROGRAM_ID => Some(TokenProgram::Token2022),
+            _ => None,
+        }
+    }
+
+    /// The program's wrapped SOL mint.
+    pub fn native_mint(self) -> Pubkey {
+        match self {
+            TokenProgram::Token => NATIVE_MINT,
+            TokenProgram::Token2022 => NATIVE_MINT_2022,
+        }
+    }
+
+    fn instruction(self, accounts: Vec<AccountMeta>, tag: u8, args: &[&[u8]]) -> Instruction {
+        let mut data = vec![tag];
+        for arg in args {
+            data.extend_from_slice(arg);
+        }
+        Instruction::new(self.id(), accounts, data)
+    }
+
+    /// Moves `amount` of `mint` from `source` to `destination`. The mint
+    /// and its `decimals` are checked on chain, and Token-2022 requires
+    /// them.
+    pub fn transfer_checked(
+        self,
+        source: &Pubkey,
+        mint: &Pubkey,
+        destination: &Pubkey,
+        authority: &Pubkey,
+        amount: u64,
+        decimals: u8,
+    ) -> Instruction {
+        self.instruction(
+            vec![
+                AccountMeta::new(*source, false),
+                AccountMeta::new_readonly(*mint, false),
+                AccountMeta::new(*destination, false),
+                AccountMeta::new_readonly(*authority, true),
+            ],
+            TRANSFER_CHECKED,
+            &[&amount.to_le_bytes(), &[decimals]],
+        )
+    }
+
+    /// Mints `amount` into `account`, signed by the mint authority.
+    pub fn mint_to(
+        self,
+        mint: &Pubkey,
+        account: &Pubkey,
+        mint_authority: &Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        self.instruction(
+            vec![
+                AccountMeta::new(*mint, false),
+                AccountMeta::new(*account, false),
+                AccountMeta::new_readonly(*mint_authority, true),
+            ],
+            MINT_TO,
+            &[&amount.to_le_bytes()],
+        )
+    }
+
+    /// Burns `amount` from `account`, signed by its owner or delegate.
+    pub fn burn(
+        self,
+        account: &Pubkey,
+        mint: &Pubkey,
+        authority: &Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        self.instruction(
+            vec![
+                AccountMeta::new(*account, false),
+                AccountMeta::new(*mint, false),
+                AccountMeta::new_readonly(*authority, true),
+            ],
+            BURN,
+            &[&amount.to_le_bytes()],
+        )
+    }
+
+    /// Lets `delegate` move up to `amount` out of `source`.
+    pub fn approve(
+        self,
+        source: &Pubkey,
+        delegate: &Pubkey,
+        owner: &Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        self.instruction(
+            vec![
+                AccountMeta::new(*source, false),
+                AccountMeta::new_readonly(*delegate, false),
+                AccountMeta::new_readonly(*owner, true),
+            ],
+            APPROVE,
+            &[&amount.to_le_bytes()],
+        )
+    }
+
+    /// Closes `account`, which must hold no tokens (wrapped SOL excepted),
+    /// and sends its lamports to `destination`.
+    pub fn close_account(
+        self,
+        account: &Pubkey,
+        destination: &Pubkey,
+        owner: &Pubkey,
+    ) -> Instruction {
+        self.instruction(
+            vec![
+                AccountMeta::new(*account, false),
+                AccountMeta::new(*destination, false),
+                AccountMeta::new_readonly(*owner, true),
+            ],
+            CLOSE_ACCOUNT,
+            &[],
+        )
+    }
+
+    /// Credits a wrapped SOL account with the lamports sent to it since
+    /// it was last synced.
+    pub fn sync_native(self, account: &Pubkey) -> Instruction {
+        self.instruction(vec![AccountMeta::new(*account, false)], SYNC_NATIVE, &[])
+    }
+
+    /// Wraps `lamports` into `owner`'s associated wrapped SOL account,
+    /// creating it if needed: create, fund, then sync. Closing the account
+    /// unwraps.
+    pub fn wrap_sol(
+        self,
+        payer: &Pubkey,
+        owner: &Pubkey,
+        lamports: u64,
+    ) -> Result<Vec<Instruction>> {
+        let mint = self.native_mint();
+        let account = associated_token_address(owner, &mint, &self.id())?;
+        Ok(vec![
+            create_associated_token_account_idempotent(payer, owner, &mint, &self.id())?,
+            system::transfer(payer, &account, lamports),
+            self.sync_native(&account),
+        ])
+    }
+}
+
+/// Splits `N` bytes off the front of `data`, which the caller has sized.
+fn split<const N: usize>(data: &[u8]) -> ([u8; N], &[u8]) {
+    let (head, rest) = data.split_at(N);
+    (head.try_into().expect("N bytes"), rest)
+}
+
+/// A `COption`: a u32 tag, then the value, present or zeroed.
+fn decode_option<const N: usize>(data: &[u8]) -> Result<(Option<[u8; N]>, &[u8])> {
+    let (tag, rest) = split::<4>(data);
+    let (value, rest) = split::<N>(rest);
+    match u32::from_le_bytes(tag) {
+        0 => Ok((None, rest)),
+        1 => Ok((Some(value), rest)),
+        tag => Err(Error::InvalidAccount(format!("option tag {tag}"))),
+    }
+}
+
+fn decode_u64(data: &[u8]) -> (u64, &[u8]) {
+    let (value, rest) = split::<8>(data);
+    (u64::from_le_bytes(value), rest)
+}
+
+/// A token mint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mint {
+    /// The key that may mint more, or `None` if the supply is fixed.
+    pub mint_authority: Option<Pubkey>,
+    pub supply: u64,
+    pub decimals: u8,
+    pub freeze_authority: Option<Pubkey>,
+    /// Token-2022 extensions; empty for Token mints.
+    pub extensions: Extensions,
+}
+
+impl Mint {
+    /// Decodes the data of an initialized mint, with any Token-2022
+    /// extensions.
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        if data.len() < MINT_LENGTH {
+            return Err(Error::InvalidLength {
+                expected: MINT_LENGTH,
+                actual: data.len(),
+            });
+        }
+        let (mint_authority, rest) = decode_option::<32>(data)?;
+        let (supply, rest) = decode_u64(rest);
+        let ([decimals, is_initialized], rest) = split::<2>(rest);
+        let (freeze_authority, _) = decode_option::<32>(rest)?;
+        if is_initialized != 1 {
+            return Err(Error::InvalidAccount("mint is not initialized".to_owned()));
+        }
+        if data.len() > MINT_LENGTH
+            && data[MINT_LENGTH..ACCOUNT_LENGTH.min(data.len())]
+                .iter()
+                .any(|b| *b != 0)
+        {
+            return Err(Error::InvalidAccount("mint padding is not zero".to_owned()));
+        }
+        Ok(Self {
+            mint_authority: mint_authority.map(Pubkey::new),
+            supply,
+            decimals,
+            freeze_authority: freeze_authority.map(Pubkey::new),
+            extensions: Extensions::decode(data, MINT_LENGTH, Extensions::MINT)?,
+        })
+    }
+}
+
+/// Whether a token account can be used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountState {
+    Initialized,
+    /// Frozen by the mint's freeze authority; no transfers in or out.
+    Frozen,
+}
+
+/// A token account: a balance of one mint held for an owner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenAccount {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub delegate: Option<Pubkey>,
+    pub state: AccountState,
+    /// For wrapped SOL accounts, the rent-exempt reserve not counted in
+    /// `amount`.
+    pub is_native: Option<u64>,
+    pub delegated_amount: u64,
+    pub close_authority: Option<Pubkey>,
+    /// Token-2022 extensions; empty for Token accounts.
+    pub extensions: Extensions,
+}
+
+impl TokenAccount {
+    /// Decodes the data of an initialized token account, with any
+    /// Token-2022 extensions.
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        if data.len() < ACCOUNT_LENGTH {
+            return Err(Error::InvalidLength {
+                expected: ACCOUNT_LENGTH,
+                actual: data.len(),
+            });
+        }
+        let (mint, rest) = split::<32>(data);
+        let (owner, rest) = split::<32>(rest);
+        let (amount, rest) = decode_u64(rest);
+        let (delegate, rest) = decode_option::<32>(rest)?;
+        let ([state], rest) = split::<1>(rest);
+        let (is_native, rest) = decode_option::<8>(rest)?;
+        let (delegated_amount, rest) = decode_u64(rest);
+        let (close_authority, _) = decode_option::<32>(rest)?;
+        let state = match state {
+            1 => AccountState::Initialized,
+            2 => AccountState::Frozen,
+            0 => {
+                return Err(Error::InvalidAccount(
+                    "token account is not initialized".to_owned(),
+                ))
+            }
+            state => return Err(Error::InvalidAccount(format!("account state {state}"))),
+        };
+        Ok(Self {
+            mint: Pubkey::new(mint),
+            owner: Pubkey::new(owner),
+            amount,
+            delegate: delegate.map(Pubkey::new),
+            state,
+            is_native: is_native.map(u64::from_le_bytes),
+            delegated_amount,
+            close_authority: close_authority.map(Pubkey::new),
+            extensions: Extensions::decode(data, ACCOUNT_LENGTH, Extensions::ACCOUNT)?,
+        })
+    }
+}
@@ -0,0 +1,334 @@
+//! Token-2022 extensions.
+//!
+//! A Token-2022 mint or account is the base layout, padded to the size of a
+//! token account, then an account-type byte and a list of type-length-value
+//! extensions. The extensions decoded here are those that change how tokens
+//! move: transfer fees, required memos and confidential transfers.
+
+use crate::error::{Error, Result};
+use crate::instruction::{AccountMeta, Instruction};
+use crate::token::{ACCOUNT_LENGTH, TOKEN_2022_PROGRAM_ID};
+use crate::Pubkey;
+
+/// Largest transfer fee rate: 100%.
+pub const MAX_FEE_BASIS_POINTS: u16 = 10_000;
+
+const TRANSFER_FEE_EXTENSION: u8 = 26;
+const TRANSFER_CHECKED_WITH_FEE: u8 = 1;
+const MEMO_TRANSFER_EXTENSION: u8 = 30;
+const ENABLE_REQUIRED_MEMOS: u8 = 0;
+const DISABLE_REQUIRED_MEMOS: u8 = 1;
+
+/// The kind of an extension, as stored in its TLV header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ExtensionType(pub u16);
+
+impl ExtensionType {
+    pub const TRANSFER_FEE_CONFIG: Self = Self(1);
+    pub const TRANSFER_FEE_AMOUNT: Self = Self(2);
+    pub const MINT_CLOSE_AUTHORITY: Self = Self(3);
+    pub const CONFIDENTIAL_TRANSFER_MINT: Self = Self(4);
+    pub const CONFIDENTIAL_TRANSFER_ACCOUNT: Self = Self(5);
+    pub const DEFAULT_ACCOUNT_STATE: Self = Self(6);
+    pub const IMMUTABLE_OWNER: Self = Self(7);
+    pub const MEMO_TRANSFER: Self = Self(8);
+    pub const NON_TRANSFERABLE: Self = Self(9);
+    pub const INTEREST_BEARING_CONFIG: Self = Self(10);
+    pub const CPI_GUARD: Self = Self(11);
+    pub const PERMANENT_DELEGATE: Self = Self(12);
+    pub const NON_TRANSFERABLE_ACCOUNT: Self = Self(13);
+    pub const TRANSFER_HOOK: Self = Self(14);
+    pub const TRANSFER_HOOK_ACCOUNT: Self = Self(15);
+    pub const METADATA_POINTER: Self = Self(18);
+    pub const TOKEN_METADATA: Self = Self(19);
+    pub const PAUSABLE: Self = Self(26);
+    pub const PAUSABLE_ACCOUNT: Self = Self(27);
+}
+
+/// One extension, undecoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Extension {
+    pub extension_type: ExtensionType,
+    pub data: Vec<u8>,
+}
+
+/// The extensions of a mint or token account, in stored order.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Extensions(pub Vec<Extension>);
+
+impl Extensions {
+    pub(crate) const MINT: u8 = 1;
+    pub(crate) const ACCOUNT: u8 = 2;
+
+    /// Decodes what follows the `base_length` bytes of the base layout in
+    /// `data`, which must be tagged `account_type`.
+    pub(crate) fn decode(data: &[u8], base_length: usize, account_type: u8) -> Result<Self> {
+        if data.len() == base_length {
+            return Ok(Self::default());
+        }
+        let Some((&tag, mut rest)) = data
+            .get(ACCOUNT_LENGTH..)
+            .and_then(|tail| tail.split_first())
+        else {
+            return Err(Error::InvalidAccount(format!(
+                "{} bytes is neither a base layout nor an extended one",
+                data.len()
+            )));
+        };
+        if tag != account_type {
+            return Err(Error::InvalidAccount(format!(
+                "account type {tag}, expected {account_type}"
+            )));
+        }
+        let mut extensions = Vec::new();
+        while rest.len() >= 4 {
+            let extension_type = u16::from_le_bytes([rest[0], rest[1]]);
+            let len = usize::from(u16::from_le_bytes([rest[2], rest[3]]));
+            // Space reserved for extensions not yet initialized.
+            if extension_type == 0 {
+                break;
+            }
+            let Some(value) = rest.get(4..4 + len) else {
+                return Err(Error::InvalidAccount(format!(
+                    "extension {extension_type} of {len} bytes overruns the account"
+                )));
+            };
+            extensions.push(Extension {
+                extension_type: ExtensionType(extension_type),
+                data: value.to_vec(),
+            });
+            rest = &rest[4 + len..];
+        }
+        Ok(Self(extensions))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn contains(&self, extension_type: ExtensionType) -> bool {
+        self.get(extension_type).is_some()
+    }
+
+    /// The data of the extension of `extension_type`, if present.
+    pub fn get(&self, extension_type: ExtensionType) -> Option<&[u8]> {
+        self.0
+            .iter()
+            .find(|extension| extension.extension_type == extension_type)
+            .map(|extension| extension.data.as_slice())
+    }
+
+    /// The extension of `extension_type`, if present, checked to be `N`
+    /// bytes.
+    fn get_fixed<const N: usize>(&self, extension_type: ExtensionType) -> Result<Option<&[u8; N]>> {
+        self.get(extension_type)
+            .map(|data| {
+                data.try_into().map_err(|_| {
+                    Error::InvalidAccount(format!(
+                        "extension {} of {} bytes, expected {N}",
+                        extension_type.0,
+                        data.len()
+                    ))
+                })
+            })
+            .transpose()
+    }
+
+    /// A mint's transfer fee schedule.
+    pub fn transfer_fee_config(&self) -> Result<Option<TransferFeeConfig>> {
+        let Some(data) = self.get_fixed::<108>(ExtensionType::TRANSFER_FEE_CONFIG)? else {
+            return Ok(None);
+        };
+        Ok(Some(TransferFeeConfig {
+            transfer_fee_config_authority: nonzero_key(&data[..32]),
+            withdraw_withheld_authority: nonzero_key(&data[32..64]),
+            withheld_amount: u64_at(data, 64),
+            older_transfer_fee: TransferFee::decode(&data[72..90]),
+            newer_transfer_fee: TransferFee::decode(&data[90..]),
+        }))
+    }
+
+    /// The fees withheld in a token account, awaiting harvest to the mint.
+    pub fn withheld_transfer_fees(&self) -> Result<Option<u64>> {
+        Ok(self
+            .get_fixed::<8>(ExtensionType::TRANSFER_FEE_AMOUNT)?
+            .map(|data| u64::from_le_bytes(*data)))
+    }
+
+    /// Whether a token account rejects transfers in that are not preceded
+    /// by a memo instruction.
+    pub fn memo_required(&self) -> Result<bool> {
+        Ok(self
+            .get_fixed::<1>(ExtensionType::MEMO_TRANSFER)?
+            .is_some_and(|[required]| *required != 0))
+    }
+
+    /// A mint's confidential transfer settings. Mints without them only
+    /// move tokens in the clear.
+    pub fn confidential_transfer_mint(&self) -> Result<Option<ConfidentialTransferMint>> {
+        let Some(data) = self.get_fixed::<65>(ExtensionType::CONFIDENTIAL_TRANSFER_MINT)? else {
+            return Ok(None);
+        };
+        Ok(Some(ConfidentialTransferMint {
+            authority: nonzero_key(&data[..32]),
+            auto_approve_new_accounts: data[32] != 0,
+            auditor_elgamal_pubkey: Some(data[33..].try_into().expect("32 bytes"))
+                .filter(|key: &[u8; 32]| key.iter().any(|b| *b != 0)),
+        }))
+    }
+
+    /// A token account's confidential transfer state.
+    pub fn confidential_transfer_account(&self) -> Result<Option<ConfidentialTransferAccount>> {
+        let Some(data) = self.get_fixed::<295>(ExtensionType::CONFIDENTIAL_TRANSFER_ACCOUNT)?
+        else {
+            return Ok(None);
+        };
+        Ok(Some(ConfidentialTransferAccount {
+            approved: data[0] != 0,
+            allow_confidential_credits: data[261] != 0,
+            allow_non_confidential_credits: data[262] != 0,
+        }))
+    }
+
+    /// Whether a token account accepts ordinary transfers in. Confidential
+    /// transfers are off unless configured, and an account configured for
+    /// them still takes plain credits unless its owner turned them off.
+    pub fn accepts_non_confidential_transfers(&self) -> Result<bool> {
+        Ok(self
+            .confidential_transfer_account()?
+            .map_or(true, |account| account.allow_non_confidential_credits))
+    }
+}
+
+/// An optional key stored as 32 bytes, all zero for none.
+fn nonzero_key(bytes: &[u8]) -> Option<Pubkey> {
+    let key: [u8; 32] = bytes.try_into().expect("32 bytes");
+    (key != [0; 32]).then(|| Pubkey::new(key))
+}
+
+fn u64_at(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().expect("8 bytes"))
+}
+
+/// A transfer fee rate, from `epoch` on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransferFee {
+    pub epoch: u64,
+    pub maximum_fee: u64,
+    pub transfer_fee_basis_points: u16,
+}
+
+impl TransferFee {
+    fn decode(data: &[u8]) -> Self {
+        Self {
+            epoch: u64_at(data, 0),
+            maximum_fee: u64_at(data, 8),
+            transfer_fee_basis_points: u16::from_le_bytes([data[16], data[17]]),
+        }
+    }
+
+    /// The fee withheld from a transfer of `amount`: the rate rounded up,
+    /// capped at the maximum.
+    pub fn calculate_fee(&self, amount: u64) -> u64 {
+        let rate = u128::from(self.transfer_fee_basis_points);
+        let fee = (u128::from(amount) * rate).div_ceil(u128::from(MAX_FEE_BASIS_POINTS));
+        fee.min(u128::from(self.maximum_fee)) as u64
+    }
+}
+
+/// A mint's transfer fee schedule: the current rate, and the next one once
+/// its epoch arrives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransferFeeConfig {
+    pub transfer_fee_config_authority: Option<Pubkey>,
+    pub withdraw_withheld_authority: Option<Pubkey>,
+    /// Fees harvested to the mint, not yet withdrawn.
+    pub withheld_amount: u64,
+    pub older_transfer_fee: TransferFee,
+    pub newer_transfer_fee: TransferFee,
+}
+
+impl TransferFeeConfig {
+    /// The rate in force during `epoch`.
+    pub fn epoch_fee(&self, epoch: u64) -> &TransferFee {
+        if epoch >= self.newer_transfer_fee.epoch {
+            &self.newer_transfer_fee
+        } else {
+            &self.older_transfer_fee
+        }
+    }
+
+    /// The fee on a transfer of `amount` during `epoch`, as
+    /// [`transfer_checked_with_fee`] expects it.
+    pub fn calculate_epoch_fee(&self, epoch: u64, amount: u64) -> u64 {
+        self.epoch_fee(epoch).calculate_fee(amount)
+    }
+}
+
+/// A mint's confidential transfer settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfidentialTransferMint {
+    /// The key that approves accounts and updates the settings.
+    pub authority: Option<Pubkey>,
+    /// Whether new accounts may transfer confidentially without approval.
+    pub auto_approve_new_accounts: bool,
+    /// The ElGamal key of the auditor who can decrypt transfer amounts.
+    pub auditor_elgamal_pubkey: Option<[u8; 32]>,
+}
+
+/// The parts of a token account's confidential transfer state that decide
+/// which transfers it accepts. Encrypted balances are not decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfidentialTransferAccount {
+    pub approved: bool,
+    pub allow_confidential_credits: bool,
+    pub allow_non_confidential_credits: bool,
+}
+
+/// [`TokenProgram::transfer_checked`](crate::token::TokenProgram::transfer_checked)
+/// for mints with a transfer fee. `fee` must equal the fee the mint charges
+/// for the current epoch, or the transfer fails.
+pub fn transfer_checked_with_fee(
+    source: &Pubkey,
+    mint: &Pubkey,
+    destination: &Pubkey,
+    authority: &Pubkey,
+    amount: u64,
+    decimals: u8,
+    fee: u64,
+) -> Instruction {
+    let mut data = vec![TRANSFER_FEE_EXTENSION, TRANSFER_CHECKED_WITH_FEE];
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.push(decimals);
+    data.extend_from_slice(&fee.to_le_bytes());
+    Instruction::new(
+        TOKEN_2022_PROGRAM_ID,
+        vec![
+            AccountMeta::new(*source, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+        data,
+    )
+}
+
+fn memo_transfer_instruction(account: &Pubkey, owner: &Pubkey, tag: u8) -> Instruction {
+    Instruction::new(
+        TOKEN_2022_PROGRAM_ID,
+        vec![
+            AccountMeta::new(*account, false),
+            AccountMeta::new_readonly(*owner, true),
+        ],
+        vec![MEMO_TRANSFER_EXTENSION, tag],
+    )
+}
+
+/// Makes `account` reject transfers in that are not preceded by a memo.
+pub fn enable_required_transfer_memos(account: &Pubkey, owner: &Pubkey) -> Instruction {
+    memo_transfer_instruction(account, owner, ENABLE_REQUIRED_MEMOS)
+}
+
+pub fn disable_required_transfer_memos(account: &Pubkey, owner: &Pubkey) -> Instruction {
+    memo_transfer_instruction(account, owner, DISABLE_REQUIRED_MEMOS)
+}
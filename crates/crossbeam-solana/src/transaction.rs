@@ -0,0 +1,143 @@
+//! Signed transactions and their wire format.
+
+use crossbeam_core::signer::{PublicKey, Signature as CoreSignature, SigningPayload};
+use crossbeam_core::Signer;
+use ed25519_dalek::{Verifier, VerifyingKey};
+
+use crate::error::{Error, Result};
+use crate::message::VersionedMessage;
+use crate::short_vec;
+use crate::signature::Signature;
+use crate::Pubkey;
+
+/// A message with a signature slot for each of its required signers.
+/// Unsigned slots hold the all-zero signature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transaction {
+    pub signatures: Vec<Signature>,
+    pub message: VersionedMessage,
+}
+
+impl Transaction {
+    /// An unsigned transaction for `message`.
+    pub fn new(message: impl Into<VersionedMessage>) -> Self {
+        let message = message.into();
+        let signatures = vec![Signature::default(); message.signers().len()];
+        Self {
+            signatures,
+            message,
+        }
+    }
+
+    /// Signs with each of `signers`, leaving the slots of other required
+    /// signers as they are so several parties can sign in turn. A signer
+    /// the message does not require is an error.
+    pub fn sign(&mut self, signers: &[&dyn Signer]) -> Result<()> {
+        let message = self.message.encode();
+        for signer in signers {
+            let PublicKey::Ed25519(key) = signer.public_key() else {
+                return Err(Error::InvalidTransaction(
+                    "Solana signers use Ed25519 keys".to_owned(),
+                ));
+            };
+            let key = Pubkey::new(key);
+            let slot = self
+                .message
+                .signers()
+                .iter()
+                .position(|signer| *signer == key)
+                .ok_or_else(|| {
+                    Error::InvalidTransaction(format!("{key} is not a signer of the message"))
+                })?;
+            let CoreSignature::Ed25519(signature) =
+                signer.sign(&SigningPayload::Message(message.clone()))?
+            else {
+                return Err(Error::InvalidTransaction(format!(
+                    "{key} returned a non-Ed25519 signature"
+                )));
+            };
+            self.signatures[slot] = Signature(signature);
+        }
+        Ok(())
+    }
+
+    /// Whether every required signature is present. Signatures are not
+    /// verified; see [`Transaction::verify`].
+    pub fn is_signed(&self) -> bool {
+        self.signatures.len() == self.message.signers().len()
+            && self.signatures.iter().all(|s| *s != Signature::default())
+    }
+
+    /// Fails unless [`Transaction::is_signed`], saying how many required
+    /// signatures are present.
+    pub fn ensure_signed(&self) -> Result<()> {
+        if self.is_signed() {
+            return Ok(());
+        }
+        Err(Error::InvalidTransaction(format!(
+            "{} of {} required signatures present",
+            self.signatures
+                .iter()
+                .filter(|s| **s != Signature::default())
+                .count(),
+            self.message.signers().len()
+        )))
+    }
+
+    /// Checks every signature against its signer's key.
+    pub fn verify(&self) -> Result<()> {
+        let signers = self.message.signers();
+        if self.signatures.len() != signers.len() {
+            return Err(Error::InvalidSignature(format!(
+                "{} signatures for {} signers",
+                self.signatures.len(),
+                signers.len()
+            )));
+        }
+        let message = self.message.encode();
+        for (signature, signer) in self.signatures.iter().zip(signers) {
+            let key = VerifyingKey::from_bytes(&signer.0)
+                .map_err(|_| Error::InvalidSignature(format!("{signer} is not a valid key")))?;
+            key.verify(
+                &message,
+                &ed25519_dalek::Signature::from_bytes(&signature.0),
+            )
+            .map_err(|_| Error::InvalidSignature(format!("{signer} did not sign")))?;
+        }
+        Ok(())
+    }
+
+    /// The transaction id: its first signature.
+    pub fn id(&self) -> Signature {
+        self.signatures.first().copied().unwrap_or_default()
+    }
+
+    /// The wire format: the signatures, then the message.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        short_vec::encode(&self.signatures, &mut out, |signature, out| {
+            out.extend_from_slice(&signature.0)
+        });
+        out.extend_from_slice(&self.message.encode());
+        out
+    }
+
+    pub fn decode(input: &[u8]) -> Result<Self> {
+        let (signatures, rest) = short_vec::decode(input, |input| {
+            let (signature, rest) = short_vec::take(input)?;
+            Ok((Signature(signature), rest))
+        })?;
+        let message = VersionedMessage::decode(rest)?;
+        if signatures.len() != message.signers().len() {
+            return Err(Error::InvalidTransaction(format!(
+                "{} signatures for {} signers",
+                signatures.len(),
+                message.signers().len()
+            )));
+        }
+        Ok(Self {
+            signatures,
+            message,
+        })
+    }
+}
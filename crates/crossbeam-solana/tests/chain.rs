@@ -0,0 +1,82 @@
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use crossbeam_core::signer::Ed25519Signer;
+use crossbeam_core::{Amount, Asset, Chain, Confirmation, Network, Signer, Transfer};
+use crossbeam_solana::{
+    Error, Hash, Provider, Pubkey, Result, Signature, SolanaChain, Transaction,
+};
+
+#[derive(Default)]
+struct RecordingProvider {
+    sent: Mutex<Vec<Vec<u8>>>,
+}
+
+#[async_trait]
+impl Provider for RecordingProvider {
+    async fn latest_blockhash(&self) -> Result<Hash> {
+        Ok(Hash([7; 32]))
+    }
+
+    async fn send_transaction(&self, wire: &[u8]) -> Result<Signature> {
+        self.sent.lock().unwrap().push(wire.to_vec());
+        Ok(Transaction::decode(wire)?.id())
+    }
+
+    async fn signature_confirmation(&self, _signature: &Signature) -> Result<Confirmation> {
+        Ok(Confirmation::Finalized { height: 12 })
+    }
+}
+
+fn sol(amount: &str) -> Amount {
+    Amount::parse(Asset::native(Network::Solana).unwrap(), amount).unwrap()
+}
+
+#[tokio::test]
+async fn transfers_are_built_signed_and_submitted_through_the_provider() {
+    let chain = SolanaChain::new(RecordingProvider::default());
+    let signer = Ed25519Signer::from_bytes(&[1; 32]);
+    let transfer = Transfer {
+        from: chain
+            .parse_address("AKnL4NNf3DGWZJS6cPknBuEGnVsV4A4m5tgebLHaRSZ9")
+            .unwrap(),
+        to: Pubkey::new([9; 32]),
+        amount: sol("0.001"),
+    };
+    let tx = chain.build_transfer(&transfer).await.unwrap();
+    let signed = chain.sign(tx, &[&signer]).unwrap();
+    let id = chain.submit(&signed).await.unwrap();
+
+    // The same transfer the Solana SDK builds and signs.
+    let sent = chain.provider().sent.lock().unwrap().clone();
+    assert_eq!(
+        hex::encode(&sent[0]),
+        "019c915e6c202f0da3b34db6e893dae265965e7919436899ef8add0915a9e83354d1b977e00572694bb42dbbc3cac96b73188f71bd7e3958ba58b3c654114f8602010001038a88e3dd7409f195fd52db2d3cba5d72ca6709bf1d94121bf3748801b40f6f5c09090909090909090909090909090909090909090909090909090909090909090000000000000000000000000000000000000000000000000000000000000000070707070707070707070707070707070707070707070707070707070707070701020200010c0200000040420f0000000000"
+    );
+    assert_eq!(id, signed.id());
+    assert_eq!(
+        chain.confirmation(&id).await.unwrap(),
+        Confirmation::Finalized { height: 12 }
+    );
+}
+
+#[tokio::test]
+async fn signing_requires_every_signer() {
+    let chain = SolanaChain::new(RecordingProvider::default());
+    let payer = Ed25519Signer::from_bytes(&[1; 32]);
+    let transfer = Transfer {
+        from: Pubkey::new([9; 32]),
+        to: Pubkey::new([8; 32]),
+        amount: sol("1"),
+    };
+    let tx = chain.build_transfer(&transfer).await.unwrap();
+    // Not a signer of the transfer at all.
+    assert!(matches!(
+        chain.sign(tx.clone(), &[&payer as &dyn Signer]),
+        Err(Error::InvalidTransaction(_))
+    ));
+    assert!(matches!(
+        chain.sign(tx, &[]),
+        Err(Error::InvalidTransaction(_))
+    ));
+}
@@ -0,0 +1,403 @@
+//! Encodings checked against the `borsh` crate, with the fixture IDLs
+//! describing the same types.
+
+use crossbeam_solana::borsh::{Field, Fields, Type, TypeDef, Types, Value};
+use crossbeam_solana::idl::{account_discriminator, instruction_discriminator, Idl};
+use crossbeam_solana::system::SYSTEM_PROGRAM_ID;
+use crossbeam_solana::token::TOKEN_PROGRAM_ID;
+use crossbeam_solana::{Error, Pubkey};
+
+const CURRENT: &str = include_str!("fixtures/idl/bridge.json");
+const LEGACY: &str = include_str!("fixtures/idl/bridge_legacy.json");
+
+/// `BridgeConfig`, as serialized by `borsh`.
+const CONFIG: &str = "adadadadadadadadadadadadadadadadadadadadadadadadadadadadadadadad1e00000300000001000000000000003800000000000000505100000000000009080706010900000063726f73736265616d0140420f0000000000fffffffffffffffffbffffffffffffff000000000000e83f00000000000000000000000010000000d4feffff";
+
+fn key(byte: u8) -> Pubkey {
+    Pubkey::new([byte; 32])
+}
+
+fn program_id() -> Pubkey {
+    "2QvgH4gtaGgiMyxqNNL7hcsdSgmG4ULFomszaQbwGCBe"
+        .parse()
+        .unwrap()
+}
+
+/// The legacy IDL spells fields in camelCase.
+fn config(fee_bps: &str) -> Value {
+    Value::Struct(vec![
+        ("admin".into(), Value::Pubkey(key(0xad))),
+        (fee_bps.into(), Value::Uint(30)),
+        ("paused".into(), Value::Bool(false)),
+        (
+            "chains".into(),
+            Value::Array(vec![Value::Uint(1), Value::Uint(56), Value::Uint(0x5150)]),
+        ),
+        (
+            "guardian".into(),
+            Value::Array([9, 8, 7, 6].map(Value::Uint).to_vec()),
+        ),
+        (
+            "memo".into(),
+            Value::Option(Some(Box::new(Value::String("crossbeam".into())))),
+        ),
+        (
+            "mode".into(),
+            Value::Enum(
+                "Burn".into(),
+                Box::new(Value::Struct(vec![(
+                    "amount".into(),
+                    Value::Uint(1_000_000),
+                )])),
+            ),
+        ),
+        (
+            "limits".into(),
+            Value::Tuple(vec![Value::Uint(u64::MAX.into()), Value::Int(-5)]),
+        ),
+        ("ratio".into(), Value::Float(0.75)),
+        ("total".into(), Value::Uint(1 << 100)),
+        ("offset".into(), Value::Int(-300)),
+    ])
+}
+
+fn lock_args(max_fee: Option<u64>) -> Vec<Value> {
+    vec![
+        Value::Uint(2_500_000),
+        Value::Bytes(vec![0x35; 20]),
+        Value::Uint(56),
+        Value::Option(Some(Box::new(Value::Uint(7)))),
+        Value::Option(max_fee.map(|fee| Box::new(Value::Uint(fee.into())))),
+    ]
+}
+
+#[test]
+fn discriminators() {
+    assert_eq!(
+        instruction_discriminator("initialize"),
+        [175, 175, 109, 31, 13, 152, 155, 237]
+    );
+    // Legacy names are camelCase; the hash is over the Rust name.
+    assert_eq!(
+        instruction_discriminator("lockTokens"),
+        instruction_discriminator("lock_tokens")
+    );
+    assert_eq!(
+        instruction_discriminator("initializeV2"),
+        instruction_discriminator("initialize_v2")
+    );
+    assert_eq!(
+        instruction_discriminator("getATA"),
+        instruction_discriminator("get_ata")
+    );
+    assert_eq!(
+        account_discriminator("BridgeConfig"),
+        [40, 206, 51, 233, 246, 40, 178, 85]
+    );
+
+    // The legacy IDL derives what the current one lists.
+    let (current, legacy) = (
+        Idl::from_json(CURRENT).unwrap(),
+        Idl::from_json(LEGACY).unwrap(),
+    );
+    assert_eq!(
+        current.instruction("lock_tokens").unwrap().discriminator,
+        legacy.instruction("lockTokens").unwrap().discriminator
+    );
+    assert_eq!(current.accounts[0], legacy.accounts[0]);
+    assert_eq!(current.events, legacy.events);
+}
+
+#[test]
+fn metadata_and_errors() {
+    let idl = Idl::from_json(CURRENT).unwrap();
+    assert_eq!(idl.name, "bridge");
+    assert_eq!(idl.version, "0.1.0");
+    assert_eq!(idl.address, Some(program_id()));
+    assert_eq!(
+        idl.error(6000).unwrap().msg.as_deref(),
+        Some("Bridge is paused")
+    );
+    assert_eq!(idl.error(6001).unwrap().name, "FeeTooHigh");
+    assert!(idl.error(6002).is_none());
+    // The zero-copy account is named, but its layout is not Borsh.
+    assert!(idl.types.get("Ring").is_none());
+    assert!(matches!(
+        idl.decode_account("Ring", &[1, 2, 3, 4, 5, 6, 7, 8, 0, 0, 0, 0, 0, 0, 0, 0]),
+        Err(Error::NotFound { kind: "type", .. })
+    ));
+
+    let legacy = Idl::from_json(LEGACY).unwrap();
+    assert_eq!(legacy.name, "bridge");
+    assert_eq!(legacy.address, Some(program_id()));
+}
+
+#[test]
+fn instructions_are_built_from_named_accounts() {
+    let idl = Idl::from_json(CURRENT).unwrap();
+    let accounts = [
+        ("user", key(1)),
+        ("user_token", key(2)),
+        ("vault", key(3)),
+        ("mint", key(4)),
+        ("fee_collector", key(5)),
+        ("config", key(6)),
+    ];
+    let instruction = idl
+        .build_instruction(&program_id(), "lock_tokens", &accounts, &lock_args(None))
+        .unwrap();
+    assert_eq!(instruction.program_id, program_id());
+    assert_eq!(
+        hex::encode(&instruction.data),
+        "880b20e8a17536d3a02526000000000014000000353535353535353535353535353535353535353538000107000000\
+         00000000"
+    );
+    let metas: Vec<_> = instruction
+        .accounts
+        .iter()
+        .map(|meta| (meta.pubkey, meta.is_signer, meta.is_writable))
+        .collect();
+    assert_eq!(
+        metas,
+        [
+            (key(1), true, false),
+            (key(2), false, true),
+            (key(3), false, true),
+            (key(4), false, false),
+            (key(5), false, true),
+            (key(6), false, false),
+            // The optional referrer, left out, and the fixed token program.
+            (program_id(), false, false),
+            (TOKEN_PROGRAM_ID, false, false),
+        ]
+    );
+
+    let (decoded, args) = idl.decode_instruction(&instruction.data).unwrap();
+    assert_eq!(decoded.name, "lock_tokens");
+    assert_eq!(args, lock_args(None));
+
+    let with_fee = idl
+        .encode_instruction_data("lock_tokens", &lock_args(Some(100)))
+        .unwrap();
+    assert!(hex::encode(&with_fee).ends_with("010000006400000000000000"));
+    assert_eq!(
+        idl.decode_instruction(&with_fee).unwrap().1,
+        lock_args(Some(100))
+    );
+
+    let missing = idl.build_instruction(
+        &program_id(),
+        "lock_tokens",
+        &accounts[1..],
+        &lock_args(None),
+    );
+    assert!(matches!(
+        missing,
+        Err(Error::NotFound {
+            kind: "account",
+            ..
+        })
+    ));
+    let unknown = idl.build_instruction(
+        &program_id(),
+        "set_paused",
+        &[("config", key(6)), ("admin", key(7)), ("admn", key(7))],
+        &[Value::Bool(true)],
+    );
+    assert!(matches!(
+        unknown,
+        Err(Error::NotFound {
+            kind: "account",
+            ..
+        })
+    ));
+}
+
+#[test]
+fn legacy_instructions_match_current_ones() {
+    let (current, legacy) = (
+        Idl::from_json(CURRENT).unwrap(),
+        Idl::from_json(LEGACY).unwrap(),
+    );
+    let args = [config("fee_bps")];
+    let legacy_args = [config("feeBps")];
+    let data = current
+        .encode_instruction_data("initialize", &args)
+        .unwrap();
+    assert_eq!(hex::encode(&data), format!("afaf6d1f0d989bed{CONFIG}"));
+    assert_eq!(
+        legacy
+            .encode_instruction_data("initialize", &legacy_args)
+            .unwrap(),
+        data
+    );
+    // Legacy IDLs flag writable and signer accounts differently.
+    let accounts = &legacy.instruction("initialize").unwrap().accounts;
+    assert!(accounts[1].writable && accounts[1].signer);
+    let system = &current.instruction("initialize").unwrap().accounts[2];
+    assert_eq!(system.address, Some(SYSTEM_PROGRAM_ID));
+}
+
+#[test]
+fn accounts_and_events() {
+    let idl = Idl::from_json(CURRENT).unwrap();
+    let data = idl
+        .encode_account("BridgeConfig", &config("fee_bps"))
+        .unwrap();
+    assert_eq!(hex::encode(&data), format!("28ce33e9f628b255{CONFIG}"));
+
+    // Accounts are usually allocated with room to spare.
+    let mut allocated = data.clone();
+    allocated.resize(data.len() + 64, 0);
+    let decoded = idl.decode_account("BridgeConfig", &allocated).unwrap();
+    assert_eq!(decoded, config("fee_bps"));
+    assert_eq!(decoded.field("paused"), Some(&Value::Bool(false)));
+    assert_eq!(idl.account_type(&allocated).unwrap().name, "BridgeConfig");
+
+    let mut other = data.clone();
+    other[0] ^= 1;
+    assert!(matches!(
+        idl.decode_account("BridgeConfig", &other),
+        Err(Error::InvalidData(_))
+    ));
+
+    let event = hex::decode("3fb8c914cbc2f98a1111111111111111111111111111111111111111111111111111111111111111a0252600000000003800140000003535353535353535353535353535353535353535").unwrap();
+    let (kind, value) = idl.decode_event(&event).unwrap();
+    assert_eq!(kind.name, "TokensLocked");
+    assert_eq!(value.field("sender"), Some(&Value::Pubkey(key(0x11))));
+    assert_eq!(value.field("target_chain"), Some(&Value::Uint(56)));
+    let (_, legacy) = Idl::from_json(LEGACY)
+        .unwrap()
+        .decode_event(&event)
+        .unwrap();
+    assert_eq!(legacy.field("targetChain"), Some(&Value::Uint(56)));
+}
+
+#[test]
+fn values_must_fit_their_types() {
+    let idl = Idl::from_json(CURRENT).unwrap();
+    let types = &idl.types;
+    let mode = Type::Defined("TransferMode".into());
+    assert_eq!(
+        hex::encode(
+            types
+                .encode(
+                    &mode,
+                    &Value::Enum(
+                        "Wrapped".into(),
+                        Box::new(Value::Tuple(vec![Value::Uint(3), Value::Int(-1)]))
+                    )
+                )
+                .unwrap()
+        ),
+        "0203ffffffff"
+    );
+    // Byte arrays may be given as bytes too.
+    let guardian = Type::Array(Box::new(Type::U8), 4);
+    assert_eq!(
+        types
+            .encode(&guardian, &Value::Bytes(vec![9, 8, 7, 6]))
+            .unwrap(),
+        [9, 8, 7, 6]
+    );
+    assert_eq!(
+        types.encode(&mode, &Value::unit_variant("Lock")).unwrap(),
+        [0]
+    );
+    let mismatch = |ty: &Type, value: Value| {
+        matches!(
+            types.encode(ty, &value),
+            Err(Error::TypeMismatch { .. })
+                | Err(Error::NotFound { .. })
+                | Err(Error::InvalidData(_))
+        )
+    };
+    assert!(mismatch(&Type::U8, Value::Uint(256)));
+    assert!(mismatch(&Type::I8, Value::Int(-129)));
+    assert!(mismatch(&Type::U64, Value::Int(1)));
+    assert!(mismatch(&Type::F64, Value::Float(f64::NAN)));
+    // f32 takes only values it holds exactly.
+    assert_eq!(
+        types.encode(&Type::F32, &Value::Float(0.75)).unwrap(),
+        0.75f32.to_le_bytes()
+    );
+    assert!(mismatch(&Type::F32, Value::Float(0.1)));
+    assert!(mismatch(&Type::F32, Value::Float(1e39)));
+    let unit = Type::Array(Box::new(Type::U8), 0);
+    assert!(mismatch(
+        &Type::Vec(Box::new(unit)),
+        Value::Array(vec![Value::Bytes(Vec::new())])
+    ));
+    assert!(mismatch(
+        &Type::Array(Box::new(Type::U8), 4),
+        Value::Bytes(vec![1, 2, 3])
+    ));
+    assert!(mismatch(
+        &Type::Vec(Box::new(Type::U16)),
+        Value::Bytes(vec![1])
+    ));
+    assert!(mismatch(&mode, Value::unit_variant("Mint")));
+    assert!(mismatch(&Type::Defined("Nope".into()), Value::Bool(true)));
+    // Fields must come in declaration order.
+    let Value::Struct(mut fields) = config("fee_bps") else {
+        unreachable!()
+    };
+    fields.swap(0, 1);
+    assert!(mismatch(
+        &Type::Defined("BridgeConfig".into()),
+        Value::Struct(fields)
+    ));
+}
+
+#[test]
+fn malformed_data_is_rejected() {
+    let types = Types::new();
+    let reject =
+        |ty: Type, data: &[u8]| matches!(types.decode(&ty, data), Err(Error::InvalidData(_)));
+    assert!(reject(Type::Bool, &[2]));
+    assert!(reject(Type::Option(Box::new(Type::U8)), &[2, 0]));
+    assert!(reject(Type::COption(Box::new(Type::U8)), &[2, 0, 0, 0]));
+    assert!(reject(Type::String, &[2, 0, 0, 0, 0xff, 0xfe]));
+    assert!(reject(Type::F32, &f32::NAN.to_le_bytes()));
+    assert!(reject(Type::U32, &[1, 2, 3]));
+    assert!(reject(Type::U8, &[1, 2]));
+    // A length prefix far beyond the data.
+    assert!(reject(
+        Type::Vec(Box::new(Type::U64)),
+        &[0xff, 0xff, 0xff, 0xff, 1]
+    ));
+    // Zero-sized items would turn the prefix into a loop of 2^32 rounds.
+    let unit = Type::Array(Box::new(Type::U8), 0);
+    assert!(reject(Type::Vec(Box::new(unit.clone())), &[0xff; 4]));
+    assert_eq!(
+        types.decode(&Type::Vec(Box::new(unit)), &[0; 4]).unwrap(),
+        Value::Array(Vec::new())
+    );
+
+    // A struct that contains itself cannot be decoded without end.
+    let mut recursive = Types::new();
+    recursive.insert(
+        "Loop",
+        TypeDef::Struct(Fields::Named(vec![Field {
+            name: "next".into(),
+            ty: Type::Defined("Loop".into()),
+        }])),
+    );
+    assert!(matches!(
+        recursive.decode(&Type::Defined("Loop".into()), &[]),
+        Err(Error::InvalidData(_))
+    ));
+
+    let idl = Idl::from_json(CURRENT).unwrap();
+    assert!(matches!(
+        idl.decode_instruction(&[0; 8]),
+        Err(Error::InvalidData(_))
+    ));
+    assert!(matches!(
+        Idl::from_json(
+            r#"{"instructions": [{"name": "x", "args": [{"name": "a", "type": "u256"}]}]}"#
+        ),
+        Err(Error::InvalidIdl(_))
+    ));
+    assert!(matches!(Idl::from_json("{"), Err(Error::Json(_))));
+}
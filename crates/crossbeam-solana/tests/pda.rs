@@ -0,0 +1,160 @@
+//! Addresses checked against the Solana SDK.
+
+use crossbeam_solana::associated_token::{
+    associated_token_address, create_associated_token_account_idempotent,
+    find_associated_token_address, ASSOCIATED_TOKEN_PROGRAM_ID,
+};
+use crossbeam_solana::pda::{
+    create_program_address, create_with_seed, find_program_address, is_on_curve, MAX_SEEDS,
+};
+use crossbeam_solana::system::SYSTEM_PROGRAM_ID;
+use crossbeam_solana::token::{TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+use crossbeam_solana::{Error, Pubkey};
+
+fn key(s: &str) -> Pubkey {
+    s.parse().unwrap()
+}
+
+const PROGRAM: Pubkey = Pubkey::new([0x50; 32]);
+const WALLET: Pubkey = Pubkey::new([0x11; 32]);
+
+fn usdc() -> Pubkey {
+    key("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v")
+}
+
+#[test]
+fn program_ids() {
+    assert_eq!(
+        TOKEN_PROGRAM_ID,
+        key("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA")
+    );
+    assert_eq!(
+        TOKEN_2022_PROGRAM_ID,
+        key("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb")
+    );
+    assert_eq!(
+        ASSOCIATED_TOKEN_PROGRAM_ID,
+        key("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL")
+    );
+}
+
+#[test]
+fn find_program_address_takes_the_highest_bump_off_the_curve() {
+    for (seeds, address, bump) in [
+        (
+            &[&b"vault"[..]][..],
+            "2QvgH4gtaGgiMyxqNNL7hcsdSgmG4ULFomszaQbwGCBe",
+            252,
+        ),
+        (
+            &[b"vault", &[1, 2, 3]],
+            "FYtd9sbYgxaa7vpXaxMKkVS6yWjeCX7iAfuSjKT4N1dV",
+            253,
+        ),
+        (&[], "ADFiabG6eWE333XuhthdESFaArhHsR1YsBvryZjTgEc5", 255),
+    ] {
+        let (found, found_bump) = find_program_address(seeds, &PROGRAM).unwrap();
+        assert_eq!((found, found_bump), (key(address), bump));
+        assert!(!is_on_curve(&found));
+
+        let bump_seed = [bump];
+        let with_bump = [seeds, &[&bump_seed]].concat();
+        assert_eq!(create_program_address(&with_bump, &PROGRAM).unwrap(), found);
+    }
+
+    // Bump 255 puts the address for "vault" on the curve.
+    assert!(matches!(
+        create_program_address(&[b"vault", &[255]], &PROGRAM),
+        Err(Error::InvalidSeeds(_))
+    ));
+}
+
+#[test]
+fn seed_limits() {
+    let long = [0; 33];
+    assert!(matches!(
+        find_program_address(&[&long], &PROGRAM),
+        Err(Error::InvalidSeeds(_))
+    ));
+    let seed = [0u8; 1];
+    let seeds = vec![&seed[..]; MAX_SEEDS];
+    // No room left for the bump.
+    assert!(matches!(
+        find_program_address(&seeds, &PROGRAM),
+        Err(Error::InvalidSeeds(_))
+    ));
+    assert!(find_program_address(&seeds[1..], &PROGRAM).is_ok());
+    assert!(matches!(
+        create_program_address(&vec![&seed[..]; MAX_SEEDS + 1], &PROGRAM),
+        Err(Error::InvalidSeeds(_))
+    ));
+}
+
+#[test]
+fn curve_points() {
+    // Public keys of real signers are on the curve.
+    assert!(is_on_curve(&key(
+        "AKnL4NNf3DGWZJS6cPknBuEGnVsV4A4m5tgebLHaRSZ9"
+    )));
+    assert!(!is_on_curve(&key(
+        "2QvgH4gtaGgiMyxqNNL7hcsdSgmG4ULFomszaQbwGCBe"
+    )));
+}
+
+#[test]
+fn addresses_with_seed() {
+    assert_eq!(
+        create_with_seed(&WALLET, "bridge-vault", &PROGRAM).unwrap(),
+        key("BuZaeVWa4oBMNXkHyqaAGDgyP6Y54A8n4Re4gbcSSmLv")
+    );
+    assert_eq!(
+        create_with_seed(&WALLET, "", &TOKEN_PROGRAM_ID).unwrap(),
+        key("F8Fj7eqyuvvcYNnTesA522hnMZ94ZLQTkK8YFzjJh6SK")
+    );
+    assert!(matches!(
+        create_with_seed(&WALLET, &"x".repeat(33), &PROGRAM),
+        Err(Error::InvalidSeeds(_))
+    ));
+    let mut marker_owner = [0; 32];
+    marker_owner[11..].copy_from_slice(b"ProgramDerivedAddress");
+    assert!(matches!(
+        create_with_seed(&WALLET, "vault", &Pubkey::new(marker_owner)),
+        Err(Error::InvalidSeeds(_))
+    ));
+}
+
+#[test]
+fn associated_token_accounts_depend_on_the_token_program() {
+    assert_eq!(
+        find_associated_token_address(&WALLET, &usdc(), &TOKEN_PROGRAM_ID).unwrap(),
+        (key("5t1xfQNtg4MaNtNHnXAPmTt5TkEbKL5brmD3wjvspEfb"), 254)
+    );
+    assert_eq!(
+        find_associated_token_address(&WALLET, &usdc(), &TOKEN_2022_PROGRAM_ID).unwrap(),
+        (key("71xwfdATBppXyP3zgaAFei5KtEPWKDdNp1xqQLAxjcY5"), 255)
+    );
+    assert!(matches!(
+        associated_token_address(&WALLET, &usdc(), &PROGRAM),
+        Err(Error::InvalidSeeds(_))
+    ));
+
+    let payer = Pubkey::new([0x22; 32]);
+    let create =
+        create_associated_token_account_idempotent(&payer, &WALLET, &usdc(), &TOKEN_PROGRAM_ID)
+            .unwrap();
+    assert_eq!(create.program_id, ASSOCIATED_TOKEN_PROGRAM_ID);
+    assert_eq!(create.data, [1]);
+    let keys: Vec<_> = create.accounts.iter().map(|a| a.pubkey).collect();
+    assert_eq!(
+        keys,
+        [
+            payer,
+            key("5t1xfQNtg4MaNtNHnXAPmTt5TkEbKL5brmD3wjvspEfb"),
+            WALLET,
+            usdc(),
+            SYSTEM_PROGRAM_ID,
+            TOKEN_PROGRAM_ID
+        ]
+    );
+    assert!(create.accounts[0].is_signer && create.accounts[1].is_writable);
+}
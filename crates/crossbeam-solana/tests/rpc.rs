@@ -0,0 +1,406 @@
+use crossbeam_core::Confirmation;
+use crossbeam_solana::rpc::jsonrpc::{ErrorObject, Message, Response};
+use crossbeam_solana::rpc::{
+    LogsFilter, MockTransport, SendTransactionConfig, SignatureStatus, Subscriptions,
+};
+use crossbeam_solana::{Client, Commitment, Error, Hash, Provider, Pubkey, Signature, Transport};
+use serde_json::{json, Value};
+
+const OWNER: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+const BLOCKHASH: &str = "EkSnNWid2cvwEVnVx9aBqawnmiCNiDgp3gUdkDPTKN1N";
+
+fn key(byte: u8) -> Pubkey {
+    Pubkey::new([byte; 32])
+}
+
+fn account(lamports: u64, data: &str) -> Value {
+    json!({
+        "data": [data, "base64"],
+        "executable": false,
+        "lamports": lamports,
+        "owner": OWNER,
+        "rentEpoch": 18446744073709551615u64,
+        "space": 3
+    })
+}
+
+fn status(slot: u64, confirmations: Option<u64>, err: Value, level: Option<&str>) -> Value {
+    let mut status = json!({
+        "slot": slot,
+        "confirmations": confirmations,
+        "err": err,
+        "status": if err.is_null() { json!({"Ok": null}) } else { json!({"Err": err}) },
+    });
+    if let Some(level) = level {
+        status["confirmationStatus"] = json!(level);
+    }
+    status
+}
+
+#[tokio::test]
+async fn accounts_are_read_at_the_given_commitment() {
+    let client = Client::new(
+        MockTransport::new()
+            .on("getAccountInfo", |params| {
+                Ok(json!({
+                    "context": {"apiVersion": "2.2.3", "slot": 341197053},
+                    "value": if params[0] == json!(key(1)) { account(2_039_280, "AQID") } else { Value::Null },
+                }))
+            })
+            .on("getMultipleAccounts", |params| {
+                let keys = params[0].as_array().unwrap();
+                let mut value: Vec<Value> = keys.iter().map(|_| Value::Null).collect();
+                value[0] = account(1, "");
+                Ok(json!({"context": {"slot": 7}, "value": value}))
+            }),
+    );
+
+    let info = client
+        .get_account_info(&key(1), Commitment::Confirmed)
+        .await
+        .unwrap();
+    assert_eq!(info.context.slot, 341197053);
+    let account = info.value.unwrap();
+    assert_eq!(account.data, [1, 2, 3]);
+    assert_eq!(account.lamports, 2_039_280);
+    assert_eq!(account.owner, OWNER.parse().unwrap());
+    assert_eq!(account.rent_epoch, u64::MAX);
+    let missing = client
+        .get_account_info(&key(2), Commitment::Processed)
+        .await
+        .unwrap();
+    assert_eq!(missing.value, None);
+
+    let accounts = client
+        .get_multiple_accounts(&[key(1), key(2)], Commitment::Finalized)
+        .await
+        .unwrap();
+    assert_eq!(accounts.value.len(), 2);
+    assert!(accounts.value[0].as_ref().unwrap().data.is_empty());
+    assert_eq!(accounts.value[1], None);
+    assert!(matches!(
+        client
+            .get_multiple_accounts(&[key(0); 101], Commitment::Finalized)
+            .await,
+        Err(Error::Provider(_))
+    ));
+
+    let requests = client.transport().requests();
+    assert_eq!(
+        requests[0],
+        (
+            "getAccountInfo".to_owned(),
+            json!([key(1).to_string(), {"encoding": "base64", "commitment": "confirmed"}])
+        )
+    );
+    assert_eq!(requests[1].1[1]["commitment"], "processed");
+    assert_eq!(
+        requests[2].1,
+        json!([[key(1).to_string(), key(2).to_string()], {"encoding": "base64", "commitment": "finalized"}])
+    );
+    // The oversized call never reached the node.
+    assert_eq!(requests.len(), 3);
+}
+
+#[tokio::test]
+async fn provider_calls_use_the_client_commitment() {
+    let client = Client::new(
+        MockTransport::new()
+            .on_result(
+                "getLatestBlockhash",
+                json!({
+                    "context": {"slot": 2792},
+                    "value": {"blockhash": BLOCKHASH, "lastValidBlockHeight": 3090}
+                }),
+            )
+            .on_result("sendTransaction", json!(Signature([7; 64]).to_string())),
+    )
+    .with_commitment(Commitment::Confirmed);
+
+    let latest = client
+        .get_latest_blockhash(Commitment::Finalized)
+        .await
+        .unwrap();
+    assert_eq!(latest.blockhash, BLOCKHASH.parse::<Hash>().unwrap());
+    assert_eq!(latest.last_valid_block_height, 3090);
+    assert_eq!(
+        Provider::latest_blockhash(&client).await.unwrap(),
+        latest.blockhash
+    );
+    assert_eq!(
+        Provider::send_transaction(&client, &[1, 2, 3])
+            .await
+            .unwrap(),
+        Signature([7; 64])
+    );
+    let config = SendTransactionConfig {
+        skip_preflight: true,
+        max_retries: Some(0),
+        min_context_slot: Some(2790),
+        ..SendTransactionConfig::default()
+    };
+    client.send_transaction(&[0xff], &config).await.unwrap();
+
+    let params: Vec<Value> = client
+        .transport()
+        .requests()
+        .into_iter()
+        .map(|(_, params)| params)
+        .collect();
+    assert_eq!(params[0], json!([{"commitment": "finalized"}]));
+    assert_eq!(params[1], json!([{"commitment": "confirmed"}]));
+    assert_eq!(
+        params[2],
+        json!(["AQID", {"encoding": "base64", "skipPreflight": false, "preflightCommitment": "confirmed"}])
+    );
+    assert_eq!(
+        params[3],
+        json!(["/w==", {"encoding": "base64", "skipPreflight": true, "maxRetries": 0, "minContextSlot": 2790}])
+    );
+}
+
+#[tokio::test]
+async fn failed_preflight_carries_the_simulation() {
+    let client = Client::new(MockTransport::new().on("sendTransaction", |_| {
+        Err(ErrorObject {
+            code: -32002,
+            message: "Transaction simulation failed: Attempt to debit an account but found no record of a prior credit.".to_owned(),
+            data: Some(json!({"err": "AccountNotFound", "logs": []})),
+        }
+        .into())
+    }));
+    let Err(Error::Rpc { code, data, .. }) = client
+        .send_transaction(&[1], &SendTransactionConfig::default())
+        .await
+    else {
+        panic!("expected a JSON-RPC error");
+    };
+    assert_eq!(code, -32002);
+    assert_eq!(data.unwrap()["err"], "AccountNotFound");
+
+    let Err(Error::Rpc { code, .. }) = client.get_latest_blockhash(Commitment::Finalized).await
+    else {
+        panic!("expected a JSON-RPC error");
+    };
+    assert_eq!(code, -32601);
+}
+
+#[tokio::test]
+async fn confirmation_follows_signature_status() {
+    let statuses = vec![
+        Value::Null,
+        status(
+            100,
+            Some(0),
+            json!({"InstructionError": [0, {"Custom": 1}]}),
+            Some("processed"),
+        ),
+        status(101, Some(0), Value::Null, Some("processed")),
+        status(102, Some(5), Value::Null, Some("confirmed")),
+        status(103, None, Value::Null, Some("finalized")),
+        // Nodes without confirmationStatus.
+        status(104, None, Value::Null, None),
+        status(105, Some(3), Value::Null, None),
+        status(
+            106,
+            None,
+            json!({"InstructionError": [0, {"Custom": 1}]}),
+            Some("finalized"),
+        ),
+    ];
+    let signatures: Vec<Signature> = (0..statuses.len() as u8)
+        .map(|i| Signature([i; 64]))
+        .collect();
+    let lookup: Vec<(String, Value)> = signatures
+        .iter()
+        .map(|s| s.to_string())
+        .zip(statuses)
+        .collect();
+    let client = Client::new(
+        MockTransport::new().on("getSignatureStatuses", move |params| {
+            assert_eq!(params[1], json!({"searchTransactionHistory": true}));
+            let value: Vec<Value> = params[0]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|s| {
+                    lookup
+                        .iter()
+                        .find(|(sig, _)| s == sig)
+                        .map_or(Value::Null, |(_, status)| status.clone())
+                })
+                .collect();
+            Ok(json!({"context": {"slot": 110}, "value": value}))
+        }),
+    );
+
+    let mut confirmations = Vec::new();
+    for signature in &signatures {
+        confirmations.push(client.signature_confirmation(signature).await.unwrap());
+    }
+    assert_eq!(
+        confirmations,
+        [
+            Confirmation::NotFound,
+            // A failure in a block that may still be skipped is not final.
+            Confirmation::Pending,
+            Confirmation::Pending,
+            Confirmation::Confirmed { height: 102 },
+            Confirmation::Finalized { height: 103 },
+            Confirmation::Finalized { height: 104 },
+            Confirmation::Pending,
+            Confirmation::Failed {
+                height: Some(106),
+                reason: r#"{"InstructionError":[0,{"Custom":1}]}"#.to_owned()
+            },
+        ]
+    );
+
+    // A client content with confirmed state treats it as final.
+    let client = client.with_commitment(Commitment::Confirmed);
+    assert_eq!(
+        client.signature_confirmation(&signatures[3]).await.unwrap(),
+        Confirmation::Finalized { height: 102 }
+    );
+
+    let statuses = client
+        .get_signature_statuses(&signatures[2..4], true)
+        .await
+        .unwrap();
+    let status: &SignatureStatus = statuses[1].as_ref().unwrap();
+    assert!(status.satisfies(Commitment::Confirmed));
+    assert!(!status.satisfies(Commitment::Finalized));
+}
+
+#[tokio::test]
+async fn account_subscriptions_stream_until_cancelled() {
+    let client = Client::new(MockTransport::new());
+    let mut subscription = client
+        .account_subscribe(&key(1), Commitment::Confirmed)
+        .await
+        .unwrap();
+    let id = subscription.id();
+    let mock = client.transport();
+    assert_eq!(mock.subscriptions(), [(id, "accountSubscribe".to_owned())]);
+
+    mock.notify(
+        id,
+        json!({"context": {"slot": 5199307}, "value": account(1, "AAEC")}),
+    );
+    mock.notify(
+        id,
+        json!({"context": {"slot": 5199308}, "value": account(2, "AwQ=")}),
+    );
+    let first = subscription.next().await.unwrap().unwrap();
+    assert_eq!(first.context.slot, 5199307);
+    assert_eq!(first.value.data, [0, 1, 2]);
+    let second = subscription.next().await.unwrap().unwrap();
+    assert_eq!((second.value.lamports, second.value.data), (2, vec![3, 4]));
+
+    subscription.unsubscribe().await.unwrap();
+    assert!(mock.subscriptions().is_empty());
+    let requests = mock.requests();
+    assert_eq!(
+        requests[0].1,
+        json!([key(1).to_string(), {"encoding": "base64", "commitment": "confirmed"}])
+    );
+    assert_eq!(requests[1], ("accountUnsubscribe".to_owned(), json!([id])));
+}
+
+#[tokio::test]
+async fn readers_wait_for_notifications() {
+    let client = Client::new(MockTransport::new());
+    let mut logs = client
+        .logs_subscribe(LogsFilter::Mentions(key(9)), Commitment::Processed)
+        .await
+        .unwrap();
+    let id = logs.id();
+    let (received, ()) = tokio::join!(logs.next(), async {
+        tokio::task::yield_now().await;
+        client.transport().notify(
+            id,
+            json!({
+                "context": {"slot": 5208469},
+                "value": {
+                    "signature": Signature([3; 64]).to_string(),
+                    "err": null,
+                    "logs": ["Program 11111111111111111111111111111111 success"]
+                }
+            }),
+        );
+    });
+    let received = received.unwrap().unwrap();
+    assert_eq!(received.value.signature, Signature([3; 64]));
+    assert_eq!(received.value.logs.len(), 1);
+
+    // The node closing the connection ends the stream.
+    let (ended, ()) = tokio::join!(logs.next(), async {
+        tokio::task::yield_now().await;
+        client.transport().disconnect();
+    });
+    assert_eq!(ended.unwrap(), None);
+    assert_eq!(
+        client.transport().requests()[0].1,
+        json!([{"mentions": [key(9).to_string()]}, {"commitment": "processed"}])
+    );
+    // Nothing to cancel once the stream has ended.
+    logs.unsubscribe().await.unwrap();
+    assert_eq!(client.transport().requests().len(), 1);
+}
+
+#[tokio::test]
+async fn signature_subscriptions_end_after_one_notification() {
+    let client = Client::new(MockTransport::new());
+    let mut subscription = client
+        .signature_subscribe(&Signature([5; 64]), Commitment::Finalized)
+        .await
+        .unwrap();
+    let mock = client.transport();
+    mock.notify(
+        subscription.id(),
+        json!({"context": {"slot": 5207624}, "value": {"err": null}}),
+    );
+    let result = subscription.next().await.unwrap().unwrap();
+    assert_eq!(result.context.slot, 5207624);
+    assert_eq!(result.value.err, None);
+    assert!(subscription.next().await.unwrap().is_none());
+    subscription.unsubscribe().await.unwrap();
+    assert!(mock.subscriptions().is_empty());
+    assert_eq!(mock.requests().len(), 1);
+
+    let all = client
+        .logs_subscribe(LogsFilter::All, Commitment::Finalized)
+        .await
+        .unwrap();
+    assert_eq!(mock.requests()[1].1[0], "all");
+    // A node that never issued the id refuses to cancel it.
+    let other = MockTransport::new();
+    let Err(Error::Rpc { code, .. }) = other.request("logsUnsubscribe", json!([all.id()])).await
+    else {
+        panic!("expected a JSON-RPC error");
+    };
+    assert_eq!(code, -32602);
+}
+
+#[tokio::test]
+async fn websocket_messages_are_routed_by_subscription() {
+    let hub = Subscriptions::new();
+    let frames = [
+        r#"{"jsonrpc":"2.0","method":"accountNotification","params":{"result":{"context":{"slot":1},"value":null},"subscription":23784}}"#,
+        r#"{"jsonrpc":"2.0","result":23784,"id":1}"#,
+        r#"{"jsonrpc":"2.0","method":"accountNotification","params":{"result":{"context":{"slot":2},"value":null},"subscription":23785}}"#,
+    ];
+    let mut responses = Vec::new();
+    for frame in frames {
+        match serde_json::from_str(frame).unwrap() {
+            Message::Notification(notification) => hub.dispatch(notification),
+            Message::Response(response) => responses.push(response),
+        }
+    }
+    // The notification that beat the subscribe response is kept.
+    assert_eq!(responses, [Response::success(1, json!(23784))]);
+    assert_eq!(hub.next(23784).await.unwrap()["context"]["slot"], 1);
+    hub.close(23785);
+    assert_eq!(hub.next(23785).await.unwrap()["context"]["slot"], 2);
+    assert_eq!(hub.next(23785).await, None);
+}
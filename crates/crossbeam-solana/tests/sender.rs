@@ -0,0 +1,319 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use crossbeam_core::signer::{Ed25519Signer, PublicKey};
+use crossbeam_core::Signer;
+use crossbeam_solana::rpc::jsonrpc::ErrorObject;
+use crossbeam_solana::rpc::MockTransport;
+use crossbeam_solana::{
+    system, Client, Commitment, Error, Hash, Message, PendingTransaction, Pubkey, SendStatus,
+    Sender, Signature, Transaction,
+};
+use serde_json::{json, Value};
+
+/// A cluster whose finalized height, blockhashes and statuses the test
+/// moves along.
+#[derive(Default)]
+struct Node {
+    height: u64,
+    /// Handed out in turn by `getLatestBlockhash`, with their last valid
+    /// heights.
+    blockhashes: Vec<(Hash, u64)>,
+    statuses: HashMap<Signature, Value>,
+    /// Each transaction received, and whether it skipped preflight.
+    sent: Vec<(Signature, bool)>,
+    already_processed: bool,
+}
+
+fn status(slot: u64, level: &str, err: Value) -> Value {
+    json!({"slot": slot, "confirmations": null, "err": err, "confirmationStatus": level})
+}
+
+fn client(node: &Arc<Mutex<Node>>) -> Client<MockTransport> {
+    let (blockhash, height, statuses, send) =
+        (node.clone(), node.clone(), node.clone(), node.clone());
+    Client::new(
+        MockTransport::new()
+            .on("getLatestBlockhash", move |_| {
+                let mut node = blockhash.lock().unwrap();
+                let (hash, last_valid) = node.blockhashes.remove(0);
+                Ok(json!({
+                    "context": {"slot": 1},
+                    "value": {"blockhash": hash.to_string(), "lastValidBlockHeight": last_valid}
+                }))
+            })
+            .on("getBlockHeight", move |params| {
+                assert_eq!(params[0]["commitment"], "finalized");
+                Ok(json!(height.lock().unwrap().height))
+            })
+            .on("getSignatureStatuses", move |params| {
+                let node = statuses.lock().unwrap();
+                let value: Vec<Value> = params[0]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|s| {
+                        let signature: Signature = s.as_str().unwrap().parse().unwrap();
+                        node.statuses.get(&signature).cloned().unwrap_or(Value::Null)
+                    })
+                    .collect();
+                Ok(json!({"context": {"slot": 1}, "value": value}))
+            })
+            .on("sendTransaction", move |params| {
+                let mut node = send.lock().unwrap();
+                if node.already_processed {
+                    return Err(ErrorObject {
+                        code: -32002,
+                        message: "Transaction simulation failed: This transaction has already been processed".to_owned(),
+                        data: Some(json!({"err": "AlreadyProcessed", "logs": []})),
+                    }
+                    .into());
+                }
+                let wire = BASE64.decode(params[0].as_str().unwrap()).unwrap();
+                let tx = Transaction::decode(&wire).unwrap();
+                tx.verify().unwrap();
+                let skip = params[1]["skipPreflight"].as_bool().unwrap();
+                node.sent.push((tx.id(), skip));
+                Ok(json!(tx.id().to_string()))
+            }),
+    )
+}
+
+fn pubkey_of(signer: &Ed25519Signer) -> Pubkey {
+    match signer.public_key() {
+        PublicKey::Ed25519(key) => Pubkey::new(key),
+        other => panic!("unexpected key {other:?}"),
+    }
+}
+
+fn setup(blockhashes: &[(u8, u64)]) -> (Arc<Mutex<Node>>, Ed25519Signer, Message) {
+    let node = Arc::new(Mutex::new(Node {
+        blockhashes: blockhashes
+            .iter()
+            .map(|(byte, last_valid)| (Hash([*byte; 32]), *last_valid))
+            .collect(),
+        ..Node::default()
+    }));
+    let payer = Ed25519Signer::from_bytes(&[1; 32]);
+    let from = pubkey_of(&payer);
+    let message = Message::new(
+        &[system::transfer(&from, &Pubkey::new([2; 32]), 1_000)],
+        &from,
+        Hash::default(),
+    )
+    .unwrap();
+    (node, payer, message)
+}
+
+#[tokio::test]
+async fn rebroadcasts_until_the_commitment_is_reached() {
+    let (node, payer, message) = setup(&[(7, 150)]);
+    let sender = Sender::new(client(&node));
+    let pending = sender.prepare(message, &[&payer]).await.unwrap();
+    assert_eq!(pending.last_valid_block_height, 150);
+    assert_eq!(
+        *pending.transaction().unwrap().message.recent_blockhash(),
+        Hash([7; 32])
+    );
+    let id = pending.signature;
+    assert_eq!(sender.send(&pending).await.unwrap(), id);
+
+    // Dropped on the way: the transaction goes out again, unsimulated.
+    node.lock().unwrap().height = 100;
+    assert_eq!(sender.poll(&pending).await.unwrap(), SendStatus::Pending);
+    node.lock()
+        .unwrap()
+        .statuses
+        .insert(id, status(4_000, "confirmed", Value::Null));
+    assert_eq!(sender.poll(&pending).await.unwrap(), SendStatus::Pending);
+    assert_eq!(node.lock().unwrap().sent, [(id, false), (id, true)]);
+
+    // Past its last valid height but seen: it may still be finalized.
+    node.lock().unwrap().height = 151;
+    assert_eq!(sender.poll(&pending).await.unwrap(), SendStatus::Pending);
+    let mut renewed = pending.clone();
+    assert!(!sender.renew(&mut renewed, &[&payer]).await.unwrap());
+    assert_eq!(renewed, pending);
+
+    node.lock()
+        .unwrap()
+        .statuses
+        .insert(id, status(4_000, "finalized", Value::Null));
+    assert_eq!(
+        sender.poll(&pending).await.unwrap(),
+        SendStatus::Landed {
+            signature: id,
+            slot: 4_000
+        }
+    );
+    // A client content with confirmed state is done earlier.
+    let confirmed = Sender::new(client(&node).with_commitment(Commitment::Confirmed));
+    node.lock()
+        .unwrap()
+        .statuses
+        .insert(id, status(4_000, "confirmed", Value::Null));
+    assert_eq!(
+        confirmed.poll(&pending).await.unwrap(),
+        SendStatus::Landed {
+            signature: id,
+            slot: 4_000
+        }
+    );
+    assert_eq!(node.lock().unwrap().sent.len(), 2);
+}
+
+#[tokio::test]
+async fn only_provably_dead_transactions_are_re_signed() {
+    let (node, payer, message) = setup(&[(7, 150), (8, 310)]);
+    let sender = Sender::new(client(&node));
+    let mut pending = sender.prepare(message, &[&payer]).await.unwrap();
+    let first = pending.signature;
+
+    // At its last valid height the blockhash is still good.
+    node.lock().unwrap().height = 150;
+    assert!(!sender.renew(&mut pending, &[&payer]).await.unwrap());
+    // On a fork that was abandoned: gone from the node's history.
+    node.lock().unwrap().height = 151;
+    assert_eq!(sender.poll(&pending).await.unwrap(), SendStatus::Expired);
+    // Nothing is rebroadcast once expired.
+    assert!(node.lock().unwrap().sent.is_empty());
+
+    assert!(sender.renew(&mut pending, &[&payer]).await.unwrap());
+    assert_ne!(pending.signature, first);
+    assert_eq!(pending.expired, [first]);
+    assert_eq!(pending.last_valid_block_height, 310);
+    let tx = pending.transaction().unwrap();
+    tx.verify().unwrap();
+    assert_eq!(tx.id(), pending.signature);
+    assert_eq!(*tx.message.recent_blockhash(), Hash([8; 32]));
+    // Renewing does not send.
+    assert!(node.lock().unwrap().sent.is_empty());
+
+    // The renewed state survives a restart.
+    let stored = serde_json::to_string(&pending).unwrap();
+    assert_eq!(
+        serde_json::from_str::<PendingTransaction>(&stored).unwrap(),
+        pending
+    );
+
+    // Every signer is needed to renew.
+    let (node, payer, message) = setup(&[(7, 150), (8, 310)]);
+    let sender = Sender::new(client(&node));
+    let mut pending = sender.prepare(message, &[&payer]).await.unwrap();
+    node.lock().unwrap().height = 200;
+    let stranger = Ed25519Signer::from_bytes(&[9; 32]);
+    assert!(matches!(
+        sender.renew(&mut pending, &[&stranger]).await,
+        Err(Error::InvalidTransaction(_))
+    ));
+}
+
+#[tokio::test]
+async fn confirm_renews_and_saves_before_resending() {
+    let (node, payer, message) = setup(&[(7, 150), (8, 310)]);
+    let sender = Sender::new(client(&node));
+    let mut pending = sender.prepare(message, &[&payer]).await.unwrap();
+    let first = pending.signature;
+    let saved = Mutex::new(Vec::new());
+    let clock = node.clone();
+
+    let outcome = sender
+        .confirm(
+            &mut pending,
+            &[&payer],
+            || {
+                let mut node = clock.lock().unwrap();
+                node.height += 60;
+                // The first attempt never lands; any later one does.
+                let landed: Vec<Signature> = node
+                    .sent
+                    .iter()
+                    .map(|(id, _)| *id)
+                    .filter(|id| *id != first)
+                    .collect();
+                for id in landed {
+                    node.statuses
+                        .insert(id, status(9_000, "finalized", Value::Null));
+                }
+                async {}
+            },
+            |renewed| {
+                // The renewed transaction must not have left yet.
+                assert!(node
+                    .lock()
+                    .unwrap()
+                    .sent
+                    .iter()
+                    .all(|(id, _)| *id != renewed.signature));
+                saved.lock().unwrap().push(renewed.clone());
+                Ok(())
+            },
+        )
+        .await
+        .unwrap();
+
+    let second = pending.signature;
+    assert_ne!(second, first);
+    assert_eq!(saved.into_inner().unwrap(), [pending.clone()]);
+    let sent = node.lock().unwrap().sent.clone();
+    // The first attempt goes out at heights 0, 60 and 120 and expires at
+    // 180, where the renewal is sent, simulated, and polled once before
+    // it lands.
+    assert_eq!(
+        sent,
+        [
+            (first, true),
+            (first, true),
+            (first, true),
+            (second, false),
+            (second, true)
+        ]
+    );
+    assert_eq!(
+        outcome,
+        SendStatus::Landed {
+            signature: second,
+            slot: 9_000
+        }
+    );
+}
+
+#[tokio::test]
+async fn failures_and_duplicates_are_reported() {
+    let (node, payer, message) = setup(&[(7, 150)]);
+    let sender = Sender::new(client(&node));
+    let pending = sender.prepare(message.clone(), &[&payer]).await.unwrap();
+    let err = json!({"InstructionError": [0, {"Custom": 1}]});
+    node.lock()
+        .unwrap()
+        .statuses
+        .insert(pending.signature, status(77, "finalized", err.clone()));
+    assert_eq!(
+        sender.poll(&pending).await.unwrap(),
+        SendStatus::Failed {
+            signature: pending.signature,
+            slot: 77,
+            err
+        }
+    );
+
+    node.lock().unwrap().already_processed = true;
+    assert_eq!(sender.send(&pending).await.unwrap(), pending.signature);
+
+    // Durable nonce transactions never expire by height.
+    let from = pubkey_of(&payer);
+    let nonce = Message::new_with_nonce(
+        &[system::transfer(&from, &Pubkey::new([2; 32]), 1_000)],
+        &from,
+        &Pubkey::new([4; 32]),
+        &from,
+        Hash([5; 32]),
+    )
+    .unwrap();
+    assert!(matches!(
+        sender.prepare(nonce, &[&payer]).await,
+        Err(Error::InvalidTransaction(_))
+    ));
+}
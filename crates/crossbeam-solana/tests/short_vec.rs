@@ -0,0 +1,43 @@
+use crossbeam_solana::short_vec::{decode_len, encode_len};
+use crossbeam_solana::Error;
+
+#[test]
+fn lengths_round_trip() {
+    for (len, bytes) in [
+        (0u16, &[0x00][..]),
+        (0x7f, &[0x7f]),
+        (0x80, &[0x80, 0x01]),
+        (0xff, &[0xff, 0x01]),
+        (0x3fff, &[0xff, 0x7f]),
+        (0x4000, &[0x80, 0x80, 0x01]),
+        (0xffff, &[0xff, 0xff, 0x03]),
+    ] {
+        let mut out = Vec::new();
+        encode_len(len, &mut out);
+        assert_eq!(out, bytes, "{len:#x}");
+        assert_eq!(
+            decode_len(&[bytes, &[0xee]].concat()).unwrap(),
+            (len, &[0xee][..])
+        );
+    }
+}
+
+#[test]
+fn malformed_lengths_are_rejected() {
+    for bytes in [
+        &[][..],
+        &[0x80],
+        &[0x80, 0x80],
+        // Redundant zero bytes.
+        &[0x80, 0x00],
+        &[0xff, 0x80, 0x00],
+        // Past u16::MAX.
+        &[0xff, 0xff, 0x04],
+        &[0x80, 0x80, 0x80, 0x01],
+    ] {
+        assert!(
+            matches!(decode_len(bytes), Err(Error::InvalidTransaction(_))),
+            "{bytes:02x?}"
+        );
+    }
+}
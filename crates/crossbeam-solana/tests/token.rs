@@ -0,0 +1,260 @@
+//! Layouts and instructions checked against the SPL token interfaces.
+
+use crossbeam_solana::associated_token::{associated_token_address, ASSOCIATED_TOKEN_PROGRAM_ID};
+use crossbeam_solana::system::SYSTEM_PROGRAM_ID;
+use crossbeam_solana::token::{
+    AccountState, Mint, TokenAccount, TokenProgram, NATIVE_MINT, NATIVE_MINT_2022,
+    TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID,
+};
+use crossbeam_solana::token_2022::{
+    disable_required_transfer_memos, enable_required_transfer_memos, transfer_checked_with_fee,
+    ConfidentialTransferAccount, ConfidentialTransferMint, ExtensionType, TransferFee,
+};
+use crossbeam_solana::{Error, Instruction, Pubkey};
+
+fn key(byte: u8) -> Pubkey {
+    Pubkey::new([byte; 32])
+}
+
+/// The instruction's data, then each account as its key's first byte,
+/// flagged `w` if writable and `s` if a signer.
+fn summary(instruction: &Instruction) -> (String, String) {
+    let accounts: Vec<String> = instruction
+        .accounts
+        .iter()
+        .map(|meta| {
+            format!(
+                "{}{}{}",
+                meta.pubkey.0[0],
+                if meta.is_writable { "w" } else { "" },
+                if meta.is_signer { "s" } else { "" }
+            )
+        })
+        .collect();
+    (hex::encode(&instruction.data), accounts.join(","))
+}
+
+#[test]
+fn program_ids() {
+    assert_eq!(
+        NATIVE_MINT.to_string(),
+        "So11111111111111111111111111111111111111112"
+    );
+    assert_eq!(
+        NATIVE_MINT_2022.to_string(),
+        "9pan9bMn5HatX4EJdBwg9VgCa7Uz5HL8N1m5D3NdXejP"
+    );
+    assert_eq!(
+        TokenProgram::from_id(&TOKEN_2022_PROGRAM_ID),
+        Some(TokenProgram::Token2022)
+    );
+    assert_eq!(TokenProgram::from_id(&SYSTEM_PROGRAM_ID), None);
+}
+
+#[test]
+fn instructions() {
+    let program = TokenProgram::Token2022;
+    let (source, mint, destination, authority) = (key(1), key(2), key(3), key(4));
+    for (instruction, data, accounts) in [
+        (
+            program.transfer_checked(&source, &mint, &destination, &authority, 1_500_000, 6),
+            "0c60e316000000000006",
+            "1w,2,3w,4s",
+        ),
+        (
+            program.mint_to(&mint, &destination, &authority, 7),
+            "070700000000000000",
+            "2w,3w,4s",
+        ),
+        (
+            program.burn(&destination, &mint, &authority, 8),
+            "080800000000000000",
+            "3w,2w,4s",
+        ),
+        (
+            program.approve(&destination, &key(5), &authority, 9),
+            "040900000000000000",
+            "3w,5,4s",
+        ),
+        (
+            program.close_account(&destination, &key(6), &authority),
+            "09",
+            "3w,6w,4s",
+        ),
+        (program.sync_native(&destination), "11", "3w"),
+        (
+            transfer_checked_with_fee(
+                &source,
+                &mint,
+                &destination,
+                &authority,
+                1_500_000,
+                6,
+                18_750,
+            ),
+            "1a0160e3160000000000063e49000000000000",
+            "1w,2,3w,4s",
+        ),
+        (
+            enable_required_transfer_memos(&destination, &authority),
+            "1e00",
+            "3w,4s",
+        ),
+        (
+            disable_required_transfer_memos(&destination, &authority),
+            "1e01",
+            "3w,4s",
+        ),
+    ] {
+        assert_eq!(instruction.program_id, TOKEN_2022_PROGRAM_ID);
+        assert_eq!(
+            summary(&instruction),
+            (data.to_owned(), accounts.to_owned())
+        );
+    }
+
+    let legacy = TokenProgram::Token.sync_native(&destination);
+    assert_eq!(legacy.program_id, TOKEN_PROGRAM_ID);
+}
+
+#[test]
+fn wrapping_sol_funds_and_syncs_the_associated_account() {
+    let (payer, owner) = (key(1), key(3));
+    let instructions = TokenProgram::Token.wrap_sol(&payer, &owner, 1_000).unwrap();
+    let account = associated_token_address(&owner, &NATIVE_MINT, &TOKEN_PROGRAM_ID).unwrap();
+    assert_eq!(instructions[0].program_id, ASSOCIATED_TOKEN_PROGRAM_ID);
+    assert_eq!(instructions[0].accounts[3].pubkey, NATIVE_MINT);
+    assert_eq!(instructions[1].program_id, SYSTEM_PROGRAM_ID);
+    assert_eq!(instructions[1].accounts[1].pubkey, account);
+    assert_eq!(instructions[2], TokenProgram::Token.sync_native(&account));
+}
+
+#[test]
+fn token_mint_and_account() {
+    let mint = Mint::decode(&hex::decode("01000000010101010101010101010101010101010101010101010101010101010101010100ca9a3b000000000601000000000000000000000000000000000000000000000000000000000000000000000000").unwrap()).unwrap();
+    assert_eq!(mint.mint_authority, Some(key(1)));
+    assert_eq!((mint.supply, mint.decimals), (1_000_000_000, 6));
+    assert_eq!(mint.freeze_authority, None);
+    assert!(mint.extensions.is_empty());
+
+    let account = TokenAccount::decode(&hex::decode("0202020202020202020202020202020202020202020202020202020202020202030303030303030303030303030303030303030303030303030303030303030388130000000000000100000004040404040404040404040404040404040404040404040404040404040404040201000000f01d1f00000000004d00000000000000010000000505050505050505050505050505050505050505050505050505050505050505").unwrap()).unwrap();
+    assert_eq!((account.mint, account.owner), (key(2), key(3)));
+    assert_eq!(account.amount, 5_000);
+    assert_eq!(account.delegate, Some(key(4)));
+    assert_eq!(account.state, AccountState::Frozen);
+    assert_eq!(account.is_native, Some(2_039_280));
+    assert_eq!(account.delegated_amount, 77);
+    assert_eq!(account.close_authority, Some(key(5)));
+    assert!(account
+        .extensions
+        .accepts_non_confidential_transfers()
+        .unwrap());
+}
+
+const MINT_2022: &str = "0100000001010101010101010101010101010101010101010101010101010101010101012a00000000000000090101000000080808080808080808080808080808080808080808080808080808080808080800000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000101006c0006060606060606060606060606060606060606060606060606060606060606060000000000000000000000000000000000000000000000000000000000000000d204000000000000640000000000000088130000000000003200c80000000000000040420f00000000007d00040041000707070707070707070707070707070707070707070707070707070707070707010000000000000000000000000000000000000000000000000000000000000000";
+
+#[test]
+fn token_2022_mint_extensions() {
+    let mint = Mint::decode(&hex::decode(MINT_2022).unwrap()).unwrap();
+    assert_eq!(mint.supply, 42);
+    assert_eq!(mint.freeze_authority, Some(key(8)));
+
+    let fees = mint.extensions.transfer_fee_config().unwrap().unwrap();
+    assert_eq!(fees.transfer_fee_config_authority, Some(key(6)));
+    assert_eq!(fees.withdraw_withheld_authority, None);
+    assert_eq!(fees.withheld_amount, 1234);
+    assert_eq!(
+        fees.older_transfer_fee,
+        TransferFee {
+            epoch: 100,
+            maximum_fee: 5_000,
+            transfer_fee_basis_points: 50,
+        }
+    );
+    assert_eq!(fees.epoch_fee(199), &fees.older_transfer_fee);
+    assert_eq!(fees.epoch_fee(200), &fees.newer_transfer_fee);
+    // Rounded up, and capped at the maximum; matches the program's
+    // arithmetic.
+    for (amount, fee) in [
+        (0, 0),
+        (1, 1),
+        (79, 1),
+        (80, 1),
+        (81, 2),
+        (10_000, 125),
+        (1_000_000_000, 1_000_000),
+        (u64::MAX, 1_000_000),
+    ] {
+        assert_eq!(fees.calculate_epoch_fee(200, amount), fee, "{amount}");
+    }
+
+    assert_eq!(
+        mint.extensions.confidential_transfer_mint().unwrap(),
+        Some(ConfidentialTransferMint {
+            authority: Some(key(7)),
+            auto_approve_new_accounts: true,
+            auditor_elgamal_pubkey: None,
+        })
+    );
+    assert!(!mint.extensions.contains(ExtensionType::MEMO_TRANSFER));
+}
+
+#[test]
+fn token_2022_account_extensions() {
+    let account = TokenAccount::decode(&hex::decode("020202020202020202020202020202020202020202020202020202020202020203030303030303030303030303030303030303030303030303030303030303030a000000000000000000000000000000000000000000000000000000000000000000000000000000000000000100000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000202000800630000000000000008000100010500270101000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000001000000000000000000000000000000000000000000000000000000000000000000").unwrap()).unwrap();
+    assert_eq!(account.amount, 10);
+    assert_eq!(account.state, AccountState::Initialized);
+    assert_eq!(
+        account.extensions.withheld_transfer_fees().unwrap(),
+        Some(99)
+    );
+    assert!(account.extensions.memo_required().unwrap());
+    assert_eq!(
+        account.extensions.confidential_transfer_account().unwrap(),
+        Some(ConfidentialTransferAccount {
+            approved: true,
+            allow_confidential_credits: true,
+            allow_non_confidential_credits: false,
+        })
+    );
+    assert!(!account
+        .extensions
+        .accepts_non_confidential_transfers()
+        .unwrap());
+}
+
+#[test]
+fn malformed_accounts_are_rejected() {
+    let mint = hex::decode(MINT_2022).unwrap();
+    // A token account is not a mint.
+    assert!(matches!(
+        TokenAccount::decode(&mint),
+        Err(Error::InvalidAccount(_))
+    ));
+    assert!(matches!(
+        Mint::decode(&mint[..81]),
+        Err(Error::InvalidLength { .. })
+    ));
+    // Between the base layout and the account-type byte.
+    assert!(matches!(
+        Mint::decode(&mint[..100]),
+        Err(Error::InvalidAccount(_))
+    ));
+    // The second extension cut short.
+    assert!(matches!(
+        Mint::decode(&mint[..mint.len() - 1]),
+        Err(Error::InvalidAccount(_))
+    ));
+    let mut uninitialized = mint[..82].to_vec();
+    uninitialized[45] = 0;
+    assert!(matches!(
+        Mint::decode(&uninitialized),
+        Err(Error::InvalidAccount(_))
+    ));
+    let mut bad_option = mint[..82].to_vec();
+    bad_option[0] = 2;
+    assert!(matches!(
+        Mint::decode(&bad_option),
+        Err(Error::InvalidAccount(_))
+    ));
+}
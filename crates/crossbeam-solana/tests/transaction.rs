@@ -0,0 +1,276 @@
+//! Encodings checked against transactions built and signed by the Solana
+//! SDK from the same keys and instructions.
+
+use crossbeam_core::signer::Ed25519Signer;
+use crossbeam_core::Signer;
+use crossbeam_solana::system::{self, SYSTEM_PROGRAM_ID};
+use crossbeam_solana::{
+    AccountMeta, AddressLookupTableAccount, Error, Hash, Instruction, Message, MessageV0, Pubkey,
+    Signature, Transaction, VersionedMessage,
+};
+
+fn signer(seed: u8) -> Ed25519Signer {
+    Ed25519Signer::from_bytes(&[seed; 32])
+}
+
+fn pubkey_of(signer: &Ed25519Signer) -> Pubkey {
+    match signer.public_key() {
+        crossbeam_core::signer::PublicKey::Ed25519(key) => Pubkey::new(key),
+        other => panic!("unexpected key {other:?}"),
+    }
+}
+
+fn key(byte: u8) -> Pubkey {
+    Pubkey::new([byte; 32])
+}
+
+const BLOCKHASH: Hash = Hash([7; 32]);
+
+/// Signs `message` with `signers`, checks the wire encoding and id, and
+/// checks that decoding gives back the same, verifiable transaction.
+fn check(message: impl Into<VersionedMessage>, signers: &[&dyn Signer], wire: &str, id: &str) {
+    let mut tx = Transaction::new(message);
+    tx.sign(signers).unwrap();
+    assert!(tx.is_signed());
+    tx.ensure_signed().unwrap();
+    tx.verify().unwrap();
+    assert_eq!(hex::encode(tx.encode()), wire);
+    assert_eq!(tx.id().to_string(), id);
+
+    let decoded = Transaction::decode(&tx.encode()).unwrap();
+    assert_eq!(decoded, tx);
+}
+
+#[test]
+fn legacy_transfer() {
+    let payer = signer(1);
+    assert_eq!(
+        pubkey_of(&payer).to_string(),
+        "AKnL4NNf3DGWZJS6cPknBuEGnVsV4A4m5tgebLHaRSZ9"
+    );
+    let transfer = system::transfer(&pubkey_of(&payer), &key(9), 1_000_000);
+    let message = Message::new(&[transfer], &pubkey_of(&payer), BLOCKHASH).unwrap();
+    assert_eq!(message.header.num_required_signatures, 1);
+    assert_eq!(message.account_keys[2], SYSTEM_PROGRAM_ID);
+    check(
+        message,
+        &[&payer],
+        "019c915e6c202f0da3b34db6e893dae265965e7919436899ef8add0915a9e83354d1b977e00572694bb42dbbc3cac96b73188f71bd7e3958ba58b3c654114f8602010001038a88e3dd7409f195fd52db2d3cba5d72ca6709bf1d94121bf3748801b40f6f5c09090909090909090909090909090909090909090909090909090909090909090000000000000000000000000000000000000000000000000000000000000000070707070707070707070707070707070707070707070707070707070707070701020200010c0200000040420f0000000000",
+        "48ZJgJ5rx5KESA5JxzXxirJvi6HS7xB5dbzgdNSteVuDNTce3SQ6ML1xeCMbJeb2stCjpAXhcNWEoenUmjk4QUqo",
+    );
+}
+
+fn multi_signer_instructions(
+    payer: &Pubkey,
+    new_account: &Pubkey,
+    sender: &Pubkey,
+) -> Vec<Instruction> {
+    let program = key(0x50);
+    vec![
+        system::create_account(payer, new_account, 2_000_000, 165, &program),
+        system::transfer(sender, &key(9), 5),
+        Instruction::new(
+            program,
+            vec![
+                AccountMeta::new(*new_account, false),
+                AccountMeta::new_readonly(key(0x20), false),
+                AccountMeta::new_readonly(*sender, true),
+                AccountMeta::new(key(0x21), false),
+            ],
+            vec![1, 2, 3],
+        ),
+    ]
+}
+
+#[test]
+fn legacy_message_with_several_signers() {
+    let (payer, new_account, sender) = (signer(1), signer(3), signer(4));
+    let instructions = multi_signer_instructions(
+        &pubkey_of(&payer),
+        &pubkey_of(&new_account),
+        &pubkey_of(&sender),
+    );
+    let message = Message::new(&instructions, &pubkey_of(&payer), BLOCKHASH).unwrap();
+    // The sender signs a transfer from itself, so it is a writable signer
+    // even though the last instruction only reads it.
+    assert_eq!(
+        (
+            message.header.num_required_signatures,
+            message.header.num_readonly_signed_accounts,
+            message.header.num_readonly_unsigned_accounts,
+        ),
+        (3, 0, 3)
+    );
+    check(
+        message,
+        &[&sender, &payer, &new_account],
+        "03efa090077e67bccca4a441e95833dfa209a97725add5227f65fedee3b7acab009094cfd9a1474821bcdc46ecc2776d7c77256033969b24629c89ad3683f90a01a4b7ca801f6f8cfad6c5918b685b5f210a8573daf8d7fc2cdfaf2c3f91e5a32b45cc915697b98e3e23c04c14eca3ae3f590dae6d37d296c77d80b5e06446e9094297415bf47d0c74612ef5704fb560e4c427aa6eae6aef4e97b3cffa89c97934ff98a0f6c05d8b3d4596e5967cc32cefd606f8146696de73ab019db9e2f9fd0c030003088a88e3dd7409f195fd52db2d3cba5d72ca6709bf1d94121bf3748801b40f6f5cca93ac1705187071d67b83c7ff0efe8108e8ec4530575d7726879333dbdabe7ced4928c628d1c2c6eae90338905995612959273a5c63f93636c14614ac8737d10909090909090909090909090909090909090909090909090909090909090909212121212121212121212121212121212121212121212121212121212121212100000000000000000000000000000000000000000000000000000000000000002020202020202020202020202020202020202020202020202020202020202020505050505050505050505050505050505050505050505050505050505050505007070707070707070707070707070707070707070707070707070707070707070305020002340000000080841e0000000000a5000000000000005050505050505050505050505050505050505050505050505050505050505050050201030c02000000050000000000000007040206010403010203",
+        "5nse3g69uWfWXox29RvZcPY4KBSLcapwSwV9LmJGNqYwUWCczDYVXRY9zUN7eoFUCSk3H242BgK2ShXsHpKDhh2g",
+    );
+}
+
+#[test]
+fn signers_can_sign_in_turn() {
+    let (payer, new_account, sender) = (signer(1), signer(3), signer(4));
+    let instructions = multi_signer_instructions(
+        &pubkey_of(&payer),
+        &pubkey_of(&new_account),
+        &pubkey_of(&sender),
+    );
+    let message = Message::new(&instructions, &pubkey_of(&payer), BLOCKHASH).unwrap();
+    let mut tx = Transaction::new(message);
+    assert_eq!(tx.signatures, vec![Signature::default(); 3]);
+
+    tx.sign(&[&new_account]).unwrap();
+    assert!(!tx.is_signed());
+    assert!(matches!(
+        tx.ensure_signed(),
+        Err(Error::InvalidTransaction(reason)) if reason == "1 of 3 required signatures present"
+    ));
+    assert!(matches!(tx.verify(), Err(Error::InvalidSignature(_))));
+
+    // Partially signed transactions survive the wire, e.g. to pass them on
+    // to the next signer.
+    let mut tx = Transaction::decode(&tx.encode()).unwrap();
+    tx.sign(&[&payer, &sender]).unwrap();
+    assert!(tx.is_signed());
+    tx.ensure_signed().unwrap();
+    tx.verify().unwrap();
+
+    let stranger = signer(0x77);
+    assert!(matches!(
+        tx.sign(&[&stranger]),
+        Err(Error::InvalidTransaction(_))
+    ));
+
+    tx.signatures[1].0[0] ^= 1;
+    assert!(matches!(tx.verify(), Err(Error::InvalidSignature(_))));
+}
+
+#[test]
+fn v0_message_loads_accounts_from_lookup_tables() {
+    let (payer, sender) = (signer(1), signer(4));
+    let program = key(0x50);
+    let instructions = vec![
+        system::transfer(&pubkey_of(&payer), &key(0x31), 42),
+        Instruction::new(
+            program,
+            vec![
+                AccountMeta::new(key(0x32), false),
+                AccountMeta::new_readonly(key(0x33), false),
+                AccountMeta::new_readonly(key(0x34), false),
+                AccountMeta::new(key(0x35), false),
+                AccountMeta::new_readonly(key(0x36), false),
+                AccountMeta::new_readonly(pubkey_of(&sender), true),
+            ],
+            vec![9; 10],
+        ),
+    ];
+    let tables = [
+        AddressLookupTableAccount {
+            key: key(0x40),
+            addresses: vec![key(0x99), key(0x33), key(0x31), program, key(0x35)],
+        },
+        AddressLookupTableAccount {
+            key: key(0x41),
+            addresses: vec![key(0x34), key(0x98)],
+        },
+        // Nothing is loaded from a table that holds none of the accounts.
+        AddressLookupTableAccount {
+            key: key(0x42),
+            addresses: vec![key(0x97)],
+        },
+    ];
+    let message =
+        MessageV0::compile(&instructions, &pubkey_of(&payer), &tables, BLOCKHASH).unwrap();
+    assert_eq!(message.address_table_lookups.len(), 2);
+    // The program stays static even though the first table holds it.
+    assert!(message.account_keys.contains(&program));
+    assert_eq!(message.address_table_lookups[0].writable_indexes, [2, 4]);
+    assert_eq!(message.address_table_lookups[0].readonly_indexes, [1]);
+    assert_eq!(message.address_table_lookups[1].readonly_indexes, [0]);
+    check(
+        message,
+        &[&payer, &sender],
+        "0263dea2e646b05347611f6a501e37eaba6e2792cad3b77965ca947e9a84e9ac9270e34d4bcbaa57c775202da9a4bc1364dc046e4f7087486f61a8529ea852e00b49fb44db0f8be2d48bfe5b3b8dcbdb62ef9ddeb4b45978186ab853268dcd8f27423ca4a9f907e3038afe769b7d47161cadf76e520a9a563bf72305191e9abd0780020103068a88e3dd7409f195fd52db2d3cba5d72ca6709bf1d94121bf3748801b40f6f5cca93ac1705187071d67b83c7ff0efe8108e8ec4530575d7726879333dbdabe7c3232323232323232323232323232323232323232323232323232323232323232000000000000000000000000000000000000000000000000000000000000000036363636363636363636363636363636363636363636363636363636363636365050505050505050505050505050505050505050505050505050505050505050070707070707070707070707070707070707070707070707070707070707070702030200060c020000002a0000000000000005060208090704010a0909090909090909090902404040404040404040404040404040404040404040404040404040404040404002020401014141414141414141414141414141414141414141414141414141414141414141000100",
+        "2zowzHidi2nt7GiDhGiJWXPQsTrNDoGexdSAJRg1gfmFgoB9QmKc7Xb5D3yVkpUpqjwc4y7ySXq4uEtjPP97M6s4",
+    );
+}
+
+#[test]
+fn durable_nonce_transaction() {
+    let (payer, authority) = (signer(1), signer(5));
+    let nonce = Hash([0x66; 32]);
+    let transfer = system::transfer(&pubkey_of(&payer), &key(9), 7);
+    let message = Message::new_with_nonce(
+        &[transfer],
+        &pubkey_of(&payer),
+        &key(0x60),
+        &pubkey_of(&authority),
+        nonce,
+    )
+    .unwrap();
+    assert_eq!(message.recent_blockhash, nonce);
+    let versioned = VersionedMessage::from(message.clone());
+    assert!(versioned.uses_durable_nonce());
+    assert_eq!(
+        versioned.signers(),
+        [pubkey_of(&payer), pubkey_of(&authority)]
+    );
+    check(
+        message,
+        &[&payer, &authority],
+        "024da01d40491d069e98fff37e13f82c7c8e23addd66eec661b3852297f8b6d088eb1237ecec571360dff4c80a4547c2ec3f754d8776de376fab9119316b1a9c04886c71248b4c9666461e556266443c481ba61edbf0ee57fee5682d2c85c3de2bf8edef8668fe44c858533e421c681c764b965c19d7be71fae942820327a21e09020102068a88e3dd7409f195fd52db2d3cba5d72ca6709bf1d94121bf3748801b40f6f5c6e7a1cdd29b0b78fd13af4c5598feff4ef2a97166e3ca6f2e4fbfccd80505bf109090909090909090909090909090909090909090909090909090909090909096060606060606060606060606060606060606060606060606060606060606060000000000000000000000000000000000000000000000000000000000000000006a7d517192c568ee08a845f73d29788cf035c3145b21ab344d8062ea940000066666666666666666666666666666666666666666666666666666666666666660204030305010404000000040200020c020000000700000000000000",
+        "2Z1sBDCngYCqsDbwqXrVwzcC35VvPusL3BxH5jis7SsRauEzyP2hDKUtHnbBuVPuZCr7nSJwRPfjj1oAfXvHwNgj",
+    );
+
+    let plain = Message::new(
+        &[system::transfer(&pubkey_of(&payer), &key(9), 7)],
+        &pubkey_of(&payer),
+        BLOCKHASH,
+    )
+    .unwrap();
+    assert!(!VersionedMessage::from(plain).uses_durable_nonce());
+}
+
+#[test]
+fn malformed_transactions_are_rejected() {
+    let payer = signer(1);
+    let transfer = system::transfer(&pubkey_of(&payer), &key(9), 1);
+    let message = Message::new(&[transfer], &pubkey_of(&payer), BLOCKHASH).unwrap();
+    let mut tx = Transaction::new(message);
+    tx.sign(&[&payer]).unwrap();
+    let wire = tx.encode();
+
+    let reject = |wire: &[u8]| Transaction::decode(wire).unwrap_err();
+    assert!(matches!(
+        reject(&wire[..wire.len() - 1]),
+        Error::InvalidTransaction(_)
+    ));
+    assert!(matches!(
+        reject(&[wire.as_slice(), &[0]].concat()),
+        Error::InvalidTransaction(_)
+    ));
+    // Two signatures for a message with one signer.
+    let mut doubled = vec![2];
+    doubled.extend_from_slice(&wire[1..65]);
+    doubled.extend_from_slice(&wire[1..]);
+    assert!(matches!(reject(&doubled), Error::InvalidTransaction(_)));
+    // A version 1 message.
+    let mut versioned = wire.clone();
+    versioned[65] = 0x81;
+    assert!(matches!(reject(&versioned), Error::InvalidTransaction(_)));
+}
+
+#[test]
+fn too_many_accounts_do_not_compile() {
+    let payer = key(1);
+    let accounts = (0..=255u8)
+        .map(|i| AccountMeta::new(Pubkey::new([i, 0xaa].repeat(16).try_into().unwrap()), false))
+        .collect();
+    let instruction = Instruction::new(key(0x50), accounts, Vec::new());
+    assert!(matches!(
+        Message::new(&[instruction], &payer, BLOCKHASH),
+        Err(Error::InvalidTransaction(_))
+    ));
+}
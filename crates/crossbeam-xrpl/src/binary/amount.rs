@@ -0,0 +1,401 @@
+//! `STAmount` in its three forms, and `STNumber`.
+//!
+//! XRP amounts are 64 bits of drops. Issued-currency amounts are a 54-bit
+//! mantissa and an 8-bit exponent followed by the currency and the issuer;
+//! MPT amounts are a flag byte, 64 bits and the issuance id.
+
+use std::cmp::Ordering;
+
+use serde_json::{json, Map, Value};
+
+use super::{invalid, types, Reader};
+use crate::error::Result;
+
+/// Set on every amount that is not XRP or MPT.
+const NOT_NATIVE: u64 = 0x8000_0000_0000_0000;
+/// Set on positive amounts.
+const POSITIVE: u64 = 0x4000_0000_0000_0000;
+/// The first byte of every MPT amount: MPT, positive.
+const MPT_PREFIX: u8 = 0x60;
+/// The MPT flag in the first byte.
+const MPT_FLAG: u8 = 0x20;
+
+/// 100 billion XRP, in drops.
+pub(crate) const MAX_DROPS: u64 = 100_000_000_000_000_000;
+
+/// Issued-currency values are normalized to a 16-digit mantissa.
+const MIN_IOU_MANTISSA: u64 = 1_000_000_000_000_000;
+const MAX_IOU_PRECISION: usize = 16;
+const MIN_IOU_EXPONENT: i64 = -96;
+const MAX_IOU_EXPONENT: i64 = 80;
+
+/// `STNumber` normalizes its mantissa to 19 digits within an `i64`.
+const MIN_NUMBER_MANTISSA: u128 = 1_000_000_000_000_000_000;
+const MAX_NUMBER_MANTISSA: u128 = 9_999_999_999_999_999_999;
+const MIN_NUMBER_EXPONENT: i64 = -32_768;
+const MAX_NUMBER_EXPONENT: i64 = 32_768;
+/// The exponent of a zero `STNumber`.
+const ZERO_NUMBER_EXPONENT: i32 = i32::MIN;
+
+/// A decimal string taken apart: `digits` has neither leading nor trailing
+/// zeros, and is empty for zero.
+struct Decimal {
+    negative: bool,
+    digits: String,
+    exponent: i64,
+}
+
+impl Decimal {
+    /// Parses `[+-]?digits[.digits][(e|E)[+-]?digits]`.
+    fn parse(s: &str) -> Option<Self> {
+        let (negative, s) = match s.as_bytes().first()? {
+            b'-' => (true, &s[1..]),
+            b'+' => (false, &s[1..]),
+            _ => (false, s),
+        };
+        let (s, exponent) = match s.find(['e', 'E']) {
+            Some(at) => {
+                let exponent = &s[at + 1..];
+                let digits = exponent.strip_prefix(['-', '+']).unwrap_or(exponent);
+                if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                    return None;
+                }
+                (&s[..at], exponent.parse::<i32>().ok()?.into())
+            }
+            None => (s, 0),
+        };
+        let (int, frac) = s.split_once('.').unwrap_or((s, ""));
+        if int.is_empty() && frac.is_empty()
+            || !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit())
+        {
+            return None;
+        }
+        let mut exponent: i64 = exponent - frac.len() as i64;
+        let digits = format!("{int}{frac}");
+        let digits = digits.trim_start_matches('0');
+        let trimmed = digits.trim_end_matches('0');
+        exponent += (digits.len() - trimmed.len()) as i64;
+        Some(Self {
+            negative,
+            digits: trimmed.to_owned(),
+            exponent,
+        })
+    }
+}
+
+/// Formats `mantissa × 10^exponent` the way rippled and xrpl.js format
+/// amounts: plainly, or in exponential notation when the leading digit is
+/// at 10^21 or above, or at 10^-7 or below.
+fn format_decimal(negative: bool, mantissa: u128, exponent: i64) -> String {
+    if mantissa == 0 {
+        return "0".to_owned();
+    }
+    let (digits, stripped) = strip_zeros(mantissa, exponent);
+    let scientific = stripped + digits.len() as i64 - 1;
+    if (-6..21).contains(&scientific) {
+        return format_plain(negative, mantissa, exponent);
+    }
+    let sign = if negative { "-" } else { "" };
+    let (lead, rest) = digits.split_at(1);
+    let point = if rest.is_empty() { "" } else { "." };
+    let exp_sign = if scientific < 0 { "-" } else { "+" };
+    format!("{sign}{lead}{point}{rest}e{exp_sign}{}", scientific.abs())
+}
+
+/// Formats `mantissa × 10^exponent` without an exponent.
+fn format_plain(negative: bool, mantissa: u128, exponent: i64) -> String {
+    if mantissa == 0 {
+        return "0".to_owned();
+    }
+    let (digits, exponent) = strip_zeros(mantissa, exponent);
+    let sign = if negative { "-" } else { "" };
+    if exponent >= 0 {
+        return format!("{sign}{digits}{}", "0".repeat(exponent as usize));
+    }
+    let point = digits.len() as i64 + exponent;
+    if point > 0 {
+        let (int, frac) = digits.split_at(point as usize);
+        format!("{sign}{int}.{frac}")
+    } else {
+        format!(
+            "{sign}0.{}{digits}",
+            "0".repeat(point.unsigned_abs() as usize)
+        )
+    }
+}
+
+/// The digits of `mantissa` without trailing zeros, and the exponent
+/// adjusted to match.
+fn strip_zeros(mantissa: u128, exponent: i64) -> (String, i64) {
+    let digits = mantissa.to_string();
+    let trimmed = digits.trim_end_matches('0');
+    let exponent = exponent + (digits.len() - trimmed.len()) as i64;
+    (trimmed.to_owned(), exponent)
+}
+
+/// Serializes an amount: a string of drops, an issued-currency object or
+/// an MPT object.
+pub(super) fn encode(value: &Value, out: &mut Vec<u8>) -> Result<()> {
+    match value {
+        Value::String(drops) => {
+            out.extend_from_slice(&encode_drops(drops)?.to_be_bytes());
+            Ok(())
+        }
+        Value::Object(map) if map.contains_key("mpt_issuance_id") => encode_mpt(map, out),
+        Value::Object(map) => encode_issued(map, out),
+        _ => Err(invalid(format!("invalid amount {value}"))),
+    }
+}
+
+fn encode_drops(drops: &str) -> Result<u64> {
+    if drops.starts_with('-') {
+        return Err(invalid(format!("XRP amount {drops} is negative")));
+    }
+    if drops.is_empty() || !drops.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid(format!(
+            "XRP amount {drops} is not a whole number of drops"
+        )));
+    }
+    match drops.parse::<u64>() {
+        Ok(drops) if drops <= MAX_DROPS => Ok(drops | POSITIVE),
+        _ => Err(invalid(format!("XRP amount {drops} exceeds the supply"))),
+    }
+}
+
+fn check_keys(map: &Map<String, Value>, allowed: &[&str], kind: &str) -> Result<()> {
+    match map.keys().find(|key| !allowed.contains(&key.as_str())) {
+        Some(key) => Err(invalid(format!("{key} is not valid for an {kind} amount"))),
+        None => Ok(()),
+    }
+}
+
+fn value_str(map: &Map<String, Value>) -> Result<&str> {
+    map.get("value")
+        .and_then(Value::as_str)
+        .ok_or_else(|| invalid("amount has no value"))
+}
+
+/// Parses an issued-currency value into its sign, a mantissa normalized to
+/// 16 digits and an exponent, or `None` for zero.
+fn parse_issued_value(value: &str) -> Result<Option<(bool, u64, i64)>> {
+    let decimal =
+        Decimal::parse(value).ok_or_else(|| invalid(format!("invalid amount value {value}")))?;
+    if decimal.digits.is_empty() {
+        return Ok(None);
+    }
+    if decimal.digits.len() > MAX_IOU_PRECISION {
+        return Err(invalid(format!(
+            "amount value {value} has more than {MAX_IOU_PRECISION} significant digits"
+        )));
+    }
+    let mut mantissa: u64 = decimal.digits.parse().expect("at most 16 digits");
+    let mut exponent = decimal.exponent;
+    while mantissa < MIN_IOU_MANTISSA {
+        mantissa *= 10;
+        exponent -= 1;
+    }
+    if !(MIN_IOU_EXPONENT..=MAX_IOU_EXPONENT).contains(&exponent) {
+        return Err(invalid(format!("amount value {value} is out of range")));
+    }
+    Ok(Some((decimal.negative, mantissa, exponent)))
+}
+
+/// An issued-currency value in the form rippled reports it, such as `1`
+/// for `1.0` or `1e-7` for `0.0000001`.
+pub(crate) fn canonical_issued_value(value: &str) -> Result<String> {
+    Ok(match parse_issued_value(value)? {
+        Some((negative, mantissa, exponent)) => format_decimal(negative, mantissa.into(), exponent),
+        None => "0".to_owned(),
+    })
+}
+
+/// Orders two non-negative issued-currency values.
+pub(crate) fn cmp_issued_values(a: &str, b: &str) -> Result<Ordering> {
+    let magnitude = |value| {
+        parse_issued_value(value)
+            .map(|parsed| parsed.map(|(_, mantissa, exponent)| (exponent, mantissa)))
+    };
+    Ok(magnitude(a)?.cmp(&magnitude(b)?))
+}
+
+fn encode_issued(map: &Map<String, Value>, out: &mut Vec<u8>) -> Result<()> {
+    check_keys(map, &["currency", "issuer", "value"], "issued-currency")?;
+    let bits = match parse_issued_value(value_str(map)?)? {
+        Some((negative, mantissa, exponent)) => {
+            let sign = if negative { 0 } else { POSITIVE };
+            NOT_NATIVE | sign | ((exponent + 97) as u64) << 54 | mantissa
+        }
+        None => NOT_NATIVE,
+    };
+    out.extend_from_slice(&bits.to_be_bytes());
+    let currency = map
+        .get("currency")
+        .and_then(Value::as_str)
+        .ok_or_else(|| invalid("amount has no currency"))?;
+    out.extend_from_slice(&types::encode_currency(currency)?);
+    let issuer = map
+        .get("issuer")
+        .and_then(Value::as_str)
+        .ok_or_else(|| invalid("amount has no issuer"))?;
+    out.extend_from_slice(&types::encode_account(issuer)?);
+    Ok(())
+}
+
+fn encode_mpt(map: &Map<String, Value>, out: &mut Vec<u8>) -> Result<()> {
+    check_keys(map, &["mpt_issuance_id", "value"], "MPT")?;
+    let value = value_str(map)?;
+    let amount = match value.strip_prefix("0x") {
+        Some(hex) if !hex.is_empty() && hex.bytes().all(|b| b.is_ascii_hexdigit()) => {
+            u64::from_str_radix(hex, 16).ok()
+        }
+        Some(_) => None,
+        None if value == "-0" => Some(0),
+        None if value.starts_with('-') => {
+            return Err(invalid(format!("MPT amount {value} is negative")))
+        }
+        None if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) => {
+            value.parse().ok()
+        }
+        None => None,
+    };
+    let amount =
+        amount.ok_or_else(|| invalid(format!("MPT amount {value} is not a whole number")))?;
+    if amount > i64::MAX as u64 {
+        return Err(invalid(format!("MPT amount {value} is out of range")));
+    }
+    out.push(MPT_PREFIX);
+    out.extend_from_slice(&amount.to_be_bytes());
+    out.extend_from_slice(&types::mpt_issuance_id(&map["mpt_issuance_id"])?);
+    Ok(())
+}
+
+pub(super) fn decode(reader: &mut Reader<'_>) -> Result<Value> {
+    let first = reader.read_u8()?;
+    let flags = u64::from(first) << 56;
+    let negative = flags & POSITIVE == 0;
+    if flags & NOT_NATIVE == 0 && first & MPT_FLAG != 0 {
+        let amount = u64::from_be_bytes(reader.read_array()?);
+        let id: [u8; 24] = reader.read_array()?;
+        let sign = if negative && amount != 0 { "-" } else { "" };
+        return Ok(json!({
+            "mpt_issuance_id": hex::encode_upper(id),
+            "value": format!("{sign}{amount}"),
+        }));
+    }
+    let mut bytes = [first; 8];
+    bytes[1..].copy_from_slice(reader.read(7)?);
+    let bits = u64::from_be_bytes(bytes);
+    if bits & NOT_NATIVE == 0 {
+        let drops = bits & (POSITIVE - 1);
+        let sign = if negative && drops != 0 { "-" } else { "" };
+        return Ok(format!("{sign}{drops}").into());
+    }
+    let mantissa = bits & ((1 << 54) - 1);
+    let exponent = ((bits >> 54) & 0xFF) as i64 - 97;
+    let currency = types::decode_currency(&reader.read_array()?);
+    let issuer = types::decode_account(reader.read_array()?);
+    Ok(json!({
+        "currency": currency,
+        "issuer": issuer,
+        "value": format_decimal(negative, mantissa.into(), exponent),
+    }))
+}
+
+/// Serializes an `STNumber`: a 64-bit mantissa and a 32-bit exponent.
+pub(super) fn encode_number(value: &str, out: &mut Vec<u8>) -> Result<()> {
+    let decimal =
+        Decimal::parse(value).ok_or_else(|| invalid(format!("invalid number {value}")))?;
+    let (mantissa, exponent) = normalize_number(decimal)
+        .ok_or_else(|| invalid(format!("number {value} is out of range")))?;
+    out.extend_from_slice(&mantissa.to_be_bytes());
+    out.extend_from_slice(&exponent.to_be_bytes());
+    Ok(())
+}
+
+/// Brings the mantissa within 19 digits and an `i64`, rounding half up
+/// on the last digit dropped, as rippled does.
+fn normalize_number(decimal: Decimal) -> Option<(i64, i32)> {
+    if decimal.digits.is_empty() {
+        return Some((0, ZERO_NUMBER_EXPONENT));
+    }
+    // Digits past the 20th cannot affect the rounded result.
+    let kept = decimal.digits.len().min(20);
+    let mut exponent = decimal.exponent + (decimal.digits.len() - kept) as i64;
+    let mut mantissa: u128 = decimal.digits[..kept].parse().ok()?;
+    while mantissa < MIN_NUMBER_MANTISSA && exponent > MIN_NUMBER_EXPONENT {
+        mantissa *= 10;
+        exponent -= 1;
+    }
+    let mut dropped = None;
+    while mantissa > MAX_NUMBER_MANTISSA {
+        if exponent >= MAX_NUMBER_EXPONENT {
+            return None;
+        }
+        dropped = Some(mantissa % 10);
+        mantissa /= 10;
+        exponent += 1;
+    }
+    if mantissa < MIN_NUMBER_MANTISSA
+        || !(MIN_NUMBER_EXPONENT..=MAX_NUMBER_EXPONENT).contains(&exponent)
+    {
+        return None;
+    }
+    // Nineteen digits may still exceed an i64; such a mantissa loses one
+    // more digit.
+    if mantissa > i64::MAX as u128 {
+        if exponent >= MAX_NUMBER_EXPONENT {
+            return None;
+        }
+        dropped = Some(mantissa % 10);
+        mantissa /= 10;
+        exponent += 1;
+    }
+    if dropped.is_some_and(|digit| digit >= 5) {
+        mantissa += 1;
+        if mantissa > i64::MAX as u128 {
+            if exponent >= MAX_NUMBER_EXPONENT {
+                return None;
+            }
+            let digit = mantissa % 10;
+            mantissa = mantissa / 10 + u128::from(digit >= 5);
+            exponent += 1;
+        }
+    }
+    let mantissa = mantissa as i64;
+    let mantissa = if decimal.negative {
+        -mantissa
+    } else {
+        mantissa
+    };
+    Some((mantissa, exponent as i32))
+}
+
+/// Formats an `STNumber` the way rippled does.
+pub(super) fn decode_number(bytes: [u8; 12]) -> Result<String> {
+    let mantissa = i64::from_be_bytes(bytes[..8].try_into().expect("8 bytes"));
+    let exponent = i32::from_be_bytes(bytes[8..].try_into().expect("4 bytes"));
+    if mantissa == 0 && exponent == ZERO_NUMBER_EXPONENT {
+        return Ok("0".to_owned());
+    }
+    let mut exponent = i64::from(exponent);
+    if !(MIN_NUMBER_EXPONENT - 1..=MAX_NUMBER_EXPONENT + 1).contains(&exponent) {
+        return Err(invalid("number exponent is out of range"));
+    }
+    let negative = mantissa < 0;
+    let mut magnitude = u128::from(mantissa.unsigned_abs());
+    // A mantissa shrunk to fit an i64 is restored to 19 digits.
+    if magnitude != 0 && magnitude < MIN_NUMBER_MANTISSA {
+        magnitude *= 10;
+        exponent -= 1;
+    }
+    // Exponents outside [-28, -8] print in exponential notation.
+    if exponent != 0 && !(-28..=-8).contains(&exponent) {
+        while magnitude != 0 && magnitude % 10 == 0 && exponent < MAX_NUMBER_EXPONENT {
+            magnitude /= 10;
+            exponent += 1;
+        }
+        let sign = if negative { "-" } else { "" };
+        return Ok(format!("{sign}{magnitude}e{exponent}"));
+    }
+    Ok(format_plain(negative, magnitude, exponent))
+}
@@ -0,0 +1,281 @@
+//! The field table of the binary format.
+//!
+//! rippled publishes its serialized types, fields and enumerations as
+//! `definitions.json` (the `server_definitions` command returns the same
+//! document). The codec is driven entirely by it: [`Definitions::xrpl`] is
+//! the table this crate ships with, and [`Definitions::from_json`] loads the
+//! one of a network with amendments the shipped table predates.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use serde::Deserialize;
+
+use crate::error::{Error, Result};
+
+/// The field table shipped with this crate.
+const XRPL_DEFINITIONS: &str = include_str!("definitions.json");
+
+/// Delegatable permissions finer than a transaction type. Delegating a
+/// whole transaction type is its type code plus one.
+const GRANULAR_PERMISSIONS: [(&str, i32); 12] = [
+    ("TrustlineAuthorize", 65537),
+    ("TrustlineFreeze", 65538),
+    ("TrustlineUnfreeze", 65539),
+    ("AccountDomainSet", 65540),
+    ("AccountEmailHashSet", 65541),
+    ("AccountMessageKeySet", 65542),
+    ("AccountTransferRateSet", 65543),
+    ("AccountTickSizeSet", 65544),
+    ("PaymentMint", 65545),
+    ("PaymentBurn", 65546),
+    ("MPTokenIssuanceLock", 65547),
+    ("MPTokenIssuanceUnlock", 65548),
+];
+
+/// The serialized type of a field, which decides how its value is encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FieldType {
+    UInt8,
+    UInt16,
+    UInt32,
+    UInt64,
+    /// A fixed-size byte string: `Hash128` through `Hash512`, and `UInt96`.
+    Hash(usize),
+    Amount,
+    Blob,
+    AccountId,
+    Number,
+    Int32,
+    Object,
+    Array,
+    PathSet,
+    Vector256,
+    Issue,
+    XChainBridge,
+    Currency,
+    /// A type this codec cannot encode.
+    Other,
+}
+
+impl FieldType {
+    fn from_name(name: &str) -> Self {
+        match name {
+            "UInt8" => FieldType::UInt8,
+            "UInt16" => FieldType::UInt16,
+            "UInt32" => FieldType::UInt32,
+            "UInt64" => FieldType::UInt64,
+            "Hash128" => FieldType::Hash(16),
+            "Hash160" => FieldType::Hash(20),
+            "Hash192" => FieldType::Hash(24),
+            "Hash256" => FieldType::Hash(32),
+            "Hash384" => FieldType::Hash(48),
+            "Hash512" => FieldType::Hash(64),
+            "UInt96" => FieldType::Hash(12),
+            "Amount" => FieldType::Amount,
+            "Blob" => FieldType::Blob,
+            "AccountID" => FieldType::AccountId,
+            "Number" => FieldType::Number,
+            "Int32" => FieldType::Int32,
+            "STObject" => FieldType::Object,
+            "STArray" => FieldType::Array,
+            "PathSet" => FieldType::PathSet,
+            "Vector256" => FieldType::Vector256,
+            "Issue" => FieldType::Issue,
+            "XChainBridge" => FieldType::XChainBridge,
+            "Currency" => FieldType::Currency,
+            _ => FieldType::Other,
+        }
+    }
+}
+
+/// A serializable field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    pub name: String,
+    pub field_type: FieldType,
+    pub type_code: u16,
+    /// The index of the field among the fields of its type.
+    pub nth: u16,
+    /// Whether the value carries a length prefix.
+    pub vl_encoded: bool,
+    /// Whether the field is covered by transaction signatures.
+    pub signing: bool,
+}
+
+impl Field {
+    /// The canonical sort key: fields serialize by type code, then by
+    /// index.
+    pub fn ordinal(&self) -> (u16, u16) {
+        (self.type_code, self.nth)
+    }
+
+    /// The field id, one to three bytes.
+    pub fn header(&self) -> Vec<u8> {
+        let (t, n) = (self.type_code as u8, self.nth as u8);
+        match (self.type_code < 16, self.nth < 16) {
+            (true, true) => vec![t << 4 | n],
+            (true, false) => vec![t << 4, n],
+            (false, true) => vec![n, t],
+            (false, false) => vec![0, t, n],
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+struct RawDefinitions {
+    types: HashMap<String, i32>,
+    fields: Vec<(String, RawField)>,
+    transaction_types: HashMap<String, i32>,
+    ledger_entry_types: HashMap<String, i32>,
+    transaction_results: HashMap<String, i32>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawField {
+    nth: i32,
+    #[serde(rename = "isVLEncoded")]
+    is_vl_encoded: bool,
+    is_serialized: bool,
+    is_signing_field: bool,
+    #[serde(rename = "type")]
+    type_name: String,
+}
+
+/// A name table, looked up both ways.
+#[derive(Debug, Clone, Default)]
+struct Names {
+    codes: HashMap<String, i32>,
+    names: HashMap<i32, String>,
+}
+
+impl Names {
+    fn new(codes: HashMap<String, i32>) -> Self {
+        let names = codes
+            .iter()
+            .map(|(name, &code)| (code, name.clone()))
+            .collect();
+        Self { codes, names }
+    }
+}
+
+/// The fields, types and enumerations of a network's binary format.
+#[derive(Debug, Clone)]
+pub struct Definitions {
+    fields: HashMap<String, Field>,
+    by_id: HashMap<(u16, u16), String>,
+    transaction_types: Names,
+    ledger_entry_types: Names,
+    transaction_results: Names,
+    permissions: Names,
+}
+
+impl Definitions {
+    /// The table shipped with this crate.
+    pub fn xrpl() -> &'static Definitions {
+        static DEFINITIONS: OnceLock<Definitions> = OnceLock::new();
+        DEFINITIONS.get_or_init(|| {
+            Definitions::from_json(XRPL_DEFINITIONS).expect("shipped definitions.json is valid")
+        })
+    }
+
+    /// Loads a table in the format of `definitions.json`.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let raw: RawDefinitions = serde_json::from_str(json)?;
+        let mut fields = HashMap::new();
+        let mut by_id = HashMap::new();
+        for (name, field) in raw.fields {
+            let code = raw.types.get(&field.type_name).copied().ok_or_else(|| {
+                Error::Codec(format!("field {name} has unknown type {}", field.type_name))
+            })?;
+            // Pseudo-types such as `Unknown` and `Transaction` have codes
+            // outside the byte range and never reach the wire.
+            let Ok(type_code @ 1..) = u8::try_from(code) else {
+                continue;
+            };
+            if !field.is_serialized {
+                continue;
+            }
+            let Ok(nth @ 1..) = u8::try_from(field.nth) else {
+                return Err(Error::Codec(format!("field {name} has an invalid id")));
+            };
+            let field = Field {
+                name: name.clone(),
+                field_type: FieldType::from_name(&field.type_name),
+                type_code: type_code.into(),
+                nth: nth.into(),
+                vl_encoded: field.is_vl_encoded,
+                signing: field.is_signing_field,
+            };
+            by_id.insert(field.ordinal(), name.clone());
+            fields.insert(name, field);
+        }
+        let permissions = raw
+            .transaction_types
+            .iter()
+            .map(|(name, &code)| (name.clone(), code + 1))
+            .chain(GRANULAR_PERMISSIONS.map(|(name, code)| (name.to_owned(), code)))
+            .collect();
+        Ok(Self {
+            fields,
+            by_id,
+            permissions: Names::new(permissions),
+            transaction_types: Names::new(raw.transaction_types),
+            ledger_entry_types: Names::new(raw.ledger_entry_types),
+            transaction_results: Names::new(raw.transaction_results),
+        })
+    }
+
+    /// The serializable field named `name`.
+    pub fn field(&self, name: &str) -> Option<&Field> {
+        self.fields.get(name)
+    }
+
+    /// The field with the given type code and index.
+    pub fn field_by_id(&self, type_code: u16, nth: u16) -> Option<&Field> {
+        self.by_id
+            .get(&(type_code, nth))
+            .and_then(|name| self.fields.get(name))
+    }
+
+    pub fn transaction_type_code(&self, name: &str) -> Option<i32> {
+        self.transaction_types.codes.get(name).copied()
+    }
+
+    pub fn transaction_type_name(&self, code: i32) -> Option<&str> {
+        self.transaction_types.names.get(&code).map(String::as_str)
+    }
+
+    pub fn ledger_entry_type_code(&self, name: &str) -> Option<i32> {
+        self.ledger_entry_types.codes.get(name).copied()
+    }
+
+    pub fn ledger_entry_type_name(&self, code: i32) -> Option<&str> {
+        self.ledger_entry_types.names.get(&code).map(String::as_str)
+    }
+
+    /// The code of a transaction result such as `tesSUCCESS`.
+    pub fn transaction_result_code(&self, name: &str) -> Option<i32> {
+        self.transaction_results.codes.get(name).copied()
+    }
+
+    pub fn transaction_result_name(&self, code: i32) -> Option<&str> {
+        self.transaction_results
+            .names
+            .get(&code)
+            .map(String::as_str)
+    }
+
+    /// The code of a delegatable permission, a transaction type or a
+    /// granular permission such as `TrustlineFreeze`.
+    pub fn permission_code(&self, name: &str) -> Option<i32> {
+        self.permissions.codes.get(name).copied()
+    }
+
+    pub fn permission_name(&self, code: i32) -> Option<&str> {
+        self.permissions.names.get(&code).map(String::as_str)
+    }
+}
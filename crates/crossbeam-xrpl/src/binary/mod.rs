@@ -0,0 +1,182 @@
+//! The XRP Ledger binary format.
+//!
+//! Transactions and ledger objects are `STObject`s: fields in canonical
+//! order, each a field id followed by its value. The codec converts between
+//! the JSON form rippled speaks and the canonical bytes it signs and
+//! hashes, driven by the field table in [`Definitions`].
+//!
+//! Keys of a JSON object that start with a lowercase letter are rippled
+//! annotations such as `hash` or `ledger_index` and are skipped; any other
+//! key must name a serializable field. X-addresses in `Account` and
+//! `Destination` are unpacked into the classic address and the
+//! `SourceTag` or `DestinationTag` field.
+
+pub(crate) mod amount;
+mod definitions;
+mod object;
+pub(crate) mod types;
+
+use serde_json::Value;
+
+pub use definitions::{Definitions, Field, FieldType};
+
+use crate::error::{Error, Result};
+use crate::hash::{sha512_half, Hash256};
+use crate::AccountId;
+
+/// Prefix of the bytes a single signature covers, `STX\0`.
+pub const SINGLE_SIGNING_PREFIX: [u8; 4] = *b"STX\0";
+/// Prefix of the bytes each multisignature covers, `SMT\0`.
+pub const MULTI_SIGNING_PREFIX: [u8; 4] = *b"SMT\0";
+/// Prefix of the bytes hashed into a transaction id, `TXN\0`.
+pub const TRANSACTION_ID_PREFIX: [u8; 4] = *b"TXN\0";
+
+/// Length prefixes encode at most this many bytes.
+pub const MAX_VL_LENGTH: usize = 918_744;
+
+/// Serializes a JSON transaction or ledger object.
+pub fn encode(json: &Value) -> Result<Vec<u8>> {
+    Definitions::xrpl().encode(json)
+}
+
+/// Deserializes a transaction or ledger object into its JSON form.
+pub fn decode(bytes: &[u8]) -> Result<Value> {
+    Definitions::xrpl().decode(bytes)
+}
+
+/// The bytes a single signature covers: the signing prefix and the
+/// signing fields of the transaction.
+pub fn encode_for_signing(json: &Value) -> Result<Vec<u8>> {
+    Definitions::xrpl().encode_for_signing(json)
+}
+
+/// The bytes the signature of `signer` covers in a multisigned
+/// transaction, whose `SigningPubKey` must be empty.
+pub fn encode_for_multisigning(json: &Value, signer: &AccountId) -> Result<Vec<u8>> {
+    Definitions::xrpl().encode_for_multisigning(json, signer)
+}
+
+/// The hash secp256k1 keys sign, SHA-512Half of
+/// [`encode_for_signing`]. Ed25519 keys sign the bytes themselves.
+pub fn signing_hash(json: &Value) -> Result<Hash256> {
+    encode_for_signing(json).map(|bytes| sha512_half(&bytes))
+}
+
+/// The id of a signed transaction given its serialized form.
+pub fn transaction_id(blob: &[u8]) -> Hash256 {
+    let mut data = Vec::with_capacity(TRANSACTION_ID_PREFIX.len() + blob.len());
+    data.extend_from_slice(&TRANSACTION_ID_PREFIX);
+    data.extend_from_slice(blob);
+    sha512_half(&data)
+}
+
+impl Definitions {
+    /// Serializes a JSON object with this field table.
+    pub fn encode(&self, json: &Value) -> Result<Vec<u8>> {
+        let mut out = Vec::new();
+        object::encode(self, as_object(json)?, false, &mut out)?;
+        Ok(out)
+    }
+
+    /// Deserializes an object with this field table.
+    pub fn decode(&self, bytes: &[u8]) -> Result<Value> {
+        object::decode(self, &mut Reader::new(bytes)).map(Value::Object)
+    }
+
+    /// See [`encode_for_signing`].
+    pub fn encode_for_signing(&self, json: &Value) -> Result<Vec<u8>> {
+        let mut out = SINGLE_SIGNING_PREFIX.to_vec();
+        object::encode(self, as_object(json)?, true, &mut out)?;
+        Ok(out)
+    }
+
+    /// See [`encode_for_multisigning`].
+    pub fn encode_for_multisigning(&self, json: &Value, signer: &AccountId) -> Result<Vec<u8>> {
+        let map = as_object(json)?;
+        if map.get("SigningPubKey").and_then(Value::as_str) != Some("") {
+            return Err(invalid(
+                "a multisigned transaction must have an empty SigningPubKey",
+            ));
+        }
+        let mut out = MULTI_SIGNING_PREFIX.to_vec();
+        object::encode(self, map, true, &mut out)?;
+        out.extend_from_slice(signer.as_bytes());
+        Ok(out)
+    }
+}
+
+fn as_object(json: &Value) -> Result<&serde_json::Map<String, Value>> {
+    json.as_object()
+        .ok_or_else(|| invalid("expected a JSON object"))
+}
+
+pub(crate) fn invalid(reason: impl Into<String>) -> Error {
+    Error::Codec(reason.into())
+}
+
+/// Appends the length prefix of a variable-length value.
+pub(crate) fn encode_vl_length(len: usize, out: &mut Vec<u8>) -> Result<()> {
+    match len {
+        0..=192 => out.push(len as u8),
+        193..=12_480 => {
+            let len = len - 193;
+            out.extend_from_slice(&[193 + (len >> 8) as u8, len as u8]);
+        }
+        12_481..=MAX_VL_LENGTH => {
+            let len = len - 12_481;
+            out.extend_from_slice(&[241 + (len >> 16) as u8, (len >> 8) as u8, len as u8]);
+        }
+        _ => return Err(invalid(format!("{len} bytes exceed the maximum length"))),
+    }
+    Ok(())
+}
+
+/// A cursor over serialized bytes.
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub(crate) fn read(&mut self, len: usize) -> Result<&'a [u8]> {
+        if len > self.bytes.len() {
+            return Err(invalid("unexpected end of input"));
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    pub(crate) fn read_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        self.read(N).map(|bytes| bytes.try_into().expect("N bytes"))
+    }
+
+    pub(crate) fn read_u8(&mut self) -> Result<u8> {
+        self.read_array::<1>().map(|[byte]| byte)
+    }
+
+    /// Everything left.
+    pub(crate) fn rest(&mut self) -> &'a [u8] {
+        std::mem::take(&mut self.bytes)
+    }
+
+    pub(crate) fn read_vl_length(&mut self) -> Result<usize> {
+        let b1 = self.read_u8()? as usize;
+        Ok(match b1 {
+            0..=192 => b1,
+            193..=240 => 193 + ((b1 - 193) << 8) + self.read_u8()? as usize,
+            241..=254 => {
+                let [b2, b3] = self.read_array::<2>()?;
+                12_481 + ((b1 - 241) << 16) + ((b2 as usize) << 8) + b3 as usize
+            }
+            _ => return Err(invalid("invalid length prefix")),
+        })
+    }
+}
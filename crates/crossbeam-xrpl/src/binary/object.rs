@@ -0,0 +1,363 @@
+//! `STObject` and `STArray`, and the dispatch of fields to their types.
+
+use std::borrow::Cow;
+
+use serde_json::{Map, Value};
+
+use super::definitions::{Definitions, Field, FieldType};
+use super::{amount, encode_vl_length, invalid, types, Reader};
+use crate::error::Result;
+use crate::XrplAddress;
+
+/// Type code and index of the marker that closes an object.
+const OBJECT_END: (u16, u16) = (14, 1);
+/// Type code and index of the marker that closes an array.
+const ARRAY_END: (u16, u16) = (15, 1);
+
+/// Objects nest at most this deep; rippled allows far less.
+const MAX_DEPTH: usize = 32;
+
+/// Serializes the fields of `map` in canonical order, without a closing
+/// marker. `signing_only` keeps only the fields signatures cover.
+pub(super) fn encode(
+    defs: &Definitions,
+    map: &Map<String, Value>,
+    signing_only: bool,
+    out: &mut Vec<u8>,
+) -> Result<()> {
+    encode_fields(defs, map, signing_only, out, 0)
+}
+
+/// Deserializes the fields of a top-level object, up to the end of input.
+pub(super) fn decode(defs: &Definitions, reader: &mut Reader<'_>) -> Result<Map<String, Value>> {
+    decode_fields(defs, reader, 0)
+}
+
+fn encode_fields(
+    defs: &Definitions,
+    map: &Map<String, Value>,
+    signing_only: bool,
+    out: &mut Vec<u8>,
+    depth: usize,
+) -> Result<()> {
+    if depth > MAX_DEPTH {
+        return Err(invalid("objects nest too deep"));
+    }
+    let mut fields = Vec::with_capacity(map.len());
+    let mut tags = Vec::new();
+    for (name, value) in map {
+        if name.starts_with(|c: char| c.is_ascii_lowercase()) {
+            continue;
+        }
+        let field = defs
+            .field(name)
+            .ok_or_else(|| invalid(format!("unknown field {name}")))?;
+        let value = match (field.field_type, value) {
+            (FieldType::AccountId, Value::String(s)) if s.starts_with(['X', 'T']) => {
+                let address: XrplAddress = s.parse()?;
+                if let Some(tag) = address.tag() {
+                    tags.push((tag_field(&field.name)?, tag));
+                }
+                Cow::Owned(Value::String(address.account().to_string()))
+            }
+            _ => Cow::Borrowed(value),
+        };
+        fields.push((field, value));
+    }
+    for (name, tag) in tags {
+        if map.contains_key(name) {
+            return Err(invalid(format!(
+                "{name} is given both as a field and in an X-address"
+            )));
+        }
+        let field = defs
+            .field(name)
+            .ok_or_else(|| invalid(format!("unknown field {name}")))?;
+        fields.push((field, Cow::Owned(tag.into())));
+    }
+    if signing_only {
+        fields.retain(|(field, _)| field.signing);
+    }
+    fields.sort_by_key(|(field, _)| field.ordinal());
+    for (field, value) in fields {
+        encode_field(defs, field, &value, out, depth)?;
+    }
+    Ok(())
+}
+
+/// The field that takes the tag of an X-address given for `field`.
+fn tag_field(field: &str) -> Result<&'static str> {
+    match field {
+        "Account" => Ok("SourceTag"),
+        "Destination" => Ok("DestinationTag"),
+        _ => Err(invalid(format!("{field} cannot carry a tag"))),
+    }
+}
+
+fn encode_field(
+    defs: &Definitions,
+    field: &Field,
+    value: &Value,
+    out: &mut Vec<u8>,
+    depth: usize,
+) -> Result<()> {
+    out.extend_from_slice(&field.header());
+    if field.vl_encoded {
+        let mut buf = Vec::new();
+        encode_value(defs, field, value, &mut buf, depth)?;
+        encode_vl_length(buf.len(), out)?;
+        out.extend_from_slice(&buf);
+        Ok(())
+    } else {
+        encode_value(defs, field, value, out, depth)
+    }
+}
+
+fn encode_value(
+    defs: &Definitions,
+    field: &Field,
+    value: &Value,
+    out: &mut Vec<u8>,
+    depth: usize,
+) -> Result<()> {
+    let name = field.name.as_str();
+    let mismatch = || invalid(format!("invalid value for {name}: {value}"));
+    match field.field_type {
+        FieldType::UInt8 => {
+            let code = match (name, value) {
+                ("TransactionResult", Value::String(result)) => defs
+                    .transaction_result_code(result)
+                    .ok_or_else(|| invalid(format!("unknown transaction result {result}")))?
+                    .into(),
+                _ => types::uint(value)
+                    .and_then(|v| i64::try_from(v).ok())
+                    .ok_or_else(mismatch)?,
+            };
+            out.push(u8::try_from(code).map_err(|_| mismatch())?);
+        }
+        FieldType::UInt16 => {
+            let code = match (name, value) {
+                ("TransactionType", Value::String(kind)) => defs
+                    .transaction_type_code(kind)
+                    .ok_or_else(|| invalid(format!("unknown transaction type {kind}")))?
+                    .into(),
+                ("LedgerEntryType", Value::String(kind)) => defs
+                    .ledger_entry_type_code(kind)
+                    .ok_or_else(|| invalid(format!("unknown ledger entry type {kind}")))?
+                    .into(),
+                _ => types::uint(value)
+                    .and_then(|v| i64::try_from(v).ok())
+                    .ok_or_else(mismatch)?,
+            };
+            let code = u16::try_from(code).map_err(|_| mismatch())?;
+            out.extend_from_slice(&code.to_be_bytes());
+        }
+        FieldType::UInt32 => {
+            let value = match (name, value) {
+                ("PermissionValue", Value::String(permission)) => defs
+                    .permission_code(permission)
+                    .and_then(|code| u64::try_from(code).ok())
+                    .ok_or_else(|| invalid(format!("unknown permission {permission}")))?,
+                _ => types::uint(value).ok_or_else(mismatch)?,
+            };
+            let value = u32::try_from(value).map_err(|_| mismatch())?;
+            out.extend_from_slice(&value.to_be_bytes());
+        }
+        FieldType::UInt64 => {
+            out.extend_from_slice(&types::encode_u64(name, value).ok_or_else(mismatch)?);
+        }
+        FieldType::Int32 => {
+            let value = value
+                .as_i64()
+                .and_then(|v| i32::try_from(v).ok())
+                .ok_or_else(mismatch)?;
+            out.extend_from_slice(&value.to_be_bytes());
+        }
+        FieldType::Hash(len) => {
+            let bytes = types::hex(value).ok_or_else(mismatch)?;
+            if bytes.len() != len {
+                return Err(invalid(format!("{name} must be {len} bytes")));
+            }
+            out.extend_from_slice(&bytes);
+        }
+        FieldType::Blob => out.extend_from_slice(&types::hex(value).ok_or_else(mismatch)?),
+        FieldType::AccountId => out.extend_from_slice(&types::encode_account(
+            value.as_str().ok_or_else(mismatch)?,
+        )?),
+        FieldType::Amount => amount::encode(value, out)?,
+        FieldType::Number => amount::encode_number(value.as_str().ok_or_else(mismatch)?, out)?,
+        FieldType::Currency => {
+            out.extend_from_slice(&types::encode_currency(
+                value.as_str().ok_or_else(mismatch)?,
+            )?);
+        }
+        FieldType::Issue => types::encode_issue(value, out)?,
+        FieldType::PathSet => types::encode_path_set(value, out)?,
+        FieldType::Vector256 => types::encode_vector256(value, out)?,
+        FieldType::XChainBridge => types::encode_xchain_bridge(value, out)?,
+        FieldType::Object => {
+            let map = value.as_object().ok_or_else(mismatch)?;
+            encode_fields(defs, map, false, out, depth + 1)?;
+            out.push(0xE1);
+        }
+        FieldType::Array => {
+            for element in value.as_array().ok_or_else(mismatch)? {
+                let (inner, object) = array_element(defs, element)?;
+                out.extend_from_slice(&inner.header());
+                encode_fields(defs, object, false, out, depth + 1)?;
+                out.push(0xE1);
+            }
+            out.push(0xF1);
+        }
+        FieldType::Other => return Err(invalid(format!("{name} has an unsupported type"))),
+    }
+    Ok(())
+}
+
+/// An array element is an object with a single key, the name of an object
+/// field wrapping the element.
+fn array_element<'a>(
+    defs: &'a Definitions,
+    element: &'a Value,
+) -> Result<(&'a Field, &'a Map<String, Value>)> {
+    let wrapped = element
+        .as_object()
+        .filter(|map| map.len() == 1)
+        .and_then(|map| map.iter().next())
+        .and_then(|(name, value)| Some((defs.field(name)?, value.as_object()?)))
+        .filter(|(field, _)| field.field_type == FieldType::Object);
+    wrapped.ok_or_else(|| invalid(format!("invalid array element {element}")))
+}
+
+/// Reads a field id.
+fn read_field_id(reader: &mut Reader<'_>) -> Result<(u16, u16)> {
+    let byte = reader.read_u8()?;
+    let mut type_code = u16::from(byte >> 4);
+    let mut nth = u16::from(byte & 0x0F);
+    if type_code == 0 {
+        type_code = reader.read_u8()?.into();
+        if type_code < 16 {
+            return Err(invalid("non-canonical field id"));
+        }
+    }
+    if nth == 0 {
+        nth = reader.read_u8()?.into();
+        if nth < 16 {
+            return Err(invalid("non-canonical field id"));
+        }
+    }
+    Ok((type_code, nth))
+}
+
+fn read_field(defs: &Definitions, id: (u16, u16)) -> Result<&Field> {
+    defs.field_by_id(id.0, id.1)
+        .ok_or_else(|| invalid(format!("unknown field id {}/{}", id.0, id.1)))
+}
+
+/// Reads fields up to the end of input, or up to the object end marker
+/// for a nested object.
+fn decode_fields(
+    defs: &Definitions,
+    reader: &mut Reader<'_>,
+    depth: usize,
+) -> Result<Map<String, Value>> {
+    if depth > MAX_DEPTH {
+        return Err(invalid("objects nest too deep"));
+    }
+    let mut map = Map::new();
+    while depth > 0 || !reader.is_empty() {
+        let id = read_field_id(reader)?;
+        if id == OBJECT_END {
+            if depth == 0 {
+                return Err(invalid("unexpected object end marker"));
+            }
+            break;
+        }
+        let field = read_field(defs, id)?;
+        let value = if field.vl_encoded {
+            let len = reader.read_vl_length()?;
+            let mut inner = Reader::new(reader.read(len)?);
+            let value = decode_value(defs, field, &mut inner, depth)?;
+            if !inner.is_empty() {
+                return Err(invalid(format!("trailing bytes in {}", field.name)));
+            }
+            value
+        } else {
+            decode_value(defs, field, reader, depth)?
+        };
+        if map.insert(field.name.clone(), value).is_some() {
+            return Err(invalid(format!("duplicate field {}", field.name)));
+        }
+    }
+    Ok(map)
+}
+
+fn decode_value(
+    defs: &Definitions,
+    field: &Field,
+    reader: &mut Reader<'_>,
+    depth: usize,
+) -> Result<Value> {
+    let name = field.name.as_str();
+    Ok(match field.field_type {
+        FieldType::UInt8 => {
+            let code = reader.read_u8()?;
+            match defs.transaction_result_name(code.into()) {
+                Some(result) if name == "TransactionResult" => result.into(),
+                _ => code.into(),
+            }
+        }
+        FieldType::UInt16 => {
+            let code = u16::from_be_bytes(reader.read_array()?);
+            let kind = match name {
+                "TransactionType" => defs.transaction_type_name(code.into()),
+                "LedgerEntryType" => defs.ledger_entry_type_name(code.into()),
+                _ => None,
+            };
+            kind.map_or_else(|| code.into(), Value::from)
+        }
+        FieldType::UInt32 => {
+            let value = u32::from_be_bytes(reader.read_array()?);
+            let permission = match (name, i32::try_from(value)) {
+                ("PermissionValue", Ok(code)) => defs.permission_name(code),
+                _ => None,
+            };
+            permission.map_or_else(|| value.into(), Value::from)
+        }
+        FieldType::UInt64 => types::decode_u64(name, reader.read_array()?).into(),
+        FieldType::Int32 => i32::from_be_bytes(reader.read_array()?).into(),
+        FieldType::Hash(len) => hex::encode_upper(reader.read(len)?).into(),
+        FieldType::Blob => hex::encode_upper(reader.rest()).into(),
+        FieldType::AccountId => types::decode_account(reader.read_array()?).into(),
+        FieldType::Amount => amount::decode(reader)?,
+        FieldType::Number => amount::decode_number(reader.read_array()?)?.into(),
+        FieldType::Currency => types::decode_currency(&reader.read_array()?).into(),
+        FieldType::Issue => types::decode_issue(reader)?,
+        FieldType::PathSet => types::decode_path_set(reader)?,
+        FieldType::Vector256 => types::decode_vector256(reader)?,
+        FieldType::XChainBridge => types::decode_xchain_bridge(reader)?,
+        FieldType::Object => decode_fields(defs, reader, depth + 1)?.into(),
+        FieldType::Array => {
+            let mut elements = Vec::new();
+            loop {
+                let id = read_field_id(reader)?;
+                if id == ARRAY_END {
+                    break;
+                }
+                let inner = read_field(defs, id)?;
+                if inner.field_type != FieldType::Object {
+                    return Err(invalid(format!(
+                        "{} cannot be an element of {name}",
+                        inner.name
+                    )));
+                }
+                let object = decode_fields(defs, reader, depth + 1)?;
+                let mut element = Map::new();
+                element.insert(inner.name.clone(), object.into());
+                elements.push(Value::Object(element));
+            }
+            elements.into()
+        }
+        FieldType::Other => return Err(invalid(format!("{name} has an unsupported type"))),
+    })
+}
@@ -0,0 +1,312 @@
+//! The serialized types other than amounts and objects.
+
+use serde_json::{json, Map, Value};
+
+use super::{encode_vl_length, invalid, Reader};
+use crate::error::Result;
+use crate::AccountId;
+
+/// `UInt64` fields whose JSON form is decimal; the others are hex.
+const DECIMAL_UINT64_FIELDS: [&str; 5] = [
+    "MaximumAmount",
+    "OutstandingAmount",
+    "MPTAmount",
+    "LockedAmount",
+    "ConfidentialOutstandingAmount",
+];
+
+/// The account an MPT issue stands in for a currency with.
+const NO_ACCOUNT: [u8; 20] = {
+    let mut account = [0; 20];
+    account[19] = 1;
+    account
+};
+
+/// Path step flags.
+const PATH_ACCOUNT: u8 = 0x01;
+const PATH_CURRENCY: u8 = 0x10;
+const PATH_ISSUER: u8 = 0x20;
+/// Separates the paths of a path set.
+const PATH_SEPARATOR: u8 = 0xFF;
+/// Ends a path set.
+const PATH_SET_END: u8 = 0x00;
+
+/// An unsigned integer given as a JSON number or a decimal string.
+pub(super) fn uint(value: &Value) -> Option<u64> {
+    match value {
+        Value::Number(n) => n.as_u64(),
+        Value::String(s) if !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()) => {
+            s.parse().ok()
+        }
+        _ => None,
+    }
+}
+
+pub(super) fn encode_u64(field: &str, value: &Value) -> Option<[u8; 8]> {
+    let value = match value {
+        Value::Number(n) => n.as_u64()?,
+        Value::String(_) if DECIMAL_UINT64_FIELDS.contains(&field) => uint(value)?,
+        Value::String(s) if !s.is_empty() && s.len() <= 16 => u64::from_str_radix(s, 16).ok()?,
+        _ => return None,
+    };
+    Some(value.to_be_bytes())
+}
+
+pub(super) fn decode_u64(field: &str, bytes: [u8; 8]) -> String {
+    if DECIMAL_UINT64_FIELDS.contains(&field) {
+        u64::from_be_bytes(bytes).to_string()
+    } else {
+        hex::encode_upper(bytes)
+    }
+}
+
+/// Bytes given as a hex string.
+pub(super) fn hex(value: &Value) -> Option<Vec<u8>> {
+    hex::decode(value.as_str()?).ok()
+}
+
+/// An account given as a classic address, or as 40 hex digits.
+pub(super) fn encode_account(s: &str) -> Result<[u8; 20]> {
+    if s.len() == 40 {
+        if let Ok(bytes) = hex::decode(s) {
+            return Ok(bytes.try_into().expect("20 bytes"));
+        }
+    }
+    let account: AccountId = s.parse()?;
+    Ok(account.0)
+}
+
+pub(super) fn decode_account(bytes: [u8; 20]) -> String {
+    AccountId(bytes).to_string()
+}
+
+fn is_iso_code(code: &str) -> bool {
+    code.len() == 3
+        && code
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"?!@#$%^&*(){}[]|".contains(&b))
+}
+
+/// A currency: `XRP`, a three-character ISO code, or 40 hex digits.
+pub(crate) fn encode_currency(code: &str) -> Result<[u8; 20]> {
+    let mut bytes = [0; 20];
+    if code == "XRP" {
+        return Ok(bytes);
+    }
+    if is_iso_code(code) {
+        bytes[12..15].copy_from_slice(code.as_bytes());
+        return Ok(bytes);
+    }
+    match hex::decode(code) {
+        Ok(decoded) if decoded.len() == 20 => {
+            bytes.copy_from_slice(&decoded);
+            Ok(bytes)
+        }
+        _ => Err(invalid(format!("invalid currency code {code}"))),
+    }
+}
+
+pub(super) fn decode_currency(bytes: &[u8; 20]) -> String {
+    if bytes == &[0; 20] {
+        return "XRP".to_owned();
+    }
+    let standard = bytes[..12] == [0; 12] && bytes[15..] == [0; 5];
+    match std::str::from_utf8(&bytes[12..15]) {
+        Ok(code) if standard && code != "XRP" && is_iso_code(code) => code.to_owned(),
+        _ => hex::encode_upper(bytes),
+    }
+}
+
+fn field<'a>(map: &'a Map<String, Value>, key: &str) -> Result<&'a str> {
+    map.get(key)
+        .and_then(Value::as_str)
+        .ok_or_else(|| invalid(format!("missing {key}")))
+}
+
+/// An asset: `{"currency": "XRP"}`, a currency with its issuer, or an MPT
+/// issuance.
+pub(super) fn encode_issue(value: &Value, out: &mut Vec<u8>) -> Result<()> {
+    let map = value
+        .as_object()
+        .ok_or_else(|| invalid(format!("invalid issue {value}")))?;
+    if let Some(id) = map.get("mpt_issuance_id") {
+        let id = mpt_issuance_id(id)?;
+        out.extend_from_slice(&id[4..]);
+        out.extend_from_slice(&NO_ACCOUNT);
+        let sequence = u32::from_be_bytes(id[..4].try_into().expect("4 bytes"));
+        out.extend_from_slice(&sequence.to_le_bytes());
+        return Ok(());
+    }
+    let currency = encode_currency(field(map, "currency")?)?;
+    out.extend_from_slice(&currency);
+    match map.get("issuer") {
+        None if currency == [0; 20] => Ok(()),
+        Some(Value::String(issuer)) if currency != [0; 20] => {
+            out.extend_from_slice(&encode_account(issuer)?);
+            Ok(())
+        }
+        _ => Err(invalid(format!("invalid issue {value}"))),
+    }
+}
+
+pub(super) fn decode_issue(reader: &mut Reader<'_>) -> Result<Value> {
+    let currency: [u8; 20] = reader.read_array()?;
+    if currency == [0; 20] {
+        return Ok(json!({ "currency": "XRP" }));
+    }
+    let account: [u8; 20] = reader.read_array()?;
+    if account == NO_ACCOUNT {
+        let sequence = u32::from_le_bytes(reader.read_array()?);
+        let mut id = sequence.to_be_bytes().to_vec();
+        id.extend_from_slice(&currency);
+        return Ok(json!({ "mpt_issuance_id": hex::encode_upper(id) }));
+    }
+    Ok(json!({
+        "currency": decode_currency(&currency),
+        "issuer": decode_account(account),
+    }))
+}
+
+/// The 24 bytes of an MPT issuance id: the issuer's sequence number and
+/// account.
+pub(super) fn mpt_issuance_id(value: &Value) -> Result<[u8; 24]> {
+    value
+        .as_str()
+        .and_then(|id| hex::decode(id).ok())
+        .and_then(|id| id.try_into().ok())
+        .ok_or_else(|| invalid(format!("invalid mpt_issuance_id {value}")))
+}
+
+pub(super) fn encode_path_set(value: &Value, out: &mut Vec<u8>) -> Result<()> {
+    let paths = value
+        .as_array()
+        .ok_or_else(|| invalid(format!("invalid path set {value}")))?;
+    for (i, path) in paths.iter().enumerate() {
+        if i > 0 {
+            out.push(PATH_SEPARATOR);
+        }
+        let steps = path
+            .as_array()
+            .ok_or_else(|| invalid(format!("invalid path {path}")))?;
+        for step in steps {
+            let map = step
+                .as_object()
+                .ok_or_else(|| invalid(format!("invalid path step {step}")))?;
+            let mut flags = 0;
+            let mut body = Vec::new();
+            if map.contains_key("account") {
+                flags |= PATH_ACCOUNT;
+                body.extend_from_slice(&encode_account(field(map, "account")?)?);
+            }
+            if map.contains_key("currency") {
+                flags |= PATH_CURRENCY;
+                body.extend_from_slice(&encode_currency(field(map, "currency")?)?);
+            }
+            if map.contains_key("issuer") {
+                flags |= PATH_ISSUER;
+                body.extend_from_slice(&encode_account(field(map, "issuer")?)?);
+            }
+            if flags == 0 {
+                return Err(invalid("empty path step"));
+            }
+            out.push(flags);
+            out.extend_from_slice(&body);
+        }
+    }
+    out.push(PATH_SET_END);
+    Ok(())
+}
+
+pub(super) fn decode_path_set(reader: &mut Reader<'_>) -> Result<Value> {
+    let mut paths = Vec::new();
+    let mut path = Vec::new();
+    loop {
+        let flags = reader.read_u8()?;
+        if flags == PATH_SEPARATOR || (flags == PATH_SET_END && !path.is_empty()) {
+            paths.push(Value::Array(std::mem::take(&mut path)));
+        }
+        if flags == PATH_SET_END {
+            return Ok(Value::Array(paths));
+        }
+        if flags == PATH_SEPARATOR {
+            continue;
+        }
+        if flags & !(PATH_ACCOUNT | PATH_CURRENCY | PATH_ISSUER) != 0 {
+            return Err(invalid(format!("invalid path step type {flags:#04x}")));
+        }
+        let mut step = Map::new();
+        if flags & PATH_ACCOUNT != 0 {
+            step.insert(
+                "account".into(),
+                decode_account(reader.read_array()?).into(),
+            );
+        }
+        if flags & PATH_CURRENCY != 0 {
+            step.insert(
+                "currency".into(),
+                decode_currency(&reader.read_array()?).into(),
+            );
+        }
+        if flags & PATH_ISSUER != 0 {
+            step.insert("issuer".into(), decode_account(reader.read_array()?).into());
+        }
+        path.push(Value::Object(step));
+    }
+}
+
+pub(super) fn encode_vector256(value: &Value, out: &mut Vec<u8>) -> Result<()> {
+    let hashes = value
+        .as_array()
+        .ok_or_else(|| invalid(format!("invalid hash list {value}")))?;
+    for hash in hashes {
+        match self::hex(hash) {
+            Some(bytes) if bytes.len() == 32 => out.extend_from_slice(&bytes),
+            _ => return Err(invalid(format!("invalid hash {hash}"))),
+        }
+    }
+    Ok(())
+}
+
+pub(super) fn decode_vector256(reader: &mut Reader<'_>) -> Result<Value> {
+    let bytes = reader.rest();
+    if bytes.len() % 32 != 0 {
+        return Err(invalid("hash list length is not a multiple of 32"));
+    }
+    Ok(bytes.chunks(32).map(hex::encode_upper).collect())
+}
+
+/// The doors and issues of a cross-chain bridge.
+pub(super) fn encode_xchain_bridge(value: &Value, out: &mut Vec<u8>) -> Result<()> {
+    let map = value
+        .as_object()
+        .ok_or_else(|| invalid(format!("invalid bridge {value}")))?;
+    for (door, issue) in [
+        ("LockingChainDoor", "LockingChainIssue"),
+        ("IssuingChainDoor", "IssuingChainIssue"),
+    ] {
+        let account = encode_account(field(map, door)?)?;
+        encode_vl_length(account.len(), out)?;
+        out.extend_from_slice(&account);
+        encode_issue(
+            map.get(issue)
+                .ok_or_else(|| invalid(format!("missing {issue}")))?,
+            out,
+        )?;
+    }
+    Ok(())
+}
+
+pub(super) fn decode_xchain_bridge(reader: &mut Reader<'_>) -> Result<Value> {
+    let mut map = Map::new();
+    for (door, issue) in [
+        ("LockingChainDoor", "LockingChainIssue"),
+        ("IssuingChainDoor", "IssuingChainIssue"),
+    ] {
+        if reader.read_vl_length()? != 20 {
+            return Err(invalid(format!("{door} must be 20 bytes")));
+        }
+        map.insert(door.into(), decode_account(reader.read_array()?).into());
+        map.insert(issue.into(), decode_issue(reader)?);
+    }
+    Ok(Value::Object(map))
+}
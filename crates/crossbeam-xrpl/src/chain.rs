@@ -0,0 +1,93 @@
+use async_trait::async_trait;
+use crossbeam_core::{Chain, Confirmation, Network, Signer, Transfer};
+
+use crate::error::{Error, Result};
+use crate::hash::Hash256;
+use crate::keys::account_id;
+use crate::provider::Provider;
+use crate::signing::SignedTransaction;
+use crate::transaction::{AutofillOptions, CurrencyAmount, Payment, Transaction};
+use crate::XrplAddress;
+
+/// [`Chain`] implementation for the XRP Ledger.
+#[derive(Debug, Clone)]
+pub struct XrplChain<P> {
+    provider: P,
+    autofill: AutofillOptions,
+}
+
+impl<P: Provider> XrplChain<P> {
+    pub fn new(provider: P) -> Self {
+        Self {
+            provider,
+            autofill: AutofillOptions::default(),
+        }
+    }
+
+    /// Replaces the options transfers are autofilled with.
+    pub fn with_autofill_options(mut self, options: AutofillOptions) -> Self {
+        self.autofill = options;
+        self
+    }
+
+    pub fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    /// Fills in `tx` with this adapter's autofill options.
+    pub async fn autofill(&self, tx: &mut Transaction) -> Result<()> {
+        tx.autofill(&self.provider, &self.autofill).await
+    }
+}
+
+#[async_trait]
+impl<P: Provider> Chain for XrplChain<P> {
+    type Address = XrplAddress;
+    type Transaction = Transaction;
+    type SignedTransaction = SignedTransaction;
+    type TxId = Hash256;
+    type Error = Error;
+
+    fn network(&self) -> Network {
+        Network::XrpLedger
+    }
+
+    fn parse_address(&self, s: &str) -> Result<XrplAddress> {
+        Ok(s.parse()?)
+    }
+
+    /// Builds a direct payment of XRP or an issued currency. Tags carried
+    /// by X-addresses become the `SourceTag` and `DestinationTag`.
+    async fn build_transfer(&self, transfer: &Transfer<XrplAddress>) -> Result<Transaction> {
+        let amount = CurrencyAmount::try_from(&transfer.amount)?;
+        let mut tx = Transaction::new(transfer.from.account(), Payment::to(&transfer.to, amount));
+        tx.source_tag = transfer.from.tag();
+        self.autofill(&mut tx).await?;
+        Ok(tx)
+    }
+
+    /// Signs with a single key, or multisigns when given several: each
+    /// signer then signs for the account its key is the master key of, and
+    /// the fee must have been filled in for that many signers.
+    fn sign(&self, tx: Transaction, signers: &[&dyn Signer]) -> Result<SignedTransaction> {
+        match signers {
+            [] => Err(Error::InvalidTransaction("no signers".to_owned())),
+            [signer] => tx.sign(*signer),
+            signers => {
+                let signatures = signers
+                    .iter()
+                    .map(|signer| tx.multisign(&account_id(&signer.public_key()), *signer))
+                    .collect::<Result<Vec<_>>>()?;
+                tx.combine(&signatures)
+            }
+        }
+    }
+
+    async fn submit(&self, tx: &SignedTransaction) -> Result<Hash256> {
+        self.provider.submit(tx.blob()).await
+    }
+
+    async fn confirmation(&self, id: &Hash256) -> Result<Confirmation> {
+        self.provider.transaction_confirmation(id).await
+    }
+}
@@ -0,0 +1,303 @@
+//! Verification of inbound deposits.
+//!
+//! A payment's `Amount` is what the sender asked to deliver, not what
+//! arrived: a partial payment may deliver a tiny fraction of it, and a
+//! failed transaction still sits in the ledger with its fee charged.
+//! [`DepositVerifier`] therefore credits only what the metadata of a
+//! validated, successful payment says was delivered, and rejects a
+//! transaction whenever the answer is not clear-cut.
+//!
+//! The verifier takes the result of rippled's `tx` method or a message of
+//! the `transactions` stream, in JSON or binary form, API version 1 or 2.
+
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+use crate::binary;
+use crate::binary::types::encode_currency;
+use crate::error::Result;
+use crate::hash::Hash256;
+use crate::transaction::CurrencyAmount;
+use crate::AccountId;
+
+type Object = Map<String, Value>;
+
+/// The first ledger whose metadata records `DeliveredAmount` whenever a
+/// payment delivers less than its `Amount`. For older payments the
+/// delivered amount is unknown.
+pub const FIRST_DELIVERED_AMOUNT_LEDGER: u32 = 4_594_095;
+
+/// Why a transaction is not credited as a deposit.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[non_exhaustive]
+pub enum Rejection {
+    /// The transaction is not in a validated ledger, so its outcome may
+    /// still change. The only rejection worth retrying.
+    #[error("transaction is not validated")]
+    NotValidated,
+    #[error("{0} is not a payment")]
+    NotAPayment(String),
+    /// The transaction is in the ledger but did not succeed.
+    #[error("transaction failed with {0}")]
+    Failed(String),
+    #[error("payment to {0} instead of the door account")]
+    WrongDestination(AccountId),
+    #[error("payment from the door account itself")]
+    FromDoorAccount,
+    #[error("payment carries no destination tag")]
+    MissingDestinationTag,
+    /// The metadata does not settle what was delivered.
+    #[error("delivered amount is ambiguous: {0}")]
+    AmbiguousDeliveredAmount(String),
+    #[error("nothing was delivered")]
+    NothingDelivered,
+    #[error("{0} is not an accepted currency")]
+    UnacceptedCurrency(CurrencyAmount),
+    #[error("malformed transaction: {0}")]
+    Malformed(String),
+}
+
+impl Rejection {
+    /// Whether the transaction can never become a valid deposit.
+    pub fn is_final(&self) -> bool {
+        !matches!(self, Rejection::NotValidated)
+    }
+}
+
+/// A payment into the door account, as validated by the ledger.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deposit {
+    pub hash: Hash256,
+    pub ledger_index: u32,
+    pub sender: AccountId,
+    pub source_tag: Option<u32>,
+    pub destination_tag: Option<u32>,
+    /// What actually arrived, which for a partial payment is less than the
+    /// transaction's `Amount`.
+    pub delivered: CurrencyAmount,
+}
+
+/// Checks transactions paying a bridge's door account.
+///
+/// By default only XRP is accepted and deposits must carry a destination
+/// tag, which is how a door account tells its depositors apart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DepositVerifier {
+    door: AccountId,
+    accept_xrp: bool,
+    issued: Vec<([u8; 20], AccountId)>,
+    require_destination_tag: bool,
+}
+
+impl DepositVerifier {
+    pub fn new(door: AccountId) -> Self {
+        Self {
+            door,
+            accept_xrp: true,
+            issued: Vec::new(),
+            require_destination_tag: true,
+        }
+    }
+
+    /// Also accepts `currency` issued by `issuer`. Anyone can issue a
+    /// currency called `USD`, so the issuer is what identifies it.
+    pub fn accept_issued(mut self, currency: &str, issuer: AccountId) -> Result<Self> {
+        self.issued.push((encode_currency(currency)?, issuer));
+        Ok(self)
+    }
+
+    /// Whether XRP deposits are accepted.
+    pub fn accept_xrp(mut self, accept: bool) -> Self {
+        self.accept_xrp = accept;
+        self
+    }
+
+    pub fn require_destination_tag(mut self, required: bool) -> Self {
+        self.require_destination_tag = required;
+        self
+    }
+
+    pub fn door(&self) -> AccountId {
+        self.door
+    }
+
+    /// Verifies a `tx` result or `transactions` stream message and returns
+    /// the deposit to credit.
+    pub fn verify(&self, response: &Value) -> Result<Deposit, Rejection> {
+        let response = response
+            .as_object()
+            .ok_or_else(|| malformed("expected a JSON object"))?;
+        if response.get("validated") != Some(&Value::Bool(true)) {
+            return Err(Rejection::NotValidated);
+        }
+        let (tx, blob) = transaction(response)?;
+        let meta = metadata(response)?;
+
+        let transaction_type = tx
+            .get("TransactionType")
+            .and_then(Value::as_str)
+            .ok_or_else(|| malformed("no TransactionType"))?;
+        if transaction_type != "Payment" {
+            return Err(Rejection::NotAPayment(transaction_type.to_owned()));
+        }
+        let result = meta
+            .get("TransactionResult")
+            .and_then(Value::as_str)
+            .ok_or_else(|| malformed("no TransactionResult"))?;
+        if result != "tesSUCCESS" {
+            return Err(Rejection::Failed(result.to_owned()));
+        }
+
+        let destination: AccountId = field(&tx, "Destination")?;
+        if destination != self.door {
+            return Err(Rejection::WrongDestination(destination));
+        }
+        let sender: AccountId = field(&tx, "Account")?;
+        if sender == self.door {
+            return Err(Rejection::FromDoorAccount);
+        }
+        let destination_tag: Option<u32> = optional_field(&tx, "DestinationTag")?;
+        if self.require_destination_tag && destination_tag.is_none() {
+            return Err(Rejection::MissingDestinationTag);
+        }
+
+        let ledger_index = response
+            .get("ledger_index")
+            .and_then(Value::as_u64)
+            .and_then(|index| u32::try_from(index).ok())
+            .ok_or_else(|| malformed("no ledger_index"))?;
+        let delivered = delivered_amount(&tx, &meta, ledger_index)?;
+        if delivered.is_zero() {
+            return Err(Rejection::NothingDelivered);
+        }
+        if !self.accepts(&delivered) {
+            return Err(Rejection::UnacceptedCurrency(delivered));
+        }
+
+        let hash = match response.get("hash").or_else(|| tx.get("hash")) {
+            Some(hash) => serde_json::from_value(hash.clone())
+                .map_err(|err| malformed(format!("hash: {err}")))?,
+            None => match blob {
+                Some(blob) => binary::transaction_id(&blob),
+                None => return Err(malformed("no hash")),
+            },
+        };
+        Ok(Deposit {
+            hash,
+            ledger_index,
+            sender,
+            source_tag: optional_field(&tx, "SourceTag")?,
+            destination_tag,
+            delivered,
+        })
+    }
+
+    fn accepts(&self, amount: &CurrencyAmount) -> bool {
+        match amount {
+            CurrencyAmount::Xrp(_) => self.accept_xrp,
+            CurrencyAmount::Issued(amount) => encode_currency(amount.currency())
+                .map(|currency| self.issued.contains(&(currency, amount.issuer())))
+                .unwrap_or(false),
+        }
+    }
+}
+
+fn malformed(reason: impl Into<String>) -> Rejection {
+    Rejection::Malformed(reason.into())
+}
+
+/// The transaction fields, and the blob when the response is binary.
+fn transaction(response: &Object) -> Result<(Object, Option<Vec<u8>>), Rejection> {
+    // API version 2 nests the transaction in `tx_json`, the stream in
+    // `transaction`; version 1 puts its fields next to the metadata.
+    if let Some(tx) = response
+        .get("tx_json")
+        .or_else(|| response.get("transaction"))
+    {
+        let tx = tx
+            .as_object()
+            .ok_or_else(|| malformed("transaction is not an object"))?;
+        return Ok((tx.clone(), None));
+    }
+    // Binary results carry the blob in `tx_blob`, or in `tx` before
+    // version 2.
+    let blob = response
+        .get("tx_blob")
+        .or_else(|| response.get("tx").filter(|tx| tx.is_string()));
+    if let Some(blob) = blob {
+        let blob = decode_hex(blob)?;
+        let tx = decode_object(&blob)?;
+        return Ok((tx, Some(blob)));
+    }
+    Ok((response.clone(), None))
+}
+
+fn metadata(response: &Object) -> Result<Object, Rejection> {
+    match response.get("meta").or_else(|| response.get("meta_blob")) {
+        Some(Value::Object(meta)) => Ok(meta.clone()),
+        Some(blob @ Value::String(_)) => decode_object(&decode_hex(blob)?),
+        _ => Err(malformed("no metadata")),
+    }
+}
+
+fn decode_hex(value: &Value) -> Result<Vec<u8>, Rejection> {
+    value
+        .as_str()
+        .and_then(|hex| hex::decode(hex).ok())
+        .ok_or_else(|| malformed("invalid hex"))
+}
+
+fn decode_object(blob: &[u8]) -> Result<Object, Rejection> {
+    match binary::decode(blob) {
+        Ok(Value::Object(object)) => Ok(object),
+        Ok(_) => Err(malformed("not an object")),
+        Err(err) => Err(malformed(err.to_string())),
+    }
+}
+
+fn field<T: serde::de::DeserializeOwned>(object: &Object, name: &str) -> Result<T, Rejection> {
+    optional_field(object, name)?.ok_or_else(|| malformed(format!("no {name}")))
+}
+
+fn optional_field<T: serde::de::DeserializeOwned>(
+    object: &Object,
+    name: &str,
+) -> Result<Option<T>, Rejection> {
+    object
+        .get(name)
+        .map(|value| serde_json::from_value(value.clone()))
+        .transpose()
+        .map_err(|err| malformed(format!("{name}: {err}")))
+}
+
+/// What the payment delivered, by rippled's own rule: `DeliveredAmount`
+/// when the metadata has it, otherwise `Amount` from the ledger on where a
+/// shortfall is always recorded, partial payment or not. rippled's JSON
+/// adds the result as `delivered_amount`, or `"unavailable"`.
+fn delivered_amount(
+    tx: &Object,
+    meta: &Object,
+    ledger_index: u32,
+) -> Result<CurrencyAmount, Rejection> {
+    let ambiguous = |reason: &str| Rejection::AmbiguousDeliveredAmount(reason.to_owned());
+    let reported = match meta.get("delivered_amount") {
+        Some(Value::String(s)) if s == "unavailable" => {
+            return Err(ambiguous("rippled reports it unavailable"))
+        }
+        Some(_) => Some(field::<CurrencyAmount>(meta, "delivered_amount")?),
+        None => None,
+    };
+    let recorded: Option<CurrencyAmount> = optional_field(meta, "DeliveredAmount")?;
+    match (reported, recorded) {
+        (Some(reported), Some(recorded)) if reported != recorded => {
+            Err(ambiguous("delivered_amount and DeliveredAmount disagree"))
+        }
+        (Some(amount), _) | (None, Some(amount)) => Ok(amount),
+        (None, None) => {
+            if ledger_index < FIRST_DELIVERED_AMOUNT_LEDGER {
+                return Err(ambiguous("ledger predates DeliveredAmount"));
+            }
+            field(tx, "Amount")
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use thiserror::Error;
+
+/// Errors produced by the XRPL adapter.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum Error {
+    #[error(transparent)]
+    Core(#[from] crossbeam_core::Error),
+    #[error("invalid hex: {0}")]
+    Hex(#[from] hex::FromHexError),
+    #[error("invalid base58: {0}")]
+    Base58(#[from] bs58::decode::Error),
+    #[error("expected {expected} bytes, got {actual}")]
+    InvalidLength { expected: usize, actual: usize },
+    #[error("invalid transaction: {0}")]
+    InvalidTransaction(String),
+    #[error("invalid signature: {0}")]
+    InvalidSignature(String),
+    #[error("invalid seed: {0}")]
+    InvalidSeed(String),
+    #[error("binary codec: {0}")]
+    Codec(String),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("deposit rejected: {0}")]
+    Deposit(#[from] crate::deposit::Rejection),
+    #[error("provider error: {0}")]
+    Provider(String),
+    /// rippled answered with an error, such as `actNotFound`.
+    #[error("rippled error {error}{}", message.as_deref().map(|m| format!(": {m}")).unwrap_or_default())]
+    Rpc {
+        error: String,
+        code: Option<i64>,
+        message: Option<String>,
+    },
+    /// The request never got an answer.
+    #[error("transport error: {0}")]
+    Transport(String),
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -0,0 +1,54 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha512};
+
+use crate::error::{Error, Result};
+
+/// A 256-bit ledger object or transaction hash, displayed as uppercase hex
+/// like rippled does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Hash256(pub [u8; 32]);
+
+impl fmt::Display for Hash256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&hex::encode_upper(self.0))
+    }
+}
+
+impl FromStr for Hash256 {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let bytes = hex::decode(s)?;
+        let actual = bytes.len();
+        bytes
+            .try_into()
+            .map(Self)
+            .map_err(|_| Error::InvalidLength {
+                expected: 32,
+                actual,
+            })
+    }
+}
+
+impl Serialize for Hash256 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Hash256 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+/// The first half of the SHA-512 digest, the hash the XRP Ledger uses
+/// everywhere.
+pub fn sha512_half(data: &[u8]) -> Hash256 {
+    let digest = Sha512::digest(data);
+    Hash256(digest[..32].try_into().expect("64-byte digest"))
+}
@@ -0,0 +1,224 @@
+//! XRPL seeds, key derivation and account ids.
+//!
+//! A [`Seed`] is 16 bytes of entropy tagged with the algorithm of the keys
+//! derived from it: `sEd…` seeds derive Ed25519 keys, the older `s…` family
+//! seeds derive secp256k1 keys. Derivation follows rippled, so a seed
+//! controls the same account here as in any XRPL wallet.
+//!
+//! Public keys travel as 33 bytes: SEC1 compressed points for secp256k1,
+//! and the 32-byte key behind an `0xED` marker for Ed25519.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crossbeam_core::signer::{Ed25519Signer, Secp256k1Signer};
+use crossbeam_core::{PublicKey, Signature, Signer, SigningPayload};
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256};
+
+use crate::error::{Error, Result};
+use crate::hash::sha512_half;
+use crate::AccountId;
+
+const FAMILY_SEED_PREFIX: [u8; 1] = [0x21];
+const ED25519_SEED_PREFIX: [u8; 3] = [0x01, 0xE1, 0x4B];
+
+/// Marks an Ed25519 public key.
+const ED25519_KEY_PREFIX: u8 = 0xED;
+
+/// The signature algorithm of an account key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyType {
+    Secp256k1,
+    Ed25519,
+}
+
+/// The entropy an account key is derived from.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Seed {
+    entropy: [u8; 16],
+    key_type: KeyType,
+}
+
+impl Seed {
+    pub fn new(entropy: [u8; 16], key_type: KeyType) -> Self {
+        Self { entropy, key_type }
+    }
+
+    /// The seed `wallet_propose` derives from a passphrase. Passphrases
+    /// are guessable; use them for test networks only.
+    pub fn from_passphrase(passphrase: &str, key_type: KeyType) -> Self {
+        let hash = sha512_half(passphrase.as_bytes());
+        Self::new(hash.0[..16].try_into().expect("16 bytes"), key_type)
+    }
+
+    pub fn entropy(&self) -> &[u8; 16] {
+        &self.entropy
+    }
+
+    pub fn key_type(&self) -> KeyType {
+        self.key_type
+    }
+
+    /// The master key pair of the account this seed controls.
+    pub fn derive_keypair(&self) -> Result<KeyPair> {
+        match self.key_type {
+            KeyType::Ed25519 => {
+                let secret = sha512_half(&self.entropy);
+                Ok(KeyPair::Ed25519(Ed25519Signer::from_bytes(&secret.0)))
+            }
+            KeyType::Secp256k1 => {
+                let secret = derive_secp256k1(&self.entropy)?;
+                Ok(KeyPair::Secp256k1(Secp256k1Signer::from_bytes(&secret)?))
+            }
+        }
+    }
+}
+
+/// rippled's derivation: a root key from the seed, plus an intermediate
+/// key for account 0 derived from the root public key.
+fn derive_secp256k1(entropy: &[u8; 16]) -> Result<[u8; 32]> {
+    let root = secp256k1_scalar(entropy)?;
+    let root_public = k256::PublicKey::from_secret_scalar(&root)
+        .to_encoded_point(true)
+        .as_bytes()
+        .to_vec();
+    let mut input = root_public;
+    input.extend_from_slice(&0u32.to_be_bytes());
+    let intermediate = secp256k1_scalar(&input)?;
+    // Both are non-zero scalars below the curve order; their sum is zero
+    // with negligible probability.
+    let secret = *root + *intermediate;
+    Ok(secret.to_bytes().into())
+}
+
+/// The first `SHA-512Half(input || i)` that is a valid secret key.
+fn secp256k1_scalar(input: &[u8]) -> Result<k256::NonZeroScalar> {
+    for i in 0u32..=u32::MAX {
+        let mut data = input.to_vec();
+        data.extend_from_slice(&i.to_be_bytes());
+        let candidate = sha512_half(&data);
+        if let Ok(key) = k256::SecretKey::from_bytes(&candidate.0.into()) {
+            return Ok(key.to_nonzero_scalar());
+        }
+    }
+    Err(Error::InvalidSeed("no valid secp256k1 key".to_owned()))
+}
+
+impl fmt::Display for Seed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let prefix: &[u8] = match self.key_type {
+            KeyType::Secp256k1 => &FAMILY_SEED_PREFIX,
+            KeyType::Ed25519 => &ED25519_SEED_PREFIX,
+        };
+        let encoded = bs58::encode([prefix, &self.entropy].concat())
+            .with_alphabet(bs58::Alphabet::RIPPLE)
+            .with_check()
+            .into_string();
+        f.write_str(&encoded)
+    }
+}
+
+/// Seeds are secrets; only the key type is shown.
+impl fmt::Debug for Seed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Seed")
+            .field("key_type", &self.key_type)
+            .finish_non_exhaustive()
+    }
+}
+
+impl FromStr for Seed {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let bytes = bs58::decode(s)
+            .with_alphabet(bs58::Alphabet::RIPPLE)
+            .with_check(None)
+            .into_vec()?;
+        let (key_type, entropy) = if let Some(entropy) = bytes.strip_prefix(&ED25519_SEED_PREFIX) {
+            (KeyType::Ed25519, entropy)
+        } else if let Some(entropy) = bytes.strip_prefix(&FAMILY_SEED_PREFIX) {
+            (KeyType::Secp256k1, entropy)
+        } else {
+            return Err(Error::InvalidSeed("unknown seed prefix".to_owned()));
+        };
+        let entropy = entropy
+            .try_into()
+            .map_err(|_| Error::InvalidSeed(format!("{} bytes of entropy", entropy.len())))?;
+        Ok(Self::new(entropy, key_type))
+    }
+}
+
+/// An in-memory account key of either algorithm.
+#[derive(Debug, Clone)]
+pub enum KeyPair {
+    Secp256k1(Secp256k1Signer),
+    Ed25519(Ed25519Signer),
+}
+
+impl KeyPair {
+    pub fn key_type(&self) -> KeyType {
+        match self {
+            KeyPair::Secp256k1(_) => KeyType::Secp256k1,
+            KeyPair::Ed25519(_) => KeyType::Ed25519,
+        }
+    }
+
+    /// The account whose master key this is.
+    pub fn account_id(&self) -> AccountId {
+        account_id(&self.public_key())
+    }
+}
+
+impl Signer for KeyPair {
+    fn public_key(&self) -> PublicKey {
+        match self {
+            KeyPair::Secp256k1(signer) => signer.public_key(),
+            KeyPair::Ed25519(signer) => signer.public_key(),
+        }
+    }
+
+    fn sign(&self, payload: &SigningPayload) -> crossbeam_core::Result<Signature> {
+        match self {
+            KeyPair::Secp256k1(signer) => signer.sign(payload),
+            KeyPair::Ed25519(signer) => signer.sign(payload),
+        }
+    }
+}
+
+/// The 33-byte XRPL encoding of a public key.
+pub fn encode_public_key(key: &PublicKey) -> [u8; 33] {
+    match key {
+        PublicKey::Secp256k1(bytes) => *bytes,
+        PublicKey::Ed25519(bytes) => {
+            let mut out = [ED25519_KEY_PREFIX; 33];
+            out[1..].copy_from_slice(bytes);
+            out
+        }
+    }
+}
+
+/// Parses the 33-byte XRPL encoding of a public key.
+pub fn decode_public_key(bytes: &[u8]) -> Result<PublicKey> {
+    let bytes: [u8; 33] = bytes.try_into().map_err(|_| Error::InvalidLength {
+        expected: 33,
+        actual: bytes.len(),
+    })?;
+    match bytes[0] {
+        ED25519_KEY_PREFIX => Ok(PublicKey::Ed25519(bytes[1..].try_into().expect("32 bytes"))),
+        0x02 | 0x03 => Ok(PublicKey::Secp256k1(bytes)),
+        prefix => Err(crossbeam_core::Error::InvalidKey(format!(
+            "unknown public key prefix {prefix:#04x}"
+        ))
+        .into()),
+    }
+}
+
+/// The account whose master key is `key`: RIPEMD-160 of SHA-256 of the
+/// encoded key.
+pub fn account_id(key: &PublicKey) -> AccountId {
+    let sha = Sha256::digest(encode_public_key(key));
+    AccountId(Ripemd160::digest(sha).into())
+}
@@ -0,0 +1,23 @@
+//! XRP Ledger adapter for the CrossBeam SDK.
+
+pub mod binary;
+pub mod chain;
+pub mod deposit;
+pub mod error;
+pub mod hash;
+pub mod keys;
+pub mod provider;
+pub mod rpc;
+pub mod signing;
+pub mod transaction;
+
+pub use chain::XrplChain;
+pub use crossbeam_core::address::{XrplAccountId as AccountId, XrplAddress};
+pub use deposit::{Deposit, DepositVerifier};
+pub use error::{Error, Result};
+pub use hash::Hash256;
+pub use keys::{KeyPair, KeyType, Seed};
+pub use provider::{AccountInfo, Provider, ServerState};
+pub use rpc::{Client, StreamTransport, Transport};
+pub use signing::{Multisignature, SignedTransaction};
+pub use transaction::{CurrencyAmount, Transaction};
@@ -0,0 +1,60 @@
+use async_trait::async_trait;
+use crossbeam_core::Confirmation;
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+use crate::hash::Hash256;
+use crate::AccountId;
+
+/// The state of an account root the SDK acts on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct AccountInfo {
+    /// The sequence number of the account's next transaction.
+    pub sequence: u32,
+    /// The `AccountRoot` flags, such as [`AccountInfo::REQUIRE_DEST_TAG`].
+    pub flags: u32,
+    /// The balance in drops.
+    pub balance: u64,
+}
+
+impl AccountInfo {
+    /// `lsfRequireDestTag`: incoming payments must carry a destination tag.
+    pub const REQUIRE_DEST_TAG: u32 = 0x0002_0000;
+
+    pub fn requires_destination_tag(&self) -> bool {
+        self.flags & Self::REQUIRE_DEST_TAG != 0
+    }
+}
+
+/// The server state transactions are filled from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ServerState {
+    /// The index of the latest validated ledger.
+    pub validated_ledger_index: u32,
+    /// The reference transaction cost in drops, before load scaling.
+    pub base_fee: u64,
+    /// What a reference transaction costs to get into the open ledger now,
+    /// in drops.
+    pub open_ledger_fee: u64,
+    /// The network id the server reports; mainnet reports none.
+    pub network_id: Option<u32>,
+}
+
+/// The rippled access [`XrplChain`](crate::XrplChain) needs.
+#[async_trait]
+pub trait Provider: Send + Sync {
+    /// Submits a signed transaction blob.
+    async fn submit(&self, blob: &[u8]) -> Result<Hash256>;
+
+    /// Reports where the transaction `hash` stands. XRPL ledgers are final
+    /// once validated, so only [`Confirmation::Finalized`] is reported for
+    /// included transactions.
+    async fn transaction_confirmation(&self, hash: &Hash256) -> Result<Confirmation>;
+
+    /// The account root of `account` in the current open ledger, or `None`
+    /// if the account does not exist.
+    async fn account_info(&self, account: &AccountId) -> Result<Option<AccountInfo>>;
+
+    /// Fees, the validated ledger and the network id.
+    async fn server_state(&self) -> Result<ServerState>;
+}
@@ -0,0 +1,196 @@
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use super::stream::StreamQueue;
+use super::{StreamTransport, Streams, Transport};
+use crate::error::{Error, Result};
+use crate::AccountId;
+
+type Handler = Box<dyn Fn(&Value) -> Result<Value> + Send + Sync>;
+
+#[derive(Debug, Default)]
+struct Connection {
+    disconnected: bool,
+    reconnects: usize,
+    ledger: bool,
+    transactions: bool,
+    accounts: BTreeSet<AccountId>,
+}
+
+/// An in-process rippled for tests.
+///
+/// Commands are answered by the handler registered for them; any other
+/// command fails as the server would. `subscribe` and `unsubscribe` also
+/// track the connection's subscriptions, and [`publish`](Self::publish)
+/// delivers a stream message only to a connection subscribed to it. Like
+/// rippled, a dropped connection forgets its subscriptions. Every request
+/// is recorded for later assertions.
+#[derive(Default)]
+pub struct MockTransport {
+    handlers: HashMap<String, Handler>,
+    requests: Mutex<Vec<(String, Value)>>,
+    connection: Mutex<Connection>,
+    stream: StreamQueue,
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answers `command` with `handler`, called with the request params.
+    pub fn on(
+        mut self,
+        command: &str,
+        handler: impl Fn(&Value) -> Result<Value> + Send + Sync + 'static,
+    ) -> Self {
+        self.handlers.insert(command.to_owned(), Box::new(handler));
+        self
+    }
+
+    /// Answers `command` with the same result every time.
+    pub fn on_result(self, command: &str, result: Value) -> Self {
+        self.on(command, move |_| Ok(result.clone()))
+    }
+
+    /// The `(command, params)` pairs received so far, in order.
+    pub fn requests(&self) -> Vec<(String, Value)> {
+        self.requests.lock().expect("mock lock poisoned").clone()
+    }
+
+    /// What the current connection is subscribed to.
+    pub fn subscriptions(&self) -> Streams {
+        let connection = self.connection.lock().expect("mock lock poisoned");
+        Streams {
+            ledger: connection.ledger,
+            transactions: connection.transactions,
+            accounts: connection.accounts.iter().copied().collect(),
+        }
+    }
+
+    /// How many times the client reconnected.
+    pub fn reconnects(&self) -> usize {
+        self.connection
+            .lock()
+            .expect("mock lock poisoned")
+            .reconnects
+    }
+
+    /// Sends a stream message if the connection is subscribed to it: a
+    /// `ledgerClosed` to the `ledger` stream, a `transaction` to the
+    /// `transactions` stream or the accounts it is from or to. Returns
+    /// whether it was sent.
+    pub fn publish(&self, message: Value) -> bool {
+        let connection = self.connection.lock().expect("mock lock poisoned");
+        if connection.disconnected {
+            return false;
+        }
+        let subscribed = match message["type"].as_str() {
+            Some("ledgerClosed") => connection.ledger,
+            Some("transaction") => {
+                connection.transactions
+                    || ["Account", "Destination"].iter().any(|field| {
+                        message["tx_json"][field]
+                            .as_str()
+                            .and_then(|account| account.parse().ok())
+                            .is_some_and(|account| connection.accounts.contains(&account))
+                    })
+            }
+            _ => true,
+        };
+        if subscribed {
+            self.stream.push(message);
+        }
+        subscribed
+    }
+
+    /// Drops the connection. Messages already sent can still be read.
+    pub fn disconnect(&self) {
+        let mut connection = self.connection.lock().expect("mock lock poisoned");
+        *connection = Connection {
+            disconnected: true,
+            reconnects: connection.reconnects,
+            ..Connection::default()
+        };
+        self.stream.disconnect();
+    }
+
+    fn update_subscriptions(&self, params: &Value, subscribe: bool) {
+        let mut connection = self.connection.lock().expect("mock lock poisoned");
+        for stream in params["streams"].as_array().into_iter().flatten() {
+            match stream.as_str() {
+                Some("ledger") => connection.ledger = subscribe,
+                Some("transactions") => connection.transactions = subscribe,
+                _ => {}
+            }
+        }
+        let accounts = params["accounts"].as_array().into_iter().flatten();
+        for account in accounts.filter_map(|account| account.as_str()?.parse().ok()) {
+            if subscribe {
+                connection.accounts.insert(account);
+            } else {
+                connection.accounts.remove(&account);
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for MockTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut commands: Vec<_> = self.handlers.keys().collect();
+        commands.sort();
+        f.debug_struct("MockTransport")
+            .field("commands", &commands)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl Transport for MockTransport {
+    async fn request(&self, command: &str, params: Value) -> Result<Value> {
+        self.requests
+            .lock()
+            .expect("mock lock poisoned")
+            .push((command.to_owned(), params.clone()));
+        if self
+            .connection
+            .lock()
+            .expect("mock lock poisoned")
+            .disconnected
+        {
+            return Err(Error::Transport("not connected".to_owned()));
+        }
+        match command {
+            "subscribe" => self.update_subscriptions(&params, true),
+            "unsubscribe" => self.update_subscriptions(&params, false),
+            _ => {}
+        }
+        match self.handlers.get(command) {
+            Some(handler) => handler(&params),
+            None if command == "subscribe" || command == "unsubscribe" => Ok(json!({})),
+            None => Err(Error::Rpc {
+                error: "unknownCmd".to_owned(),
+                code: Some(32),
+                message: Some("Unknown method.".to_owned()),
+            }),
+        }
+    }
+}
+
+#[async_trait]
+impl StreamTransport for MockTransport {
+    async fn next_message(&self) -> Result<Option<Value>> {
+        Ok(self.stream.next().await)
+    }
+
+    async fn reconnect(&self) -> Result<()> {
+        let mut connection = self.connection.lock().expect("mock lock poisoned");
+        connection.disconnected = false;
+        connection.reconnects += 1;
+        self.stream.reconnect();
+        Ok(())
+    }
+}
@@ -0,0 +1,371 @@
+//! rippled WebSocket API access.
+//!
+//! [`Client`] speaks the commands over any [`Transport`], and the
+//! subscriptions over any [`StreamTransport`]. The SDK ships no network
+//! stack: applications implement the transports over the WebSocket client
+//! they already use, with [`wire`] providing the envelopes and
+//! [`StreamQueue`] the buffering of stream messages. Tests use
+//! [`MockTransport`], an in-process server.
+//!
+//! Requests use API version 2, so transactions come back with their fields
+//! under `tx_json`, the form [`DepositVerifier`](crate::DepositVerifier)
+//! reads. Subscriptions do not survive a dropped connection; [`Watcher`]
+//! reconnects and backfills what was missed in between.
+
+mod mock;
+mod stream;
+mod types;
+mod watcher;
+pub mod wire;
+
+use async_trait::async_trait;
+use crossbeam_core::Confirmation;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use crate::error::{Error, Result};
+use crate::hash::Hash256;
+use crate::provider::{AccountInfo, Provider, ServerState};
+use crate::AccountId;
+
+pub use mock::MockTransport;
+pub use stream::StreamQueue;
+pub use types::{
+    BookOffer, Issue, Ledger, LedgerClosed, LedgerIndex, StreamMessage, Streams, SubmitResult,
+    TrustLine,
+};
+pub use watcher::{Event, Watcher};
+
+use types::{index, malformed};
+
+/// The API version requests ask for.
+pub const API_VERSION: u32 = 2;
+/// How many items a page of `account_lines` or `account_tx` holds.
+pub const PAGE_LIMIT: u32 = 400;
+
+/// Carries commands to a rippled server.
+#[async_trait]
+pub trait Transport: Send + Sync {
+    /// Sends one command with its parameters, an object, and returns its
+    /// `result`. An error response is reported as [`Error::Rpc`].
+    async fn request(&self, command: &str, params: Value) -> Result<Value>;
+}
+
+/// A transport over a connection the server pushes stream messages on.
+///
+/// Subscribe and unsubscribe calls go through [`Transport::request`]; the
+/// transport hands every other message to
+/// [`next_message`](Self::next_message), typically through a
+/// [`StreamQueue`].
+#[async_trait]
+pub trait StreamTransport: Transport {
+    /// Waits for the next stream message; `None` once the connection has
+    /// dropped.
+    async fn next_message(&self) -> Result<Option<Value>>;
+
+    /// Opens a new connection after the last one dropped. The server has
+    /// forgotten the subscriptions of the old one.
+    async fn reconnect(&self) -> Result<()>;
+}
+
+#[async_trait]
+impl<T: Transport + ?Sized> Transport for &T {
+    async fn request(&self, command: &str, params: Value) -> Result<Value> {
+        (**self).request(command, params).await
+    }
+}
+
+#[async_trait]
+impl<T: Transport + ?Sized> Transport for std::sync::Arc<T> {
+    async fn request(&self, command: &str, params: Value) -> Result<Value> {
+        (**self).request(command, params).await
+    }
+}
+
+#[async_trait]
+impl<T: StreamTransport + ?Sized> StreamTransport for &T {
+    async fn next_message(&self) -> Result<Option<Value>> {
+        (**self).next_message().await
+    }
+
+    async fn reconnect(&self) -> Result<()> {
+        (**self).reconnect().await
+    }
+}
+
+#[async_trait]
+impl<T: StreamTransport + ?Sized> StreamTransport for std::sync::Arc<T> {
+    async fn next_message(&self) -> Result<Option<Value>> {
+        (**self).next_message().await
+    }
+
+    async fn reconnect(&self) -> Result<()> {
+        (**self).reconnect().await
+    }
+}
+
+/// A rippled WebSocket API client.
+#[derive(Debug, Clone)]
+pub struct Client<T> {
+    transport: T,
+}
+
+impl<T: Transport> Client<T> {
+    pub fn new(transport: T) -> Self {
+        Self { transport }
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Sends `command` with `params`, an object, at [`API_VERSION`] and
+    /// deserializes its result.
+    pub async fn request<R: DeserializeOwned>(&self, command: &str, params: Value) -> Result<R> {
+        let mut params = match params {
+            Value::Null => json!({}),
+            params => params,
+        };
+        params["api_version"] = json!(API_VERSION);
+        let result = self.transport.request(command, params).await?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    /// `submit` of a signed transaction blob. The result is provisional;
+    /// only a validated ledger settles the outcome.
+    pub async fn submit(&self, blob: &[u8]) -> Result<SubmitResult> {
+        self.request("submit", json!({"tx_blob": hex::encode_upper(blob)}))
+            .await
+    }
+
+    /// `tx`; `None` if the server does not know the transaction. The
+    /// result is what [`DepositVerifier`](crate::DepositVerifier) checks.
+    pub async fn tx(&self, hash: &Hash256) -> Result<Option<Value>> {
+        not_found_as_none(
+            self.request("tx", json!({"transaction": hash, "binary": false}))
+                .await,
+            "txnNotFound",
+        )
+    }
+
+    /// `account_info`; `None` if the account does not exist in `ledger`.
+    pub async fn account_info(
+        &self,
+        account: &AccountId,
+        ledger: LedgerIndex,
+    ) -> Result<Option<AccountInfo>> {
+        let result: Option<Value> = not_found_as_none(
+            self.request(
+                "account_info",
+                json!({"account": account, "ledger_index": ledger}),
+            )
+            .await,
+            "actNotFound",
+        )?;
+        let Some(result) = result else {
+            return Ok(None);
+        };
+        let data = &result["account_data"];
+        let field = |name: &str| {
+            data[name]
+                .as_u64()
+                .ok_or_else(|| malformed(format!("account_data without {name}")))
+        };
+        Ok(Some(AccountInfo {
+            sequence: field("Sequence")?
+                .try_into()
+                .map_err(|_| malformed("Sequence out of range"))?,
+            flags: field("Flags")?
+                .try_into()
+                .map_err(|_| malformed("Flags out of range"))?,
+            balance: data["Balance"]
+                .as_str()
+                .and_then(|balance| balance.parse().ok())
+                .ok_or_else(|| malformed("account_data without Balance"))?,
+        }))
+    }
+
+    /// `account_lines`, every page of them.
+    pub async fn account_lines(
+        &self,
+        account: &AccountId,
+        ledger: LedgerIndex,
+    ) -> Result<Vec<TrustLine>> {
+        let mut lines = Vec::new();
+        let mut marker = Value::Null;
+        loop {
+            let mut params =
+                json!({"account": account, "ledger_index": ledger, "limit": PAGE_LIMIT});
+            if !marker.is_null() {
+                params["marker"] = marker;
+            }
+            let mut page: Value = self.request("account_lines", params).await?;
+            let page_lines: Vec<TrustLine> = serde_json::from_value(page["lines"].take())?;
+            lines.extend(page_lines);
+            marker = page["marker"].take();
+            if marker.is_null() {
+                return Ok(lines);
+            }
+        }
+    }
+
+    /// `account_tx` over the validated ledgers `min..=max`, oldest first,
+    /// every page of them. Each transaction is in the form of a `tx`
+    /// result.
+    pub async fn account_tx(&self, account: &AccountId, min: u32, max: u32) -> Result<Vec<Value>> {
+        let mut transactions = Vec::new();
+        let mut marker = Value::Null;
+        loop {
+            let mut params = json!({
+                "account": account,
+                "ledger_index_min": min,
+                "ledger_index_max": max,
+                "forward": true,
+                "limit": PAGE_LIMIT,
+            });
+            if !marker.is_null() {
+                params["marker"] = marker;
+            }
+            let mut page: Value = self.request("account_tx", params).await?;
+            let Value::Array(page_transactions) = page["transactions"].take() else {
+                return Err(malformed("account_tx without transactions"));
+            };
+            transactions.extend(page_transactions);
+            marker = page["marker"].take();
+            if marker.is_null() {
+                return Ok(transactions);
+            }
+        }
+    }
+
+    /// `ledger`, with its transactions expanded if `transactions` is set.
+    pub async fn ledger(&self, ledger: LedgerIndex, transactions: bool) -> Result<Ledger> {
+        let result: Value = self
+            .request(
+                "ledger",
+                json!({
+                    "ledger_index": ledger,
+                    "transactions": transactions,
+                    "expand": transactions,
+                }),
+            )
+            .await?;
+        Ledger::from_result(&result)
+    }
+
+    /// `book_offers`: the offers selling `taker_gets` for `taker_pays`,
+    /// best first.
+    pub async fn book_offers(
+        &self,
+        taker_gets: &Issue,
+        taker_pays: &Issue,
+        limit: u32,
+    ) -> Result<Vec<BookOffer>> {
+        let mut result: Value = self
+            .request(
+                "book_offers",
+                json!({
+                    "taker_gets": taker_gets,
+                    "taker_pays": taker_pays,
+                    "ledger_index": LedgerIndex::Validated,
+                    "limit": limit,
+                }),
+            )
+            .await?;
+        Ok(serde_json::from_value(result["offers"].take())?)
+    }
+}
+
+impl<T: StreamTransport> Client<T> {
+    /// `subscribe`. With the `ledger` stream the result describes the
+    /// latest validated ledger, whose transactions will not be streamed.
+    pub async fn subscribe(&self, streams: &Streams) -> Result<Value> {
+        self.request("subscribe", streams.params()).await
+    }
+
+    /// `unsubscribe`.
+    pub async fn unsubscribe(&self, streams: &Streams) -> Result<()> {
+        self.request::<Value>("unsubscribe", streams.params())
+            .await
+            .map(drop)
+    }
+
+    /// Waits for the next stream message; `None` once the connection has
+    /// dropped.
+    pub async fn next_message(&self) -> Result<Option<StreamMessage>> {
+        self.transport
+            .next_message()
+            .await?
+            .map(StreamMessage::parse)
+            .transpose()
+    }
+}
+
+fn not_found_as_none<R>(result: Result<R>, not_found: &str) -> Result<Option<R>> {
+    match result {
+        Ok(result) => Ok(Some(result)),
+        Err(Error::Rpc { error, .. }) if error == not_found => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+#[async_trait]
+impl<T: Transport> Provider for Client<T> {
+    /// Submits the blob, failing if the server already knows the
+    /// transaction can never succeed.
+    async fn submit(&self, blob: &[u8]) -> Result<Hash256> {
+        let result = Client::submit(self, blob).await?;
+        if !result.may_succeed() {
+            return Err(Error::Provider(format!(
+                "{}: {}",
+                result.engine_result, result.engine_result_message
+            )));
+        }
+        Ok(result.hash)
+    }
+
+    async fn transaction_confirmation(&self, hash: &Hash256) -> Result<Confirmation> {
+        let Some(tx) = self.tx(hash).await? else {
+            return Ok(Confirmation::NotFound);
+        };
+        if tx["validated"] != json!(true) {
+            return Ok(Confirmation::Pending);
+        }
+        let height = index(&tx["ledger_index"])
+            .map(u64::from)
+            .ok_or_else(|| malformed("validated transaction without ledger_index"))?;
+        match tx["meta"]["TransactionResult"].as_str() {
+            Some("tesSUCCESS") => Ok(Confirmation::Finalized { height }),
+            Some(result) => Ok(Confirmation::Failed {
+                height: Some(height),
+                reason: result.to_owned(),
+            }),
+            None => Err(malformed("validated transaction without a result")),
+        }
+    }
+
+    async fn account_info(&self, account: &AccountId) -> Result<Option<AccountInfo>> {
+        Client::account_info(self, account, LedgerIndex::Current).await
+    }
+
+    async fn server_state(&self) -> Result<ServerState> {
+        let fee: Value = self.request("fee", Value::Null).await?;
+        let info: Value = self.request("server_info", Value::Null).await?;
+        let drops = |name: &str| {
+            fee["drops"][name]
+                .as_str()
+                .and_then(|drops| drops.parse().ok())
+                .ok_or_else(|| malformed(format!("fee without {name}")))
+        };
+        let info = &info["info"];
+        Ok(ServerState {
+            validated_ledger_index: index(&info["validated_ledger"]["seq"])
+                .ok_or_else(|| malformed("server_info without a validated ledger"))?,
+            base_fee: drops("base_fee")?,
+            open_ledger_fee: drops("open_ledger_fee")?,
+            network_id: info["network_id"]
+                .as_u64()
+                .and_then(|id| u32::try_from(id).ok()),
+        })
+    }
+}
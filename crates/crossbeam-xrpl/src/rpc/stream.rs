@@ -0,0 +1,72 @@
+use std::collections::VecDeque;
+use std::future::poll_fn;
+use std::sync::Mutex;
+use std::task::{Poll, Waker};
+
+use serde_json::Value;
+
+#[derive(Debug, Default)]
+struct State {
+    queue: VecDeque<Value>,
+    disconnected: bool,
+    waker: Option<Waker>,
+}
+
+/// Buffers stream messages for the reader of a connection.
+///
+/// A websocket transport feeds every stream message it reads to
+/// [`push`](Self::push), reports a dropped connection with
+/// [`disconnect`](Self::disconnect) and a new one with
+/// [`reconnect`](Self::reconnect), and implements
+/// [`StreamTransport::next_message`](super::StreamTransport::next_message)
+/// with [`next`](Self::next).
+#[derive(Debug, Default)]
+pub struct StreamQueue {
+    state: Mutex<State>,
+}
+
+impl StreamQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn update(&self, f: impl FnOnce(&mut State)) {
+        let mut state = self.state.lock().expect("stream lock poisoned");
+        f(&mut state);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+
+    pub fn push(&self, message: Value) {
+        self.update(|state| state.queue.push_back(message));
+    }
+
+    /// Ends the stream once the queued messages are read.
+    pub fn disconnect(&self) {
+        self.update(|state| state.disconnected = true);
+    }
+
+    /// Starts the stream of a new connection. Messages still queued from
+    /// the old one are kept: they were received.
+    pub fn reconnect(&self) {
+        self.update(|state| state.disconnected = false);
+    }
+
+    /// Waits for the next message; `None` once the connection has dropped
+    /// and the queue is drained.
+    pub async fn next(&self) -> Option<Value> {
+        poll_fn(|cx| {
+            let mut state = self.state.lock().expect("stream lock poisoned");
+            if let Some(message) = state.queue.pop_front() {
+                return Poll::Ready(Some(message));
+            }
+            if state.disconnected {
+                return Poll::Ready(None);
+            }
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        })
+        .await
+    }
+}
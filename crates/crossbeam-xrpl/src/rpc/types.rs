@@ -0,0 +1,315 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{json, Value};
+
+use crate::error::{Error, Result};
+use crate::hash::Hash256;
+use crate::transaction::CurrencyAmount;
+use crate::AccountId;
+
+/// The ledger a request reads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LedgerIndex {
+    /// The latest validated ledger, whose contents are final.
+    #[default]
+    Validated,
+    /// The latest closed ledger, not yet validated.
+    Closed,
+    /// The open ledger new transactions go into.
+    Current,
+    Index(u32),
+}
+
+impl Serialize for LedgerIndex {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            LedgerIndex::Validated => serializer.serialize_str("validated"),
+            LedgerIndex::Closed => serializer.serialize_str("closed"),
+            LedgerIndex::Current => serializer.serialize_str("current"),
+            LedgerIndex::Index(index) => serializer.serialize_u32(*index),
+        }
+    }
+}
+
+/// A side of an order book: XRP, or a currency and its issuer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Issue {
+    Xrp,
+    Issued { currency: String, issuer: AccountId },
+}
+
+impl Serialize for Issue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Issue::Xrp => json!({"currency": "XRP"}),
+            Issue::Issued { currency, issuer } => json!({"currency": currency, "issuer": issuer}),
+        }
+        .serialize(serializer)
+    }
+}
+
+/// What `submit` reports: the provisional result of applying the
+/// transaction to the open ledger, which is not final.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubmitResult {
+    pub hash: Hash256,
+    /// `tesSUCCESS`, `terQUEUED`, `tefPAST_SEQ` and so on.
+    pub engine_result: String,
+    pub engine_result_code: i32,
+    pub engine_result_message: String,
+}
+
+impl SubmitResult {
+    /// Whether the transaction can still make it into a validated ledger.
+    /// Malformed (`tem`), failed (`tef`) and locally rejected (`tel`)
+    /// transactions never will; anything else may, including `tec`
+    /// results, which claim the fee.
+    pub fn may_succeed(&self) -> bool {
+        !["tem", "tef", "tel"]
+            .iter()
+            .any(|class| self.engine_result.starts_with(class))
+    }
+}
+
+impl<'de> Deserialize<'de> for SubmitResult {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Raw {
+            engine_result: String,
+            engine_result_code: i32,
+            engine_result_message: String,
+            tx_json: TxJson,
+        }
+        #[derive(Deserialize)]
+        struct TxJson {
+            hash: Hash256,
+        }
+        let raw = Raw::deserialize(deserializer)?;
+        Ok(Self {
+            hash: raw.tx_json.hash,
+            engine_result: raw.engine_result,
+            engine_result_code: raw.engine_result_code,
+            engine_result_message: raw.engine_result_message,
+        })
+    }
+}
+
+/// A trust line of an account, from its own side: a positive balance is
+/// owed to the account by `account`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrustLine {
+    /// The counterparty.
+    pub account: AccountId,
+    pub balance: String,
+    pub currency: String,
+    pub limit: String,
+    pub limit_peer: String,
+    #[serde(default)]
+    pub no_ripple: bool,
+    #[serde(default)]
+    pub no_ripple_peer: bool,
+    #[serde(default)]
+    pub freeze: bool,
+    #[serde(default)]
+    pub freeze_peer: bool,
+    #[serde(default)]
+    pub authorized: bool,
+    #[serde(default)]
+    pub peer_authorized: bool,
+}
+
+/// An offer of an order book, as `book_offers` reports it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct BookOffer {
+    pub account: AccountId,
+    pub sequence: u32,
+    pub taker_gets: CurrencyAmount,
+    pub taker_pays: CurrencyAmount,
+    #[serde(default)]
+    pub flags: u32,
+    /// `TakerPays / TakerGets`, the price of the offer.
+    #[serde(rename = "quality")]
+    pub quality: String,
+    /// How much of `TakerGets` the owner actually holds; reported for the
+    /// first offer of each owner.
+    #[serde(
+        default,
+        rename = "owner_funds",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub owner_funds: Option<String>,
+}
+
+/// A ledger header, and its transactions when requested.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ledger {
+    pub ledger_index: u32,
+    pub ledger_hash: Hash256,
+    pub parent_hash: Hash256,
+    /// Seconds since the Ripple epoch.
+    pub close_time: u32,
+    pub validated: bool,
+    /// Each in the form of a `tx` result, with the transaction under
+    /// `tx_json`, its metadata under `meta`, its `hash` and `ledger_index`.
+    /// rippled does not list them in the order they were applied; that is
+    /// `meta.TransactionIndex`.
+    pub transactions: Vec<Value>,
+}
+
+impl Ledger {
+    /// Reads a `ledger` result. API version 1 reports indexes as strings,
+    /// version 2 as numbers; both are read.
+    pub fn from_result(result: &Value) -> Result<Self> {
+        let header = &result["ledger"];
+        let ledger_index = index(&header["ledger_index"])
+            .or_else(|| index(&result["ledger_index"]))
+            .ok_or_else(|| malformed("ledger without ledger_index"))?;
+        let hash = |field: &str| -> Result<Hash256> {
+            header[field]
+                .as_str()
+                .ok_or_else(|| malformed(format!("ledger without {field}")))?
+                .parse()
+        };
+        let validated = result["validated"].as_bool().unwrap_or(false);
+        let transactions = match &header["transactions"] {
+            Value::Null => Vec::new(),
+            Value::Array(transactions) => transactions
+                .iter()
+                .map(|tx| {
+                    let mut tx = tx.clone();
+                    let Some(fields) = tx.as_object_mut() else {
+                        return Err(malformed("transactions must be expanded"));
+                    };
+                    fields.insert("ledger_index".to_owned(), ledger_index.into());
+                    fields.insert("validated".to_owned(), validated.into());
+                    Ok(tx)
+                })
+                .collect::<Result<_>>()?,
+            _ => return Err(malformed("ledger transactions are not an array")),
+        };
+        Ok(Self {
+            ledger_index,
+            ledger_hash: hash("ledger_hash")?,
+            parent_hash: hash("parent_hash")?,
+            close_time: header["close_time"]
+                .as_u64()
+                .and_then(|time| u32::try_from(time).ok())
+                .ok_or_else(|| malformed("ledger without close_time"))?,
+            validated,
+            transactions,
+        })
+    }
+}
+
+/// A message of the `ledger` stream: a ledger has been validated.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LedgerClosed {
+    pub ledger_index: u32,
+    pub ledger_hash: Hash256,
+    /// Close time in seconds since the Ripple epoch.
+    pub ledger_time: u32,
+    /// The reference transaction cost in drops.
+    pub fee_base: u64,
+    #[serde(default)]
+    pub txn_count: u32,
+    /// The ranges of ledgers the server holds, such as `32570-91017234`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validated_ledgers: Option<String>,
+}
+
+/// A message of a subscribed stream.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum StreamMessage {
+    LedgerClosed(LedgerClosed),
+    /// A transaction of the `transactions` or `accounts` streams, in the
+    /// form of a `tx` result.
+    Transaction(Value),
+    /// Any other message, such as those of the `server` stream.
+    Other(Value),
+}
+
+impl StreamMessage {
+    pub fn parse(message: Value) -> Result<Self> {
+        Ok(match message.get("type").and_then(Value::as_str) {
+            Some("ledgerClosed") => StreamMessage::LedgerClosed(serde_json::from_value(message)?),
+            Some("transaction") => StreamMessage::Transaction(message),
+            _ => StreamMessage::Other(message),
+        })
+    }
+}
+
+/// What to subscribe to.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Streams {
+    /// `ledger`: every validated ledger.
+    pub ledger: bool,
+    /// `transactions`: every validated transaction.
+    pub transactions: bool,
+    /// `accounts`: the validated transactions affecting these accounts.
+    pub accounts: Vec<AccountId>,
+}
+
+impl Streams {
+    pub fn ledger() -> Self {
+        Self {
+            ledger: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn transactions() -> Self {
+        Self {
+            transactions: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn accounts(accounts: impl IntoIterator<Item = AccountId>) -> Self {
+        Self {
+            accounts: accounts.into_iter().collect(),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_ledger(mut self) -> Self {
+        self.ledger = true;
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.ledger && !self.transactions && self.accounts.is_empty()
+    }
+
+    /// The parameters of `subscribe` and `unsubscribe`.
+    pub(super) fn params(&self) -> Value {
+        let mut params = json!({});
+        let mut streams = Vec::new();
+        if self.ledger {
+            streams.push("ledger");
+        }
+        if self.transactions {
+            streams.push("transactions");
+        }
+        if !streams.is_empty() {
+            params["streams"] = json!(streams);
+        }
+        if !self.accounts.is_empty() {
+            params["accounts"] = json!(self.accounts);
+        }
+        params
+    }
+}
+
+/// Ledger indexes come as numbers or, in API version 1, strings.
+pub(super) fn index(value: &Value) -> Option<u32> {
+    match value {
+        Value::Number(n) => n.as_u64().and_then(|n| u32::try_from(n).ok()),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+pub(super) fn malformed(reason: impl Into<String>) -> Error {
+    Error::Provider(reason.into())
+}
@@ -0,0 +1,209 @@
+use std::collections::{HashSet, VecDeque};
+
+use serde_json::Value;
+
+use super::types::{index, malformed};
+use super::{Client, LedgerIndex, StreamMessage, StreamTransport, Streams};
+use crate::error::Result;
+use crate::hash::Hash256;
+
+/// What a [`Watcher`] reports.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    /// A ledger was validated. Its transactions follow, as rippled sends
+    /// them.
+    LedgerClosed {
+        ledger_index: u32,
+        ledger_hash: Hash256,
+        /// Seconds since the Ripple epoch.
+        close_time: u32,
+    },
+    /// A validated transaction, in the form of a `tx` result.
+    Transaction(Value),
+}
+
+/// Follows streams across reconnects without losing or repeating
+/// validated transactions.
+///
+/// The watcher always subscribes to the `ledger` stream to learn how far
+/// it got, and reports [`Event::LedgerClosed`] only if `streams` asks for
+/// it. When the connection drops it reconnects, subscribes again and
+/// backfills the ledgers it missed: ledger by ledger with the `ledger`
+/// command for the `ledger` and `transactions` streams, with `account_tx`
+/// over the whole range for the `accounts` stream. Transactions seen both
+/// in the backfill and on the stream are reported once.
+///
+/// A failed reconnect or backfill is returned by [`next`](Self::next);
+/// calling it again, after a backoff of the caller's choosing, retries.
+#[derive(Debug)]
+pub struct Watcher<'a, T> {
+    client: &'a Client<T>,
+    streams: Streams,
+    connected: bool,
+    started: bool,
+    /// The latest ledger all of whose transactions have been reported.
+    complete: Option<u32>,
+    /// The latest ledger whose `ledgerClosed` has been seen.
+    last_closed: Option<u32>,
+    /// The transactions of ledgers after `complete` already reported.
+    seen: HashSet<Hash256>,
+    backlog: VecDeque<Event>,
+}
+
+impl<'a, T: StreamTransport> Watcher<'a, T> {
+    /// Watches `streams` from the ledgers validated after the first
+    /// subscription on.
+    pub fn new(client: &'a Client<T>, streams: Streams) -> Self {
+        Self {
+            client,
+            streams,
+            connected: false,
+            started: false,
+            complete: None,
+            last_closed: None,
+            seen: HashSet::new(),
+            backlog: VecDeque::new(),
+        }
+    }
+
+    /// Starts after `ledger_index` instead, typically a
+    /// [`checkpoint`](Self::checkpoint) an earlier run persisted: the
+    /// ledgers since are backfilled on the first connection.
+    pub fn resume_after(mut self, ledger_index: u32) -> Self {
+        self.complete = Some(ledger_index);
+        self.last_closed = Some(ledger_index);
+        self
+    }
+
+    /// The latest ledger all of whose events have been reported, which is
+    /// where a restarted watcher should resume.
+    pub fn checkpoint(&self) -> Option<u32> {
+        self.complete
+    }
+
+    /// Waits for the next event, reconnecting as needed.
+    pub async fn next(&mut self) -> Result<Event> {
+        loop {
+            while let Some(event) = self.backlog.pop_front() {
+                if self.accept(&event)? {
+                    return Ok(event);
+                }
+            }
+            if !self.connected {
+                self.connect().await?;
+                continue;
+            }
+            match self.client.next_message().await? {
+                None => self.connected = false,
+                Some(StreamMessage::LedgerClosed(ledger)) => {
+                    self.backlog.push_back(Event::LedgerClosed {
+                        ledger_index: ledger.ledger_index,
+                        ledger_hash: ledger.ledger_hash,
+                        close_time: ledger.ledger_time,
+                    })
+                }
+                Some(StreamMessage::Transaction(tx)) if tx["validated"] == Value::Bool(true) => {
+                    self.backlog.push_back(Event::Transaction(tx))
+                }
+                Some(_) => {}
+            }
+        }
+    }
+
+    async fn connect(&mut self) -> Result<()> {
+        if self.started {
+            self.client.transport().reconnect().await?;
+        }
+        self.started = true;
+        let mut subscription = self.streams.clone();
+        subscription.ledger = true;
+        let result = self.client.subscribe(&subscription).await?;
+        let validated = index(&result["ledger_index"])
+            .ok_or_else(|| malformed("subscribe without ledger_index"))?;
+        match self.complete {
+            None => {
+                self.complete = Some(validated);
+                self.last_closed = Some(validated);
+            }
+            Some(complete) if complete < validated => {
+                let backfill = self.backfill(complete + 1, validated).await?;
+                self.backlog.extend(backfill);
+            }
+            Some(_) => {}
+        }
+        self.connected = true;
+        Ok(())
+    }
+
+    /// The events of the validated ledgers `from..=to`, in ledger order.
+    async fn backfill(&self, from: u32, to: u32) -> Result<Vec<Event>> {
+        let mut events = Vec::new();
+        if self.streams.ledger || self.streams.transactions {
+            for ledger_index in from..=to {
+                let ledger = self
+                    .client
+                    .ledger(LedgerIndex::Index(ledger_index), self.streams.transactions)
+                    .await?;
+                if !ledger.validated {
+                    return Err(malformed(format!("ledger {ledger_index} is not validated")));
+                }
+                events.push(Event::LedgerClosed {
+                    ledger_index: ledger.ledger_index,
+                    ledger_hash: ledger.ledger_hash,
+                    close_time: ledger.close_time,
+                });
+                // `ledger` lists transactions by hash, not in the order
+                // they were applied.
+                let mut transactions = ledger.transactions;
+                transactions.sort_by_key(|tx| tx["meta"]["TransactionIndex"].as_u64());
+                events.extend(transactions.into_iter().map(Event::Transaction));
+            }
+        }
+        if !self.streams.transactions {
+            let mut transactions = Vec::new();
+            for account in &self.streams.accounts {
+                transactions.extend(self.client.account_tx(account, from, to).await?);
+            }
+            // Ledger events stay ahead of their transactions.
+            let key = |event: &Event| match event {
+                Event::LedgerClosed { ledger_index, .. } => (*ledger_index, 0),
+                Event::Transaction(tx) => (
+                    index(&tx["ledger_index"]).unwrap_or(0),
+                    tx["meta"]["TransactionIndex"].as_u64().unwrap_or(0) + 1,
+                ),
+            };
+            events.extend(transactions.into_iter().map(Event::Transaction));
+            events.sort_by_key(key);
+        }
+        Ok(events)
+    }
+
+    /// Updates the progress with `event` and tells whether to report it.
+    fn accept(&mut self, event: &Event) -> Result<bool> {
+        match event {
+            Event::LedgerClosed { ledger_index, .. } => {
+                if self.last_closed >= Some(*ledger_index) {
+                    return Ok(false);
+                }
+                // rippled sends the transactions of a ledger after its
+                // `ledgerClosed`, so only the ledger before is complete.
+                self.last_closed = Some(*ledger_index);
+                self.complete = self.complete.max(ledger_index.checked_sub(1));
+                self.seen.clear();
+                Ok(self.streams.ledger)
+            }
+            Event::Transaction(tx) => {
+                let ledger_index = index(&tx["ledger_index"])
+                    .ok_or_else(|| malformed("transaction without ledger_index"))?;
+                if self.complete >= Some(ledger_index) {
+                    return Ok(false);
+                }
+                let hash: Hash256 = tx["hash"]
+                    .as_str()
+                    .ok_or_else(|| malformed("transaction without hash"))?
+                    .parse()?;
+                Ok(self.seen.insert(hash))
+            }
+        }
+    }
+}
@@ -0,0 +1,108 @@
+//! rippled WebSocket envelopes, for transports that speak the wire format.
+//!
+//! A request is a JSON object with an `id`, the `command` and its
+//! parameters side by side. The answer echoes the `id` with a `status` of
+//! `success` and a `result`, or `error` and the error fields; everything
+//! else the server sends is a stream message, told apart by its `type`.
+
+use serde_json::{Map, Value};
+
+use crate::error::{Error, Result};
+
+/// The request object for `command`, whose `params` must be an object or
+/// `null`.
+pub fn request(id: u64, command: &str, params: Value) -> Result<Value> {
+    let mut request = match params {
+        Value::Object(params) => params,
+        Value::Null => Map::new(),
+        _ => {
+            return Err(Error::Transport(format!(
+                "{command} parameters are not an object"
+            )))
+        }
+    };
+    request.insert("id".to_owned(), id.into());
+    request.insert("command".to_owned(), command.into());
+    Ok(Value::Object(request))
+}
+
+/// Anything a rippled WebSocket endpoint sends.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+    /// The answer to request `id`, already unpacked.
+    Response { id: u64, result: ResponseResult },
+    /// A message of a subscribed stream.
+    Stream(Value),
+}
+
+/// The outcome of a request; [`Error`] is not `Clone`, so failures keep
+/// their fields until [`Message::Response`] is consumed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResponseResult {
+    Success(Value),
+    Failure {
+        error: String,
+        code: Option<i64>,
+        message: Option<String>,
+    },
+}
+
+impl ResponseResult {
+    /// The result, or the failure as [`Error::Rpc`].
+    pub fn into_result(self) -> Result<Value> {
+        match self {
+            ResponseResult::Success(result) => Ok(result),
+            ResponseResult::Failure {
+                error,
+                code,
+                message,
+            } => Err(Error::Rpc {
+                error,
+                code,
+                message,
+            }),
+        }
+    }
+}
+
+impl Message {
+    /// Classifies a message read off the socket.
+    pub fn parse(message: Value) -> Result<Self> {
+        let is_response = message.get("type").and_then(Value::as_str) == Some("response");
+        let Value::Object(mut message) = message else {
+            return Err(Error::Transport("message is not an object".to_owned()));
+        };
+        if !is_response {
+            return Ok(Message::Stream(Value::Object(message)));
+        }
+        let id = message
+            .get("id")
+            .and_then(Value::as_u64)
+            .ok_or_else(|| Error::Transport("response without an id".to_owned()))?;
+        let result = match message.get("status").and_then(Value::as_str) {
+            Some("success") => {
+                ResponseResult::Success(message.remove("result").unwrap_or(Value::Null))
+            }
+            _ => {
+                // Some errors come back inside `result`.
+                let fields = match message.remove("result") {
+                    Some(Value::Object(result)) if result.contains_key("error") => result,
+                    _ => message,
+                };
+                ResponseResult::Failure {
+                    error: fields
+                        .get("error")
+                        .and_then(Value::as_str)
+                        .unwrap_or("unknown")
+                        .to_owned(),
+                    code: fields.get("error_code").and_then(Value::as_i64),
+                    message: fields
+                        .get("error_message")
+                        .and_then(Value::as_str)
+                        .map(str::to_owned),
+                }
+            }
+        };
+        Ok(Message::Response { id, result })
+    }
+}
@@ -0,0 +1,284 @@
+//! Single-signing and multisigning.
+//!
+//! A single signature covers [`binary::encode_for_signing`] of the
+//! transaction with the signer's `SigningPubKey` filled in. A multisigned
+//! transaction has an empty `SigningPubKey` instead and a `Signers` array:
+//! each signer signs [`binary::encode_for_multisigning`] for its own
+//! account, independently of the others, so operators can sign on separate
+//! machines and [`combine`] their [`Multisignature`]s afterwards. rippled
+//! requires the `Signers` entries in ascending order of account id.
+//!
+//! The functions work on the JSON form, so they sign any transaction type
+//! the binary codec knows; [`Transaction`] has typed shortcuts.
+
+use crossbeam_core::{PublicKey, Signature, Signer, SigningPayload};
+use ed25519_dalek::Verifier as _;
+use k256::ecdsa::signature::hazmat::PrehashVerifier as _;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use crate::binary;
+use crate::error::{Error, Result};
+use crate::hash::{sha512_half, Hash256};
+use crate::keys::{decode_public_key, encode_public_key};
+use crate::transaction::Transaction;
+use crate::{AccountId, XrplAddress};
+
+/// rippled accepts at most this many signers.
+pub const MAX_SIGNERS: usize = 32;
+
+/// A signed transaction, ready to submit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedTransaction {
+    json: Value,
+    blob: Vec<u8>,
+    hash: Hash256,
+}
+
+impl SignedTransaction {
+    /// Decodes a signed transaction blob.
+    pub fn from_blob(blob: Vec<u8>) -> Result<Self> {
+        let json = binary::decode(&blob)?;
+        let hash = binary::transaction_id(&blob);
+        Ok(Self { json, blob, hash })
+    }
+
+    fn from_json(json: Value) -> Result<Self> {
+        let blob = binary::encode(&json)?;
+        let hash = binary::transaction_id(&blob);
+        Ok(Self { json, blob, hash })
+    }
+
+    pub fn json(&self) -> &Value {
+        &self.json
+    }
+
+    /// The serialized transaction `submit` takes.
+    pub fn blob(&self) -> &[u8] {
+        &self.blob
+    }
+
+    /// The transaction id.
+    pub fn hash(&self) -> Hash256 {
+        self.hash
+    }
+}
+
+/// One signer's signature for a multisigned transaction, an entry of its
+/// `Signers` array.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Multisignature {
+    /// The account signing, a member of the sender's signer list.
+    pub account: AccountId,
+    /// The master or regular key of `account` that signed.
+    pub signing_pub_key: PublicKey,
+    pub txn_signature: Vec<u8>,
+}
+
+/// Signs `tx` with a single key, which must be the master or regular key
+/// of the sending account.
+pub fn sign(tx: &Value, signer: &dyn Signer) -> Result<SignedTransaction> {
+    let mut json = prepare(tx)?;
+    json.remove("Signers");
+    let public_key = signer.public_key();
+    json.insert(
+        "SigningPubKey".to_owned(),
+        hex::encode_upper(encode_public_key(&public_key)).into(),
+    );
+    let message = binary::encode_for_signing(&Value::Object(json.clone()))?;
+    let signature = sign_message(signer, &message)?;
+    json.insert(
+        "TxnSignature".to_owned(),
+        hex::encode_upper(signature).into(),
+    );
+    SignedTransaction::from_json(Value::Object(json))
+}
+
+/// Signs `tx` as `account`, one of the signers of a multisigned
+/// transaction. The fee must already cover every signer.
+pub fn multisign(tx: &Value, account: &AccountId, signer: &dyn Signer) -> Result<Multisignature> {
+    let json = Value::Object(prepare_multisigned(tx)?);
+    let message = binary::encode_for_multisigning(&json, account)?;
+    Ok(Multisignature {
+        account: *account,
+        signing_pub_key: signer.public_key(),
+        txn_signature: sign_message(signer, &message)?,
+    })
+}
+
+/// Assembles a multisigned transaction. Every signature is checked against
+/// `tx`, so a signer who signed a different transaction is caught here
+/// rather than by the ledger. Whether the signers' weights meet the
+/// quorum depends on the sender's signer list and is left to rippled.
+pub fn combine(tx: &Value, signatures: &[Multisignature]) -> Result<SignedTransaction> {
+    if signatures.is_empty() {
+        return Err(invalid("a multisigned transaction needs signers"));
+    }
+    if signatures.len() > MAX_SIGNERS {
+        return Err(invalid(format!(
+            "{} signers exceed the maximum of {MAX_SIGNERS}",
+            signatures.len()
+        )));
+    }
+    let mut json = prepare_multisigned(tx)?;
+    let sender = json
+        .get("Account")
+        .and_then(Value::as_str)
+        .and_then(|account| account.parse::<XrplAddress>().ok())
+        .map(|address| address.account());
+    let unsigned = Value::Object(json.clone());
+    let mut signatures = signatures.to_vec();
+    signatures.sort_by_key(|signature| signature.account);
+    for (i, signature) in signatures.iter().enumerate() {
+        if i > 0 && signatures[i - 1].account == signature.account {
+            return Err(invalid(format!("{} signed twice", signature.account)));
+        }
+        if sender == Some(signature.account) {
+            return Err(invalid("the sending account cannot sign for itself"));
+        }
+        let message = binary::encode_for_multisigning(&unsigned, &signature.account)?;
+        verify(
+            &signature.signing_pub_key,
+            &message,
+            &signature.txn_signature,
+        )
+        .map_err(|err| Error::InvalidSignature(format!("{}: {err}", signature.account)))?;
+    }
+    json.insert("Signers".to_owned(), serde_json::to_value(&signatures)?);
+    SignedTransaction::from_json(Value::Object(json))
+}
+
+/// Checks an XRPL signature over `message`: DER-encoded, fully canonical
+/// ECDSA over its SHA-512Half for secp256k1, plain Ed25519 otherwise.
+pub fn verify(public_key: &PublicKey, message: &[u8], signature: &[u8]) -> Result<()> {
+    let bad = |reason: &dyn std::fmt::Display| Error::InvalidSignature(reason.to_string());
+    match public_key {
+        PublicKey::Secp256k1(key) => {
+            let key = k256::ecdsa::VerifyingKey::from_sec1_bytes(key).map_err(|err| bad(&err))?;
+            let signature = k256::ecdsa::Signature::from_der(signature).map_err(|err| bad(&err))?;
+            if signature.normalize_s().is_some() {
+                return Err(bad(&"signature is not fully canonical"));
+            }
+            key.verify_prehash(&sha512_half(message).0, &signature)
+                .map_err(|err| bad(&err))
+        }
+        PublicKey::Ed25519(key) => {
+            let key = ed25519_dalek::VerifyingKey::from_bytes(key).map_err(|err| bad(&err))?;
+            let signature =
+                ed25519_dalek::Signature::from_slice(signature).map_err(|err| bad(&err))?;
+            key.verify(message, &signature).map_err(|err| bad(&err))
+        }
+    }
+}
+
+impl Transaction {
+    /// Validates and signs the transaction with a single key; see
+    /// [`sign`].
+    pub fn sign(&self, signer: &dyn Signer) -> Result<SignedTransaction> {
+        sign(&self.signable_json()?, signer)
+    }
+
+    /// Validates the transaction and signs it as one of its multisigners;
+    /// see [`multisign`].
+    pub fn multisign(&self, account: &AccountId, signer: &dyn Signer) -> Result<Multisignature> {
+        multisign(&self.signable_json()?, account, signer)
+    }
+
+    /// See [`combine`].
+    pub fn combine(&self, signatures: &[Multisignature]) -> Result<SignedTransaction> {
+        combine(&self.signable_json()?, signatures)
+    }
+
+    fn signable_json(&self) -> Result<Value> {
+        self.validate()?;
+        self.to_json()
+    }
+}
+
+fn invalid(reason: impl Into<String>) -> Error {
+    Error::InvalidTransaction(reason.into())
+}
+
+/// The transaction without any signature, checked to have what signing
+/// needs.
+fn prepare(tx: &Value) -> Result<Map<String, Value>> {
+    let mut json = tx
+        .as_object()
+        .cloned()
+        .ok_or_else(|| invalid("expected a JSON object"))?;
+    for field in ["Account", "Fee"] {
+        if !json.contains_key(field) {
+            return Err(invalid(format!("{field} must be set before signing")));
+        }
+    }
+    if !json.contains_key("Sequence") && !json.contains_key("TicketSequence") {
+        return Err(invalid("Sequence must be set before signing"));
+    }
+    json.remove("TxnSignature");
+    Ok(json)
+}
+
+fn prepare_multisigned(tx: &Value) -> Result<Map<String, Value>> {
+    let mut json = prepare(tx)?;
+    json.remove("Signers");
+    json.insert("SigningPubKey".to_owned(), "".into());
+    Ok(json)
+}
+
+/// Signs serialized signing data the way XRPL keys do.
+fn sign_message(signer: &dyn Signer, message: &[u8]) -> Result<Vec<u8>> {
+    let payload = match signer.public_key() {
+        PublicKey::Secp256k1(_) => SigningPayload::Digest(sha512_half(message).0),
+        PublicKey::Ed25519(_) => SigningPayload::Message(message.to_vec()),
+    };
+    match signer.sign(&payload)? {
+        Signature::Secp256k1 { r, s, .. } => {
+            let signature = k256::ecdsa::Signature::from_scalars(r, s)
+                .map_err(|err| Error::InvalidSignature(err.to_string()))?;
+            // rippled only accepts low-s signatures.
+            let signature = signature.normalize_s().unwrap_or(signature);
+            Ok(signature.to_der().as_bytes().to_vec())
+        }
+        Signature::Ed25519(bytes) => Ok(bytes.to_vec()),
+    }
+}
+
+/// `Signers` entries appear in JSON wrapped as `{"Signer": {...}}`.
+#[derive(Serialize, Deserialize)]
+struct SignerEntry {
+    #[serde(rename = "Signer")]
+    signer: SignerFields,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SignerFields {
+    account: AccountId,
+    signing_pub_key: String,
+    txn_signature: String,
+}
+
+impl Serialize for Multisignature {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SignerEntry {
+            signer: SignerFields {
+                account: self.account,
+                signing_pub_key: hex::encode_upper(encode_public_key(&self.signing_pub_key)),
+                txn_signature: hex::encode_upper(&self.txn_signature),
+            },
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Multisignature {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let SignerEntry { signer } = SignerEntry::deserialize(deserializer)?;
+        let key = hex::decode(&signer.signing_pub_key).map_err(de::Error::custom)?;
+        Ok(Self {
+            account: signer.account,
+            signing_pub_key: decode_public_key(&key).map_err(de::Error::custom)?,
+            txn_signature: hex::decode(&signer.txn_signature).map_err(de::Error::custom)?,
+        })
+    }
+}
@@ -0,0 +1,142 @@
+use serde::{Deserialize, Serialize};
+
+use super::{check_flags, invalid, is_zero, serde_hex};
+use crate::error::Result;
+
+/// Modifies the settings of an account.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct AccountSet {
+    /// An `asf` flag to enable, such as [`AccountSet::ASF_REQUIRE_DEST`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub set_flag: Option<u32>,
+    /// An `asf` flag to disable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clear_flag: Option<u32>,
+    /// The domain that owns the account, in ASCII. Empty clears it.
+    #[serde(default, with = "serde_hex", skip_serializing_if = "Option::is_none")]
+    pub domain: Option<Vec<u8>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email_hash: Option<String>,
+    /// The fee on transfers of the account's currencies, in billionths
+    /// above one: `1_002_000_000` charges 0.2%. 0 clears it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transfer_rate: Option<u32>,
+    /// Significant digits of offer exchange rates, 3 to 15. 0 clears it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tick_size: Option<u8>,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub flags: u32,
+}
+
+impl AccountSet {
+    /// `asfRequireDest`: incoming payments need a destination tag.
+    pub const ASF_REQUIRE_DEST: u32 = 1;
+    /// `asfRequireAuth`: trust lines need the issuer's authorization.
+    pub const ASF_REQUIRE_AUTH: u32 = 2;
+    /// `asfDisallowXRP`: ask senders not to send XRP.
+    pub const ASF_DISALLOW_XRP: u32 = 3;
+    /// `asfDisableMaster`.
+    pub const ASF_DISABLE_MASTER: u32 = 4;
+    /// `asfAccountTxnID`.
+    pub const ASF_ACCOUNT_TXN_ID: u32 = 5;
+    /// `asfNoFreeze`: permanently give up freezing trust lines.
+    pub const ASF_NO_FREEZE: u32 = 6;
+    /// `asfGlobalFreeze`.
+    pub const ASF_GLOBAL_FREEZE: u32 = 7;
+    /// `asfDefaultRipple`.
+    pub const ASF_DEFAULT_RIPPLE: u32 = 8;
+    /// `asfDepositAuth`: only preauthorized senders may pay the account.
+    pub const ASF_DEPOSIT_AUTH: u32 = 9;
+
+    /// `tfRequireDestTag`.
+    pub const REQUIRE_DEST_TAG: u32 = 0x0001_0000;
+    /// `tfOptionalDestTag`.
+    pub const OPTIONAL_DEST_TAG: u32 = 0x0002_0000;
+    /// `tfRequireAuth`.
+    pub const REQUIRE_AUTH: u32 = 0x0004_0000;
+    /// `tfOptionalAuth`.
+    pub const OPTIONAL_AUTH: u32 = 0x0008_0000;
+    /// `tfDisallowXRP`.
+    pub const DISALLOW_XRP: u32 = 0x0010_0000;
+    /// `tfAllowXRP`.
+    pub const ALLOW_XRP: u32 = 0x0020_0000;
+
+    const FLAGS: u32 = Self::REQUIRE_DEST_TAG
+        | Self::OPTIONAL_DEST_TAG
+        | Self::REQUIRE_AUTH
+        | Self::OPTIONAL_AUTH
+        | Self::DISALLOW_XRP
+        | Self::ALLOW_XRP;
+
+    const MIN_TRANSFER_RATE: u32 = 1_000_000_000;
+    const MAX_TRANSFER_RATE: u32 = 2_000_000_000;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_flag(mut self, flag: u32) -> Self {
+        self.set_flag = Some(flag);
+        self
+    }
+
+    pub fn clear_flag(mut self, flag: u32) -> Self {
+        self.clear_flag = Some(flag);
+        self
+    }
+
+    pub fn domain(mut self, domain: &str) -> Self {
+        self.domain = Some(domain.as_bytes().to_vec());
+        self
+    }
+
+    pub fn transfer_rate(mut self, rate: u32) -> Self {
+        self.transfer_rate = Some(rate);
+        self
+    }
+
+    pub fn tick_size(mut self, tick_size: u8) -> Self {
+        self.tick_size = Some(tick_size);
+        self
+    }
+
+    pub(super) fn validate(&self) -> Result<()> {
+        check_flags("AccountSet", self.flags, Self::FLAGS)?;
+        if self.set_flag.is_some() && self.set_flag == self.clear_flag {
+            return Err(invalid("SetFlag and ClearFlag cannot be the same flag"));
+        }
+        let pairs = [
+            (Self::REQUIRE_DEST_TAG, Self::OPTIONAL_DEST_TAG),
+            (Self::REQUIRE_AUTH, Self::OPTIONAL_AUTH),
+            (Self::DISALLOW_XRP, Self::ALLOW_XRP),
+        ];
+        if pairs
+            .iter()
+            .any(|&(set, clear)| self.flags & set != 0 && self.flags & clear != 0)
+        {
+            return Err(invalid("a flag cannot be both set and cleared"));
+        }
+        if let Some(rate) = self.transfer_rate {
+            if rate != 0 && !(Self::MIN_TRANSFER_RATE..=Self::MAX_TRANSFER_RATE).contains(&rate) {
+                return Err(invalid(format!("invalid TransferRate {rate}")));
+            }
+        }
+        if let Some(tick_size) = self.tick_size {
+            if tick_size != 0 && !(3..=15).contains(&tick_size) {
+                return Err(invalid(format!("invalid TickSize {tick_size}")));
+            }
+        }
+        if let Some(domain) = &self.domain {
+            if domain.len() > 256 {
+                return Err(invalid("Domain is longer than 256 bytes"));
+            }
+        }
+        if let Some(hash) = &self.email_hash {
+            if hash.len() != 32 || !hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return Err(invalid("EmailHash must be 32 hex digits"));
+            }
+        }
+        Ok(())
+    }
+}
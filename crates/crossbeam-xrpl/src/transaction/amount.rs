@@ -0,0 +1,261 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use crossbeam_core::{Amount, Asset, AssetId, Network, Rounding};
+use serde::ser::SerializeMap;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::binary::amount::{canonical_issued_value, cmp_issued_values, MAX_DROPS};
+use crate::binary::types::encode_currency;
+use crate::error::{Error, Result};
+use crate::AccountId;
+
+/// An amount of XRP or of an issued currency, as transactions carry it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CurrencyAmount {
+    /// XRP in drops.
+    Xrp(u64),
+    Issued(IssuedAmount),
+}
+
+/// An amount of a currency issued by `issuer`. The value is kept in the
+/// canonical form rippled reports, so amounts compare equal when they
+/// serialize equal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssuedAmount {
+    currency: String,
+    issuer: AccountId,
+    value: String,
+}
+
+impl IssuedAmount {
+    /// Checks the currency code and the value, which must be a
+    /// non-negative decimal representable with 16 significant digits.
+    pub fn new(
+        currency: impl Into<String>,
+        issuer: AccountId,
+        value: impl AsRef<str>,
+    ) -> Result<Self> {
+        let currency = currency.into();
+        if encode_currency(&currency)? == [0; 20] {
+            return Err(Error::InvalidTransaction(
+                "XRP cannot be an issued currency".to_owned(),
+            ));
+        }
+        let value = canonical_issued_value(value.as_ref())?;
+        if value.starts_with('-') {
+            return Err(Error::InvalidTransaction(format!(
+                "amount {value} is negative"
+            )));
+        }
+        Ok(Self {
+            currency,
+            issuer,
+            value,
+        })
+    }
+
+    pub fn currency(&self) -> &str {
+        &self.currency
+    }
+
+    pub fn issuer(&self) -> AccountId {
+        self.issuer
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.value == "0"
+    }
+}
+
+impl CurrencyAmount {
+    /// An amount of XRP in drops, at most 100 billion XRP.
+    pub fn xrp(drops: u64) -> Result<Self> {
+        if drops > MAX_DROPS {
+            return Err(Error::InvalidTransaction(format!(
+                "{drops} drops exceed the XRP supply"
+            )));
+        }
+        Ok(CurrencyAmount::Xrp(drops))
+    }
+
+    /// See [`IssuedAmount::new`].
+    pub fn issued(
+        currency: impl Into<String>,
+        issuer: AccountId,
+        value: impl AsRef<str>,
+    ) -> Result<Self> {
+        IssuedAmount::new(currency, issuer, value).map(CurrencyAmount::Issued)
+    }
+
+    pub fn is_xrp(&self) -> bool {
+        matches!(self, CurrencyAmount::Xrp(_))
+    }
+
+    pub fn is_zero(&self) -> bool {
+        match self {
+            CurrencyAmount::Xrp(drops) => *drops == 0,
+            CurrencyAmount::Issued(amount) => amount.is_zero(),
+        }
+    }
+
+    /// Whether both amounts are XRP, or the same currency from the same
+    /// issuer.
+    pub fn same_issue(&self, other: &Self) -> bool {
+        match (self, other) {
+            (CurrencyAmount::Xrp(_), CurrencyAmount::Xrp(_)) => true,
+            (CurrencyAmount::Issued(a), CurrencyAmount::Issued(b)) => {
+                a.currency == b.currency && a.issuer == b.issuer
+            }
+            _ => false,
+        }
+    }
+
+    /// Orders amounts of the same issue; `None` across issues.
+    pub(crate) fn cmp_value(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (CurrencyAmount::Xrp(a), CurrencyAmount::Xrp(b)) => Some(a.cmp(b)),
+            (CurrencyAmount::Issued(a), CurrencyAmount::Issued(b)) if self.same_issue(other) => {
+                // Both values were canonicalized on construction.
+                cmp_issued_values(&a.value, &b.value).ok()
+            }
+            _ => None,
+        }
+    }
+
+    /// The drops of an XRP amount.
+    pub fn drops(&self) -> Option<u64> {
+        match *self {
+            CurrencyAmount::Xrp(drops) => Some(drops),
+            CurrencyAmount::Issued(_) => None,
+        }
+    }
+}
+
+impl From<IssuedAmount> for CurrencyAmount {
+    fn from(amount: IssuedAmount) -> Self {
+        CurrencyAmount::Issued(amount)
+    }
+}
+
+/// Converts XRP and XRPL issued-currency amounts exactly; anything that
+/// would need rounding is refused.
+impl TryFrom<&Amount> for CurrencyAmount {
+    type Error = Error;
+
+    fn try_from(amount: &Amount) -> Result<Self> {
+        let asset = amount.asset();
+        if asset.network() != Network::XrpLedger {
+            return Err(Error::InvalidTransaction(format!(
+                "{} is not an XRP Ledger asset",
+                asset.symbol()
+            )));
+        }
+        match asset.id() {
+            AssetId::Native => {
+                // Native amounts may be accounted in other decimals.
+                let xrp = Asset::native(Network::XrpLedger).expect("XRP is native");
+                let drops = amount.rescale(&xrp, Rounding::Exact)?.to_u64()?;
+                CurrencyAmount::xrp(drops)
+            }
+            AssetId::Issued { currency, issuer } => {
+                let (mantissa, exponent) = amount.to_mantissa_exponent(Rounding::Exact)?;
+                CurrencyAmount::issued(currency, *issuer, format!("{mantissa}e{exponent}"))
+            }
+            _ => Err(Error::InvalidTransaction(format!(
+                "{} is not XRP or an issued currency",
+                asset.symbol()
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for CurrencyAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CurrencyAmount::Xrp(drops) => write!(f, "{drops} drops"),
+            CurrencyAmount::Issued(amount) => {
+                write!(f, "{} {}/{}", amount.value, amount.currency, amount.issuer)
+            }
+        }
+    }
+}
+
+impl Serialize for IssuedAmount {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(3))?;
+        map.serialize_entry("currency", &self.currency)?;
+        map.serialize_entry("issuer", &self.issuer)?;
+        map.serialize_entry("value", &self.value)?;
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for IssuedAmount {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Raw {
+            currency: String,
+            issuer: AccountId,
+            value: String,
+        }
+        let raw = Raw::deserialize(deserializer)?;
+        IssuedAmount::new(raw.currency, raw.issuer, raw.value).map_err(de::Error::custom)
+    }
+}
+
+/// XRP serializes as a string of drops, issued currencies as an object.
+impl Serialize for CurrencyAmount {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            CurrencyAmount::Xrp(drops) => serializer.collect_str(drops),
+            CurrencyAmount::Issued(amount) => amount.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for CurrencyAmount {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Drops(String),
+            Issued(IssuedAmount),
+        }
+        match Raw::deserialize(deserializer)? {
+            Raw::Drops(drops) => drops
+                .parse()
+                .map_err(|_| de::Error::custom(format!("invalid drops {drops}")))
+                .and_then(|drops| CurrencyAmount::xrp(drops).map_err(de::Error::custom)),
+            Raw::Issued(amount) => Ok(CurrencyAmount::Issued(amount)),
+        }
+    }
+}
+
+/// Serializes fees and other drop counts as the strings rippled expects.
+pub(crate) mod drops {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(drops: &Option<u64>, serializer: S) -> Result<S::Ok, S::Error> {
+        match drops {
+            Some(drops) => serializer.collect_str(drops),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<u64>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|drops| {
+                drops
+                    .parse()
+                    .map_err(|_| de::Error::custom(format!("invalid drops {drops}")))
+            })
+            .transpose()
+    }
+}
@@ -0,0 +1,119 @@
+use super::{invalid, Transaction, TransactionKind};
+use crate::error::Result;
+use crate::provider::Provider;
+
+/// Network ids up to this one belong to networks that predate `NetworkID`,
+/// which rejects the field.
+const LEGACY_NETWORK_ID_MAX: u32 = 1024;
+
+/// Fee units of an `EscrowFinish` with a fulfillment, before adding one
+/// per 16 bytes of fulfillment.
+const FULFILLMENT_BASE_UNITS: u64 = 33;
+
+/// How [`Transaction::autofill`] fills in the transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AutofillOptions {
+    /// Ledgers after the latest validated one the transaction stays valid
+    /// for.
+    pub last_ledger_offset: u32,
+    /// The most autofill may set as fee, in drops. A busier network fails
+    /// instead of paying more.
+    pub max_fee: u64,
+    /// The number of multisigners, each of whom adds a base fee.
+    pub signer_count: u32,
+}
+
+impl Default for AutofillOptions {
+    fn default() -> Self {
+        Self {
+            last_ledger_offset: 20,
+            max_fee: 2_000_000,
+            signer_count: 0,
+        }
+    }
+}
+
+impl AutofillOptions {
+    pub fn last_ledger_offset(mut self, offset: u32) -> Self {
+        self.last_ledger_offset = offset;
+        self
+    }
+
+    pub fn max_fee(mut self, drops: u64) -> Self {
+        self.max_fee = drops;
+        self
+    }
+
+    pub fn signer_count(mut self, count: u32) -> Self {
+        self.signer_count = count;
+        self
+    }
+}
+
+impl Transaction {
+    /// Fills in `Sequence`, `Fee`, `LastLedgerSequence` and, on networks
+    /// that need it, `NetworkID`. Fields already set are left alone.
+    ///
+    /// Payments and escrows to an account that requires a destination tag
+    /// fail here when they carry none, rather than being rejected by the
+    /// ledger.
+    pub async fn autofill<P: Provider + ?Sized>(
+        &mut self,
+        provider: &P,
+        options: &AutofillOptions,
+    ) -> Result<()> {
+        self.validate()?;
+        if let Some((destination, None)) = self.kind.destination() {
+            let info = provider.account_info(&destination).await?;
+            if info.is_some_and(|info| info.requires_destination_tag()) {
+                return Err(invalid(format!("{destination} requires a destination tag")));
+            }
+        }
+        if self.sequence.is_none() {
+            let info = provider
+                .account_info(&self.account)
+                .await?
+                .ok_or_else(|| invalid(format!("account {} does not exist", self.account)))?;
+            self.sequence = Some(info.sequence);
+        }
+        if self.fee.is_some() && self.last_ledger_sequence.is_some() && self.network_id.is_some() {
+            return Ok(());
+        }
+        let state = provider.server_state().await?;
+        if self.network_id.is_none() {
+            self.network_id = state.network_id.filter(|&id| id > LEGACY_NETWORK_ID_MAX);
+        }
+        if self.last_ledger_sequence.is_none() {
+            let last = state
+                .validated_ledger_index
+                .checked_add(options.last_ledger_offset)
+                .ok_or_else(|| invalid("LastLedgerSequence overflows"))?;
+            self.last_ledger_sequence = Some(last);
+        }
+        if self.fee.is_none() {
+            let fee = state
+                .open_ledger_fee
+                .max(state.base_fee)
+                .saturating_mul(self.fee_units() + u64::from(options.signer_count));
+            if fee > options.max_fee {
+                return Err(invalid(format!(
+                    "fee of {fee} drops exceeds the maximum of {} drops",
+                    options.max_fee
+                )));
+            }
+            self.fee = Some(fee);
+        }
+        Ok(())
+    }
+
+    /// The cost of the transaction in reference transactions.
+    fn fee_units(&self) -> u64 {
+        match &self.kind {
+            TransactionKind::EscrowFinish(tx) => match &tx.fulfillment {
+                Some(fulfillment) => FULFILLMENT_BASE_UNITS + fulfillment.len() as u64 / 16,
+                None => 1,
+            },
+            _ => 1,
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use serde::{Deserialize, Serialize};
+
+use super::{check_flags, invalid, is_zero, serde_hex, CurrencyAmount};
+use crate::error::Result;
+use crate::{AccountId, XrplAddress};
+
+/// Locks XRP until a time passes, a crypto-condition is fulfilled, or both.
+///
+/// Times are seconds since the Ripple epoch; see
+/// [`ripple_time`](super::ripple_time).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct EscrowCreate {
+    /// The XRP to lock.
+    pub amount: CurrencyAmount,
+    pub destination: AccountId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destination_tag: Option<u32>,
+    /// The escrow can be finished from this time on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finish_after: Option<u32>,
+    /// The escrow can be cancelled from this time on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cancel_after: Option<u32>,
+    /// A PREIMAGE-SHA-256 crypto-condition, DER encoded.
+    #[serde(default, with = "serde_hex", skip_serializing_if = "Option::is_none")]
+    pub condition: Option<Vec<u8>>,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub flags: u32,
+}
+
+impl EscrowCreate {
+    /// An escrow of `drops` to `destination`, taking the destination tag
+    /// from an X-address. Set a finish time, or a condition and a cancel
+    /// time, before submitting.
+    pub fn new(destination: &XrplAddress, drops: u64) -> Self {
+        Self {
+            amount: CurrencyAmount::Xrp(drops),
+            destination: destination.account(),
+            destination_tag: destination.tag(),
+            finish_after: None,
+            cancel_after: None,
+            condition: None,
+            flags: 0,
+        }
+    }
+
+    pub fn finish_after(mut self, time: u32) -> Self {
+        self.finish_after = Some(time);
+        self
+    }
+
+    pub fn cancel_after(mut self, time: u32) -> Self {
+        self.cancel_after = Some(time);
+        self
+    }
+
+    pub fn condition(mut self, condition: Vec<u8>) -> Self {
+        self.condition = Some(condition);
+        self
+    }
+
+    pub(super) fn validate(&self) -> Result<()> {
+        check_flags("EscrowCreate", self.flags, 0)?;
+        if !self.amount.is_xrp() || self.amount.is_zero() {
+            return Err(invalid("an escrow must lock a positive amount of XRP"));
+        }
+        if self.finish_after.is_none() && self.condition.is_none() {
+            return Err(invalid("an escrow needs FinishAfter or a Condition"));
+        }
+        if self.finish_after.is_none() && self.cancel_after.is_none() {
+            return Err(invalid("an escrow needs FinishAfter or CancelAfter"));
+        }
+        if let (Some(finish), Some(cancel)) = (self.finish_after, self.cancel_after) {
+            if cancel <= finish {
+                return Err(invalid("CancelAfter must be later than FinishAfter"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Delivers escrowed XRP to its destination.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct EscrowFinish {
+    /// The account that created the escrow.
+    pub owner: AccountId,
+    /// The sequence of the `EscrowCreate` transaction.
+    pub offer_sequence: u32,
+    #[serde(default, with = "serde_hex", skip_serializing_if = "Option::is_none")]
+    pub condition: Option<Vec<u8>>,
+    #[serde(default, with = "serde_hex", skip_serializing_if = "Option::is_none")]
+    pub fulfillment: Option<Vec<u8>>,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub flags: u32,
+}
+
+impl EscrowFinish {
+    pub fn new(owner: AccountId, offer_sequence: u32) -> Self {
+        Self {
+            owner,
+            offer_sequence,
+            condition: None,
+            fulfillment: None,
+            flags: 0,
+        }
+    }
+
+    /// Supplies the fulfillment of the escrow's condition.
+    pub fn fulfill(mut self, condition: Vec<u8>, fulfillment: Vec<u8>) -> Self {
+        self.condition = Some(condition);
+        self.fulfillment = Some(fulfillment);
+        self
+    }
+
+    pub(super) fn validate(&self) -> Result<()> {
+        check_flags("EscrowFinish", self.flags, 0)?;
+        if self.condition.is_some() != self.fulfillment.is_some() {
+            return Err(invalid("Condition and Fulfillment must be given together"));
+        }
+        Ok(())
+    }
+}
+
+/// Returns escrowed XRP to its owner once the escrow has expired.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct EscrowCancel {
+    pub owner: AccountId,
+    pub offer_sequence: u32,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub flags: u32,
+}
+
+impl EscrowCancel {
+    pub fn new(owner: AccountId, offer_sequence: u32) -> Self {
+        Self {
+            owner,
+            offer_sequence,
+            flags: 0,
+        }
+    }
+
+    pub(super) fn validate(&self) -> Result<()> {
+        check_flags("EscrowCancel", self.flags, 0)
+    }
+}
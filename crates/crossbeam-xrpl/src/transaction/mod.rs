@@ -0,0 +1,324 @@
+//! Typed XRPL transactions.
+//!
+//! A [`Transaction`] holds the fields common to every transaction and a
+//! [`TransactionKind`] with the fields of its type. Both serialize to the
+//! JSON form rippled speaks, and [`Transaction::encode`] produces the
+//! canonical binary form through [`binary`](crate::binary).
+//!
+//! Builders only describe what a transaction does; the account state it
+//! depends on (`Sequence`, `Fee`, `LastLedgerSequence` and, on sidechains,
+//! `NetworkID`) is filled from a [`Provider`](crate::Provider) by
+//! [`Transaction::autofill`]. Every transaction is checked before it is
+//! encoded, so combinations rippled would reject, or accept with surprising
+//! results such as a partial payment without `DeliverMin`, never leave the
+//! SDK.
+
+mod account_set;
+mod amount;
+mod autofill;
+mod escrow;
+mod offer_create;
+mod payment;
+mod serde_hex;
+mod trust_set;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::binary;
+use crate::error::{Error, Result};
+use crate::AccountId;
+
+pub use account_set::AccountSet;
+pub use amount::{CurrencyAmount, IssuedAmount};
+pub use autofill::AutofillOptions;
+pub use escrow::{EscrowCancel, EscrowCreate, EscrowFinish};
+pub use offer_create::OfferCreate;
+pub use payment::Payment;
+pub use trust_set::TrustSet;
+
+/// `tfFullyCanonicalSig`, valid on every transaction type and implied
+/// since the `RequireFullyCanonicalSig` amendment.
+pub const FULLY_CANONICAL_SIG: u32 = 0x8000_0000;
+
+/// The Ripple epoch, 2000-01-01T00:00:00Z, in seconds since the Unix
+/// epoch. Ledger times count seconds from it.
+pub const RIPPLE_EPOCH: u64 = 946_684_800;
+
+/// Converts a Unix timestamp to ledger time.
+pub fn ripple_time(unix_seconds: u64) -> Result<u32> {
+    unix_seconds
+        .checked_sub(RIPPLE_EPOCH)
+        .and_then(|seconds| u32::try_from(seconds).ok())
+        .ok_or_else(|| {
+            invalid(format!(
+                "{unix_seconds} is outside the range of ledger time"
+            ))
+        })
+}
+
+fn invalid(reason: impl Into<String>) -> Error {
+    Error::InvalidTransaction(reason.into())
+}
+
+fn is_zero(flags: &u32) -> bool {
+    *flags == 0
+}
+
+fn check_flags(transaction_type: &str, flags: u32, allowed: u32) -> Result<()> {
+    let unknown = flags & !(allowed | FULLY_CANONICAL_SIG);
+    if unknown != 0 {
+        return Err(invalid(format!(
+            "flags {unknown:#010x} are not valid for {transaction_type}"
+        )));
+    }
+    Ok(())
+}
+
+/// The type-specific part of a transaction, tagged by `TransactionType`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "TransactionType")]
+#[non_exhaustive]
+pub enum TransactionKind {
+    Payment(Payment),
+    TrustSet(TrustSet),
+    EscrowCreate(EscrowCreate),
+    EscrowFinish(EscrowFinish),
+    EscrowCancel(EscrowCancel),
+    AccountSet(AccountSet),
+    OfferCreate(OfferCreate),
+}
+
+macro_rules! impl_from_kind {
+    ($($kind:ident),*) => {
+        $(
+            impl From<$kind> for TransactionKind {
+                fn from(kind: $kind) -> Self {
+                    TransactionKind::$kind(kind)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_kind!(
+    Payment,
+    TrustSet,
+    EscrowCreate,
+    EscrowFinish,
+    EscrowCancel,
+    AccountSet,
+    OfferCreate
+);
+
+impl TransactionKind {
+    /// The `TransactionType` name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TransactionKind::Payment(_) => "Payment",
+            TransactionKind::TrustSet(_) => "TrustSet",
+            TransactionKind::EscrowCreate(_) => "EscrowCreate",
+            TransactionKind::EscrowFinish(_) => "EscrowFinish",
+            TransactionKind::EscrowCancel(_) => "EscrowCancel",
+            TransactionKind::AccountSet(_) => "AccountSet",
+            TransactionKind::OfferCreate(_) => "OfferCreate",
+        }
+    }
+
+    pub fn flags(&self) -> u32 {
+        match self {
+            TransactionKind::Payment(tx) => tx.flags,
+            TransactionKind::TrustSet(tx) => tx.flags,
+            TransactionKind::EscrowCreate(tx) => tx.flags,
+            TransactionKind::EscrowFinish(tx) => tx.flags,
+            TransactionKind::EscrowCancel(tx) => tx.flags,
+            TransactionKind::AccountSet(tx) => tx.flags,
+            TransactionKind::OfferCreate(tx) => tx.flags,
+        }
+    }
+
+    /// The account receiving funds and the tag it was given, for the
+    /// kinds that have one.
+    pub fn destination(&self) -> Option<(AccountId, Option<u32>)> {
+        match self {
+            TransactionKind::Payment(tx) => Some((tx.destination, tx.destination_tag)),
+            TransactionKind::EscrowCreate(tx) => Some((tx.destination, tx.destination_tag)),
+            _ => None,
+        }
+    }
+
+    fn validate(&self, account: &AccountId) -> Result<()> {
+        match self {
+            TransactionKind::Payment(tx) => tx.validate(account),
+            TransactionKind::TrustSet(tx) => tx.validate(account),
+            TransactionKind::EscrowCreate(tx) => tx.validate(),
+            TransactionKind::EscrowFinish(tx) => tx.validate(),
+            TransactionKind::EscrowCancel(tx) => tx.validate(),
+            TransactionKind::AccountSet(tx) => tx.validate(),
+            TransactionKind::OfferCreate(tx) => tx.validate(),
+        }
+    }
+}
+
+/// Arbitrary data attached to a transaction. Each part is a blob; by
+/// convention the type and format are UTF-8 such as `text/plain`.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(from = "MemoEntry", into = "MemoEntry")]
+pub struct Memo {
+    pub memo_type: Option<Vec<u8>>,
+    pub memo_data: Option<Vec<u8>>,
+    pub memo_format: Option<Vec<u8>>,
+}
+
+impl Memo {
+    /// A `text/plain` memo.
+    pub fn text(data: &str) -> Self {
+        Self {
+            memo_type: None,
+            memo_data: Some(data.as_bytes().to_vec()),
+            memo_format: Some(b"text/plain".to_vec()),
+        }
+    }
+}
+
+/// Memos appear in JSON wrapped as `{"Memo": {...}}`.
+#[derive(Serialize, Deserialize)]
+struct MemoEntry {
+    #[serde(rename = "Memo")]
+    memo: MemoFields,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct MemoFields {
+    #[serde(default, with = "serde_hex", skip_serializing_if = "Option::is_none")]
+    memo_type: Option<Vec<u8>>,
+    #[serde(default, with = "serde_hex", skip_serializing_if = "Option::is_none")]
+    memo_data: Option<Vec<u8>>,
+    #[serde(default, with = "serde_hex", skip_serializing_if = "Option::is_none")]
+    memo_format: Option<Vec<u8>>,
+}
+
+impl From<MemoEntry> for Memo {
+    fn from(entry: MemoEntry) -> Self {
+        let MemoFields {
+            memo_type,
+            memo_data,
+            memo_format,
+        } = entry.memo;
+        Self {
+            memo_type,
+            memo_data,
+            memo_format,
+        }
+    }
+}
+
+impl From<Memo> for MemoEntry {
+    fn from(memo: Memo) -> Self {
+        MemoEntry {
+            memo: MemoFields {
+                memo_type: memo.memo_type,
+                memo_data: memo.memo_data,
+                memo_format: memo.memo_format,
+            },
+        }
+    }
+}
+
+/// An unsigned XRPL transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Transaction {
+    /// The sending account.
+    pub account: AccountId,
+    #[serde(flatten)]
+    pub kind: TransactionKind,
+    /// The fee in drops.
+    #[serde(
+        default,
+        with = "amount::drops",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub fee: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<u32>,
+    /// The last ledger the transaction can be included in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_ledger_sequence: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_tag: Option<u32>,
+    /// Required on networks with an id above 1024, and rejected on the
+    /// others.
+    #[serde(default, rename = "NetworkID", skip_serializing_if = "Option::is_none")]
+    pub network_id: Option<u32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub memos: Vec<Memo>,
+}
+
+impl Transaction {
+    pub fn new(account: AccountId, kind: impl Into<TransactionKind>) -> Self {
+        Self {
+            account,
+            kind: kind.into(),
+            fee: None,
+            sequence: None,
+            last_ledger_sequence: None,
+            source_tag: None,
+            network_id: None,
+            memos: Vec::new(),
+        }
+    }
+
+    pub fn source_tag(mut self, tag: u32) -> Self {
+        self.source_tag = Some(tag);
+        self
+    }
+
+    pub fn memo(mut self, memo: Memo) -> Self {
+        self.memos.push(memo);
+        self
+    }
+
+    pub fn transaction_type(&self) -> &'static str {
+        self.kind.name()
+    }
+
+    pub fn flags(&self) -> u32 {
+        self.kind.flags()
+    }
+
+    /// Checks the transaction against the rules rippled applies before
+    /// looking at the ledger.
+    pub fn validate(&self) -> Result<()> {
+        self.kind.validate(&self.account)?;
+        if self.memos.iter().any(|memo| *memo == Memo::default()) {
+            return Err(invalid("a memo must have a type, data or format"));
+        }
+        Ok(())
+    }
+
+    /// Whether every field autofill provides is present.
+    pub fn is_complete(&self) -> bool {
+        self.fee.is_some() && self.sequence.is_some() && self.last_ledger_sequence.is_some()
+    }
+
+    pub fn to_json(&self) -> Result<Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+
+    pub fn from_json(json: &Value) -> Result<Self> {
+        Ok(Self::deserialize(json)?)
+    }
+
+    /// Validates and serializes the transaction.
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        self.validate()?;
+        binary::encode(&self.to_json()?)
+    }
+
+    /// Deserializes a transaction of one of the supported types.
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        Self::from_json(&binary::decode(bytes)?)
+    }
+}
@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+use super::{check_flags, invalid, is_zero, CurrencyAmount};
+use crate::error::Result;
+
+/// Places an offer on the decentralized exchange.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct OfferCreate {
+    /// What the offer creator gives up.
+    pub taker_gets: CurrencyAmount,
+    /// What the offer creator receives.
+    pub taker_pays: CurrencyAmount,
+    /// Seconds since the Ripple epoch after which the offer is inactive.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiration: Option<u32>,
+    /// The sequence of an offer to cancel first.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offer_sequence: Option<u32>,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub flags: u32,
+}
+
+impl OfferCreate {
+    /// `tfPassive`: do not consume offers that match exactly.
+    pub const PASSIVE: u32 = 0x0001_0000;
+    /// `tfImmediateOrCancel`: never rest on the books.
+    pub const IMMEDIATE_OR_CANCEL: u32 = 0x0002_0000;
+    /// `tfFillOrKill`: execute in full or not at all.
+    pub const FILL_OR_KILL: u32 = 0x0004_0000;
+    /// `tfSell`: exchange the entire `TakerGets`, even for more than
+    /// `TakerPays`.
+    pub const SELL: u32 = 0x0008_0000;
+
+    const FLAGS: u32 = Self::PASSIVE | Self::IMMEDIATE_OR_CANCEL | Self::FILL_OR_KILL | Self::SELL;
+
+    pub fn new(taker_gets: CurrencyAmount, taker_pays: CurrencyAmount) -> Self {
+        Self {
+            taker_gets,
+            taker_pays,
+            expiration: None,
+            offer_sequence: None,
+            flags: 0,
+        }
+    }
+
+    pub(super) fn validate(&self) -> Result<()> {
+        check_flags("OfferCreate", self.flags, Self::FLAGS)?;
+        if self.flags & Self::IMMEDIATE_OR_CANCEL != 0 && self.flags & Self::FILL_OR_KILL != 0 {
+            return Err(invalid(
+                "ImmediateOrCancel and FillOrKill are mutually exclusive",
+            ));
+        }
+        if self.taker_gets.is_zero() || self.taker_pays.is_zero() {
+            return Err(invalid("offer amounts must be positive"));
+        }
+        if self.taker_gets.is_xrp() && self.taker_pays.is_xrp() {
+            return Err(invalid("an offer cannot exchange XRP for XRP"));
+        }
+        if self.taker_gets.same_issue(&self.taker_pays) {
+            return Err(invalid("an offer cannot exchange a currency for itself"));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,133 @@
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use super::{check_flags, invalid, is_zero, CurrencyAmount};
+use crate::error::Result;
+use crate::hash::Hash256;
+use crate::{AccountId, XrplAddress};
+
+/// Sends XRP or an issued currency, possibly through paths and order books.
+///
+/// A partial payment may deliver far less than [`Payment::amount`], which
+/// makes it the classic way to fake deposits. The flag is therefore only
+/// set through [`Payment::partial`], which also fixes the least the payment
+/// may deliver.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Payment {
+    pub destination: AccountId,
+    pub amount: CurrencyAmount,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destination_tag: Option<u32>,
+    /// The most the sender is willing to spend, in the source currency.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub send_max: Option<CurrencyAmount>,
+    /// The least a partial payment must deliver.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deliver_min: Option<CurrencyAmount>,
+    #[serde(default, rename = "InvoiceID", skip_serializing_if = "Option::is_none")]
+    pub invoice_id: Option<Hash256>,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub flags: u32,
+}
+
+impl Payment {
+    /// `tfNoRippleDirect`: do not use the default path.
+    pub const NO_RIPPLE_DIRECT: u32 = 0x0001_0000;
+    /// `tfPartialPayment`: deliver what is possible, down to `DeliverMin`.
+    pub const PARTIAL_PAYMENT: u32 = 0x0002_0000;
+    /// `tfLimitQuality`: only take paths at least as good as
+    /// `Amount / SendMax`.
+    pub const LIMIT_QUALITY: u32 = 0x0004_0000;
+
+    const FLAGS: u32 = Self::NO_RIPPLE_DIRECT | Self::PARTIAL_PAYMENT | Self::LIMIT_QUALITY;
+
+    pub fn new(destination: AccountId, amount: CurrencyAmount) -> Self {
+        Self {
+            destination,
+            amount,
+            destination_tag: None,
+            send_max: None,
+            deliver_min: None,
+            invoice_id: None,
+            flags: 0,
+        }
+    }
+
+    /// A payment to `destination`, taking the destination tag from an
+    /// X-address.
+    pub fn to(destination: &XrplAddress, amount: CurrencyAmount) -> Self {
+        Self {
+            destination_tag: destination.tag(),
+            ..Self::new(destination.account(), amount)
+        }
+    }
+
+    pub fn destination_tag(mut self, tag: u32) -> Self {
+        self.destination_tag = Some(tag);
+        self
+    }
+
+    pub fn send_max(mut self, send_max: CurrencyAmount) -> Self {
+        self.send_max = Some(send_max);
+        self
+    }
+
+    pub fn invoice_id(mut self, invoice_id: Hash256) -> Self {
+        self.invoice_id = Some(invoice_id);
+        self
+    }
+
+    /// Makes this a partial payment that must deliver at least
+    /// `deliver_min`.
+    pub fn partial(mut self, deliver_min: CurrencyAmount) -> Self {
+        self.flags |= Self::PARTIAL_PAYMENT;
+        self.deliver_min = Some(deliver_min);
+        self
+    }
+
+    pub fn is_partial(&self) -> bool {
+        self.flags & Self::PARTIAL_PAYMENT != 0
+    }
+
+    pub(super) fn validate(&self, account: &AccountId) -> Result<()> {
+        check_flags("Payment", self.flags, Self::FLAGS)?;
+        if self.amount.is_zero() {
+            return Err(invalid("a payment must deliver a positive amount"));
+        }
+        match (&self.deliver_min, self.is_partial()) {
+            (None, true) => {
+                return Err(invalid("a partial payment must set DeliverMin"));
+            }
+            (Some(_), false) => {
+                return Err(invalid("DeliverMin is only valid for partial payments"));
+            }
+            (Some(min), true) => {
+                if min.is_zero() || !min.same_issue(&self.amount) {
+                    return Err(invalid(
+                        "DeliverMin must be a positive amount of the delivered currency",
+                    ));
+                }
+                if min.cmp_value(&self.amount) == Some(Ordering::Greater) {
+                    return Err(invalid("DeliverMin cannot exceed Amount"));
+                }
+            }
+            (None, false) => {}
+        }
+        let direct_xrp =
+            self.amount.is_xrp() && self.send_max.as_ref().map_or(true, CurrencyAmount::is_xrp);
+        if direct_xrp {
+            if self.send_max.is_some() {
+                return Err(invalid("XRP-to-XRP payments cannot set SendMax"));
+            }
+            if self.is_partial() {
+                return Err(invalid("XRP-to-XRP payments cannot be partial"));
+            }
+            if self.destination == *account {
+                return Err(invalid("an XRP payment cannot go to its sender"));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+use super::{check_flags, invalid, is_zero, IssuedAmount};
+use crate::error::Result;
+use crate::AccountId;
+
+/// Creates or modifies a trust line to the issuer of
+/// [`TrustSet::limit_amount`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct TrustSet {
+    /// The issuer and currency of the line and the most the account is
+    /// willing to hold.
+    pub limit_amount: IssuedAmount,
+    /// Incoming balances are valued at this ratio per billion.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality_in: Option<u32>,
+    /// Outgoing balances are valued at this ratio per billion.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality_out: Option<u32>,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub flags: u32,
+}
+
+impl TrustSet {
+    /// `tfSetfAuth`: authorize the other party to hold the issuer's
+    /// currency.
+    pub const SET_AUTH: u32 = 0x0001_0000;
+    /// `tfSetNoRipple`.
+    pub const SET_NO_RIPPLE: u32 = 0x0002_0000;
+    /// `tfClearNoRipple`.
+    pub const CLEAR_NO_RIPPLE: u32 = 0x0004_0000;
+    /// `tfSetFreeze`.
+    pub const SET_FREEZE: u32 = 0x0010_0000;
+    /// `tfClearFreeze`.
+    pub const CLEAR_FREEZE: u32 = 0x0020_0000;
+
+    const FLAGS: u32 = Self::SET_AUTH
+        | Self::SET_NO_RIPPLE
+        | Self::CLEAR_NO_RIPPLE
+        | Self::SET_FREEZE
+        | Self::CLEAR_FREEZE;
+
+    pub fn new(limit_amount: IssuedAmount) -> Self {
+        Self {
+            limit_amount,
+            quality_in: None,
+            quality_out: None,
+            flags: 0,
+        }
+    }
+
+    pub(super) fn validate(&self, account: &AccountId) -> Result<()> {
+        check_flags("TrustSet", self.flags, Self::FLAGS)?;
+        if self.limit_amount.issuer() == *account {
+            return Err(invalid("an account cannot trust itself"));
+        }
+        let both = |set, clear| self.flags & set != 0 && self.flags & clear != 0;
+        if both(Self::SET_NO_RIPPLE, Self::CLEAR_NO_RIPPLE)
+            || both(Self::SET_FREEZE, Self::CLEAR_FREEZE)
+        {
+            return Err(invalid("a flag cannot be both set and cleared"));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,321 @@
+//! The binary codec against the fixtures of the reference implementations
+//! (`codec-fixtures.json` and `data-driven-tests.json` from xrpl.js).
+
+use crossbeam_xrpl::binary::{self, Definitions};
+use crossbeam_xrpl::{AccountId, Error};
+use serde_json::{json, Value};
+
+const CODEC_FIXTURES: &str = include_str!("fixtures/binary/codec-fixtures.json");
+const DATA_DRIVEN: &str = include_str!("fixtures/binary/data-driven-tests.json");
+
+fn fixtures(text: &str) -> Value {
+    serde_json::from_str(text).unwrap()
+}
+
+fn encode_hex(json: &Value) -> String {
+    hex::encode_upper(binary::encode(json).unwrap())
+}
+
+#[test]
+fn ledger_objects_and_transactions_encode_to_their_fixtures() {
+    let fixtures = fixtures(CODEC_FIXTURES);
+    for kind in ["accountState", "transactions"] {
+        for fixture in fixtures[kind].as_array().unwrap() {
+            let expected = fixture["binary"].as_str().unwrap();
+            assert_eq!(
+                encode_hex(&fixture["json"]),
+                expected,
+                "{}",
+                fixture["json"]
+            );
+            let decoded = binary::decode(&hex::decode(expected).unwrap()).unwrap();
+            assert_eq!(encode_hex(&decoded), expected, "{decoded}");
+        }
+    }
+}
+
+#[test]
+fn whole_objects_encode_to_their_fixtures() {
+    let fixtures = fixtures(DATA_DRIVEN);
+    for object in fixtures["whole_objects"].as_array().unwrap() {
+        assert_eq!(
+            encode_hex(&object["tx_json"]),
+            object["blob_with_no_signing"],
+            "{}",
+            object["tx_json"]
+        );
+    }
+}
+
+#[test]
+fn field_ids() {
+    let fixtures = fixtures(DATA_DRIVEN);
+    let defs = Definitions::xrpl();
+    let mut checked = 0;
+    for test in fixtures["fields_tests"].as_array().unwrap() {
+        let Some(field) = defs.field(test["name"].as_str().unwrap()) else {
+            continue;
+        };
+        assert_eq!(hex::encode_upper(field.header()), test["expected_hex"]);
+        assert_eq!(
+            defs.field_by_id(field.type_code, field.nth).unwrap().name,
+            field.name
+        );
+        checked += 1;
+    }
+    assert!(checked > 100);
+}
+
+#[test]
+fn amount_values() {
+    let fixtures = fixtures(DATA_DRIVEN);
+    for test in fixtures["values_tests"].as_array().unwrap() {
+        let amount = &test["test_json"];
+        let encoded = binary::encode(&json!({ "Amount": amount }));
+        match test["expected_hex"].as_str() {
+            Some(expected) => {
+                let encoded = encoded.unwrap();
+                // Strip the field id.
+                assert_eq!(hex::encode_upper(&encoded[1..]), expected, "{amount}");
+                let decoded = binary::decode(&encoded).unwrap();
+                assert_eq!(binary::encode(&decoded).unwrap(), encoded, "{decoded}");
+            }
+            None => assert!(
+                matches!(encoded, Err(Error::Codec(_))),
+                "{amount} should not encode"
+            ),
+        }
+    }
+}
+
+#[test]
+fn issued_currency_values_decode_canonically() {
+    for (value, canonical) in [
+        ("1.0", "1"),
+        ("-0.0", "0"),
+        ("0.0000001", "1e-7"),
+        ("0.000001", "0.000001"),
+        ("1234567890123456e5", "123456789012345600000"),
+        ("1500000000000000000000", "1.5e+21"),
+        ("11111000.00000001", "11111000.00000001"),
+    ] {
+        let tx = json!({
+            "LimitAmount": {
+                "currency": "USD",
+                "issuer": "rrrrrrrrrrrrrrrrrrrrBZbvji",
+                "value": value,
+            }
+        });
+        let decoded = binary::decode(&binary::encode(&tx).unwrap()).unwrap();
+        assert_eq!(decoded["LimitAmount"]["value"], canonical, "{value}");
+    }
+}
+
+#[test]
+fn numbers_round_trip() {
+    for (value, hex, canonical) in [
+        ("0", "000000000000000080000000", "0"),
+        ("1", "0DE0B6B3A7640000FFFFFFEE", "1"),
+        ("-1.5", "EB2EEDF284EA0000FFFFFFEE", "-1.5"),
+        ("1e20", "0DE0B6B3A764000000000002", "1e20"),
+        (
+            "9223372036854775807",
+            "7FFFFFFFFFFFFFFF00000000",
+            "9223372036854775807",
+        ),
+        (
+            "9223372036854775808",
+            "0CCCCCCCCCCCCCCD00000001",
+            "9223372036854775810",
+        ),
+    ] {
+        let encoded = binary::encode(&json!({ "AssetsMaximum": value })).unwrap();
+        assert_eq!(hex::encode_upper(&encoded[1..]), hex, "{value}");
+        assert_eq!(
+            binary::decode(&encoded).unwrap()["AssetsMaximum"],
+            canonical
+        );
+    }
+}
+
+fn payment() -> Value {
+    json!({
+        "Account": "r9LqNeG6qHxjeUocjvVki2XR35weJ9mZgQ",
+        "Amount": "1000",
+        "Destination": "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh",
+        "Fee": "10",
+        "Flags": 2147483648u32,
+        "Sequence": 1,
+        "TransactionType": "Payment",
+        "TxnSignature": "30440220718D264EF05CAED7C781FF6DE298DCAC68D002562C9BF3A07C1E721B420C0DAB02203A5A4779EF4D2CCC7BC3EF886676D803A9981B928D3B8ACA483B80ECA3CD7B9B",
+        "SigningPubKey": "ED5F5AC8B98974A3CA843326D9B88CEBD0560177B973EE0B149F782CFAA06DC66A"
+    })
+}
+
+const PAYMENT_SIGNING_FIELDS: &str = concat!(
+    "120000",
+    "2280000000",
+    "2400000001",
+    "6140000000000003E8",
+    "68400000000000000A",
+    "7321ED5F5AC8B98974A3CA843326D9B88CEBD0560177B973EE0B149F782CFAA06DC66A",
+    "81145B812C9D57731E27A2DA8B1830195F88EF32A3B6",
+    "8314B5F762798A53D543A014CAF8B297CFF8F2F937E8",
+);
+
+#[test]
+fn signing_data_leaves_out_signatures() {
+    let signing = binary::encode_for_signing(&payment()).unwrap();
+    assert_eq!(
+        hex::encode_upper(signing),
+        format!("53545800{PAYMENT_SIGNING_FIELDS}")
+    );
+}
+
+#[test]
+fn multisigning_data_ends_with_the_signer() {
+    let mut tx = payment();
+    tx["SigningPubKey"] = "".into();
+    let signer: AccountId = "rJZdUusLDtY9NEsGea7ijqhVrXv98rYBYN".parse().unwrap();
+    let signing = binary::encode_for_multisigning(&tx, &signer).unwrap();
+    let fields = PAYMENT_SIGNING_FIELDS.replace(
+        "7321ED5F5AC8B98974A3CA843326D9B88CEBD0560177B973EE0B149F782CFAA06DC66A",
+        "7300",
+    );
+    assert_eq!(
+        hex::encode_upper(signing),
+        format!("534D5400{fields}C0A5ABEF242802EFED4B041E8F2D4A8CC86AE3D1")
+    );
+
+    // The signing key of a multisigned transaction is empty.
+    assert!(binary::encode_for_multisigning(&payment(), &signer).is_err());
+}
+
+#[test]
+fn transaction_ids() {
+    let offer = json!({
+        "Account": "rLyttXLh7Ttca9CMUaD3exVoXY2fn2zwj3",
+        "Fee": "10",
+        "Flags": 0,
+        "LastLedgerSequence": 16409087,
+        "Sequence": 16409064,
+        "SigningPubKey": "ED93BFA583E83331E9DC498DE4558CE4861ACFAB9385EBBC43BC56A0D9845A1DF2",
+        "TakerGets": "13100000",
+        "TakerPays": {
+            "currency": "USD",
+            "issuer": "rLyttXLh7Ttca9CMUaD3exVoXY2fn2zwj3",
+            "value": "10"
+        },
+        "TransactionType": "OfferCreate",
+        "TxnSignature": "71135999783658A0CB4EBCF02E59ACD94C4D06D5BF909E05E6B97588155482BBA598535AD4728ACA1F90C4DE73FFC741B0A6AB87141BDA8BCC2F2DF9CD8C3703"
+    });
+    let account_set = json!({
+        "Account": "rEbY5Tr5B6AjyjuVRhajpnvCWLGkYk5z6",
+        "Domain": "6578616d706c652e636f6d",
+        "Fee": "10",
+        "Flags": 0,
+        "LastLedgerSequence": 596447,
+        "Sequence": 596427,
+        "SigningPubKey": "EDAF73A0E6745EA9C17A2F4EB7043134A055213116CFF6F7888BBFF557B002874F",
+        "TransactionType": "AccountSet",
+        "TxnSignature": "8666A7E6AF0D6A4B4F19F25D315FA1C31D132FB2E974686C415D5499D43710384FF851C75CCC4E57972DE5C5354289F574B2F604B6AF15E2DADA6BB9F1330A07"
+    });
+    for (tx, id) in [
+        (
+            offer,
+            "66F3D6158CAB6E53405F8C264DB39F07D8D0454433A63DDFB98218ED1BC99B60",
+        ),
+        (
+            account_set,
+            "5B765D6C6058CF54F5DBF6230A7F51E23295004FCC043660A77D73AA8537737B",
+        ),
+    ] {
+        let blob = binary::encode(&tx).unwrap();
+        assert_eq!(binary::transaction_id(&blob).to_string(), id);
+    }
+}
+
+#[test]
+fn x_addresses_carry_tags() {
+    let classic = json!({
+        "TransactionType": "Payment",
+        "Account": "r9cZA1mLK5R5Am25ArfXFmqgNwjZgnfk59",
+        "SourceTag": 12345,
+        "Destination": "rGWrZyQqhTp9Xu7G5Pkayo7bXjH4k4QYpf",
+        "Amount": "1",
+    });
+    let account = "r9cZA1mLK5R5Am25ArfXFmqgNwjZgnfk59"
+        .parse::<AccountId>()
+        .unwrap();
+    let x_address = crossbeam_xrpl::XrplAddress::from(account).to_x_address(Some(12345), false);
+    let tagged = json!({
+        "TransactionType": "Payment",
+        "Account": x_address.to_string(),
+        "Destination": "rGWrZyQqhTp9Xu7G5Pkayo7bXjH4k4QYpf",
+        "Amount": "1",
+    });
+    assert_eq!(
+        binary::encode(&tagged).unwrap(),
+        binary::encode(&classic).unwrap()
+    );
+
+    // A tag given twice is ambiguous.
+    let mut both = tagged.clone();
+    both["SourceTag"] = 12345.into();
+    assert!(binary::encode(&both).is_err());
+
+    // Only Account and Destination take tags.
+    let issuer = json!({ "Owner": x_address.to_string() });
+    assert!(binary::encode(&issuer).is_err());
+}
+
+#[test]
+fn unknown_fields_are_rejected_and_annotations_skipped() {
+    let mut tx = payment();
+    tx["hash"] = "00".into();
+    tx["ledger_index"] = 5.into();
+    assert_eq!(
+        binary::encode(&tx).unwrap(),
+        binary::encode(&payment()).unwrap()
+    );
+
+    tx["Amont"] = "1".into();
+    assert!(matches!(binary::encode(&tx), Err(Error::Codec(_))));
+}
+
+#[test]
+fn malformed_input_does_not_decode() {
+    let blob = binary::encode(&payment()).unwrap();
+    for len in 1..blob.len() {
+        // Truncations may end on a field boundary, but never parse to the
+        // same transaction.
+        if let Ok(decoded) = binary::decode(&blob[..len]) {
+            assert_ne!(binary::encode(&decoded).unwrap(), blob);
+        }
+    }
+    // An unknown field id.
+    assert!(binary::decode(&[0x20, 0xFF, 0, 0, 0, 0]).is_err());
+    // An object end marker outside any object.
+    assert!(binary::decode(&[0xE1]).is_err());
+    // A nested object that never ends.
+    assert!(binary::decode(&[0xE0, 0x07]).is_err());
+}
+
+#[test]
+fn vl_lengths() {
+    for (len, prefix) in [
+        (0, "00"),
+        (192, "C0"),
+        (193, "C100"),
+        (12_480, "F0FF"),
+        (12_481, "F10000"),
+        (918_744, "FED417"),
+    ] {
+        let tx = json!({ "Domain": "AB".repeat(len) });
+        let encoded = binary::encode(&tx).unwrap();
+        assert_eq!(hex::encode_upper(&encoded[1..1 + prefix.len() / 2]), prefix);
+        assert_eq!(binary::decode(&encoded).unwrap(), tx);
+    }
+    let too_long = json!({ "Domain": "AB".repeat(918_745) });
+    assert!(binary::encode(&too_long).is_err());
+}
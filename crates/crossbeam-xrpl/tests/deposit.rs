@@ -0,0 +1,247 @@
+//! Deposit verification against `tx` responses shaped like rippled's.
+
+use crossbeam_xrpl::deposit::{Rejection, FIRST_DELIVERED_AMOUNT_LEDGER};
+use crossbeam_xrpl::{binary, AccountId, CurrencyAmount, DepositVerifier, Error, Hash256};
+use serde_json::{json, Value};
+
+const DOOR: &str = "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh";
+const SENDER: &str = "rU6K7V3Po4snVhBBaU29sesqs2qTQJWDw1";
+const ISSUER: &str = "rLUEXYuLiQptky37CqLcm9USQpPiz5rkpD";
+const HASH: &str = "C53ECF838647FA5A4C780377025FEC7999AB4182590510CA461444B207AB74A9";
+
+fn account(address: &str) -> AccountId {
+    address.parse().unwrap()
+}
+
+fn verifier() -> DepositVerifier {
+    DepositVerifier::new(account(DOOR))
+}
+
+fn payment(amount: Value) -> Value {
+    json!({
+        "TransactionType": "Payment",
+        "Account": SENDER,
+        "Destination": DOOR,
+        "DestinationTag": 7,
+        "SourceTag": 11,
+        "Amount": amount,
+        "Fee": "12",
+        "Flags": 0,
+        "Sequence": 42,
+        "SigningPubKey": "030D58EB48B4420B1F7B9DF55087E0E29FEF0E8468F9A6825B01CA2C361042D435",
+    })
+}
+
+/// An API version 2 `tx` result.
+fn response(tx: Value, meta: Value) -> Value {
+    json!({
+        "tx_json": tx,
+        "meta": meta,
+        "hash": HASH,
+        "ledger_index": 90_000_000,
+        "validated": true,
+    })
+}
+
+fn meta(result: &str, delivered: Value) -> Value {
+    json!({
+        "TransactionIndex": 3,
+        "TransactionResult": result,
+        "AffectedNodes": [],
+        "delivered_amount": delivered,
+    })
+}
+
+#[test]
+fn validated_payments_are_credited_with_what_they_delivered() {
+    let deposit = verifier()
+        .verify(&response(
+            payment(json!("1000000")),
+            meta("tesSUCCESS", json!("1000000")),
+        ))
+        .unwrap();
+    assert_eq!(deposit.hash, HASH.parse::<Hash256>().unwrap());
+    assert_eq!(deposit.ledger_index, 90_000_000);
+    assert_eq!(deposit.sender, account(SENDER));
+    assert_eq!(deposit.source_tag, Some(11));
+    assert_eq!(deposit.destination_tag, Some(7));
+    assert_eq!(deposit.delivered, CurrencyAmount::Xrp(1_000_000));
+
+    // API version 1 puts the transaction fields at the top level.
+    let mut v1 = payment(json!("1000000"));
+    let fields = v1.as_object_mut().unwrap();
+    fields.insert("meta".into(), meta("tesSUCCESS", json!("1000000")));
+    fields.insert("hash".into(), HASH.into());
+    fields.insert("ledger_index".into(), 90_000_000.into());
+    fields.insert("validated".into(), true.into());
+    assert_eq!(verifier().verify(&v1).unwrap(), deposit);
+}
+
+#[test]
+fn partial_payments_credit_the_delivered_amount_not_the_amount() {
+    let verifier = verifier().accept_issued("USD", account(ISSUER)).unwrap();
+    let mut tx = payment(json!({"currency": "USD", "issuer": ISSUER, "value": "1000000"}));
+    tx["Flags"] = 0x0002_0000.into();
+    let delivered = json!({"currency": "USD", "issuer": ISSUER, "value": "0.000001"});
+    let mut meta = meta("tesSUCCESS", delivered.clone());
+    meta["DeliveredAmount"] = delivered;
+
+    let deposit = verifier
+        .verify(&response(tx.clone(), meta.clone()))
+        .unwrap();
+    assert_eq!(
+        deposit.delivered,
+        CurrencyAmount::issued("USD", account(ISSUER), "0.000001").unwrap()
+    );
+
+    meta["delivered_amount"] = json!({"currency": "USD", "issuer": ISSUER, "value": "1000000"});
+    assert!(matches!(
+        verifier.verify(&response(tx.clone(), meta.clone())),
+        Err(Rejection::AmbiguousDeliveredAmount(_))
+    ));
+    meta["delivered_amount"] = "unavailable".into();
+    assert!(matches!(
+        verifier.verify(&response(tx, meta)),
+        Err(Rejection::AmbiguousDeliveredAmount(_))
+    ));
+}
+
+#[test]
+fn without_a_delivered_amount_only_recent_ledgers_settle_it() {
+    let mut meta = meta("tesSUCCESS", Value::Null);
+    meta.as_object_mut().unwrap().remove("delivered_amount");
+    let mut response = response(payment(json!("5000")), meta);
+    assert_eq!(
+        verifier().verify(&response).unwrap().delivered,
+        CurrencyAmount::Xrp(5000)
+    );
+
+    response["ledger_index"] = (FIRST_DELIVERED_AMOUNT_LEDGER - 1).into();
+    assert!(matches!(
+        verifier().verify(&response),
+        Err(Rejection::AmbiguousDeliveredAmount(_))
+    ));
+}
+
+#[test]
+fn binary_responses_are_decoded_and_hashed() {
+    let tx = binary::encode(&payment(json!("2500"))).unwrap();
+    let meta = binary::encode(&json!({
+        "TransactionIndex": 0,
+        "TransactionResult": "tesSUCCESS",
+        "AffectedNodes": [],
+        "DeliveredAmount": "2000",
+    }))
+    .unwrap();
+    let response = json!({
+        "tx_blob": hex::encode_upper(&tx),
+        "meta_blob": hex::encode_upper(&meta),
+        "ledger_index": 90_000_000,
+        "validated": true,
+    });
+    let deposit = verifier().verify(&response).unwrap();
+    assert_eq!(deposit.hash, binary::transaction_id(&tx));
+    assert_eq!(deposit.delivered, CurrencyAmount::Xrp(2000));
+
+    // API version 1 puts the blobs in `tx` and `meta`.
+    let response = json!({
+        "tx": hex::encode_upper(&tx),
+        "meta": hex::encode_upper(&meta),
+        "ledger_index": 90_000_000,
+        "validated": true,
+    });
+    assert_eq!(verifier().verify(&response).unwrap(), deposit);
+}
+
+#[test]
+fn anything_but_a_successful_validated_deposit_is_rejected() {
+    let ok = response(payment(json!("1000")), meta("tesSUCCESS", json!("1000")));
+    let rejection = |edit: &dyn Fn(&mut Value)| {
+        let mut response = ok.clone();
+        edit(&mut response);
+        verifier().verify(&response).unwrap_err()
+    };
+
+    let pending = rejection(&|r| r["validated"] = false.into());
+    assert_eq!(pending, Rejection::NotValidated);
+    assert!(!pending.is_final());
+    assert_eq!(
+        rejection(&|r| {
+            r.as_object_mut().unwrap().remove("validated");
+        }),
+        Rejection::NotValidated
+    );
+
+    let failed = rejection(&|r| r["meta"]["TransactionResult"] = "tecPATH_PARTIAL".into());
+    assert_eq!(failed, Rejection::Failed("tecPATH_PARTIAL".into()));
+    assert!(failed.is_final());
+    assert_eq!(
+        rejection(&|r| r["tx_json"]["TransactionType"] = "EscrowCreate".into()),
+        Rejection::NotAPayment("EscrowCreate".into())
+    );
+    assert_eq!(
+        rejection(&|r| r["tx_json"]["Destination"] = ISSUER.into()),
+        Rejection::WrongDestination(account(ISSUER))
+    );
+    assert_eq!(
+        rejection(&|r| r["tx_json"]["Account"] = DOOR.into()),
+        Rejection::FromDoorAccount
+    );
+    assert_eq!(
+        rejection(&|r| {
+            r["tx_json"]
+                .as_object_mut()
+                .unwrap()
+                .remove("DestinationTag");
+        }),
+        Rejection::MissingDestinationTag
+    );
+    assert_eq!(
+        rejection(&|r| r["meta"]["delivered_amount"] = "0".into()),
+        Rejection::NothingDelivered
+    );
+    assert!(matches!(
+        rejection(&|r| {
+            r.as_object_mut().unwrap().remove("meta");
+        }),
+        Rejection::Malformed(_)
+    ));
+    assert!(matches!(
+        rejection(&|r| r["meta"]["delivered_amount"] = "-1".into()),
+        Rejection::Malformed(_)
+    ));
+
+    let untagged = verifier().require_destination_tag(false);
+    let mut response = ok.clone();
+    response["tx_json"]
+        .as_object_mut()
+        .unwrap()
+        .remove("DestinationTag");
+    assert_eq!(untagged.verify(&response).unwrap().destination_tag, None);
+}
+
+#[test]
+fn only_accepted_currencies_are_credited() {
+    let usd = json!({"currency": "USD", "issuer": ISSUER, "value": "10"});
+    let issued = response(payment(usd.clone()), meta("tesSUCCESS", usd));
+    assert!(matches!(
+        verifier().verify(&issued),
+        Err(Rejection::UnacceptedCurrency(_))
+    ));
+    // The same code from another issuer is another currency.
+    let other = verifier().accept_issued("USD", account(SENDER)).unwrap();
+    assert!(matches!(
+        other.verify(&issued),
+        Err(Rejection::UnacceptedCurrency(_))
+    ));
+    let accepted = verifier().accept_issued("USD", account(ISSUER)).unwrap();
+    assert!(accepted.verify(&issued).is_ok());
+
+    let xrp = response(payment(json!("1000")), meta("tesSUCCESS", json!("1000")));
+    let issued_only = accepted.accept_xrp(false);
+    let err: Error = issued_only.verify(&xrp).unwrap_err().into();
+    assert!(matches!(
+        err,
+        Error::Deposit(Rejection::UnacceptedCurrency(_))
+    ));
+}
@@ -0,0 +1,128 @@
+//! Seeds and key derivation against rippled's `wallet_propose` and the
+//! ripple-keypairs fixtures.
+
+use crossbeam_core::{PublicKey, Signature, Signer, SigningPayload};
+use crossbeam_xrpl::hash::sha512_half;
+use crossbeam_xrpl::keys::{decode_public_key, encode_public_key};
+use crossbeam_xrpl::signing::verify;
+use crossbeam_xrpl::{Error, KeyType, Seed};
+
+const TEST_MESSAGE: &[u8] = b"test message";
+
+#[test]
+fn passphrase_seeds_match_wallet_propose() {
+    let seed = Seed::from_passphrase("masterpassphrase", KeyType::Secp256k1);
+    assert_eq!(
+        hex::encode_upper(seed.entropy()),
+        "DEDCE9CE67B451D852FD4E846FCDE31C"
+    );
+    assert_eq!(seed.to_string(), "snoPBrXtMeMyMHUVTgbuqAfg1SUTb");
+
+    let keys = seed.derive_keypair().unwrap();
+    assert_eq!(
+        hex::encode_upper(encode_public_key(&keys.public_key())),
+        "0330E7FC9D56BB25D6893BA3F317AE5BCF33B3291BD63DB32654A313222F7FD020"
+    );
+    assert_eq!(
+        keys.account_id().to_string(),
+        "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh"
+    );
+}
+
+#[test]
+fn secp256k1_family_seeds_derive_rippled_keys() {
+    let seed: Seed = "sp5fghtJtpUorTwvof1NpDXAzNwf5".parse().unwrap();
+    assert_eq!(seed.key_type(), KeyType::Secp256k1);
+    assert_eq!(seed.to_string(), "sp5fghtJtpUorTwvof1NpDXAzNwf5");
+
+    let keys = seed.derive_keypair().unwrap();
+    assert_eq!(keys.key_type(), KeyType::Secp256k1);
+    assert_eq!(
+        hex::encode_upper(encode_public_key(&keys.public_key())),
+        "030D58EB48B4420B1F7B9DF55087E0E29FEF0E8468F9A6825B01CA2C361042D435"
+    );
+    assert_eq!(
+        keys.account_id().to_string(),
+        "rU6K7V3Po4snVhBBaU29sesqs2qTQJWDw1"
+    );
+
+    // RFC 6979 signatures are deterministic, so they match the fixture.
+    let der = hex::decode(
+        "30440220583A91C95E54E6A651C47BEC22744E0B101E2C4060E7B08F6341657DAD9BC3EE\
+         02207D1489C7395DB0188D3A56A977ECBA54B36FA9371B40319655B1B4429E33EF2D",
+    )
+    .unwrap();
+    let digest = sha512_half(TEST_MESSAGE).0;
+    let Signature::Secp256k1 { r, s, .. } = keys.sign(&SigningPayload::Digest(digest)).unwrap()
+    else {
+        panic!("expected an ECDSA signature");
+    };
+    assert_eq!([r, s].concat(), [&der[4..36], &der[38..]].concat());
+    verify(&keys.public_key(), TEST_MESSAGE, &der).unwrap();
+}
+
+#[test]
+fn ed25519_seeds_derive_rippled_keys() {
+    let seed: Seed = "sEdSKaCy2JT7JaM7v95H9SxkhP9wS2r".parse().unwrap();
+    assert_eq!(seed.key_type(), KeyType::Ed25519);
+    assert_eq!(seed.to_string(), "sEdSKaCy2JT7JaM7v95H9SxkhP9wS2r");
+
+    let keys = seed.derive_keypair().unwrap();
+    assert_eq!(
+        hex::encode_upper(encode_public_key(&keys.public_key())),
+        "ED01FA53FA5A7E77798F882ECE20B1ABC00BB358A9E55A202D0D0676BD0CE37A63"
+    );
+    assert_eq!(
+        keys.account_id().to_string(),
+        "rLUEXYuLiQptky37CqLcm9USQpPiz5rkpD"
+    );
+
+    let signature = keys
+        .sign(&SigningPayload::Message(TEST_MESSAGE.to_vec()))
+        .unwrap();
+    let expected = hex::decode(
+        "CB199E1BFD4E3DAA105E4832EEDFA36413E1F44205E4EFB9E27E826044C21E3E\
+         2E848BBC8195E8959BADF887599B7310AD1B7047EF11B682E0D068F73749750E",
+    )
+    .unwrap();
+    assert_eq!(
+        signature,
+        Signature::Ed25519(expected.clone().try_into().unwrap())
+    );
+    verify(&keys.public_key(), TEST_MESSAGE, &expected).unwrap();
+    assert!(matches!(
+        verify(&keys.public_key(), b"other message", &expected),
+        Err(Error::InvalidSignature(_))
+    ));
+}
+
+#[test]
+fn public_keys_round_trip_their_xrpl_encoding() {
+    let ed = decode_public_key(
+        &hex::decode("EDBB664A14F510A366404BC4352A2230A5608364B3D51105C39D7B652DDEAD3ED3").unwrap(),
+    )
+    .unwrap();
+    assert!(matches!(ed, PublicKey::Ed25519(_)));
+    assert_eq!(
+        hex::encode_upper(encode_public_key(&ed)),
+        "EDBB664A14F510A366404BC4352A2230A5608364B3D51105C39D7B652DDEAD3ED3"
+    );
+    assert!(decode_public_key(&[0x04; 33]).is_err());
+    assert!(decode_public_key(&[0x02; 32]).is_err());
+}
+
+#[test]
+fn malformed_seeds_are_rejected() {
+    // A classic address has a valid checksum but is not a seed.
+    assert!(matches!(
+        "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh".parse::<Seed>(),
+        Err(Error::InvalidSeed(_))
+    ));
+    assert!(matches!(
+        "sp5fghtJtpUorTwvof1NpDXAzNwf6".parse::<Seed>(),
+        Err(Error::Base58(_))
+    ));
+    let seed = Seed::new([7; 16], KeyType::Ed25519);
+    assert_eq!(seed.to_string().parse::<Seed>().unwrap(), seed);
+    assert!(!format!("{seed:?}").contains(&seed.to_string()));
+}
@@ -0,0 +1,488 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+use crossbeam_core::Confirmation;
+use crossbeam_xrpl::rpc::wire::{self, Message, ResponseResult};
+use crossbeam_xrpl::rpc::{Event, Issue, LedgerIndex, MockTransport, Streams, Watcher};
+use crossbeam_xrpl::{
+    AccountId, AccountInfo, Client, CurrencyAmount, DepositVerifier, Error, Hash256, Provider,
+};
+use serde_json::{json, Value};
+
+const DOOR: &str = "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh";
+const SENDER: &str = "rU6K7V3Po4snVhBBaU29sesqs2qTQJWDw1";
+const ISSUER: &str = "rLUEXYuLiQptky37CqLcm9USQpPiz5rkpD";
+
+fn account(address: &str) -> AccountId {
+    address.parse().unwrap()
+}
+
+fn hash(byte: u8) -> Hash256 {
+    Hash256([byte; 32])
+}
+
+/// A validated payment to the door in the form of a `tx` result, as the
+/// streams, `account_tx` and `tx` report it.
+fn payment(id: u8, ledger_index: u32, transaction_index: u32) -> Value {
+    json!({
+        "type": "transaction",
+        "tx_json": {
+            "TransactionType": "Payment",
+            "Account": SENDER,
+            "Destination": DOOR,
+            "DestinationTag": id,
+            "Amount": "1000",
+            "Fee": "12",
+            "Sequence": id,
+        },
+        "meta": {
+            "TransactionIndex": transaction_index,
+            "TransactionResult": "tesSUCCESS",
+            "delivered_amount": "1000",
+        },
+        "hash": hash(id),
+        "ledger_index": ledger_index,
+        "validated": true,
+    })
+}
+
+fn ledger_closed(ledger_index: u32) -> Value {
+    json!({
+        "type": "ledgerClosed",
+        "ledger_index": ledger_index,
+        "ledger_hash": hash(ledger_index as u8),
+        "ledger_time": 800_000_000 + ledger_index,
+        "fee_base": 10,
+        "txn_count": 1,
+    })
+}
+
+fn transaction_hash(event: &Event) -> Hash256 {
+    match event {
+        Event::Transaction(tx) => tx["hash"].as_str().unwrap().parse().unwrap(),
+        event => panic!("expected a transaction, got {event:?}"),
+    }
+}
+
+#[test]
+fn wire_envelopes() {
+    assert_eq!(
+        wire::request(7, "tx", json!({"transaction": "AB"})).unwrap(),
+        json!({"id": 7, "command": "tx", "transaction": "AB"})
+    );
+    assert!(wire::request(7, "tx", json!(["AB"])).is_err());
+
+    let success = Message::parse(json!({
+        "id": 7, "status": "success", "type": "response", "result": {"ledger_index": 5},
+    }))
+    .unwrap();
+    assert_eq!(
+        success,
+        Message::Response {
+            id: 7,
+            result: ResponseResult::Success(json!({"ledger_index": 5})),
+        }
+    );
+    let Message::Response { result, .. } = Message::parse(json!({
+        "id": 8, "status": "error", "type": "response",
+        "error": "actNotFound", "error_code": 19, "error_message": "Account not found.",
+        "request": {"command": "account_info"},
+    }))
+    .unwrap() else {
+        panic!("expected a response");
+    };
+    assert!(matches!(
+        result.into_result(),
+        Err(Error::Rpc { error, code: Some(19), .. }) if error == "actNotFound"
+    ));
+    assert!(matches!(
+        Message::parse(ledger_closed(3)).unwrap(),
+        Message::Stream(_)
+    ));
+}
+
+#[tokio::test]
+async fn commands_are_sent_at_api_version_2_and_read() {
+    let client = Client::new(
+        MockTransport::new()
+            .on("account_info", |params| {
+                if params["account"] == DOOR {
+                    Ok(json!({"account_data": {
+                        "Account": DOOR, "Balance": "25000000", "Flags": 131072, "Sequence": 9,
+                    }}))
+                } else {
+                    Err(Error::Rpc {
+                        error: "actNotFound".into(),
+                        code: Some(19),
+                        message: None,
+                    })
+                }
+            })
+            .on("tx", |params| {
+                if params["transaction"] == json!(hash(1)) {
+                    Ok(payment(1, 90, 0))
+                } else {
+                    Err(Error::Rpc {
+                        error: "txnNotFound".into(),
+                        code: Some(29),
+                        message: None,
+                    })
+                }
+            })
+            .on_result(
+                "book_offers",
+                json!({"offers": [{
+                    "Account": ISSUER, "Sequence": 3, "Flags": 0,
+                    "TakerGets": {"currency": "USD", "issuer": ISSUER, "value": "10"},
+                    "TakerPays": "20000000", "quality": "2000000", "owner_funds": "100",
+                }]}),
+            ),
+    );
+
+    let info = client
+        .account_info(&account(DOOR), LedgerIndex::Validated)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        info,
+        AccountInfo {
+            sequence: 9,
+            flags: 131072,
+            balance: 25_000_000
+        }
+    );
+    assert!(info.requires_destination_tag());
+    assert_eq!(
+        client
+            .account_info(&account(SENDER), LedgerIndex::Index(5))
+            .await
+            .unwrap(),
+        None
+    );
+    assert_eq!(
+        client.transport().requests()[1].1,
+        json!({"account": SENDER, "ledger_index": 5, "api_version": 2})
+    );
+
+    let tx = client.tx(&hash(1)).await.unwrap().unwrap();
+    DepositVerifier::new(account(DOOR)).verify(&tx).unwrap();
+    assert_eq!(client.tx(&hash(2)).await.unwrap(), None);
+    assert!(matches!(
+        client.request::<Value>("peers", Value::Null).await,
+        Err(Error::Rpc { error, .. }) if error == "unknownCmd"
+    ));
+
+    let issuer = account(ISSUER);
+    let usd = Issue::Issued {
+        currency: "USD".into(),
+        issuer,
+    };
+    let offers = client.book_offers(&usd, &Issue::Xrp, 10).await.unwrap();
+    assert_eq!(offers[0].taker_pays, CurrencyAmount::Xrp(20_000_000));
+    assert_eq!(offers[0].owner_funds.as_deref(), Some("100"));
+    let (_, params) = client.transport().requests().pop().unwrap();
+    assert_eq!(
+        params["taker_gets"],
+        json!({"currency": "USD", "issuer": ISSUER})
+    );
+    assert_eq!(params["taker_pays"], json!({"currency": "XRP"}));
+}
+
+#[tokio::test]
+async fn paged_results_are_collected() {
+    let lines = |page: u64| {
+        json!({
+            "account": ISSUER, "balance": format!("{page}"), "currency": "USD",
+            "limit": "100", "limit_peer": "0", "no_ripple": true,
+        })
+    };
+    let client = Client::new(MockTransport::new().on("account_lines", move |params| {
+        Ok(match params["marker"].as_u64() {
+            None => json!({"lines": [lines(0)], "marker": 1}),
+            Some(1) => json!({"lines": [lines(1), lines(2)], "marker": 2}),
+            Some(_) => json!({"lines": [lines(3)]}),
+        })
+    }));
+    let lines = client
+        .account_lines(&account(DOOR), LedgerIndex::Validated)
+        .await
+        .unwrap();
+    let balances: Vec<_> = lines.iter().map(|line| line.balance.as_str()).collect();
+    assert_eq!(balances, ["0", "1", "2", "3"]);
+    assert!(lines[0].no_ripple && !lines[0].freeze);
+    assert_eq!(client.transport().requests().len(), 3);
+}
+
+#[tokio::test]
+async fn the_client_is_a_provider() {
+    let client = Client::new(
+        MockTransport::new()
+            .on("submit", |params| {
+                let result = if params["tx_blob"] == "00" {
+                    "temMALFORMED"
+                } else {
+                    "terQUEUED"
+                };
+                Ok(json!({
+                    "engine_result": result, "engine_result_code": -98,
+                    "engine_result_message": "Held until escalated fee drops.",
+                    "tx_json": {"hash": hash(1)},
+                }))
+            })
+            .on("tx", |params| {
+                let mut tx = payment(1, 90, 0);
+                match params["transaction"].as_str().unwrap().as_bytes()[0] {
+                    b'2' => tx["validated"] = false.into(),
+                    b'3' => tx["meta"]["TransactionResult"] = "tecNO_DST_INSUF_XRP".into(),
+                    _ => {}
+                }
+                Ok(tx)
+            })
+            .on_result(
+                "fee",
+                json!({"drops": {"base_fee": "10", "open_ledger_fee": "12", "median_fee": "5000"}}),
+            )
+            .on_result(
+                "server_info",
+                json!({"info": {"validated_ledger": {"seq": 90}, "network_id": 21338}}),
+            ),
+    );
+
+    assert_eq!(Provider::submit(&client, &[1, 2]).await.unwrap(), hash(1));
+    assert!(matches!(
+        Provider::submit(&client, &[0]).await,
+        Err(Error::Provider(reason)) if reason.starts_with("temMALFORMED")
+    ));
+    assert_eq!(
+        client.transaction_confirmation(&hash(1)).await.unwrap(),
+        Confirmation::Finalized { height: 90 }
+    );
+    assert_eq!(
+        client.transaction_confirmation(&hash(0x22)).await.unwrap(),
+        Confirmation::Pending
+    );
+    assert_eq!(
+        client.transaction_confirmation(&hash(0x33)).await.unwrap(),
+        Confirmation::Failed {
+            height: Some(90),
+            reason: "tecNO_DST_INSUF_XRP".into()
+        }
+    );
+    let state = client.server_state().await.unwrap();
+    assert_eq!(state.validated_ledger_index, 90);
+    assert_eq!((state.base_fee, state.open_ledger_fee), (10, 12));
+    assert_eq!(state.network_id, Some(21338));
+}
+
+/// A server whose validated ledger is set by the test and whose
+/// `account_tx` answers from `history`, one transaction per page.
+fn account_server(validated: Arc<AtomicU32>, history: Vec<Value>) -> MockTransport {
+    MockTransport::new()
+        .on("subscribe", move |_| {
+            Ok(json!({"ledger_index": validated.load(Ordering::SeqCst), "fee_base": 10}))
+        })
+        .on("account_tx", move |params| {
+            let (min, max) = (
+                params["ledger_index_min"].as_u64().unwrap(),
+                params["ledger_index_max"].as_u64().unwrap(),
+            );
+            let in_range: Vec<_> = history
+                .iter()
+                .filter(|tx| (min..=max).contains(&tx["ledger_index"].as_u64().unwrap()))
+                .collect();
+            let position = params["marker"].as_u64().unwrap_or(0) as usize;
+            let mut page =
+                json!({"transactions": in_range.get(position).into_iter().collect::<Vec<_>>()});
+            if position + 1 < in_range.len() {
+                page["marker"] = json!(position + 1);
+            }
+            Ok(page)
+        })
+}
+
+#[tokio::test]
+async fn account_watchers_backfill_what_a_reconnect_missed() {
+    let validated = Arc::new(AtomicU32::new(100));
+    let history = vec![
+        payment(1, 97, 0),
+        payment(2, 100, 4),
+        payment(4, 102, 0),
+        payment(5, 103, 1),
+        payment(6, 104, 0),
+    ];
+    let client = Client::new(account_server(validated.clone(), history));
+    let mock = client.transport();
+    let mut watcher = Watcher::new(&client, Streams::accounts([account(DOOR)])).resume_after(95);
+
+    // The ledgers since the checkpoint come first.
+    assert_eq!(transaction_hash(&watcher.next().await.unwrap()), hash(1));
+    assert_eq!(transaction_hash(&watcher.next().await.unwrap()), hash(2));
+    let subscribed = mock.subscriptions();
+    assert!(subscribed.ledger && subscribed.accounts == [account(DOOR)]);
+
+    // Ledger events track progress without being reported.
+    assert!(mock.publish(ledger_closed(101)));
+    assert!(mock.publish(payment(3, 101, 0)));
+    assert_eq!(transaction_hash(&watcher.next().await.unwrap()), hash(3));
+    assert_eq!(watcher.checkpoint(), Some(100));
+
+    // The connection drops in the middle of ledger 102.
+    mock.publish(ledger_closed(102));
+    mock.publish(payment(4, 102, 0));
+    mock.disconnect();
+    assert!(!mock.publish(payment(5, 103, 1)));
+    validated.store(104, Ordering::SeqCst);
+
+    assert_eq!(transaction_hash(&watcher.next().await.unwrap()), hash(4));
+    assert_eq!(transaction_hash(&watcher.next().await.unwrap()), hash(5));
+    assert_eq!(transaction_hash(&watcher.next().await.unwrap()), hash(6));
+    assert_eq!(mock.reconnects(), 1);
+    assert_eq!(mock.subscriptions(), subscribed);
+    let backfill: Vec<_> = mock
+        .requests()
+        .into_iter()
+        .filter(|(command, _)| command == "account_tx")
+        .map(|(_, params)| {
+            (
+                params["ledger_index_min"].clone(),
+                params["ledger_index_max"].clone(),
+            )
+        })
+        .collect();
+    assert_eq!(backfill.first(), Some(&(json!(96), json!(100))));
+    assert_eq!(backfill.last(), Some(&(json!(102), json!(104))));
+
+    // A transaction both backfilled and streamed is reported once.
+    mock.publish(payment(6, 104, 0));
+    mock.publish(ledger_closed(105));
+    mock.publish(payment(7, 105, 0));
+    let event = watcher.next().await.unwrap();
+    assert_eq!(transaction_hash(&event), hash(7));
+    assert_eq!(watcher.checkpoint(), Some(104));
+    let Event::Transaction(tx) = event else {
+        unreachable!()
+    };
+    assert_eq!(
+        DepositVerifier::new(account(DOOR))
+            .verify(&tx)
+            .unwrap()
+            .destination_tag,
+        Some(7)
+    );
+}
+
+#[tokio::test]
+async fn ledger_watchers_backfill_ledger_by_ledger() {
+    let ledger = |index: u32, transactions: Vec<Value>| {
+        json!({
+            "ledger": {
+                "ledger_index": index,
+                "ledger_hash": hash(index as u8),
+                "parent_hash": hash(index as u8 - 1),
+                "close_time": 800_000_000 + index,
+                "transactions": transactions.into_iter().map(|mut tx| {
+                    let fields = tx.as_object_mut().unwrap();
+                    for field in ["type", "ledger_index", "validated"] {
+                        fields.remove(field);
+                    }
+                    tx
+                }).collect::<Vec<_>>(),
+            },
+            "ledger_index": index,
+            "validated": true,
+        })
+    };
+    let client = Client::new(
+        MockTransport::new()
+            .on_result("subscribe", json!({"ledger_index": 201}))
+            .on("ledger", move |params| {
+                assert_eq!(params["expand"], true);
+                Ok(match params["ledger_index"].as_u64().unwrap() {
+                    200 => ledger(200, vec![payment(1, 200, 0), payment(2, 200, 1)]),
+                    // Listed by hash, not in the order they were applied.
+                    201 => ledger(201, vec![payment(3, 201, 1), payment(4, 201, 0)]),
+                    index => panic!("ledger {index} was not missed"),
+                })
+            }),
+    );
+    let mock = client.transport();
+    let mut watcher =
+        Watcher::new(&client, Streams::transactions().with_ledger()).resume_after(199);
+
+    let mut events = Vec::new();
+    for _ in 0..6 {
+        events.push(watcher.next().await.unwrap());
+    }
+    assert!(matches!(
+        events[0],
+        Event::LedgerClosed {
+            ledger_index: 200,
+            close_time: 800_000_200,
+            ..
+        }
+    ));
+    assert_eq!(transaction_hash(&events[1]), hash(1));
+    assert_eq!(transaction_hash(&events[2]), hash(2));
+    assert!(matches!(
+        events[3],
+        Event::LedgerClosed {
+            ledger_index: 201,
+            ..
+        }
+    ));
+    assert_eq!(transaction_hash(&events[4]), hash(4));
+    assert_eq!(transaction_hash(&events[5]), hash(3));
+    assert_eq!(watcher.checkpoint(), Some(200));
+
+    // Streamed again after a reconnect, ledger 201 is not reported twice.
+    mock.disconnect();
+    mock.publish(ledger_closed(201));
+    let next = async {
+        let event = watcher.next().await.unwrap();
+        (event, watcher.checkpoint())
+    };
+    let publish = async {
+        while mock.reconnects() == 0 {
+            tokio::task::yield_now().await;
+        }
+        mock.publish(ledger_closed(201));
+        mock.publish(payment(3, 201, 1));
+        mock.publish(ledger_closed(202));
+    };
+    let ((event, checkpoint), ()) = tokio::join!(next, publish);
+    assert!(matches!(
+        event,
+        Event::LedgerClosed {
+            ledger_index: 202,
+            ..
+        }
+    ));
+    assert_eq!(checkpoint, Some(201));
+}
+
+#[tokio::test]
+async fn fresh_watchers_start_at_the_validated_ledger() {
+    let client =
+        Client::new(MockTransport::new().on_result("subscribe", json!({"ledger_index": 50})));
+    let mock = client.transport();
+    let mut watcher = Watcher::new(&client, Streams::accounts([account(DOOR)]));
+    assert_eq!(watcher.checkpoint(), None);
+
+    let publish = async {
+        while mock.subscriptions().is_empty() {
+            tokio::task::yield_now().await;
+        }
+        // Transactions of the ledger validated before subscribing are not
+        // reported; a later one is.
+        mock.publish(payment(1, 50, 0));
+        mock.publish(payment(2, 51, 0));
+    };
+    let (event, ()) = tokio::join!(watcher.next(), publish);
+    assert_eq!(transaction_hash(&event.unwrap()), hash(2));
+    assert_eq!(watcher.checkpoint(), Some(50));
+    assert!(!mock
+        .requests()
+        .iter()
+        .any(|(command, _)| command == "account_tx"));
+}
@@ -0,0 +1,227 @@
+//! Single-signing and multisigning against xrpl-rust's signing fixtures.
+
+use async_trait::async_trait;
+use crossbeam_core::{Chain, Confirmation, Signer};
+use crossbeam_xrpl::signing::{self, MAX_SIGNERS};
+use crossbeam_xrpl::transaction::AccountSet;
+use crossbeam_xrpl::{
+    binary, AccountId, AccountInfo, Error, Hash256, KeyPair, Multisignature, Provider, Result,
+    Seed, ServerState, Transaction, XrplChain,
+};
+use serde_json::{json, Value};
+
+fn keys(seed: &str) -> KeyPair {
+    seed.parse::<Seed>().unwrap().derive_keypair().unwrap()
+}
+
+fn account_set(account: &AccountId, fee: &str, sequence: u32, last_ledger: u32) -> Value {
+    json!({
+        "TransactionType": "AccountSet",
+        "Account": account.to_string(),
+        "Fee": fee,
+        "Flags": 0,
+        "Sequence": sequence,
+        "LastLedgerSequence": last_ledger,
+        "Domain": "6578616D706C652E636F6D",
+    })
+}
+
+#[test]
+fn single_signatures_match_the_fixture() {
+    let wallet = keys("sEdT7wHTCLzDG7ueaw4hroSTBvH7Mk5");
+    let mut tx = account_set(&wallet.account_id(), "10", 227_234, 0);
+    tx.as_object_mut().unwrap().remove("LastLedgerSequence");
+
+    let signed = signing::sign(&tx, &wallet).unwrap();
+    assert_eq!(
+        signed.json()["TxnSignature"],
+        "C3F435CFBFAE996FE297F3A71BEAB68FF5322CBF039E41A9615BC48A59FB4EC\
+         5A55F8D4EC0225D47056E02ECCCDF7E8FF5F8B7FAA1EBBCBF7D0491FCB2D98807"
+    );
+    assert_eq!(signed.hash(), binary::transaction_id(signed.blob()));
+    assert_eq!(
+        signing::SignedTransaction::from_blob(signed.blob().to_vec()).unwrap(),
+        signed
+    );
+
+    // The signature covers the signing fields, public key included.
+    let message = binary::encode_for_signing(signed.json()).unwrap();
+    let signature = hex::decode(signed.json()["TxnSignature"].as_str().unwrap()).unwrap();
+    signing::verify(&wallet.public_key(), &message, &signature).unwrap();
+}
+
+#[test]
+fn secp256k1_signatures_are_canonical_der() {
+    let wallet = keys("sp5fghtJtpUorTwvof1NpDXAzNwf5");
+    let tx = account_set(&wallet.account_id(), "12", 5, 100);
+    let signed = signing::sign(&tx, &wallet).unwrap();
+    let signature = hex::decode(signed.json()["TxnSignature"].as_str().unwrap()).unwrap();
+    assert_eq!(signature[0], 0x30);
+    let message = binary::encode_for_signing(signed.json()).unwrap();
+    signing::verify(&wallet.public_key(), &message, &signature).unwrap();
+
+    let mut unfilled = tx.clone();
+    unfilled.as_object_mut().unwrap().remove("Fee");
+    assert!(matches!(
+        signing::sign(&unfilled, &wallet),
+        Err(Error::InvalidTransaction(_))
+    ));
+}
+
+#[test]
+fn multisignatures_match_the_fixture_and_combine_in_account_order() {
+    let door = keys("sEdSkooMk31MeTjbHVE7vLvgCpEMAdB").account_id();
+    let first = keys("sEdTLQkHAWpdS7FDk7EvuS7Mz8aSMRh");
+    let second = keys("sEd7DXaHkGQD8mz8xcRLDxfMLqCurif");
+    let tx = account_set(&door, "40", 4_814_738, 4_814_775);
+
+    let a = signing::multisign(&tx, &first.account_id(), &first).unwrap();
+    let b = signing::multisign(&tx, &second.account_id(), &second).unwrap();
+    assert_eq!(
+        hex::encode_upper(&a.txn_signature),
+        "E3BEF86AEFC61E5ED66C95D0C5CE699721A8DAF86B6ED0D1CBAC86C2C03D96A0\
+         98767B4F163FADBD937A99AC40BD6CED16B2CA98B198C2343D4BA31ECE57530C"
+    );
+    assert_eq!(
+        hex::encode_upper(&b.txn_signature),
+        "DB64FC69F34A4881F6087226681E7BDDB212027B3FAFB617E598DCA5BBC8FA1A\
+         15A6E37A760B534BA554FBCD8D4A9FDEC8DFED206E3EBC393B875F59C765D304"
+    );
+
+    // Operators exchange signatures as `Signers` entries.
+    let shared: Multisignature = serde_json::from_value(serde_json::to_value(&b).unwrap()).unwrap();
+    assert_eq!(shared, b);
+
+    let signed = signing::combine(&tx, &[b.clone(), a.clone()]).unwrap();
+    let mut expected = vec![a, b];
+    expected.sort_by_key(|signature| signature.account);
+    assert_eq!(signed.json()["SigningPubKey"], "");
+    assert_eq!(
+        signed.json()["Signers"],
+        serde_json::to_value(&expected).unwrap()
+    );
+    let decoded = binary::decode(signed.blob()).unwrap();
+    assert_eq!(decoded["Signers"], signed.json()["Signers"]);
+}
+
+#[test]
+fn combine_rejects_bad_signer_sets() {
+    let door = keys("sEdSkooMk31MeTjbHVE7vLvgCpEMAdB");
+    let operator = keys("sEdTLQkHAWpdS7FDk7EvuS7Mz8aSMRh");
+    let tx = account_set(&door.account_id(), "40", 4_814_738, 4_814_775);
+    let signature = signing::multisign(&tx, &operator.account_id(), &operator).unwrap();
+
+    assert!(matches!(
+        signing::combine(&tx, &[]),
+        Err(Error::InvalidTransaction(_))
+    ));
+    assert!(matches!(
+        signing::combine(&tx, &[signature.clone(), signature.clone()]),
+        Err(Error::InvalidTransaction(_))
+    ));
+    let too_many = vec![signature.clone(); MAX_SIGNERS + 1];
+    assert!(signing::combine(&tx, &too_many).is_err());
+
+    // A signature for another transaction, or by another account, fails.
+    let other = account_set(&door.account_id(), "40", 4_814_739, 4_814_775);
+    assert!(matches!(
+        signing::combine(&other, std::slice::from_ref(&signature)),
+        Err(Error::InvalidSignature(_))
+    ));
+    let mut forged = signature;
+    forged.account = door.account_id();
+    assert!(signing::combine(&tx, &[forged]).is_err());
+}
+
+#[test]
+fn typed_transactions_sign_and_multisign() {
+    let door = keys("sEdSkooMk31MeTjbHVE7vLvgCpEMAdB");
+    let operators = [
+        keys("sEdTLQkHAWpdS7FDk7EvuS7Mz8aSMRh"),
+        keys("sEd7DXaHkGQD8mz8xcRLDxfMLqCurif"),
+    ];
+    let tx = Transaction {
+        fee: Some(30),
+        sequence: Some(3),
+        ..Transaction::new(door.account_id(), AccountSet::new().domain("example.com"))
+    };
+
+    let signed = tx.sign(&door).unwrap();
+    assert_eq!(
+        Transaction::decode(signed.blob()).unwrap(),
+        tx,
+        "signature fields are not part of the typed transaction"
+    );
+
+    let signatures: Vec<_> = operators
+        .iter()
+        .map(|operator| tx.multisign(&operator.account_id(), operator).unwrap())
+        .collect();
+    let signed = tx.combine(&signatures).unwrap();
+    assert_eq!(signed.json()["Signers"].as_array().unwrap().len(), 2);
+
+    let unfilled = Transaction::new(door.account_id(), AccountSet::new());
+    assert!(matches!(
+        unfilled.sign(&door),
+        Err(Error::InvalidTransaction(_))
+    ));
+}
+
+struct FixedProvider;
+
+#[async_trait]
+impl Provider for FixedProvider {
+    async fn submit(&self, blob: &[u8]) -> Result<Hash256> {
+        Ok(binary::transaction_id(blob))
+    }
+
+    async fn transaction_confirmation(&self, _hash: &Hash256) -> Result<Confirmation> {
+        Ok(Confirmation::NotFound)
+    }
+
+    async fn account_info(&self, _account: &AccountId) -> Result<Option<AccountInfo>> {
+        Ok(None)
+    }
+
+    async fn server_state(&self) -> Result<ServerState> {
+        Ok(ServerState::default())
+    }
+}
+
+#[tokio::test]
+async fn the_chain_adapter_multisigns_with_several_signers() {
+    let chain = XrplChain::new(FixedProvider);
+    let door = keys("sEdSkooMk31MeTjbHVE7vLvgCpEMAdB");
+    let operators = [
+        keys("sEdTLQkHAWpdS7FDk7EvuS7Mz8aSMRh"),
+        keys("sEd7DXaHkGQD8mz8xcRLDxfMLqCurif"),
+        keys("sp5fghtJtpUorTwvof1NpDXAzNwf5"),
+    ];
+    let tx = Transaction {
+        fee: Some(40),
+        sequence: Some(9),
+        ..Transaction::new(door.account_id(), AccountSet::new())
+    };
+
+    let single = chain.sign(tx.clone(), &[&door]).unwrap();
+    assert!(single.json().get("TxnSignature").is_some());
+
+    let signers: Vec<&dyn Signer> = operators.iter().map(|k| k as &dyn Signer).collect();
+    let multi = chain.sign(tx, &signers).unwrap();
+    let accounts: Vec<_> = multi.json()["Signers"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| {
+            entry["Signer"]["Account"]
+                .as_str()
+                .unwrap()
+                .parse::<AccountId>()
+                .unwrap()
+        })
+        .collect();
+    let mut sorted = accounts.clone();
+    sorted.sort();
+    assert_eq!(accounts, sorted);
+    assert_eq!(chain.submit(&multi).await.unwrap(), multi.hash());
+}
@@ -0,0 +1,456 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use crossbeam_core::{Amount, Asset, Chain, Confirmation, Network, Transfer};
+use crossbeam_xrpl::binary;
+use crossbeam_xrpl::transaction::{
+    ripple_time, AccountSet, AutofillOptions, EscrowCreate, EscrowFinish, IssuedAmount, Memo,
+    OfferCreate, Payment, TrustSet, RIPPLE_EPOCH,
+};
+use crossbeam_xrpl::{
+    AccountId, AccountInfo, CurrencyAmount, Error, Hash256, Provider, Result, ServerState,
+    Transaction, XrplAddress, XrplChain,
+};
+use serde_json::json;
+
+const ALICE: &str = "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh";
+const BOB: &str = "rPT1Sjq2YGrBMTttX4GZHjKu9dyfzbpAYe";
+const ISSUER: &str = "rvYAfWj5gh67oV6fW32ZzP3Aw4Eubs59B";
+
+fn account(address: &str) -> AccountId {
+    address.parse().unwrap()
+}
+
+fn usd(value: &str) -> CurrencyAmount {
+    CurrencyAmount::issued("USD", account(ISSUER), value).unwrap()
+}
+
+#[derive(Default)]
+struct MockProvider {
+    accounts: HashMap<AccountId, AccountInfo>,
+    state: ServerState,
+}
+
+impl MockProvider {
+    fn new() -> Self {
+        let mut accounts = HashMap::new();
+        accounts.insert(
+            account(ALICE),
+            AccountInfo {
+                sequence: 7,
+                flags: 0,
+                balance: 50_000_000,
+            },
+        );
+        Self {
+            accounts,
+            state: ServerState {
+                validated_ledger_index: 1_000,
+                base_fee: 10,
+                open_ledger_fee: 12,
+                network_id: None,
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl Provider for MockProvider {
+    async fn submit(&self, blob: &[u8]) -> Result<Hash256> {
+        Ok(binary::transaction_id(blob))
+    }
+
+    async fn transaction_confirmation(&self, _hash: &Hash256) -> Result<Confirmation> {
+        Ok(Confirmation::NotFound)
+    }
+
+    async fn account_info(&self, account: &AccountId) -> Result<Option<AccountInfo>> {
+        Ok(self.accounts.get(account).copied())
+    }
+
+    async fn server_state(&self) -> Result<ServerState> {
+        Ok(self.state)
+    }
+}
+
+fn assert_invalid(result: Result<impl std::fmt::Debug>) {
+    assert!(
+        matches!(result, Err(Error::InvalidTransaction(_))),
+        "{result:?}"
+    );
+}
+
+#[test]
+fn payments_serialize_to_the_json_rippled_expects() {
+    let tx = Transaction {
+        fee: Some(12),
+        sequence: Some(7),
+        last_ledger_sequence: Some(1_020),
+        ..Transaction::new(
+            account(ALICE),
+            Payment::new(account(BOB), CurrencyAmount::Xrp(1_000_000)).destination_tag(42),
+        )
+    }
+    .memo(Memo::text("invoice 9"));
+    let expected = json!({
+        "TransactionType": "Payment",
+        "Account": ALICE,
+        "Destination": BOB,
+        "Amount": "1000000",
+        "DestinationTag": 42,
+        "Fee": "12",
+        "Sequence": 7,
+        "LastLedgerSequence": 1020,
+        "Memos": [{"Memo": {"MemoData": "696E766F6963652039", "MemoFormat": "746578742F706C61696E"}}],
+    });
+    assert_eq!(tx.to_json().unwrap(), expected);
+    assert_eq!(tx.encode().unwrap(), binary::encode(&expected).unwrap());
+    assert_eq!(Transaction::decode(&tx.encode().unwrap()).unwrap(), tx);
+}
+
+#[test]
+fn issued_values_are_kept_canonical() {
+    let amount = IssuedAmount::new("USD", account(ISSUER), "0001.500").unwrap();
+    assert_eq!(amount.value(), "1.5");
+    assert_eq!(usd("0.0000001"), usd("1e-7"));
+    assert_eq!(
+        serde_json::to_value(usd("25")).unwrap(),
+        json!({"currency": "USD", "issuer": ISSUER, "value": "25"})
+    );
+    assert_invalid(IssuedAmount::new("XRP", account(ISSUER), "1"));
+    assert_invalid(IssuedAmount::new("USD", account(ISSUER), "-1"));
+    assert!(IssuedAmount::new("USD", account(ISSUER), "1.2345678901234567").is_err());
+    assert_invalid(CurrencyAmount::xrp(100_000_000_000_000_001));
+}
+
+#[test]
+fn sdk_amounts_convert_exactly() {
+    let xrp = Asset::native(Network::XrpLedger).unwrap();
+    let amount = Amount::parse(xrp, "1.5").unwrap();
+    assert_eq!(
+        CurrencyAmount::try_from(&amount).unwrap(),
+        CurrencyAmount::Xrp(1_500_000)
+    );
+
+    let asset = Asset::xrpl_issued("USD", account(ISSUER), 6).unwrap();
+    let amount = Amount::parse(asset, "12.25").unwrap();
+    assert_eq!(CurrencyAmount::try_from(&amount).unwrap(), usd("12.25"));
+
+    let eth = Amount::parse(Asset::native(Network::Ethereum).unwrap(), "1").unwrap();
+    assert_invalid(CurrencyAmount::try_from(&eth));
+}
+
+#[test]
+fn partial_payments_always_carry_deliver_min() {
+    let payment = Payment::new(account(BOB), usd("100"))
+        .send_max(CurrencyAmount::Xrp(200_000_000))
+        .partial(usd("95"));
+    let tx = Transaction::new(account(ALICE), payment.clone());
+    assert!(payment.is_partial());
+    assert_eq!(tx.flags(), Payment::PARTIAL_PAYMENT);
+    tx.validate().unwrap();
+
+    // The flag without a floor delivers whatever paths allow.
+    let mut unsafe_payment = payment.clone();
+    unsafe_payment.deliver_min = None;
+    assert_invalid(Transaction::new(account(ALICE), unsafe_payment).validate());
+
+    let mut unflagged = payment.clone();
+    unflagged.flags = 0;
+    assert_invalid(Transaction::new(account(ALICE), unflagged).validate());
+
+    let mut wrong_currency = payment.clone();
+    wrong_currency.deliver_min = Some(CurrencyAmount::Xrp(1));
+    assert_invalid(Transaction::new(account(ALICE), wrong_currency).validate());
+
+    // USD from another gateway is a different currency altogether.
+    let mut wrong_issuer = payment.clone();
+    wrong_issuer.deliver_min = Some(CurrencyAmount::issued("USD", account(BOB), "95").unwrap());
+    assert_invalid(Transaction::new(account(ALICE), wrong_issuer).validate());
+
+    let mut above_amount = payment.clone();
+    above_amount.deliver_min = Some(usd("100.5"));
+    assert_invalid(Transaction::new(account(ALICE), above_amount).validate());
+    let mut whole_amount = payment;
+    whole_amount.deliver_min = Some(usd("1e2"));
+    Transaction::new(account(ALICE), whole_amount)
+        .validate()
+        .unwrap();
+
+    let xrp = Payment::new(account(BOB), CurrencyAmount::Xrp(10)).partial(CurrencyAmount::Xrp(5));
+    assert_invalid(Transaction::new(account(ALICE), xrp).encode());
+}
+
+#[test]
+fn invalid_payments_are_refused() {
+    let to_self = Payment::new(account(ALICE), CurrencyAmount::Xrp(10));
+    assert_invalid(Transaction::new(account(ALICE), to_self).validate());
+
+    let send_max =
+        Payment::new(account(BOB), CurrencyAmount::Xrp(10)).send_max(CurrencyAmount::Xrp(11));
+    assert_invalid(Transaction::new(account(ALICE), send_max).validate());
+
+    let zero = Payment::new(account(BOB), CurrencyAmount::Xrp(0));
+    assert_invalid(Transaction::new(account(ALICE), zero).validate());
+
+    let mut unknown_flag = Payment::new(account(BOB), CurrencyAmount::Xrp(10));
+    unknown_flag.flags = 0x0100_0000;
+    assert_invalid(Transaction::new(account(ALICE), unknown_flag).validate());
+}
+
+#[test]
+fn x_address_destinations_carry_their_tag() {
+    let destination = XrplAddress::from(account(BOB)).to_x_address(Some(12_345), false);
+    let payment = Payment::to(&destination, CurrencyAmount::Xrp(1));
+    assert_eq!(payment.destination, account(BOB));
+    assert_eq!(payment.destination_tag, Some(12_345));
+}
+
+#[test]
+fn trust_lines_offers_and_settings_serialize() {
+    let limit = IssuedAmount::new("USD", account(ISSUER), "1000").unwrap();
+    let mut trust = TrustSet::new(limit);
+    trust.flags = TrustSet::SET_NO_RIPPLE;
+    let tx = Transaction::new(account(ALICE), trust);
+    assert_eq!(
+        tx.to_json().unwrap(),
+        json!({
+            "TransactionType": "TrustSet",
+            "Account": ALICE,
+            "LimitAmount": {"currency": "USD", "issuer": ISSUER, "value": "1000"},
+            "Flags": 131072,
+        })
+    );
+    assert_eq!(Transaction::decode(&tx.encode().unwrap()).unwrap(), tx);
+
+    let mut offer = OfferCreate::new(CurrencyAmount::Xrp(5_000_000), usd("2.5"));
+    offer.flags = OfferCreate::SELL;
+    let tx = Transaction::new(account(ALICE), offer);
+    assert_eq!(Transaction::decode(&tx.encode().unwrap()).unwrap(), tx);
+
+    let settings = AccountSet::new()
+        .set_flag(AccountSet::ASF_REQUIRE_DEST)
+        .domain("example.com")
+        .transfer_rate(1_002_000_000)
+        .tick_size(5);
+    let tx = Transaction::new(account(ALICE), settings);
+    assert_eq!(tx.to_json().unwrap()["Domain"], "6578616D706C652E636F6D");
+    assert_eq!(Transaction::decode(&tx.encode().unwrap()).unwrap(), tx);
+}
+
+#[test]
+fn trust_lines_offers_and_settings_are_checked() {
+    let own = IssuedAmount::new("USD", account(ALICE), "10").unwrap();
+    assert_invalid(Transaction::new(account(ALICE), TrustSet::new(own)).validate());
+
+    let mut freeze = TrustSet::new(IssuedAmount::new("USD", account(ISSUER), "10").unwrap());
+    freeze.flags = TrustSet::SET_FREEZE | TrustSet::CLEAR_FREEZE;
+    assert_invalid(Transaction::new(account(ALICE), freeze).validate());
+
+    let xrp_for_xrp = OfferCreate::new(CurrencyAmount::Xrp(1), CurrencyAmount::Xrp(2));
+    assert_invalid(Transaction::new(account(ALICE), xrp_for_xrp).validate());
+    let usd_for_usd = OfferCreate::new(usd("1"), usd("2"));
+    assert_invalid(Transaction::new(account(ALICE), usd_for_usd).validate());
+    let other_gateway = CurrencyAmount::issued("USD", account(BOB), "2").unwrap();
+    Transaction::new(account(ALICE), OfferCreate::new(usd("1"), other_gateway))
+        .validate()
+        .unwrap();
+
+    let mut ioc_fok = OfferCreate::new(CurrencyAmount::Xrp(1), usd("1"));
+    ioc_fok.flags = OfferCreate::IMMEDIATE_OR_CANCEL | OfferCreate::FILL_OR_KILL;
+    assert_invalid(Transaction::new(account(ALICE), ioc_fok).validate());
+
+    for settings in [
+        AccountSet::new().set_flag(1).clear_flag(1),
+        AccountSet::new().transfer_rate(999_999_999),
+        AccountSet::new().tick_size(2),
+    ] {
+        assert_invalid(Transaction::new(account(ALICE), settings).validate());
+    }
+    let clear = AccountSet::new().transfer_rate(0).tick_size(0);
+    Transaction::new(account(ALICE), clear).validate().unwrap();
+}
+
+#[test]
+fn escrows_need_a_release_condition() {
+    let destination = XrplAddress::from(account(BOB));
+    let finish = ripple_time(1_767_225_600).unwrap();
+    assert_eq!(finish, 820_540_800);
+
+    let escrow = EscrowCreate::new(&destination, 1_000)
+        .finish_after(finish)
+        .cancel_after(finish + 3_600);
+    let tx = Transaction::new(account(ALICE), escrow);
+    assert_eq!(Transaction::decode(&tx.encode().unwrap()).unwrap(), tx);
+
+    assert_invalid(
+        Transaction::new(account(ALICE), EscrowCreate::new(&destination, 1_000)).validate(),
+    );
+    let backwards = EscrowCreate::new(&destination, 1_000)
+        .finish_after(finish)
+        .cancel_after(finish);
+    assert_invalid(Transaction::new(account(ALICE), backwards).validate());
+    // A condition alone could lock the XRP forever.
+    let condition = hex::decode(
+        "A0258020E3B0C44298FC1C149AFBF4C8996FB92427AE41E4649B934CA495991B7852B855810100",
+    )
+    .unwrap();
+    let unbounded = EscrowCreate::new(&destination, 1_000).condition(condition.clone());
+    assert_invalid(Transaction::new(account(ALICE), unbounded).validate());
+    let cancellable = EscrowCreate::new(&destination, 1_000)
+        .condition(condition)
+        .cancel_after(finish);
+    Transaction::new(account(ALICE), cancellable)
+        .validate()
+        .unwrap();
+    let mut issued = EscrowCreate::new(&destination, 1_000).finish_after(finish);
+    issued.amount = usd("1");
+    assert_invalid(Transaction::new(account(ALICE), issued).validate());
+
+    let mut half = EscrowFinish::new(account(ALICE), 3);
+    half.fulfillment = Some(vec![0xA0, 0x02, 0x80, 0x00]);
+    assert_invalid(Transaction::new(account(BOB), half).validate());
+
+    assert_invalid(ripple_time(RIPPLE_EPOCH - 1));
+}
+
+#[tokio::test]
+async fn autofill_fills_only_missing_fields() {
+    let provider = MockProvider::new();
+    let mut tx = Transaction::new(
+        account(ALICE),
+        Payment::new(account(BOB), CurrencyAmount::Xrp(1)),
+    );
+    tx.autofill(&provider, &AutofillOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(tx.sequence, Some(7));
+    assert_eq!(tx.fee, Some(12));
+    assert_eq!(tx.last_ledger_sequence, Some(1_020));
+    assert_eq!(tx.network_id, None);
+    assert!(tx.is_complete());
+
+    let mut preset = Transaction {
+        fee: Some(100),
+        sequence: Some(9),
+        ..Transaction::new(
+            account(ALICE),
+            Payment::new(account(BOB), CurrencyAmount::Xrp(1)),
+        )
+    };
+    let options = AutofillOptions::default()
+        .last_ledger_offset(5)
+        .signer_count(3);
+    preset.autofill(&provider, &options).await.unwrap();
+    assert_eq!((preset.fee, preset.sequence), (Some(100), Some(9)));
+    assert_eq!(preset.last_ledger_sequence, Some(1_005));
+}
+
+#[tokio::test]
+async fn autofill_sets_network_id_only_on_sidechains() {
+    let mut provider = MockProvider::new();
+    for (network_id, expected) in [(Some(1), None), (Some(21_338), Some(21_338))] {
+        provider.state.network_id = network_id;
+        let mut tx = Transaction::new(account(ALICE), AccountSet::new());
+        tx.autofill(&provider, &AutofillOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(tx.network_id, expected);
+    }
+}
+
+#[tokio::test]
+async fn autofill_scales_fees_and_enforces_the_cap() {
+    let provider = MockProvider::new();
+
+    let multisigned = AutofillOptions::default().signer_count(2);
+    let mut tx = Transaction::new(account(ALICE), AccountSet::new());
+    tx.autofill(&provider, &multisigned).await.unwrap();
+    assert_eq!(tx.fee, Some(36));
+
+    // 33 reference transactions plus one per 16 bytes of fulfillment.
+    let finish = EscrowFinish::new(account(BOB), 3).fulfill(vec![0xA0; 39], vec![0xA0; 36]);
+    let mut tx = Transaction::new(account(ALICE), finish);
+    tx.autofill(&provider, &AutofillOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(tx.fee, Some(12 * 35));
+
+    // Each signer adds one reference transaction to that cost.
+    let finish = EscrowFinish::new(account(BOB), 3).fulfill(vec![0xA0; 39], vec![0xA0; 36]);
+    let mut tx = Transaction::new(account(ALICE), finish);
+    tx.autofill(&provider, &multisigned).await.unwrap();
+    assert_eq!(tx.fee, Some(12 * 37));
+
+    let mut tx = Transaction::new(account(ALICE), AccountSet::new());
+    let cheap = AutofillOptions::default().max_fee(11);
+    assert_invalid(tx.autofill(&provider, &cheap).await);
+}
+
+#[tokio::test]
+async fn autofill_refuses_missing_accounts_and_destination_tags() {
+    let mut provider = MockProvider::new();
+    provider.accounts.insert(
+        account(BOB),
+        AccountInfo {
+            sequence: 1,
+            flags: AccountInfo::REQUIRE_DEST_TAG,
+            balance: 0,
+        },
+    );
+
+    let mut untagged = Transaction::new(
+        account(ALICE),
+        Payment::new(account(BOB), CurrencyAmount::Xrp(1)),
+    );
+    assert_invalid(
+        untagged
+            .autofill(&provider, &AutofillOptions::default())
+            .await,
+    );
+
+    let mut tagged = Transaction::new(
+        account(ALICE),
+        Payment::new(account(BOB), CurrencyAmount::Xrp(1)).destination_tag(5),
+    );
+    tagged
+        .autofill(&provider, &AutofillOptions::default())
+        .await
+        .unwrap();
+
+    let mut unfunded = Transaction::new(
+        account(ISSUER),
+        Payment::new(account(ALICE), CurrencyAmount::Xrp(1)),
+    );
+    assert_invalid(
+        unfunded
+            .autofill(&provider, &AutofillOptions::default())
+            .await,
+    );
+}
+
+#[tokio::test]
+async fn transfers_become_autofilled_payments() {
+    let chain = XrplChain::new(MockProvider::new());
+    let from = XrplAddress::from(account(ALICE)).to_x_address(Some(77), false);
+    let to = XrplAddress::from(account(BOB)).to_x_address(Some(12_345), false);
+    let amount = Amount::parse(Asset::native(Network::XrpLedger).unwrap(), "2").unwrap();
+    let tx = chain
+        .build_transfer(&Transfer { from, to, amount })
+        .await
+        .unwrap();
+    assert_eq!(
+        tx.to_json().unwrap(),
+        json!({
+            "TransactionType": "Payment",
+            "Account": ALICE,
+            "Destination": BOB,
+            "Amount": "2000000",
+            "DestinationTag": 12345,
+            "SourceTag": 77,
+            "Fee": "12",
+            "Sequence": 7,
+            "LastLedgerSequence": 1020,
+        })
+    );
+}